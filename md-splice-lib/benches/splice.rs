@@ -0,0 +1,111 @@
+//! Benchmarks for the phases of an edit: parsing, locating nodes, applying operations, and
+//! rendering, on generated documents large enough for indexing and copying costs to show.
+//!
+//! Run with `cargo bench -p md-splice-lib`, and compare against a saved baseline with
+//! `cargo bench -p md-splice-lib -- --save-baseline main` followed by `-- --baseline main`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use md_splice_lib::locator::{locate, Selector};
+use md_splice_lib::transaction::{Operation, ReplaceOperation, Selector as TxSelector};
+use md_splice_lib::MarkdownDocument;
+use std::hint::black_box;
+use std::str::FromStr;
+
+/// The number of top-level blocks in the generated document.
+const BLOCKS: usize = 10_000;
+
+/// The number of operations in an applied batch.
+const OPERATIONS: usize = 100;
+
+/// Builds a document of `blocks` top-level blocks: sections made of a heading, two
+/// paragraphs, a list, and a code block.
+fn generate_document(blocks: usize) -> String {
+    let mut markdown = String::new();
+    for section in 0..blocks / 5 {
+        markdown.push_str(&format!(
+            "## Section {section}\n\n\
+             Paragraph {section} introduces the section with some *emphasis*.\n\n\
+             It links to [the reference](https://example.com/{section}) and ends.\n\n\
+             - First item of {section}\n- Second item of {section}\n\n\
+             ```rust\nfn section_{section}() {{}}\n```\n\n"
+        ));
+    }
+    markdown
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let markdown = generate_document(BLOCKS);
+    c.bench_function("parse_10k_blocks", |b| {
+        b.iter(|| MarkdownDocument::from_str(black_box(&markdown)).unwrap())
+    });
+}
+
+fn bench_locate(c: &mut Criterion) {
+    let doc = MarkdownDocument::from_str(&generate_document(BLOCKS)).unwrap();
+    let last_section = BLOCKS / 5 - 1;
+    let by_text = Selector {
+        select_type: Some("p".to_string()),
+        select_contains: Some(format!("Paragraph {last_section} ")),
+        select_ordinal: 1,
+        ..Selector::default()
+    };
+    let by_scope = Selector {
+        select_type: Some("li".to_string()),
+        select_ordinal: 2,
+        within: Some(Box::new(Selector {
+            select_type: Some("h2".to_string()),
+            select_contains: Some(format!("Section {last_section}")),
+            select_ordinal: 1,
+            ..Selector::default()
+        })),
+        ..Selector::default()
+    };
+
+    c.bench_function("locate_10k_blocks_by_text", |b| {
+        b.iter(|| locate(black_box(doc.blocks()), black_box(&by_text)).unwrap())
+    });
+    c.bench_function("locate_10k_blocks_within_section", |b| {
+        b.iter(|| locate(black_box(doc.blocks()), black_box(&by_scope)).unwrap())
+    });
+}
+
+fn bench_apply(c: &mut Criterion) {
+    let doc = MarkdownDocument::from_str(&generate_document(BLOCKS)).unwrap();
+    let step = BLOCKS / 5 / OPERATIONS;
+    let operations: Vec<Operation> = (0..OPERATIONS)
+        .map(|operation| {
+            let section = operation * step;
+            Operation::Replace(ReplaceOperation {
+                selector: Some(TxSelector {
+                    select_type: Some("p".to_string()),
+                    select_contains: Some(format!("Paragraph {section} ")),
+                    ..TxSelector::default()
+                }),
+                content: Some(format!("Paragraph {section} was rewritten.")),
+                ..ReplaceOperation::default()
+            })
+        })
+        .collect();
+
+    c.bench_function("apply_100_operations_10k_blocks", |b| {
+        b.iter_batched(
+            || (doc.clone(), operations.clone()),
+            |(mut doc, operations)| doc.apply(operations).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn bench_render(c: &mut Criterion) {
+    let doc = MarkdownDocument::from_str(&generate_document(BLOCKS)).unwrap();
+    c.bench_function("render_10k_blocks", |b| b.iter(|| black_box(&doc).render()));
+}
+
+criterion_group!(
+    benches,
+    bench_parse,
+    bench_locate,
+    bench_apply,
+    bench_render
+);
+criterion_main!(benches);
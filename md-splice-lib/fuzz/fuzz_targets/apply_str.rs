@@ -0,0 +1,11 @@
+//! Feeds arbitrary documents and operation batches to `apply_str`, which must report every
+//! failure as an error instead of panicking.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (&str, &str)| {
+    let (doc, ops_yaml) = input;
+    let _ = md_splice_lib::apply_str(doc, ops_yaml);
+});
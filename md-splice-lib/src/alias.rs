@@ -0,0 +1,171 @@
+//! Contains the types used to share selector aliases between separate batches.
+//!
+//! Aliases defined in one `apply` run are normally forgotten once the batch finishes.
+//! Exporting them as [`ResolvedAlias`] values (for example to a JSON file) and passing
+//! them to [`MarkdownDocument::apply_with_aliases`](crate::MarkdownDocument::apply_with_aliases)
+//! lets later stages of a pipeline keep referring to the same document landmarks.
+
+use crate::locator::{self, locate_cached, FoundNode, LocateCache};
+use crate::transaction::{Between as TransactionBetween, Selector as TransactionSelector};
+use markdown_ppp::ast::Block;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// A selector alias together with the node it resolved to in the document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedAlias {
+    /// The alias name, as referenced by `selector_ref` and the other `*_ref` fields.
+    pub name: String,
+    /// The selector the alias stands for, with any nested alias references inlined.
+    pub selector: TransactionSelector,
+    /// Top-level blocks spanned by the matched node, or `None` if the selector matched nothing.
+    ///
+    /// For a list item this is the range of its parent list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocks: Option<Range<usize>>,
+    /// The 0-indexed position of the matched item within its list, when the alias selects a list item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_item: Option<usize>,
+}
+
+impl ResolvedAlias {
+    /// Resolves `selector` against `blocks` through `cache`, which knows their source spans,
+    /// and records where it matched.
+    pub(crate) fn resolve(
+        name: &str,
+        selector: &locator::Selector,
+        blocks: &[Block],
+        cache: &mut LocateCache,
+    ) -> Self {
+        let (span, list_item) = match locate_cached(blocks, selector, cache) {
+            Ok((FoundNode::Block { index, .. }, _)) => (Some(index..index + 1), None),
+            Ok((
+                FoundNode::ListItem {
+                    block_index,
+                    item_index,
+                    ..
+                },
+                _,
+            )) => (Some(block_index..block_index + 1), Some(item_index)),
+            Ok((
+                FoundNode::ContainedBlock { block_index, .. }
+                | FoundNode::TableRow { block_index, .. }
+                | FoundNode::TableCell { block_index, .. }
+                | FoundNode::Inline { block_index, .. },
+                _,
+            )) => (Some(block_index..block_index + 1), None),
+            Err(_) => (None, None),
+        };
+
+        Self {
+            name: name.to_string(),
+            selector: transaction_selector(selector),
+            blocks: span,
+            list_item,
+        }
+    }
+}
+
+/// Converts a resolved locator selector back into its serializable form.
+fn transaction_selector(selector: &locator::Selector) -> TransactionSelector {
+    TransactionSelector {
+        select_type: selector.select_type.clone(),
+        select_contains: selector.select_contains.clone(),
+        select_contains_ci: selector.select_contains_ci.clone(),
+        select_regex: selector
+            .select_regex
+            .as_ref()
+            .map(|regex| regex.as_str().to_string()),
+        select_not_contains: selector.select_not_contains.clone(),
+        select_not_regex: selector
+            .select_not_regex
+            .as_ref()
+            .map(|regex| regex.as_str().to_string()),
+        select_fingerprint: selector.select_fingerprint.clone(),
+        select_annotation: selector.select_annotation.clone(),
+        select_tag: selector.select_tag.clone(),
+        select_info_contains: selector.select_info_contains.clone(),
+        select_slug: selector.select_slug.clone(),
+        select_min_words: selector.select_min_words,
+        select_max_words: selector.select_max_words,
+        select_empty: selector.select_empty,
+        select_depth: selector.select_depth,
+        recursive: selector.recursive,
+        task_state: selector.task_state,
+        select_ordinal: selector.select_ordinal,
+        select_last: selector.select_last,
+        max_matches: selector.max_matches,
+        tie_break: selector.tie_break,
+        after: selector
+            .after
+            .as_deref()
+            .map(|nested| Box::new(transaction_selector(nested))),
+        within: selector
+            .within
+            .as_deref()
+            .map(|nested| Box::new(transaction_selector(nested))),
+        between: selector.between.as_deref().map(|between| {
+            Box::new(TransactionBetween {
+                start: Some(Box::new(transaction_selector(&between.start))),
+                end: Some(Box::new(transaction_selector(&between.end))),
+                ..TransactionBetween::default()
+            })
+        }),
+        within_slide: selector.within_slide,
+        at_line: selector.at_line,
+        ..TransactionSelector::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+    use regex::Regex;
+
+    #[test]
+    fn test_resolve_records_list_item_position() {
+        let document =
+            parse_markdown(MarkdownParserState::default(), "# Tasks\n\n- one\n- two\n").unwrap();
+        let selector = locator::Selector {
+            select_type: Some("li".to_string()),
+            select_contains: None,
+            select_contains_ci: None,
+            select_regex: Some(Regex::new("tw").unwrap()),
+            select_not_contains: None,
+            select_not_regex: None,
+            select_fingerprint: None,
+            select_annotation: None,
+            select_tag: None,
+            select_info_contains: None,
+            select_slug: None,
+            select_min_words: None,
+            select_max_words: None,
+            select_empty: None,
+            select_depth: None,
+            recursive: false,
+            task_state: None,
+            select_ordinal: 1,
+            select_last: false,
+            after: None,
+            within: None,
+            between: None,
+            within_slide: None,
+            at_line: None,
+            max_matches: None,
+            tie_break: locator::TieBreak::default(),
+        };
+
+        let alias = ResolvedAlias::resolve(
+            "second",
+            &selector,
+            &document.blocks,
+            &mut LocateCache::default(),
+        );
+
+        assert_eq!(alias.blocks, Some(1..2));
+        assert_eq!(alias.list_item, Some(1));
+        assert_eq!(alias.selector.select_regex.as_deref(), Some("tw"));
+        assert_eq!(alias.selector.alias, None);
+    }
+}
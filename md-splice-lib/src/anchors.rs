@@ -0,0 +1,216 @@
+//! Computes the anchors of headings and rewrites the intra-document links pointing at them.
+//!
+//! A heading's anchor is its explicit `{#id}` attribute when it has one (as in
+//! `## Setup {#install}`), otherwise the slug GitHub derives from its text. Renaming a
+//! heading changes its slug, so links such as `[see setup](#setup)` elsewhere in the
+//! document stop resolving unless they are rewritten with [`rewrite_anchor_links`].
+
+use crate::slugify::heading_slugs;
+use markdown_ppp::ast::{Block, Heading, Inline};
+use std::collections::HashMap;
+
+/// Returns the explicit `{#id}` attribute at the end of a heading's text, if any.
+pub fn explicit_id(heading: &Heading) -> Option<&str> {
+    let Some(Inline::Text(text)) = heading.content.last() else {
+        return None;
+    };
+    let attribute = text.trim_end().strip_suffix('}')?;
+    let id = &attribute[attribute.rfind("{#")? + 2..];
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'));
+    valid.then_some(id)
+}
+
+/// Appends an explicit `{#id}` attribute to the text of `heading`.
+pub(crate) fn append_explicit_id(heading: &mut Heading, id: &str) {
+    let attribute = format!(" {{#{id}}}");
+    match heading.content.last_mut() {
+        Some(Inline::Text(text)) => text.push_str(&attribute),
+        _ => heading.content.push(Inline::Text(attribute)),
+    }
+}
+
+/// Returns the block index and anchor of every top-level heading, in document order.
+///
+/// Headings without an explicit `{#id}` get the GitHub slug of their text, with `-1`,
+/// `-2`, ... suffixes for repeated slugs.
+pub fn heading_anchors(blocks: &[Block]) -> Vec<(usize, String)> {
+    heading_slugs(blocks)
+        .into_iter()
+        .map(|heading| (heading.index, heading.anchor))
+        .collect()
+}
+
+/// Pairs the old and new anchors of the headings whose anchor differs between `before`
+/// and `after`, both as returned by [`heading_anchors`] for the same headings.
+pub fn changed_anchors(
+    before: &[(usize, String)],
+    after: &[(usize, String)],
+) -> HashMap<String, String> {
+    let after: HashMap<usize, &String> = after.iter().map(|(index, a)| (*index, a)).collect();
+    before
+        .iter()
+        .filter_map(|(index, old)| {
+            let new = after.get(index)?;
+            (*new != old).then(|| (old.clone(), (*new).clone()))
+        })
+        .collect()
+}
+
+/// Pairs the old and new anchors of the headings whose anchor changed when the blocks
+/// `start..start + old_len` were replaced by `new_len` blocks, with `before` and `after`
+/// as returned by [`heading_anchors`] on either side of the splice.
+///
+/// Headings outside the spliced range are paired by position, and headings inside it are
+/// paired in document order; replaced headings without a counterpart are left out.
+pub fn spliced_anchors(
+    before: &[(usize, String)],
+    after: &[(usize, String)],
+    start: usize,
+    old_len: usize,
+    new_len: usize,
+) -> HashMap<String, String> {
+    let (inside_before, outside_before): (Vec<_>, Vec<_>) = before
+        .iter()
+        .cloned()
+        .partition(|(index, _)| (start..start + old_len).contains(index));
+    let (inside_after, outside_after): (Vec<_>, Vec<_>) = after
+        .iter()
+        .cloned()
+        .partition(|(index, _)| (start..start + new_len).contains(index));
+
+    let outside_after: Vec<(usize, String)> = outside_after
+        .into_iter()
+        .map(|(index, anchor)| {
+            let index = if index >= start + new_len {
+                index - new_len + old_len
+            } else {
+                index
+            };
+            (index, anchor)
+        })
+        .collect();
+    let mut renamed = changed_anchors(&outside_before, &outside_after);
+    renamed.extend(
+        inside_before
+            .into_iter()
+            .zip(inside_after)
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((_, old), (_, new))| (old, new)),
+    );
+    renamed
+}
+
+/// Rewrites every link and link reference definition whose destination is `#old` into
+/// `#new`, for each `old` → `new` pair of `renamed`. Each destination is rewritten at most
+/// once, so anchors that trade places are handled. Returns the number of rewritten links.
+pub fn rewrite_anchor_links(blocks: &mut [Block], renamed: &HashMap<String, String>) -> usize {
+    if renamed.is_empty() {
+        return 0;
+    }
+    blocks
+        .iter_mut()
+        .map(|block| rewrite_block(block, renamed))
+        .sum()
+}
+
+fn rewrite_block(block: &mut Block, renamed: &HashMap<String, String>) -> usize {
+    match block {
+        Block::Paragraph(inlines) => rewrite_inlines(inlines, renamed),
+        Block::Heading(heading) => rewrite_inlines(&mut heading.content, renamed),
+        Block::BlockQuote(blocks) => rewrite_anchor_links(blocks, renamed),
+        Block::List(list) => list
+            .items
+            .iter_mut()
+            .map(|item| rewrite_anchor_links(&mut item.blocks, renamed))
+            .sum(),
+        Block::Table(table) => table
+            .rows
+            .iter_mut()
+            .flatten()
+            .map(|cell| rewrite_inlines(cell, renamed))
+            .sum(),
+        Block::FootnoteDefinition(footnote) => rewrite_anchor_links(&mut footnote.blocks, renamed),
+        Block::GitHubAlert(alert) => rewrite_anchor_links(&mut alert.blocks, renamed),
+        Block::Definition(definition) => rewrite_destination(&mut definition.destination, renamed),
+        Block::ThematicBreak | Block::CodeBlock(_) | Block::HtmlBlock(_) | Block::Empty => 0,
+    }
+}
+
+fn rewrite_inlines(inlines: &mut [Inline], renamed: &HashMap<String, String>) -> usize {
+    inlines
+        .iter_mut()
+        .map(|inline| match inline {
+            Inline::Link(link) => {
+                rewrite_destination(&mut link.destination, renamed)
+                    + rewrite_inlines(&mut link.children, renamed)
+            }
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children) => rewrite_inlines(children, renamed),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn rewrite_destination(destination: &mut String, renamed: &HashMap<String, String>) -> usize {
+    let Some(new) = destination
+        .strip_prefix('#')
+        .and_then(|anchor| renamed.get(anchor))
+    else {
+        return 0;
+    };
+    *destination = format!("#{new}");
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    fn parse(markdown: &str) -> Vec<Block> {
+        parse_markdown(MarkdownParserState::default(), markdown)
+            .unwrap()
+            .blocks
+    }
+
+    #[test]
+    fn test_heading_anchors_prefer_explicit_ids_and_rewrite_swapped_links() {
+        let mut blocks = parse(
+            "# Setup\n\n## Setup {#install}\n\n# Setup\n\n\
+             See [a](#setup), [b](#setup-1), [c](#install), and [web](https://x.y/#setup).\n",
+        );
+        let anchors = heading_anchors(&blocks);
+        assert_eq!(
+            anchors,
+            vec![
+                (0, "setup".to_string()),
+                (1, "install".to_string()),
+                (2, "setup-1".to_string())
+            ]
+        );
+
+        let renamed = HashMap::from([
+            ("setup".to_string(), "setup-1".to_string()),
+            ("setup-1".to_string(), "setup".to_string()),
+        ]);
+        assert_eq!(rewrite_anchor_links(&mut blocks, &renamed), 2);
+        let Block::Paragraph(inlines) = &blocks[3] else {
+            panic!("expected a paragraph");
+        };
+        let destinations: Vec<&str> = inlines
+            .iter()
+            .filter_map(|inline| match inline {
+                Inline::Link(link) => Some(link.destination.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            destinations,
+            vec!["#setup-1", "#setup", "#install", "https://x.y/#setup"]
+        );
+    }
+}
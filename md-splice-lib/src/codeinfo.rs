@@ -0,0 +1,247 @@
+//! Reads and edits the info string of fenced code blocks.
+//!
+//! The parser keeps the whole info string of a fence, so attributes written after the
+//! language survive a round trip. Three common layouts are understood when editing them:
+//!
+//! * comma-separated flags, as in rustdoc: `rust,no_run,edition2021`;
+//! * space-separated attributes after the language: `js title="app.js" linenos`;
+//! * Pandoc attribute braces: `{.python #example caption="Example"}`.
+//!
+//! An attribute is either a bare flag (`no_run`) or a `key=value` pair, and is identified by
+//! its key. Values holding whitespace or quotes are written in double quotes.
+
+use markdown_ppp::ast::{Block, CodeBlockKind};
+
+/// Returns the info string of a code block, such as `rust,no_run` for a fence opened with
+/// ```` ```rust,no_run ````, or an empty string for a code block without one. Returns `None`
+/// for any other block.
+pub fn code_info(block: &Block) -> Option<&str> {
+    match block {
+        Block::CodeBlock(code) => Some(match &code.kind {
+            CodeBlockKind::Fenced { info } => info.as_deref().map_or("", str::trim),
+            CodeBlockKind::Indented => "",
+        }),
+        _ => None,
+    }
+}
+
+/// Returns the language of a code block: the first word of its info string, without the
+/// comma-separated flags following it, or the first class of Pandoc attribute braces (`python`
+/// for `{.python #example}`). Returns `None` for a code block without a language and for any
+/// other block.
+pub fn code_language(block: &Block) -> Option<String> {
+    let parsed = InfoString::parse(code_info(block)?);
+    if parsed.braced {
+        return parsed
+            .attributes
+            .iter()
+            .find_map(|attribute| attribute.strip_prefix('.'))
+            .map(str::to_string);
+    }
+    parsed.language
+}
+
+/// Returns `info` with the attribute `key` set to `value`, or to a bare flag when `value` is
+/// `None`. An existing attribute with the same key is replaced in place; otherwise the
+/// attribute is added at the end. Without a language, the result uses Pandoc braces so that
+/// the attribute is not mistaken for one.
+pub fn set_info_attribute(info: &str, key: &str, value: Option<&str>) -> String {
+    let mut parsed = InfoString::parse(info);
+    let attribute = match value {
+        Some(value) if parsed.comma_separated => format!("{key}={value}"),
+        Some(value) => format!("{key}={}", quote_value(value)),
+        None => key.to_string(),
+    };
+    match parsed
+        .attributes
+        .iter_mut()
+        .find(|token| attribute_key(token) == key)
+    {
+        Some(token) => *token = attribute,
+        None => {
+            if parsed.language.is_none() {
+                parsed.braced = true;
+            }
+            parsed.attributes.push(attribute);
+        }
+    }
+    parsed.render()
+}
+
+/// Returns `info` without the attribute `key`. Braces left empty are dropped.
+pub fn remove_info_attribute(info: &str, key: &str) -> String {
+    let mut parsed = InfoString::parse(info);
+    parsed
+        .attributes
+        .retain(|token| attribute_key(token) != key);
+    parsed.render()
+}
+
+/// An info string split into its language and its attributes.
+struct InfoString {
+    braced: bool,
+    comma_separated: bool,
+    language: Option<String>,
+    attributes: Vec<String>,
+    /// Words following comma-separated flags, kept verbatim.
+    trailing: Vec<String>,
+}
+
+impl InfoString {
+    fn parse(info: &str) -> Self {
+        let info = info.trim();
+        if let Some(inner) = info
+            .strip_prefix('{')
+            .and_then(|rest| rest.strip_suffix('}'))
+        {
+            return Self {
+                braced: true,
+                comma_separated: false,
+                language: None,
+                attributes: split_words(inner),
+                trailing: Vec::new(),
+            };
+        }
+
+        let mut words = split_words(info).into_iter();
+        let Some(first) = words.next() else {
+            return Self {
+                braced: false,
+                comma_separated: false,
+                language: None,
+                attributes: Vec::new(),
+                trailing: Vec::new(),
+            };
+        };
+        if first.contains(',') {
+            let mut flags = first.split(',').map(str::to_string);
+            let language = flags.next().filter(|language| !language.is_empty());
+            Self {
+                braced: false,
+                comma_separated: true,
+                language,
+                attributes: flags.filter(|flag| !flag.is_empty()).collect(),
+                trailing: words.collect(),
+            }
+        } else {
+            Self {
+                braced: false,
+                comma_separated: false,
+                language: Some(first),
+                attributes: words.collect(),
+                trailing: Vec::new(),
+            }
+        }
+    }
+
+    fn render(&self) -> String {
+        if self.braced {
+            return if self.attributes.is_empty() {
+                String::new()
+            } else {
+                format!("{{{}}}", self.attributes.join(" "))
+            };
+        }
+        let mut info = self.language.clone().unwrap_or_default();
+        let separator = if self.comma_separated { "," } else { " " };
+        for attribute in &self.attributes {
+            info.push_str(separator);
+            info.push_str(attribute);
+        }
+        for word in &self.trailing {
+            info.push(' ');
+            info.push_str(word);
+        }
+        info
+    }
+}
+
+/// Splits on whitespace outside double quotes.
+fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        if c.is_whitespace() && !quoted {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c == '"' && !escaped {
+            quoted = !quoted;
+        }
+        escaped = c == '\\' && !escaped;
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn attribute_key(token: &str) -> &str {
+    token.split_once('=').map_or(token, |(key, _)| key)
+}
+
+fn quote_value(value: &str) -> String {
+    if !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || c == '"') {
+        return value.to_string();
+    }
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    #[test]
+    fn test_info_attributes_are_edited_in_each_layout() {
+        assert_eq!(
+            set_info_attribute("rust,no_run", "edition", Some("2021")),
+            "rust,no_run,edition=2021"
+        );
+        assert_eq!(
+            remove_info_attribute("rust,no_run,edition2021", "no_run"),
+            "rust,edition2021"
+        );
+        assert_eq!(
+            set_info_attribute(r#"js title="a b.js" linenos"#, "title", Some("app.js")),
+            "js title=app.js linenos"
+        );
+        assert_eq!(
+            set_info_attribute("js", "title", Some("My app")),
+            r#"js title="My app""#
+        );
+        assert_eq!(
+            set_info_attribute(r#"{.python #id caption="x"}"#, "caption", Some("Example")),
+            "{.python #id caption=Example}"
+        );
+        assert_eq!(remove_info_attribute("{.python}", ".python"), "");
+        assert_eq!(set_info_attribute("", "linenos", None), "{linenos}");
+    }
+
+    #[test]
+    fn test_code_language_reads_each_layout() {
+        let language = |markdown: &str| {
+            let document = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+            code_language(&document.blocks[0])
+        };
+        assert_eq!(
+            language("```rust,no_run\nfn a() {}\n```\n").as_deref(),
+            Some("rust")
+        );
+        assert_eq!(
+            language("```js title=\"app.js\"\nx\n```\n").as_deref(),
+            Some("js")
+        );
+        assert_eq!(
+            language("```{#id .python}\nx\n```\n").as_deref(),
+            Some("python")
+        );
+        assert_eq!(language("```\nx\n```\n"), None);
+        assert_eq!(language("Text.\n"), None);
+    }
+}
@@ -0,0 +1,49 @@
+//! Recognizes and builds HTML comment blocks (`<!-- ... -->`).
+//!
+//! Comment blocks let md-splice leave annotations next to the content it manages, such as
+//! provenance stamps, and select them again later with `select_type: comment`.
+
+use crate::error::SpliceError;
+use markdown_ppp::ast::Block;
+
+/// Returns the trimmed text of an HTML block holding exactly one comment, or `None` if `html`
+/// is anything else.
+pub fn comment_text(html: &str) -> Option<&str> {
+    let inner = html.trim().strip_prefix("<!--")?.strip_suffix("-->")?;
+    (!inner.contains("-->")).then(|| inner.trim())
+}
+
+/// Builds a comment block holding `text`.
+///
+/// Fails if `text` contains `-->`, which would end the comment early.
+pub fn comment_block(text: &str) -> Result<Block, SpliceError> {
+    if text.contains("-->") {
+        return Err(SpliceError::OperationFailed(
+            "Comment text cannot contain '-->'".to_string(),
+        ));
+    }
+    Ok(Block::HtmlBlock(format!("<!-- {} -->", text.trim())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_text_accepts_single_comments_only() {
+        assert_eq!(comment_text("<!-- slot: intro -->\n"), Some("slot: intro"));
+        assert_eq!(comment_text("<!---->"), Some(""));
+        assert_eq!(comment_text("<div>text</div>"), None);
+        assert_eq!(comment_text("<!-- a --> text <!-- b -->"), None);
+    }
+
+    #[test]
+    fn test_comment_block_round_trips() {
+        let block = comment_block("generated by md-splice").unwrap();
+        assert_eq!(
+            block,
+            Block::HtmlBlock("<!-- generated by md-splice -->".to_string())
+        );
+        assert!(comment_block("bad --> text").is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! Options and helpers for combining several documents with [`MarkdownDocument::concat`].
+//!
+//! [`MarkdownDocument::concat`]: crate::MarkdownDocument::concat
+
+use crate::sections::{get_heading_level, set_heading_level};
+use markdown_ppp::ast::Block;
+use serde_yaml::Value as YamlValue;
+
+/// Chooses how the frontmatter of concatenated documents is combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrontmatterStrategy {
+    /// Keep the frontmatter of the first document that has one, exactly as written.
+    #[default]
+    First,
+    /// Merge every frontmatter block. Later documents override the values of earlier ones,
+    /// and nested mappings are merged key by key.
+    Merge,
+    /// Leave the combined document without frontmatter.
+    Drop,
+}
+
+/// Options controlling [`MarkdownDocument::concat`](crate::MarkdownDocument::concat).
+#[derive(Debug, Clone, Default)]
+pub struct ConcatOptions {
+    /// Shifts the headings of each document so that its shallowest heading lands on this
+    /// level, keeping their relative depth. Shifted levels are clamped to 1-6.
+    pub base_level: Option<u8>,
+    /// How the frontmatter blocks of the documents are combined.
+    pub frontmatter: FrontmatterStrategy,
+}
+
+/// Shifts every top-level heading in `blocks` by the same amount so the shallowest one
+/// ends up at `base_level`.
+pub(crate) fn rebase_heading_levels(blocks: &mut [Block], base_level: u8) {
+    let Some(top_level) = blocks.iter().filter_map(get_heading_level).min() else {
+        return;
+    };
+    let shift = i16::from(base_level) - i16::from(top_level);
+    for block in blocks {
+        if let Some(level) = get_heading_level(block) {
+            let shifted = (i16::from(level) + shift).clamp(1, 6);
+            set_heading_level(block, shifted as u8);
+        }
+    }
+}
+
+/// Merges `overlay` into `base`. Mappings are merged recursively; any other value in
+/// `overlay` replaces the one in `base`.
+pub(crate) fn merge_yaml(base: &mut YamlValue, overlay: YamlValue) {
+    match (base, overlay) {
+        (YamlValue::Mapping(base), YamlValue::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_yaml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    #[test]
+    fn test_rebase_heading_levels_keeps_relative_depth() {
+        let markdown = "## Guide\n\n### Setup\n\n##### Deep\n";
+        let mut doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        rebase_heading_levels(&mut doc.blocks, 3);
+        let levels: Vec<u8> = doc.blocks.iter().filter_map(get_heading_level).collect();
+        assert_eq!(levels, vec![3, 4, 6]);
+
+        rebase_heading_levels(&mut doc.blocks, 1);
+        let levels: Vec<u8> = doc.blocks.iter().filter_map(get_heading_level).collect();
+        assert_eq!(levels, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_merge_yaml_overrides_scalars_and_merges_mappings() {
+        let mut base: YamlValue =
+            serde_yaml::from_str("title: Guide\nauthor:\n  name: Ann\ntags: [a]\n").unwrap();
+        let overlay: YamlValue =
+            serde_yaml::from_str("title: Handbook\nauthor:\n  email: ann@example.com\ntags: [b]\n")
+                .unwrap();
+
+        merge_yaml(&mut base, overlay);
+
+        let expected: YamlValue = serde_yaml::from_str(
+            "title: Handbook\nauthor:\n  name: Ann\n  email: ann@example.com\ntags: [b]\n",
+        )
+        .unwrap();
+        assert_eq!(base, expected);
+    }
+}
@@ -0,0 +1,421 @@
+//! Contains the logic for detecting conflicting operations before a batch executes.
+//!
+//! Selector aliases are re-evaluated each time they are referenced, so a batch that
+//! deletes an aliased node and then references the alias again, or that replaces the
+//! same target twice, usually fails halfway through with a confusing
+//! [`SpliceError::NodeNotFound`]. Detecting these patterns up front lets the batch name
+//! the operations involved when that happens. Whether they actually conflict depends on
+//! the document: a selector matching several nodes can still find one after the first
+//! operation, so each suspected conflict is only checked when its later operation runs.
+
+use crate::locator::locate;
+use crate::resolve_selector_tree;
+use crate::transaction::{AssertExpectation, Operation, Selector};
+use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+use std::collections::HashMap;
+
+/// An operation that may fail because an earlier operation of the batch removed its target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// The 0-indexed position of the later operation.
+    pub operation_index: usize,
+    /// The selector, without alias names, that the later operation needs to match.
+    pub target: Selector,
+    /// Names the operations involved, numbered from 1.
+    pub description: String,
+}
+
+/// Checks a batch of operations for possible conflicts without executing it.
+///
+/// The following patterns are reported:
+///
+/// * two `replace` operations that target the same selector or selector alias, unless
+///   the first replacement's content still matches that selector;
+/// * an operation that references a selector alias after a `delete` removed the node
+///   that alias points to.
+///
+/// When the batch runs, a conflict whose target no longer matches fails with
+/// [`SpliceError::ConflictingOperations`](crate::error::SpliceError::ConflictingOperations),
+/// and the others are reported as
+/// [`WarningKind::PossibleConflict`](crate::warning::WarningKind::PossibleConflict) warnings.
+pub fn detect_conflicts(operations: &[Operation]) -> Vec<Conflict> {
+    let mut alias_targets: HashMap<&str, Selector> = HashMap::new();
+    let mut replaced_targets: Vec<(Selector, String)> = Vec::new();
+    let mut deleted_targets: Vec<(Selector, String)> = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for (index, operation) in operations.iter().enumerate() {
+        let number = index + 1;
+        let label = format!("operation {number} (`{operation}`)");
+        let Some((selector, selector_ref)) = operation_target(operation) else {
+            continue;
+        };
+
+        for alias in referenced_aliases(operation) {
+            let Some(target) = alias_targets.get(alias) else {
+                continue;
+            };
+            if let Some((_, deleted_by)) = deleted_targets.iter().find(|(key, _)| key == target) {
+                conflicts.push(Conflict {
+                    operation_index: index,
+                    target: target.clone(),
+                    description: format!(
+                        "{label} references alias '{alias}', whose node was deleted by {deleted_by}"
+                    ),
+                });
+            }
+        }
+
+        for selector in operation_selectors(operation) {
+            register_aliases(&mut alias_targets, selector);
+        }
+
+        let target = match (selector, selector_ref) {
+            (Some(selector), _) => Some(strip_aliases(selector)),
+            (None, Some(alias)) => alias_targets.get(alias.as_str()).cloned(),
+            (None, None) => None,
+        };
+        let Some(target) = target else {
+            continue;
+        };
+
+        match operation {
+            Operation::Replace(op) => {
+                // A cell replacement leaves the table in place for later operations.
+                if let Some((_, first)) = replaced_targets.iter().find(|(key, _)| *key == target) {
+                    conflicts.push(Conflict {
+                        operation_index: index,
+                        target,
+                        description: format!("{first} and {label} both replace the same target"),
+                    });
+                } else if op.cell.is_none()
+                    && !replacement_preserves_target(op.content.as_deref(), &target)
+                {
+                    replaced_targets.push((target, label));
+                }
+            }
+            Operation::Delete(_) => deleted_targets.push((target, label)),
+            _ => {}
+        }
+    }
+
+    conflicts
+}
+
+/// Returns `true` when the inline replacement content still contains a node matching
+/// `target`, so that later operations can keep addressing it.
+fn replacement_preserves_target(content: Option<&str>, target: &Selector) -> bool {
+    let Some(content) = content else {
+        return false;
+    };
+    let Ok(document) = parse_markdown(MarkdownParserState::default(), content) else {
+        return false;
+    };
+    // Only the node's own criteria apply inside the replacement content.
+    let own = Selector {
+        alias: None,
+        select_ordinal: 1,
+        select_last: false,
+        max_matches: None,
+        tie_break: Default::default(),
+        after: None,
+        after_ref: None,
+        within: None,
+        within_ref: None,
+        between: None,
+        within_slide: None,
+        at_line: None,
+        ..target.clone()
+    };
+    let Ok(resolution) = resolve_selector_tree(&HashMap::new(), &own) else {
+        return false;
+    };
+    locate(&document.blocks, &resolution.selector).is_ok()
+}
+
+/// Returns the primary selector (inline or by alias) of a body operation.
+fn operation_target(operation: &Operation) -> Option<(Option<&Selector>, Option<&String>)> {
+    match operation {
+        Operation::Insert(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::Replace(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::ReplaceText(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::Delete(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::Upsert(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::Move(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::Copy(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::Swap(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::RenameHeading(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::ShiftHeading(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::MergeSections(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::ImportSection(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::SetAlert(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::RemoveAlert(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::SetComment(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::RemoveComment(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::ReplaceInnerHtml(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::Wrap(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::Unwrap(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::WrapDetails(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::UnwrapDetails(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::SetCodeLanguage(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::SetCodeAttribute(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::InsertTableRow(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::DeleteTableRow(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::AddTableColumn(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::RemoveTableColumn(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::RenameTableColumn(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::Assert(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        Operation::Get(op) => Some((op.selector.as_ref(), op.selector_ref.as_ref())),
+        _ => None,
+    }
+}
+
+/// Returns every inline selector attached to a body operation.
+fn operation_selectors(operation: &Operation) -> Vec<&Selector> {
+    match operation {
+        Operation::Insert(op) => op.selector.iter().collect(),
+        Operation::Replace(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::ReplaceText(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::Delete(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::Upsert(op) => op.selector.iter().chain(op.fallback.iter()).collect(),
+        Operation::Move(op) => op.selector.iter().chain(op.destination.iter()).collect(),
+        Operation::Copy(op) => op
+            .selector
+            .iter()
+            .chain(op.until.iter())
+            .chain(op.destination.iter())
+            .collect(),
+        Operation::Swap(op) => op.selector.iter().chain(op.other.iter()).collect(),
+        Operation::RenameHeading(op) => op.selector.iter().collect(),
+        Operation::ShiftHeading(op) => op.selector.iter().collect(),
+        Operation::MergeSections(op) => op.selector.iter().chain(op.destination.iter()).collect(),
+        Operation::ImportSection(op) => op.selector.iter().collect(),
+        Operation::SetAlert(op) => op.selector.iter().collect(),
+        Operation::RemoveAlert(op) => op.selector.iter().collect(),
+        Operation::SetComment(op) => op.selector.iter().collect(),
+        Operation::RemoveComment(op) => op.selector.iter().collect(),
+        Operation::ReplaceInnerHtml(op) => op.selector.iter().collect(),
+        Operation::Wrap(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::Unwrap(op) => op.selector.iter().collect(),
+        Operation::WrapDetails(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::UnwrapDetails(op) => op.selector.iter().collect(),
+        Operation::SetCodeLanguage(op) => op.selector.iter().collect(),
+        Operation::SetCodeAttribute(op) => op.selector.iter().collect(),
+        Operation::InsertTableRow(op) => op.selector.iter().collect(),
+        Operation::DeleteTableRow(op) => op.selector.iter().collect(),
+        Operation::AddTableColumn(op) => op.selector.iter().collect(),
+        Operation::RemoveTableColumn(op) => op.selector.iter().collect(),
+        Operation::RenameTableColumn(op) => op.selector.iter().collect(),
+        Operation::UpdateToc(op) => op.selector.iter().collect(),
+        Operation::Assert(op) => op.selector.iter().collect(),
+        Operation::Get(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns every selector alias referenced (rather than defined) by a body operation.
+fn referenced_aliases(operation: &Operation) -> Vec<&str> {
+    let references = match operation {
+        Operation::Insert(op) => vec![op.selector_ref.as_ref()],
+        Operation::Replace(op) => vec![op.selector_ref.as_ref(), op.until_ref.as_ref()],
+        Operation::ReplaceText(op) => vec![op.selector_ref.as_ref(), op.until_ref.as_ref()],
+        Operation::Delete(op) => vec![op.selector_ref.as_ref(), op.until_ref.as_ref()],
+        Operation::Upsert(op) => vec![op.selector_ref.as_ref(), op.fallback_ref.as_ref()],
+        Operation::Move(op) => vec![op.selector_ref.as_ref(), op.destination_ref.as_ref()],
+        Operation::Copy(op) => vec![
+            op.selector_ref.as_ref(),
+            op.until_ref.as_ref(),
+            op.destination_ref.as_ref(),
+        ],
+        Operation::Swap(op) => vec![op.selector_ref.as_ref(), op.other_ref.as_ref()],
+        Operation::RenameHeading(op) => vec![op.selector_ref.as_ref()],
+        Operation::ShiftHeading(op) => vec![op.selector_ref.as_ref()],
+        Operation::MergeSections(op) => {
+            vec![op.selector_ref.as_ref(), op.destination_ref.as_ref()]
+        }
+        Operation::Get(op) => vec![op.selector_ref.as_ref(), op.until_ref.as_ref()],
+        Operation::ImportSection(op) => vec![op.selector_ref.as_ref()],
+        Operation::SetAlert(op) => vec![op.selector_ref.as_ref()],
+        Operation::RemoveAlert(op) => vec![op.selector_ref.as_ref()],
+        Operation::SetComment(op) => vec![op.selector_ref.as_ref()],
+        Operation::RemoveComment(op) => vec![op.selector_ref.as_ref()],
+        Operation::ReplaceInnerHtml(op) => vec![op.selector_ref.as_ref()],
+        Operation::Wrap(op) => vec![op.selector_ref.as_ref(), op.until_ref.as_ref()],
+        Operation::Unwrap(op) => vec![op.selector_ref.as_ref()],
+        Operation::WrapDetails(op) => vec![op.selector_ref.as_ref(), op.until_ref.as_ref()],
+        Operation::UnwrapDetails(op) => vec![op.selector_ref.as_ref()],
+        Operation::SetCodeLanguage(op) => vec![op.selector_ref.as_ref()],
+        Operation::SetCodeAttribute(op) => vec![op.selector_ref.as_ref()],
+        Operation::InsertTableRow(op) => vec![op.selector_ref.as_ref()],
+        Operation::DeleteTableRow(op) => vec![op.selector_ref.as_ref()],
+        Operation::AddTableColumn(op) => vec![op.selector_ref.as_ref()],
+        Operation::RemoveTableColumn(op) => vec![op.selector_ref.as_ref()],
+        Operation::RenameTableColumn(op) => vec![op.selector_ref.as_ref()],
+        Operation::UpdateToc(op) => vec![op.selector_ref.as_ref()],
+        // Asserting that a deleted alias no longer matches is legitimate.
+        Operation::Assert(op) if op.expect == AssertExpectation::Match => {
+            vec![op.selector_ref.as_ref()]
+        }
+        _ => Vec::new(),
+    };
+
+    let mut aliases: Vec<&str> = references
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    for selector in operation_selectors(operation) {
+        collect_nested_references(selector, &mut aliases);
+    }
+    aliases
+}
+
+fn collect_nested_references<'a>(selector: &'a Selector, aliases: &mut Vec<&'a str>) {
+    aliases.extend(selector.nested_refs().map(String::as_str));
+    for nested in selector.nested_selectors() {
+        collect_nested_references(nested, aliases);
+    }
+}
+
+fn register_aliases<'a>(alias_targets: &mut HashMap<&'a str, Selector>, selector: &'a Selector) {
+    if let Some(alias) = selector.alias.as_deref() {
+        alias_targets.insert(alias, strip_aliases(selector));
+    }
+    for nested in selector.nested_selectors() {
+        register_aliases(alias_targets, nested);
+    }
+}
+
+/// Returns a copy of `selector` without alias names so that equivalent selectors compare equal.
+fn strip_aliases(selector: &Selector) -> Selector {
+    let mut stripped = selector.clone();
+    stripped.alias = None;
+    stripped.after = selector
+        .after
+        .as_ref()
+        .map(|nested| Box::new(strip_aliases(nested)));
+    stripped.within = selector
+        .within
+        .as_ref()
+        .map(|nested| Box::new(strip_aliases(nested)));
+    if let Some(between) = stripped.between.as_deref_mut() {
+        for landmark in [&mut between.start, &mut between.end] {
+            if let Some(nested) = landmark.as_deref_mut() {
+                *nested = strip_aliases(nested);
+            }
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{DeleteOperation, InsertOperation, ReplaceOperation};
+
+    fn contains(text: &str) -> Selector {
+        Selector {
+            select_contains: Some(text.to_string()),
+            ..Selector::default()
+        }
+    }
+
+    #[test]
+    fn test_independent_operations_do_not_conflict() {
+        let operations = vec![
+            Operation::Replace(ReplaceOperation {
+                selector: Some(contains("One")),
+                ..ReplaceOperation::default()
+            }),
+            Operation::Replace(ReplaceOperation {
+                selector: Some(contains("Two")),
+                ..ReplaceOperation::default()
+            }),
+            Operation::Delete(DeleteOperation {
+                selector: Some(contains("One")),
+                ..DeleteOperation::default()
+            }),
+        ];
+
+        assert!(detect_conflicts(&operations).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_replace_through_alias_is_reported() {
+        let operations = vec![
+            Operation::Replace(ReplaceOperation {
+                selector: Some(Selector {
+                    alias: Some("intro".to_string()),
+                    ..contains("Intro")
+                }),
+                ..ReplaceOperation::default()
+            }),
+            Operation::Replace(ReplaceOperation {
+                selector_ref: Some("intro".to_string()),
+                ..ReplaceOperation::default()
+            }),
+        ];
+
+        let conflicts = detect_conflicts(&operations);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].operation_index, 1);
+        assert_eq!(conflicts[0].target, contains("Intro"));
+        assert_eq!(
+            conflicts[0].description,
+            "operation 1 (`replace *~\"Intro\" as @intro`) and operation 2 (`replace @intro`) \
+             both replace the same target"
+        );
+    }
+
+    #[test]
+    fn test_replacement_that_keeps_its_target_can_be_replaced_again() {
+        let operations = vec![
+            Operation::Replace(ReplaceOperation {
+                selector: Some(contains("Changelog")),
+                content: Some("## Changelog\n- First entry\n".to_string()),
+                ..ReplaceOperation::default()
+            }),
+            Operation::Replace(ReplaceOperation {
+                selector: Some(contains("Changelog")),
+                content: Some("## Changelog\n- Second entry\n".to_string()),
+                ..ReplaceOperation::default()
+            }),
+        ];
+
+        assert!(detect_conflicts(&operations).is_empty());
+    }
+
+    #[test]
+    fn test_reference_to_deleted_alias_is_reported() {
+        let operations = vec![
+            Operation::Insert(InsertOperation {
+                selector: Some(Selector {
+                    alias: Some("legacy".to_string()),
+                    ..contains("Legacy")
+                }),
+                ..InsertOperation::default()
+            }),
+            Operation::Delete(DeleteOperation {
+                selector_ref: Some("legacy".to_string()),
+                ..DeleteOperation::default()
+            }),
+            Operation::Insert(InsertOperation {
+                selector: Some(Selector {
+                    after_ref: Some("legacy".to_string()),
+                    ..contains("Notes")
+                }),
+                ..InsertOperation::default()
+            }),
+        ];
+
+        let conflicts = detect_conflicts(&operations);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].operation_index, 2);
+        assert_eq!(conflicts[0].target, contains("Legacy"));
+        assert_eq!(
+            conflicts[0].description,
+            "operation 3 (`insert after *~\"Notes\" after @legacy`) references alias 'legacy', \
+             whose node was deleted by operation 2 (`delete @legacy`)"
+        );
+    }
+}
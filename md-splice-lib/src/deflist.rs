@@ -0,0 +1,144 @@
+//! Recognizes definition lists (Pandoc and PHP Markdown Extra style) in the parsed AST.
+//!
+//! The parser has no definition list node: a term and its definitions parse as paragraphs,
+//! and a compact entry such as `Term\n: Definition` becomes a single paragraph that the
+//! printer would reflow into `Term : Definition`. When a document is parsed with
+//! [`ParseOptions::definition_lists`](crate::ParseOptions::definition_lists),
+//! [`split_definition_lists`] splits such paragraphs so that every term and every definition
+//! is a paragraph of its own, which renders as a loose definition list:
+//!
+//! ```markdown
+//! Term
+//!
+//! : Definition
+//! ```
+//!
+//! A definition (`dd`) is a paragraph starting with `:` followed by whitespace, and a term
+//! (`dt`) is any other paragraph directly followed by a definition.
+
+use markdown_ppp::ast::{Block, Inline};
+
+/// Returns whether `block` is the definition of a definition list, i.e. a paragraph whose
+/// text starts with `:` followed by a space or a tab.
+pub fn is_definition(block: &Block) -> bool {
+    match block {
+        Block::Paragraph(inlines) => starts_with_marker(inlines),
+        _ => false,
+    }
+}
+
+/// Returns whether the block at `index` is the term of a definition list, i.e. a paragraph
+/// that is not a definition itself and is directly followed by one.
+pub fn is_definition_term(blocks: &[Block], index: usize) -> bool {
+    matches!(blocks.get(index), Some(block @ Block::Paragraph(_)) if !is_definition(block))
+        && blocks.get(index + 1).is_some_and(is_definition)
+}
+
+/// Splits compact definition list entries into one paragraph per term and per definition,
+/// in `blocks` and in the blocks nested in block quotes, list items, footnote definitions
+/// and GitHub alerts.
+///
+/// Lines of a paragraph are grouped so that each line starting with the `: ` marker opens a
+/// new definition, and the lines that follow it (lazy continuation lines) stay with it.
+/// Paragraphs without a marker after their first line are left untouched.
+pub fn split_definition_lists(blocks: &mut Vec<Block>) {
+    let mut index = 0;
+    while index < blocks.len() {
+        match &mut blocks[index] {
+            Block::Paragraph(inlines) => {
+                let parts = split_paragraph(inlines);
+                if parts.len() > 1 {
+                    let count = parts.len();
+                    blocks.splice(index..=index, parts.into_iter().map(Block::Paragraph));
+                    index += count;
+                    continue;
+                }
+            }
+            Block::BlockQuote(children) => split_definition_lists(children),
+            Block::List(list) => {
+                for item in &mut list.items {
+                    split_definition_lists(&mut item.blocks);
+                }
+            }
+            Block::FootnoteDefinition(footnote) => split_definition_lists(&mut footnote.blocks),
+            Block::GitHubAlert(alert) => split_definition_lists(&mut alert.blocks),
+            _ => {}
+        }
+        index += 1;
+    }
+}
+
+/// Splits the inlines of a paragraph before each line starting with the definition marker.
+/// Returns a single part when the paragraph holds no compact entry.
+fn split_paragraph(inlines: &[Inline]) -> Vec<Vec<Inline>> {
+    let mut parts = Vec::new();
+    let mut current = Vec::new();
+    for inline in inlines {
+        let Inline::Text(text) = inline else {
+            current.push(inline.clone());
+            continue;
+        };
+        let mut pending = String::new();
+        for (line_index, line) in text.split('\n').enumerate() {
+            if line_index > 0 {
+                if has_marker(line) {
+                    if !pending.is_empty() {
+                        current.push(Inline::Text(std::mem::take(&mut pending)));
+                    }
+                    parts.push(std::mem::take(&mut current));
+                } else {
+                    pending.push('\n');
+                }
+            }
+            pending.push_str(line);
+        }
+        if !pending.is_empty() {
+            current.push(Inline::Text(pending));
+        }
+    }
+    parts.push(current);
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+fn starts_with_marker(inlines: &[Inline]) -> bool {
+    matches!(inlines.first(), Some(Inline::Text(text)) if has_marker(text))
+}
+
+/// Checks whether a line opens a definition: up to three spaces of indentation, a `:`, and
+/// a space or a tab.
+fn has_marker(line: &str) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+    line.len() - trimmed.len() <= 3
+        && trimmed
+            .strip_prefix(':')
+            .is_some_and(|rest| rest.starts_with([' ', '\t']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+    use markdown_ppp::printer::{config::Config, render_markdown};
+
+    #[test]
+    fn test_split_definition_lists_separates_terms_and_definitions() {
+        let markdown = "# Glossary\n\nApple\n: A *red* fruit.\n: A company.\n\n\
+                        Banana\n\n:   A yellow fruit\n    with a second line.\n\nPlain text.\n";
+        let mut doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        split_definition_lists(&mut doc.blocks);
+
+        assert_eq!(
+            render_markdown(&doc, Config::default()),
+            "# Glossary\n\nApple\n\n: A *red* fruit.\n\n: A company.\n\nBanana\n\n\
+             : A yellow fruit with a second line.\n\nPlain text."
+        );
+        let terms: Vec<usize> = (0..doc.blocks.len())
+            .filter(|&index| is_definition_term(&doc.blocks, index))
+            .collect();
+        assert_eq!(terms, vec![1, 4]);
+        assert!(is_definition(&doc.blocks[2]));
+        assert!(!is_definition(&doc.blocks[6]));
+    }
+}
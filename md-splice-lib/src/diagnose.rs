@@ -0,0 +1,474 @@
+//! Explains why a selector matched nothing by finding the nodes that come closest to matching.
+//!
+//! A near miss is a node that satisfies every criterion of the selector but one: it sits outside
+//! the `after`/`within`/`between` scope, has another type, lacks the annotation, comes after the last
+//! ordinal, or has text close to `select_contains`. [`explain_no_match`] attaches them to
+//! [`SpliceError::NodeNotFound`] so the error points at the node the selector was meant for.
+
+use crate::error::SpliceError;
+use crate::infer::block_type_name;
+use crate::inlines::inline_select_type;
+use crate::locator::{locate_all, node_text, node_word_count, FoundNode, Selector, TaskStatus};
+use markdown_ppp::ast::Block;
+use std::fmt;
+
+/// The number of near misses reported for a selector.
+const MAX_NEAR_MISSES: usize = 3;
+
+/// The lowest text similarity, between 0 and 1, for a node to count as a near miss.
+const MIN_SIMILARITY: f64 = 0.6;
+
+/// The number of characters of a node's text quoted in its description.
+const EXCERPT_CHARS: usize = 40;
+
+/// A node that comes close to matching a selector that matched nothing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearMiss {
+    /// The 0-indexed top-level block holding the node.
+    pub block_index: usize,
+    /// The 0-indexed position of the node within its list, for list items.
+    pub item_index: Option<usize>,
+    /// The 0-indexed row of the node within its table, for table rows and cells.
+    pub row_index: Option<usize>,
+    /// The 0-indexed column of the node within its table, for table cells.
+    pub column_index: Option<usize>,
+    /// The 0-indexed position of the node among the inlines of its block, for links,
+    /// images and code spans.
+    pub inline_position: Option<usize>,
+    /// The node type, as accepted by `select_type`.
+    pub node_type: String,
+    /// The beginning of the node's text.
+    pub excerpt: String,
+    /// Why the node does not match.
+    pub reason: String,
+    /// The 1-indexed source line where the top-level block holding the node starts, when
+    /// known (see [`MarkdownDocument::cite_source_lines`](crate::MarkdownDocument::cite_source_lines)).
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for NearMiss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}: ")?;
+        }
+        write!(f, "block {}", self.block_index)?;
+        if let Some(item_index) = self.item_index {
+            write!(f, " item {item_index}")?;
+        }
+        if let Some(row_index) = self.row_index {
+            write!(f, " row {row_index}")?;
+        }
+        if let Some(column_index) = self.column_index {
+            write!(f, " column {column_index}")?;
+        }
+        if let Some(inline_position) = self.inline_position {
+            write!(f, " inline {inline_position}")?;
+        }
+        write!(
+            f,
+            " ({} {:?}): {}",
+            self.node_type, self.excerpt, self.reason
+        )
+    }
+}
+
+/// Turns [`SpliceError::NodeNotFound`] into [`SpliceError::NodeNotFoundWithNearMisses`] when
+/// some nodes of `blocks` come close to matching `selector`. Other errors are returned as is.
+pub fn explain_no_match(err: SpliceError, blocks: &[Block], selector: &Selector) -> SpliceError {
+    match err {
+        SpliceError::NodeNotFound => {
+            let near_misses = nearest_misses(blocks, selector);
+            if near_misses.is_empty() {
+                SpliceError::NodeNotFound
+            } else {
+                SpliceError::NodeNotFoundWithNearMisses(near_misses)
+            }
+        }
+        other => other,
+    }
+}
+
+/// Finds the nodes closest to matching `selector`, most relevant first.
+///
+/// Relaxing a single criterion is tried first, in this order: `select_ordinal`, the
+/// `after`/`within`/`within_slide`/`between` scope, `select_annotation`, `select_tag`, `select_info_contains`,
+/// `select_slug`, `at_line`, `select_not_contains`, `select_not_regex`, `select_depth`, `task_state`,
+/// `recursive`, the word-count criteria and `select_type`.
+/// When `select_contains` is set, nodes of the right type whose text is similar to it come
+/// next.
+pub fn nearest_misses(blocks: &[Block], selector: &Selector) -> Vec<NearMiss> {
+    let base = Selector {
+        select_ordinal: 1,
+        max_matches: None,
+        ..selector.clone()
+    };
+    let mut misses = Vec::new();
+
+    let matches = matches_of(blocks, &base);
+    if !matches.is_empty() {
+        // Every criterion holds, so only the ordinal points past the last match.
+        let reason = format!(
+            "only {} node(s) match, but select_ordinal is {}{}",
+            matches.len(),
+            selector.select_ordinal,
+            if selector.select_last {
+                " from the last"
+            } else {
+                ""
+            }
+        );
+        push_misses(&mut misses, &matches, &reason);
+        return misses;
+    }
+
+    if base.after.is_some()
+        || base.within.is_some()
+        || base.within_slide.is_some()
+        || base.between.is_some()
+    {
+        let unscoped = Selector {
+            after: None,
+            within: None,
+            within_slide: None,
+            between: None,
+            ..base.clone()
+        };
+        let reason = if base.within.is_some() {
+            "outside the `within` scope"
+        } else if base.within_slide.is_some() {
+            "outside the `within_slide` slide"
+        } else if base.between.is_some() {
+            "outside the `between` landmarks"
+        } else {
+            "not after the `after` landmark"
+        };
+        push_relaxed_misses(&mut misses, blocks, &unscoped, reason);
+    }
+
+    if let Some(annotation) = &base.select_annotation {
+        let unannotated = Selector {
+            select_annotation: None,
+            ..base.clone()
+        };
+        let reason = format!("not directly after `<!-- {annotation} -->`");
+        push_relaxed_misses(&mut misses, blocks, &unannotated, &reason);
+    }
+
+    if let Some(tag) = &base.select_tag {
+        let untagged = Selector {
+            select_tag: None,
+            ..base.clone()
+        };
+        let reason = format!("not a `<{tag}>` HTML block");
+        push_relaxed_misses(&mut misses, blocks, &untagged, &reason);
+    }
+
+    if let Some(info) = &base.select_info_contains {
+        let any_info = Selector {
+            select_info_contains: None,
+            ..base.clone()
+        };
+        let reason = format!("not a code block whose info string contains {info:?}");
+        push_relaxed_misses(&mut misses, blocks, &any_info, &reason);
+    }
+
+    if let Some(slug) = &base.select_slug {
+        // The other headings are the nearest misses of a bare slug.
+        let any_slug = Selector {
+            select_slug: None,
+            select_type: base.select_type.clone().or(Some("heading".to_string())),
+            ..base.clone()
+        };
+        let reason = format!("not the heading with anchor `#{slug}`");
+        push_relaxed_misses(&mut misses, blocks, &any_slug, &reason);
+    }
+
+    if let Some(line) = base.at_line {
+        // Blocks are searched without their spans here, so no node matches a line.
+        let anywhere = Selector {
+            at_line: None,
+            ..base.clone()
+        };
+        let reason = format!("not written across line {line}");
+        push_relaxed_misses(&mut misses, blocks, &anywhere, &reason);
+    }
+
+    if let Some(needle) = &base.select_not_contains {
+        let unexcluded = Selector {
+            select_not_contains: None,
+            ..base.clone()
+        };
+        let reason = format!("contains the excluded text {needle:?}");
+        push_relaxed_misses(&mut misses, blocks, &unexcluded, &reason);
+    }
+
+    if let Some(regex) = &base.select_not_regex {
+        let unexcluded = Selector {
+            select_not_regex: None,
+            ..base.clone()
+        };
+        let reason = format!("matches the excluded regex `{regex}`");
+        push_relaxed_misses(&mut misses, blocks, &unexcluded, &reason);
+    }
+
+    if let Some(depth) = base.select_depth {
+        let any_depth = Selector {
+            select_depth: None,
+            ..base.clone()
+        };
+        let reason = format!("not a list item at depth {depth}");
+        push_relaxed_misses(&mut misses, blocks, &any_depth, &reason);
+    }
+
+    if let Some(status) = base.task_state {
+        let any_state = Selector {
+            task_state: None,
+            ..base.clone()
+        };
+        let wanted = match status {
+            TaskStatus::Complete => "a complete task",
+            TaskStatus::Incomplete => "an incomplete task",
+            TaskStatus::Any => "a task",
+        };
+        push_relaxed_misses(&mut misses, blocks, &any_state, &format!("not {wanted}"));
+    }
+
+    if !base.recursive && has_criteria(&base) {
+        let recursive = Selector {
+            recursive: true,
+            ..base.clone()
+        };
+        let nested: Vec<FoundNode> = matches_of(blocks, &recursive)
+            .into_iter()
+            .filter(|found| matches!(found, FoundNode::ContainedBlock { .. }))
+            .collect();
+        let reason = "nested in a container, which only recursive selectors search";
+        push_misses(&mut misses, &nested, reason);
+    }
+
+    if base.select_min_words.is_some()
+        || base.select_max_words.is_some()
+        || base.select_empty.is_some()
+    {
+        let unmeasured = Selector {
+            select_min_words: None,
+            select_max_words: None,
+            select_empty: None,
+            ..base.clone()
+        };
+        let relaxed = if has_criteria(&unmeasured) {
+            matches_of(blocks, &unmeasured)
+        } else {
+            Vec::new()
+        };
+        for found in relaxed {
+            let reason = format!(
+                "has {} word(s), outside the word-count criteria",
+                node_word_count(blocks, &found)
+            );
+            push_misses(&mut misses, &[found], &reason);
+        }
+    }
+
+    if let Some(select_type) = &base.select_type {
+        let untyped = Selector {
+            select_type: None,
+            ..base.clone()
+        };
+        let relaxed = if has_criteria(&untyped) {
+            matches_of(blocks, &untyped)
+        } else {
+            Vec::new()
+        };
+        for found in relaxed {
+            let reason = format!("is a `{}`, not a `{select_type}`", node_type(&found));
+            push_misses(&mut misses, &[found], &reason);
+        }
+    }
+
+    if let Some(needle) = &base.select_contains {
+        let typed = Selector {
+            select_contains: None,
+            after: None,
+            within: None,
+            within_slide: None,
+            ..base.clone()
+        };
+        let mut similar: Vec<(f64, FoundNode)> = matches_of(blocks, &typed)
+            .into_iter()
+            .filter_map(|found| {
+                let text = node_text(&found);
+                // Nodes containing the text verbatim fail another criterion, reported above.
+                let score = similarity(needle, &text);
+                (score >= MIN_SIMILARITY && !text.contains(needle.as_str()))
+                    .then_some((score, found))
+            })
+            .collect();
+        similar.sort_by(|left, right| right.0.total_cmp(&left.0));
+        for (score, found) in similar {
+            let reason = if score >= 1.0 {
+                format!("contains {needle:?} with different capitalization")
+            } else {
+                format!("text is {:.0}% similar to {needle:?}", score * 100.0)
+            };
+            push_misses(&mut misses, &[found], &reason);
+        }
+    }
+
+    misses
+}
+
+/// Returns the nodes matching `selector`, or none when its scope cannot be resolved.
+fn matches_of<'a>(blocks: &'a [Block], selector: &Selector) -> Vec<FoundNode<'a>> {
+    locate_all(blocks, selector).unwrap_or_default()
+}
+
+/// Records the nodes matching `relaxed`, a selector with one criterion removed, unless no
+/// criterion is left to tell them apart from any other node.
+fn push_relaxed_misses(
+    misses: &mut Vec<NearMiss>,
+    blocks: &[Block],
+    relaxed: &Selector,
+    reason: &str,
+) {
+    if has_criteria(relaxed) {
+        push_misses(misses, &matches_of(blocks, relaxed), reason);
+    }
+}
+
+fn has_criteria(selector: &Selector) -> bool {
+    selector.select_type.is_some()
+        || selector.select_contains.is_some()
+        || selector.select_contains_ci.is_some()
+        || selector.select_regex.is_some()
+        || selector.select_fingerprint.is_some()
+        || selector.select_annotation.is_some()
+        || selector.select_tag.is_some()
+        || selector.select_info_contains.is_some()
+        || selector.select_slug.is_some()
+        || selector.select_depth.is_some()
+        || selector.task_state.is_some()
+}
+
+/// Records `found` nodes not reported yet, up to [`MAX_NEAR_MISSES`] in total.
+fn push_misses(misses: &mut Vec<NearMiss>, found: &[FoundNode], reason: &str) {
+    for node in found {
+        if misses.len() >= MAX_NEAR_MISSES {
+            return;
+        }
+        let (block_index, item_index, row_index, column_index, inline_position) = match node {
+            FoundNode::Block { index, .. } => (*index, None, None, None, None),
+            FoundNode::ContainedBlock { block_index, .. } => (*block_index, None, None, None, None),
+            FoundNode::ListItem {
+                block_index,
+                item_index,
+                ..
+            } => (*block_index, Some(*item_index), None, None, None),
+            FoundNode::TableRow {
+                block_index,
+                row_index,
+                ..
+            } => (*block_index, None, Some(*row_index), None, None),
+            FoundNode::TableCell {
+                block_index,
+                row_index,
+                column_index,
+                ..
+            } => (
+                *block_index,
+                None,
+                Some(*row_index),
+                Some(*column_index),
+                None,
+            ),
+            FoundNode::Inline {
+                block_index,
+                position,
+                ..
+            } => (*block_index, None, None, None, Some(*position)),
+        };
+        let already_reported = misses.iter().any(|miss| {
+            miss.block_index == block_index
+                && miss.item_index == item_index
+                && miss.row_index == row_index
+                && miss.column_index == column_index
+                && miss.inline_position == inline_position
+        });
+        if !already_reported {
+            misses.push(NearMiss {
+                block_index,
+                item_index,
+                row_index,
+                column_index,
+                inline_position,
+                node_type: node_type(node),
+                excerpt: excerpt(&node_text(node)),
+                reason: reason.to_string(),
+                line: None,
+            });
+        }
+    }
+}
+
+fn node_type(found: &FoundNode) -> String {
+    match found {
+        FoundNode::Block { block, .. } | FoundNode::ContainedBlock { block, .. } => {
+            block_type_name(block)
+        }
+        FoundNode::ListItem { .. } => "li".to_string(),
+        FoundNode::TableRow { .. } => "tr".to_string(),
+        FoundNode::TableCell { .. } => "td".to_string(),
+        FoundNode::Inline { inline, .. } => inline_select_type(inline).to_string(),
+    }
+}
+
+/// Returns the first line of `text`, shortened to [`EXCERPT_CHARS`] characters.
+fn excerpt(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    if line.chars().count() <= EXCERPT_CHARS {
+        return line.to_string();
+    }
+    let mut shortened: String = line.chars().take(EXCERPT_CHARS).collect();
+    shortened.push('…');
+    shortened
+}
+
+/// Scores how closely `needle` appears somewhere in `text`, ignoring case: 1 when it occurs
+/// verbatim, decreasing with the number of character edits the closest substring needs.
+fn similarity(needle: &str, text: &str) -> f64 {
+    let needle: Vec<char> = needle.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    if needle.is_empty() {
+        return 0.0;
+    }
+
+    // Edit distance between `needle` and the best-matching substring of `text`: the first
+    // row is all zeros so that a match may start anywhere, and the best cell of the last
+    // row lets it end anywhere.
+    let mut previous = vec![0; text.len() + 1];
+    for (row, needle_char) in needle.iter().enumerate() {
+        let mut current = vec![row + 1; text.len() + 1];
+        for (column, text_char) in text.iter().enumerate() {
+            let substitution = previous[column] + usize::from(needle_char != text_char);
+            current[column + 1] = substitution
+                .min(previous[column + 1] + 1)
+                .min(current[column] + 1);
+        }
+        previous = current;
+    }
+    let distance = previous.into_iter().min().unwrap_or(needle.len());
+
+    1.0 - distance as f64 / needle.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similarity_finds_closest_substring() {
+        assert_eq!(similarity("Install", "Run the installer."), 1.0);
+        assert_eq!(similarity("packages", "Install the pakages."), 0.875);
+        assert!(similarity("Deployment", "Usage") < MIN_SIMILARITY);
+    }
+}
@@ -0,0 +1,351 @@
+//! Reports the constructs of a document that md-splice does not preserve faithfully.
+//!
+//! md-splice edits the parsed document and renders it again, so anything the parser does not
+//! model is normalized or lost on the way. [`examine`] parses a document the way an edit
+//! would, renders it back, and lists the constructs that are known to be affected:
+//!
+//! * malformed regions that only parse leniently;
+//! * frontmatter written between delimiters md-splice does not recognize;
+//! * setext headings, whose underline is redrawn;
+//! * link reference definitions, which are separate blocks from the links using them;
+//! * HTML blocks, whose content is opaque to selectors;
+//! * MDX statements, components, and expressions, which are not understood at all.
+
+use crate::error::SpliceError;
+use crate::{MarkdownDocument, ParseOptions};
+use markdown_ppp::ast::{Block, HeadingKind};
+use std::fmt;
+
+/// A construct that may not survive editing faithfully.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindingKind {
+    /// Markdown that is malformed, and only kept verbatim in lenient mode.
+    Malformed,
+    /// Leading lines that look like frontmatter between unrecognized delimiters.
+    UnrecognizedFrontmatter {
+        /// The delimiter line that opens and closes the block.
+        delimiter: String,
+    },
+    /// A heading underlined with `=` or `-`.
+    SetextHeading,
+    /// A link reference definition such as `[label]: https://example.com`.
+    ReferenceDefinition,
+    /// A block of raw HTML.
+    HtmlBlock,
+    /// MDX syntax: an `import`/`export` statement, a JSX component, or an expression.
+    Mdx,
+    /// Rendering the document and parsing it again does not give the same document.
+    UnstableRoundTrip,
+}
+
+/// A construct found by [`examine`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// The 1-indexed line where the construct starts, if it has a single location.
+    pub line: Option<usize>,
+    /// What was found.
+    pub kind: FindingKind,
+    /// A human-readable description of what happens to the construct when it is edited.
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// The result of [`examine`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctorReport {
+    /// The constructs found, in document order, followed by document-wide findings.
+    pub findings: Vec<Finding>,
+    /// The document as md-splice renders it after parsing, without any edit.
+    pub rendered: String,
+}
+
+impl DoctorReport {
+    /// Returns `true` when rendering the document reproduces its source exactly.
+    pub fn round_trip_is_exact(&self, content: &str) -> bool {
+        self.rendered == content
+    }
+}
+
+/// Parses `content` with `options`, renders it back, and reports the constructs that do not
+/// survive editing faithfully.
+///
+/// Regions that lenient mode would recover, such as an unclosed code fence, are reported as
+/// [`FindingKind::Malformed`]; a document that only parses in lenient mode is examined
+/// leniently. Invalid frontmatter is still reported as an error.
+pub fn examine(content: &str, options: &ParseOptions) -> Result<DoctorReport, SpliceError> {
+    let lenient = ParseOptions {
+        lenient: true,
+        ..options.clone()
+    };
+    let (lenient_doc, warnings) = MarkdownDocument::from_str_with_options(content, &lenient)?;
+    let mut findings: Vec<Finding> = warnings.into_iter().map(malformed).collect();
+    let (doc, options) = match MarkdownDocument::from_str_with_options(content, options) {
+        Ok((doc, _)) => (doc, options),
+        Err(_) => (lenient_doc, &lenient),
+    };
+
+    let body = &doc.parsed.body;
+    let first_line = content[..content.len() - body.len()].matches('\n').count() + 1;
+    if doc.parsed.frontmatter.is_none() {
+        findings.extend(unrecognized_frontmatter(body));
+    }
+    findings.extend(scan_body(body, first_line, doc.blocks()));
+    findings.sort_by_key(|finding| finding.line);
+
+    let rendered = doc.render();
+    let stable = MarkdownDocument::from_str_with_options(&rendered, options)
+        .is_ok_and(|(reparsed, _)| doc.semantically_equals(&reparsed));
+    if !stable {
+        findings.push(Finding {
+            line: None,
+            kind: FindingKind::UnstableRoundTrip,
+            message: "parsing the rendered document gives a different document, so every edit \
+                      also changes its structure"
+                .to_string(),
+        });
+    }
+
+    Ok(DoctorReport { findings, rendered })
+}
+
+fn malformed(warning: crate::lenient::ParseWarning) -> Finding {
+    Finding {
+        line: Some(warning.line),
+        kind: FindingKind::Malformed,
+        message: format!(
+            "malformed Markdown; when parsed leniently: {}",
+            warning.message
+        ),
+    }
+}
+
+/// Detects a first line of three or more identical punctuation characters repeated later on a
+/// line of its own, such as `;;;` frontmatter.
+fn unrecognized_frontmatter(body: &str) -> Option<Finding> {
+    let mut lines = body.lines();
+    let delimiter = lines.next()?.trim_end();
+    let mut chars = delimiter.chars();
+    let first = chars.next()?;
+    if delimiter.len() < 3 || !first.is_ascii_punctuation() || !chars.all(|c| c == first) {
+        return None;
+    }
+    lines
+        .any(|line| line.trim_end() == delimiter)
+        .then(|| Finding {
+            line: Some(1),
+            kind: FindingKind::UnrecognizedFrontmatter {
+                delimiter: delimiter.to_string(),
+            },
+            message: format!(
+                "the document starts with a block between `{delimiter}` lines, which is not \
+             recognized as frontmatter and is edited as Markdown"
+            ),
+        })
+}
+
+/// Scans the lines of the body, outside code fences, for the constructs found in `blocks`.
+fn scan_body(body: &str, first_line: usize, blocks: &[Block]) -> Vec<Finding> {
+    let has_setext = blocks.iter().any(|block| {
+        matches!(block, Block::Heading(heading) if matches!(heading.kind, HeadingKind::Setext(_)))
+    });
+    let has_definitions = blocks
+        .iter()
+        .any(|block| matches!(block, Block::Definition(_)));
+
+    let lines: Vec<&str> = body.lines().collect();
+    let mut findings = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = strip_indent(line);
+        if let Some((marker, length)) = fence {
+            if fence_marker(trimmed).is_some_and(|(c, n)| c == marker && n >= length)
+                && trimmed.trim_start_matches(marker).trim().is_empty()
+            {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(opening) = fence_marker(trimmed) {
+            fence = Some(opening);
+            continue;
+        }
+
+        let previous = index.checked_sub(1).map(|previous| lines[previous]);
+        let starts_block = previous.is_none_or(|previous| previous.trim().is_empty());
+        let finding = |kind, message: String| Finding {
+            line: Some(first_line + index),
+            kind,
+            message,
+        };
+
+        if has_setext
+            && !starts_block
+            && is_setext_underline(trimmed)
+            && previous.is_some_and(continues_paragraph)
+        {
+            findings.push(finding(
+                FindingKind::SetextHeading,
+                "setext heading: its underline is redrawn at a fixed width, and replacing it \
+                 with `#` content switches it to ATX style"
+                    .to_string(),
+            ));
+        } else if has_definitions && is_reference_definition(trimmed) {
+            let label = &trimmed[1..trimmed.find("]:").unwrap_or(1)];
+            findings.push(finding(
+                FindingKind::ReferenceDefinition,
+                format!(
+                    "link reference definition [{label}]: it is a block of its own, so links \
+                     using it break when it is deleted or split into another file"
+                ),
+            ));
+        } else if starts_block {
+            if let Some((kind, message)) = classify_block_start(trimmed) {
+                findings.push(finding(kind, message));
+            }
+        }
+    }
+    findings
+}
+
+/// Classifies the first line of a block as HTML or MDX.
+fn classify_block_start(line: &str) -> Option<(FindingKind, String)> {
+    if ["import ", "export "]
+        .iter()
+        .any(|keyword| line.starts_with(keyword))
+    {
+        return Some((
+            FindingKind::Mdx,
+            "MDX statement: it is parsed as a paragraph and may be reflowed into adjacent text"
+                .to_string(),
+        ));
+    }
+    if line.starts_with('{') && line.trim_end().ends_with('}') {
+        return Some((
+            FindingKind::Mdx,
+            "MDX expression: it is parsed as a paragraph and may be reflowed into adjacent text"
+                .to_string(),
+        ));
+    }
+    let tag = line.strip_prefix('<')?;
+    let name: String = tag
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '.')
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return Some((
+            FindingKind::Mdx,
+            format!(
+                "MDX component <{name}>: it is kept as raw HTML, so its props and children \
+                 cannot be selected"
+            ),
+        ));
+    }
+    if name.starts_with(|c: char| c.is_ascii_lowercase()) {
+        return Some((
+            FindingKind::HtmlBlock,
+            format!(
+                "HTML block <{name}>: its text is opaque to selectors, and Markdown between \
+                 its tags is parsed as separate blocks"
+            ),
+        ));
+    }
+    None
+}
+
+/// Strips up to three spaces of indentation, which do not change the meaning of a line.
+fn strip_indent(line: &str) -> &str {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() <= 3 {
+        trimmed
+    } else {
+        line
+    }
+}
+
+/// Returns the marker character and length of a code fence line.
+fn fence_marker(line: &str) -> Option<(char, usize)> {
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = line.chars().take_while(|c| *c == marker).count();
+    (length >= 3).then_some((marker, length))
+}
+
+fn is_setext_underline(line: &str) -> bool {
+    let line = line.trim_end();
+    !line.is_empty() && (line.chars().all(|c| c == '=') || line.chars().all(|c| c == '-'))
+}
+
+/// Returns whether `line` can be the last line of a paragraph, rather than a list item, a
+/// block quote, a heading, or a table row.
+fn continues_paragraph(line: &str) -> bool {
+    let line = strip_indent(line);
+    let list_item = line
+        .strip_prefix(['-', '*', '+'])
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']));
+    !list_item && !line.starts_with(['>', '#', '|', '<'])
+}
+
+fn is_reference_definition(line: &str) -> bool {
+    line.starts_with('[')
+        && !line.starts_with("[^")
+        && line
+            .find("]:")
+            .is_some_and(|end| end > 1 && !line[1..end].contains(['[', ']']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examine_reports_constructs_by_line() {
+        let content = "Title\n=====\n\nSee [docs].\n\n[docs]: https://example.com\n\n\
+                       ```md\n<div>\n```\n\n<details>\n\n*text*\n\n</details>\n\n\
+                       import { Chart } from './chart'\n\n<Chart />\n";
+
+        let report = examine(content, &ParseOptions::default()).unwrap();
+
+        let found: Vec<(Option<usize>, FindingKind)> = report
+            .findings
+            .iter()
+            .map(|finding| (finding.line, finding.kind.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some(2), FindingKind::SetextHeading),
+                (Some(6), FindingKind::ReferenceDefinition),
+                (Some(12), FindingKind::HtmlBlock),
+                (Some(18), FindingKind::Mdx),
+                (Some(20), FindingKind::Mdx),
+            ]
+        );
+        assert!(!report.round_trip_is_exact(content));
+    }
+
+    #[test]
+    fn test_examine_recovers_from_malformed_markdown() {
+        let content = ";;;\ntitle: x\n;;;\n\n# Notes\n\n```rust\nfn main() {}\n";
+
+        let report = examine(content, &ParseOptions::default()).unwrap();
+
+        let kinds: Vec<&FindingKind> = report.findings.iter().map(|f| &f.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &FindingKind::UnrecognizedFrontmatter {
+                    delimiter: ";;;".to_string()
+                },
+                &FindingKind::Malformed,
+            ]
+        );
+        assert_eq!(report.findings[1].line, Some(7));
+    }
+}
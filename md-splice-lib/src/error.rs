@@ -1,5 +1,6 @@
 //! Defines custom error types for the application.
 
+use crate::diagnose::NearMiss;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,6 +9,12 @@ pub enum SpliceError {
     #[error("Selector did not match any nodes in the document")]
     NodeNotFound,
 
+    #[error(
+        "Selector did not match any nodes in the document. Nearest misses:{}",
+        list_near_misses(.0)
+    )]
+    NodeNotFoundWithNearMisses(Vec<NearMiss>),
+
     #[error("Invalid operation: Cannot insert child content into a '{0}'. Use --position 'before' or 'after' to insert as a sibling.")]
     InvalidChildInsertion(String),
 
@@ -28,15 +35,24 @@ pub enum SpliceError {
     #[error("The --section flag can only be used when deleting a heading (h1-h6).")]
     InvalidSectionDelete,
 
+    #[error("The move destination lies inside the content being moved.")]
+    InvalidMoveDestination,
+
+    #[error("The swapped nodes overlap: one lies inside the other.")]
+    OverlappingSwap,
+
     #[error("The --section flag can only be used when targeting a heading (h1-h6).")]
     SectionRequiresHeading,
 
-    #[error("Cannot combine --after-* and --within-* selectors in the same query.")]
+    #[error("Cannot combine more than one of --after-*, --within-*, --within-slide and between in the same query.")]
     ConflictingScopeModifiers,
 
     #[error("Range selectors are only supported for block-level selections.")]
     RangeRequiresBlock,
 
+    #[error("Selector matched {found} nodes, more than its max_matches limit of {max}.")]
+    TooManyMatches { found: usize, max: usize },
+
     #[error("Selector alias '{0}' was referenced before being defined.")]
     SelectorAliasNotDefined(String),
 
@@ -70,6 +86,28 @@ pub enum SpliceError {
     #[error("Operation failed: {0}")]
     OperationFailed(String),
 
+    #[error("Assertion failed: {0}")]
+    AssertionFailed(String),
+
+    #[error("Conflicting operations detected: {0}")]
+    ConflictingOperations(String),
+
+    #[error("Limit exceeded: {0}")]
+    LimitExceeded(String),
+
+    #[error("Time limit exceeded: {0}")]
+    TimeLimitExceeded(String),
+
+    #[error("Operation not allowed: {0}")]
+    OperationNotAllowed(String),
+
     #[error("I/O error: {0}")]
     Io(String),
 }
+
+fn list_near_misses(near_misses: &[NearMiss]) -> String {
+    near_misses
+        .iter()
+        .map(|near_miss| format!("\n  - {near_miss}"))
+        .collect()
+}
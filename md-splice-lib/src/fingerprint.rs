@@ -0,0 +1,124 @@
+//! Content fingerprints that identify a node independently of its position.
+//!
+//! A fingerprint hashes a node's type together with its text, with runs of whitespace
+//! collapsed, so it survives re-wrapping and changes to the surrounding document. Two
+//! nodes with the same type and text share a fingerprint; use `select_ordinal` to pick
+//! between them.
+//!
+//! Fingerprints are 16 lowercase hex digits of a 64-bit FNV-1a hash. The algorithm is
+//! fixed, so fingerprints can be stored and compared across runs and versions.
+
+use crate::infer::block_type_name;
+use crate::inlines::{inline_match_text, inline_select_type};
+use crate::locator::{block_to_text, inlines_to_text, list_item_to_text, FoundNode};
+use crate::table::row_text;
+use markdown_ppp::ast::{Block, Inline, ListItem, TableCell};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Returns the fingerprint of a block, derived from its type (as in `select_type`) and text.
+pub fn block_fingerprint(block: &Block) -> String {
+    fingerprint(&block_type_name(block), &block_to_text(block))
+}
+
+/// Returns the fingerprint of a list item, derived from the text of its blocks.
+pub fn list_item_fingerprint(item: &ListItem) -> String {
+    fingerprint("li", &list_item_to_text(item))
+}
+
+/// Returns the fingerprint of a table row, derived from the text of its cells.
+pub fn table_row_fingerprint(row: &[TableCell]) -> String {
+    fingerprint("tr", &row_text(row))
+}
+
+/// Returns the fingerprint of a table cell, derived from its text.
+pub fn table_cell_fingerprint(cell: &[Inline]) -> String {
+    fingerprint("td", &inlines_to_text(cell))
+}
+
+/// Returns the fingerprint of a link, an image or a code span, derived from the text it is
+/// matched by.
+pub fn inline_fingerprint(inline: &Inline) -> String {
+    fingerprint(inline_select_type(inline), &inline_match_text(inline))
+}
+
+/// Returns the fingerprint of a matched node, as `select_fingerprint` compares it.
+pub fn node_fingerprint(found: &FoundNode) -> String {
+    match found {
+        FoundNode::Block { block, .. } | FoundNode::ContainedBlock { block, .. } => {
+            block_fingerprint(block)
+        }
+        FoundNode::ListItem { item, .. } => list_item_fingerprint(item),
+        FoundNode::TableRow { row, .. } => table_row_fingerprint(row),
+        FoundNode::TableCell { cell, .. } => table_cell_fingerprint(cell),
+        FoundNode::Inline { inline, .. } => inline_fingerprint(inline),
+    }
+}
+
+/// Reports whether `fingerprint` starts with `prefix`, ignoring ASCII case.
+///
+/// An empty prefix matches nothing, so that a missing value never selects the first node.
+pub(crate) fn fingerprint_matches(fingerprint: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim();
+    !prefix.is_empty()
+        && fingerprint
+            .get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+}
+
+fn fingerprint(kind: &str, text: &str) -> String {
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in kind.bytes().chain([0]).chain(normalized.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MarkdownDocument;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_fingerprint_ignores_position_and_wrapping() {
+        let before = MarkdownDocument::from_str("# Title\n\nSome text\nwrapped here.").unwrap();
+        let after =
+            MarkdownDocument::from_str("# Title\n\nIntro.\n\nSome  text wrapped here.").unwrap();
+
+        assert_eq!(
+            block_fingerprint(&before.blocks()[1]),
+            block_fingerprint(&after.blocks()[2])
+        );
+        assert_ne!(
+            block_fingerprint(&before.blocks()[0]),
+            block_fingerprint(&before.blocks()[1])
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_depends_on_type() {
+        let doc = MarkdownDocument::from_str("# Same\n\nSame").unwrap();
+        let fingerprints = doc.fingerprints();
+
+        assert_eq!(fingerprints.len(), 2);
+        assert_ne!(fingerprints[0], fingerprints[1]);
+        assert_eq!(fingerprints[0].len(), 16);
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        assert_eq!(fingerprint("p", ""), "08d5a807b576079d");
+    }
+
+    #[test]
+    fn test_fingerprint_matches_prefix() {
+        assert!(fingerprint_matches("0123abcd", "0123AB"));
+        assert!(!fingerprint_matches("0123abcd", "123"));
+        assert!(!fingerprint_matches("0123abcd", ""));
+        assert!(!fingerprint_matches("0123", "0123abcd"));
+    }
+}
@@ -1,8 +1,8 @@
 use anyhow::{anyhow, Context};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_yaml::Value as YamlValue;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 /// The serialization format used for the document frontmatter.
 pub enum FrontmatterFormat {
@@ -20,6 +20,7 @@ pub struct ParsedDocument {
     /// Serialization format of the frontmatter block.
     pub format: Option<FrontmatterFormat>,
     pub(crate) frontmatter_block: Option<String>,
+    pub(crate) delimiter: Option<FrontmatterDelimiter>,
 }
 
 impl ParsedDocument {
@@ -28,42 +29,95 @@ impl ParsedDocument {
             self.format = Some(FrontmatterFormat::Yaml);
         }
     }
+
+    /// Returns the frontmatter text exactly as written between its delimiters.
+    ///
+    /// Once the frontmatter has been modified, this reflects the re-serialized block.
+    pub fn raw_frontmatter(&self) -> Option<&str> {
+        let block = self.frontmatter_block.as_deref()?;
+        let delimiter = self.delimiter.as_ref()?;
+        let bounds = find_frontmatter(block, std::slice::from_ref(delimiter)).ok()??;
+        Some(bounds.raw)
+    }
+}
+
+/// A pair of delimiter lines enclosing a frontmatter block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrontmatterDelimiter {
+    /// The line that opens the block, such as `---`.
+    pub open: String,
+    /// The line that closes the block, such as `---` or `...`.
+    pub close: String,
+    /// The format used to parse the text between the delimiters.
+    pub format: FrontmatterFormat,
+}
+
+impl FrontmatterDelimiter {
+    /// Creates a delimiter pair for the given format.
+    pub fn new(
+        open: impl Into<String>,
+        close: impl Into<String>,
+        format: FrontmatterFormat,
+    ) -> Self {
+        Self {
+            open: open.into(),
+            close: close.into(),
+            format,
+        }
+    }
+
+    /// Returns the delimiters recognized without any configuration: `---` closed by `---`
+    /// or `...` for YAML, and `+++` for TOML.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new("---", "---", FrontmatterFormat::Yaml),
+            Self::new("---", "...", FrontmatterFormat::Yaml),
+            Self::new("+++", "+++", FrontmatterFormat::Toml),
+        ]
+    }
+
+    fn default_for(format: FrontmatterFormat) -> Self {
+        match format {
+            FrontmatterFormat::Yaml => Self::new("---", "---", format),
+            FrontmatterFormat::Toml => Self::new("+++", "+++", format),
+        }
+    }
 }
 
 /// Splits the provided Markdown source into frontmatter metadata and body content.
 pub fn parse(content: &str) -> anyhow::Result<ParsedDocument> {
+    parse_with_delimiters(content, &[])
+}
+
+/// Behaves like [`parse`], additionally recognizing the `extra` delimiter pairs.
+///
+/// Extra delimiters are tried before the [defaults](FrontmatterDelimiter::defaults), and
+/// the matched pair is reused when the frontmatter is serialized again.
+pub fn parse_with_delimiters(
+    content: &str,
+    extra: &[FrontmatterDelimiter],
+) -> anyhow::Result<ParsedDocument> {
     let mut parsed = ParsedDocument {
         frontmatter: None,
         body: content.to_string(),
         format: None,
         frontmatter_block: None,
+        delimiter: None,
     };
 
-    let Some(first_line) = content.lines().next() else {
-        return Ok(parsed);
-    };
-
-    let first_line = first_line.trim_end_matches('\r');
-    let (format, delimiter) = match first_line {
-        "---" => (FrontmatterFormat::Yaml, "---"),
-        "+++" => (FrontmatterFormat::Toml, "+++"),
-        _ => return Ok(parsed),
-    };
-
-    let Some(rest) = strip_opening_delimiter(content, delimiter) else {
+    let delimiters: Vec<_> = extra
+        .iter()
+        .cloned()
+        .chain(FrontmatterDelimiter::defaults())
+        .collect();
+    let Some(bounds) = find_frontmatter(content, &delimiters)? else {
         return Ok(parsed);
     };
 
-    let (frontmatter_str, body_start_idx) =
-        extract_frontmatter_block(rest, delimiter).ok_or_else(|| {
-            anyhow!(
-                "Failed to locate closing frontmatter delimiter `{delimiter}` at start of document"
-            )
-        })?;
-
-    let opening_len = content.len() - rest.len();
-    let frontmatter_block = &content[..opening_len + body_start_idx];
-    let body_slice = &content[opening_len + body_start_idx..];
+    let format = bounds.delimiter.format;
+    let frontmatter_str = bounds.raw;
+    let frontmatter_block = &content[..bounds.body_start];
+    let body_slice = &content[bounds.body_start..];
 
     let frontmatter_value = match format {
         FrontmatterFormat::Yaml => {
@@ -86,80 +140,271 @@ pub fn parse(content: &str) -> anyhow::Result<ParsedDocument> {
     parsed.body = body_slice.to_string();
     parsed.format = Some(format);
     parsed.frontmatter_block = Some(frontmatter_block.to_string());
+    parsed.delimiter = Some(bounds.delimiter.clone());
 
     Ok(parsed)
 }
 
+/// Returns the frontmatter text exactly as written between its delimiters.
+///
+/// The delimiter lines are excluded while whitespace, comments and line endings are kept
+/// verbatim. Returns `None` when the document does not start with a terminated
+/// frontmatter block.
+pub fn raw_frontmatter(content: &str) -> Option<&str> {
+    let delimiters = FrontmatterDelimiter::defaults();
+    let bounds = find_frontmatter(content, &delimiters).ok()??;
+    Some(bounds.raw)
+}
+
+/// The location of a frontmatter block at the start of a document.
+struct FrontmatterBounds<'a, 'd> {
+    delimiter: &'d FrontmatterDelimiter,
+    /// The text between the delimiter lines.
+    raw: &'a str,
+    /// The byte offset at which the Markdown body starts.
+    body_start: usize,
+}
+
+/// Finds the frontmatter block opened by the first line of `content`.
+///
+/// Returns `Ok(None)` when the first line is not an opening delimiter, and an error when
+/// the block is never closed.
+fn find_frontmatter<'a, 'd>(
+    content: &'a str,
+    delimiters: &'d [FrontmatterDelimiter],
+) -> anyhow::Result<Option<FrontmatterBounds<'a, 'd>>> {
+    let Some(first_line) = content.lines().next() else {
+        return Ok(None);
+    };
+    let first_line = first_line.trim_end_matches('\r');
+    let candidates: Vec<_> = delimiters
+        .iter()
+        .filter(|delimiter| delimiter.open == first_line)
+        .collect();
+    let Some(rest) = candidates
+        .first()
+        .and_then(|delimiter| strip_opening_delimiter(content, &delimiter.open))
+    else {
+        return Ok(None);
+    };
+    let opening_len = content.len() - rest.len();
+
+    let mut offset = 0;
+    for line in rest.split_terminator('\n') {
+        let trimmed = line.trim_end_matches('\r');
+
+        if let Some(delimiter) = candidates.iter().find(|d| d.close == trimmed) {
+            let mut body_start = offset + line.len();
+
+            if rest.len() > body_start && rest.as_bytes()[body_start] == b'\n' {
+                body_start += 1;
+            }
+
+            return Ok(Some(FrontmatterBounds {
+                delimiter,
+                raw: &rest[..offset],
+                body_start: opening_len + body_start,
+            }));
+        }
+
+        offset += line.len() + 1;
+    }
+
+    Err(anyhow!(
+        "Failed to locate closing frontmatter delimiter `{}` at start of document",
+        candidates[0].close
+    ))
+}
+
 /// Recomputes the raw frontmatter block from the structured YAML representation.
-pub fn refresh_frontmatter_block(parsed: &mut ParsedDocument) -> anyhow::Result<()> {
+///
+/// The delimiters found when parsing are reused as long as the format is unchanged. For
+/// YAML frontmatter, top-level entries whose values did not change keep their original
+/// text, so anchors, aliases, tags, comments and quoting survive edits to other keys.
+pub(crate) fn refresh_frontmatter_block(parsed: &mut ParsedDocument) -> anyhow::Result<()> {
     if parsed.frontmatter.is_some() {
         parsed.ensure_format();
         let format = parsed
             .format
             .ok_or_else(|| anyhow!("Frontmatter format missing during serialization"))?;
 
-        let block = {
-            let value = parsed
-                .frontmatter
-                .as_ref()
-                .ok_or_else(|| anyhow!("Frontmatter missing during serialization"))?;
-            serialize_frontmatter_block(value, format)?
+        let value = parsed
+            .frontmatter
+            .as_ref()
+            .ok_or_else(|| anyhow!("Frontmatter missing during serialization"))?;
+        let preserved = match (format, parsed.delimiter.as_ref()) {
+            (FrontmatterFormat::Yaml, Some(previous))
+                if previous.format == FrontmatterFormat::Yaml =>
+            {
+                parsed
+                    .raw_frontmatter()
+                    .and_then(|raw| preserve_untouched_entries(raw, value))
+            }
+            _ => None,
+        };
+        let serialized = match preserved {
+            Some(serialized) => serialized,
+            None => serialize_frontmatter_value(value, format)?,
+        };
+        let delimiter = match parsed.delimiter.take() {
+            Some(previous) if previous.format == format => previous,
+            _ => FrontmatterDelimiter::default_for(format),
         };
 
-        parsed.frontmatter_block = Some(block);
+        parsed.frontmatter_block = Some(wrap_frontmatter_block(&serialized, &delimiter));
+        parsed.delimiter = Some(delimiter);
     } else {
         parsed.frontmatter_block = None;
         parsed.format = None;
+        parsed.delimiter = None;
     }
 
     Ok(())
 }
 
-fn serialize_frontmatter_block(
+/// Re-serializes `value` while reusing the original text of unchanged top-level entries
+/// from the `raw` YAML frontmatter.
+///
+/// Returns `None` when the previous block cannot be split into top-level entries or when
+/// the merged text would not parse back to `value` (for example, because an untouched
+/// entry refers to an anchor defined in an entry that changed).
+fn preserve_untouched_entries(raw: &str, value: &YamlValue) -> Option<String> {
+    let YamlValue::Mapping(original) = serde_yaml::from_str::<YamlValue>(raw).ok()? else {
+        return None;
+    };
+    let YamlValue::Mapping(updated) = value else {
+        return None;
+    };
+
+    let (entries, tail) = split_top_level_entries(raw)?;
+    if entries.len() != original.len() {
+        return None;
+    }
+
+    let mut merged = String::new();
+    for (key, new_value) in updated {
+        let position = original.keys().position(|candidate| candidate == key);
+        match position {
+            Some(index) if original.get(key) == Some(new_value) => {
+                merged.push_str(entries[index].leading);
+                merged.push_str(entries[index].text);
+            }
+            _ => {
+                if let Some(index) = position {
+                    merged.push_str(entries[index].leading);
+                }
+                let mut entry = serde_yaml::Mapping::new();
+                entry.insert(key.clone(), new_value.clone());
+                merged.push_str(&serialize_yaml_value(&YamlValue::Mapping(entry)).ok()?);
+                merged.push('\n');
+            }
+        }
+    }
+    merged.push_str(tail);
+
+    let body = merged.trim_end_matches(['\n', '\r']);
+    if updated.is_empty() || serde_yaml::from_str::<YamlValue>(body).ok()? != *value {
+        return None;
+    }
+
+    Some(body.to_string())
+}
+
+/// A top-level entry of a YAML mapping, as written in the source.
+struct RawEntry<'a> {
+    /// Comment and blank lines preceding the entry.
+    leading: &'a str,
+    /// The key line and its indented continuation lines.
+    text: &'a str,
+}
+
+/// Splits a block-style YAML mapping into its top-level entries and trailing comments.
+fn split_top_level_entries(raw: &str) -> Option<(Vec<RawEntry<'_>>, &str)> {
+    let mut entries = Vec::new();
+    let mut leading_start = 0;
+    let mut entry_start: Option<usize> = None;
+    let mut pending_start: Option<usize> = None;
+    let mut offset = 0;
+
+    for line in raw.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() || content.starts_with('#') {
+            pending_start.get_or_insert(line_start);
+            continue;
+        }
+
+        if content.starts_with([' ', '\t']) {
+            entry_start?;
+            pending_start = None;
+            continue;
+        }
+
+        if content.starts_with(['-', '?', '[', '{']) || content.starts_with("...") {
+            return None;
+        }
+
+        let key_start = pending_start.take().unwrap_or(line_start);
+        if let Some(start) = entry_start {
+            entries.push(RawEntry {
+                leading: &raw[leading_start..start],
+                text: &raw[start..key_start],
+            });
+            leading_start = key_start;
+        } else {
+            leading_start = 0;
+        }
+        entry_start = Some(line_start);
+    }
+
+    let tail_start = pending_start.unwrap_or(raw.len());
+    let start = entry_start?;
+    entries.push(RawEntry {
+        leading: &raw[leading_start..start],
+        text: &raw[start..tail_start],
+    });
+
+    Some((entries, &raw[tail_start..]))
+}
+
+fn serialize_frontmatter_value(
     value: &YamlValue,
     format: FrontmatterFormat,
 ) -> anyhow::Result<String> {
-    let (delimiter, mut serialized) = match format {
-        FrontmatterFormat::Yaml => {
-            if value.is_null() {
-                ("---", String::new())
-            } else {
-                let yaml = serialize_yaml_value(value)?;
-                ("---", yaml)
-            }
-        }
+    if value.is_null() {
+        return Ok(String::new());
+    }
+
+    match format {
+        FrontmatterFormat::Yaml => serialize_yaml_value(value),
         FrontmatterFormat::Toml => {
-            if value.is_null() {
-                ("+++", String::new())
-            } else {
-                let toml_value: toml::Value =
-                    serde_yaml::from_value(value.clone()).map_err(|err| {
-                        anyhow!("Failed to convert YAML value into TOML frontmatter: {err}")
-                    })?;
-                let toml = toml::to_string_pretty(&toml_value)
-                    .map_err(|err| anyhow!("Failed to serialize TOML frontmatter: {err}"))?;
-                ("+++", toml)
-            }
+            let toml_value: toml::Value = serde_yaml::from_value(value.clone()).map_err(|err| {
+                anyhow!("Failed to convert YAML value into TOML frontmatter: {err}")
+            })?;
+            toml::to_string_pretty(&toml_value)
+                .map_err(|err| anyhow!("Failed to serialize TOML frontmatter: {err}"))
         }
-    };
-
-    while serialized.ends_with(['\n', '\r']) {
-        serialized.pop();
     }
+}
+
+fn wrap_frontmatter_block(serialized: &str, delimiter: &FrontmatterDelimiter) -> String {
+    let serialized = serialized.trim_end_matches(['\n', '\r']);
 
     let mut block = String::new();
-    block.push_str(delimiter);
+    block.push_str(&delimiter.open);
     block.push('\n');
 
     if !serialized.is_empty() {
-        block.push_str(&serialized);
+        block.push_str(serialized);
         block.push('\n');
     }
 
-    block.push_str(delimiter);
+    block.push_str(&delimiter.close);
     block.push('\n');
 
-    Ok(block)
+    block
 }
 
 /// Serializes a YAML value to a trimmed string without YAML document markers.
@@ -168,7 +413,7 @@ pub fn serialize_yaml_value(value: &YamlValue) -> anyhow::Result<String> {
     Ok(trim_yaml_document_markers(&serialized))
 }
 
-pub fn trim_yaml_document_markers(serialized: &str) -> String {
+pub(crate) fn trim_yaml_document_markers(serialized: &str) -> String {
     let without_start = serialized
         .strip_prefix("---\n")
         .or_else(|| serialized.strip_prefix("---\r\n"))
@@ -200,30 +445,6 @@ fn strip_opening_delimiter<'a>(content: &'a str, delimiter: &str) -> Option<&'a
     Some(rest)
 }
 
-fn extract_frontmatter_block<'a>(content: &'a str, delimiter: &'a str) -> Option<(&'a str, usize)> {
-    let mut offset = 0;
-
-    for line in content.split_terminator('\n') {
-        let trimmed = line.trim_end_matches('\r');
-
-        if trimmed == delimiter {
-            let delimiter_len = line.len();
-            let mut body_start = offset + delimiter_len;
-
-            if content.len() > body_start && content.as_bytes()[body_start] == b'\n' {
-                body_start += 1;
-            }
-
-            let frontmatter_str = &content[..offset];
-            return Some((frontmatter_str, body_start));
-        }
-
-        offset += line.len() + 1;
-    }
-
-    None
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,4 +509,102 @@ mod tests {
             .to_string()
             .contains("Failed to parse YAML frontmatter at start of document"));
     }
+
+    fn set_top_level(parsed: &mut ParsedDocument, key: &str, value: YamlValue) {
+        let Some(YamlValue::Mapping(mapping)) = parsed.frontmatter.as_mut() else {
+            panic!("expected mapping frontmatter");
+        };
+        mapping.insert(YamlValue::String(key.to_string()), value);
+        refresh_frontmatter_block(parsed).unwrap();
+    }
+
+    #[test]
+    fn refresh_preserves_anchors_aliases_and_tags_of_untouched_keys() {
+        let content = "---\n# Shared settings\ndefaults: &defaults\n  retries: 3\nservice:\n  <<: *defaults\n  name: api\nsecret: !vault 'abc'\nstatus: draft\n# end\n---\nBody\n";
+        let mut parsed = parse(content).unwrap();
+
+        set_top_level(
+            &mut parsed,
+            "status",
+            YamlValue::String("published".to_string()),
+        );
+
+        assert_eq!(
+            parsed.frontmatter_block.as_deref(),
+            Some("---\n# Shared settings\ndefaults: &defaults\n  retries: 3\nservice:\n  <<: *defaults\n  name: api\nsecret: !vault 'abc'\nstatus: published\n# end\n---\n")
+        );
+    }
+
+    #[test]
+    fn refresh_falls_back_when_aliases_would_dangle() {
+        let content = "---\nbase: &base\n  retries: 3\nservice: *base\n---\nBody\n";
+        let mut parsed = parse(content).unwrap();
+
+        set_top_level(&mut parsed, "base", YamlValue::String("none".to_string()));
+
+        assert_eq!(
+            parsed.frontmatter_block.as_deref(),
+            Some("---\nbase: none\nservice:\n  retries: 3\n---\n")
+        );
+    }
+
+    #[test]
+    fn parses_yaml_terminated_by_document_end_marker() {
+        let content = "---\ntitle: Guide\n...\n# Body\n";
+        let mut parsed = parse(content).unwrap();
+
+        assert_eq!(parsed.format, Some(FrontmatterFormat::Yaml));
+        assert_eq!(parsed.body, "# Body\n");
+
+        set_top_level(
+            &mut parsed,
+            "status",
+            YamlValue::String("draft".to_string()),
+        );
+        assert_eq!(
+            parsed.frontmatter_block.as_deref(),
+            Some("---\ntitle: Guide\nstatus: draft\n...\n")
+        );
+    }
+
+    #[test]
+    fn parses_and_reuses_extra_delimiters() {
+        let extra = [
+            FrontmatterDelimiter::new(";;;", ";;;", FrontmatterFormat::Yaml),
+            FrontmatterDelimiter::new("---yaml", "---", FrontmatterFormat::Yaml),
+        ];
+
+        let mut parsed =
+            parse_with_delimiters(";;;\n{\"title\": \"Guide\"}\n;;;\nBody\n", &extra).unwrap();
+        assert_eq!(parsed.body, "Body\n");
+        assert_eq!(parsed.raw_frontmatter(), Some("{\"title\": \"Guide\"}\n"));
+        set_top_level(
+            &mut parsed,
+            "title",
+            YamlValue::String("Manual".to_string()),
+        );
+        assert_eq!(
+            parsed.frontmatter_block.as_deref(),
+            Some(";;;\ntitle: Manual\n;;;\n")
+        );
+
+        let parsed = parse_with_delimiters("---yaml\ntitle: Guide\n---\nBody\n", &extra).unwrap();
+        assert_eq!(parsed.body, "Body\n");
+
+        let parsed = parse(";;;\ntitle: Guide\n;;;\nBody\n").unwrap();
+        assert!(parsed.frontmatter.is_none());
+    }
+
+    #[test]
+    fn raw_frontmatter_keeps_original_text() {
+        let content = "---\r\n# reviewed\r\ntitle:   Guide\r\n---\r\nBody\r\n";
+        assert_eq!(
+            raw_frontmatter(content),
+            Some("# reviewed\r\ntitle:   Guide\r\n")
+        );
+
+        assert_eq!(raw_frontmatter("+++\n+++\nBody\n"), Some(""));
+        assert_eq!(raw_frontmatter("# Heading\n"), None);
+        assert_eq!(raw_frontmatter("---\ntitle: Guide\n"), None);
+    }
 }
@@ -0,0 +1,187 @@
+//! Recognizes raw HTML blocks by the element they open and finds where that element ends.
+//!
+//! CommonMark ends an HTML block at the first blank line, so a wrapper element holding
+//! Markdown, such as a `<details>` block in a README, spans several top-level blocks: an HTML
+//! block with the opening tag, the Markdown blocks, and an HTML block with the closing tag.
+//! [`element_span`] finds all of them so that the inner HTML of the wrapper can be replaced.
+
+use crate::error::SpliceError;
+use markdown_ppp::ast::Block;
+use std::ops::Range;
+
+/// Returns the tag name of the element an HTML block opens, such as `details` for
+/// `<details open>`, or `None` for comments, closing tags, and declarations.
+pub fn html_tag(html: &str) -> Option<&str> {
+    let rest = html.trim_start().strip_prefix('<')?;
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(rest.len());
+    let name = &rest[..end];
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        .then_some(name)
+}
+
+/// The top-level blocks spanned by an HTML element, from its opening tag to its closing tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementSpan {
+    /// The blocks holding the element; the first and last are HTML blocks.
+    pub blocks: Range<usize>,
+    /// Byte offset just past the opening tag, within the first block.
+    pub inner_start: usize,
+    /// Byte offset of the closing tag, within the last block.
+    pub inner_end: usize,
+}
+
+/// Finds the blocks spanned by the element opened by the HTML block at `index`.
+///
+/// The closing tag is searched in the HTML blocks that follow, skipping the Markdown blocks
+/// between them and accounting for nested elements with the same tag. Fails if the block
+/// does not open an element, if the element closes itself, or if no closing tag is found.
+pub fn element_span(blocks: &[Block], index: usize) -> Result<ElementSpan, SpliceError> {
+    let Some(Block::HtmlBlock(html)) = blocks.get(index) else {
+        return Err(SpliceError::OperationFailed(
+            "The selector must match an HTML block".to_string(),
+        ));
+    };
+    let tag = html_tag(html)
+        .ok_or_else(|| {
+            SpliceError::OperationFailed("The HTML block does not open an element".to_string())
+        })?
+        .to_ascii_lowercase();
+    let inner_start = opening_tag_end(html).ok_or_else(|| {
+        SpliceError::OperationFailed(format!("The opening <{tag}> tag is not terminated"))
+    })?;
+    if html[..inner_start].ends_with("/>") {
+        return Err(SpliceError::OperationFailed(format!(
+            "The <{tag}> element closes itself and has no inner HTML"
+        )));
+    }
+
+    let mut depth = 1;
+    for (offset, block) in blocks[index..].iter().enumerate() {
+        let Block::HtmlBlock(html) = block else {
+            continue;
+        };
+        let from = if offset == 0 { inner_start } else { 0 };
+        if let Some(inner_end) = find_closing_tag(html, &tag, from, &mut depth) {
+            return Ok(ElementSpan {
+                blocks: index..index + offset + 1,
+                inner_start,
+                inner_end,
+            });
+        }
+    }
+    Err(SpliceError::OperationFailed(format!(
+        "No closing </{tag}> tag was found"
+    )))
+}
+
+/// Returns `html` without the `<summary>` element at its start, as found at the start of the
+/// inner HTML of a `<details>` element, or `html` unchanged when it does not start with one.
+pub fn strip_summary(html: &str) -> &str {
+    let trimmed = html.trim_start();
+    if !html_tag(trimmed).is_some_and(|tag| tag.eq_ignore_ascii_case("summary")) {
+        return html;
+    }
+    let Some(inner_start) = opening_tag_end(trimmed) else {
+        return html;
+    };
+    let mut depth = 1;
+    let Some(closing) = find_closing_tag(trimmed, "summary", inner_start, &mut depth) else {
+        return html;
+    };
+    let rest = &trimmed[closing..];
+    rest.find('>').map_or("", |end| &rest[end + 1..])
+}
+
+/// Returns the byte offset just past the first tag of `html`, skipping `>` characters
+/// inside quoted attribute values.
+fn opening_tag_end(html: &str) -> Option<usize> {
+    let start = html.find('<')?;
+    let mut quote = None;
+    for (offset, c) in html[start..].char_indices() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => return Some(start + offset + 1),
+            None => {}
+        }
+    }
+    None
+}
+
+/// Scans `html` from `from` for opening and closing `tag` elements, updating `depth`, and
+/// returns the offset of the closing tag that brings `depth` to zero.
+fn find_closing_tag(html: &str, tag: &str, from: usize, depth: &mut usize) -> Option<usize> {
+    let lower = html.to_ascii_lowercase();
+    let mut position = from;
+    while let Some(offset) = lower[position..].find('<') {
+        let start = position + offset;
+        position = start + 1;
+        let rest = &lower[position..];
+        let (closing, name) = match rest.strip_prefix('/') {
+            Some(name) => (true, name),
+            None => (false, rest),
+        };
+        let Some(after) = name.strip_prefix(tag) else {
+            continue;
+        };
+        if after.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-') {
+            continue;
+        }
+        if !closing {
+            *depth += 1;
+        } else {
+            *depth -= 1;
+            if *depth == 0 {
+                return Some(start);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    #[test]
+    fn test_html_tag_names_opening_elements_only() {
+        assert_eq!(html_tag("<details open>\n"), Some("details"));
+        assert_eq!(html_tag("  <DIV align=\"center\">"), Some("DIV"));
+        assert_eq!(html_tag("<!-- note -->"), None);
+        assert_eq!(html_tag("</details>"), None);
+        assert_eq!(html_tag("<!DOCTYPE html>"), None);
+    }
+
+    #[test]
+    fn test_element_span_crosses_markdown_blocks_and_nested_elements() {
+        let blocks = parse_markdown(
+            MarkdownParserState::default(),
+            "<details>\n<summary title=\"a > b\">More</summary>\n\nSome *Markdown*.\n\n\
+             <details><summary>Nested</summary></details>\n\n</details>\n\nAfter.\n",
+        )
+        .unwrap()
+        .blocks;
+
+        let span = element_span(&blocks, 0).unwrap();
+        assert_eq!(span.blocks, 0..4);
+        assert_eq!(span.inner_start, "<details>".len());
+        assert_eq!(span.inner_end, 0);
+        assert!(element_span(&blocks, 1).is_err());
+    }
+
+    #[test]
+    fn test_strip_summary_removes_leading_summary_only() {
+        assert_eq!(
+            strip_summary("\n<Summary>A <b>B</b></summary>\nText"),
+            "\nText"
+        );
+        assert_eq!(
+            strip_summary("Text <summary>A</summary>"),
+            "Text <summary>A</summary>"
+        );
+    }
+}
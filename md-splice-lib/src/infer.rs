@@ -0,0 +1,569 @@
+//! Contains the logic for inferring operations from a before/after pair of documents,
+//! and for merging the changes two documents made to a common base.
+//!
+//! The inferred operations locate blocks by type and by a snippet of their text rather
+//! than by position, so they can be replayed against similar documents. Every inferred
+//! batch is replayed against the original before being returned, which guarantees that
+//! it reproduces the edited document.
+
+use crate::error::SpliceError;
+use crate::locator::{self, block_to_text, locate_all, FoundNode};
+use crate::transaction::{
+    DeleteFrontmatterOperation, DeleteOperation, InsertOperation, InsertPosition, Operation,
+    ReplaceFrontmatterOperation, ReplaceOperation, Selector, SetFrontmatterOperation,
+};
+use crate::{default_printer_config, render_fragment, MarkdownDocument};
+use markdown_ppp::ast::{Block, Document, HeadingKind, SetextHeading};
+use serde_yaml::{Mapping, Value as YamlValue};
+use std::ops::Range;
+
+/// Maximum number of characters of block text used in an inferred `select_contains`.
+const MAX_CONTAINS_CHARS: usize = 60;
+
+/// Infers a batch of operations that turns `original` into `edited`.
+///
+/// Frontmatter changes become `set_frontmatter`/`delete_frontmatter` operations per
+/// top-level key (or a single `replace_frontmatter` when keys cannot be addressed by
+/// path). Body changes are computed with a block-level diff and become `replace`,
+/// `insert` and `delete` operations.
+///
+/// Returns [`SpliceError::OperationFailed`] if no batch reproducing the edit can be
+/// inferred, for example when content is added to a document without any body block
+/// to anchor it to.
+pub fn infer_operations(
+    original: &MarkdownDocument,
+    edited: &MarkdownDocument,
+) -> Result<Vec<Operation>, SpliceError> {
+    let mut operations = infer_frontmatter_operations(original, edited);
+    let mut working = original.clone();
+    working.apply(operations.clone())?;
+
+    for hunk in diff_hunks(original.blocks(), edited.blocks()) {
+        // Earlier hunks may have shifted the blocks of this one.
+        let offset = working.blocks().len() as isize - original.blocks().len() as isize;
+        let start = (hunk.original.start as isize + offset) as usize;
+        let inserted = &edited.blocks()[hunk.edited.clone()];
+        let mut removed = hunk.original.len();
+
+        let mut emit = |working: &mut MarkdownDocument, operation: Operation| {
+            working.apply(vec![operation.clone()])?;
+            operations.push(operation);
+            Ok::<_, SpliceError>(())
+        };
+
+        if inserted.is_empty() {
+            // Pure deletion: handled below.
+        } else if removed > 0 {
+            let selector = selector_for(working.blocks(), start);
+            emit(
+                &mut working,
+                Operation::Replace(ReplaceOperation {
+                    selector: Some(selector),
+                    content: Some(render_content(inserted)),
+                    ..ReplaceOperation::default()
+                }),
+            )?;
+            removed -= 1;
+        } else if start > 0 {
+            let selector = selector_for(working.blocks(), start - 1);
+            emit(
+                &mut working,
+                Operation::Insert(InsertOperation {
+                    selector: Some(selector),
+                    content: Some(render_content(inserted)),
+                    position: InsertPosition::After,
+                    ..InsertOperation::default()
+                }),
+            )?;
+        } else if !working.blocks().is_empty() {
+            let selector = selector_for(working.blocks(), 0);
+            emit(
+                &mut working,
+                Operation::Insert(InsertOperation {
+                    selector: Some(selector),
+                    content: Some(render_content(inserted)),
+                    position: InsertPosition::Before,
+                    ..InsertOperation::default()
+                }),
+            )?;
+        } else {
+            return Err(SpliceError::OperationFailed(
+                "cannot infer an insertion into a document without body content".to_string(),
+            ));
+        }
+
+        let trailing = start + inserted.len();
+        for _ in 0..removed {
+            let selector = selector_for(working.blocks(), trailing);
+            emit(
+                &mut working,
+                Operation::Delete(DeleteOperation {
+                    selector: Some(selector),
+                    ..DeleteOperation::default()
+                }),
+            )?;
+        }
+    }
+
+    // Replay the whole batch, as `apply` would, to catch anything the incremental
+    // simulation cannot (such as batch-level conflict detection).
+    let mut replayed = original.clone();
+    replayed.apply(operations.clone())?;
+    if !replayed.semantically_equals(edited) {
+        return Err(SpliceError::OperationFailed(
+            "the inferred operations do not reproduce the edited document".to_string(),
+        ));
+    }
+
+    Ok(operations)
+}
+
+fn infer_frontmatter_operations(
+    original: &MarkdownDocument,
+    edited: &MarkdownDocument,
+) -> Vec<Operation> {
+    let empty = Mapping::new();
+    let before = match original.frontmatter() {
+        None => Some(&empty),
+        Some(YamlValue::Mapping(mapping)) => Some(mapping),
+        Some(_) => None,
+    };
+    let after = match edited.frontmatter() {
+        None => Some(&empty),
+        Some(YamlValue::Mapping(mapping)) => Some(mapping),
+        Some(_) => None,
+    };
+
+    let addressable = |mapping: &Mapping| {
+        mapping.keys().all(|key| {
+            key.as_str()
+                .is_some_and(|key| !key.is_empty() && !key.contains(['.', '[', ']']))
+        })
+    };
+
+    match (before, after) {
+        (Some(before), Some(after)) if addressable(before) && addressable(after) => {
+            let mut operations = Vec::new();
+            for (key, value) in after {
+                if before.get(key) != Some(value) {
+                    operations.push(Operation::SetFrontmatter(SetFrontmatterOperation {
+                        key: key.as_str().unwrap_or_default().to_string(),
+                        value: Some(value.clone()),
+                        format: original
+                            .frontmatter_format()
+                            .xor(edited.frontmatter_format()),
+                        ..SetFrontmatterOperation::default()
+                    }));
+                }
+            }
+            for key in before.keys() {
+                if !after.contains_key(key) {
+                    operations.push(Operation::DeleteFrontmatter(DeleteFrontmatterOperation {
+                        key: key.as_str().unwrap_or_default().to_string(),
+                        ..DeleteFrontmatterOperation::default()
+                    }));
+                }
+            }
+            operations
+        }
+        _ if original.frontmatter() == edited.frontmatter() => Vec::new(),
+        _ => vec![Operation::ReplaceFrontmatter(ReplaceFrontmatterOperation {
+            content: Some(edited.frontmatter().cloned().unwrap_or(YamlValue::Null)),
+            format: edited.frontmatter_format(),
+            ..ReplaceFrontmatterOperation::default()
+        })],
+    }
+}
+
+/// A run of top-level blocks that differs between two documents.
+///
+/// An empty `original` range marks blocks inserted before `original.start`, and an empty
+/// `edited` range marks blocks deleted from the original.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The differing blocks of the original document.
+    pub original: Range<usize>,
+    /// The blocks of the edited document that take their place.
+    pub edited: Range<usize>,
+}
+
+/// Computes the differing runs of top-level blocks, in document order, using a
+/// longest-common-subsequence alignment of the two block lists.
+///
+/// Blocks are compared by their syntax tree rather than their text, so edits that only
+/// change how a block is written are not reported.
+pub fn diff_hunks(original: &[Block], edited: &[Block]) -> Vec<Hunk> {
+    let (n, m) = (original.len(), edited.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if original[i] == edited[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut hunk_i, mut hunk_j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && original[i] == edited[j] {
+            if hunk_i < i || hunk_j < j {
+                hunks.push(Hunk {
+                    original: hunk_i..i,
+                    edited: hunk_j..j,
+                });
+            }
+            i += 1;
+            j += 1;
+            hunk_i = i;
+            hunk_j = j;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            j += 1;
+        } else {
+            i += 1;
+        }
+    }
+    if hunk_i < n || hunk_j < m {
+        hunks.push(Hunk {
+            original: hunk_i..n,
+            edited: hunk_j..m,
+        });
+    }
+
+    hunks
+}
+
+/// A run of blocks that both sides of a three-way merge changed differently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// The blocks of the base document that both sides changed.
+    pub base: Range<usize>,
+    /// The blocks of our document that take their place, kept in the merged document.
+    pub ours: Range<usize>,
+    /// The blocks of their document that take their place.
+    pub theirs: Range<usize>,
+}
+
+/// Merges the changes that `ours` and `theirs` each made to `base` into one document.
+///
+/// Both sides are diffed against `base` with [`diff_hunks`]. Changes to different blocks
+/// are combined, and a change made identically by both sides is applied once. Where
+/// the sides changed the same blocks differently, or inserted different blocks at the
+/// same place, our version is kept and a [`MergeConflict`] is reported. The frontmatter
+/// is taken from `theirs` if only they changed it, and from `ours` otherwise.
+pub fn merge_documents(
+    base: &MarkdownDocument,
+    ours: &MarkdownDocument,
+    theirs: &MarkdownDocument,
+) -> (MarkdownDocument, Vec<MergeConflict>) {
+    let (base_blocks, our_blocks, their_blocks) = (base.blocks(), ours.blocks(), theirs.blocks());
+    let mut changes: Vec<(bool, Hunk)> = diff_hunks(base_blocks, our_blocks)
+        .into_iter()
+        .map(|hunk| (true, hunk))
+        .chain(
+            diff_hunks(base_blocks, their_blocks)
+                .into_iter()
+                .map(|hunk| (false, hunk)),
+        )
+        .collect();
+    changes.sort_by_key(|(_, hunk)| (hunk.original.start, hunk.original.end));
+
+    let mut blocks = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut cursor = 0;
+    let mut changes = changes.into_iter().peekable();
+    while let Some(first) = changes.next() {
+        // Group the changes touching the same base blocks; insertions at the same place
+        // belong together, but an insertion next to a changed block does not.
+        let start = first.1.original.start;
+        let mut end = first.1.original.end;
+        let mut group = vec![first];
+        while let Some(next) = changes.next_if(|(_, hunk)| {
+            hunk.original.start < end || (start == end && hunk.original == (start..end))
+        }) {
+            end = end.max(next.1.original.end);
+            group.push(next);
+        }
+
+        blocks.extend_from_slice(&base_blocks[cursor..start]);
+        cursor = end;
+        let base_range = start..end;
+        match (
+            side_range(&group, true, &base_range),
+            side_range(&group, false, &base_range),
+        ) {
+            (Some(ours), Some(theirs))
+                if our_blocks[ours.clone()] != their_blocks[theirs.clone()] =>
+            {
+                blocks.extend_from_slice(&our_blocks[ours.clone()]);
+                conflicts.push(MergeConflict {
+                    base: base_range,
+                    ours,
+                    theirs,
+                });
+            }
+            (Some(ours), _) => blocks.extend_from_slice(&our_blocks[ours]),
+            (None, Some(theirs)) => blocks.extend_from_slice(&their_blocks[theirs]),
+            (None, None) => unreachable!("a group holds at least one change"),
+        }
+    }
+    blocks.extend_from_slice(&base_blocks[cursor..]);
+
+    let mut parsed = if ours.parsed.frontmatter == base.parsed.frontmatter {
+        theirs.parsed.clone()
+    } else {
+        ours.parsed.clone()
+    };
+    parsed.body.clear();
+    let merged = MarkdownDocument {
+        parsed,
+        doc: Document { blocks },
+        source: None,
+    };
+    (merged, conflicts)
+}
+
+/// Returns the blocks of one side that replace `base_range`, or `None` if that side
+/// left them unchanged.
+///
+/// Outside its own hunks, a side keeps the base blocks, so the range extends from its
+/// first and last hunk by the unchanged base blocks around them.
+fn side_range(
+    group: &[(bool, Hunk)],
+    ours: bool,
+    base_range: &Range<usize>,
+) -> Option<Range<usize>> {
+    let mut hunks = group
+        .iter()
+        .filter(|(side, _)| *side == ours)
+        .map(|(_, hunk)| hunk);
+    let first = hunks.next()?;
+    let last = hunks.next_back().unwrap_or(first);
+    Some(
+        first.edited.start - (first.original.start - base_range.start)
+            ..last.edited.end + (base_range.end - last.original.end),
+    )
+}
+
+/// Builds a selector that matches the block at `index` by type, text and ordinal.
+fn selector_for(blocks: &[Block], index: usize) -> Selector {
+    let block = &blocks[index];
+    let select_type = block_type_name(block);
+    let select_contains = block_to_text(block)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(|line| line.chars().take(MAX_CONTAINS_CHARS).collect::<String>());
+
+    let probe = locator::Selector {
+        select_type: Some(select_type.clone()),
+        select_contains: select_contains.clone(),
+        select_ordinal: 1,
+        ..Default::default()
+    };
+    let select_ordinal = locate_all(blocks, &probe)
+        .ok()
+        .and_then(|matches| {
+            matches.iter().position(
+                |found| matches!(found, FoundNode::Block { index: found, .. } if *found == index),
+            )
+        })
+        .map_or(1, |position| position + 1);
+
+    Selector {
+        select_type: Some(select_type),
+        select_contains,
+        select_ordinal,
+        ..Selector::default()
+    }
+}
+
+pub(crate) fn block_type_name(block: &Block) -> String {
+    let name = match block {
+        Block::Paragraph(_) => "p",
+        Block::Heading(heading) => {
+            let level = match heading.kind {
+                HeadingKind::Atx(level) => level,
+                HeadingKind::Setext(SetextHeading::Level1) => 1,
+                HeadingKind::Setext(SetextHeading::Level2) => 2,
+            };
+            return format!("h{level}");
+        }
+        Block::ThematicBreak => "thematicbreak",
+        Block::BlockQuote(_) => "blockquote",
+        Block::List(_) => "list",
+        Block::CodeBlock(_) => "code",
+        Block::HtmlBlock(_) => "html",
+        Block::Definition(_) => "definition",
+        Block::Table(_) => "table",
+        Block::FootnoteDefinition(_) => "footnotedefinition",
+        Block::GitHubAlert(_) => "alert",
+        Block::Empty => "empty",
+    };
+    name.to_string()
+}
+
+/// Renders `blocks` as operation content, without the fragment's trailing newline.
+fn render_content(blocks: &[Block]) -> String {
+    render_fragment(blocks, default_printer_config())
+        .trim_end_matches('\n')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn infer(original: &str, edited: &str) -> (Vec<Operation>, MarkdownDocument) {
+        let original = MarkdownDocument::from_str(original).unwrap();
+        let edited = MarkdownDocument::from_str(edited).unwrap();
+        let operations = infer_operations(&original, &edited).unwrap();
+
+        let mut replayed = original.clone();
+        replayed.apply(operations.clone()).unwrap();
+        assert!(replayed.semantically_equals(&edited));
+        (operations, replayed)
+    }
+
+    #[test]
+    fn test_diff_hunks_groups_adjacent_changes() {
+        let original = MarkdownDocument::from_str("A\n\nB\n\nC\n\nD\n").unwrap();
+        let edited = MarkdownDocument::from_str("A\n\nX\n\nY\n\nD\n\nE\n").unwrap();
+
+        assert_eq!(
+            diff_hunks(original.blocks(), edited.blocks()),
+            vec![
+                Hunk {
+                    original: 1..3,
+                    edited: 1..3,
+                },
+                Hunk {
+                    original: 4..4,
+                    edited: 4..5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identical_documents_infer_no_operations() {
+        let (operations, _) = infer("# Title\n\nBody.\n", "# Title\n\nBody.\n");
+        assert!(operations.is_empty());
+    }
+
+    #[test]
+    fn test_infers_replace_insert_and_delete() {
+        let (operations, _) = infer(
+            "# Guide\n\nIntro.\n\n## Setup\n\nOld steps.\n\n## Legacy\n\nRemove me.\n",
+            "# Guide\n\nNew intro.\n\nMore context.\n\n## Setup\n\nOld steps.\n",
+        );
+
+        let kinds: Vec<_> = operations.iter().map(Operation::kind).collect();
+        assert_eq!(kinds, vec!["replace", "delete", "delete"]);
+
+        let Operation::Replace(replace) = &operations[0] else {
+            panic!("expected a replace operation");
+        };
+        let selector = replace.selector.as_ref().unwrap();
+        assert_eq!(selector.select_type.as_deref(), Some("p"));
+        assert_eq!(selector.select_contains.as_deref(), Some("Intro."));
+    }
+
+    #[test]
+    fn test_infers_insert_anchored_on_neighbouring_block() {
+        let (operations, _) = infer("# Title\n\nBody.\n", "# Title\n\nBody.\n\nAppendix.\n");
+
+        let Operation::Insert(insert) = &operations[0] else {
+            panic!("expected an insert operation");
+        };
+        assert_eq!(insert.position, InsertPosition::After);
+        assert_eq!(
+            insert.selector.as_ref().unwrap().select_contains.as_deref(),
+            Some("Body.")
+        );
+    }
+
+    #[test]
+    fn test_disambiguates_repeated_blocks_with_ordinals() {
+        let (operations, _) = infer("Note.\n\nNote.\n\nNote.\n", "Note.\n\nNote.\n\nDone.\n");
+
+        let Operation::Replace(replace) = &operations[0] else {
+            panic!("expected a replace operation");
+        };
+        assert_eq!(replace.selector.as_ref().unwrap().select_ordinal, 3);
+    }
+
+    #[test]
+    fn test_infers_frontmatter_key_changes() {
+        let (operations, replayed) = infer(
+            "---\ntitle: Guide\nstatus: draft\n---\nBody.\n",
+            "---\ntitle: Guide\nowner: docs\n---\nBody.\n",
+        );
+
+        let kinds: Vec<_> = operations.iter().map(Operation::kind).collect();
+        assert_eq!(kinds, vec!["set_frontmatter", "delete_frontmatter"]);
+        assert!(replayed.render().contains("owner: docs"));
+    }
+
+    fn merge(base: &str, ours: &str, theirs: &str) -> (String, Vec<MergeConflict>) {
+        let parse = |text| MarkdownDocument::from_str(text).unwrap();
+        let (merged, conflicts) = merge_documents(&parse(base), &parse(ours), &parse(theirs));
+        (merged.render(), conflicts)
+    }
+
+    #[test]
+    fn test_merge_combines_changes_to_different_blocks() {
+        let (merged, conflicts) = merge(
+            "---\ntitle: Guide\n---\nA\n\nB\n\nC\n",
+            "---\ntitle: Guide\n---\nA2\n\nB\n\nC\n\nD\n",
+            "---\ntitle: Manual\n---\nA\n\nB2\n\nC\n",
+        );
+
+        assert!(conflicts.is_empty());
+        assert_eq!(merged, "---\ntitle: Manual\n---\nA2\n\nB2\n\nC\n\nD");
+    }
+
+    #[test]
+    fn test_merge_reports_conflicting_changes_and_keeps_ours() {
+        let (merged, conflicts) = merge(
+            "A
+
+B
+
+C
+",
+            "A
+
+Ours.
+
+C
+
+Same.
+",
+            "A
+
+Theirs.
+
+More.
+
+C
+
+Same.
+",
+        );
+
+        assert_eq!(merged, "A\n\nOurs.\n\nC\n\nSame.");
+        assert_eq!(
+            conflicts,
+            vec![MergeConflict {
+                base: 1..2,
+                ours: 1..2,
+                theirs: 1..3,
+            }]
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Finds and edits the inline elements of blocks: links, images and code spans.
+//!
+//! The inline nodes of a block are walked in document order, through the blocks nested in
+//! it and through the content of links and emphasis, so that an image inside a link is found
+//! after the link. Every inline node of a block has a position, its index in that walk,
+//! which addresses it until the block is edited.
+
+use crate::error::SpliceError;
+use crate::locator::{inlines_to_text, FoundNode};
+use markdown_ppp::ast::{Block, Inline};
+use markdown_ppp::printer::{config::Config as PrinterConfig, render_markdown};
+
+/// The kinds of inline elements that can be selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlineKind {
+    /// Inline, reference and autolinks.
+    Link,
+    /// Images.
+    Image,
+    /// Code spans such as `` `cargo test` ``.
+    CodeSpan,
+}
+
+impl InlineKind {
+    /// Returns whether `inline` is an element of this kind.
+    pub fn matches(self, inline: &Inline) -> bool {
+        match self {
+            InlineKind::Link => matches!(
+                inline,
+                Inline::Link(_) | Inline::LinkReference(_) | Inline::Autolink(_)
+            ),
+            InlineKind::Image => matches!(inline, Inline::Image(_)),
+            InlineKind::CodeSpan => matches!(inline, Inline::Code(_)),
+        }
+    }
+}
+
+/// Describes an inline element, as in "the selector matched a link".
+pub fn inline_description(inline: &Inline) -> &'static str {
+    match inline {
+        Inline::Link(_) | Inline::LinkReference(_) | Inline::Autolink(_) => "link",
+        Inline::Image(_) => "image",
+        Inline::Code(_) => "code span",
+        _ => "inline",
+    }
+}
+
+/// Returns the name `select_type` accepts for the kind of an inline element.
+pub fn inline_select_type(inline: &Inline) -> &'static str {
+    match inline {
+        Inline::Image(_) => "image",
+        Inline::Code(_) => "codespan",
+        _ => "link",
+    }
+}
+
+/// Returns every inline node of `block` in document order; the index of a node is its
+/// position.
+pub fn block_inlines(block: &Block) -> Vec<&Inline> {
+    let mut found = Vec::new();
+    for inlines in inline_runs(block) {
+        collect_inlines(inlines, &mut found);
+    }
+    found
+}
+
+/// Counts the inline nodes of `inlines`, nested ones included.
+pub fn inline_count(inlines: &[Inline]) -> usize {
+    inlines
+        .iter()
+        .map(|inline| 1 + children(inline).map_or(0, |children| inline_count(children)))
+        .sum()
+}
+
+/// Replaces the inline node of `block` at `position` with `replacement`, which may hold any
+/// number of inlines. Returns `false` when the block has no node at that position.
+pub fn splice_inline(block: &mut Block, position: usize, mut replacement: Vec<Inline>) -> bool {
+    let mut remaining = position;
+    inline_runs_mut(block)
+        .into_iter()
+        .any(|inlines| splice_in(inlines, &mut remaining, &mut replacement))
+}
+
+/// Returns the text an inline element is matched by: the text of a link followed by its
+/// destination, or the alternative text of an image followed by its source, separated by a
+/// tab; the content of a code span; or the address of an autolink.
+pub fn inline_match_text(inline: &Inline) -> String {
+    match inline {
+        Inline::Link(link) => format!("{}\t{}", inlines_to_text(&link.children), link.destination),
+        Inline::LinkReference(link) => format!(
+            "{}\t{}",
+            inlines_to_text(&link.text),
+            inlines_to_text(&link.label)
+        ),
+        Inline::Image(image) => format!("{}\t{}", image.alt, image.destination),
+        Inline::Autolink(url) => url.clone(),
+        inline => inlines_to_text(std::slice::from_ref(inline)),
+    }
+}
+
+/// Renders an inline element as Markdown, without a trailing line break.
+pub fn render_inline(inline: &Inline) -> String {
+    let fragment = markdown_ppp::ast::Document {
+        blocks: vec![Block::Paragraph(vec![inline.clone()])],
+    };
+    render_markdown(&fragment, PrinterConfig::default())
+        .trim_end()
+        .to_string()
+}
+
+/// Renders an inline element matched by a `link`, `image` or `codespan` selector as
+/// Markdown followed by a line break. Returns `None` for any other node.
+pub fn render_inline_node(found: &FoundNode) -> Option<String> {
+    match found {
+        FoundNode::Inline { inline, .. } => Some(format!("{}\n", render_inline(inline))),
+        _ => None,
+    }
+}
+
+/// Turns the blocks parsed from new content for an inline element into the inlines that
+/// replace it. No blocks remove the element.
+///
+/// Fails unless the content is a single paragraph, since an inline element cannot be
+/// replaced with blocks.
+pub fn inline_content(blocks: Vec<Block>) -> Result<Vec<Inline>, SpliceError> {
+    let mut blocks = blocks.into_iter();
+    match (blocks.next(), blocks.next()) {
+        (None, _) => Ok(Vec::new()),
+        (Some(Block::Paragraph(inlines)), None) => Ok(inlines),
+        _ => Err(SpliceError::OperationFailed(
+            "Content replacing an inline element must be a single paragraph of inline Markdown"
+                .to_string(),
+        )),
+    }
+}
+
+/// Returns the runs of inline content of `block` and of the blocks nested in it.
+fn inline_runs(block: &Block) -> Vec<&Vec<Inline>> {
+    match block {
+        Block::Paragraph(inlines) => vec![inlines],
+        Block::Heading(heading) => vec![&heading.content],
+        Block::BlockQuote(blocks) => blocks.iter().flat_map(inline_runs).collect(),
+        Block::List(list) => list
+            .items
+            .iter()
+            .flat_map(|item| item.blocks.iter().flat_map(inline_runs))
+            .collect(),
+        Block::Table(table) => table.rows.iter().flatten().collect(),
+        Block::FootnoteDefinition(footnote) => {
+            footnote.blocks.iter().flat_map(inline_runs).collect()
+        }
+        Block::GitHubAlert(alert) => alert.blocks.iter().flat_map(inline_runs).collect(),
+        Block::ThematicBreak
+        | Block::CodeBlock(_)
+        | Block::HtmlBlock(_)
+        | Block::Definition(_)
+        | Block::Empty => Vec::new(),
+    }
+}
+
+/// Returns the runs of inline content of `block` in the same order as [`inline_runs`].
+fn inline_runs_mut(block: &mut Block) -> Vec<&mut Vec<Inline>> {
+    match block {
+        Block::Paragraph(inlines) => vec![inlines],
+        Block::Heading(heading) => vec![&mut heading.content],
+        Block::BlockQuote(blocks) => blocks.iter_mut().flat_map(inline_runs_mut).collect(),
+        Block::List(list) => list
+            .items
+            .iter_mut()
+            .flat_map(|item| item.blocks.iter_mut().flat_map(inline_runs_mut))
+            .collect(),
+        Block::Table(table) => table.rows.iter_mut().flatten().collect(),
+        Block::FootnoteDefinition(footnote) => footnote
+            .blocks
+            .iter_mut()
+            .flat_map(inline_runs_mut)
+            .collect(),
+        Block::GitHubAlert(alert) => alert.blocks.iter_mut().flat_map(inline_runs_mut).collect(),
+        Block::ThematicBreak
+        | Block::CodeBlock(_)
+        | Block::HtmlBlock(_)
+        | Block::Definition(_)
+        | Block::Empty => Vec::new(),
+    }
+}
+
+fn collect_inlines<'a>(inlines: &'a [Inline], found: &mut Vec<&'a Inline>) {
+    for inline in inlines {
+        found.push(inline);
+        if let Some(children) = children(inline) {
+            collect_inlines(children, found);
+        }
+    }
+}
+
+/// Walks `inlines` like [`collect_inlines`], counting `remaining` down, and splices
+/// `replacement` in place of the node it reaches zero at.
+fn splice_in(
+    inlines: &mut Vec<Inline>,
+    remaining: &mut usize,
+    replacement: &mut Vec<Inline>,
+) -> bool {
+    for index in 0..inlines.len() {
+        if *remaining == 0 {
+            inlines.splice(index..=index, std::mem::take(replacement));
+            return true;
+        }
+        *remaining -= 1;
+        if let Some(children) = children_mut(&mut inlines[index]) {
+            if splice_in(children, remaining, replacement) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn children(inline: &Inline) -> Option<&Vec<Inline>> {
+    match inline {
+        Inline::Link(link) => Some(&link.children),
+        Inline::LinkReference(link) => Some(&link.text),
+        Inline::Emphasis(children) | Inline::Strong(children) | Inline::Strikethrough(children) => {
+            Some(children)
+        }
+        _ => None,
+    }
+}
+
+fn children_mut(inline: &mut Inline) -> Option<&mut Vec<Inline>> {
+    match inline {
+        Inline::Link(link) => Some(&mut link.children),
+        Inline::LinkReference(link) => Some(&mut link.text),
+        Inline::Emphasis(children) | Inline::Strong(children) | Inline::Strikethrough(children) => {
+            Some(children)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    #[test]
+    fn test_inlines_are_walked_in_document_order_and_spliced_by_position() {
+        let mut document = parse_markdown(
+            MarkdownParserState::default(),
+            "Run `make` and see [the `docs`](docs.md) or ![logo](logo.png).\n",
+        )
+        .unwrap();
+        let block = &mut document.blocks[0];
+
+        let codes: Vec<(usize, String)> = block_inlines(block)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, inline)| InlineKind::CodeSpan.matches(inline))
+            .map(|(position, inline)| (position, inline_match_text(inline)))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![(1, "make".to_string()), (5, "docs".to_string())]
+        );
+        assert_eq!(
+            inline_match_text(block_inlines(block)[3]),
+            "the docs\tdocs.md"
+        );
+        assert_eq!(render_inline(block_inlines(block)[7]), "![logo](logo.png)");
+
+        assert!(splice_inline(
+            block,
+            1,
+            vec![Inline::Code("make check".to_string())]
+        ));
+        assert!(splice_inline(
+            block,
+            5,
+            vec![Inline::Text("manual".to_string())]
+        ));
+        assert!(!splice_inline(block, 42, Vec::new()));
+        assert_eq!(
+            render_markdown(&document, PrinterConfig::default()),
+            "Run `make check` and see [the manual](docs.md) or ![logo](logo.png)."
+        );
+    }
+}
@@ -0,0 +1,378 @@
+//! Contains the logic for parsing malformed Markdown without failing the whole document.
+//!
+//! In lenient mode, constructs that cannot be parsed reliably are preserved verbatim as
+//! opaque HTML blocks, which the printer emits unchanged, and a [`ParseWarning`] records
+//! where the problem was found. These are unclosed code fences, which would swallow the rest
+//! of the document, and tables whose header, delimiter and body rows disagree on their
+//! number of cells, which would otherwise be reflowed into a paragraph or lose cells.
+
+use markdown_ppp::ast::Block;
+use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+use std::fmt;
+use std::ops::Range;
+
+/// A recoverable problem encountered while parsing Markdown in lenient mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// The 1-indexed line of the original input where the problem starts.
+    pub line: usize,
+    /// A human-readable description of the problem and how it was recovered.
+    pub message: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Parses a Markdown body, preserving malformed regions as opaque blocks.
+///
+/// `first_line` is the 1-indexed line number of the body's first line within the
+/// original input (for example, the line following a frontmatter block). It is used
+/// to report warning locations relative to the full document.
+pub(crate) fn parse_body(body: &str, first_line: usize) -> (Vec<Block>, Vec<ParseWarning>) {
+    let mut warnings = Vec::new();
+
+    let (parsable, opaque_tail) = match find_unclosed_fence(body) {
+        Some((offset, line_index)) => {
+            warnings.push(ParseWarning {
+                line: first_line + line_index,
+                message: "unclosed code fence; the remaining text is preserved verbatim"
+                    .to_string(),
+            });
+            (&body[..offset], Some(&body[offset..]))
+        }
+        None => (body, None),
+    };
+
+    let mut blocks = Vec::new();
+    let mut start = (0, 0);
+    for table in find_malformed_tables(parsable) {
+        let (offset, line_index) = start;
+        blocks.extend(parse_chunks(
+            &parsable[offset..table.bytes.start],
+            first_line + line_index,
+            &mut warnings,
+        ));
+        warnings.push(ParseWarning {
+            line: first_line + table.lines.start,
+            message: format!(
+                "malformed table, {}; the table is preserved verbatim",
+                table.problem
+            ),
+        });
+        blocks.push(Block::HtmlBlock(
+            parsable[table.bytes.clone()].trim_end().to_string(),
+        ));
+        start = (table.bytes.end, table.lines.end);
+    }
+    let (offset, line_index) = start;
+    blocks.extend(parse_chunks(
+        &parsable[offset..],
+        first_line + line_index,
+        &mut warnings,
+    ));
+    if let Some(tail) = opaque_tail {
+        blocks.push(Block::HtmlBlock(tail.trim_end().to_string()));
+    }
+
+    (blocks, warnings)
+}
+
+/// Parses `text` as a whole, falling back to blank-line separated chunks on failure.
+fn parse_chunks(text: &str, first_line: usize, warnings: &mut Vec<ParseWarning>) -> Vec<Block> {
+    if let Ok(doc) = parse_markdown(MarkdownParserState::default(), text) {
+        return doc.blocks;
+    }
+
+    let mut blocks = Vec::new();
+    for (line_index, chunk) in split_chunks(text) {
+        match parse_markdown(MarkdownParserState::default(), chunk) {
+            Ok(doc) => blocks.extend(doc.blocks),
+            Err(err) => {
+                warnings.push(ParseWarning {
+                    line: first_line + line_index,
+                    message: format!(
+                        "failed to parse block ({err}); the text is preserved verbatim"
+                    ),
+                });
+                blocks.push(Block::HtmlBlock(chunk.trim_end().to_string()));
+            }
+        }
+    }
+
+    blocks
+}
+
+/// Splits `text` into runs of non-blank lines, returning each run with its starting line index.
+fn split_chunks(text: &str) -> Vec<(usize, &str)> {
+    let mut chunks = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    for (line_index, line) in text.split_inclusive('\n').enumerate() {
+        if line.trim().is_empty() {
+            if let Some((chunk_line, chunk_offset)) = start.take() {
+                chunks.push((chunk_line, &text[chunk_offset..offset]));
+            }
+        } else if start.is_none() {
+            start = Some((line_index, offset));
+        }
+        offset += line.len();
+    }
+
+    if let Some((chunk_line, chunk_offset)) = start {
+        chunks.push((chunk_line, &text[chunk_offset..]));
+    }
+
+    chunks
+}
+
+/// Returns the byte offset and line index of a code fence that is never closed.
+fn find_unclosed_fence(text: &str) -> Option<(usize, usize)> {
+    let mut fences = Fences::default();
+    let mut opened = None;
+    let mut offset = 0;
+
+    for (line_index, line) in text.split_inclusive('\n').enumerate() {
+        if !fences.feed(line) && fences.is_open() {
+            opened = Some((offset, line_index));
+        }
+        offset += line.len();
+    }
+
+    opened.filter(|_| fences.is_open())
+}
+
+/// Tracks the code fences opened and closed line by line.
+#[derive(Default)]
+struct Fences {
+    open: Option<(char, usize)>,
+}
+
+impl Fences {
+    fn is_open(&self) -> bool {
+        self.open.is_some()
+    }
+
+    /// Moves past `line`, returning whether a fence was open before it.
+    fn feed(&mut self, line: &str) -> bool {
+        let was_open = self.is_open();
+        let trimmed = line.trim_start_matches([' ', '\t', '>']);
+        let Some(marker) = trimmed.chars().next().filter(|ch| *ch == '`' || *ch == '~') else {
+            return was_open;
+        };
+        let run = trimmed.chars().take_while(|ch| *ch == marker).count();
+        let rest = &trimmed[run..];
+
+        match self.open {
+            Some((open_marker, open_run)) => {
+                if marker == open_marker && run >= open_run && rest.trim().is_empty() {
+                    self.open = None;
+                }
+            }
+            None => {
+                if run >= 3 && !(marker == '`' && rest.contains('`')) {
+                    self.open = Some((marker, run));
+                }
+            }
+        }
+        was_open
+    }
+}
+
+/// A table whose rows disagree on their number of cells.
+#[derive(Debug, PartialEq, Eq)]
+struct MalformedTable {
+    /// Byte offsets of the table, from its header row to its last row.
+    bytes: Range<usize>,
+    /// Line indices of the table, from its header row to its last row.
+    lines: Range<usize>,
+    /// What disagrees, such as `the delimiter row has 1 cell(s) but the header has 2`.
+    problem: String,
+}
+
+/// Finds the tables, outside code fences, whose delimiter row or body rows do not have as
+/// many cells as their header row. A table is a line holding a `|` followed by a delimiter
+/// row (such as `|---|:--:|`), then by the following lines holding a `|`.
+fn find_malformed_tables(text: &str) -> Vec<MalformedTable> {
+    let mut lines = Vec::new();
+    let mut fences = Fences::default();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let fenced = fences.feed(line) || fences.is_open();
+        lines.push((offset..offset + line.len(), line, fenced));
+        offset += line.len();
+    }
+
+    let is_row = |index: usize| {
+        lines.get(index).is_some_and(|(_, line, fenced)| {
+            !fenced && line.contains('|') && !line.starts_with("    ") && !line.starts_with('\t')
+        })
+    };
+
+    let mut tables = Vec::new();
+    let mut index = 0;
+    while index + 1 < lines.len() {
+        if !is_row(index) || !is_row(index + 1) || !is_delimiter_row(lines[index + 1].1) {
+            index += 1;
+            continue;
+        }
+        let mut end = index + 2;
+        while is_row(end) {
+            end += 1;
+        }
+
+        let header = cell_count(lines[index].1);
+        let delimiter = cell_count(lines[index + 1].1);
+        let problem = if delimiter != header {
+            Some(format!(
+                "the delimiter row has {delimiter} cell(s) but the header has {header}"
+            ))
+        } else {
+            (index + 2..end).find_map(|row| {
+                let cells = cell_count(lines[row].1);
+                (cells != header).then(|| {
+                    format!(
+                        "row {} has {cells} cell(s) but the header has {header}",
+                        row - index - 1
+                    )
+                })
+            })
+        };
+        if let Some(problem) = problem {
+            tables.push(MalformedTable {
+                bytes: lines[index].0.start..lines[end - 1].0.end,
+                lines: index..end,
+                problem,
+            });
+        }
+        index = end;
+    }
+
+    tables
+}
+
+/// Checks whether `line` is a table delimiter row: cells of dashes, optionally flanked by
+/// colons, separated by `|`.
+fn is_delimiter_row(line: &str) -> bool {
+    let line = line.trim();
+    line.contains('|')
+        && line.contains('-')
+        && line
+            .chars()
+            .all(|ch| matches!(ch, '|' | '-' | ':' | ' ' | '\t'))
+}
+
+/// Counts the cells of a table row, ignoring its optional leading and trailing `|` and the
+/// escaped `\|` pipes inside cells.
+fn cell_count(line: &str) -> usize {
+    let row = line.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = match row.strip_suffix('|') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => row,
+    };
+
+    let mut cells = 1;
+    let mut chars = row.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                chars.next();
+            }
+            '|' => cells += 1,
+            _ => {}
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::ast::HeadingKind;
+
+    #[test]
+    fn test_well_formed_input_has_no_warnings() {
+        let (blocks, warnings) = parse_body("# Title\n\n```rust\nfn main() {}\n```\n", 1);
+        assert_eq!(blocks.len(), 2);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_unclosed_fence_is_preserved_verbatim() {
+        let body = "# Title\n\nIntro.\n\n```rust\nfn main() {\n\n## Not a heading\n";
+        let (blocks, warnings) = parse_body(body, 3);
+
+        assert_eq!(
+            warnings,
+            vec![ParseWarning {
+                line: 7,
+                message: "unclosed code fence; the remaining text is preserved verbatim"
+                    .to_string(),
+            }]
+        );
+        assert_eq!(blocks.len(), 3);
+        assert!(matches!(
+            &blocks[0],
+            Block::Heading(heading) if heading.kind == HeadingKind::Atx(1)
+        ));
+        assert_eq!(
+            blocks[2],
+            Block::HtmlBlock("```rust\nfn main() {\n\n## Not a heading".to_string())
+        );
+    }
+
+    #[test]
+    fn test_longer_fences_close_shorter_openers() {
+        assert_eq!(find_unclosed_fence("~~~\ncode\n~~~~\n"), None);
+        assert_eq!(find_unclosed_fence("````\ncode\n```\n"), Some((0, 0)));
+        assert_eq!(find_unclosed_fence("```code``` span.\n"), None);
+    }
+
+    #[test]
+    fn test_malformed_table_is_preserved_verbatim() {
+        let body = "# a\n\n| a | b\n|--|\n| 1 | 2 | 3 |\n\nAfter.\n";
+        let (blocks, warnings) = parse_body(body, 1);
+
+        assert_eq!(
+            warnings,
+            vec![ParseWarning {
+                line: 3,
+                message: "malformed table, the delimiter row has 1 cell(s) but the header has 2; \
+                          the table is preserved verbatim"
+                    .to_string(),
+            }]
+        );
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(
+            blocks[1],
+            Block::HtmlBlock("| a | b\n|--|\n| 1 | 2 | 3 |".to_string())
+        );
+        assert!(matches!(&blocks[2], Block::Paragraph(_)));
+    }
+
+    #[test]
+    fn test_find_malformed_tables_checks_every_row() {
+        let text = "| a | b |\n|---|:-:|\n| 1 | 2 |\n| 3 \\| 4 | 5 | 6 |\n";
+        let tables = find_malformed_tables(text);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].bytes, 0..text.len());
+        assert_eq!(tables[0].lines, 0..4);
+        assert_eq!(
+            tables[0].problem,
+            "row 2 has 3 cell(s) but the header has 2"
+        );
+
+        assert!(find_malformed_tables("| a | b |\n|---|---|\n| 1 \\| x | 2 |\n").is_empty());
+        assert!(find_malformed_tables("```\n| a | b\n|--|\n```\n").is_empty());
+    }
+
+    #[test]
+    fn test_split_chunks_reports_starting_lines() {
+        let chunks = split_chunks("one\ntwo\n\n\nthree\n");
+        assert_eq!(chunks, vec![(0, "one\ntwo\n"), (4, "three\n")]);
+    }
+}
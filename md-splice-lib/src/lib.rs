@@ -11,23 +11,14 @@
 //! ```rust
 //! use std::str::FromStr;
 //!
-//! use md_splice_lib::transaction::{InsertOperation, InsertPosition, Operation, Selector};
-//! use md_splice_lib::MarkdownDocument;
+//! use md_splice_lib::prelude::*;
 //!
-//! # fn demo() -> Result<(), md_splice_lib::error::SpliceError> {
+//! # fn demo() -> Result<(), SpliceError> {
 //! let mut document = MarkdownDocument::from_str(
 //!     "---\nstatus: pending\n---\n\n## Tasks\n\n- [ ] Write docs\n",
 //! )?;
 //!
-//! let selector = Selector {
-//!     select_type: Some("list".into()),
-//!     within: Some(Box::new(Selector {
-//!         select_type: Some("h2".into()),
-//!         select_contains: Some("Tasks".into()),
-//!         ..Selector::default()
-//!     })),
-//!     ..Selector::default()
-//! };
+//! let selector = Selector::of_type("list").within(Selector::of_type("h2").containing("Tasks"));
 //!
 //! let operation = Operation::Insert(InsertOperation {
 //!     selector: Some(selector),
@@ -41,35 +32,124 @@
 //! # Ok(())
 //! # }
 //! ```
-
+//!
+//! The [`prelude`] re-exports the document, operation, and error types used above. The
+//! other public modules expose the building blocks behind them, such as the
+//! [`locator`] that resolves selectors and the [`frontmatter`] parser. Types returned by
+//! internal modules, such as [`SourceSpan`] and [`ParseWarning`], are re-exported from
+//! the crate root.
+
+pub mod alias;
+pub(crate) mod anchors;
+pub(crate) mod codeinfo;
+pub(crate) mod comments;
+pub mod concat;
+pub(crate) mod conflict;
+pub(crate) mod deflist;
+pub(crate) mod diagnose;
+pub mod doctor;
 pub mod error;
+pub mod fingerprint;
 pub mod frontmatter;
+pub(crate) mod html;
+pub mod infer;
+pub mod inlines;
+pub(crate) mod lenient;
+pub(crate) mod limits;
+pub(crate) mod links;
+pub mod lint;
 pub mod locator;
-pub mod splicer;
+pub mod outline;
+pub mod policy;
+pub mod prelude;
+pub mod replay;
+pub(crate) mod routing;
+pub mod sections;
+pub(crate) mod semantic;
+pub mod slugify;
+pub(crate) mod spans;
+pub(crate) mod splicer;
+pub mod table;
+pub(crate) mod toc;
 pub mod transaction;
-
+pub mod warning;
+
+pub use crate::diagnose::NearMiss;
+pub use crate::lenient::ParseWarning;
+pub use crate::limits::Limits;
+pub use crate::routing::{route_operations, OperationEntry, OperationGroup};
+pub use crate::spans::SourceSpan;
+
+use crate::alias::ResolvedAlias;
+use crate::anchors::{
+    append_explicit_id, changed_anchors, explicit_id, heading_anchors, rewrite_anchor_links,
+    spliced_anchors,
+};
+use crate::codeinfo::{code_info, remove_info_attribute, set_info_attribute};
+use crate::comments::{comment_block, comment_text};
+use crate::concat::{merge_yaml, rebase_heading_levels, ConcatOptions, FrontmatterStrategy};
+use crate::conflict::Conflict;
+use crate::deflist::split_definition_lists;
+use crate::diagnose::explain_no_match;
 use crate::error::SpliceError;
-use crate::frontmatter::{refresh_frontmatter_block, FrontmatterFormat, ParsedDocument};
-use crate::locator::{locate, FoundNode, Selector};
+use crate::frontmatter::{
+    refresh_frontmatter_block, FrontmatterDelimiter, FrontmatterFormat, ParsedDocument,
+};
+use crate::html::{element_span, html_tag, strip_summary};
+use crate::infer::block_type_name;
+use crate::inlines::{inline_content, render_inline_node, splice_inline};
+use crate::limits::{Deadline, Stopwatch};
+use crate::lint::{find_duplicate_headings, DuplicateHeadings};
+use crate::locator::{
+    found_block_index, item_list, locate_all_cached, locate_cached, node_text, Between, FoundNode,
+    LocateCache, Selector,
+};
+use crate::outline::{heading_tree, HeadingNode};
+use crate::policy::OperationPolicy;
+use crate::sections::{
+    find_heading_section_end, get_heading_level, section_range, set_heading_level,
+};
+use crate::spans::{block_spans, track_spans};
 use crate::splicer::{
-    delete, delete_list_item, delete_section, insert, insert_list_item, replace, replace_list_item,
+    contained_blocks, dedupe_headings, delete, delete_list_item, delete_section, insert,
+    insert_code_lines, insert_list_item, insert_table_column, insert_tracked, item_list_blocks,
+    merge_sections, prune_empty_sections, remove_table_column, renumber_ordered_lists, replace,
+    replace_inline_text, replace_list_item, replace_text, unwrap, wrap, BlockChange,
+};
+use crate::table::{
+    cell_content, find_body_row, find_column, parse_cell, render_cell, render_table_node,
+    table_cell, table_cell_mut, CellAddress, TableRow,
 };
+use crate::toc::{build_toc, find_toc_markers, toc_markers, TOC_START};
 use crate::transaction::{
-    DeleteFrontmatterOperation, DeleteOperation, InsertOperation, Operation,
-    ReplaceFrontmatterOperation, ReplaceOperation, Selector as TransactionSelector,
-    SetFrontmatterOperation,
+    AddTableColumnOperation, AlertType, AssertExpectation, AssertOperation, ColumnAlignment,
+    CommentPosition, CopyOperation, DedupeHeadingsOperation, DeleteFrontmatterOperation,
+    DeleteOperation, DeleteTableRowOperation, GetOperation, ImportSectionOperation,
+    InsertOperation, InsertPosition, InsertTableRowOperation, MergeSectionsOperation,
+    MoveOperation, Operation, PruneEmptySectionsOperation, RemoveAlertOperation,
+    RemoveCommentOperation, RemoveTableColumnOperation, RenameHeadingOperation,
+    RenameTableColumnOperation, ReplaceFrontmatterOperation, ReplaceInnerHtmlOperation,
+    ReplaceOperation, ReplaceTextOperation, Selector as TransactionSelector, SetAlertOperation,
+    SetCodeAttributeOperation, SetCodeLanguageOperation, SetCommentOperation,
+    SetFrontmatterOperation, ShiftHeadingOperation, SwapOperation, UnwrapDetailsOperation,
+    UnwrapOperation, UpdateTocOperation, UpsertOperation, WrapDetailsOperation, WrapOperation,
 };
+use crate::warning::{Warning, WarningKind};
 use anyhow::{anyhow, Context};
-use markdown_ppp::ast::Block;
 use markdown_ppp::ast::Document;
+use markdown_ppp::ast::{
+    Alignment, Block, CodeBlockKind, GitHubAlert, GitHubAlertType, Inline, List, ListItem,
+    ListKind, Table,
+};
 use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
 use markdown_ppp::printer::{config::Config as PrinterConfig, render_markdown};
 use regex::Regex;
 use serde_yaml::{Mapping, Value as YamlValue};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::io::{self, Read};
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 /// Represents an in-memory Markdown document that can be manipulated using
@@ -77,6 +157,7 @@ use std::str::FromStr;
 pub struct MarkdownDocument {
     parsed: ParsedDocument,
     doc: Document,
+    source: Option<Source>,
 }
 
 impl Clone for MarkdownDocument {
@@ -84,20 +165,210 @@ impl Clone for MarkdownDocument {
         Self {
             parsed: self.parsed.clone(),
             doc: self.doc.clone(),
+            source: self.source.clone(),
         }
     }
 }
 
+/// Where the body of a document starts in its source, and how it is parsed.
+#[derive(Debug, Clone, Copy)]
+struct SourceOrigin {
+    first_line: usize,
+    lenient: bool,
+    definition_lists: bool,
+}
+
+/// The blocks of a parsed document as they were parsed, along with their source spans,
+/// which are recovered once at parse time and carried over to later versions of the blocks.
+#[derive(Debug, Clone)]
+struct Source {
+    definition_lists: bool,
+    blocks: Vec<Block>,
+    spans: Vec<Option<SourceSpan>>,
+}
+
 /// Result metadata describing the side-effects of applying a batch of operations.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ApplyOutcome {
     /// Whether the frontmatter payload was mutated by any operation in the batch.
     pub frontmatter_mutated: bool,
     /// Whether any selector matched more than one node (ambiguity) during execution.
     pub ambiguity_detected: bool,
+    /// Non-fatal conditions encountered while applying the batch, in operation order.
+    pub warnings: Vec<Warning>,
+    /// Number of operations executed in the batch, keyed by operation kind (e.g. `"replace"`).
+    pub operation_counts: BTreeMap<&'static str, usize>,
+    /// Top-level block ranges touched by each body operation, in operation order.
+    pub affected_blocks: Vec<AffectedBlocks>,
+    /// Total number of top-level blocks added across the batch.
+    ///
+    /// A block that was modified in place (such as a list gaining an item) counts as
+    /// one block removed and one block added.
+    pub blocks_added: usize,
+    /// Total number of top-level blocks removed across the batch.
+    pub blocks_removed: usize,
+    /// Every selector alias known at the end of the batch (including imported ones),
+    /// resolved against the resulting document and sorted by name.
+    pub aliases: Vec<ResolvedAlias>,
+    /// Content rendered by `get` operations, in operation order.
+    pub captures: Vec<Capture>,
+}
+
+impl ApplyOutcome {
+    /// Returns the net change in the number of top-level blocks.
+    pub fn net_blocks(&self) -> isize {
+        self.blocks_added as isize - self.blocks_removed as isize
+    }
+}
+
+/// The top-level blocks touched by a single body operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffectedBlocks {
+    /// The 0-indexed position of the operation within the batch.
+    pub operation_index: usize,
+    /// Indices of the resulting blocks, as they stood right after the operation ran.
+    ///
+    /// The range is empty when the operation only removed blocks; its start is then
+    /// the index where the removed blocks used to be.
+    pub range: Range<usize>,
+}
+
+/// The content rendered by a single `get` operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capture {
+    /// The 0-indexed position of the operation within the batch.
+    pub operation_index: usize,
+    /// The variable name given by the operation's `capture` field, if any.
+    pub name: Option<String>,
+    /// The rendered Markdown, as `get` prints it.
+    pub content: String,
+}
+
+/// Options controlling how [`MarkdownDocument::from_str_with_options`] parses a document.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Recover from malformed constructs instead of failing, as
+    /// [`MarkdownDocument::from_str_lenient`] does.
+    pub lenient: bool,
+    /// Frontmatter delimiter pairs recognized in addition to
+    /// [`FrontmatterDelimiter::defaults`].
+    pub frontmatter_delimiters: Vec<FrontmatterDelimiter>,
+    /// Split compact definition list entries (`Term\n: Definition`) into a paragraph per
+    /// term and per definition, in the document and in the content of operations, so that
+    /// `dt` and `dd` selectors match them. The split entries are written back in their loose
+    /// form. Without it, such an entry stays the paragraph it parses to and is written back
+    /// as it was.
+    pub definition_lists: bool,
 }
 
 impl MarkdownDocument {
+    /// Parses Markdown with explicit [`ParseOptions`].
+    ///
+    /// The returned warnings are always empty unless [`ParseOptions::lenient`] is set.
+    pub fn from_str_with_options(
+        content: &str,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), SpliceError> {
+        let parsed = frontmatter::parse_with_delimiters(content, &options.frontmatter_delimiters)
+            .map_err(|err| SpliceError::FrontmatterParse(err.to_string()))?;
+
+        let offset = content.len() - parsed.body.len();
+        let origin = SourceOrigin {
+            first_line: content[..offset].matches('\n').count() + 1,
+            lenient: options.lenient,
+            definition_lists: options.definition_lists,
+        };
+        let (blocks, warnings) = parse_body_blocks(&parsed.body, origin)?;
+        let spans = block_spans(
+            &parsed.body,
+            &blocks,
+            offset,
+            origin.first_line,
+            origin.definition_lists,
+        );
+
+        Ok((
+            Self {
+                parsed,
+                doc: Document {
+                    blocks: blocks.clone(),
+                },
+                source: Some(Source {
+                    definition_lists: options.definition_lists,
+                    blocks,
+                    spans,
+                }),
+            },
+            warnings,
+        ))
+    }
+
+    /// Parses Markdown in lenient mode, recovering from malformed constructs.
+    ///
+    /// Regions that cannot be parsed reliably (such as an unclosed code fence) are kept
+    /// verbatim as opaque blocks instead of failing the whole document. Each recovery is
+    /// reported as a [`ParseWarning`] with the line where the problem starts. Malformed
+    /// frontmatter is still reported as an error.
+    pub fn from_str_lenient(content: &str) -> Result<(Self, Vec<ParseWarning>), SpliceError> {
+        let options = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        Self::from_str_with_options(content, &options)
+    }
+
+    /// Combines `documents` into a new document, in order.
+    ///
+    /// Heading levels and frontmatter are combined as described by [`ConcatOptions`]. The
+    /// combined frontmatter keeps the format and delimiters of the first document that has one.
+    pub fn concat(
+        documents: &[MarkdownDocument],
+        options: &ConcatOptions,
+    ) -> Result<Self, SpliceError> {
+        let mut blocks = Vec::new();
+        for document in documents {
+            let start = blocks.len();
+            blocks.extend_from_slice(&document.doc.blocks);
+            if let Some(base_level) = options.base_level {
+                rebase_heading_levels(&mut blocks[start..], base_level);
+            }
+        }
+
+        let mut with_frontmatter = documents
+            .iter()
+            .filter_map(|document| Some((document, document.parsed.frontmatter.as_ref()?)));
+        let mut parsed = match (options.frontmatter, with_frontmatter.next()) {
+            (FrontmatterStrategy::Drop, _) | (_, None) => ParsedDocument {
+                frontmatter: None,
+                body: String::new(),
+                format: None,
+                frontmatter_block: None,
+                delimiter: None,
+            },
+            (_, Some((first, _))) => first.parsed.clone(),
+        };
+        if options.frontmatter == FrontmatterStrategy::Merge {
+            let mut rest = with_frontmatter.peekable();
+            if rest.peek().is_some() {
+                let mut merged = parsed.frontmatter.clone().unwrap_or_default();
+                for (_, value) in rest {
+                    merge_yaml(&mut merged, value.clone());
+                }
+                replace_entire_frontmatter(&mut parsed, merged, None)
+                    .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
+                refresh_frontmatter_block(&mut parsed)
+                    .map_err(|err| SpliceError::FrontmatterSerialize(err.to_string()))?;
+            }
+        }
+        parsed.body.clear();
+
+        Ok(Self {
+            parsed,
+            doc: Document { blocks },
+            source: None,
+        })
+    }
+
     /// Applies a list of transactional operations to the document.
     ///
     /// Operations are executed sequentially against a temporary copy of the
@@ -114,8 +385,54 @@ impl MarkdownDocument {
         &mut self,
         operations: Vec<Operation>,
     ) -> Result<ApplyOutcome, SpliceError> {
-        let outcome =
-            apply_operations_with_ambiguity(&mut self.doc.blocks, &mut self.parsed, operations)?;
+        self.apply_with_aliases(operations, &[])
+    }
+
+    /// Applies operations with selector aliases exported by an earlier batch already defined.
+    ///
+    /// The imported aliases can be referenced through `selector_ref` and the other `*_ref`
+    /// fields just like aliases defined inside `operations`. Defining an alias with the same
+    /// name as an imported one raises [`SpliceError::SelectorAliasAlreadyDefined`].
+    pub fn apply_with_aliases(
+        &mut self,
+        operations: Vec<Operation>,
+        aliases: &[ResolvedAlias],
+    ) -> Result<ApplyOutcome, SpliceError> {
+        self.apply_with_aliases_and_limits(operations, aliases, &Limits::default())
+    }
+
+    /// Applies operations like [`MarkdownDocument::apply_with_aliases`], after checking them
+    /// against `limits`, and stops with [`SpliceError::TimeLimitExceeded`] once an operation
+    /// or the batch runs longer than the limits allow, leaving the document untouched.
+    pub fn apply_with_aliases_and_limits(
+        &mut self,
+        operations: Vec<Operation>,
+        aliases: &[ResolvedAlias],
+        limits: &Limits,
+    ) -> Result<ApplyOutcome, SpliceError> {
+        limits.check_operations(&operations)?;
+        // Near misses refer to the blocks the failing operation searched, which are only
+        // known to be the document's own when no earlier operation could have changed them.
+        let single_operation = operations.len() == 1;
+        let spans = self.block_spans();
+        let outcome = apply_operations_with_ambiguity(
+            &mut self.doc.blocks,
+            &mut self.parsed,
+            operations,
+            aliases,
+            spans,
+            limits,
+            self.source
+                .as_ref()
+                .is_some_and(|source| source.definition_lists),
+        )
+        .map_err(|err| {
+            if single_operation {
+                self.cite_source_lines(err)
+            } else {
+                err
+            }
+        })?;
 
         if outcome.frontmatter_mutated {
             refresh_frontmatter_block(&mut self.parsed)
@@ -125,6 +442,36 @@ impl MarkdownDocument {
         Ok(outcome)
     }
 
+    /// Applies operations like [`MarkdownDocument::apply_with_ambiguity`], after checking
+    /// them against `limits`.
+    ///
+    /// Fails with [`SpliceError::LimitExceeded`], leaving the document untouched, if the
+    /// batch exceeds one of the limits, and with [`SpliceError::TimeLimitExceeded`] if it
+    /// runs out of time. Check the size of the document itself with [`Limits::check_input`]
+    /// before parsing it.
+    pub fn apply_with_limits(
+        &mut self,
+        operations: Vec<Operation>,
+        limits: &Limits,
+    ) -> Result<ApplyOutcome, SpliceError> {
+        self.apply_with_aliases_and_limits(operations, &[], limits)
+    }
+
+    /// Applies operations like [`MarkdownDocument::apply_with_ambiguity`], after checking
+    /// that `policy` allows them.
+    ///
+    /// Fails with [`SpliceError::OperationNotAllowed`], leaving the document untouched, if
+    /// an operation does something `policy` forbids, such as reading a `content_file` under
+    /// [`OperationPolicy::sandboxed`].
+    pub fn apply_with_policy(
+        &mut self,
+        operations: Vec<Operation>,
+        policy: &OperationPolicy,
+    ) -> Result<ApplyOutcome, SpliceError> {
+        policy.check_operations(&operations)?;
+        self.apply_with_aliases(operations, &[])
+    }
+
     /// Renders the document, including frontmatter, back to a Markdown string.
     ///
     /// The output preserves the original frontmatter delimiter style and renders the body
@@ -132,30 +479,221 @@ impl MarkdownDocument {
     pub fn render(&self) -> String {
         let mut output = String::new();
 
-        if let Some(prefix) = self.parsed.frontmatter_block.as_deref() {
+        if let Some(prefix) = self.render_frontmatter() {
             output.push_str(prefix);
         }
 
-        let body_output = render_markdown(&self.doc, default_printer_config());
-        output.push_str(&body_output);
+        output.push_str(&self.render_body());
 
         output
     }
 
+    /// Renders the Markdown body without the frontmatter block.
+    ///
+    /// Uses the same printer configuration as [`render`](Self::render), so
+    /// `render_frontmatter` followed by `render_body` reproduces its output.
+    pub fn render_body(&self) -> String {
+        render_markdown(&self.doc, default_printer_config())
+    }
+
+    /// Returns the frontmatter block as it is rendered, including its delimiters and the
+    /// separator that precedes the body, or `None` if the document has no frontmatter.
+    pub fn render_frontmatter(&self) -> Option<&str> {
+        self.parsed.frontmatter_block.as_deref()
+    }
+
     /// Provides read-only access to the Markdown AST blocks.
     pub fn blocks(&self) -> &[Block] {
         &self.doc.blocks
     }
 
+    /// Finds the node matching `selector` like [`locator::locate`], also matching `at_line` against
+    /// the [`block_spans`](MarkdownDocument::block_spans) of the document.
+    pub fn locate(&self, selector: &Selector) -> Result<(FoundNode<'_>, bool), SpliceError> {
+        locate_cached(
+            &self.doc.blocks,
+            selector,
+            &mut LocateCache::with_spans(self.block_spans()),
+        )
+    }
+
+    /// Finds every node matching `selector` like [`locator::locate_all`], also matching `at_line`
+    /// against the [`block_spans`](MarkdownDocument::block_spans) of the document.
+    pub fn locate_all(&self, selector: &Selector) -> Result<Vec<FoundNode<'_>>, SpliceError> {
+        locate_all_cached(
+            &self.doc.blocks,
+            selector,
+            &mut LocateCache::with_spans(self.block_spans()),
+        )
+    }
+
+    /// Returns the end of the range starting at the top-level block `start` and running up
+    /// to the first following block matched by `until`, like the `until` selector of an
+    /// operation: the index of that block, or the number of blocks if none matches. `at_line`
+    /// is matched as in [`MarkdownDocument::locate`].
+    ///
+    /// Fails with [`SpliceError::RangeRequiresBlock`] if `until` matches a nested node.
+    pub fn range_end(&self, start: usize, until: &Selector) -> Result<usize, SpliceError> {
+        compute_range_end(
+            &self.doc.blocks,
+            start,
+            until,
+            &LocateCache::with_spans(self.block_spans()),
+        )
+    }
+
+    /// Turns the [`SpliceError::NodeNotFound`] error of a search for `selector` in this document
+    /// into a [`SpliceError::NodeNotFoundWithNearMisses`] error listing the nodes closest to
+    /// matching, with their source lines. Other errors are returned unchanged.
+    pub fn explain_no_match(&self, err: SpliceError, selector: &Selector) -> SpliceError {
+        self.cite_source_lines(explain_no_match(err, &self.doc.blocks, selector))
+    }
+
+    /// Returns the top-level blocks spanned by the section of the heading matched by `selector`,
+    /// including the heading itself.
+    ///
+    /// Fails with [`SpliceError::NodeNotFound`] (or [`SpliceError::NodeNotFoundWithNearMisses`])
+    /// if nothing matches and with [`SpliceError::SectionRequiresHeading`] if the match is not a
+    /// heading.
+    pub fn section_of(&self, selector: &Selector) -> Result<Range<usize>, SpliceError> {
+        let (found, _) = self
+            .locate(selector)
+            .map_err(|err| self.explain_no_match(err, selector))?;
+        match found {
+            FoundNode::Block { index, .. } => section_range(&self.doc.blocks, index),
+            _ => None,
+        }
+        .ok_or(SpliceError::SectionRequiresHeading)
+    }
+
+    /// Returns where each top-level block was written in the source the document was parsed
+    /// from, in document order.
+    ///
+    /// Spans survive edits: blocks left unchanged since parsing keep theirs, while blocks
+    /// added or modified by operations, and the blocks of a document built by
+    /// [`MarkdownDocument::concat`], have none. See `spans` for how they are recovered.
+    pub fn block_spans(&self) -> Vec<Option<SourceSpan>> {
+        match &self.source {
+            Some(source) => track_spans(&source.blocks, &source.spans, &self.doc.blocks),
+            None => vec![None; self.doc.blocks.len()],
+        }
+    }
+
+    /// Adds to the near misses listed by a [`SpliceError::NodeNotFoundWithNearMisses`] error
+    /// the source line of the block holding each of them. `err` must come from a search of
+    /// this document as it currently stands. Other errors are returned unchanged.
+    pub fn cite_source_lines(&self, err: SpliceError) -> SpliceError {
+        let SpliceError::NodeNotFoundWithNearMisses(mut near_misses) = err else {
+            return err;
+        };
+        let spans = self.block_spans();
+        for near_miss in &mut near_misses {
+            near_miss.line = spans
+                .get(near_miss.block_index)
+                .and_then(|span| Some(span.as_ref()?.start_line));
+        }
+        SpliceError::NodeNotFoundWithNearMisses(near_misses)
+    }
+
+    /// Returns the content fingerprint of each top-level block, in document order.
+    ///
+    /// See [`fingerprint`] for how fingerprints are computed. They can be passed back as
+    /// `select_fingerprint` to find the same block after edits move it around.
+    pub fn fingerprints(&self) -> Vec<String> {
+        self.doc
+            .blocks
+            .iter()
+            .map(fingerprint::block_fingerprint)
+            .collect()
+    }
+
+    /// Returns the document's headings as a tree of sections.
+    ///
+    /// See [`outline::heading_tree`] for how levels are nested.
+    pub fn heading_tree(&self) -> Vec<HeadingNode> {
+        heading_tree(&self.doc.blocks)
+    }
+
     /// Returns the parsed frontmatter value, if present.
     pub fn frontmatter(&self) -> Option<&YamlValue> {
         self.parsed.frontmatter.as_ref()
     }
 
+    /// Returns the frontmatter text exactly as written between its delimiters, if present.
+    pub fn raw_frontmatter(&self) -> Option<&str> {
+        self.parsed.raw_frontmatter()
+    }
+
     /// Returns the serialization format of the frontmatter, if known.
     pub fn frontmatter_format(&self) -> Option<FrontmatterFormat> {
         self.parsed.format
     }
+
+    /// Replaces the entire frontmatter with `value`, serialized as `format`.
+    ///
+    /// A document without frontmatter gains a new block. Passing [`YamlValue::Null`]
+    /// removes the block, like [`remove_frontmatter`](Self::remove_frontmatter). If `value`
+    /// cannot be serialized in `format`, the document is left untouched.
+    pub fn set_frontmatter(
+        &mut self,
+        value: YamlValue,
+        format: FrontmatterFormat,
+    ) -> Result<(), SpliceError> {
+        let mut parsed = self.parsed.clone();
+        replace_entire_frontmatter(&mut parsed, value, Some(format))
+            .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
+        refresh_frontmatter_block(&mut parsed)
+            .map_err(|err| SpliceError::FrontmatterSerialize(err.to_string()))?;
+        self.parsed = parsed;
+        Ok(())
+    }
+
+    /// Removes the frontmatter block, returning its value if the document had one.
+    pub fn remove_frontmatter(&mut self) -> Option<YamlValue> {
+        let removed = self.parsed.frontmatter.take();
+        self.parsed.frontmatter_block = None;
+        self.parsed.format = None;
+        self.parsed.delimiter = None;
+        removed
+    }
+
+    /// Rewrites the relative destinations of links and images for the document moving from
+    /// the directory `from` to the directory `to`, so that they keep pointing at the same
+    /// files. Returns the number of rewritten destinations.
+    ///
+    /// See `links::rebase_links` for which destinations are rewritten.
+    pub fn rebase_links(&mut self, from: &Path, to: &Path) -> usize {
+        links::rebase_links(&mut self.doc.blocks, from, to)
+    }
+
+    /// Compares two documents while ignoring formatting-only differences.
+    ///
+    /// Both the Markdown bodies and the frontmatter payloads are compared after
+    /// normalization (see `semantic`), so heading styles, bullet markers, line
+    /// wrapping, and the frontmatter serialization format do not affect the result.
+    pub fn semantically_equals(&self, other: &MarkdownDocument) -> bool {
+        semantic::frontmatter_equals(self.frontmatter(), other.frontmatter())
+            && semantic::normalize_blocks(self.blocks())
+                == semantic::normalize_blocks(other.blocks())
+    }
+}
+
+/// Parses the body of a document as [`MarkdownDocument::from_str_with_options`] does.
+fn parse_body_blocks(
+    body: &str,
+    origin: SourceOrigin,
+) -> Result<(Vec<Block>, Vec<ParseWarning>), SpliceError> {
+    let (mut blocks, warnings) = if origin.lenient {
+        lenient::parse_body(body, origin.first_line)
+    } else {
+        let doc = parse_markdown(MarkdownParserState::default(), body)
+            .map_err(|err| SpliceError::MarkdownParse(err.to_string()))?;
+        (doc.blocks, Vec::new())
+    };
+    if origin.definition_lists {
+        split_definition_lists(&mut blocks);
+    }
+    Ok((blocks, warnings))
 }
 
 /// Returns the default printer configuration used by `md-splice` when rendering Markdown.
@@ -166,18 +704,59 @@ pub fn default_printer_config() -> PrinterConfig {
     PrinterConfig::default().with_spaces_before_list_item(0)
 }
 
+/// Renders a slice of blocks as a standalone Markdown fragment using `config`.
+///
+/// Non-empty output always ends with a newline, so fragments can be printed or
+/// concatenated as-is. An empty slice renders to an empty string. The configuration is
+/// taken by value because the printer's `Config` cannot be cloned.
+pub fn render_fragment(blocks: &[Block], config: PrinterConfig) -> String {
+    let fragment = Document {
+        blocks: blocks.to_vec(),
+    };
+    let mut rendered = render_markdown(&fragment, config);
+    if !rendered.is_empty() && !rendered.ends_with('\n') {
+        rendered.push('\n');
+    }
+    rendered
+}
+
+/// Parses `doc`, applies the operations listed in `ops_yaml`, and renders the result.
+///
+/// `ops_yaml` holds a list of operations in the YAML or JSON format of `md-splice apply`.
+/// Every failure, whether in the Markdown, the operations, or while applying them, is
+/// reported as a [`SpliceError`]: no input makes this function panic, and the same inputs
+/// always give the same result. This makes it suitable as a fuzz target and for handling
+/// untrusted requests, with one caveat: operations with a `content_file`, `value_file`, or
+/// `source_file` read it from disk. Reject them first with [`OperationPolicy::sandboxed`].
+pub fn apply_str(doc: &str, ops_yaml: &str) -> Result<String, SpliceError> {
+    apply_str_with_limits(doc, ops_yaml, &Limits::default())
+}
+
+/// Behaves like [`apply_str`], but first checks both inputs and the parsed operations
+/// against `limits`, failing with [`SpliceError::LimitExceeded`] before doing any work
+/// they would bound.
+pub fn apply_str_with_limits(
+    doc: &str,
+    ops_yaml: &str,
+    limits: &Limits,
+) -> Result<String, SpliceError> {
+    limits.check_input("document", doc)?;
+    limits.check_input("operations text", ops_yaml)?;
+    let operations: Vec<Operation> = serde_yaml::from_str(ops_yaml)
+        .map_err(|err| SpliceError::OperationParse(err.to_string()))?;
+    let mut document = MarkdownDocument::from_str(doc)?;
+    document.apply_with_limits(operations, limits)?;
+    Ok(document.render())
+}
+
 impl FromStr for MarkdownDocument {
     type Err = SpliceError;
 
     /// Parses Markdown (including optional YAML/TOML frontmatter) into a
     /// [`MarkdownDocument`].
     fn from_str(content: &str) -> Result<Self, Self::Err> {
-        let parsed = frontmatter::parse(content)
-            .map_err(|err| SpliceError::FrontmatterParse(err.to_string()))?;
-        let doc = parse_markdown(MarkdownParserState::default(), &parsed.body)
-            .map_err(|err| SpliceError::MarkdownParse(err.to_string()))?;
-
-        Ok(Self { parsed, doc })
+        let (doc, _) = Self::from_str_with_options(content, &ParseOptions::default())?;
+        Ok(doc)
     }
 }
 
@@ -185,16 +764,18 @@ fn compute_range_end(
     blocks: &[Block],
     start_index: usize,
     until_selector: &Selector,
-) -> anyhow::Result<usize> {
+    cache: &LocateCache,
+) -> Result<usize, SpliceError> {
     if start_index + 1 >= blocks.len() {
         return Ok(blocks.len());
     }
 
-    match locate(&blocks[start_index + 1..], until_selector) {
+    let following = &blocks[start_index + 1..];
+    match locate_cached(following, until_selector, &mut cache.tail(start_index + 1)) {
         Ok((FoundNode::Block { index, .. }, _)) => Ok(start_index + 1 + index),
-        Ok((FoundNode::ListItem { .. }, _)) => Err(SpliceError::RangeRequiresBlock.into()),
+        Ok(_) => Err(SpliceError::RangeRequiresBlock),
         Err(SpliceError::NodeNotFound) => Ok(blocks.len()),
-        Err(other) => Err(other.into()),
+        Err(other) => Err(other),
     }
 }
 
@@ -204,7 +785,15 @@ fn apply_operations(
     parsed_document: &mut ParsedDocument,
     operations: Vec<Operation>,
 ) -> Result<bool, SpliceError> {
-    let outcome = apply_operations_with_ambiguity(doc_blocks, parsed_document, operations)?;
+    let outcome = apply_operations_with_ambiguity(
+        doc_blocks,
+        parsed_document,
+        operations,
+        &[],
+        Vec::new(),
+        &Limits::default(),
+        false,
+    )?;
     Ok(outcome.frontmatter_mutated)
 }
 
@@ -212,14 +801,55 @@ fn apply_operations_with_ambiguity(
     doc_blocks: &mut Vec<Block>,
     parsed_document: &mut ParsedDocument,
     operations: Vec<Operation>,
+    imported_aliases: &[ResolvedAlias],
+    spans: Vec<Option<SourceSpan>>,
+    limits: &Limits,
+    definition_lists: bool,
 ) -> Result<ApplyOutcome, SpliceError> {
+    let conflicts = conflict::detect_conflicts(&operations);
+    let mut stopwatch = Stopwatch::start(limits);
+
     let mut working_blocks = doc_blocks.clone();
     let mut working_document = parsed_document.clone();
     let mut frontmatter_mutated = false;
     let mut ambiguity_detected = false;
+    let mut warnings = Vec::new();
+    let mut operation_counts = BTreeMap::new();
+    let mut affected_blocks = Vec::new();
+    let mut blocks_added = 0;
+    let mut blocks_removed = 0;
     let mut alias_map: HashMap<String, Selector> = HashMap::new();
+    let mut locate_cache = LocateCache::with_spans(spans);
+    let mut captures: Vec<Capture> = Vec::new();
+
+    for alias in imported_aliases {
+        let resolved = resolve_selector_tree(&alias_map, &alias.selector)?;
+        register_aliases(
+            &mut alias_map,
+            vec![(alias.name.clone(), resolved.selector)],
+        )?;
+    }
+
+    for (operation_index, mut operation) in operations.into_iter().enumerate() {
+        stopwatch.start_operation(operation_index)?;
+        locate_cache.set_deadline(stopwatch.deadline(operation_index));
+        for conflict in conflicts
+            .iter()
+            .filter(|conflict| conflict.operation_index == operation_index)
+        {
+            check_conflict(
+                conflict,
+                &alias_map,
+                &working_blocks,
+                &mut locate_cache,
+                &mut warnings,
+            )?;
+        }
+        *operation_counts.entry(operation.kind()).or_insert(0) += 1;
+        interpolate_captures(&mut operation, &captures);
+        let description = operation.to_string();
+        let mut change = None;
 
-    for operation in operations {
         match operation {
             Operation::Replace(replace_op) => {
                 let SelectorResolution {
@@ -240,16 +870,53 @@ fn apply_operations_with_ambiguity(
                     replace_op.until_ref.as_ref(),
                     "until",
                 )?;
-                let was_ambiguous = apply_replace_operation(
+                let report = apply_replace_operation(
                     &mut working_blocks,
                     replace_op,
                     selector,
                     until_selector,
+                    definition_lists,
+                    &mut locate_cache,
                 )
-                .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
+                .map_err(operation_failed)?;
+                aliases.append(&mut until_aliases);
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::ReplaceText(text_op) => {
+                let SelectorResolution {
+                    selector,
+                    mut aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    text_op.selector.as_ref(),
+                    text_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let OptionalSelectorResolution {
+                    selector: until_selector,
+                    aliases: mut until_aliases,
+                } = resolve_optional_operation_selector(
+                    &alias_map,
+                    text_op.until.as_ref(),
+                    text_op.until_ref.as_ref(),
+                    "until",
+                )?;
+                let report = apply_replace_text_operation(
+                    &mut working_blocks,
+                    text_op,
+                    selector,
+                    until_selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
                 aliases.append(&mut until_aliases);
                 register_aliases(&mut alias_map, aliases)?;
-                ambiguity_detected |= was_ambiguous;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
             }
             Operation::Insert(insert_op) => {
                 let SelectorResolution { selector, aliases } = resolve_operation_selector(
@@ -258,11 +925,18 @@ fn apply_operations_with_ambiguity(
                     insert_op.selector_ref.as_ref(),
                     "selector",
                 )?;
-                let was_ambiguous =
-                    apply_insert_operation(&mut working_blocks, insert_op, selector)
-                        .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
+                let report = apply_insert_operation(
+                    &mut working_blocks,
+                    insert_op,
+                    selector,
+                    definition_lists,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
                 register_aliases(&mut alias_map, aliases)?;
-                ambiguity_detected |= was_ambiguous;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
             }
             Operation::Delete(delete_op) => {
                 let SelectorResolution {
@@ -283,200 +957,3371 @@ fn apply_operations_with_ambiguity(
                     delete_op.until_ref.as_ref(),
                     "until",
                 )?;
-                let was_ambiguous = apply_delete_operation(
+                let report = apply_delete_operation(
                     &mut working_blocks,
                     delete_op,
                     selector,
                     until_selector,
+                    &mut locate_cache,
                 )
-                .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
+                .map_err(operation_failed)?;
                 aliases.append(&mut until_aliases);
                 register_aliases(&mut alias_map, aliases)?;
-                ambiguity_detected |= was_ambiguous;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
             }
-            Operation::SetFrontmatter(set_op) => {
-                apply_set_frontmatter_operation(&mut working_document, set_op)
-                    .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
-                frontmatter_mutated = true;
+            Operation::Upsert(upsert_op) => {
+                let SelectorResolution {
+                    selector,
+                    mut aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    upsert_op.selector.as_ref(),
+                    upsert_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let OptionalSelectorResolution {
+                    selector: fallback,
+                    aliases: mut fallback_aliases,
+                } = resolve_optional_operation_selector(
+                    &alias_map,
+                    upsert_op.fallback.as_ref(),
+                    upsert_op.fallback_ref.as_ref(),
+                    "fallback",
+                )?;
+                let report = apply_upsert_operation(
+                    &mut working_blocks,
+                    upsert_op,
+                    selector,
+                    fallback,
+                    definition_lists,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                aliases.append(&mut fallback_aliases);
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
             }
-            Operation::DeleteFrontmatter(delete_op) => {
-                apply_delete_frontmatter_operation(&mut working_document, delete_op)
-                    .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
-                frontmatter_mutated = true;
+            Operation::Move(move_op) => {
+                let SelectorResolution {
+                    selector,
+                    mut aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    move_op.selector.as_ref(),
+                    move_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let SelectorResolution {
+                    selector: destination,
+                    aliases: mut destination_aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    move_op.destination.as_ref(),
+                    move_op.destination_ref.as_ref(),
+                    "destination",
+                )?;
+                let report = apply_move_operation(
+                    &mut working_blocks,
+                    move_op,
+                    selector,
+                    destination,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                aliases.append(&mut destination_aliases);
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
             }
-            Operation::ReplaceFrontmatter(replace_op) => {
-                apply_replace_frontmatter_operation(&mut working_document, replace_op)
-                    .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
-                frontmatter_mutated = true;
+            Operation::Copy(copy_op) => {
+                let SelectorResolution {
+                    selector,
+                    mut aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    copy_op.selector.as_ref(),
+                    copy_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let OptionalSelectorResolution {
+                    selector: until_selector,
+                    aliases: mut until_aliases,
+                } = resolve_optional_operation_selector(
+                    &alias_map,
+                    copy_op.until.as_ref(),
+                    copy_op.until_ref.as_ref(),
+                    "until",
+                )?;
+                let SelectorResolution {
+                    selector: destination,
+                    aliases: mut destination_aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    copy_op.destination.as_ref(),
+                    copy_op.destination_ref.as_ref(),
+                    "destination",
+                )?;
+                let report = apply_copy_operation(
+                    &mut working_blocks,
+                    copy_op,
+                    selector,
+                    until_selector,
+                    destination,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                aliases.append(&mut until_aliases);
+                aliases.append(&mut destination_aliases);
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::Swap(swap_op) => {
+                let SelectorResolution {
+                    selector,
+                    mut aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    swap_op.selector.as_ref(),
+                    swap_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let SelectorResolution {
+                    selector: other,
+                    aliases: mut other_aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    swap_op.other.as_ref(),
+                    swap_op.other_ref.as_ref(),
+                    "other",
+                )?;
+                let report = apply_swap_operation(
+                    &mut working_blocks,
+                    swap_op,
+                    selector,
+                    other,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                aliases.append(&mut other_aliases);
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
             }
+            Operation::RenameHeading(rename_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    rename_op.selector.as_ref(),
+                    rename_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_rename_heading_operation(
+                    &mut working_blocks,
+                    rename_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::ShiftHeading(shift_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    shift_op.selector.as_ref(),
+                    shift_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_shift_heading_operation(
+                    &mut working_blocks,
+                    shift_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::MergeSections(merge_op) => {
+                let SelectorResolution {
+                    selector,
+                    mut aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    merge_op.selector.as_ref(),
+                    merge_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let SelectorResolution {
+                    selector: destination,
+                    aliases: mut destination_aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    merge_op.destination.as_ref(),
+                    merge_op.destination_ref.as_ref(),
+                    "destination",
+                )?;
+                let report = apply_merge_sections_operation(
+                    &mut working_blocks,
+                    merge_op,
+                    selector,
+                    destination,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                aliases.append(&mut destination_aliases);
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::Get(get_op) => {
+                let SelectorResolution {
+                    selector,
+                    mut aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    get_op.selector.as_ref(),
+                    get_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let OptionalSelectorResolution {
+                    selector: until_selector,
+                    aliases: mut until_aliases,
+                } = resolve_optional_operation_selector(
+                    &alias_map,
+                    get_op.until.as_ref(),
+                    get_op.until_ref.as_ref(),
+                    "until",
+                )?;
+                let name = get_op.capture.clone();
+                let (content, report) = apply_get_operation(
+                    &working_blocks,
+                    get_op,
+                    selector,
+                    until_selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                aliases.append(&mut until_aliases);
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+                captures.push(Capture {
+                    operation_index,
+                    name,
+                    content,
+                });
+            }
+            Operation::PruneEmptySections(prune_op) => {
+                let OptionalSelectorResolution {
+                    selector: scope_selector,
+                    aliases,
+                } = resolve_optional_operation_selector(
+                    &alias_map,
+                    prune_op.within.as_ref(),
+                    prune_op.within_ref.as_ref(),
+                    "within",
+                )?;
+                let report = apply_prune_empty_sections_operation(
+                    &mut working_blocks,
+                    prune_op,
+                    scope_selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::DedupeHeadings(dedupe_op) => {
+                let OptionalSelectorResolution {
+                    selector: scope_selector,
+                    aliases,
+                } = resolve_optional_operation_selector(
+                    &alias_map,
+                    dedupe_op.within.as_ref(),
+                    dedupe_op.within_ref.as_ref(),
+                    "within",
+                )?;
+                let report = apply_dedupe_headings_operation(
+                    &mut working_blocks,
+                    dedupe_op,
+                    scope_selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::Renumber(renumber_op) => {
+                let OptionalSelectorResolution {
+                    selector: scope_selector,
+                    aliases,
+                } = resolve_optional_operation_selector(
+                    &alias_map,
+                    renumber_op.within.as_ref(),
+                    renumber_op.within_ref.as_ref(),
+                    "within",
+                )?;
+                let report = apply_renumber_operation(
+                    &mut working_blocks,
+                    scope_selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::ImportSection(import_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    import_op.selector.as_ref(),
+                    import_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_import_section_operation(
+                    &mut working_blocks,
+                    import_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::SetAlert(alert_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    alert_op.selector.as_ref(),
+                    alert_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_set_alert_operation(
+                    &mut working_blocks,
+                    alert_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::RemoveAlert(alert_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    alert_op.selector.as_ref(),
+                    alert_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_remove_alert_operation(
+                    &mut working_blocks,
+                    alert_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::SetComment(comment_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    comment_op.selector.as_ref(),
+                    comment_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_set_comment_operation(
+                    &mut working_blocks,
+                    comment_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::RemoveComment(comment_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    comment_op.selector.as_ref(),
+                    comment_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_remove_comment_operation(
+                    &mut working_blocks,
+                    comment_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::ReplaceInnerHtml(html_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    html_op.selector.as_ref(),
+                    html_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_replace_inner_html_operation(
+                    &mut working_blocks,
+                    html_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::Wrap(wrap_op) => {
+                let SelectorResolution {
+                    selector,
+                    mut aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    wrap_op.selector.as_ref(),
+                    wrap_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let OptionalSelectorResolution {
+                    selector: until_selector,
+                    aliases: mut until_aliases,
+                } = resolve_optional_operation_selector(
+                    &alias_map,
+                    wrap_op.until.as_ref(),
+                    wrap_op.until_ref.as_ref(),
+                    "until",
+                )?;
+                let report = apply_wrap_operation(
+                    &mut working_blocks,
+                    wrap_op,
+                    selector,
+                    until_selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                aliases.append(&mut until_aliases);
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::Unwrap(unwrap_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    unwrap_op.selector.as_ref(),
+                    unwrap_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_unwrap_operation(
+                    &mut working_blocks,
+                    unwrap_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::WrapDetails(wrap_op) => {
+                let SelectorResolution {
+                    selector,
+                    mut aliases,
+                } = resolve_operation_selector(
+                    &alias_map,
+                    wrap_op.selector.as_ref(),
+                    wrap_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let OptionalSelectorResolution {
+                    selector: until_selector,
+                    aliases: mut until_aliases,
+                } = resolve_optional_operation_selector(
+                    &alias_map,
+                    wrap_op.until.as_ref(),
+                    wrap_op.until_ref.as_ref(),
+                    "until",
+                )?;
+                let report = apply_wrap_details_operation(
+                    &mut working_blocks,
+                    wrap_op,
+                    selector,
+                    until_selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                aliases.append(&mut until_aliases);
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::UnwrapDetails(unwrap_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    unwrap_op.selector.as_ref(),
+                    unwrap_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_unwrap_details_operation(
+                    &mut working_blocks,
+                    unwrap_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::SetCodeLanguage(language_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    language_op.selector.as_ref(),
+                    language_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_set_code_language_operation(
+                    &mut working_blocks,
+                    language_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::SetCodeAttribute(attribute_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    attribute_op.selector.as_ref(),
+                    attribute_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_set_code_attribute_operation(
+                    &mut working_blocks,
+                    attribute_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::InsertTableRow(row_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    row_op.selector.as_ref(),
+                    row_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_insert_table_row_operation(
+                    &mut working_blocks,
+                    row_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::DeleteTableRow(row_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    row_op.selector.as_ref(),
+                    row_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_delete_table_row_operation(
+                    &mut working_blocks,
+                    row_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::AddTableColumn(column_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    column_op.selector.as_ref(),
+                    column_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_add_table_column_operation(
+                    &mut working_blocks,
+                    column_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::RemoveTableColumn(column_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    column_op.selector.as_ref(),
+                    column_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_remove_table_column_operation(
+                    &mut working_blocks,
+                    column_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::RenameTableColumn(column_op) => {
+                let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                    &alias_map,
+                    column_op.selector.as_ref(),
+                    column_op.selector_ref.as_ref(),
+                    "selector",
+                )?;
+                let report = apply_rename_table_column_operation(
+                    &mut working_blocks,
+                    column_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::UpdateToc(toc_op) => {
+                let OptionalSelectorResolution { selector, aliases } =
+                    resolve_optional_operation_selector(
+                        &alias_map,
+                        toc_op.selector.as_ref(),
+                        toc_op.selector_ref.as_ref(),
+                        "selector",
+                    )?;
+                let report = apply_update_toc_operation(
+                    &mut working_blocks,
+                    toc_op,
+                    selector,
+                    &mut locate_cache,
+                )
+                .map_err(operation_failed)?;
+                register_aliases(&mut alias_map, aliases)?;
+                ambiguity_detected |= !report.candidates.is_empty();
+                record_operation_report(&mut warnings, operation_index, &description, &report);
+                change = report.change;
+            }
+            Operation::Assert(assert_op) => {
+                let aliases = apply_assert_operation(
+                    &working_blocks,
+                    &working_document,
+                    &alias_map,
+                    assert_op,
+                    &mut locate_cache,
+                )?;
+                register_aliases(&mut alias_map, aliases)?;
+            }
+            Operation::SetFrontmatter(set_op) => {
+                apply_set_frontmatter_operation(&mut working_document, set_op)
+                    .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
+                frontmatter_mutated = true;
+            }
+            Operation::DeleteFrontmatter(delete_op) => {
+                apply_delete_frontmatter_operation(&mut working_document, delete_op)
+                    .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
+                frontmatter_mutated = true;
+            }
+            Operation::ReplaceFrontmatter(replace_op) => {
+                apply_replace_frontmatter_operation(&mut working_document, replace_op)
+                    .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
+                frontmatter_mutated = true;
+            }
+        }
+
+        if let Some(change) = change.filter(|change| !change.is_empty()) {
+            locate_cache.invalidate(change.range.clone(), change.removed);
+            blocks_added += change.range.len();
+            blocks_removed += change.removed;
+            affected_blocks.push(AffectedBlocks {
+                operation_index,
+                range: change.range,
+            });
+        }
+        stopwatch.finish_operation(operation_index)?;
+    }
+
+    // Aliases are resolved once the batch succeeded, outside any operation's time limit.
+    locate_cache.set_deadline(Deadline::default());
+    let mut aliases: Vec<ResolvedAlias> = alias_map
+        .iter()
+        .map(|(name, selector)| {
+            ResolvedAlias::resolve(name, selector, &working_blocks, &mut locate_cache)
+        })
+        .collect();
+    aliases.sort_by(|a, b| a.name.cmp(&b.name));
+
+    *doc_blocks = working_blocks;
+    *parsed_document = working_document;
+
+    Ok(ApplyOutcome {
+        frontmatter_mutated,
+        ambiguity_detected,
+        warnings,
+        operation_counts,
+        affected_blocks,
+        blocks_added,
+        blocks_removed,
+        aliases,
+        captures,
+    })
+}
+
+/// Side-effects of a single body operation that are surfaced to callers as warnings.
+#[derive(Debug, Default)]
+struct OperationReport {
+    /// The nodes an ambiguous selector matched, in ranking order (empty when unambiguous).
+    candidates: Vec<String>,
+    empty_content: bool,
+    duplicate_headings: Vec<DuplicateHeadings>,
+    skipped: Option<String>,
+    /// How the operation changed the top-level blocks, if it changed them.
+    change: Option<BlockChange>,
+}
+
+/// Applies `edit` to each block of `range` on its own, returning the sum of its counts and
+/// the change covering the blocks it counted anything in.
+fn edit_each_block(
+    doc_blocks: &mut [Block],
+    range: Range<usize>,
+    deadline: Deadline,
+    mut edit: impl FnMut(&mut [Block]) -> usize,
+) -> Result<(usize, Option<BlockChange>), SpliceError> {
+    let mut total = 0;
+    let mut edited = Vec::new();
+    for index in range {
+        deadline.check()?;
+        let count = edit(std::slice::from_mut(&mut doc_blocks[index]));
+        if count > 0 {
+            total += count;
+            edited.push(index);
+        }
+    }
+    Ok((total, BlockChange::edits(edited)))
+}
+
+/// Fails with [`SpliceError::ConflictingOperations`] when the target of `conflict` no
+/// longer matches in `doc_blocks`, just before its operation runs, and otherwise records a
+/// warning. Targets that cannot be resolved are left for the operation itself to report.
+fn check_conflict(
+    conflict: &Conflict,
+    alias_map: &HashMap<String, Selector>,
+    doc_blocks: &[Block],
+    locate_cache: &mut LocateCache,
+    warnings: &mut Vec<Warning>,
+) -> Result<(), SpliceError> {
+    let Ok(SelectorResolution { selector, .. }) =
+        resolve_selector_tree(alias_map, &conflict.target)
+    else {
+        return Ok(());
+    };
+    match locate_cached(doc_blocks, &selector, locate_cache) {
+        Err(SpliceError::NodeNotFound) => Err(SpliceError::ConflictingOperations(
+            conflict.description.clone(),
+        )),
+        Ok(_) => {
+            warnings.push(Warning::new(
+                WarningKind::PossibleConflict,
+                conflict.operation_index,
+                format!(
+                    "{}, but a node still matched it, so the operation was applied to that node",
+                    conflict.description
+                ),
+            ));
+            Ok(())
+        }
+        Err(_) => Ok(()),
+    }
+}
+
+fn record_operation_report(
+    warnings: &mut Vec<Warning>,
+    operation_index: usize,
+    description: &str,
+    report: &OperationReport,
+) {
+    if !report.candidates.is_empty() {
+        warnings.push(Warning::new(
+            WarningKind::AmbiguousSelector,
+            operation_index,
+            format!(
+                "selector of `{description}` matched multiple nodes ({}); the operation was applied to the first match only",
+                report.candidates.join(", ")
+            ),
+        ));
+    }
+    if report.empty_content {
+        warnings.push(Warning::new(
+            WarningKind::EmptyContent,
+            operation_index,
+            "content parsed to no Markdown blocks",
+        ));
+    }
+    if let Some(reason) = &report.skipped {
+        warnings.push(Warning::new(
+            WarningKind::SkippedOperation,
+            operation_index,
+            reason.clone(),
+        ));
+    }
+    for duplicate in &report.duplicate_headings {
+        warnings.push(Warning::new(
+            WarningKind::DuplicateHeading,
+            operation_index,
+            format!(
+                "heading '{}' appears {} times",
+                duplicate.text,
+                duplicate.indices.len()
+            ),
+        ));
+    }
+}
+
+/// Converts the error of a failed body operation, keeping selector and time limit errors
+/// that callers handle on their own (such as [`SpliceError::TooManyMatches`]) as they are.
+fn operation_failed(err: anyhow::Error) -> SpliceError {
+    match err.downcast::<SpliceError>() {
+        Ok(
+            err @ (SpliceError::TooManyMatches { .. }
+            | SpliceError::NodeNotFound
+            | SpliceError::NodeNotFoundWithNearMisses(_)
+            | SpliceError::TimeLimitExceeded(_)),
+        ) => err,
+        Ok(err) => SpliceError::OperationFailed(err.to_string()),
+        Err(err) => SpliceError::OperationFailed(err.to_string()),
+    }
+}
+
+/// Locates `selector` like [`locate_cached`], also listing the nodes it matched when it is
+/// ambiguous (as `block 2` or `block 4 item 1`, see [`FoundNode::position_description`])
+/// for the warning.
+/// When nothing matches, the error lists the nearest misses.
+fn locate_target<'a>(
+    doc_blocks: &'a [Block],
+    selector: &Selector,
+    locate_cache: &mut LocateCache,
+) -> Result<(FoundNode<'a>, Vec<String>), SpliceError> {
+    let (found_node, is_ambiguous) = locate_cached(doc_blocks, selector, locate_cache)
+        .map_err(|err| explain_no_match(err, doc_blocks, selector))?;
+    if !is_ambiguous {
+        return Ok((found_node, Vec::new()));
+    }
+    let candidates = locate_all_cached(doc_blocks, selector, locate_cache)?
+        .iter()
+        .map(FoundNode::position_description)
+        .collect();
+    Ok((found_node, candidates))
+}
+
+#[allow(dead_code)]
+fn apply_replace_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: ReplaceOperation,
+    selector: Selector,
+    until_selector: Option<Selector>,
+    definition_lists: bool,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let ReplaceOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        content,
+        content_file,
+        until: _,
+        until_ref: _,
+        update_links,
+        cell,
+    } = operation;
+
+    let (found_node, candidates) = locate_target(&*doc_blocks, &selector, locate_cache)?;
+
+    if !candidates.is_empty() {
+        log::warn!(
+            "Warning: Selector matched multiple nodes. Operation was applied to the first match only."
+        );
+    }
+
+    let content = content.map(|content| expand_regex_captures(content, &selector, &found_node));
+    let content_str = resolve_operation_content(content, content_file)?;
+    let new_blocks = parse_content_blocks(&content_str, definition_lists)?;
+    let empty_content = new_blocks.is_empty();
+
+    if let Some(address) = cell {
+        if until_selector.is_some() || update_links {
+            anyhow::bail!("A table cell replacement cannot be combined with until or update_links");
+        }
+        let FoundNode::Block { index, .. } = found_node else {
+            return Err(anyhow!(
+                "A table cell can only be replaced in a table, but the selector matched a {}",
+                found_node.kind_description()
+            ));
+        };
+        *table_cell_mut(&mut doc_blocks[index], address)? = cell_content(new_blocks)?;
+        return Ok(OperationReport {
+            candidates,
+            empty_content,
+            change: Some(BlockChange::edit(index)),
+            ..OperationReport::default()
+        });
+    }
+
+    let new_len = new_blocks.len();
+    let anchors_before = update_links.then(|| heading_anchors(doc_blocks));
+
+    let change = match found_node {
+        FoundNode::Block { index, .. } => {
+            let old_len = if let Some(until_selector) = until_selector.as_ref() {
+                let end_index = compute_range_end(doc_blocks, index, until_selector, locate_cache)?;
+                doc_blocks.splice(index..end_index, new_blocks);
+                end_index - index
+            } else {
+                replace(doc_blocks, index, new_blocks)?;
+                1
+            };
+            let mut change = Some(BlockChange::splice(index, old_len, new_len));
+            if let Some(anchors_before) = anchors_before {
+                let anchors_after = heading_anchors(doc_blocks);
+                let renamed =
+                    spliced_anchors(&anchors_before, &anchors_after, index, old_len, new_len);
+                let all = 0..doc_blocks.len();
+                let (_, rewritten) =
+                    edit_each_block(doc_blocks, all, locate_cache.deadline(), |blocks| {
+                        rewrite_anchor_links(blocks, &renamed)
+                    })?;
+                change = BlockChange::chain(change, rewritten);
+            }
+            change
+        }
+        FoundNode::ContainedBlock {
+            block_index, path, ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            let (blocks, index) = contained_blocks(doc_blocks, block_index, &path)?;
+            replace(blocks, index, new_blocks)?;
+            Some(BlockChange::edit(block_index))
+        }
+        FoundNode::ListItem {
+            block_index,
+            item_index,
+            path,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            let (blocks, list_index) = item_list_blocks(doc_blocks, block_index, &path)?;
+            replace_list_item(blocks, list_index, item_index, new_blocks)?;
+            Some(BlockChange::edit(block_index))
+        }
+        FoundNode::TableRow {
+            block_index,
+            row_index,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            let table = table_at(doc_blocks, block_index)?;
+            table.rows[row_index] =
+                TableRow::Markdown(content_str).to_cells(table.alignments.len())?;
+            Some(BlockChange::edit(block_index))
+        }
+        FoundNode::TableCell {
+            block_index,
+            row_index,
+            column_index,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            let address = CellAddress {
+                row: row_index,
+                column: column_index + 1,
+            };
+            *table_cell_mut(&mut doc_blocks[block_index], address)? = cell_content(new_blocks)?;
+            Some(BlockChange::edit(block_index))
+        }
+        FoundNode::Inline {
+            block_index,
+            position,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            let inlines = inline_content(new_blocks)?;
+            splice_inline_at(doc_blocks, block_index, position, inlines)?;
+            Some(BlockChange::edit(block_index))
+        }
+    };
+
+    Ok(OperationReport {
+        candidates,
+        empty_content,
+        change,
+        ..OperationReport::default()
+    })
+}
+
+fn apply_replace_text_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: ReplaceTextOperation,
+    selector: Selector,
+    until_selector: Option<Selector>,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let ReplaceTextOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        pattern,
+        replacement,
+        section,
+        until: _,
+        until_ref: _,
+    } = operation;
+
+    let pattern = Regex::new(&pattern)
+        .map_err(|err| anyhow!("Invalid replace_text pattern '{pattern}': {err}"))?;
+    let (found_node, candidates) = locate_target(doc_blocks, &selector, locate_cache)?;
+    let block_index = found_block_index(&found_node);
+    let mut change = None;
+
+    let replaced = match found_node {
+        FoundNode::Block { index, .. } => {
+            let range = if let Some(until_selector) = until_selector.as_ref() {
+                index..compute_range_end(doc_blocks, index, until_selector, locate_cache)?
+            } else if section {
+                section_range(doc_blocks, index).ok_or(SpliceError::SectionRequiresHeading)?
+            } else {
+                index..index + 1
+            };
+            let (replaced, edited) =
+                edit_each_block(doc_blocks, range, locate_cache.deadline(), |blocks| {
+                    replace_text(blocks, &pattern, &replacement)
+                })?;
+            change = edited;
+            replaced
+        }
+        FoundNode::ContainedBlock {
+            block_index, path, ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            let (blocks, index) = contained_blocks(doc_blocks, block_index, &path)?;
+            replace_text(&mut blocks[index..index + 1], &pattern, &replacement)
+        }
+        FoundNode::ListItem {
+            block_index,
+            item_index,
+            path,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            let (blocks, list_index) = item_list_blocks(doc_blocks, block_index, &path)?;
+            let Block::List(list) = &mut blocks[list_index] else {
+                anyhow::bail!(
+                    "Internal error: block at index {} is not a list",
+                    list_index
+                );
+            };
+            replace_text(&mut list.items[item_index].blocks, &pattern, &replacement)
+        }
+        FoundNode::TableRow {
+            block_index,
+            row_index,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            table_at(doc_blocks, block_index)?.rows[row_index]
+                .iter_mut()
+                .map(|cell| replace_inline_text(cell, &pattern, &replacement))
+                .sum()
+        }
+        FoundNode::TableCell {
+            block_index,
+            row_index,
+            column_index,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            table_at(doc_blocks, block_index)?.rows[row_index]
+                .get_mut(column_index)
+                .map_or(0, |cell| replace_inline_text(cell, &pattern, &replacement))
+        }
+        FoundNode::Inline {
+            block_index,
+            position,
+            inline,
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            let mut inline = inline.clone();
+            let replaced = match &mut inline {
+                // Code is otherwise left alone, but a selected code span is edited itself.
+                Inline::Code(code) => {
+                    let count = pattern.find_iter(code).count();
+                    *code = pattern.replace_all(code, replacement.as_str()).into_owned();
+                    count
+                }
+                inline => replace_inline_text(std::slice::from_mut(inline), &pattern, &replacement),
+            };
+            if replaced > 0 {
+                splice_inline_at(doc_blocks, block_index, position, vec![inline])?;
+            }
+            replaced
+        }
+    };
+    if change.is_none() && replaced > 0 {
+        change = Some(BlockChange::edit(block_index));
+    }
+
+    Ok(OperationReport {
+        candidates,
+        skipped: (replaced == 0).then(|| format!("pattern '{pattern}' matched no text")),
+        change,
+        ..OperationReport::default()
+    })
+}
+
+#[allow(dead_code)]
+fn apply_insert_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: InsertOperation,
+    selector: Selector,
+    definition_lists: bool,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let InsertOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        content,
+        content_file,
+        position,
+        renumber,
+    } = operation;
+
+    let (found_node, candidates) = locate_target(&*doc_blocks, &selector, locate_cache)?;
+
+    if !candidates.is_empty() {
+        log::warn!(
+            "Warning: Selector matched multiple nodes. Operation was applied to the first match only."
+        );
+    }
+
+    let content = content.map(|content| expand_regex_captures(content, &selector, &found_node));
+    let content_str = resolve_operation_content(content, content_file)?;
+
+    // Child content of a code block is literal code, not Markdown.
+    if let FoundNode::Block {
+        index,
+        block: Block::CodeBlock(_),
+    } = found_node
+    {
+        if matches!(
+            position,
+            InsertPosition::PrependChild | InsertPosition::AppendChild
+        ) {
+            let empty_content = content_str.trim_end_matches(['\r', '\n']).is_empty();
+            if !empty_content {
+                insert_code_lines(doc_blocks, index, &content_str, position)?;
+            }
+            return Ok(OperationReport {
+                candidates,
+                empty_content,
+                change: (!empty_content).then(|| BlockChange::edit(index)),
+                ..OperationReport::default()
+            });
+        }
+    }
+
+    let new_blocks = parse_content_blocks(&content_str, definition_lists)?;
+    let empty_content = new_blocks.is_empty();
+
+    let mut change = match found_node {
+        FoundNode::Block { index, .. } => insert_tracked(doc_blocks, index, new_blocks, position)?,
+        FoundNode::ContainedBlock {
+            block_index, path, ..
+        } => {
+            let (blocks, index) = contained_blocks(doc_blocks, block_index, &path)?;
+            insert(blocks, index, new_blocks, position)?;
+            BlockChange::edit(block_index)
+        }
+        FoundNode::ListItem {
+            block_index,
+            item_index,
+            path,
+            ..
+        } => {
+            let (blocks, list_index) = item_list_blocks(doc_blocks, block_index, &path)?;
+            insert_list_item(blocks, list_index, item_index, new_blocks, position)?;
+            BlockChange::edit(block_index)
+        }
+        found => return Err(nested_node_unsupported("insert", &found)),
+    };
+    if renumber {
+        if let (_, Some(renumbered)) = renumber_ordered_lists(doc_blocks) {
+            change = change.then(renumbered);
+        }
+    }
+
+    Ok(OperationReport {
+        candidates,
+        empty_content,
+        change: Some(change),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_upsert_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: UpsertOperation,
+    selector: Selector,
+    fallback: Option<Selector>,
+    definition_lists: bool,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let UpsertOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        content,
+        content_file,
+        section,
+        fallback: _,
+        fallback_ref: _,
+        position,
+    } = operation;
+
+    let (found_node, candidates) = match locate_target(&*doc_blocks, &selector, locate_cache) {
+        Ok(found) => found,
+        Err(SpliceError::NodeNotFound | SpliceError::NodeNotFoundWithNearMisses(_)) => {
+            // Nothing matched: insert relative to the fallback, or append to the document.
+            let content_str = resolve_operation_content(content, content_file)?;
+            let new_blocks = parse_content_blocks(&content_str, definition_lists)?;
+            let empty_content = new_blocks.is_empty();
+            let Some(fallback) = fallback else {
+                let change = BlockChange::splice(doc_blocks.len(), 0, new_blocks.len());
+                doc_blocks.extend(new_blocks);
+                return Ok(OperationReport {
+                    empty_content,
+                    change: Some(change),
+                    ..OperationReport::default()
+                });
+            };
+            let (found_node, candidates) = locate_target(&*doc_blocks, &fallback, locate_cache)?;
+            let change = match found_node {
+                FoundNode::Block { index, .. } => {
+                    insert_tracked(doc_blocks, index, new_blocks, position)?
+                }
+                FoundNode::ListItem {
+                    block_index,
+                    item_index,
+                    path,
+                    ..
+                } => {
+                    let (blocks, list_index) = item_list_blocks(doc_blocks, block_index, &path)?;
+                    insert_list_item(blocks, list_index, item_index, new_blocks, position)?;
+                    BlockChange::edit(block_index)
+                }
+                found => return Err(nested_node_unsupported("upsert", &found)),
+            };
+            return Ok(OperationReport {
+                candidates,
+                empty_content,
+                change: Some(change),
+                ..OperationReport::default()
+            });
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    if !candidates.is_empty() {
+        log::warn!(
+            "Warning: Selector matched multiple nodes. Operation was applied to the first match only."
+        );
+    }
+
+    let content = content.map(|content| expand_regex_captures(content, &selector, &found_node));
+    let content_str = resolve_operation_content(content, content_file)?;
+    let new_blocks = parse_content_blocks(&content_str, definition_lists)?;
+    let empty_content = new_blocks.is_empty();
+    let new_len = new_blocks.len();
+
+    let change = match found_node {
+        FoundNode::Block { index, .. } if section => {
+            let range =
+                section_range(doc_blocks, index).ok_or(SpliceError::SectionRequiresHeading)?;
+            let removed = range.len();
+            doc_blocks.splice(range, new_blocks);
+            BlockChange::splice(index, removed, new_len)
+        }
+        FoundNode::Block { index, .. } => {
+            replace(doc_blocks, index, new_blocks)?;
+            BlockChange::splice(index, 1, new_len)
+        }
+        FoundNode::ListItem { .. } if section => {
+            return Err(SpliceError::SectionRequiresHeading.into());
+        }
+        FoundNode::ListItem {
+            block_index,
+            item_index,
+            path,
+            ..
+        } => {
+            let (blocks, list_index) = item_list_blocks(doc_blocks, block_index, &path)?;
+            replace_list_item(blocks, list_index, item_index, new_blocks)?;
+            BlockChange::edit(block_index)
+        }
+        found => return Err(nested_node_unsupported("upsert", &found)),
+    };
+
+    Ok(OperationReport {
+        candidates,
+        empty_content,
+        change: Some(change),
+        ..OperationReport::default()
+    })
+}
+
+#[allow(dead_code)]
+fn apply_delete_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: DeleteOperation,
+    selector: Selector,
+    until_selector: Option<Selector>,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let DeleteOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        section,
+        until: _,
+        until_ref: _,
+        renumber,
+    } = operation;
+
+    let (found_node, candidates) = locate_target(&*doc_blocks, &selector, locate_cache)?;
+
+    if !candidates.is_empty() {
+        log::warn!(
+            "Warning: Selector matched multiple nodes. Operation was applied to the first match only."
+        );
+    }
+
+    let len_before = doc_blocks.len();
+    let mut change = match found_node {
+        FoundNode::Block { index, block } => {
+            if let Some(until_selector) = until_selector.as_ref() {
+                let end_index = compute_range_end(doc_blocks, index, until_selector, locate_cache)?;
+                doc_blocks.drain(index..end_index);
+            } else if section {
+                if matches!(block, Block::Heading(_)) {
+                    delete_section(doc_blocks, index)?;
+                } else {
+                    return Err(SpliceError::InvalidSectionDelete.into());
+                }
+            } else {
+                delete(doc_blocks, index)?;
+            }
+            BlockChange::splice(index, len_before - doc_blocks.len(), 0)
+        }
+        FoundNode::ContainedBlock {
+            block_index, path, ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            if section {
+                return Err(SpliceError::InvalidSectionDelete.into());
+            }
+            let (blocks, index) = contained_blocks(doc_blocks, block_index, &path)?;
+            delete(blocks, index)?;
+            BlockChange::edit(block_index)
+        }
+        FoundNode::ListItem {
+            block_index,
+            item_index,
+            path,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            if section {
+                return Err(SpliceError::InvalidSectionDelete.into());
+            }
+            let (blocks, list_index) = item_list_blocks(doc_blocks, block_index, &path)?;
+            if delete_list_item(blocks, list_index, item_index)? {
+                blocks.remove(list_index);
+            }
+            // Removing an emptied top-level list shortens the document itself.
+            BlockChange::splice(block_index, 1, 1 + doc_blocks.len() - len_before)
+        }
+        FoundNode::TableRow {
+            block_index,
+            row_index,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            if section {
+                return Err(SpliceError::InvalidSectionDelete.into());
+            }
+            table_at(doc_blocks, block_index)?.rows.remove(row_index);
+            BlockChange::edit(block_index)
+        }
+        // A cell cannot leave the grid of its table, so deleting it empties it.
+        FoundNode::TableCell {
+            block_index,
+            row_index,
+            column_index,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            if section {
+                return Err(SpliceError::InvalidSectionDelete.into());
+            }
+            let address = CellAddress {
+                row: row_index,
+                column: column_index + 1,
+            };
+            table_cell_mut(&mut doc_blocks[block_index], address)?.clear();
+            BlockChange::edit(block_index)
+        }
+        FoundNode::Inline {
+            block_index,
+            position,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            if section {
+                return Err(SpliceError::InvalidSectionDelete.into());
+            }
+            splice_inline_at(doc_blocks, block_index, position, Vec::new())?;
+            BlockChange::edit(block_index)
+        }
+    };
+    if renumber {
+        if let (_, Some(renumbered)) = renumber_ordered_lists(doc_blocks) {
+            change = change.then(renumbered);
+        }
+    }
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(change),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_move_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: MoveOperation,
+    selector: Selector,
+    destination: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let MoveOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        section,
+        destination: _,
+        destination_ref: _,
+        position,
+    } = operation;
+
+    // Both nodes are located before anything moves, so ordinals count the original nodes.
+    let (found_node, mut candidates) = locate_target(&*doc_blocks, &selector, locate_cache)?;
+    let (source, source_item) = match found_node {
+        FoundNode::Block { index, .. } if section => (
+            section_range(doc_blocks, index).ok_or(SpliceError::SectionRequiresHeading)?,
+            None,
+        ),
+        FoundNode::Block { index, .. } => (index..index + 1, None),
+        found if is_nested_list_item(&found) => {
+            return Err(nested_node_unsupported("move", &found));
+        }
+        FoundNode::ListItem { .. } if section => {
+            return Err(SpliceError::SectionRequiresHeading.into());
+        }
+        // Moving the only item of a list moves the whole list.
+        FoundNode::ListItem {
+            block_index,
+            item_index,
+            ..
+        } => match &doc_blocks[block_index] {
+            Block::List(list) if list.items.len() == 1 => (block_index..block_index + 1, None),
+            _ => (block_index..block_index + 1, Some(item_index)),
+        },
+        found => return Err(nested_node_unsupported("move", &found)),
+    };
+
+    let (found_node, destination_candidates) =
+        locate_target(&*doc_blocks, &destination, locate_cache)?;
+    candidates.extend(destination_candidates);
+    let (mut destination_index, mut destination_item) = match found_node {
+        FoundNode::Block { index, .. } => (index, None),
+        found if is_nested_list_item(&found) => {
+            return Err(nested_node_unsupported("move", &found));
+        }
+        FoundNode::ListItem {
+            block_index,
+            item_index,
+            ..
+        } => (block_index, Some(item_index)),
+        found => return Err(nested_node_unsupported("move", &found)),
+    };
+
+    let inside_source = match source_item {
+        None => source.contains(&destination_index),
+        Some(item_index) => {
+            destination_index == source.start && destination_item == Some(item_index)
+        }
+    };
+    if inside_source {
+        return Err(SpliceError::InvalidMoveDestination.into());
+    }
+
+    if !candidates.is_empty() {
+        log::warn!(
+            "Warning: Selector matched multiple nodes. Operation was applied to the first match only."
+        );
+    }
+
+    let (moved_blocks, removal) = match source_item {
+        None => {
+            let moved_blocks: Vec<Block> = doc_blocks.drain(source.clone()).collect();
+            if destination_index >= source.end {
+                destination_index -= source.len();
+            }
+            (
+                moved_blocks,
+                BlockChange::splice(source.start, source.len(), 0),
+            )
+        }
+        Some(item_index) => {
+            let Some(Block::List(list)) = doc_blocks.get_mut(source.start) else {
+                anyhow::bail!(
+                    "Internal error: block at index {} is not a list",
+                    source.start
+                );
+            };
+            let kind = list.kind.clone();
+            let item = list.items.remove(item_index);
+            // Later items of the same list shift up by one.
+            if let Some(destination_item) = destination_item.as_mut() {
+                if destination_index == source.start && *destination_item > item_index {
+                    *destination_item -= 1;
+                }
+            }
+            (
+                vec![single_item_list(kind, item)],
+                BlockChange::edit(source.start),
+            )
+        }
+    };
+
+    let insertion = match destination_item {
+        None => insert_tracked(doc_blocks, destination_index, moved_blocks, position)?,
+        Some(item_index) => {
+            insert_list_item(
+                doc_blocks,
+                destination_index,
+                item_index,
+                moved_blocks,
+                position,
+            )?;
+            BlockChange::edit(destination_index)
+        }
+    };
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(removal.then(insertion)),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_swap_operation(
+    doc_blocks: &mut [Block],
+    operation: SwapOperation,
+    selector: Selector,
+    other: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let SwapOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        section,
+        other: _,
+        other_ref: _,
+    } = operation;
+
+    // Both nodes are located before anything moves, so ordinals count the original nodes.
+    let (found_node, mut candidates) = locate_target(doc_blocks, &selector, locate_cache)?;
+    let first = swap_target(doc_blocks, found_node, section)?;
+    let (found_node, other_candidates) = locate_target(doc_blocks, &other, locate_cache)?;
+    candidates.extend(other_candidates);
+    let second = swap_target(doc_blocks, found_node, section)?;
+
+    if !candidates.is_empty() {
+        log::warn!(
+            "Warning: Selector matched multiple nodes. Operation was applied to the first match only."
+        );
+    }
+
+    let change = match (first, second) {
+        ((first, None), (second, None)) => {
+            let (a, b) = if first.start <= second.start {
+                (first, second)
+            } else {
+                (second, first)
+            };
+            if b.start < a.end {
+                return Err(SpliceError::OverlappingSwap.into());
+            }
+            // [a][between][b] becomes [b][between][a].
+            let between = b.start - a.end;
+            let region = &mut doc_blocks[a.start..b.end];
+            region.rotate_left(a.len());
+            region[..between + b.len()].rotate_right(b.len());
+            BlockChange::splice(a.start, b.end - a.start, b.end - a.start)
+        }
+        ((first, Some(first_item)), (second, Some(second_item))) => {
+            let (a, b) = if (first.start, first_item) <= (second.start, second_item) {
+                ((first.start, first_item), (second.start, second_item))
+            } else {
+                ((second.start, second_item), (first.start, first_item))
+            };
+            if a == b {
+                return Err(SpliceError::OverlappingSwap.into());
+            }
+            if a.0 == b.0 {
+                let Block::List(list) = &mut doc_blocks[a.0] else {
+                    anyhow::bail!("Internal error: block at index {} is not a list", a.0);
+                };
+                list.items.swap(a.1, b.1);
+                BlockChange::edit(a.0)
+            } else {
+                let (head, tail) = doc_blocks.split_at_mut(b.0);
+                let (Block::List(first_list), Block::List(second_list)) =
+                    (&mut head[a.0], &mut tail[0])
+                else {
+                    anyhow::bail!(
+                        "Internal error: blocks at indices {} and {} are not lists",
+                        a.0,
+                        b.0
+                    );
+                };
+                std::mem::swap(&mut first_list.items[a.1], &mut second_list.items[b.1]);
+                BlockChange::splice(a.0, b.0 - a.0 + 1, b.0 - a.0 + 1)
+            }
+        }
+        ((blocks, None), (list, Some(_))) | ((list, Some(_)), (blocks, None)) => {
+            if blocks.contains(&list.start) {
+                return Err(SpliceError::OverlappingSwap.into());
+            }
+            anyhow::bail!("A list item can only be swapped with another list item");
+        }
+    };
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(change),
+        ..OperationReport::default()
+    })
+}
+
+/// Returns the blocks a swap exchanges for `found_node` (its whole section with `section`),
+/// with the index of the list item when it matched one.
+fn swap_target(
+    doc_blocks: &[Block],
+    found_node: FoundNode<'_>,
+    section: bool,
+) -> Result<(Range<usize>, Option<usize>), SpliceError> {
+    match found_node {
+        FoundNode::Block { index, .. } if section => Ok((
+            section_range(doc_blocks, index).ok_or(SpliceError::SectionRequiresHeading)?,
+            None,
+        )),
+        FoundNode::Block { index, .. } => Ok((index..index + 1, None)),
+        found if is_nested_list_item(&found) => Err(SpliceError::OperationFailed(
+            nested_node_unsupported("swap", &found).to_string(),
+        )),
+        FoundNode::ListItem { .. } if section => Err(SpliceError::SectionRequiresHeading),
+        FoundNode::ListItem {
+            block_index,
+            item_index,
+            ..
+        } => Ok((block_index..block_index + 1, Some(item_index))),
+        found => Err(SpliceError::OperationFailed(
+            nested_node_unsupported("swap", &found).to_string(),
+        )),
+    }
+}
+
+fn apply_copy_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: CopyOperation,
+    selector: Selector,
+    until_selector: Option<Selector>,
+    destination: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let CopyOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        section,
+        until: _,
+        until_ref: _,
+        destination: _,
+        destination_ref: _,
+        position,
+    } = operation;
+
+    let (found_node, mut candidates) = locate_target(&*doc_blocks, &selector, locate_cache)?;
+    let copied_blocks = match found_node {
+        FoundNode::Block { index, .. } => {
+            let range = if let Some(until_selector) = until_selector.as_ref() {
+                index..compute_range_end(doc_blocks, index, until_selector, locate_cache)?
+            } else if section {
+                section_range(doc_blocks, index).ok_or(SpliceError::SectionRequiresHeading)?
+            } else {
+                index..index + 1
+            };
+            doc_blocks[range].to_vec()
+        }
+        FoundNode::ListItem {
+            block_index,
+            path,
+            item,
+            ..
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            if section {
+                return Err(SpliceError::SectionRequiresHeading.into());
+            }
+            let Some(list) = item_list(doc_blocks, block_index, &path) else {
+                anyhow::bail!(
+                    "Internal error: block at index {} is not a list",
+                    block_index
+                );
+            };
+            vec![single_item_list(list.kind.clone(), item.clone())]
+        }
+        found => return Err(nested_node_unsupported("copy", &found)),
+    };
+
+    let (found_node, destination_candidates) =
+        locate_target(&*doc_blocks, &destination, locate_cache)?;
+    candidates.extend(destination_candidates);
+
+    if !candidates.is_empty() {
+        log::warn!(
+            "Warning: Selector matched multiple nodes. Operation was applied to the first match only."
+        );
+    }
+
+    let change = match found_node {
+        FoundNode::Block { index, .. } => {
+            insert_tracked(doc_blocks, index, copied_blocks, position)?
+        }
+        FoundNode::ListItem {
+            block_index,
+            item_index,
+            path,
+            ..
+        } => {
+            let (blocks, list_index) = item_list_blocks(doc_blocks, block_index, &path)?;
+            insert_list_item(blocks, list_index, item_index, copied_blocks, position)?;
+            BlockChange::edit(block_index)
+        }
+        found => return Err(nested_node_unsupported("copy", &found)),
+    };
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(change),
+        ..OperationReport::default()
+    })
+}
+
+/// Wraps a list item taken out of its list into a list of the same kind.
+fn single_item_list(kind: ListKind, item: ListItem) -> Block {
+    Block::List(List {
+        kind,
+        items: vec![item],
+    })
+}
+
+fn apply_rename_heading_operation(
+    doc_blocks: &mut [Block],
+    operation: RenameHeadingOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let RenameHeadingOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        text,
+        id,
+        update_links,
+    } = operation;
+
+    let (index, candidates) = match locate_target(doc_blocks, &selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "rename_heading requires a heading, but the selector matched a {}",
+                found.kind_description()
+            ));
+        }
+    };
+    let anchors_before = update_links.then(|| heading_anchors(doc_blocks));
+
+    let heading = match &mut doc_blocks[index] {
+        Block::Heading(heading) => heading,
+        other => {
+            return Err(anyhow!(
+                "rename_heading requires a heading, but the selector matched a '{}'",
+                block_type_name(other)
+            ));
+        }
+    };
+    let id = id.or_else(|| explicit_id(heading).map(str::to_string));
+    heading.content = parse_heading_text(&text)?;
+    if let Some(id) = id.filter(|_| explicit_id(heading).is_none()) {
+        append_explicit_id(heading, &id);
+    }
+
+    let mut change = Some(BlockChange::edit(index));
+    if let Some(anchors_before) = anchors_before {
+        let renamed = changed_anchors(&anchors_before, &heading_anchors(doc_blocks));
+        let all = 0..doc_blocks.len();
+        let (_, rewritten) = edit_each_block(doc_blocks, all, locate_cache.deadline(), |blocks| {
+            rewrite_anchor_links(blocks, &renamed)
+        })?;
+        change = BlockChange::chain(change, rewritten);
+    }
+
+    Ok(OperationReport {
+        candidates,
+        change,
+        ..OperationReport::default()
+    })
+}
+
+fn apply_shift_heading_operation(
+    doc_blocks: &mut [Block],
+    operation: ShiftHeadingOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let ShiftHeadingOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        delta,
+        include_section,
+    } = operation;
+
+    let (index, candidates) = match locate_target(doc_blocks, &selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "shift_heading requires a heading, but the selector matched a {}",
+                found.kind_description()
+            ));
+        }
+    };
+    let range = match section_range(doc_blocks, index) {
+        Some(range) if include_section => range,
+        Some(_) => index..index + 1,
+        None => {
+            return Err(anyhow!(
+                "shift_heading requires a heading, but the selector matched a '{}'",
+                block_type_name(&doc_blocks[index])
+            ));
+        }
+    };
+
+    // Check every heading before shifting any, so that a failure leaves the blocks as they were.
+    let mut shifted: Vec<(usize, u8)> = Vec::new();
+    for block_index in range {
+        let Some(level) = get_heading_level(&doc_blocks[block_index]) else {
+            continue;
+        };
+        let new_level = i16::from(level) + i16::from(delta);
+        if !(1..=6).contains(&new_level) {
+            return Err(anyhow!(
+                "shift_heading cannot shift an h{level} heading by {delta:+}: heading levels range from h1 to h6"
+            ));
+        }
+        shifted.push((block_index, new_level as u8));
+    }
+    for &(block_index, level) in &shifted {
+        set_heading_level(&mut doc_blocks[block_index], level);
+    }
+
+    Ok(OperationReport {
+        candidates,
+        change: BlockChange::edits(shifted.into_iter().map(|(block_index, _)| block_index)),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_merge_sections_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: MergeSectionsOperation,
+    selector: Selector,
+    destination: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let MergeSectionsOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        destination: _,
+        destination_ref: _,
+        adjust_levels,
+    } = operation;
+
+    // Both headings are located before anything moves, so ordinals count the original nodes.
+    let (source_index, mut candidates) = match locate_target(doc_blocks, &selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "merge_sections requires a heading, but the selector matched a {}",
+                found.kind_description()
+            ));
+        }
+    };
+    let (target_index, destination_candidates) =
+        match locate_target(doc_blocks, &destination, locate_cache)? {
+            (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+            (found, _) => {
+                return Err(anyhow!(
+                    "merge_sections requires a heading, but the destination matched a {}",
+                    found.kind_description()
+                ));
+            }
+        };
+    candidates.extend(destination_candidates);
+
+    // Only the blocks from the first heading to the end of the later section move, and the
+    // source heading is dropped.
+    let section_end = |index| section_range(doc_blocks, index).map_or(index + 1, |range| range.end);
+    let start = source_index.min(target_index);
+    let end = section_end(source_index).max(section_end(target_index));
+    merge_sections(doc_blocks, source_index, target_index, adjust_levels)?;
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::splice(start, end - start, end - start - 1)),
+        ..OperationReport::default()
+    })
+}
+
+/// Parses `text` as the inline content of a heading.
+fn parse_heading_text(text: &str) -> anyhow::Result<Vec<Inline>> {
+    let document = parse_markdown(MarkdownParserState::default(), &format!("# {text}"))
+        .map_err(|e| anyhow!("Failed to parse heading text: {}", e))?;
+    match <[Block; 1]>::try_from(document.blocks) {
+        Ok([Block::Heading(heading)]) => Ok(heading.content),
+        _ => Err(anyhow!(
+            "Heading text must be a single line of inline Markdown, got {text:?}"
+        )),
+    }
+}
+
+fn apply_get_operation(
+    doc_blocks: &[Block],
+    operation: GetOperation,
+    selector: Selector,
+    until_selector: Option<Selector>,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<(String, OperationReport)> {
+    let GetOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        section,
+        until: _,
+        until_ref: _,
+        capture: _,
+        cell,
+    } = operation;
+
+    let (found_node, candidates) = locate_target(doc_blocks, &selector, locate_cache)?;
+
+    if let Some(address) = cell {
+        if until_selector.is_some() || section {
+            anyhow::bail!("A table cell cannot be read together with until or section");
+        }
+        let FoundNode::Block { block, .. } = found_node else {
+            return Err(anyhow!(
+                "A table cell can only be read from a table, but the selector matched a {}",
+                found_node.kind_description()
+            ));
+        };
+        let mut rendered = render_cell(table_cell(block, address)?);
+        if !rendered.is_empty() {
+            rendered.push('\n');
+        }
+        return Ok((
+            rendered,
+            OperationReport {
+                candidates,
+                ..OperationReport::default()
+            },
+        ));
+    }
+
+    let blocks = match found_node {
+        FoundNode::Block { index, block } => {
+            if let Some(until_selector) = until_selector.as_ref() {
+                let end_index = compute_range_end(doc_blocks, index, until_selector, locate_cache)?;
+                doc_blocks[index..end_index].to_vec()
+            } else if section {
+                let range =
+                    section_range(doc_blocks, index).ok_or(SpliceError::SectionRequiresHeading)?;
+                doc_blocks[range].to_vec()
+            } else {
+                vec![block.clone()]
+            }
+        }
+        FoundNode::ContainedBlock { block, .. } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            if section {
+                return Err(SpliceError::SectionRequiresHeading.into());
+            }
+            vec![block.clone()]
+        }
+        FoundNode::ListItem {
+            block_index,
+            item_index,
+            path,
+            item,
+        } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            let Some(list) = item_list(doc_blocks, block_index, &path) else {
+                return Err(anyhow!(
+                    "Internal error: block at index {} is not a list",
+                    block_index
+                ));
+            };
+            let mut single_list = list.clone();
+            single_list.items = vec![item.clone()];
+            // Keep the item's own number when it is extracted from an ordered list.
+            if let ListKind::Ordered(options) = &mut single_list.kind {
+                options.start += item_index as u64;
+            }
+            vec![Block::List(single_list)]
+        }
+        FoundNode::TableRow { .. } | FoundNode::TableCell { .. } | FoundNode::Inline { .. } => {
+            if until_selector.is_some() {
+                return Err(SpliceError::RangeRequiresBlock.into());
+            }
+            if section {
+                return Err(SpliceError::SectionRequiresHeading.into());
+            }
+            return Ok((
+                render_table_node(doc_blocks, &found_node)
+                    .or_else(|| render_inline_node(&found_node))
+                    .unwrap_or_default(),
+                OperationReport {
+                    candidates,
+                    ..OperationReport::default()
+                },
+            ));
+        }
+    };
+
+    Ok((
+        render_fragment(&blocks, default_printer_config()),
+        OperationReport {
+            candidates,
+            ..OperationReport::default()
+        },
+    ))
+}
+
+/// Replaces `{{name}}` in the inline content of `insert` and `replace` operations with the
+/// content most recently captured under that name. Placeholders naming no capture are left
+/// as written.
+fn interpolate_captures(operation: &mut Operation, captures: &[Capture]) {
+    let content = match operation {
+        Operation::Insert(op) => op.content.as_mut(),
+        Operation::Replace(op) => op.content.as_mut(),
+        Operation::Upsert(op) => op.content.as_mut(),
+        _ => None,
+    };
+    let Some(content) = content.filter(|content| content.contains("{{")) else {
+        return;
+    };
+    let variables: HashMap<&str, &str> = captures
+        .iter()
+        .filter_map(|capture| {
+            let name = capture.name.as_deref()?;
+            Some((name, capture.content.trim_end_matches('\n')))
+        })
+        .collect();
+    let placeholder = Regex::new(r"\{\{\s*([A-Za-z0-9_.-]+)\s*\}\}").expect("valid regex");
+    let interpolated = placeholder.replace_all(content, |caps: &regex::Captures| {
+        match variables.get(&caps[1]) {
+            Some(value) => value.to_string(),
+            None => caps[0].to_string(),
+        }
+    });
+    if let std::borrow::Cow::Owned(interpolated) = interpolated {
+        *content = interpolated;
+    }
+}
+
+fn apply_prune_empty_sections_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: PruneEmptySectionsOperation,
+    scope_selector: Option<Selector>,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let PruneEmptySectionsOperation {
+        comment: _,
+        min_level,
+        max_level,
+        within: _,
+        within_ref: _,
+    } = operation;
+
+    let min_level = min_level.unwrap_or(1);
+    let max_level = max_level.unwrap_or(6);
+    if !(1..=6).contains(&min_level) || !(1..=6).contains(&max_level) || min_level > max_level {
+        return Err(anyhow!(
+            "Invalid heading levels for prune_empty_sections: expected 1 <= min_level <= max_level <= 6"
+        ));
+    }
+
+    let (scope, candidates) = resolve_section_scope(doc_blocks, scope_selector, locate_cache)?;
+
+    let (_, change) = prune_empty_sections(doc_blocks, scope, min_level..=max_level);
+
+    Ok(OperationReport {
+        candidates,
+        change,
+        ..OperationReport::default()
+    })
+}
+
+fn apply_dedupe_headings_operation(
+    doc_blocks: &mut [Block],
+    operation: DedupeHeadingsOperation,
+    scope_selector: Option<Selector>,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let DedupeHeadingsOperation {
+        comment: _,
+        within: _,
+        within_ref: _,
+        report_only,
+    } = operation;
+
+    let (scope, candidates) = resolve_section_scope(doc_blocks, scope_selector, locate_cache)?;
+
+    let (duplicate_headings, change) = if report_only {
+        (find_duplicate_headings(doc_blocks, scope), None)
+    } else {
+        (Vec::new(), dedupe_headings(doc_blocks, scope).1)
+    };
+
+    Ok(OperationReport {
+        candidates,
+        duplicate_headings,
+        change,
+        ..OperationReport::default()
+    })
+}
+
+fn apply_renumber_operation(
+    doc_blocks: &mut [Block],
+    scope_selector: Option<Selector>,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let (scope, candidates) = resolve_section_scope(doc_blocks, scope_selector, locate_cache)?;
+
+    let (_, change) = renumber_ordered_lists(&mut doc_blocks[scope.clone()]);
+
+    Ok(OperationReport {
+        candidates,
+        change: change.map(|change| {
+            BlockChange::splice(
+                scope.start + change.range.start,
+                change.removed,
+                change.range.len(),
+            )
+        }),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_import_section_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: ImportSectionOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let ImportSectionOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        source_file,
+        source_selector,
+        position,
+    } = operation;
+
+    let source_content = fs::read_to_string(&source_file)
+        .with_context(|| format!("Failed to read source file: {}", source_file.display()))?;
+    let source = MarkdownDocument::from_str(&source_content)?;
+    // Aliases name nodes of the current document, so the source selector cannot use them.
+    let source_selector = resolve_selector_tree(&HashMap::new(), &source_selector)?.selector;
+    let section = source.section_of(&source_selector).with_context(|| {
+        format!(
+            "Failed to find the source section in {}",
+            source_file.display()
+        )
+    })?;
+    let new_blocks = source.blocks()[section].to_vec();
+
+    let (found_node, candidates) = locate_target(&*doc_blocks, &selector, locate_cache)?;
+
+    let change = match (found_node, position) {
+        (FoundNode::Block { index, .. }, None) => {
+            let section =
+                section_range(doc_blocks, index).ok_or(SpliceError::SectionRequiresHeading)?;
+            let change = BlockChange::splice(index, section.len(), new_blocks.len());
+            doc_blocks.splice(section, new_blocks);
+            change
+        }
+        (FoundNode::ListItem { .. }, None) => {
+            return Err(SpliceError::SectionRequiresHeading.into());
+        }
+        (FoundNode::Block { index, .. }, Some(position)) => {
+            insert_tracked(doc_blocks, index, new_blocks, position)?
+        }
+        (
+            FoundNode::ListItem {
+                block_index,
+                item_index,
+                path,
+                ..
+            },
+            Some(position),
+        ) => {
+            let (blocks, list_index) = item_list_blocks(doc_blocks, block_index, &path)?;
+            insert_list_item(blocks, list_index, item_index, new_blocks, position)?;
+            BlockChange::edit(block_index)
+        }
+        (found, _) => return Err(nested_node_unsupported("import_section", &found)),
+    };
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(change),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_set_alert_operation(
+    doc_blocks: &mut [Block],
+    operation: SetAlertOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let (index, candidates) = locate_alert_target(doc_blocks, &selector, locate_cache)?;
+    let alert_type = github_alert_type(operation.alert_type);
+
+    let block = &mut doc_blocks[index];
+    match block {
+        Block::GitHubAlert(alert) => alert.alert_type = alert_type,
+        Block::BlockQuote(blocks) => {
+            let blocks = std::mem::take(blocks);
+            *block = Block::GitHubAlert(GitHubAlert { alert_type, blocks });
+        }
+        other => {
+            return Err(anyhow!(
+                "set_alert requires a blockquote or an alert, but the selector matched a '{}'",
+                block_type_name(other)
+            ));
+        }
+    }
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::edit(index)),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_remove_alert_operation(
+    doc_blocks: &mut [Block],
+    _operation: RemoveAlertOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let (index, candidates) = locate_alert_target(doc_blocks, &selector, locate_cache)?;
+
+    let block = &mut doc_blocks[index];
+    let Block::GitHubAlert(alert) = block else {
+        return Err(anyhow!(
+            "remove_alert requires an alert, but the selector matched a '{}'",
+            block_type_name(block)
+        ));
+    };
+    *block = Block::BlockQuote(std::mem::take(&mut alert.blocks));
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::edit(index)),
+        ..OperationReport::default()
+    })
+}
+
+/// Locates the top-level block targeted by an alert operation, which cannot be a list item.
+fn locate_alert_target(
+    doc_blocks: &[Block],
+    selector: &Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<(usize, Vec<String>)> {
+    match locate_target(doc_blocks, selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => Ok((index, candidates)),
+        (found, _) => Err(anyhow!(
+            "Alert operations require a blockquote or an alert, but the selector matched a {}",
+            found.kind_description()
+        )),
+    }
+}
+
+fn apply_set_comment_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: SetCommentOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let SetCommentOperation {
+        text,
+        position,
+        marker,
+        ..
+    } = operation;
+    let new_comment = comment_block(&text)?;
+    let (index, candidates) = locate_annotated_block(doc_blocks, &selector, locate_cache)?;
+
+    let change = match adjacent_comment(doc_blocks, index, position, marker.as_deref()) {
+        Some(existing) => {
+            doc_blocks[existing] = new_comment;
+            BlockChange::edit(existing)
+        }
+        None => {
+            let at = match position {
+                CommentPosition::Before => index,
+                CommentPosition::After => index + 1,
+            };
+            doc_blocks.insert(at, new_comment);
+            BlockChange::splice(at, 0, 1)
+        }
+    };
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(change),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_remove_comment_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: RemoveCommentOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let RemoveCommentOperation {
+        position, marker, ..
+    } = operation;
+    let (index, candidates) = locate_annotated_block(doc_blocks, &selector, locate_cache)?;
+
+    let (skipped, change) = match adjacent_comment(doc_blocks, index, position, marker.as_deref()) {
+        Some(existing) => {
+            doc_blocks.remove(existing);
+            (None, Some(BlockChange::splice(existing, 1, 0)))
+        }
+        None => (
+            Some("no matching comment next to the selected block".to_string()),
+            None,
+        ),
+    };
+
+    Ok(OperationReport {
+        candidates,
+        skipped,
+        change,
+        ..OperationReport::default()
+    })
+}
+
+/// Locates the top-level block that a comment operation annotates.
+fn locate_annotated_block(
+    doc_blocks: &[Block],
+    selector: &Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<(usize, Vec<String>)> {
+    match locate_target(doc_blocks, selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => Ok((index, candidates)),
+        (found, _) => Err(anyhow!(
+            "Comments can only annotate top-level blocks, but the selector matched a {}",
+            found.kind_description()
+        )),
+    }
+}
+
+/// Returns the index of the comment block directly on the `position` side of the block at
+/// `index`, provided its text contains `marker` (when given).
+fn adjacent_comment(
+    doc_blocks: &[Block],
+    index: usize,
+    position: CommentPosition,
+    marker: Option<&str>,
+) -> Option<usize> {
+    let neighbour = match position {
+        CommentPosition::Before => index.checked_sub(1)?,
+        CommentPosition::After => index + 1,
+    };
+    let Some(Block::HtmlBlock(html)) = doc_blocks.get(neighbour) else {
+        return None;
+    };
+    let text = comment_text(html)?;
+    marker
+        .is_none_or(|marker| text.contains(marker))
+        .then_some(neighbour)
+}
+
+fn apply_replace_inner_html_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: ReplaceInnerHtmlOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let ReplaceInnerHtmlOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        content,
+        content_file,
+    } = operation;
+
+    let (index, candidates) = match locate_target(doc_blocks, &selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "replace_inner_html requires an HTML block, but the selector matched a {}",
+                found.kind_description()
+            ));
+        }
+    };
+    let span = element_span(doc_blocks, index)?;
+    let inner = resolve_operation_content(content, content_file)?;
+
+    let (Block::HtmlBlock(first), Block::HtmlBlock(last)) = (
+        &doc_blocks[span.blocks.start],
+        &doc_blocks[span.blocks.end - 1],
+    ) else {
+        anyhow::bail!("Internal error: an HTML element must start and end in HTML blocks");
+    };
+    let opening = &first[..span.inner_start];
+    let closing = &last[span.inner_end..];
+    let source = if span.blocks.len() == 1 {
+        // Keep the whitespace around the old inner HTML, so `<b>x</b>` stays on one line.
+        let old = &first[span.inner_start..span.inner_end];
+        let leading = &old[..old.len() - old.trim_start().len()];
+        let trailing = &old[old.trim_end().len()..];
+        format!("{opening}{leading}{}{trailing}{closing}", inner.trim())
+    } else {
+        // The element wraps Markdown blocks, which need blank lines around them to render.
+        format!(
+            "{}\n\n{}\n\n{}",
+            opening.trim_end(),
+            inner.trim(),
+            closing.trim_start()
+        )
+    };
+    let new_blocks = parse_html_fragment(&source)?;
+    let change = BlockChange::splice(span.blocks.start, span.blocks.len(), new_blocks.len());
+    doc_blocks.splice(span.blocks, new_blocks);
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(change),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_wrap_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: WrapOperation,
+    selector: Selector,
+    until_selector: Option<Selector>,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let WrapOperation {
+        selector: _,
+        selector_ref: _,
+        comment: _,
+        alert_type,
+        section,
+        until: _,
+        until_ref: _,
+    } = operation;
+
+    let (index, candidates) = match locate_target(doc_blocks, &selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "wrap requires a block, but the selector matched a {}",
+                found.kind_description()
+            ));
+        }
+    };
+    let range = if let Some(until_selector) = until_selector.as_ref() {
+        index..compute_range_end(doc_blocks, index, until_selector, locate_cache)?
+    } else if section {
+        section_range(doc_blocks, index).ok_or(SpliceError::SectionRequiresHeading)?
+    } else {
+        index..index + 1
+    };
+
+    let change = BlockChange::splice(range.start, range.len(), 1);
+    wrap(doc_blocks, range, alert_type.map(github_alert_type))?;
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(change),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_unwrap_operation(
+    doc_blocks: &mut Vec<Block>,
+    _operation: UnwrapOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let (index, candidates) = match locate_target(doc_blocks, &selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "unwrap requires a blockquote or an alert, but the selector matched a {}",
+                found.kind_description()
+            ));
         }
-    }
+    };
 
-    *doc_blocks = working_blocks;
-    *parsed_document = working_document;
+    let len_before = doc_blocks.len();
+    unwrap(doc_blocks, index)?;
 
-    Ok(ApplyOutcome {
-        frontmatter_mutated,
-        ambiguity_detected,
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::splice(
+            index,
+            1,
+            doc_blocks.len() + 1 - len_before,
+        )),
+        ..OperationReport::default()
     })
 }
 
-#[allow(dead_code)]
-fn apply_replace_operation(
+fn apply_wrap_details_operation(
     doc_blocks: &mut Vec<Block>,
-    operation: ReplaceOperation,
+    operation: WrapDetailsOperation,
     selector: Selector,
     until_selector: Option<Selector>,
-) -> anyhow::Result<bool> {
-    let ReplaceOperation {
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let WrapDetailsOperation {
         selector: _,
         selector_ref: _,
         comment: _,
-        content,
-        content_file,
+        summary,
+        open,
+        section,
         until: _,
         until_ref: _,
     } = operation;
 
-    let (found_node, is_ambiguous) = locate(&*doc_blocks, &selector)?;
+    let (index, candidates) = match locate_target(doc_blocks, &selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "wrap_details requires a block, but the selector matched a {}",
+                found.kind_description()
+            ));
+        }
+    };
+    let range = if let Some(until_selector) = until_selector.as_ref() {
+        index..compute_range_end(doc_blocks, index, until_selector, locate_cache)?
+    } else if section {
+        section_range(doc_blocks, index).ok_or(SpliceError::SectionRequiresHeading)?
+    } else {
+        index..index + 1
+    };
 
-    if is_ambiguous {
-        log::warn!(
-            "Warning: Selector matched multiple nodes. Operation was applied to the first match only."
-        );
+    let opening = if open { "<details open>" } else { "<details>" };
+    doc_blocks.insert(range.end, Block::HtmlBlock("</details>".to_string()));
+    doc_blocks.insert(
+        range.start,
+        Block::HtmlBlock(format!("{opening}\n<summary>{}</summary>", summary.trim())),
+    );
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::splice(
+            range.start,
+            range.len(),
+            range.len() + 2,
+        )),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_set_code_language_operation(
+    doc_blocks: &mut [Block],
+    operation: SetCodeLanguageOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let (index, candidates) = match locate_target(doc_blocks, &selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "set_code_language requires a code block, but the selector matched a {}",
+                found.kind_description()
+            ));
+        }
+    };
+    let block = &mut doc_blocks[index];
+    let Block::CodeBlock(code_block) = block else {
+        return Err(anyhow!(
+            "set_code_language requires a code block, but the selector matched a '{}'",
+            block_type_name(block)
+        ));
+    };
+
+    let current = match &code_block.kind {
+        CodeBlockKind::Fenced { info } => info.as_deref(),
+        CodeBlockKind::Indented => None,
+    };
+    let info = operation.language.as_deref().map(|language| {
+        match current.and_then(|info| info.trim().split_once(char::is_whitespace)) {
+            Some((_, attributes)) => format!("{language} {}", attributes.trim_start()),
+            None => language.to_string(),
+        }
+    });
+    if info.as_deref() == current {
+        return Ok(OperationReport {
+            candidates,
+            skipped: Some(match &info {
+                Some(info) => format!("code block info string is already '{info}'"),
+                None => "code block has no language".to_string(),
+            }),
+            ..OperationReport::default()
+        });
     }
+    code_block.kind = CodeBlockKind::Fenced { info };
 
-    let content_str = resolve_operation_content(content, content_file)?;
-    let new_content_doc = parse_markdown(MarkdownParserState::default(), &content_str)
-        .map_err(|e| anyhow!("Failed to parse content markdown: {}", e))?;
-    let new_blocks = new_content_doc.blocks;
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::edit(index)),
+        ..OperationReport::default()
+    })
+}
 
-    match found_node {
-        FoundNode::Block { index, .. } => {
-            if let Some(until_selector) = until_selector.as_ref() {
-                let end_index = compute_range_end(doc_blocks, index, until_selector)?;
-                doc_blocks.splice(index..end_index, new_blocks);
+fn apply_set_code_attribute_operation(
+    doc_blocks: &mut [Block],
+    operation: SetCodeAttributeOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let SetCodeAttributeOperation {
+        attribute,
+        value,
+        remove,
+        ..
+    } = operation;
+    if attribute.trim().is_empty() || attribute.contains(|c: char| c.is_whitespace() || c == ',') {
+        anyhow::bail!("set_code_attribute requires an attribute key without whitespace or commas");
+    }
+    if remove && value.is_some() {
+        anyhow::bail!("set_code_attribute cannot both set a value and remove the attribute");
+    }
+
+    let (index, candidates) = match locate_target(doc_blocks, &selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "set_code_attribute requires a code block, but the selector matched a {}",
+                found.kind_description()
+            ));
+        }
+    };
+    let block = &mut doc_blocks[index];
+    let Some(current) = code_info(block).map(str::to_string) else {
+        return Err(anyhow!(
+            "set_code_attribute requires a code block, but the selector matched a '{}'",
+            block_type_name(block)
+        ));
+    };
+
+    let info = if remove {
+        remove_info_attribute(&current, &attribute)
+    } else {
+        set_info_attribute(&current, &attribute, value.as_deref())
+    };
+    if info == current {
+        return Ok(OperationReport {
+            candidates,
+            skipped: Some(if remove {
+                format!("code block has no '{attribute}' attribute")
             } else {
-                replace(doc_blocks, index, new_blocks);
-            }
+                format!("code block info string is already '{info}'")
+            }),
+            ..OperationReport::default()
+        });
+    }
+    if let Block::CodeBlock(code_block) = block {
+        code_block.kind = CodeBlockKind::Fenced {
+            info: (!info.is_empty()).then_some(info),
+        };
+    }
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::edit(index)),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_insert_table_row_operation(
+    doc_blocks: &mut [Block],
+    operation: InsertTableRowOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let InsertTableRowOperation {
+        row,
+        position,
+        row_contains,
+        row_regex,
+        ..
+    } = operation;
+    if matches!(
+        position,
+        InsertPosition::PrependChild | InsertPosition::AppendChild
+    ) {
+        anyhow::bail!("insert_table_row only accepts the 'before' and 'after' positions");
+    }
+    let row_regex = compile_row_regex(row_regex.as_deref())?;
+
+    let (table_index, table, candidates) =
+        locate_table(doc_blocks, &selector, locate_cache, "insert_table_row")?;
+    let cells = row.to_cells(table.alignments.len())?;
+    let index = if row_contains.is_none() && row_regex.is_none() {
+        match position {
+            InsertPosition::Before => 1,
+            _ => table.rows.len(),
         }
-        FoundNode::ListItem {
-            block_index,
-            item_index,
-            ..
-        } => {
-            if until_selector.is_some() {
-                return Err(SpliceError::RangeRequiresBlock.into());
-            }
-            replace_list_item(doc_blocks, block_index, item_index, new_blocks)?;
+    } else {
+        let matched = find_body_row(table, row_contains.as_deref(), row_regex.as_ref())
+            .ok_or_else(|| no_row_match(row_contains.as_deref(), row_regex.as_ref()))?;
+        match position {
+            InsertPosition::Before => matched,
+            _ => matched + 1,
         }
-    }
+    };
+    table.rows.insert(index, cells);
 
-    Ok(is_ambiguous)
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::edit(table_index)),
+        ..OperationReport::default()
+    })
 }
 
-#[allow(dead_code)]
-fn apply_insert_operation(
-    doc_blocks: &mut Vec<Block>,
-    operation: InsertOperation,
+fn apply_delete_table_row_operation(
+    doc_blocks: &mut [Block],
+    operation: DeleteTableRowOperation,
     selector: Selector,
-) -> anyhow::Result<bool> {
-    let InsertOperation {
-        selector: _,
-        selector_ref: _,
-        comment: _,
-        content,
-        content_file,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let DeleteTableRowOperation {
+        row_contains,
+        row_regex,
+        ..
+    } = operation;
+    if row_contains.is_none() && row_regex.is_none() {
+        anyhow::bail!("delete_table_row requires 'row_contains' or 'row_regex'");
+    }
+    let row_regex = compile_row_regex(row_regex.as_deref())?;
+
+    let (table_index, table, candidates) =
+        locate_table(doc_blocks, &selector, locate_cache, "delete_table_row")?;
+    let index = find_body_row(table, row_contains.as_deref(), row_regex.as_ref())
+        .ok_or_else(|| no_row_match(row_contains.as_deref(), row_regex.as_ref()))?;
+    table.rows.remove(index);
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::edit(table_index)),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_add_table_column_operation(
+    doc_blocks: &mut [Block],
+    operation: AddTableColumnOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let AddTableColumnOperation {
+        header,
+        value,
         position,
+        column,
+        alignment,
+        ..
     } = operation;
+    if matches!(
+        position,
+        InsertPosition::PrependChild | InsertPosition::AppendChild
+    ) {
+        anyhow::bail!("add_table_column only accepts the 'before' and 'after' positions");
+    }
+    let header = parse_cell(&header)?;
+    let value = parse_cell(value.as_deref().unwrap_or_default())?;
+
+    let (table_index, table, candidates) =
+        locate_table(doc_blocks, &selector, locate_cache, "add_table_column")?;
+    let index = match (column, position) {
+        (Some(column), InsertPosition::Before) => find_column(table, &column)?,
+        (Some(column), _) => find_column(table, &column)? + 1,
+        (None, InsertPosition::Before) => 0,
+        (None, _) => table.alignments.len(),
+    };
+    let alignment = match alignment {
+        ColumnAlignment::None => Alignment::None,
+        ColumnAlignment::Left => Alignment::Left,
+        ColumnAlignment::Center => Alignment::Center,
+        ColumnAlignment::Right => Alignment::Right,
+    };
+    insert_table_column(table, index, header, &value, alignment)?;
 
-    let (found_node, is_ambiguous) = locate(&*doc_blocks, &selector)?;
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::edit(table_index)),
+        ..OperationReport::default()
+    })
+}
 
-    if is_ambiguous {
-        log::warn!(
-            "Warning: Selector matched multiple nodes. Operation was applied to the first match only."
-        );
+fn apply_remove_table_column_operation(
+    doc_blocks: &mut [Block],
+    operation: RemoveTableColumnOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let (table_index, table, candidates) =
+        locate_table(doc_blocks, &selector, locate_cache, "remove_table_column")?;
+    let index = find_column(table, &operation.column)?;
+    remove_table_column(table, index)?;
+
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::edit(table_index)),
+        ..OperationReport::default()
+    })
+}
+
+fn apply_rename_table_column_operation(
+    doc_blocks: &mut [Block],
+    operation: RenameTableColumnOperation,
+    selector: Selector,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let RenameTableColumnOperation { column, header, .. } = operation;
+    let header = parse_cell(&header)?;
+
+    let (table_index, table, candidates) =
+        locate_table(doc_blocks, &selector, locate_cache, "rename_table_column")?;
+    let index = find_column(table, &column)?;
+    let cell = &mut table.rows[0][index];
+    if *cell == header {
+        return Ok(OperationReport {
+            candidates,
+            skipped: Some(format!("column '{column}' already has that header")),
+            ..OperationReport::default()
+        });
     }
+    *cell = header;
 
-    let content_str = resolve_operation_content(content, content_file)?;
-    let new_content_doc = parse_markdown(MarkdownParserState::default(), &content_str)
-        .map_err(|e| anyhow!("Failed to parse content markdown: {}", e))?;
-    let new_blocks = new_content_doc.blocks;
+    Ok(OperationReport {
+        candidates,
+        change: Some(BlockChange::edit(table_index)),
+        ..OperationReport::default()
+    })
+}
 
-    match found_node {
-        FoundNode::Block { index, .. } => {
-            insert(doc_blocks, index, new_blocks, position)?;
+fn apply_update_toc_operation(
+    doc_blocks: &mut Vec<Block>,
+    operation: UpdateTocOperation,
+    selector: Option<Selector>,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let UpdateTocOperation {
+        min_level,
+        max_level,
+        slug_style,
+        ..
+    } = operation;
+
+    let min_level = min_level.unwrap_or(1);
+    let max_level = max_level.unwrap_or(6);
+    if !(1..=6).contains(&min_level) || !(1..=6).contains(&max_level) || min_level > max_level {
+        return Err(anyhow!(
+            "Invalid heading levels for update_toc: expected 1 <= min_level <= max_level <= 6"
+        ));
+    }
+
+    let (heading, candidates) = match selector {
+        Some(selector) => match locate_target(doc_blocks, &selector, locate_cache)? {
+            (
+                FoundNode::Block {
+                    index,
+                    block: Block::Heading(_),
+                },
+                candidates,
+            ) => (Some(index), candidates),
+            (found, _) => {
+                return Err(anyhow!(
+                    "update_toc places the table of contents under a heading, but the selector matched a {}",
+                    found.kind_description()
+                ))
+            }
+        },
+        None => (None, Vec::new()),
+    };
+
+    let search = match heading {
+        Some(index) => {
+            let level = get_heading_level(&doc_blocks[index]).unwrap_or(1);
+            index + 1..find_heading_section_end(doc_blocks, index, level)
         }
-        FoundNode::ListItem {
+        None => 0..doc_blocks.len(),
+    };
+    let mut change = None;
+    let (start, end) = match (find_toc_markers(&doc_blocks[search.clone()])?, heading) {
+        (Some((start, end)), _) => (search.start + start, search.start + end),
+        (None, Some(index)) => {
+            doc_blocks.splice(index + 1..index + 1, toc_markers());
+            change = Some(BlockChange::splice(index + 1, 0, 2));
+            (index + 1, index + 2)
+        }
+        (None, None) => {
+            return Err(anyhow!(
+                "The document has no {TOC_START} comment; select the heading to place the table of contents under"
+            ))
+        }
+    };
+
+    // The heading the table of contents sits under is not listed in it.
+    let owner = heading.or_else(|| {
+        start
+            .checked_sub(1)
+            .filter(|&index| matches!(doc_blocks[index], Block::Heading(_)))
+    });
+    let toc = build_toc(doc_blocks, min_level..=max_level, slug_style, owner);
+    if doc_blocks[start + 1..end] != *toc.as_slice() {
+        let toc_change = BlockChange::splice(start + 1, end - start - 1, toc.as_slice().len());
+        doc_blocks.splice(start + 1..end, toc);
+        change = BlockChange::chain(change, Some(toc_change));
+    }
+
+    Ok(OperationReport {
+        candidates,
+        change,
+        ..OperationReport::default()
+    })
+}
+
+/// Returns the table at `block_index`, which holds a matched table row or cell.
+fn table_at(doc_blocks: &mut [Block], block_index: usize) -> anyhow::Result<&mut Table> {
+    match &mut doc_blocks[block_index] {
+        Block::Table(table) => Ok(table),
+        _ => Err(anyhow!(
+            "Internal error: block at index {} is not a table",
+            block_index
+        )),
+    }
+}
+
+/// Replaces the inline element at `position` in the block at `block_index`, which holds a
+/// matched link, image or code span.
+fn splice_inline_at(
+    doc_blocks: &mut [Block],
+    block_index: usize,
+    position: usize,
+    inlines: Vec<Inline>,
+) -> anyhow::Result<()> {
+    if splice_inline(&mut doc_blocks[block_index], position, inlines) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Internal error: block at index {} has no inline at position {}",
             block_index,
-            item_index,
-            ..
-        } => {
-            insert_list_item(doc_blocks, block_index, item_index, new_blocks, position)?;
+            position
+        ))
+    }
+}
+
+/// The error of an operation named `op` whose selector matched a table row or cell, an
+/// inline element or a block nested in a container, which only `get`, `replace`,
+/// `replace_text` and `delete` (and `insert`, for nested blocks) can target.
+fn nested_node_unsupported(op: &str, found: &FoundNode) -> anyhow::Error {
+    let container = match found {
+        FoundNode::ContainedBlock { .. } => "the top-level block holding it",
+        FoundNode::ListItem { .. } => "the top-level list holding it",
+        FoundNode::Inline { .. } => "the block holding it",
+        _ => "the table",
+    };
+    anyhow!(
+        "{op} cannot target a {}; select {container} instead",
+        found.kind_description()
+    )
+}
+
+/// Returns whether `found` is an item of a list nested in another list item.
+fn is_nested_list_item(found: &FoundNode) -> bool {
+    matches!(found, FoundNode::ListItem { path, .. } if !path.is_empty())
+}
+
+/// Locates the table targeted by a table row or column operation named `op`, returning its
+/// block index with it.
+fn locate_table<'a>(
+    doc_blocks: &'a mut [Block],
+    selector: &Selector,
+    locate_cache: &mut LocateCache,
+    op: &str,
+) -> anyhow::Result<(usize, &'a mut Table, Vec<String>)> {
+    let (index, candidates) = match locate_target(doc_blocks, selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "{op} requires a table, but the selector matched a {}",
+                found.kind_description()
+            ));
         }
+    };
+    match &mut doc_blocks[index] {
+        Block::Table(table) => Ok((index, table, candidates)),
+        block => Err(anyhow!(
+            "{op} requires a table, but the selector matched a '{}'",
+            block_type_name(block)
+        )),
     }
+}
 
-    Ok(is_ambiguous)
+fn compile_row_regex(pattern: Option<&str>) -> anyhow::Result<Option<Regex>> {
+    pattern
+        .map(|pattern| {
+            Regex::new(pattern)
+                .map_err(|err| anyhow!("Invalid row_regex pattern '{pattern}': {err}"))
+        })
+        .transpose()
 }
 
-#[allow(dead_code)]
-fn apply_delete_operation(
+fn no_row_match(contains: Option<&str>, regex: Option<&Regex>) -> anyhow::Error {
+    let mut criteria = Vec::new();
+    if let Some(contains) = contains {
+        criteria.push(format!("contains {contains:?}"));
+    }
+    if let Some(regex) = regex {
+        criteria.push(format!("matches /{regex}/"));
+    }
+    anyhow!("No body row of the table {}", criteria.join(" and "))
+}
+
+fn apply_unwrap_details_operation(
     doc_blocks: &mut Vec<Block>,
-    operation: DeleteOperation,
+    _operation: UnwrapDetailsOperation,
     selector: Selector,
-    until_selector: Option<Selector>,
-) -> anyhow::Result<bool> {
-    let DeleteOperation {
-        selector: _,
-        selector_ref: _,
-        comment: _,
-        section,
-        until: _,
-        until_ref: _,
-    } = operation;
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<OperationReport> {
+    let (index, candidates) = match locate_target(doc_blocks, &selector, locate_cache)? {
+        (FoundNode::Block { index, .. }, candidates) => (index, candidates),
+        (found, _) => {
+            return Err(anyhow!(
+                "unwrap_details requires a <details> HTML block, but the selector matched a {}",
+                found.kind_description()
+            ));
+        }
+    };
+    let is_details = matches!(
+        &doc_blocks[index],
+        Block::HtmlBlock(html) if html_tag(html).is_some_and(|tag| tag.eq_ignore_ascii_case("details"))
+    );
+    if !is_details {
+        anyhow::bail!("unwrap_details requires the selector to match a <details> HTML block");
+    }
+    let span = element_span(doc_blocks, index)?;
+
+    let (Block::HtmlBlock(first), Block::HtmlBlock(last)) = (
+        &doc_blocks[span.blocks.start],
+        &doc_blocks[span.blocks.end - 1],
+    ) else {
+        anyhow::bail!("Internal error: an HTML element must start and end in HTML blocks");
+    };
+    // Inner HTML sharing a block with the tags is reparsed; the blocks between them are kept.
+    let new_blocks = if span.blocks.len() == 1 {
+        parse_html_fragment(strip_summary(&first[span.inner_start..span.inner_end]).trim())?
+    } else {
+        let mut new_blocks = parse_html_fragment(strip_summary(&first[span.inner_start..]).trim())?;
+        new_blocks.extend_from_slice(&doc_blocks[span.blocks.start + 1..span.blocks.end - 1]);
+        new_blocks.extend(parse_html_fragment(last[..span.inner_end].trim())?);
+        new_blocks
+    };
+    let change = BlockChange::splice(span.blocks.start, span.blocks.len(), new_blocks.len());
+    doc_blocks.splice(span.blocks, new_blocks);
 
-    let (found_node, is_ambiguous) = locate(&*doc_blocks, &selector)?;
+    Ok(OperationReport {
+        candidates,
+        change: Some(change),
+        ..OperationReport::default()
+    })
+}
 
-    if is_ambiguous {
-        log::warn!(
-            "Warning: Selector matched multiple nodes. Operation was applied to the first match only."
-        );
+/// Parses Markdown mixing HTML and Markdown blocks, such as the inside of an HTML element.
+fn parse_html_fragment(source: &str) -> anyhow::Result<Vec<Block>> {
+    let mut blocks = parse_markdown(MarkdownParserState::default(), source)
+        .map_err(|e| anyhow!("Failed to parse content markdown: {}", e))?
+        .blocks;
+    // The parser keeps the line break ending an HTML block, which the printer would double.
+    for block in &mut blocks {
+        if let Block::HtmlBlock(html) = block {
+            html.truncate(html.trim_end().len());
+        }
     }
+    Ok(blocks)
+}
 
-    match found_node {
-        FoundNode::Block { index, block } => {
-            if let Some(until_selector) = until_selector.as_ref() {
-                let end_index = compute_range_end(doc_blocks, index, until_selector)?;
-                doc_blocks.drain(index..end_index);
-            } else if section {
-                if matches!(block, Block::Heading(_)) {
-                    delete_section(doc_blocks, index);
-                } else {
-                    return Err(SpliceError::InvalidSectionDelete.into());
-                }
+fn github_alert_type(alert_type: AlertType) -> GitHubAlertType {
+    match alert_type {
+        AlertType::Note => GitHubAlertType::Note,
+        AlertType::Tip => GitHubAlertType::Tip,
+        AlertType::Important => GitHubAlertType::Important,
+        AlertType::Warning => GitHubAlertType::Warning,
+        AlertType::Caution => GitHubAlertType::Caution,
+    }
+}
+
+/// Returns the blocks following the heading matched by `scope_selector` up to the end of its
+/// section, or the whole document when no selector is given, along with the match ambiguity.
+fn resolve_section_scope(
+    doc_blocks: &[Block],
+    scope_selector: Option<Selector>,
+    locate_cache: &mut LocateCache,
+) -> anyhow::Result<(Range<usize>, Vec<String>)> {
+    let Some(selector) = scope_selector else {
+        return Ok((0..doc_blocks.len(), Vec::new()));
+    };
+
+    let (found_node, candidates) = locate_target(doc_blocks, &selector, locate_cache)?;
+    let FoundNode::Block { index, block } = found_node else {
+        return Err(SpliceError::SectionRequiresHeading.into());
+    };
+    let level = get_heading_level(block).ok_or(SpliceError::SectionRequiresHeading)?;
+    Ok((
+        index + 1..find_heading_section_end(doc_blocks, index, level),
+        candidates,
+    ))
+}
+
+/// Checks the condition of an `assert` operation, returning the aliases its selector defines.
+fn apply_assert_operation(
+    doc_blocks: &[Block],
+    parsed_document: &ParsedDocument,
+    alias_map: &HashMap<String, Selector>,
+    operation: AssertOperation,
+    locate_cache: &mut LocateCache,
+) -> Result<Vec<(String, Selector)>, SpliceError> {
+    let AssertOperation {
+        selector,
+        selector_ref,
+        comment,
+        frontmatter_key,
+        equals,
+        expect,
+    } = operation;
+    let expect_match = expect == AssertExpectation::Match;
+    let has_selector = selector.is_some() || selector_ref.is_some();
+
+    let (holds, failure, aliases) = match frontmatter_key {
+        Some(key) if !has_selector => {
+            let segments = parse_frontmatter_path(&key)
+                .map_err(|err| SpliceError::OperationFailed(err.to_string()))?;
+            let value = parsed_document
+                .frontmatter
+                .as_ref()
+                .and_then(|frontmatter| get_value_at_path(frontmatter, &segments));
+            let (holds, condition) = match &equals {
+                Some(expected) => (
+                    value == Some(expected),
+                    format!("equal {}", inline_yaml(expected)),
+                ),
+                None => (value.is_some(), "exist".to_string()),
+            };
+            let failure = if expect_match {
+                format!("frontmatter key '{key}' does not {condition}")
             } else {
-                delete(doc_blocks, index);
-            }
+                format!("frontmatter key '{key}' was expected not to {condition}")
+            };
+            (holds, failure, Vec::new())
         }
-        FoundNode::ListItem {
-            block_index,
-            item_index,
-            ..
-        } => {
-            if until_selector.is_some() {
-                return Err(SpliceError::RangeRequiresBlock.into());
-            }
-            if section {
-                return Err(SpliceError::InvalidSectionDelete.into());
-            }
-            let list_became_empty = delete_list_item(doc_blocks, block_index, item_index)?;
-            if list_became_empty {
-                delete(doc_blocks, block_index);
+        None if has_selector => {
+            if equals.is_some() {
+                return Err(SpliceError::OperationFailed(
+                    "assert only accepts 'equals' together with 'frontmatter_key'".to_string(),
+                ));
             }
+            let SelectorResolution { selector, aliases } = resolve_operation_selector(
+                alias_map,
+                selector.as_ref(),
+                selector_ref.as_ref(),
+                "selector",
+            )?;
+            let holds = match locate_cached(doc_blocks, &selector, locate_cache) {
+                Ok(_) => true,
+                Err(SpliceError::NodeNotFound) => false,
+                Err(err) => return Err(err),
+            };
+            let failure = if expect_match {
+                "selector did not match any nodes"
+            } else {
+                "selector was expected to match nothing, but matched a node"
+            };
+            (holds, failure.to_string(), aliases)
         }
+        _ => {
+            return Err(SpliceError::OperationFailed(
+                "assert requires either a selector (or selector_ref) or a frontmatter_key"
+                    .to_string(),
+            ))
+        }
+    };
+
+    if holds != expect_match {
+        return Err(SpliceError::AssertionFailed(match comment {
+            Some(comment) => format!("{failure} ({comment})"),
+            None => failure,
+        }));
     }
+    Ok(aliases)
+}
 
-    Ok(is_ambiguous)
+/// Renders a YAML value on a single line for error messages.
+fn inline_yaml(value: &YamlValue) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("{value:?}"))
 }
 
 fn apply_set_frontmatter_operation(
@@ -521,8 +4366,8 @@ fn apply_replace_frontmatter_operation(
 }
 
 #[derive(Debug)]
-struct SelectorResolution {
-    selector: Selector,
+pub(crate) struct SelectorResolution {
+    pub(crate) selector: Selector,
     aliases: Vec<(String, Selector)>,
 }
 
@@ -588,19 +4433,21 @@ fn resolve_optional_operation_selector(
     }
 }
 
-fn resolve_selector_tree(
+pub(crate) fn resolve_selector_tree(
     alias_map: &HashMap<String, Selector>,
     selector: &TransactionSelector,
 ) -> Result<SelectorResolution, SpliceError> {
-    let select_regex = match &selector.select_regex {
-        Some(pattern) => Some(Regex::new(pattern).map_err(|err| {
+    let compile = |pattern: &Option<String>| match pattern {
+        Some(pattern) => Regex::new(pattern).map(Some).map_err(|err| {
             SpliceError::OperationFailed(format!(
                 "Invalid regex pattern in operation selector: {}",
                 err
             ))
-        })?),
-        None => None,
+        }),
+        None => Ok(None),
     };
+    let select_regex = compile(&selector.select_regex)?;
+    let select_not_regex = compile(&selector.select_not_regex)?;
 
     let after_resolution = resolve_nested_selector(
         alias_map,
@@ -618,13 +4465,55 @@ fn resolve_selector_tree(
     let mut aliases = after_resolution.aliases;
     aliases.extend(within_resolution.aliases);
 
+    let between = match selector.between.as_deref() {
+        Some(between) => {
+            let mut landmark = |landmark, landmark_ref, field_name: &str| {
+                let resolution =
+                    resolve_nested_selector(alias_map, landmark, landmark_ref, field_name)?;
+                aliases.extend(resolution.aliases);
+                resolution.selector.ok_or_else(|| {
+                    SpliceError::AmbiguousNestedSelectorSource(field_name.to_string())
+                })
+            };
+            Some(Box::new(Between {
+                start: landmark(
+                    between.start.as_deref(),
+                    between.start_ref.as_ref(),
+                    "start",
+                )?,
+                end: landmark(between.end.as_deref(), between.end_ref.as_ref(), "end")?,
+            }))
+        }
+        None => None,
+    };
+
     let locator_selector = Selector {
         select_type: selector.select_type.clone(),
         select_contains: selector.select_contains.clone(),
+        select_contains_ci: selector.select_contains_ci.clone(),
         select_regex,
+        select_not_contains: selector.select_not_contains.clone(),
+        select_not_regex,
+        select_fingerprint: selector.select_fingerprint.clone(),
+        select_annotation: selector.select_annotation.clone(),
+        select_tag: selector.select_tag.clone(),
+        select_info_contains: selector.select_info_contains.clone(),
+        select_slug: selector.select_slug.clone(),
+        select_min_words: selector.select_min_words,
+        select_max_words: selector.select_max_words,
+        select_empty: selector.select_empty,
+        select_depth: selector.select_depth,
+        recursive: selector.recursive,
+        task_state: selector.task_state,
         select_ordinal: selector.select_ordinal,
+        select_last: selector.select_last,
+        max_matches: selector.max_matches,
+        tie_break: selector.tie_break,
         after: after_resolution.selector.map(Box::new),
         within: within_resolution.selector.map(Box::new),
+        between,
+        within_slide: selector.within_slide,
+        at_line: selector.at_line,
     };
 
     if let Some(alias) = &selector.alias {
@@ -700,6 +4589,24 @@ fn register_aliases(
 }
 
 #[allow(dead_code)]
+/// Expands `$1`, `${name}`, and the other capture group references in inline `content`
+/// with the groups of the selector's `select_regex` matched against the node's text. Use
+/// `$$` for a literal dollar sign. Content is returned unchanged when the selector has no
+/// regex.
+fn expand_regex_captures(content: String, selector: &Selector, found: &FoundNode) -> String {
+    let Some(regex) = &selector.select_regex else {
+        return content;
+    };
+    match regex.captures(&node_text(found)) {
+        Some(captures) => {
+            let mut expanded = String::with_capacity(content.len());
+            captures.expand(&content, &mut expanded);
+            expanded
+        }
+        None => content,
+    }
+}
+
 fn resolve_operation_content(
     content: Option<String>,
     content_file: Option<PathBuf>,
@@ -727,6 +4634,18 @@ fn resolve_operation_content(
     }
 }
 
+/// Parses the Markdown content of an operation into blocks, splitting compact definition
+/// list entries when the document was parsed with [`ParseOptions::definition_lists`].
+fn parse_content_blocks(content: &str, definition_lists: bool) -> anyhow::Result<Vec<Block>> {
+    let mut blocks = parse_markdown(MarkdownParserState::default(), content)
+        .map_err(|e| anyhow!("Failed to parse content markdown: {}", e))?
+        .blocks;
+    if definition_lists {
+        split_definition_lists(&mut blocks);
+    }
+    Ok(blocks)
+}
+
 #[derive(Debug)]
 enum FrontmatterPathSegment {
     Key(String),
@@ -907,6 +4826,18 @@ fn set_value_at_path(
     Ok(())
 }
 
+fn get_value_at_path<'a>(
+    current: &'a YamlValue,
+    segments: &[FrontmatterPathSegment],
+) -> Option<&'a YamlValue> {
+    segments
+        .iter()
+        .try_fold(current, |cursor, segment| match segment {
+            FrontmatterPathSegment::Key(key) => cursor.as_mapping()?.get(key.as_str()),
+            FrontmatterPathSegment::Index(index) => cursor.as_sequence()?.get(*index),
+        })
+}
+
 fn delete_value_at_path(
     current: &mut YamlValue,
     segments: &[FrontmatterPathSegment],
@@ -1123,6 +5054,7 @@ fn yaml_type_name(value: &YamlValue) -> &'static str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::locator::TieBreak;
     use crate::transaction::{
         DeleteOperation, InsertOperation, InsertPosition as TxInsertPosition, Operation,
         ReplaceOperation, Selector as TxSelector,
@@ -1141,6 +5073,7 @@ mod tests {
             body: initial.to_string(),
             format: None,
             frontmatter_block: None,
+            delimiter: None,
         };
 
         let operations = vec![Operation::Replace(ReplaceOperation {
@@ -1148,12 +5081,32 @@ mod tests {
                 alias: None,
                 select_type: None,
                 select_contains: Some("Status: In Progress".to_string()),
+                select_contains_ci: None,
                 select_regex: None,
+                select_not_contains: None,
+                select_not_regex: None,
+                select_fingerprint: None,
+                select_annotation: None,
+                select_tag: None,
+                select_info_contains: None,
+                select_slug: None,
+                select_min_words: None,
+                select_max_words: None,
+                select_empty: None,
+                select_depth: None,
+                recursive: false,
+                task_state: None,
                 select_ordinal: 1,
+                select_last: false,
+                max_matches: None,
+                tie_break: TieBreak::default(),
                 after: None,
                 after_ref: None,
                 within: None,
                 within_ref: None,
+                between: None,
+                within_slide: None,
+                at_line: None,
             }),
             selector_ref: None,
             comment: None,
@@ -1161,6 +5114,8 @@ mod tests {
             content_file: None,
             until: None,
             until_ref: None,
+            update_links: false,
+            cell: None,
         })];
 
         let frontmatter_changed = apply_operations(&mut blocks, &mut parsed_document, operations)
@@ -1188,6 +5143,7 @@ mod tests {
             body: initial.to_string(),
             format: None,
             frontmatter_block: None,
+            delimiter: None,
         };
 
         let operations = vec![Operation::Insert(InsertOperation {
@@ -1195,18 +5151,39 @@ mod tests {
                 alias: None,
                 select_type: Some("li".to_string()),
                 select_contains: Some("Write documentation".to_string()),
+                select_contains_ci: None,
                 select_regex: None,
+                select_not_contains: None,
+                select_not_regex: None,
+                select_fingerprint: None,
+                select_annotation: None,
+                select_tag: None,
+                select_info_contains: None,
+                select_slug: None,
+                select_min_words: None,
+                select_max_words: None,
+                select_empty: None,
+                select_depth: None,
+                recursive: false,
+                task_state: None,
                 select_ordinal: 1,
+                select_last: false,
+                max_matches: None,
+                tie_break: TieBreak::default(),
                 after: None,
                 after_ref: None,
                 within: None,
                 within_ref: None,
+                between: None,
+                within_slide: None,
+                at_line: None,
             }),
             selector_ref: None,
             comment: None,
             content: Some("- [ ] Implement unit tests".to_string()),
             content_file: None,
             position: TxInsertPosition::Before,
+            renumber: false,
         })];
 
         let frontmatter_changed = apply_operations(&mut blocks, &mut parsed_document, operations)
@@ -1242,6 +5219,7 @@ mod tests {
             body: initial.to_string(),
             format: None,
             frontmatter_block: None,
+            delimiter: None,
         };
 
         let operations = vec![
@@ -1250,36 +5228,78 @@ mod tests {
                     alias: None,
                     select_type: Some("li".to_string()),
                     select_contains: Some("Old task".to_string()),
+                    select_contains_ci: None,
                     select_regex: None,
+                    select_not_contains: None,
+                    select_not_regex: None,
+                    select_fingerprint: None,
+                    select_annotation: None,
+                    select_tag: None,
+                    select_info_contains: None,
+                    select_slug: None,
+                    select_min_words: None,
+                    select_max_words: None,
+                    select_empty: None,
+                    select_depth: None,
+                    recursive: false,
+                    task_state: None,
                     select_ordinal: 1,
+                    select_last: false,
+                    max_matches: None,
+                    tie_break: TieBreak::default(),
                     after: None,
                     after_ref: None,
                     within: None,
                     within_ref: None,
+                    between: None,
+                    within_slide: None,
+                    at_line: None,
                 }),
                 selector_ref: None,
                 comment: None,
                 section: false,
                 until: None,
                 until_ref: None,
+                renumber: false,
             }),
             Operation::Delete(DeleteOperation {
                 selector: Some(TxSelector {
                     alias: None,
                     select_type: Some("h2".to_string()),
                     select_contains: Some("Low Priority".to_string()),
+                    select_contains_ci: None,
                     select_regex: None,
+                    select_not_contains: None,
+                    select_not_regex: None,
+                    select_fingerprint: None,
+                    select_annotation: None,
+                    select_tag: None,
+                    select_info_contains: None,
+                    select_slug: None,
+                    select_min_words: None,
+                    select_max_words: None,
+                    select_empty: None,
+                    select_depth: None,
+                    recursive: false,
+                    task_state: None,
                     select_ordinal: 1,
+                    select_last: false,
+                    max_matches: None,
+                    tie_break: TieBreak::default(),
                     after: None,
                     after_ref: None,
                     within: None,
                     within_ref: None,
+                    between: None,
+                    within_slide: None,
+                    at_line: None,
                 }),
                 selector_ref: None,
                 comment: None,
                 section: true,
                 until: None,
                 until_ref: None,
+                renumber: false,
             }),
         ];
 
@@ -1311,6 +5331,7 @@ mod tests {
             body: initial.to_string(),
             format: None,
             frontmatter_block: None,
+            delimiter: None,
         };
 
         let operations = vec![Operation::Replace(ReplaceOperation {
@@ -1318,12 +5339,32 @@ mod tests {
                 alias: None,
                 select_type: Some("h2".to_string()),
                 select_contains: Some("Installation".to_string()),
+                select_contains_ci: None,
                 select_regex: None,
+                select_not_contains: None,
+                select_not_regex: None,
+                select_fingerprint: None,
+                select_annotation: None,
+                select_tag: None,
+                select_info_contains: None,
+                select_slug: None,
+                select_min_words: None,
+                select_max_words: None,
+                select_empty: None,
+                select_depth: None,
+                recursive: false,
+                task_state: None,
                 select_ordinal: 1,
+                select_last: false,
+                max_matches: None,
+                tie_break: TieBreak::default(),
                 after: None,
                 after_ref: None,
                 within: None,
                 within_ref: None,
+                between: None,
+                within_slide: None,
+                at_line: None,
             }),
             selector_ref: None,
             comment: None,
@@ -1333,14 +5374,36 @@ mod tests {
                 alias: None,
                 select_type: Some("h2".to_string()),
                 select_contains: Some("Usage".to_string()),
+                select_contains_ci: None,
                 select_regex: None,
+                select_not_contains: None,
+                select_not_regex: None,
+                select_fingerprint: None,
+                select_annotation: None,
+                select_tag: None,
+                select_info_contains: None,
+                select_slug: None,
+                select_min_words: None,
+                select_max_words: None,
+                select_empty: None,
+                select_depth: None,
+                recursive: false,
+                task_state: None,
                 select_ordinal: 1,
+                select_last: false,
+                max_matches: None,
+                tie_break: TieBreak::default(),
                 after: None,
                 after_ref: None,
                 within: None,
                 within_ref: None,
+                between: None,
+                within_slide: None,
+                at_line: None,
             }),
             until_ref: None,
+            update_links: false,
+            cell: None,
         })];
 
         let frontmatter_changed = apply_operations(&mut blocks, &mut parsed_document, operations)
@@ -1369,6 +5432,7 @@ mod tests {
             body: initial.to_string(),
             format: None,
             frontmatter_block: None,
+            delimiter: None,
         };
 
         let operations = vec![Operation::Delete(DeleteOperation {
@@ -1376,28 +5440,69 @@ mod tests {
                 alias: None,
                 select_type: Some("li".to_string()),
                 select_contains: Some("Task Beta".to_string()),
+                select_contains_ci: None,
                 select_regex: None,
+                select_not_contains: None,
+                select_not_regex: None,
+                select_fingerprint: None,
+                select_annotation: None,
+                select_tag: None,
+                select_info_contains: None,
+                select_slug: None,
+                select_min_words: None,
+                select_max_words: None,
+                select_empty: None,
+                select_depth: None,
+                recursive: false,
+                task_state: None,
                 select_ordinal: 1,
+                select_last: false,
+                max_matches: None,
+                tie_break: TieBreak::default(),
                 after: None,
                 after_ref: None,
                 within: Some(Box::new(TxSelector {
                     alias: None,
                     select_type: Some("h2".to_string()),
                     select_contains: Some("Future Features".to_string()),
+                    select_contains_ci: None,
                     select_regex: None,
+                    select_not_contains: None,
+                    select_not_regex: None,
+                    select_fingerprint: None,
+                    select_annotation: None,
+                    select_tag: None,
+                    select_info_contains: None,
+                    select_slug: None,
+                    select_min_words: None,
+                    select_max_words: None,
+                    select_empty: None,
+                    select_depth: None,
+                    recursive: false,
+                    task_state: None,
                     select_ordinal: 1,
+                    select_last: false,
+                    max_matches: None,
+                    tie_break: TieBreak::default(),
                     after: None,
                     after_ref: None,
                     within: None,
                     within_ref: None,
+                    between: None,
+                    within_slide: None,
+                    at_line: None,
                 })),
                 within_ref: None,
+                between: None,
+                within_slide: None,
+                at_line: None,
             }),
             selector_ref: None,
             comment: None,
             section: false,
             until: None,
             until_ref: None,
+            renumber: false,
         })];
 
         let frontmatter_changed = apply_operations(&mut blocks, &mut parsed_document, operations)
@@ -1427,6 +5532,7 @@ mod tests {
             body: initial.to_string(),
             format: None,
             frontmatter_block: None,
+            delimiter: None,
         };
         let original_blocks = blocks.clone();
         let original_document = parsed_document.clone();
@@ -1437,12 +5543,32 @@ mod tests {
                     alias: None,
                     select_type: None,
                     select_contains: Some("Status: In Progress".to_string()),
+                    select_contains_ci: None,
                     select_regex: None,
+                    select_not_contains: None,
+                    select_not_regex: None,
+                    select_fingerprint: None,
+                    select_annotation: None,
+                    select_tag: None,
+                    select_info_contains: None,
+                    select_slug: None,
+                    select_min_words: None,
+                    select_max_words: None,
+                    select_empty: None,
+                    select_depth: None,
+                    recursive: false,
+                    task_state: None,
                     select_ordinal: 1,
+                    select_last: false,
+                    max_matches: None,
+                    tie_break: TieBreak::default(),
                     after: None,
                     after_ref: None,
                     within: None,
                     within_ref: None,
+                    between: None,
+                    within_slide: None,
+                    at_line: None,
                 }),
                 selector_ref: None,
                 comment: None,
@@ -1450,24 +5576,47 @@ mod tests {
                 content_file: None,
                 until: None,
                 until_ref: None,
+                update_links: false,
+                cell: None,
             }),
             Operation::Delete(DeleteOperation {
                 selector: Some(TxSelector {
                     alias: None,
                     select_type: Some("h2".to_string()),
                     select_contains: Some("Does Not Exist".to_string()),
+                    select_contains_ci: None,
                     select_regex: None,
+                    select_not_contains: None,
+                    select_not_regex: None,
+                    select_fingerprint: None,
+                    select_annotation: None,
+                    select_tag: None,
+                    select_info_contains: None,
+                    select_slug: None,
+                    select_min_words: None,
+                    select_max_words: None,
+                    select_empty: None,
+                    select_depth: None,
+                    recursive: false,
+                    task_state: None,
                     select_ordinal: 1,
+                    select_last: false,
+                    max_matches: None,
+                    tie_break: TieBreak::default(),
                     after: None,
                     after_ref: None,
                     within: None,
                     within_ref: None,
+                    between: None,
+                    within_slide: None,
+                    at_line: None,
                 }),
                 selector_ref: None,
                 comment: None,
                 section: false,
                 until: None,
                 until_ref: None,
+                renumber: false,
             }),
         ];
 
@@ -1497,6 +5646,7 @@ mod tests {
             body: initial.to_string(),
             format: None,
             frontmatter_block: None,
+            delimiter: None,
         };
 
         let operations = vec![
@@ -1505,12 +5655,32 @@ mod tests {
                     alias: Some("overview_h2".to_string()),
                     select_type: Some("h2".to_string()),
                     select_contains: Some("Overview".to_string()),
+                    select_contains_ci: None,
                     select_regex: None,
+                    select_not_contains: None,
+                    select_not_regex: None,
+                    select_fingerprint: None,
+                    select_annotation: None,
+                    select_tag: None,
+                    select_info_contains: None,
+                    select_slug: None,
+                    select_min_words: None,
+                    select_max_words: None,
+                    select_empty: None,
+                    select_depth: None,
+                    recursive: false,
+                    task_state: None,
                     select_ordinal: 1,
+                    select_last: false,
+                    max_matches: None,
+                    tie_break: TieBreak::default(),
                     after: None,
                     after_ref: None,
                     within: None,
                     within_ref: None,
+                    between: None,
+                    within_slide: None,
+                    at_line: None,
                 }),
                 selector_ref: None,
                 comment: None,
@@ -1518,18 +5688,40 @@ mod tests {
                 content_file: None,
                 until: None,
                 until_ref: None,
+                update_links: false,
+                cell: None,
             }),
             Operation::Replace(ReplaceOperation {
                 selector: Some(TxSelector {
                     alias: Some("changelog_h2".to_string()),
                     select_type: Some("h2".to_string()),
                     select_contains: Some("Changelog".to_string()),
+                    select_contains_ci: None,
                     select_regex: None,
+                    select_not_contains: None,
+                    select_not_regex: None,
+                    select_fingerprint: None,
+                    select_annotation: None,
+                    select_tag: None,
+                    select_info_contains: None,
+                    select_slug: None,
+                    select_min_words: None,
+                    select_max_words: None,
+                    select_empty: None,
+                    select_depth: None,
+                    recursive: false,
+                    task_state: None,
                     select_ordinal: 1,
+                    select_last: false,
+                    max_matches: None,
+                    tie_break: TieBreak::default(),
                     after: None,
                     after_ref: Some("overview_h2".to_string()),
                     within: None,
                     within_ref: None,
+                    between: None,
+                    within_slide: None,
+                    at_line: None,
                 }),
                 selector_ref: None,
                 comment: None,
@@ -1537,6 +5729,8 @@ mod tests {
                 content_file: None,
                 until: None,
                 until_ref: None,
+                update_links: false,
+                cell: None,
             }),
             Operation::Insert(InsertOperation {
                 selector: None,
@@ -1545,6 +5739,7 @@ mod tests {
                 content: Some("- Added alias reuse support".to_string()),
                 content_file: None,
                 position: TxInsertPosition::AppendChild,
+                renumber: false,
             }),
             Operation::Replace(ReplaceOperation {
                 selector: None,
@@ -1556,6 +5751,8 @@ mod tests {
                 content_file: None,
                 until: None,
                 until_ref: None,
+                update_links: false,
+                cell: None,
             }),
         ];
 
@@ -1578,6 +5775,7 @@ mod tests {
             body: initial.to_string(),
             format: None,
             frontmatter_block: None,
+            delimiter: None,
         };
 
         let operations = vec![Operation::Insert(InsertOperation {
@@ -1587,6 +5785,7 @@ mod tests {
             content: Some("- Beta".to_string()),
             content_file: None,
             position: TxInsertPosition::AppendChild,
+            renumber: false,
         })];
 
         let err = apply_operations(&mut blocks, &mut parsed_document, operations)
@@ -1609,6 +5808,7 @@ mod tests {
             body: initial.to_string(),
             format: None,
             frontmatter_block: None,
+            delimiter: None,
         };
 
         let operations = vec![
@@ -1617,12 +5817,32 @@ mod tests {
                     alias: Some("dup_alias".to_string()),
                     select_type: Some("h2".to_string()),
                     select_contains: Some("Overview".to_string()),
+                    select_contains_ci: None,
                     select_regex: None,
+                    select_not_contains: None,
+                    select_not_regex: None,
+                    select_fingerprint: None,
+                    select_annotation: None,
+                    select_tag: None,
+                    select_info_contains: None,
+                    select_slug: None,
+                    select_min_words: None,
+                    select_max_words: None,
+                    select_empty: None,
+                    select_depth: None,
+                    recursive: false,
+                    task_state: None,
                     select_ordinal: 1,
+                    select_last: false,
+                    max_matches: None,
+                    tie_break: TieBreak::default(),
                     after: None,
                     after_ref: None,
                     within: None,
                     within_ref: None,
+                    between: None,
+                    within_slide: None,
+                    at_line: None,
                 }),
                 selector_ref: None,
                 comment: None,
@@ -1630,24 +5850,47 @@ mod tests {
                 content_file: None,
                 until: None,
                 until_ref: None,
+                update_links: false,
+                cell: None,
             }),
             Operation::Insert(InsertOperation {
                 selector: Some(TxSelector {
                     alias: Some("dup_alias".to_string()),
                     select_type: Some("h2".to_string()),
                     select_contains: Some("Overview".to_string()),
+                    select_contains_ci: None,
                     select_regex: None,
+                    select_not_contains: None,
+                    select_not_regex: None,
+                    select_fingerprint: None,
+                    select_annotation: None,
+                    select_tag: None,
+                    select_info_contains: None,
+                    select_slug: None,
+                    select_min_words: None,
+                    select_max_words: None,
+                    select_empty: None,
+                    select_depth: None,
+                    recursive: false,
+                    task_state: None,
                     select_ordinal: 1,
+                    select_last: false,
+                    max_matches: None,
+                    tie_break: TieBreak::default(),
                     after: None,
                     after_ref: None,
                     within: None,
                     within_ref: None,
+                    between: None,
+                    within_slide: None,
+                    at_line: None,
                 }),
                 selector_ref: None,
                 comment: None,
                 content: Some("## Duplicate heading".to_string()),
                 content_file: None,
                 position: TxInsertPosition::After,
+                renumber: false,
             }),
         ];
 
@@ -0,0 +1,334 @@
+//! Bounds on the size and complexity of the documents and operations a caller accepts.
+//!
+//! Services that edit user-supplied documents with user-supplied operations can reject
+//! oversized requests before spending CPU or memory on them. Every limit is optional, and
+//! [`Limits::default`] enforces none, which is how the CLI and [`crate::apply_str`] behave.
+//!
+//! Selector regexes need no time limit of their own: the `regex` crate matches in time
+//! linear in the size of the compiled regex times the length of the text, so
+//! [`Limits::max_regex_size`] and [`Limits::max_input_bytes`] together bound matching time.
+//! [`Limits::operation_timeout`] and [`Limits::batch_timeout`] bound the wall-clock time of
+//! the batch as a whole, for pipelines that must fail fast rather than stall. They are
+//! checked between operations and, within an operation, once per top-level block visited
+//! while locating its selectors, so a single slow operation stops where it overran.
+
+use crate::error::SpliceError;
+use crate::transaction::{Operation, Selector};
+use regex::RegexBuilder;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Limits enforced by [`Limits::check_input`], [`Limits::check_operations`],
+/// [`MarkdownDocument::apply_with_limits`](crate::MarkdownDocument::apply_with_limits), and
+/// [`apply_str_with_limits`](crate::apply_str_with_limits).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// The largest accepted document or operations text, in bytes.
+    pub max_input_bytes: Option<usize>,
+    /// The largest number of operations in a batch.
+    pub max_operations: Option<usize>,
+    /// The largest compiled size of a `select_regex` pattern, in bytes.
+    pub max_regex_size: Option<usize>,
+    /// The largest number of selectors chained through `after` and `within`, counting the
+    /// outermost one. Selectors reached through `after_ref`, `within_ref` and `between` aliases count too.
+    pub max_selector_depth: Option<usize>,
+    /// The longest time a single operation may take to apply.
+    pub operation_timeout: Option<Duration>,
+    /// The longest time the whole batch may take to apply.
+    pub batch_timeout: Option<Duration>,
+}
+
+impl Limits {
+    /// Limits suited to documents and operations received from untrusted clients: 1 MiB of
+    /// text, 100 operations, 100 KiB per compiled regex, 8 nested selectors, and 10 seconds
+    /// per batch.
+    pub fn untrusted() -> Self {
+        Self {
+            max_input_bytes: Some(1024 * 1024),
+            max_operations: Some(100),
+            max_regex_size: Some(100 * 1024),
+            max_selector_depth: Some(8),
+            operation_timeout: None,
+            batch_timeout: Some(Duration::from_secs(10)),
+        }
+    }
+
+    /// Fails with [`SpliceError::LimitExceeded`] if `input`, a document or the text of an
+    /// operations file named by `what`, is longer than [`Limits::max_input_bytes`].
+    pub fn check_input(&self, what: &str, input: &str) -> Result<(), SpliceError> {
+        match self.max_input_bytes {
+            Some(max) if input.len() > max => Err(SpliceError::LimitExceeded(format!(
+                "the {what} is {} bytes, more than the max_input_bytes limit of {max}",
+                input.len()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails with [`SpliceError::LimitExceeded`] if `operations` holds too many operations,
+    /// too deeply nested selectors, or regexes that compile to more than
+    /// [`Limits::max_regex_size`] bytes.
+    ///
+    /// Aliases are followed in batch order, as when the batch is applied. Operations are
+    /// numbered from 1 in error messages.
+    pub fn check_operations(&self, operations: &[Operation]) -> Result<(), SpliceError> {
+        if let Some(max) = self.max_operations {
+            if operations.len() > max {
+                return Err(SpliceError::LimitExceeded(format!(
+                    "the batch has {} operations, more than the max_operations limit of {max}",
+                    operations.len()
+                )));
+            }
+        }
+
+        let mut alias_depths = HashMap::new();
+        for (index, operation) in operations.iter().enumerate() {
+            for selector in operation_selectors(operation) {
+                let depth = self.check_selector(selector, &mut alias_depths, index + 1)?;
+                if let Some(max) = self.max_selector_depth {
+                    if depth > max {
+                        return Err(SpliceError::LimitExceeded(format!(
+                            "operation {} nests {depth} selectors, more than the max_selector_depth limit of {max}",
+                            index + 1
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the regexes of `selector` and its nested selectors, registering the depth of
+    /// the aliases they define, and returns the depth of `selector`.
+    fn check_selector<'a>(
+        &self,
+        selector: &'a Selector,
+        alias_depths: &mut HashMap<&'a str, usize>,
+        number: usize,
+    ) -> Result<usize, SpliceError> {
+        let patterns = [
+            ("select_regex", &selector.select_regex),
+            ("select_not_regex", &selector.select_not_regex),
+        ];
+        for (field, pattern) in patterns {
+            if let (Some(pattern), Some(max)) = (pattern, self.max_regex_size) {
+                // Invalid patterns are reported when the batch is applied.
+                if let Err(regex::Error::CompiledTooBig(_)) =
+                    RegexBuilder::new(pattern).size_limit(max).build()
+                {
+                    return Err(SpliceError::LimitExceeded(format!(
+                        "the {field} of operation {number} compiles to more than the max_regex_size limit of {max} bytes"
+                    )));
+                }
+            }
+        }
+
+        let mut nested_depth = 0;
+        for nested in selector.nested_selectors() {
+            nested_depth = nested_depth.max(self.check_selector(nested, alias_depths, number)?);
+        }
+        for alias in selector.nested_refs() {
+            // Undefined aliases fail when the batch is applied.
+            let alias_depth = alias_depths.get(alias.as_str()).copied().unwrap_or(1);
+            nested_depth = nested_depth.max(alias_depth);
+        }
+
+        let depth = nested_depth + 1;
+        if let Some(alias) = selector.alias.as_deref() {
+            alias_depths.insert(alias, depth);
+        }
+        Ok(depth)
+    }
+}
+
+/// Measures the time spent applying a batch against [`Limits::operation_timeout`] and
+/// [`Limits::batch_timeout`].
+pub(crate) struct Stopwatch<'a> {
+    limits: &'a Limits,
+    batch_start: Instant,
+    operation_start: Instant,
+}
+
+impl<'a> Stopwatch<'a> {
+    /// Starts timing a batch.
+    pub(crate) fn start(limits: &'a Limits) -> Self {
+        let now = Instant::now();
+        Self {
+            limits,
+            batch_start: now,
+            operation_start: now,
+        }
+    }
+
+    /// Starts timing the operation at `index`, failing if the batch is already out of time.
+    pub(crate) fn start_operation(&mut self, index: usize) -> Result<(), SpliceError> {
+        self.deadline(index).check_batch()?;
+        self.operation_start = Instant::now();
+        Ok(())
+    }
+
+    /// Fails with [`SpliceError::TimeLimitExceeded`] if the operation at `index`, which just
+    /// finished, or the batch so far took longer than allowed.
+    pub(crate) fn finish_operation(&self, index: usize) -> Result<(), SpliceError> {
+        self.deadline(index).check()
+    }
+
+    /// Returns the time limits of the operation at `index`, for the loops that apply it to
+    /// check as they go.
+    pub(crate) fn deadline(&self, index: usize) -> Deadline {
+        Deadline {
+            index,
+            operation: self
+                .limits
+                .operation_timeout
+                .map(|max| (self.operation_start, max)),
+            batch: self.limits.batch_timeout.map(|max| (self.batch_start, max)),
+        }
+    }
+}
+
+/// The time limits of the operation being applied, checked once per top-level block by
+/// the loops that locate its selectors, so that a single slow operation stops as soon as
+/// it runs out of time. The default deadline never passes.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Deadline {
+    index: usize,
+    operation: Option<(Instant, Duration)>,
+    batch: Option<(Instant, Duration)>,
+}
+
+impl Deadline {
+    /// Fails with [`SpliceError::TimeLimitExceeded`] if the operation or the batch has run
+    /// longer than allowed.
+    pub(crate) fn check(&self) -> Result<(), SpliceError> {
+        if let Some((start, max)) = self.operation {
+            let elapsed = start.elapsed();
+            if elapsed > max {
+                return Err(SpliceError::TimeLimitExceeded(format!(
+                    "operation {} took {elapsed:.2?}, more than the operation_timeout limit of {max:?}",
+                    self.index + 1
+                )));
+            }
+        }
+        self.check_batch()
+    }
+
+    fn check_batch(&self) -> Result<(), SpliceError> {
+        if let Some((start, max)) = self.batch {
+            let elapsed = start.elapsed();
+            if elapsed > max {
+                return Err(SpliceError::TimeLimitExceeded(format!(
+                    "the batch ran for {elapsed:.2?} by operation {}, more than the batch_timeout limit of {max:?}",
+                    self.index + 1
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns every inline selector of `operation`, including `until` and `within` scopes and
+/// `move` destinations.
+fn operation_selectors(operation: &Operation) -> Vec<&Selector> {
+    match operation {
+        Operation::Insert(op) => op.selector.iter().collect(),
+        Operation::Replace(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::ReplaceText(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::Delete(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::Upsert(op) => op.selector.iter().chain(op.fallback.iter()).collect(),
+        Operation::Move(op) => op.selector.iter().chain(op.destination.iter()).collect(),
+        Operation::Copy(op) => op
+            .selector
+            .iter()
+            .chain(op.until.iter())
+            .chain(op.destination.iter())
+            .collect(),
+        Operation::Swap(op) => op.selector.iter().chain(op.other.iter()).collect(),
+        Operation::RenameHeading(op) => op.selector.iter().collect(),
+        Operation::ShiftHeading(op) => op.selector.iter().collect(),
+        Operation::MergeSections(op) => op.selector.iter().chain(op.destination.iter()).collect(),
+        Operation::PruneEmptySections(op) => op.within.iter().collect(),
+        Operation::DedupeHeadings(op) => op.within.iter().collect(),
+        Operation::Renumber(op) => op.within.iter().collect(),
+        Operation::ImportSection(op) => op
+            .selector
+            .iter()
+            .chain(std::iter::once(&op.source_selector))
+            .collect(),
+        Operation::SetAlert(op) => op.selector.iter().collect(),
+        Operation::RemoveAlert(op) => op.selector.iter().collect(),
+        Operation::SetComment(op) => op.selector.iter().collect(),
+        Operation::RemoveComment(op) => op.selector.iter().collect(),
+        Operation::ReplaceInnerHtml(op) => op.selector.iter().collect(),
+        Operation::Wrap(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::Unwrap(op) => op.selector.iter().collect(),
+        Operation::WrapDetails(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::UnwrapDetails(op) => op.selector.iter().collect(),
+        Operation::SetCodeLanguage(op) => op.selector.iter().collect(),
+        Operation::SetCodeAttribute(op) => op.selector.iter().collect(),
+        Operation::InsertTableRow(op) => op.selector.iter().collect(),
+        Operation::DeleteTableRow(op) => op.selector.iter().collect(),
+        Operation::AddTableColumn(op) => op.selector.iter().collect(),
+        Operation::RemoveTableColumn(op) => op.selector.iter().collect(),
+        Operation::RenameTableColumn(op) => op.selector.iter().collect(),
+        Operation::UpdateToc(op) => op.selector.iter().collect(),
+        Operation::Assert(op) => op.selector.iter().collect(),
+        Operation::Get(op) => op.selector.iter().chain(op.until.iter()).collect(),
+        Operation::SetFrontmatter(_)
+        | Operation::DeleteFrontmatter(_)
+        | Operation::ReplaceFrontmatter(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::ReplaceOperation;
+
+    fn within(depth: usize) -> Selector {
+        let mut selector = Selector {
+            select_type: Some("h2".to_string()),
+            ..Selector::default()
+        };
+        for _ in 1..depth {
+            selector = Selector {
+                within: Some(Box::new(selector)),
+                ..Selector::default()
+            };
+        }
+        selector
+    }
+
+    fn replace(selector: Selector) -> Operation {
+        Operation::Replace(ReplaceOperation {
+            selector: Some(selector),
+            content: Some("Text.".to_string()),
+            ..ReplaceOperation::default()
+        })
+    }
+
+    #[test]
+    fn test_selector_depth_follows_aliases() {
+        let limits = Limits {
+            max_selector_depth: Some(3),
+            ..Limits::default()
+        };
+        let aliased = Selector {
+            alias: Some("deep".to_string()),
+            ..within(3)
+        };
+        let through_alias = Selector {
+            within_ref: Some("deep".to_string()),
+            ..Selector::default()
+        };
+
+        assert!(limits.check_operations(&[replace(within(3))]).is_ok());
+        let err = limits
+            .check_operations(&[replace(aliased), replace(through_alias)])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Limit exceeded: operation 2 nests 4 selectors, more than the max_selector_depth limit of 3"
+        );
+    }
+}
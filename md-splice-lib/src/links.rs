@@ -0,0 +1,226 @@
+//! Rewrites the relative destinations of links and images when a document moves.
+//!
+//! A relative destination such as `../img/logo.png` is resolved against the directory the
+//! document was in, then made relative to the directory it moves to, so that it keeps
+//! pointing at the same file. The fragment and query of a destination are kept, and so is
+//! a leading `./`.
+//!
+//! Destinations with a scheme (`https:`, `mailto:`), absolute paths, and links to an anchor
+//! of the document itself are left untouched, as are destinations that cannot be expressed
+//! from the new directory because it lies above what the directories have in common.
+
+use markdown_ppp::ast::{Block, Inline};
+use std::path::{Component, Path};
+
+/// Rewrites the relative destinations of the links, images and link reference definitions
+/// in `blocks` for a document moving from the directory `from` to the directory `to`.
+/// Returns the number of rewritten destinations.
+///
+/// Both directories are resolved lexically, so they should either both be absolute or both
+/// be relative to the same directory; otherwise nothing is rewritten.
+pub fn rebase_links(blocks: &mut [Block], from: &Path, to: &Path) -> usize {
+    if from.has_root() != to.has_root() {
+        return 0;
+    }
+    let from = directory_parts(from);
+    let to = directory_parts(to);
+    if from == to {
+        return 0;
+    }
+    blocks
+        .iter_mut()
+        .map(|block| rebase_block(block, &from, &to))
+        .sum()
+}
+
+/// Returns the destination `destination` takes once a document moves from the directory
+/// whose parts are `from` to the one whose parts are `to`, or `None` when it is not a
+/// relative path or stays the same.
+fn rebase_destination(destination: &str, from: &[String], to: &[String]) -> Option<String> {
+    if !is_relative(destination) {
+        return None;
+    }
+    let split = destination.find(['?', '#']).unwrap_or(destination.len());
+    let (path, suffix) = destination.split_at(split);
+    if path.is_empty() {
+        return None;
+    }
+
+    let target = normalize(from.iter().map(String::as_str).chain(path.split('/')));
+    let common = target
+        .iter()
+        .zip(to)
+        .take_while(|(left, right)| left == right)
+        .count();
+    // Climbing out of a directory requires knowing its name.
+    if to[common..].iter().any(|part| part == "..") {
+        return None;
+    }
+
+    let mut parts: Vec<&str> = vec![".."; to.len() - common];
+    parts.extend(target[common..].iter().map(String::as_str));
+    let mut rebased = match parts.join("/") {
+        joined if joined.is_empty() => ".".to_string(),
+        joined if path.starts_with("./") && !joined.starts_with("..") => format!("./{joined}"),
+        joined => joined,
+    };
+    if path.ends_with('/') {
+        rebased.push('/');
+    }
+    rebased.push_str(suffix);
+    (rebased != destination).then_some(rebased)
+}
+
+/// Returns whether `destination` is a relative path: neither empty, nor an anchor or a
+/// query on the document itself, nor an absolute path, nor a URL with a scheme.
+fn is_relative(destination: &str) -> bool {
+    let has_scheme = destination.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    !destination.is_empty() && !destination.starts_with(['#', '?', '/', '\\']) && !has_scheme
+}
+
+/// Splits a directory into its normalized parts, the root of an absolute path included.
+fn directory_parts(directory: &Path) -> Vec<String> {
+    let parts: Vec<String> = directory
+        .components()
+        .map(|component| match component {
+            Component::Prefix(prefix) => prefix.as_os_str().to_string_lossy().into_owned(),
+            Component::RootDir => "/".to_string(),
+            Component::CurDir => ".".to_string(),
+            Component::ParentDir => "..".to_string(),
+            Component::Normal(part) => part.to_string_lossy().into_owned(),
+        })
+        .collect();
+    normalize(parts.iter().map(String::as_str))
+}
+
+/// Resolves `.` and `..` parts lexically. Leading `..` parts of a relative path are kept.
+fn normalize<'a>(parts: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for part in parts {
+        match part {
+            "" | "." => {}
+            ".." => match normalized.last().map(String::as_str) {
+                Some("..") | None => normalized.push("..".to_string()),
+                Some("/") => {}
+                Some(_) => {
+                    normalized.pop();
+                }
+            },
+            part => normalized.push(part.to_string()),
+        }
+    }
+    normalized
+}
+
+fn rebase_block(block: &mut Block, from: &[String], to: &[String]) -> usize {
+    match block {
+        Block::Paragraph(inlines) => rebase_inlines(inlines, from, to),
+        Block::Heading(heading) => rebase_inlines(&mut heading.content, from, to),
+        Block::BlockQuote(blocks) => blocks
+            .iter_mut()
+            .map(|block| rebase_block(block, from, to))
+            .sum(),
+        Block::List(list) => list
+            .items
+            .iter_mut()
+            .flat_map(|item| item.blocks.iter_mut())
+            .map(|block| rebase_block(block, from, to))
+            .sum(),
+        Block::Table(table) => table
+            .rows
+            .iter_mut()
+            .flatten()
+            .map(|cell| rebase_inlines(cell, from, to))
+            .sum(),
+        Block::FootnoteDefinition(footnote) => footnote
+            .blocks
+            .iter_mut()
+            .map(|block| rebase_block(block, from, to))
+            .sum(),
+        Block::GitHubAlert(alert) => alert
+            .blocks
+            .iter_mut()
+            .map(|block| rebase_block(block, from, to))
+            .sum(),
+        Block::Definition(definition) => rebase(&mut definition.destination, from, to),
+        Block::ThematicBreak | Block::CodeBlock(_) | Block::HtmlBlock(_) | Block::Empty => 0,
+    }
+}
+
+fn rebase_inlines(inlines: &mut [Inline], from: &[String], to: &[String]) -> usize {
+    inlines
+        .iter_mut()
+        .map(|inline| match inline {
+            Inline::Link(link) => {
+                rebase(&mut link.destination, from, to)
+                    + rebase_inlines(&mut link.children, from, to)
+            }
+            Inline::Image(image) => rebase(&mut image.destination, from, to),
+            Inline::LinkReference(link) => rebase_inlines(&mut link.text, from, to),
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children) => rebase_inlines(children, from, to),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn rebase(destination: &mut String, from: &[String], to: &[String]) -> usize {
+    match rebase_destination(destination, from, to) {
+        Some(rebased) => {
+            *destination = rebased;
+            1
+        }
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(directory: &str) -> Vec<String> {
+        directory_parts(Path::new(directory))
+    }
+
+    #[test]
+    fn test_rebase_destination_keeps_targets_and_suffixes() {
+        let from = parts("/repo/docs/guide");
+        let to = parts("/repo/docs");
+        let rebase = |destination| rebase_destination(destination, &from, &to);
+
+        assert_eq!(
+            rebase("setup.md#install"),
+            Some("guide/setup.md#install".into())
+        );
+        assert_eq!(rebase("../img/logo.png"), Some("img/logo.png".into()));
+        assert_eq!(rebase("./api/"), Some("./guide/api/".into()));
+        assert_eq!(
+            rebase("../../README.md?plain=1"),
+            Some("../README.md?plain=1".into())
+        );
+        assert_eq!(rebase("https://example.com/a.md"), None);
+        assert_eq!(rebase("mailto:team@example.com"), None);
+        assert_eq!(rebase("/abs/path.md"), None);
+        assert_eq!(rebase("#usage"), None);
+
+        let deeper = parts("/repo/docs/guide/v2");
+        assert_eq!(
+            rebase_destination("../img/logo.png", &from, &deeper),
+            Some("../../img/logo.png".into())
+        );
+        assert_eq!(
+            rebase_destination("v2/page.md", &from, &deeper),
+            Some("page.md".into())
+        );
+        assert_eq!(
+            rebase_destination("a.md", &parts("docs"), &parts("../elsewhere")),
+            None
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Contains checks that flag structural problems in a document without modifying it.
+
+use crate::anchors::explicit_id;
+use crate::locator::block_to_text;
+use crate::sections::get_heading_level;
+use crate::slugify::{heading_slugs, slugify};
+use markdown_ppp::ast::Block;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+/// A group of headings that share the same text, and therefore the same anchor link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateHeadings {
+    /// The heading text, as written by its first occurrence.
+    pub text: String,
+    /// Indices of the top-level heading blocks in document order, starting with the original.
+    pub indices: Vec<usize>,
+}
+
+/// Finds headings within `scope` whose text collides with another heading in the same scope.
+///
+/// Texts are compared after trimming and ignoring case, mirroring how anchor slugs are
+/// generated. Groups are returned in the order of their first occurrence. The part of `scope`
+/// past the last block is ignored.
+pub fn find_duplicate_headings(blocks: &[Block], scope: Range<usize>) -> Vec<DuplicateHeadings> {
+    let mut groups: Vec<DuplicateHeadings> = Vec::new();
+    let mut group_by_key: HashMap<String, usize> = HashMap::new();
+
+    for (index, block) in blocks.iter().enumerate().take(scope.end).skip(scope.start) {
+        if get_heading_level(block).is_none() {
+            continue;
+        }
+        let text = block_to_text(block).trim().to_string();
+        let key = heading_key(&text);
+        match group_by_key.get(&key) {
+            Some(&group) => groups[group].indices.push(index),
+            None => {
+                group_by_key.insert(key, groups.len());
+                groups.push(DuplicateHeadings {
+                    text,
+                    indices: vec![index],
+                });
+            }
+        }
+    }
+
+    groups.retain(|group| group.indices.len() > 1);
+    groups
+}
+
+/// A group of headings with different texts but the same anchor, such as `C++` and `C`, which
+/// both slugify to `c`. Anchor links only reach the first of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlugCollision {
+    /// The anchor the headings share: their slug, or an explicit `{#id}`.
+    pub slug: String,
+    /// The texts of the headings, without their explicit `{#id}` attribute.
+    pub texts: Vec<String>,
+    /// Indices of the top-level heading blocks in document order.
+    pub indices: Vec<usize>,
+}
+
+/// Finds top-level headings whose anchors collide although their texts differ; headings with
+/// the same text are reported by [`find_duplicate_headings`] instead. An explicit `{#id}` is
+/// the anchor of its heading, so it collides with a heading slugified to the same id.
+///
+/// Groups are returned in the order of their first occurrence.
+pub fn find_slug_collisions(blocks: &[Block]) -> Vec<SlugCollision> {
+    let mut groups: Vec<SlugCollision> = Vec::new();
+    let mut group_by_slug: HashMap<String, usize> = HashMap::new();
+
+    for heading in heading_slugs(blocks) {
+        let slug = match &blocks[heading.index] {
+            Block::Heading(block) => explicit_id(block).map(str::to_string),
+            _ => None,
+        }
+        .unwrap_or_else(|| slugify(&heading.text));
+        match group_by_slug.get(&slug) {
+            Some(&group) => {
+                groups[group].texts.push(heading.text);
+                groups[group].indices.push(heading.index);
+            }
+            None => {
+                group_by_slug.insert(slug.clone(), groups.len());
+                groups.push(SlugCollision {
+                    slug,
+                    texts: vec![heading.text],
+                    indices: vec![heading.index],
+                });
+            }
+        }
+    }
+
+    groups.retain(|group| {
+        let keys: HashSet<String> = group.texts.iter().map(|text| heading_key(text)).collect();
+        keys.len() > 1
+    });
+    groups
+}
+
+/// Returns the normalized form used to decide whether two heading texts collide.
+pub(crate) fn heading_key(text: &str) -> String {
+    text.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    #[test]
+    fn test_find_duplicate_headings_groups_by_normalized_text() {
+        let markdown = "# Guide\n\n## Usage\n\nText.\n\n## usage\n\n## Notes\n\n### Usage\n";
+        let doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        let duplicates = find_duplicate_headings(&doc.blocks, 0..doc.blocks.len());
+
+        assert_eq!(
+            duplicates,
+            vec![DuplicateHeadings {
+                text: "Usage".to_string(),
+                indices: vec![1, 3, 5],
+            }]
+        );
+        assert!(find_duplicate_headings(&doc.blocks, 0..3).is_empty());
+    }
+
+    #[test]
+    fn test_find_slug_collisions_ignores_identical_texts() {
+        let markdown = "# C++\n\n## C\n\n## Setup {#c}\n\n## Usage\n\n## usage\n";
+        let doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        assert_eq!(
+            find_slug_collisions(&doc.blocks),
+            vec![SlugCollision {
+                slug: "c".to_string(),
+                texts: vec!["C++".to_string(), "C".to_string(), "Setup".to_string()],
+                indices: vec![0, 1, 2],
+            }]
+        );
+    }
+}
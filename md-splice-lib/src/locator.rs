@@ -1,10 +1,37 @@
 //! Contains the logic for finding a target node within the Markdown AST.
-
+//!
+//! Nodes are mostly found by what they contain. The parser keeps no byte or line positions,
+//! so `at_line` selectors, which find the block written across a given line of the original
+//! file, are resolved against the spans recovered by
+//! [`MarkdownDocument::block_spans`](crate::MarkdownDocument::block_spans) and held in a
+//! a crate-internal cache. The public [`locate`] and [`locate_all`] know no spans: use
+//! [`MarkdownDocument::locate`](crate::MarkdownDocument::locate) for those selectors.
+
+use crate::anchors::explicit_id;
+use crate::codeinfo::{code_info, code_language};
+use crate::comments::comment_text;
+use crate::deflist::{is_definition, is_definition_term};
 use crate::error::SpliceError;
+use crate::fingerprint::{block_fingerprint, fingerprint_matches, node_fingerprint};
+use crate::html::html_tag;
+use crate::inlines::{
+    block_inlines, inline_count, inline_description, inline_match_text, inline_select_type,
+    InlineKind,
+};
+use crate::limits::Deadline;
+use crate::sections::{get_heading_level, slide_range};
+use crate::slugify::{heading_slugs, slugify};
+use crate::spans::SourceSpan;
+use crate::table::row_text;
 use markdown_ppp::ast::{
-    Block, FootnoteDefinition, HeadingKind, Inline, List, ListItem, SetextHeading, Table, TaskState,
+    Block, FootnoteDefinition, GitHubAlertType, HeadingKind, Inline, List, ListItem, SetextHeading,
+    Table, TableCell, TaskState,
 };
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::ops::Range;
 
 /// Represents the location of a found block.
 #[derive(Debug, PartialEq)]
@@ -13,11 +40,186 @@ pub enum FoundNode<'a> {
         index: usize,
         block: &'a Block,
     },
+    ContainedBlock {
+        block_index: usize, // Index of the top-level Block the block is nested in
+        // Indices leading from the top-level block to the block: the index of a block among
+        // those of a block quote, an alert or a footnote definition, or the index of a list
+        // item followed by the index of a block among the item's blocks
+        path: Vec<usize>,
+        block: &'a Block,
+    },
     ListItem {
-        block_index: usize, // Index of the parent Block::List
-        item_index: usize,  // Index of the ListItem within the list
+        block_index: usize, // Index of the top-level Block::List holding the item
+        item_index: usize,  // Index of the ListItem within its own list
+        // For each level of nesting, the index of an item and the index of the list nested
+        // among its blocks, leading from the top-level list to the item's own list
+        path: Vec<(usize, usize)>,
         item: &'a ListItem,
     },
+    TableRow {
+        block_index: usize, // Index of the parent Block::Table
+        row_index: usize,   // Index of the row within the table, the header row being 0
+        row: &'a [TableCell],
+    },
+    TableCell {
+        block_index: usize,  // Index of the parent Block::Table
+        row_index: usize,    // Index of the row within the table, the header row being 0
+        column_index: usize, // Index of the column within the table
+        cell: &'a [Inline],
+    },
+    Inline {
+        block_index: usize, // Index of the top-level Block holding the inline
+        position: usize,    // Position of the inline among the inlines of its block
+        inline: &'a Inline,
+    },
+}
+
+impl FoundNode<'_> {
+    /// Describes the kind of node found, as in "the selector matched a list item".
+    pub fn kind_description(&self) -> &'static str {
+        match self {
+            FoundNode::Block { .. } => "block",
+            FoundNode::ContainedBlock { .. } => "nested block",
+            FoundNode::ListItem { path, .. } if !path.is_empty() => "nested list item",
+            FoundNode::ListItem { .. } => "list item",
+            FoundNode::TableRow { .. } => "table row",
+            FoundNode::TableCell { .. } => "table cell",
+            FoundNode::Inline { inline, .. } => inline_description(inline),
+        }
+    }
+
+    /// Describes where the node is, with 0-indexed positions: `block 2`, `block 2 nested 0.1`
+    /// (a block nested in containers), `block 4 item 1`, `block 4 item 1 item 0` (a nested
+    /// item), `block 3 row 2`, `block 3 row 2 column 1` or `block 5 inline 3`.
+    pub fn position_description(&self) -> String {
+        match self {
+            FoundNode::Block { index, .. } => format!("block {index}"),
+            FoundNode::ContainedBlock {
+                block_index, path, ..
+            } => {
+                let path: Vec<String> = path.iter().map(usize::to_string).collect();
+                format!("block {block_index} nested {}", path.join("."))
+            }
+            FoundNode::ListItem {
+                block_index,
+                item_index,
+                path,
+                ..
+            } => {
+                let parents: String = path
+                    .iter()
+                    .map(|(item, _)| format!(" item {item}"))
+                    .collect();
+                format!("block {block_index}{parents} item {item_index}")
+            }
+            FoundNode::TableRow {
+                block_index,
+                row_index,
+                ..
+            } => format!("block {block_index} row {row_index}"),
+            FoundNode::TableCell {
+                block_index,
+                row_index,
+                column_index,
+                ..
+            } => format!("block {block_index} row {row_index} column {column_index}"),
+            FoundNode::Inline {
+                block_index,
+                position,
+                ..
+            } => format!("block {block_index} inline {position}"),
+        }
+    }
+
+    /// Returns the canonical `select_type` name of the node, such as `h2`, `p`, `li`, `td`
+    /// or `link`, so that a match can be selected again by its type.
+    pub fn node_type(&self) -> &'static str {
+        match self {
+            FoundNode::Block { block, .. } | FoundNode::ContainedBlock { block, .. } => {
+                SELECT_TYPES
+                    .iter()
+                    .find_map(|select_type| match select_type.kind {
+                        TypeKind::Block(matches) if matches(block) => Some(select_type.names[0]),
+                        _ => None,
+                    })
+                    .unwrap_or("block")
+            }
+            FoundNode::ListItem { .. } => "li",
+            FoundNode::TableRow { .. } => "tr",
+            FoundNode::TableCell { .. } => "td",
+            FoundNode::Inline { inline, .. } => inline_select_type(inline),
+        }
+    }
+
+    /// Returns the position and type of the node as structured data, for callers that
+    /// correlate matches back to the document.
+    pub fn metadata(&self) -> MatchMetadata {
+        let mut metadata = MatchMetadata {
+            node_type: self.node_type(),
+            block_index: found_block_index(self),
+            nested_path: Vec::new(),
+            list_indices: Vec::new(),
+            row_index: None,
+            column_index: None,
+            inline_position: None,
+            heading_level: None,
+        };
+        match self {
+            FoundNode::Block { block, .. } => metadata.heading_level = get_heading_level(block),
+            FoundNode::ContainedBlock { path, block, .. } => {
+                metadata.nested_path = path.clone();
+                metadata.heading_level = get_heading_level(block);
+            }
+            FoundNode::ListItem {
+                item_index, path, ..
+            } => {
+                metadata.list_indices = path.iter().map(|(item, _)| *item).collect();
+                metadata.list_indices.push(*item_index);
+            }
+            FoundNode::TableRow { row_index, .. } => metadata.row_index = Some(*row_index),
+            FoundNode::TableCell {
+                row_index,
+                column_index,
+                ..
+            } => {
+                metadata.row_index = Some(*row_index);
+                metadata.column_index = Some(*column_index);
+            }
+            FoundNode::Inline { position, .. } => metadata.inline_position = Some(*position),
+        }
+        metadata
+    }
+}
+
+/// Where a [`FoundNode`] is and what it is, with 0-indexed positions. Fields that do not
+/// apply to the kind of node are left empty and omitted when serialized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MatchMetadata {
+    /// The canonical `select_type` name of the node (see [`FoundNode::node_type`]).
+    pub node_type: &'static str,
+    /// Index of the top-level block that is, or holds, the node.
+    #[serde(rename = "block")]
+    pub block_index: usize,
+    /// For a block nested in containers, the indices leading to it from its top-level block,
+    /// as in [`FoundNode::ContainedBlock`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub nested_path: Vec<usize>,
+    /// For a list item, the index of the item at each level of nesting, from the item of the
+    /// top-level list down to the item itself.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub list_indices: Vec<usize>,
+    /// For a table row or cell, the index of its row, the header row being 0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_index: Option<usize>,
+    /// For a table cell, the index of its column.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column_index: Option<usize>,
+    /// For an inline element, its position among the inlines of its block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inline_position: Option<usize>,
+    /// For a heading, its level.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_level: Option<u8>,
 }
 
 /// A set of criteria for selecting a node.
@@ -25,15 +227,764 @@ pub enum FoundNode<'a> {
 pub struct Selector {
     pub select_type: Option<String>,
     pub select_contains: Option<String>,
+    /// Only match nodes whose text contains this text, ignoring case and runs of whitespace
+    /// (see [`normalize_text`]).
+    pub select_contains_ci: Option<String>,
     pub select_regex: Option<Regex>,
+    /// Only match nodes whose text does not contain this text.
+    pub select_not_contains: Option<String>,
+    /// Only match nodes whose text does not match this regex.
+    pub select_not_regex: Option<Regex>,
+    pub select_fingerprint: Option<String>,
+    pub select_annotation: Option<String>,
+    /// Only match HTML blocks whose first element has this tag name (see `html_tag`).
+    pub select_tag: Option<String>,
+    /// Only match code blocks whose info string contains this text (see `code_info`).
+    pub select_info_contains: Option<String>,
+    /// Only match the heading whose anchor is this slug (see [`heading_slugs`]).
+    pub select_slug: Option<String>,
+    /// Only match nodes with at least this many words (see [`word_count`]).
+    pub select_min_words: Option<usize>,
+    /// Only match nodes with at most this many words (see [`word_count`]).
+    pub select_max_words: Option<usize>,
+    /// Only match nodes without any word (`true`) or with at least one (`false`).
+    pub select_empty: Option<bool>,
+    /// Only match list items nested this many lists deep, 1 being the items of a top-level
+    /// list. List items are searched at depth 1 when unset.
+    pub select_depth: Option<usize>,
+    /// Also search the blocks nested in block quotes, alerts, footnote definitions and list
+    /// items (see [`FoundNode::ContainedBlock`]). A container comes before the blocks it holds.
+    pub recursive: bool,
+    /// Only match list items that are tasks in this state (see [`TaskStatus`]).
+    pub task_state: Option<TaskStatus>,
     pub select_ordinal: usize,
+    /// Count `select_ordinal` from the last match instead of the first.
+    pub select_last: bool,
     pub after: Option<Box<Selector>>,
     pub within: Option<Box<Selector>>,
+    /// Only search the blocks between two landmarks (see [`Between`]).
+    pub between: Option<Box<Between>>,
+    /// Only search the _n_th slide (1-indexed) of a deck separated by thematic breaks.
+    pub within_slide: Option<usize>,
+    /// Only match the top-level block whose source span covers this 1-indexed line, and the
+    /// nodes nested in it. Blocks without a span never match.
+    pub at_line: Option<usize>,
+    /// Fail with [`SpliceError::TooManyMatches`] when more nodes than this match.
+    pub max_matches: Option<usize>,
+    /// The order in which matches are ranked before `select_ordinal` picks one.
+    pub tie_break: TieBreak,
+}
+
+/// Two landmarks bounding a search to the top-level blocks strictly between them.
+///
+/// The `end` landmark is searched after the `start` one, so its `select_ordinal` counts the
+/// matches following `start`. A landmark nested in a block, such as a list item, bounds the
+/// search at the block holding it.
+#[derive(Debug, Clone)]
+pub struct Between {
+    pub start: Selector,
+    pub end: Selector,
+}
+
+/// The order in which the nodes matching a selector are ranked.
+///
+/// `select_ordinal` counts matches in this order, and the first match is the one an
+/// operation applies to when a selector is ambiguous.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    /// Matches are ranked in document order.
+    #[default]
+    DocumentOrder,
+    /// Matches in deeper heading sections come first (a node under an `h3` before one
+    /// under an `h2`), then matches are ranked in document order.
+    DeepestFirst,
+}
+
+/// The state of a task list item (`- [ ]` or `- [x]`) that a selector matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    /// Checked tasks (`- [x]`).
+    Complete,
+    /// Unchecked tasks (`- [ ]`).
+    Incomplete,
+    /// Tasks in either state, but not plain list items.
+    Any,
+}
+
+impl TaskStatus {
+    /// Checks the task checkbox of a list item, `None` for a plain item, against this state.
+    pub fn matches(self, task: Option<TaskState>) -> bool {
+        match self {
+            TaskStatus::Complete => task == Some(TaskState::Complete),
+            TaskStatus::Incomplete => task == Some(TaskState::Incomplete),
+            TaskStatus::Any => task.is_some(),
+        }
+    }
+}
+
+/// Memoizes selector matches across the operations of a batch.
+///
+/// Entries are keyed by a selector's filters (type, text, fingerprint, tag and info string) and
+/// store one result per top-level block, computed lazily. After a mutation, call
+/// [`LocateCache::invalidate`] with the changed region so that only those blocks are
+/// re-evaluated; results for untouched blocks are shifted and kept.
+///
+/// The cache also tracks the source span of each block, which `at_line` selectors are
+/// matched against: replaced blocks lose theirs.
+#[derive(Debug, Default)]
+pub(crate) struct LocateCache {
+    block_count: usize,
+    entries: HashMap<FilterKey, Vec<Option<Vec<bool>>>>,
+    spans: Vec<Option<SourceSpan>>,
+    deadline: Deadline,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct FilterKey {
+    select_type: Option<String>,
+    select_contains: Option<String>,
+    select_contains_ci: Option<String>,
+    select_regex: Option<String>,
+    select_not_contains: Option<String>,
+    select_not_regex: Option<String>,
+    select_fingerprint: Option<String>,
+    select_tag: Option<String>,
+    select_info_contains: Option<String>,
+    select_depth: Option<usize>,
+    task_state: Option<TaskStatus>,
+}
+
+impl FilterKey {
+    fn new(selector: &Selector) -> Self {
+        Self {
+            select_type: selector.select_type.as_ref().map(|t| t.to_lowercase()),
+            select_contains: selector.select_contains.clone(),
+            select_contains_ci: selector.select_contains_ci.clone(),
+            select_regex: selector
+                .select_regex
+                .as_ref()
+                .map(|re| re.as_str().to_string()),
+            select_not_contains: selector.select_not_contains.clone(),
+            select_not_regex: selector
+                .select_not_regex
+                .as_ref()
+                .map(|re| re.as_str().to_string()),
+            select_fingerprint: selector.select_fingerprint.clone(),
+            select_tag: selector.select_tag.as_ref().map(|t| t.to_lowercase()),
+            select_info_contains: selector.select_info_contains.clone(),
+            select_depth: selector.select_depth,
+            task_state: selector.task_state,
+        }
+    }
 }
 
-/// Checks if a type string refers to a list item.
-fn is_list_item_type(type_str: &str) -> bool {
-    matches!(type_str.to_lowercase().as_str(), "li" | "item" | "listitem")
+impl LocateCache {
+    /// Creates an empty cache for blocks written at `spans` of the source.
+    pub fn with_spans(spans: Vec<Option<SourceSpan>>) -> Self {
+        Self {
+            block_count: spans.len(),
+            spans,
+            ..Self::default()
+        }
+    }
+
+    /// Returns an empty cache for the blocks from `start` on, keeping their spans and the
+    /// deadline, to search a tail of the document such as the end of a range.
+    pub fn tail(&self, start: usize) -> Self {
+        Self {
+            deadline: self.deadline,
+            ..Self::with_spans(self.spans.get(start..).unwrap_or_default().to_vec())
+        }
+    }
+
+    /// Discards cached results for the blocks that a mutation replaced.
+    ///
+    /// `range` is the region of the updated document occupied by the new blocks and
+    /// `removed` is the number of blocks the mutation removed from that position.
+    pub fn invalidate(&mut self, range: Range<usize>, removed: usize) {
+        let replaced = range.start..range.start + removed;
+        if replaced.end > self.block_count {
+            self.entries.clear();
+            self.spans.clear();
+            return;
+        }
+        for results in self.entries.values_mut() {
+            results.splice(replaced.clone(), std::iter::repeat_n(None, range.len()));
+        }
+        if !self.spans.is_empty() {
+            self.spans
+                .splice(replaced, std::iter::repeat_n(None, range.len()));
+        }
+        self.block_count = self.block_count - removed + range.len();
+    }
+
+    /// Sets the time limits that locating through the cache checks once per block visited.
+    pub fn set_deadline(&mut self, deadline: Deadline) {
+        self.deadline = deadline;
+    }
+
+    /// Returns the time limits of the operation being applied.
+    pub fn deadline(&self) -> Deadline {
+        self.deadline
+    }
+
+    /// Drops every cached result if the cache no longer describes `blocks`.
+    fn sync(&mut self, blocks: &[Block]) {
+        if self.block_count != blocks.len() {
+            self.entries.clear();
+            self.spans.clear();
+            self.block_count = blocks.len();
+        }
+    }
+
+    /// Returns the match flags of the block at `index`: a single flag for block
+    /// selectors, or one flag per nested node (see [`nested_nodes`]) for the others.
+    fn block_results(&mut self, blocks: &[Block], index: usize, selector: &Selector) -> &[bool] {
+        let results = self
+            .entries
+            .entry(FilterKey::new(selector))
+            .or_insert_with(|| vec![None; blocks.len()]);
+        if results.len() != blocks.len() {
+            *results = vec![None; blocks.len()];
+        }
+        results[index].get_or_insert_with(|| compute_block_results(&blocks[index], selector))
+    }
+}
+
+fn compute_block_results(block: &Block, selector: &Selector) -> Vec<bool> {
+    let Some(kind) = nested_kind(selector) else {
+        return vec![block_matches_selector(block, selector)];
+    };
+    nested_nodes(0, block, kind)
+        .iter()
+        .map(|node| nested_node_matches_filters(selector, node))
+        .collect()
+}
+
+/// Evaluates selector filters, either directly or through a [`LocateCache`], which also
+/// holds the [`Deadline`] of the operation being applied.
+enum Matcher<'c> {
+    Direct,
+    Cached(&'c mut LocateCache),
+}
+
+impl Matcher<'_> {
+    fn check_deadline(&self) -> Result<(), SpliceError> {
+        match self {
+            Matcher::Direct => Ok(()),
+            Matcher::Cached(cache) => cache.deadline.check(),
+        }
+    }
+
+    /// Checks the `at_line` criterion of `selector` against the block at `index`. Only a
+    /// cache knows where blocks were written, so nothing else matches a line.
+    fn covers_line(&self, index: usize, selector: &Selector) -> bool {
+        let Some(line) = selector.at_line else {
+            return true;
+        };
+        match self {
+            Matcher::Direct => false,
+            Matcher::Cached(cache) => cache
+                .spans
+                .get(index)
+                .and_then(Option::as_ref)
+                .is_some_and(|span| (span.start_line..=span.end_line).contains(&line)),
+        }
+    }
+
+    fn block_matches(&mut self, blocks: &[Block], index: usize, selector: &Selector) -> bool {
+        if !self.covers_line(index, selector)
+            || !follows_annotation(blocks, index, selector)
+            || !precedes_definition(blocks, index, selector)
+            || !has_slug(blocks, index, selector)
+        {
+            return false;
+        }
+        let matches = match self {
+            Matcher::Direct => block_matches_selector(&blocks[index], selector),
+            Matcher::Cached(cache) => cache.block_results(blocks, index, selector)[0],
+        };
+        matches && meets_length_criteria(selector, || block_word_count(blocks, index))
+    }
+
+    /// Checks the nested `node`, found at `position` among the nested nodes of its block.
+    fn nested_node_matches(
+        &mut self,
+        blocks: &[Block],
+        position: usize,
+        node: &FoundNode,
+        selector: &Selector,
+    ) -> bool {
+        let block_index = found_block_index(node);
+        if !self.covers_line(block_index, selector)
+            || !follows_annotation(blocks, block_index, selector)
+        {
+            return false;
+        }
+        let matches = match self {
+            Matcher::Direct => nested_node_matches_filters(selector, node),
+            Matcher::Cached(cache) => cache
+                .block_results(blocks, block_index, selector)
+                .get(position)
+                .copied()
+                .unwrap_or(false),
+        };
+        matches && meets_length_criteria(selector, || node_word_count(blocks, node))
+    }
+}
+
+/// Checks the `select_min_words`, `select_max_words` and `select_empty` criteria against the
+/// word count returned by `words`, which is only computed when one of them is set.
+///
+/// These criteria are evaluated outside the per-block cache because the word count of a
+/// heading depends on the blocks of its section.
+fn meets_length_criteria(selector: &Selector, words: impl FnOnce() -> usize) -> bool {
+    if selector.select_min_words.is_none()
+        && selector.select_max_words.is_none()
+        && selector.select_empty.is_none()
+    {
+        return true;
+    }
+    let words = words();
+    selector.select_min_words.is_none_or(|min| words >= min)
+        && selector.select_max_words.is_none_or(|max| words <= max)
+        && selector
+            .select_empty
+            .is_none_or(|empty| empty == (words == 0))
+}
+
+/// Counts the words of `text`: whitespace-separated tokens containing at least one letter or
+/// digit, so that list markers, separators and lone punctuation are not counted.
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace()
+        .filter(|token| token.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+/// Counts the words of a matched node, as the `select_min_words`, `select_max_words` and
+/// `select_empty` criteria do.
+pub(crate) fn node_word_count(blocks: &[Block], found: &FoundNode) -> usize {
+    match found {
+        FoundNode::Block { index, .. } => block_word_count(blocks, *index),
+        FoundNode::ContainedBlock { block, .. } => word_count(&block_to_text(block)),
+        FoundNode::ListItem { item, .. } => list_item_word_count(item),
+        FoundNode::TableRow { row, .. } => word_count(&row_text(row)),
+        FoundNode::TableCell { cell, .. } => word_count(&inlines_to_text(cell)),
+        FoundNode::Inline { inline, .. } => {
+            word_count(&inlines_to_text(std::slice::from_ref(*inline)))
+        }
+    }
+}
+
+/// Counts the words of the block at `index`. A heading is measured by the content of its
+/// section, subsections included, rather than by its own text.
+fn block_word_count(blocks: &[Block], index: usize) -> usize {
+    match &blocks[index] {
+        Block::Heading(heading) => {
+            let end = find_section_end(blocks, index, heading_level(&heading.kind));
+            blocks[index + 1..end]
+                .iter()
+                .map(|block| word_count(&block_to_text(block)))
+                .sum()
+        }
+        block => word_count(&block_to_text(block)),
+    }
+}
+
+/// Counts the words of a list item and the blocks nested in it, ignoring its task checkbox.
+fn list_item_word_count(item: &ListItem) -> usize {
+    item.blocks
+        .iter()
+        .map(|block| word_count(&block_to_text(block)))
+        .sum()
+}
+
+/// Checks that a `dt` match is followed by a definition, which depends on the next block and
+/// is therefore evaluated outside the per-block cache like [`follows_annotation`].
+fn precedes_definition(blocks: &[Block], index: usize, selector: &Selector) -> bool {
+    let is_term_type = selector.select_type.as_deref().is_some_and(|type_str| {
+        ["dt", "term"]
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(type_str))
+    });
+    !is_term_type || is_definition_term(blocks, index)
+}
+
+/// Checks the `select_annotation` criterion, which depends on the block preceding `index`
+/// and is therefore evaluated outside the per-block cache.
+fn follows_annotation(blocks: &[Block], index: usize, selector: &Selector) -> bool {
+    let Some(annotation) = &selector.select_annotation else {
+        return true;
+    };
+    let Some(Block::HtmlBlock(html)) = index.checked_sub(1).and_then(|prev| blocks.get(prev))
+    else {
+        return false;
+    };
+    comment_text(html).is_some_and(|text| text == annotation.trim())
+}
+
+/// Checks the `select_slug` criterion. Repeated slugs are suffixed in document order, so the
+/// anchor of a heading depends on the headings before it and is checked outside the
+/// per-block cache.
+fn has_slug(blocks: &[Block], index: usize, selector: &Selector) -> bool {
+    let Some(slug) = &selector.select_slug else {
+        return true;
+    };
+    let Block::Heading(heading) = &blocks[index] else {
+        return false;
+    };
+    if let Some(id) = explicit_id(heading) {
+        return id == slug;
+    }
+    // Only number the headings before this one when its own slug could be the one wanted.
+    let base = slugify(block_to_text(&blocks[index]).trim());
+    let suffixed = slug
+        .strip_prefix(base.as_str())
+        .and_then(|rest| rest.strip_prefix('-'))
+        .is_some_and(|count| !count.is_empty() && count.chars().all(|c| c.is_ascii_digit()));
+    (*slug == base || suffixed)
+        && heading_slugs(&blocks[..=index])
+            .last()
+            .is_some_and(|last| last.anchor == *slug)
+}
+
+/// A node type accepted by `select_type`.
+#[derive(Debug, Clone, Copy)]
+pub struct SelectType {
+    /// The names selecting this type, compared case-insensitively. The first is canonical.
+    pub names: &'static [&'static str],
+    /// A short description of the nodes this type matches.
+    pub description: &'static str,
+    kind: TypeKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TypeKind {
+    Block(fn(&Block) -> bool),
+    Nested(NestedKind),
+}
+
+/// The nodes searched inside top-level blocks rather than among them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NestedKind {
+    /// List items nested this many lists deep.
+    ListItem(usize),
+    TableRow,
+    TableCell,
+    Inline(InlineKind),
+}
+
+impl SelectType {
+    const fn block(
+        names: &'static [&'static str],
+        description: &'static str,
+        matches: fn(&Block) -> bool,
+    ) -> Self {
+        Self {
+            names,
+            description,
+            kind: TypeKind::Block(matches),
+        }
+    }
+
+    /// Returns whether this type selects list items rather than top-level blocks.
+    pub fn is_list_item(&self) -> bool {
+        matches!(self.kind, TypeKind::Nested(NestedKind::ListItem(_)))
+    }
+
+    fn is_named(&self, type_str: &str) -> bool {
+        self.names
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(type_str))
+    }
+}
+
+/// Every value accepted by `select_type`, in the order they are documented.
+pub const SELECT_TYPES: &[SelectType] = &[
+    SelectType::block(&["p", "paragraph"], "Paragraphs", |block| {
+        matches!(block, Block::Paragraph(_))
+    }),
+    SelectType::block(&["h1"], "Level 1 headings", |block| {
+        get_heading_level(block) == Some(1)
+    }),
+    SelectType::block(&["h2"], "Level 2 headings", |block| {
+        get_heading_level(block) == Some(2)
+    }),
+    SelectType::block(&["h3"], "Level 3 headings", |block| {
+        get_heading_level(block) == Some(3)
+    }),
+    SelectType::block(&["h4"], "Level 4 headings", |block| {
+        get_heading_level(block) == Some(4)
+    }),
+    SelectType::block(&["h5"], "Level 5 headings", |block| {
+        get_heading_level(block) == Some(5)
+    }),
+    SelectType::block(&["h6"], "Level 6 headings", |block| {
+        get_heading_level(block) == Some(6)
+    }),
+    SelectType::block(&["heading"], "Headings of any level", |block| {
+        get_heading_level(block).is_some()
+    }),
+    SelectType::block(&["list"], "Ordered and unordered lists", |block| {
+        matches!(block, Block::List(_))
+    }),
+    SelectType {
+        names: &["li", "item", "listitem"],
+        description: "List items of top-level lists, or of nested lists with `select_depth`",
+        kind: TypeKind::Nested(NestedKind::ListItem(1)),
+    },
+    SelectType::block(
+        &["dt", "term"],
+        "Terms of a definition list (paragraphs followed by a `: ` definition)",
+        |block| matches!(block, Block::Paragraph(_)) && !is_definition(block),
+    ),
+    SelectType::block(
+        &["dd"],
+        "Definitions of a definition list (paragraphs starting with `: `)",
+        is_definition,
+    ),
+    SelectType::block(
+        &["code", "codeblock"],
+        "Fenced and indented code blocks, or with `code:LANG` those in the language LANG",
+        |block| matches!(block, Block::CodeBlock(_)),
+    ),
+    SelectType::block(&["table"], "Tables", |block| {
+        matches!(block, Block::Table(_))
+    }),
+    SelectType {
+        names: &["tr", "row", "tablerow"],
+        description: "Body rows of tables, matched by their cells separated by tabs",
+        kind: TypeKind::Nested(NestedKind::TableRow),
+    },
+    SelectType {
+        names: &["td", "cell", "tablecell"],
+        description: "Table cells, header cells included, row by row",
+        kind: TypeKind::Nested(NestedKind::TableCell),
+    },
+    SelectType::block(
+        &["blockquote"],
+        "Block quotes (not GitHub alerts)",
+        |block| matches!(block, Block::BlockQuote(_)),
+    ),
+    SelectType::block(
+        &["alert", "githubalert"],
+        "GitHub alerts of any type",
+        |block| matches!(block, Block::GitHubAlert(_)),
+    ),
+    SelectType::block(&["note", "alert-note"], "`[!NOTE]` alerts", |block| {
+        alert_type(block) == Some(GitHubAlertType::Note)
+    }),
+    SelectType::block(&["tip", "alert-tip"], "`[!TIP]` alerts", |block| {
+        alert_type(block) == Some(GitHubAlertType::Tip)
+    }),
+    SelectType::block(
+        &["important", "alert-important"],
+        "`[!IMPORTANT]` alerts",
+        |block| alert_type(block) == Some(GitHubAlertType::Important),
+    ),
+    SelectType::block(
+        &["warning", "alert-warning"],
+        "`[!WARNING]` alerts",
+        |block| alert_type(block) == Some(GitHubAlertType::Warning),
+    ),
+    SelectType::block(
+        &["caution", "alert-caution"],
+        "`[!CAUTION]` alerts",
+        |block| alert_type(block) == Some(GitHubAlertType::Caution),
+    ),
+    SelectType::block(
+        &["html", "htmlblock"],
+        "Raw HTML blocks, including comments",
+        |block| matches!(block, Block::HtmlBlock(_)),
+    ),
+    SelectType::block(
+        &["comment"],
+        "HTML comments such as `<!-- note -->`",
+        |block| matches!(block, Block::HtmlBlock(html) if comment_text(html).is_some()),
+    ),
+    SelectType::block(&["thematicbreak"], "Thematic breaks (`---`)", |block| {
+        matches!(block, Block::ThematicBreak)
+    }),
+    SelectType::block(&["definition"], "Link reference definitions", |block| {
+        matches!(block, Block::Definition(_))
+    }),
+    SelectType::block(&["footnotedefinition"], "Footnote definitions", |block| {
+        matches!(block, Block::FootnoteDefinition(_))
+    }),
+    SelectType::block(&["empty"], "Empty blocks", |block| {
+        matches!(block, Block::Empty)
+    }),
+    SelectType {
+        names: &["link", "a"],
+        description: "Links, matched by their text and destination separated by a tab",
+        kind: TypeKind::Nested(NestedKind::Inline(InlineKind::Link)),
+    },
+    SelectType {
+        names: &["image", "img"],
+        description: "Images, matched by their alternative text and source separated by a tab",
+        kind: TypeKind::Nested(NestedKind::Inline(InlineKind::Image)),
+    },
+    SelectType {
+        names: &["codespan", "inlinecode"],
+        description: "Inline code spans",
+        kind: TypeKind::Nested(NestedKind::Inline(InlineKind::CodeSpan)),
+    },
+];
+
+fn alert_type(block: &Block) -> Option<GitHubAlertType> {
+    match block {
+        Block::GitHubAlert(alert) => Some(alert.alert_type),
+        _ => None,
+    }
+}
+
+/// Returns the kind of nested node `selector` searches for, if it does not search for
+/// top-level blocks. List items are searched at the depth given by `select_depth`.
+fn nested_kind(selector: &Selector) -> Option<NestedKind> {
+    let type_str = selector.select_type.as_deref()?;
+    SELECT_TYPES
+        .iter()
+        .find_map(|select_type| match select_type.kind {
+            TypeKind::Nested(NestedKind::ListItem(_)) if select_type.is_named(type_str) => {
+                Some(NestedKind::ListItem(selector.select_depth.unwrap_or(1)))
+            }
+            TypeKind::Nested(kind) if select_type.is_named(type_str) => Some(kind),
+            _ => None,
+        })
+}
+
+/// Returns the nodes of `kind` nested in `block`, in document order: the items of a list
+/// at the given depth, the body rows of a table, the cells of a table row by row, or the
+/// inline elements of any block. Cells missing from short rows are returned empty.
+fn nested_nodes(block_index: usize, block: &Block, kind: NestedKind) -> Vec<FoundNode<'_>> {
+    match (kind, block) {
+        (NestedKind::ListItem(depth), Block::List(list)) => {
+            let mut nodes = Vec::new();
+            collect_list_items(block_index, list, depth, &mut Vec::new(), &mut nodes);
+            nodes
+        }
+        (NestedKind::TableRow, Block::Table(table)) => table
+            .rows
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(row_index, row)| FoundNode::TableRow {
+                block_index,
+                row_index,
+                row,
+            })
+            .collect(),
+        (NestedKind::TableCell, Block::Table(table)) => table
+            .rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row_index, row)| {
+                (0..table.alignments.len()).map(move |column_index| FoundNode::TableCell {
+                    block_index,
+                    row_index,
+                    column_index,
+                    cell: row.get(column_index).map_or(&[], Vec::as_slice),
+                })
+            })
+            .collect(),
+        (NestedKind::Inline(kind), block) => block_inlines(block)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, inline)| kind.matches(inline))
+            .map(|(position, inline)| FoundNode::Inline {
+                block_index,
+                position,
+                inline,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Pushes the items of `list` that are nested `depth` lists deep onto `nodes`, in document
+/// order. `path` leads from the top-level list at `block_index` to `list`.
+fn collect_list_items<'a>(
+    block_index: usize,
+    list: &'a List,
+    depth: usize,
+    path: &mut Vec<(usize, usize)>,
+    nodes: &mut Vec<FoundNode<'a>>,
+) {
+    for (item_index, item) in list.items.iter().enumerate() {
+        match depth {
+            0 => return,
+            1 => nodes.push(FoundNode::ListItem {
+                block_index,
+                item_index,
+                path: path.clone(),
+                item,
+            }),
+            _ => {
+                for (list_index, block) in item.blocks.iter().enumerate() {
+                    if let Block::List(nested) = block {
+                        path.push((item_index, list_index));
+                        collect_list_items(block_index, nested, depth - 1, path, nodes);
+                        path.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the list that a list item found at `block_index` belongs to, following `path`
+/// into nested lists (see [`FoundNode::ListItem`]).
+pub fn item_list<'a>(
+    blocks: &'a [Block],
+    block_index: usize,
+    path: &[(usize, usize)],
+) -> Option<&'a List> {
+    let mut list = match blocks.get(block_index)? {
+        Block::List(list) => list,
+        _ => return None,
+    };
+    for &(item_index, list_index) in path {
+        let Block::List(nested) = list.items.get(item_index)?.blocks.get(list_index)? else {
+            return None;
+        };
+        list = nested;
+    }
+    Some(list)
+}
+
+/// Returns the position a [`NestedRestriction`] compares: the index of a list item (of the
+/// top-level item holding it, for a nested one), the row of a table row or cell, or the
+/// position of an inline element.
+fn nested_position(node: &FoundNode) -> usize {
+    match node {
+        FoundNode::Block { index, .. }
+        | FoundNode::ContainedBlock {
+            block_index: index, ..
+        } => *index,
+        FoundNode::ListItem {
+            item_index, path, ..
+        } => path.first().map_or(*item_index, |&(item, _)| item),
+        FoundNode::TableRow { row_index, .. } | FoundNode::TableCell { row_index, .. } => {
+            *row_index
+        }
+        FoundNode::Inline { position, .. } => *position,
+    }
+}
+
+/// Returns the plain text of a matched node, as `select_contains` and `select_regex` see
+/// it for nested nodes.
+pub(crate) fn node_text(found: &FoundNode) -> String {
+    match found {
+        FoundNode::Block { block, .. } | FoundNode::ContainedBlock { block, .. } => {
+            block_to_text(block)
+        }
+        FoundNode::ListItem { item, .. } => list_item_to_text(item),
+        FoundNode::TableRow { row, .. } => row_text(row),
+        FoundNode::TableCell { cell, .. } => inlines_to_text(cell),
+        FoundNode::Inline { inline, .. } => inline_match_text(inline),
+    }
 }
 
 /// Recursively extracts the plain text content from a `ListItem` node.
@@ -68,7 +1019,7 @@ pub(crate) fn list_item_to_text(item: &ListItem) -> String {
 struct Scope {
     block_start: usize,
     block_end: usize,
-    list_restriction: Option<ListRestriction>,
+    nested_restriction: Option<NestedRestriction>,
 }
 
 impl Scope {
@@ -76,15 +1027,80 @@ impl Scope {
         Self {
             block_start: 0,
             block_end: len,
-            list_restriction: None,
+            nested_restriction: None,
         }
     }
 }
 
+/// Restricts the nested nodes searched in one block to those whose [`nested_position`] is
+/// after `start_item` and before `end_item`, whichever are given.
 #[derive(Debug, Clone, Copy)]
-struct ListRestriction {
+struct NestedRestriction {
     block_index: usize,
     start_item: Option<usize>,
+    end_item: Option<usize>,
+}
+
+impl NestedRestriction {
+    fn contains(&self, position: usize) -> bool {
+        self.start_item.is_none_or(|start| position > start)
+            && self.end_item.is_none_or(|end| position < end)
+    }
+}
+
+/// Returns the positions of the inline elements nested in a list item, a table row or cell,
+/// or an inline element, so that inline elements can be searched within or after it.
+fn inline_span(blocks: &[Block], landmark: &FoundNode) -> Option<Range<usize>> {
+    let count_blocks =
+        |blocks: &[Block]| -> usize { blocks.iter().map(|block| block_inlines(block).len()).sum() };
+    let count_cells =
+        |cells: &[TableCell]| -> usize { cells.iter().map(|cell| inline_count(cell)).sum() };
+    match (landmark, blocks.get(found_block_index(landmark))?) {
+        (
+            FoundNode::ListItem {
+                item_index, path, ..
+            },
+            Block::List(list),
+        ) if path.is_empty() => {
+            let start = list.items[..*item_index]
+                .iter()
+                .map(|item| count_blocks(&item.blocks))
+                .sum::<usize>();
+            Some(start..start + count_blocks(&list.items[*item_index].blocks))
+        }
+        (FoundNode::TableRow { row_index, row, .. }, Block::Table(table)) => {
+            let start = table.rows[..*row_index]
+                .iter()
+                .map(|row| count_cells(row))
+                .sum::<usize>();
+            Some(start..start + count_cells(row))
+        }
+        (
+            FoundNode::TableCell {
+                row_index,
+                column_index,
+                cell,
+                ..
+            },
+            Block::Table(table),
+        ) => {
+            let row = &table.rows[*row_index];
+            let start = table.rows[..*row_index]
+                .iter()
+                .map(|row| count_cells(row))
+                .sum::<usize>()
+                + count_cells(&row[..(*column_index).min(row.len())]);
+            Some(start..start + inline_count(cell))
+        }
+        // The span of an inline element holds its content, but not the element itself.
+        (
+            FoundNode::Inline {
+                position, inline, ..
+            },
+            _,
+        ) => Some(position + 1..position + inline_count(std::slice::from_ref(*inline))),
+        _ => None,
+    }
 }
 
 fn heading_level(kind: &HeadingKind) -> usize {
@@ -108,34 +1124,54 @@ fn find_section_end(blocks: &[Block], heading_index: usize, level: usize) -> usi
     end
 }
 
-fn apply_scope(blocks: &[Block], selector: &Selector) -> Result<Scope, SpliceError> {
-    if selector.after.is_some() && selector.within.is_some() {
+fn apply_scope(
+    blocks: &[Block],
+    selector: &Selector,
+    matcher: &mut Matcher<'_>,
+) -> Result<Scope, SpliceError> {
+    let modifiers = [
+        selector.after.is_some(),
+        selector.within.is_some(),
+        selector.between.is_some(),
+        selector.within_slide.is_some(),
+    ];
+    if modifiers.into_iter().filter(|&set| set).count() > 1 {
         return Err(SpliceError::ConflictingScopeModifiers);
     }
 
     if let Some(after_selector) = selector.after.as_ref() {
-        let (landmark, _) = locate(blocks, after_selector)?;
+        let (landmark, _) = locate_with(blocks, after_selector, matcher)?;
         match landmark {
             FoundNode::Block { index, .. } => Ok(Scope {
                 block_start: index.saturating_add(1),
                 block_end: blocks.len(),
-                list_restriction: None,
-            }),
-            FoundNode::ListItem {
-                block_index,
-                item_index,
-                ..
-            } => Ok(Scope {
-                block_start: block_index.saturating_add(1),
-                block_end: blocks.len(),
-                list_restriction: Some(ListRestriction {
-                    block_index,
-                    start_item: Some(item_index),
-                }),
+                nested_restriction: None,
             }),
+            // The search resumes with the next item, the next row of a table, or the next
+            // inline element. Other nested nodes are searched from the next block on.
+            nested => {
+                let block_index = found_block_index(&nested);
+                let start_item = match (&nested, nested_kind(selector)) {
+                    (_, Some(NestedKind::Inline(_))) => {
+                        inline_span(blocks, &nested).map(|span| span.end.checked_sub(1))
+                    }
+                    (FoundNode::Inline { .. }, _) => None,
+                    _ => Some(Some(nested_position(&nested))),
+                };
+                Ok(Scope {
+                    block_start: block_index.saturating_add(1),
+                    block_end: blocks.len(),
+                    nested_restriction: start_item.map(|start_item| NestedRestriction {
+                        block_index,
+                        start_item,
+                        end_item: None,
+                    }),
+                })
+            }
         }
     } else if let Some(within_selector) = selector.within.as_ref() {
-        let (landmark, _) = locate(blocks, within_selector)?;
+        let (landmark, _) = locate_with(blocks, within_selector, matcher)?;
+        let searches_inlines = matches!(nested_kind(selector), Some(NestedKind::Inline(_)));
         match landmark {
             FoundNode::Block { index, block } => match block {
                 Block::Heading(heading) => {
@@ -145,117 +1181,254 @@ fn apply_scope(blocks: &[Block], selector: &Selector) -> Result<Scope, SpliceErr
                     Ok(Scope {
                         block_start: start,
                         block_end: end,
-                        list_restriction: None,
+                        nested_restriction: None,
                     })
                 }
-                Block::List(_) => Ok(Scope {
+                Block::List(_) | Block::Table(_) => Ok(Scope {
                     block_start: index,
                     block_end: index + 1,
-                    list_restriction: Some(ListRestriction {
+                    nested_restriction: Some(NestedRestriction {
                         block_index: index,
                         start_item: None,
+                        end_item: None,
                     }),
                 }),
+                _ if searches_inlines => Ok(Scope {
+                    block_start: index,
+                    block_end: index + 1,
+                    nested_restriction: None,
+                }),
                 _ => Err(SpliceError::NodeNotFound),
             },
-            FoundNode::ListItem { .. } => Err(SpliceError::NodeNotFound),
+            // Inline elements are searched among those nested in the landmark.
+            nested if searches_inlines => {
+                let block_index = found_block_index(&nested);
+                let span = inline_span(blocks, &nested).ok_or(SpliceError::NodeNotFound)?;
+                Ok(Scope {
+                    block_start: block_index,
+                    block_end: block_index + 1,
+                    nested_restriction: Some(NestedRestriction {
+                        block_index,
+                        start_item: span.start.checked_sub(1),
+                        end_item: Some(span.end),
+                    }),
+                })
+            }
+            // Within a row, only its cells are searched.
+            FoundNode::TableRow {
+                block_index,
+                row_index,
+                ..
+            } => Ok(Scope {
+                block_start: block_index,
+                block_end: block_index + 1,
+                nested_restriction: Some(NestedRestriction {
+                    block_index,
+                    start_item: row_index.checked_sub(1),
+                    end_item: Some(row_index + 1),
+                }),
+            }),
+            FoundNode::ContainedBlock { .. }
+            | FoundNode::ListItem { .. }
+            | FoundNode::TableCell { .. }
+            | FoundNode::Inline { .. } => Err(SpliceError::NodeNotFound),
         }
+    } else if let Some(between) = selector.between.as_ref() {
+        let (start, _) = locate_with(blocks, &between.start, matcher)?;
+        let start_index = found_block_index(&start);
+        let ends: Vec<usize> = collect_matches(blocks, &between.end, matcher)?
+            .iter()
+            .map(found_block_index)
+            .filter(|&index| index > start_index)
+            .collect();
+        let end_index = ordinal_index(&between.end, ends.len())
+            .map(|ordinal| ends[ordinal])
+            .ok_or(SpliceError::NodeNotFound)?;
+        Ok(Scope {
+            block_start: start_index + 1,
+            block_end: end_index,
+            nested_restriction: None,
+        })
+    } else if let Some(slide) = selector.within_slide {
+        let range = slide_range(blocks, slide).ok_or(SpliceError::NodeNotFound)?;
+        Ok(Scope {
+            block_start: range.start,
+            block_end: range.end,
+            nested_restriction: None,
+        })
     } else {
         Ok(Scope::entire_document(blocks.len()))
     }
 }
 
 fn block_matches_selector(block: &Block, selector: &Selector) -> bool {
+    // Only list items are nested in lists or have a task state.
+    if selector.select_depth.is_some() || selector.task_state.is_some() {
+        return false;
+    }
+
     if let Some(type_str) = &selector.select_type {
         if !block_type_matches(block, type_str) {
             return false;
         }
     }
 
-    if selector.select_contains.is_some() || selector.select_regex.is_some() {
-        let text_content = block_to_text(block);
+    if let Some(tag) = &selector.select_tag {
+        let Block::HtmlBlock(html) = block else {
+            return false;
+        };
+        if !html_tag(html).is_some_and(|name| name.eq_ignore_ascii_case(tag)) {
+            return false;
+        }
+    }
 
-        if let Some(contains_str) = &selector.select_contains {
-            if !text_content.contains(contains_str) {
-                return false;
-            }
+    if let Some(needle) = &selector.select_info_contains {
+        if !code_info(block).is_some_and(|info| info.contains(needle.as_str())) {
+            return false;
         }
+    }
 
-        if let Some(re) = &selector.select_regex {
-            if !re.is_match(&text_content) {
-                return false;
-            }
+    if has_text_criteria(selector) && !text_matches(selector, &match_text(block)) {
+        return false;
+    }
+
+    if let Some(prefix) = &selector.select_fingerprint {
+        if !fingerprint_matches(&block_fingerprint(block), prefix) {
+            return false;
         }
     }
 
     true
 }
 
-fn list_item_matches_filters(selector: &Selector, item: &ListItem) -> bool {
-    if selector.select_contains.is_some() || selector.select_regex.is_some() {
-        let text_content = list_item_to_text(item);
+fn has_text_criteria(selector: &Selector) -> bool {
+    selector.select_contains.is_some()
+        || selector.select_contains_ci.is_some()
+        || selector.select_regex.is_some()
+        || selector.select_not_contains.is_some()
+        || selector.select_not_regex.is_some()
+}
 
-        if let Some(contains_str) = &selector.select_contains {
-            if !text_content.contains(contains_str) {
-                return false;
-            }
+/// Checks the text criteria: `text` must contain `select_contains` (and `select_contains_ci`
+/// once both are normalized) and match `select_regex`, but neither contain
+/// `select_not_contains` nor match `select_not_regex`.
+fn text_matches(selector: &Selector, text: &str) -> bool {
+    selector
+        .select_contains
+        .as_ref()
+        .is_none_or(|needle| text.contains(needle.as_str()))
+        && selector
+            .select_contains_ci
+            .as_ref()
+            .is_none_or(|needle| normalize_text(text).contains(&normalize_text(needle)))
+        && selector
+            .select_regex
+            .as_ref()
+            .is_none_or(|re| re.is_match(text))
+        && !selector
+            .select_not_contains
+            .as_ref()
+            .is_some_and(|needle| text.contains(needle.as_str()))
+        && !selector
+            .select_not_regex
+            .as_ref()
+            .is_some_and(|re| re.is_match(text))
+}
+
+/// Lowercases `text` and collapses its runs of whitespace into single spaces, as
+/// `select_contains_ci` compares texts.
+///
+/// ```
+/// use md_splice_lib::locator::normalize_text;
+///
+/// assert_eq!(normalize_text("  Getting\n  STARTED "), "getting started");
+/// ```
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Returns the text that `select_contains` and `select_regex` are matched against. Raw HTML
+/// blocks other than comments are matched by their source, so that their tags and attributes
+/// can be selected.
+fn match_text(block: &Block) -> String {
+    match block {
+        Block::HtmlBlock(html) if comment_text(html).is_none() => html.clone(),
+        _ => block_to_text(block),
+    }
+}
+
+fn nested_node_matches_filters(selector: &Selector, node: &FoundNode) -> bool {
+    if selector.select_tag.is_some()
+        || selector.select_info_contains.is_some()
+        || selector.select_slug.is_some()
+        || ((selector.select_depth.is_some() || selector.task_state.is_some())
+            && !matches!(node, FoundNode::ListItem { .. }))
+    {
+        return false;
+    }
+
+    if let (Some(status), FoundNode::ListItem { item, .. }) = (selector.task_state, node) {
+        if !status.matches(item.task) {
+            return false;
         }
+    }
 
-        if let Some(re) = &selector.select_regex {
-            if !re.is_match(&text_content) {
-                return false;
-            }
+    if has_text_criteria(selector) && !text_matches(selector, &node_text(node)) {
+        return false;
+    }
+
+    if let Some(prefix) = &selector.select_fingerprint {
+        if !fingerprint_matches(&node_fingerprint(node), prefix) {
+            return false;
         }
     }
 
     true
 }
 
-fn collect_scoped_list_items<'a>(
+fn collect_scoped_nested_nodes<'a>(
     blocks: &'a [Block],
     selector: &Selector,
+    kind: NestedKind,
     scope: Scope,
-) -> Vec<(usize, usize, &'a ListItem)> {
-    let mut items = Vec::new();
-
-    if let Some(restriction) = scope.list_restriction {
-        if let Some(Block::List(list)) = blocks.get(restriction.block_index) {
-            for (item_index, item) in list.items.iter().enumerate() {
-                if let Some(start) = restriction.start_item {
-                    if item_index <= start {
-                        continue;
-                    }
-                }
-
-                if list_item_matches_filters(selector, item) {
-                    items.push((restriction.block_index, item_index, item));
-                }
-            }
-        }
-    }
-
-    for block_index in scope.block_start..scope.block_end {
-        if let Some(restriction) = scope.list_restriction {
-            if restriction.block_index == block_index {
-                continue;
-            }
-        }
-
-        if let Some(Block::List(list)) = blocks.get(block_index) {
-            for (item_index, item) in list.items.iter().enumerate() {
-                if list_item_matches_filters(selector, item) {
-                    items.push((block_index, item_index, item));
-                }
+    matcher: &mut Matcher<'_>,
+) -> Result<Vec<FoundNode<'a>>, SpliceError> {
+    // The restricted block comes first: it holds the landmark of an `after` modifier.
+    let restricted = scope
+        .nested_restriction
+        .map(|restriction| (restriction.block_index, Some(restriction)));
+    let following = (scope.block_start..scope.block_end)
+        .filter(|&block_index| restricted.is_none_or(|(restricted, _)| restricted != block_index))
+        .map(|block_index| (block_index, None));
+
+    let mut nodes = Vec::new();
+    for (block_index, restriction) in restricted.into_iter().chain(following) {
+        matcher.check_deadline()?;
+        let Some(block) = blocks.get(block_index) else {
+            continue;
+        };
+        for (position, node) in nested_nodes(block_index, block, kind)
+            .into_iter()
+            .enumerate()
+        {
+            let in_scope = restriction.is_none_or(|restriction: NestedRestriction| {
+                restriction.contains(nested_position(&node))
+            });
+            if in_scope && matcher.nested_node_matches(blocks, position, &node, selector) {
+                nodes.push(node);
             }
         }
     }
-
-    items
+    Ok(nodes)
 }
 
 /// Finds the first node in the document that matches all the given selectors.
 ///
-/// The function can find top-level `Block` nodes or nested `ListItem` nodes.
+/// The function can find top-level `Block` nodes, nested `ListItem`, `TableRow` and
+/// `TableCell` nodes, or `Inline` links, images and code spans.
 ///
 /// # Arguments
 ///
@@ -271,150 +1444,212 @@ pub fn locate<'a>(
     blocks: &'a [Block],
     selector: &Selector,
 ) -> Result<(FoundNode<'a>, bool), SpliceError> {
-    let ordinal_index = selector.select_ordinal.saturating_sub(1);
-    let scope = apply_scope(blocks, selector)?;
-
-    // --- Search Strategy ---
-    // If the selector type is for a list item, we perform a nested search.
-    // Otherwise, we perform the standard top-level block search.
-    if let Some(type_str) = &selector.select_type {
-        if is_list_item_type(type_str) {
-            // --- List Item Search Logic ---
-            let matches = collect_scoped_list_items(blocks, selector, scope);
-
-            let is_ambiguous = matches.len() > 1;
-
-            return matches
-                .get(ordinal_index)
-                .map(|(block_index, item_index, item)| {
-                    (
-                        FoundNode::ListItem {
-                            block_index: *block_index,
-                            item_index: *item_index,
-                            item,
-                        },
-                        is_ambiguous,
-                    )
-                })
-                .ok_or(SpliceError::NodeNotFound);
-        }
-    }
+    locate_with(blocks, selector, &mut Matcher::Direct)
+}
 
-    // --- Block Search Logic (default) ---
-    let matches: Vec<_> = (scope.block_start..scope.block_end)
-        .filter_map(|index| {
-            let block = blocks.get(index)?;
-            if block_matches_selector(block, selector) {
-                Some((index, block))
-            } else {
-                None
-            }
-        })
-        .collect();
+/// Behaves like [`locate`], reusing and filling the match results memoized in `cache`.
+///
+/// The cache must only be used with successive versions of the same document, and
+/// every mutation must be reported through [`LocateCache::invalidate`].
+pub(crate) fn locate_cached<'a>(
+    blocks: &'a [Block],
+    selector: &Selector,
+    cache: &mut LocateCache,
+) -> Result<(FoundNode<'a>, bool), SpliceError> {
+    cache.sync(blocks);
+    locate_with(blocks, selector, &mut Matcher::Cached(cache))
+}
 
+fn locate_with<'a>(
+    blocks: &'a [Block],
+    selector: &Selector,
+    matcher: &mut Matcher<'_>,
+) -> Result<(FoundNode<'a>, bool), SpliceError> {
+    let mut matches = collect_matches(blocks, selector, matcher)?;
     let is_ambiguous = matches.len() > 1;
 
-    matches
-        .get(ordinal_index)
-        .map(|(index, block)| {
-            (
-                FoundNode::Block {
-                    index: *index,
-                    block,
-                },
-                is_ambiguous,
-            )
-        })
-        .ok_or(SpliceError::NodeNotFound)
+    let ordinal_index = ordinal_index(selector, matches.len()).ok_or(SpliceError::NodeNotFound)?;
+    Ok((matches.swap_remove(ordinal_index), is_ambiguous))
 }
 
-/// Finds all nodes matching the selector criteria.
+/// Returns the index of the match `selector` picks among `count` ranked matches, counting
+/// `select_ordinal` from the last one when `select_last` is set, or `None` when there are
+/// too few matches.
+fn ordinal_index(selector: &Selector, count: usize) -> Option<usize> {
+    let ordinal = selector.select_ordinal.max(1);
+    if selector.select_last {
+        count.checked_sub(ordinal)
+    } else {
+        (ordinal <= count).then(|| ordinal - 1)
+    }
+}
+
+/// Finds all nodes matching the selector criteria, ranked by its [`TieBreak`] order.
 pub fn locate_all<'a>(
     blocks: &'a [Block],
     selector: &Selector,
 ) -> Result<Vec<FoundNode<'a>>, SpliceError> {
-    let scope = apply_scope(blocks, selector)?;
+    collect_matches(blocks, selector, &mut Matcher::Direct)
+}
 
-    if let Some(type_str) = &selector.select_type {
-        if is_list_item_type(type_str) {
-            let matches = collect_scoped_list_items(blocks, selector, scope)
-                .into_iter()
-                .map(|(block_index, item_index, item)| FoundNode::ListItem {
-                    block_index,
-                    item_index,
-                    item,
-                })
-                .collect();
+/// Behaves like [`locate_all`], reusing and filling the match results memoized in `cache`.
+pub(crate) fn locate_all_cached<'a>(
+    blocks: &'a [Block],
+    selector: &Selector,
+    cache: &mut LocateCache,
+) -> Result<Vec<FoundNode<'a>>, SpliceError> {
+    cache.sync(blocks);
+    collect_matches(blocks, selector, &mut Matcher::Cached(cache))
+}
 
-            return Ok(matches);
+/// Collects every node matching `selector` in its scope, ranks them by its tie-break
+/// order, and enforces its `max_matches` limit.
+fn collect_matches<'a>(
+    blocks: &'a [Block],
+    selector: &Selector,
+    matcher: &mut Matcher<'_>,
+) -> Result<Vec<FoundNode<'a>>, SpliceError> {
+    let scope = apply_scope(blocks, selector, matcher)?;
+
+    // --- Search Strategy ---
+    // If the selector type is for a list item, a table row or a cell, or an inline element,
+    // we perform a nested search. Otherwise, we perform the standard top-level block search.
+    let mut matches: Vec<FoundNode<'a>> = if let Some(kind) = nested_kind(selector) {
+        collect_scoped_nested_nodes(blocks, selector, kind, scope, matcher)?
+    } else {
+        let mut nodes = Vec::new();
+        for index in scope.block_start..scope.block_end.min(blocks.len()) {
+            matcher.check_deadline()?;
+            if matcher.block_matches(blocks, index, selector) {
+                nodes.push(FoundNode::Block {
+                    index,
+                    block: &blocks[index],
+                });
+            }
+            if selector.recursive && matcher.covers_line(index, selector) {
+                collect_contained_blocks(index, &blocks[index], &[], selector, &mut nodes);
+            }
+        }
+        nodes
+    };
+
+    if let Some(max_matches) = selector.max_matches {
+        if matches.len() > max_matches {
+            return Err(SpliceError::TooManyMatches {
+                found: matches.len(),
+                max: max_matches,
+            });
         }
     }
 
-    let matches = (scope.block_start..scope.block_end)
-        .filter_map(|index| {
-            let block = blocks.get(index)?;
-            if block_matches_selector(block, selector) {
-                Some(FoundNode::Block { index, block })
-            } else {
-                None
-            }
-        })
-        .collect();
+    if selector.tie_break == TieBreak::DeepestFirst && matches.len() > 1 {
+        let depths = section_depths(blocks);
+        // The sort is stable, so matches at the same depth stay in document order.
+        matches.sort_by_key(|found| Reverse(depths[found_block_index(found)]));
+    }
 
     Ok(matches)
 }
 
-/// Checks if a block matches the string representation of its type.
-/// This version is more explicit and robust for handling heading levels.
-fn block_type_matches(block: &Block, type_str: &str) -> bool {
-    let type_str = type_str.to_lowercase();
-    match block {
-        Block::Paragraph(_) => type_str == "p" || type_str == "paragraph",
-        Block::Heading(h) => {
-            let level = match h.kind {
-                HeadingKind::Atx(level) => level,
-                HeadingKind::Setext(SetextHeading::Level1) => 1,
-                HeadingKind::Setext(SetextHeading::Level2) => 2,
-            };
-            match type_str.as_str() {
-                "heading" => true,
-                "h1" if level == 1 => true,
-                "h2" if level == 2 => true,
-                "h3" if level == 3 => true,
-                "h4" if level == 4 => true,
-                "h5" if level == 5 => true,
-                "h6" if level == 6 => true,
-                _ => false,
+/// Pushes the blocks nested in `block`, found at `block_index` or in the block found there
+/// through `path`, that match `selector`, each container before the blocks it holds.
+fn collect_contained_blocks<'a>(
+    block_index: usize,
+    block: &'a Block,
+    path: &[usize],
+    selector: &Selector,
+    nodes: &mut Vec<FoundNode<'a>>,
+) {
+    for (item_index, children) in child_blocks(block) {
+        for (child_index, child) in children.iter().enumerate() {
+            let mut child_path = path.to_vec();
+            child_path.extend(item_index);
+            child_path.push(child_index);
+            if contained_block_matches(children, child_index, selector) {
+                nodes.push(FoundNode::ContainedBlock {
+                    block_index,
+                    path: child_path.clone(),
+                    block: child,
+                });
             }
+            collect_contained_blocks(block_index, child, &child_path, selector, nodes);
         }
-        Block::List(_) => type_str == "list",
-        Block::Table(_) => type_str == "table",
-        Block::BlockQuote(_) => type_str == "blockquote",
-        Block::CodeBlock(_) => type_str == "code" || type_str == "codeblock",
-        Block::HtmlBlock(_) => type_str == "html" || type_str == "htmlblock",
-        Block::ThematicBreak => type_str == "thematicbreak",
-        Block::Definition(_) => type_str == "definition",
-        Block::FootnoteDefinition(_) => type_str == "footnotedefinition",
-        Block::GitHubAlert(alert) => {
-            let alert_type = match alert.alert_type {
-                markdown_ppp::ast::GitHubAlertType::Note => "note",
-                markdown_ppp::ast::GitHubAlertType::Tip => "tip",
-                markdown_ppp::ast::GitHubAlertType::Important => "important",
-                markdown_ppp::ast::GitHubAlertType::Warning => "warning",
-                markdown_ppp::ast::GitHubAlertType::Caution => "caution",
-            };
-
-            type_str == "githubalert"
-                || type_str == "alert"
-                || type_str == alert_type
-                || type_str == format!("alert-{}", alert_type)
-        }
-        Block::Empty => type_str == "empty",
     }
 }
 
+/// Returns the blocks directly nested in a block quote, an alert or a footnote definition,
+/// or those of each item of a list along with the item's index.
+fn child_blocks(block: &Block) -> Vec<(Option<usize>, &[Block])> {
+    match block {
+        Block::BlockQuote(blocks) => vec![(None, blocks.as_slice())],
+        Block::GitHubAlert(alert) => vec![(None, alert.blocks.as_slice())],
+        Block::FootnoteDefinition(definition) => vec![(None, definition.blocks.as_slice())],
+        Block::List(list) => list
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (Some(index), item.blocks.as_slice()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Checks a block nested in a container against `selector`, its siblings standing in for
+/// the document's blocks. Headings nested in containers have no anchor, so `select_slug`
+/// never matches them.
+fn contained_block_matches(siblings: &[Block], index: usize, selector: &Selector) -> bool {
+    selector.select_slug.is_none()
+        && follows_annotation(siblings, index, selector)
+        && precedes_definition(siblings, index, selector)
+        && block_matches_selector(&siblings[index], selector)
+        && meets_length_criteria(selector, || block_word_count(siblings, index))
+}
+
+/// Returns the top-level block holding `found`.
+pub fn found_block_index(found: &FoundNode) -> usize {
+    match found {
+        FoundNode::Block { index, .. } => *index,
+        FoundNode::ContainedBlock { block_index, .. }
+        | FoundNode::ListItem { block_index, .. }
+        | FoundNode::TableRow { block_index, .. }
+        | FoundNode::TableCell { block_index, .. }
+        | FoundNode::Inline { block_index, .. } => *block_index,
+    }
+}
+
+/// Returns, for every top-level block, the level of the heading whose section it belongs
+/// to (a heading's own level for headings, 0 before the first heading).
+fn section_depths(blocks: &[Block]) -> Vec<u8> {
+    let mut current = 0;
+    blocks
+        .iter()
+        .map(|block| {
+            if let Some(level) = get_heading_level(block) {
+                current = level;
+            }
+            current
+        })
+        .collect()
+}
+
+/// Checks if a block matches one of the [`SELECT_TYPES`] named `type_str`. A type followed
+/// by `:LANG`, such as `code:rust`, only matches code blocks in that language (see
+/// [`code_language`]), ignoring case.
+fn block_type_matches(block: &Block, type_str: &str) -> bool {
+    if let Some((type_str, language)) = type_str.split_once(':') {
+        return block_type_matches(block, type_str)
+            && code_language(block).is_some_and(|lang| lang.eq_ignore_ascii_case(language));
+    }
+    SELECT_TYPES
+        .iter()
+        .any(|select_type| match select_type.kind {
+            TypeKind::Block(matches) => select_type.is_named(type_str) && matches(block),
+            TypeKind::Nested(_) => false,
+        })
+}
+
 /// Recursively extracts the plain text from a slice of `Inline` nodes.
-fn inlines_to_text(inlines: &[Inline]) -> String {
+pub(crate) fn inlines_to_text(inlines: &[Inline]) -> String {
     inlines
         .iter()
         .map(|inline| -> String {
@@ -480,17 +1715,19 @@ pub(crate) fn block_to_text(block: &Block) -> String {
             .map(block_to_text)
             .collect::<Vec<_>>()
             .join("\n"),
+        // Comments are matched by their text so that annotations can be selected.
+        Block::HtmlBlock(html) => comment_text(html).unwrap_or_default().to_string(),
         // Per spec, these blocks have no user-facing text content
-        Block::ThematicBreak | Block::HtmlBlock(_) | Block::Definition(_) | Block::Empty => {
-            String::new()
-        }
+        Block::ThematicBreak | Block::Definition(_) | Block::Empty => String::new(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::limits::{Limits, Stopwatch};
     use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+    use std::time::Duration;
 
     const TEST_MARKDOWN: &str = r#"# A Heading
 
@@ -508,6 +1745,18 @@ fn main() {
 ```
 "#;
 
+    #[test]
+    fn test_word_count_skips_punctuation_and_measures_heading_sections() {
+        assert_eq!(word_count("Hello, world - again!"), 3);
+        assert_eq!(word_count(" --- * "), 0);
+
+        let markdown = "# Guide\n\n## Empty\n\n## Full\n\nTwo words.\n\n### Sub\n";
+        let doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+        assert_eq!(block_word_count(&doc.blocks, 0), 5);
+        assert_eq!(block_word_count(&doc.blocks, 1), 0);
+        assert_eq!(block_word_count(&doc.blocks, 2), 3);
+    }
+
     #[test]
     fn test_l1_select_first_paragraph_by_type() {
         // L1 (Simple Type): Select the first paragraph.
@@ -602,6 +1851,11 @@ fn main() {
         let doc = parse_markdown(MarkdownParserState::default(), TEST_MARKDOWN).unwrap();
         let selector = Selector {
             select_regex: Some(Regex::new(r"Hello, World!").unwrap()),
+            select_fingerprint: None,
+            select_annotation: None,
+            select_tag: None,
+            select_info_contains: None,
+            select_slug: None,
             select_ordinal: 1,
             ..Default::default()
         };
@@ -782,6 +2036,7 @@ More usage guidance.
             block_index,
             item_index,
             item,
+            ..
         } = found
         {
             assert_eq!(block_index, 3);
@@ -813,6 +2068,7 @@ More usage guidance.
             block_index,
             item_index,
             item,
+            ..
         } = found
         {
             assert_eq!(block_index, 3);
@@ -847,6 +2103,7 @@ More usage guidance.
             block_index,
             item_index,
             item,
+            ..
         } = found
         {
             assert_eq!(block_index, 1);
@@ -948,6 +2205,7 @@ More usage guidance.
             block_index,
             item_index,
             item,
+            ..
         } = found
         {
             assert_eq!(
@@ -1072,6 +2330,7 @@ More usage guidance.
             block_index,
             item_index,
             item,
+            ..
         } = found
         {
             assert_eq!(
@@ -1090,4 +2349,108 @@ More usage guidance.
             panic!("Expected to find a list item after Step zero");
         }
     }
+
+    #[test]
+    fn test_locate_cached_tracks_invalidated_regions() {
+        let doc = parse_markdown(MarkdownParserState::default(), TEST_MARKDOWN).unwrap();
+        let mut blocks = doc.blocks;
+        let mut cache = LocateCache::default();
+        let paragraph = Selector {
+            select_type: Some("p".to_string()),
+            select_ordinal: 2,
+            ..Default::default()
+        };
+        let list_item = Selector {
+            select_type: Some("li".to_string()),
+            select_contains: Some("Another".to_string()),
+            select_ordinal: 1,
+            ..Default::default()
+        };
+
+        let (found, _) = locate_cached(&blocks, &paragraph, &mut cache).unwrap();
+        assert!(matches!(found, FoundNode::Block { index: 3, .. }));
+        locate_cached(&blocks, &list_item, &mut cache).unwrap();
+
+        // Replace the list with two paragraphs, shifting every later block.
+        let inserted = parse_markdown(MarkdownParserState::default(), "New one.\n\nNew two.\n")
+            .unwrap()
+            .blocks;
+        blocks.splice(2..3, inserted);
+        cache.invalidate(2..4, 1);
+
+        let cached = locate_cached(&blocks, &paragraph, &mut cache).unwrap();
+        assert_eq!(cached, locate(&blocks, &paragraph).unwrap());
+        assert!(matches!(cached.0, FoundNode::Block { index: 2, .. }));
+        assert!(matches!(
+            locate_cached(&blocks, &list_item, &mut cache),
+            Err(SpliceError::NodeNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_locating_stops_once_the_operation_runs_out_of_time() {
+        let doc = parse_markdown(MarkdownParserState::default(), TEST_MARKDOWN).unwrap();
+        let limits = Limits {
+            operation_timeout: Some(Duration::ZERO),
+            ..Limits::default()
+        };
+        let stopwatch = Stopwatch::start(&limits);
+        std::thread::sleep(Duration::from_millis(1));
+        let mut cache = LocateCache::default();
+        cache.set_deadline(stopwatch.deadline(0));
+        let paragraph = Selector {
+            select_type: Some("p".to_string()),
+            select_ordinal: 1,
+            ..Default::default()
+        };
+        let list_item = Selector {
+            select_type: Some("li".to_string()),
+            select_ordinal: 1,
+            ..Default::default()
+        };
+
+        for result in [
+            locate_cached(&doc.blocks, &paragraph, &mut cache).map(|_| ()),
+            locate_all_cached(&doc.blocks, &list_item, &mut cache).map(|_| ()),
+            locate_cached(&doc.blocks[1..], &paragraph, &mut cache.tail(1)).map(|_| ()),
+        ] {
+            let err = result.unwrap_err();
+            assert!(matches!(err, SpliceError::TimeLimitExceeded(_)));
+            assert!(err.to_string().contains("operation 1 took"));
+        }
+
+        cache.set_deadline(Deadline::default());
+        assert!(locate_cached(&doc.blocks, &paragraph, &mut cache).is_ok());
+    }
+
+    #[test]
+    fn test_select_types_match_names_case_insensitively() {
+        let blocks = parse_markdown(
+            MarkdownParserState::default(),
+            "# Title\n\nText.\n\n> [!TIP]\n> Hint.\n\n<!-- slot -->\n",
+        )
+        .unwrap()
+        .blocks;
+
+        for block in &blocks {
+            let name = crate::infer::block_type_name(block);
+            assert!(block_type_matches(block, &name), "{name} does not match");
+        }
+        assert!(block_type_matches(&blocks[0], "HEADING"));
+        assert!(block_type_matches(&blocks[1], "Paragraph"));
+        assert!(block_type_matches(&blocks[2], "alert-tip"));
+        assert!(!block_type_matches(&blocks[2], "note"));
+        assert!(block_type_matches(&blocks[3], "html"));
+        let selector = |select_type: &str| Selector {
+            select_type: Some(select_type.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            nested_kind(&selector("ListItem")),
+            Some(NestedKind::ListItem(1))
+        );
+        assert_eq!(nested_kind(&selector("TD")), Some(NestedKind::TableCell));
+        assert_eq!(nested_kind(&selector("table")), None);
+        assert!(!block_type_matches(&blocks[1], "li"));
+    }
 }
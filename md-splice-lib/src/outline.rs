@@ -0,0 +1,98 @@
+//! Builds the nested heading structure of a document for outlines and navigation.
+
+use crate::sections::find_heading_section_end;
+use crate::slugify::heading_slugs;
+use markdown_ppp::ast::Block;
+use serde::Serialize;
+use std::ops::Range;
+
+/// A heading together with the headings nested inside its section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeadingNode {
+    /// The heading level, from 1 to 6.
+    pub level: u8,
+    /// The plain text of the heading, with inline formatting and its explicit `{#id}`
+    /// attribute removed.
+    pub text: String,
+    /// The explicit `{#id}` of the heading, or the anchor GitHub generates for it, including
+    /// `-1`, `-2`, ... suffixes for repeated headings.
+    pub slug: String,
+    /// Top-level blocks of the section: the heading itself and everything up to the next
+    /// heading of the same or a higher level.
+    pub blocks: Range<usize>,
+    /// Headings of a deeper level inside this section, in document order.
+    pub children: Vec<HeadingNode>,
+}
+
+/// Returns the headings of `blocks` as a tree, where each heading's children are the
+/// deeper headings inside its section.
+///
+/// Skipped levels are tolerated: an `h3` directly under an `h1` becomes its child, and
+/// headings that appear before the first top-level heading are returned as roots.
+pub fn heading_tree(blocks: &[Block]) -> Vec<HeadingNode> {
+    let headings: Vec<HeadingNode> = heading_slugs(blocks)
+        .into_iter()
+        .map(|heading| HeadingNode {
+            level: heading.level,
+            text: heading.text,
+            slug: heading.anchor,
+            blocks: heading.index..find_heading_section_end(blocks, heading.index, heading.level),
+            children: Vec::new(),
+        })
+        .collect();
+
+    let mut headings = headings.into_iter().peekable();
+    nest(&mut headings, 0)
+}
+
+/// Collects consecutive headings deeper than `parent_level`, attaching deeper ones as children.
+fn nest(
+    headings: &mut std::iter::Peekable<impl Iterator<Item = HeadingNode>>,
+    parent_level: u8,
+) -> Vec<HeadingNode> {
+    let mut nodes = Vec::new();
+    while let Some(mut node) = headings.next_if(|heading| heading.level > parent_level) {
+        node.children = nest(headings, node.level);
+        nodes.push(node);
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    #[test]
+    fn test_heading_tree_nests_sections_and_tolerates_skipped_levels() {
+        let markdown =
+            "## Preface\n\n# Guide\n\nIntro.\n\n### Deep\n\n## Setup\n\n## Setup\n\n# Appendix {#extra}\n";
+        let doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        let tree = heading_tree(&doc.blocks);
+
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree[0].text, "Preface");
+        assert_eq!(tree[0].blocks, 0..1);
+        let guide = &tree[1];
+        assert_eq!(guide.blocks, 1..6);
+        let children: Vec<(&str, &str, u8)> = guide
+            .children
+            .iter()
+            .map(|child| (child.text.as_str(), child.slug.as_str(), child.level))
+            .collect();
+        assert_eq!(
+            children,
+            vec![
+                ("Deep", "deep", 3),
+                ("Setup", "setup", 2),
+                ("Setup", "setup-1", 2)
+            ]
+        );
+        assert_eq!(
+            (tree[2].text.as_str(), tree[2].slug.as_str()),
+            ("Appendix", "extra")
+        );
+        assert!(tree[2].children.is_empty());
+    }
+}
@@ -0,0 +1,71 @@
+//! Restricts which operations a batch may contain.
+//!
+//! Operations normally read their content from files when given a `content_file`,
+//! `value_file`, or `source_file`. A server running batches received from untrusted
+//! clients would let those clients read any file it can access, so
+//! [`OperationPolicy::sandboxed`] rejects such operations before anything is applied.
+
+use crate::error::SpliceError;
+use crate::transaction::Operation;
+
+/// What the operations of a batch are allowed to do, checked by
+/// [`OperationPolicy::check_operations`] and
+/// [`MarkdownDocument::apply_with_policy`](crate::MarkdownDocument::apply_with_policy).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationPolicy {
+    /// Whether operations may read files (or stdin, with `-`) through `content_file`,
+    /// `value_file`, or the `source_file` of `import_section`.
+    pub allow_filesystem: bool,
+}
+
+impl Default for OperationPolicy {
+    fn default() -> Self {
+        Self {
+            allow_filesystem: true,
+        }
+    }
+}
+
+impl OperationPolicy {
+    /// A policy for batches received from untrusted clients, whose content must be inline.
+    pub fn sandboxed() -> Self {
+        Self {
+            allow_filesystem: false,
+        }
+    }
+
+    /// Fails with [`SpliceError::OperationNotAllowed`] if an operation of the batch does
+    /// something the policy forbids. Operations are numbered from 1 in error messages.
+    pub fn check_operations(&self, operations: &[Operation]) -> Result<(), SpliceError> {
+        if self.allow_filesystem {
+            return Ok(());
+        }
+        for (index, operation) in operations.iter().enumerate() {
+            if let Some(field) = filesystem_field(operation) {
+                return Err(SpliceError::OperationNotAllowed(format!(
+                    "operation {} ({}) reads `{field}`, but filesystem access is disabled",
+                    index + 1,
+                    operation.kind()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the field through which `operation` reads a file, if any.
+fn filesystem_field(operation: &Operation) -> Option<&'static str> {
+    let reads_file = match operation {
+        Operation::Insert(op) => op.content_file.is_some(),
+        Operation::Replace(op) => op.content_file.is_some(),
+        Operation::Upsert(op) => op.content_file.is_some(),
+        Operation::ReplaceFrontmatter(op) => op.content_file.is_some(),
+        Operation::ReplaceInnerHtml(op) => op.content_file.is_some(),
+        Operation::SetFrontmatter(op) => {
+            return op.value_file.is_some().then_some("value_file");
+        }
+        Operation::ImportSection(_) => return Some("source_file"),
+        _ => false,
+    };
+    reads_file.then_some("content_file")
+}
@@ -0,0 +1,43 @@
+//! Re-exports the types most programs need to edit documents, so that a single glob import
+//! brings them into scope:
+//!
+//! ```rust
+//! use md_splice_lib::prelude::*;
+//! use std::str::FromStr;
+//!
+//! # fn demo() -> Result<(), SpliceError> {
+//! let mut document = MarkdownDocument::from_str("# Guide\n\n## Draft\n\nTODO.\n")?;
+//! document.apply(vec![Operation::Delete(DeleteOperation {
+//!     selector: Some(Selector::of_type("h2").containing("Draft")),
+//!     section: true,
+//!     ..DeleteOperation::default()
+//! })])?;
+//! assert_eq!(document.render(), "# Guide");
+//! # Ok(())
+//! # }
+//! # demo().unwrap();
+//! ```
+//!
+//! `Selector` is the selector of [`transaction`](crate::transaction), as written in
+//! operation files; the locator's compiled selector stays under
+//! [`locator::Selector`](crate::locator::Selector).
+
+pub use crate::error::SpliceError;
+pub use crate::limits::Limits;
+pub use crate::policy::OperationPolicy;
+pub use crate::transaction::{
+    AddTableColumnOperation, AlertType, AssertExpectation, AssertOperation, ColumnAlignment,
+    CommentPosition, CopyOperation, DedupeHeadingsOperation, DeleteFrontmatterOperation,
+    DeleteOperation, DeleteTableRowOperation, GetOperation, ImportSectionOperation,
+    InsertOperation, InsertPosition, InsertTableRowOperation, MergeSectionsOperation,
+    MoveOperation, Operation, PruneEmptySectionsOperation, RemoveAlertOperation,
+    RemoveCommentOperation, RemoveTableColumnOperation, RenameHeadingOperation,
+    RenameTableColumnOperation, RenumberOperation, ReplaceFrontmatterOperation,
+    ReplaceInnerHtmlOperation, ReplaceOperation, ReplaceTextOperation, Selector, SetAlertOperation,
+    SetCodeAttributeOperation, SetCodeLanguageOperation, SetCommentOperation,
+    SetFrontmatterOperation, ShiftHeadingOperation, SlugStyle, SwapOperation,
+    UnwrapDetailsOperation, UnwrapOperation, UpdateTocOperation, UpsertOperation,
+    WrapDetailsOperation, WrapOperation,
+};
+pub use crate::warning::{Warning, WarningKind};
+pub use crate::{apply_str, ApplyOutcome, MarkdownDocument, ParseOptions};
@@ -0,0 +1,715 @@
+//! Rewrites operations so they can be replayed on translated copies of a document.
+//!
+//! Selectors that match on text (`select_contains`, `select_regex`) stop matching once a
+//! document is translated, while its structure usually stays the same. [`positional_operations`]
+//! resolves every selector against a reference document and replaces it with one that only
+//! relies on node types, ordinals and `within` heading scopes, such as "the 2nd paragraph in
+//! the section of the 3rd `h2`".
+
+use crate::alias::ResolvedAlias;
+use crate::error::SpliceError;
+use crate::infer::block_type_name;
+use crate::inlines::inline_select_type;
+use crate::locator::{self, locate_all, locate_cached, FoundNode, LocateCache};
+use crate::sections::get_heading_level;
+use crate::transaction::{AssertExpectation, Operation, Selector};
+use crate::{
+    resolve_operation_selector, resolve_optional_operation_selector, resolve_selector_tree,
+    MarkdownDocument,
+};
+use markdown_ppp::ast::Block;
+use std::collections::HashMap;
+
+/// Rewrites the selectors of `operations` into positional selectors recorded from `reference`.
+///
+/// Operations are replayed on a copy of `reference` one at a time, so each selector is recorded
+/// against the document as the earlier operations left it. Alias references are inlined, `until`
+/// selectors are recorded relative to the start of their range, and inline selectors keep their
+/// `alias` name so the aliases can still be exported. Selectors that point into other files, such
+/// as the `source_selector` of `import_section`, are left untouched. `aliases` are imported as in
+/// [`MarkdownDocument::apply_with_aliases`].
+///
+/// Fails with the error the operation raises on `reference` if it cannot be applied there.
+pub fn positional_operations(
+    reference: &MarkdownDocument,
+    operations: Vec<Operation>,
+    aliases: &[ResolvedAlias],
+) -> Result<Vec<Operation>, SpliceError> {
+    let mut reference = reference.clone();
+    let mut aliases = aliases.to_vec();
+    let mut rewritten = Vec::with_capacity(operations.len());
+
+    for operation in operations {
+        let alias_map = alias_map(&aliases)?;
+        let anchoring = Anchoring {
+            blocks: reference.blocks(),
+            cache: LocateCache::with_spans(reference.block_spans()),
+        };
+        let mut positional = operation.clone();
+
+        match &mut positional {
+            Operation::Insert(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::Replace(op) => {
+                let start = anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+                anchor_until(
+                    &anchoring,
+                    &alias_map,
+                    start,
+                    &mut op.until,
+                    &mut op.until_ref,
+                )?;
+            }
+            Operation::ReplaceText(op) => {
+                let start = anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+                anchor_until(
+                    &anchoring,
+                    &alias_map,
+                    start,
+                    &mut op.until,
+                    &mut op.until_ref,
+                )?;
+            }
+            Operation::Delete(op) => {
+                let start = anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+                anchor_until(
+                    &anchoring,
+                    &alias_map,
+                    start,
+                    &mut op.until,
+                    &mut op.until_ref,
+                )?;
+            }
+            Operation::Upsert(op) => {
+                // Only the node the upsert uses is anchored; the other selector is left as written.
+                let resolved = resolve_operation_selector(
+                    &alias_map,
+                    op.selector.as_ref(),
+                    op.selector_ref.as_ref(),
+                    "selector",
+                )?
+                .selector;
+                if reference.locate(&resolved).is_ok() {
+                    anchor(
+                        &anchoring,
+                        &alias_map,
+                        &mut op.selector,
+                        &mut op.selector_ref,
+                        "selector",
+                    )?;
+                } else if op.fallback.is_some() || op.fallback_ref.is_some() {
+                    anchor(
+                        &anchoring,
+                        &alias_map,
+                        &mut op.fallback,
+                        &mut op.fallback_ref,
+                        "fallback",
+                    )?;
+                }
+            }
+            Operation::Move(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.destination,
+                    &mut op.destination_ref,
+                    "destination",
+                )?;
+            }
+            Operation::Copy(op) => {
+                let start = anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+                anchor_until(
+                    &anchoring,
+                    &alias_map,
+                    start,
+                    &mut op.until,
+                    &mut op.until_ref,
+                )?;
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.destination,
+                    &mut op.destination_ref,
+                    "destination",
+                )?;
+            }
+            Operation::Swap(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.other,
+                    &mut op.other_ref,
+                    "other",
+                )?;
+            }
+            Operation::RenameHeading(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::ShiftHeading(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::MergeSections(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.destination,
+                    &mut op.destination_ref,
+                    "destination",
+                )?;
+            }
+            Operation::PruneEmptySections(op) => {
+                if op.within.is_some() || op.within_ref.is_some() {
+                    anchor(
+                        &anchoring,
+                        &alias_map,
+                        &mut op.within,
+                        &mut op.within_ref,
+                        "within",
+                    )?;
+                }
+            }
+            Operation::DedupeHeadings(op) => {
+                if op.within.is_some() || op.within_ref.is_some() {
+                    anchor(
+                        &anchoring,
+                        &alias_map,
+                        &mut op.within,
+                        &mut op.within_ref,
+                        "within",
+                    )?;
+                }
+            }
+            Operation::Renumber(op) => {
+                if op.within.is_some() || op.within_ref.is_some() {
+                    anchor(
+                        &anchoring,
+                        &alias_map,
+                        &mut op.within,
+                        &mut op.within_ref,
+                        "within",
+                    )?;
+                }
+            }
+            Operation::ImportSection(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::SetAlert(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::RemoveAlert(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::SetComment(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::RemoveComment(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::ReplaceInnerHtml(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::Wrap(op) => {
+                let start = anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+                anchor_until(
+                    &anchoring,
+                    &alias_map,
+                    start,
+                    &mut op.until,
+                    &mut op.until_ref,
+                )?;
+            }
+            Operation::Unwrap(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::WrapDetails(op) => {
+                let start = anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+                anchor_until(
+                    &anchoring,
+                    &alias_map,
+                    start,
+                    &mut op.until,
+                    &mut op.until_ref,
+                )?;
+            }
+            Operation::UnwrapDetails(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::SetCodeLanguage(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::SetCodeAttribute(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::InsertTableRow(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::DeleteTableRow(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::AddTableColumn(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::RemoveTableColumn(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::RenameTableColumn(op) => {
+                anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+            }
+            Operation::UpdateToc(op) => {
+                if op.selector.is_some() || op.selector_ref.is_some() {
+                    anchor(
+                        &anchoring,
+                        &alias_map,
+                        &mut op.selector,
+                        &mut op.selector_ref,
+                        "selector",
+                    )?;
+                }
+            }
+            Operation::Get(op) => {
+                let start = anchor(
+                    &anchoring,
+                    &alias_map,
+                    &mut op.selector,
+                    &mut op.selector_ref,
+                    "selector",
+                )?;
+                anchor_until(
+                    &anchoring,
+                    &alias_map,
+                    start,
+                    &mut op.until,
+                    &mut op.until_ref,
+                )?;
+            }
+            Operation::Assert(op) => {
+                // A `no_match` assertion has no node to anchor to, so it is left as written.
+                let has_selector = op.selector.is_some() || op.selector_ref.is_some();
+                if has_selector && op.expect == AssertExpectation::Match {
+                    anchor(
+                        &anchoring,
+                        &alias_map,
+                        &mut op.selector,
+                        &mut op.selector_ref,
+                        "selector",
+                    )?;
+                }
+            }
+            Operation::SetFrontmatter(_)
+            | Operation::DeleteFrontmatter(_)
+            | Operation::ReplaceFrontmatter(_) => {}
+        }
+
+        aliases = reference
+            .apply_with_aliases(vec![operation], &aliases)?
+            .aliases;
+        rewritten.push(positional);
+    }
+
+    Ok(rewritten)
+}
+
+/// The blocks of the reference document as an operation finds them, along with the source
+/// spans that `at_line` selectors are matched against.
+struct Anchoring<'a> {
+    blocks: &'a [Block],
+    cache: LocateCache,
+}
+
+/// Replaces `selector`/`selector_ref` with the positional selector of the node they match,
+/// returning the index of the top-level block holding that node.
+fn anchor(
+    anchoring: &Anchoring,
+    alias_map: &HashMap<String, locator::Selector>,
+    selector: &mut Option<Selector>,
+    selector_ref: &mut Option<String>,
+    field_name: &str,
+) -> Result<usize, SpliceError> {
+    let resolved = resolve_operation_selector(
+        alias_map,
+        selector.as_ref(),
+        selector_ref.as_ref(),
+        field_name,
+    )?
+    .selector;
+    let blocks = anchoring.blocks;
+    let (found, _) = locate_cached(blocks, &resolved, &mut anchoring.cache.tail(0))?;
+    let alias = selector
+        .as_ref()
+        .and_then(|selector| selector.alias.clone());
+    *selector = Some(Selector {
+        alias,
+        ..positional_selector(blocks, &found)?
+    });
+    *selector_ref = None;
+
+    Ok(locator::found_block_index(&found))
+}
+
+/// Records an `until` selector relative to the blocks following `start`, where the range
+/// end is searched. Selectors that match nothing (the range runs to the end of the
+/// document) or a list item (which fails the operation) are left as written.
+fn anchor_until(
+    anchoring: &Anchoring,
+    alias_map: &HashMap<String, locator::Selector>,
+    start: usize,
+    until: &mut Option<Selector>,
+    until_ref: &mut Option<String>,
+) -> Result<(), SpliceError> {
+    let Some(resolved) = resolve_optional_operation_selector(
+        alias_map,
+        until.as_ref(),
+        until_ref.as_ref(),
+        "until",
+    )?
+    .selector
+    else {
+        return Ok(());
+    };
+    let following = anchoring.blocks.get(start + 1..).unwrap_or_default();
+    let mut cache = anchoring.cache.tail(start + 1);
+    if let Ok((found @ FoundNode::Block { .. }, _)) =
+        locate_cached(following, &resolved, &mut cache)
+    {
+        let alias = until.as_ref().and_then(|selector| selector.alias.clone());
+        *until = Some(Selector {
+            alias,
+            ..positional_selector(following, &found)?
+        });
+        *until_ref = None;
+    }
+
+    Ok(())
+}
+
+/// Builds a selector matching `found` by its type and ordinal, scoped to the section of
+/// the closest enclosing heading (itself described positionally).
+fn positional_selector(blocks: &[Block], found: &FoundNode) -> Result<Selector, SpliceError> {
+    let (index, select_type, own_level) = match found {
+        FoundNode::Block { index, block } => {
+            (*index, block_type_name(block), get_heading_level(block))
+        }
+        FoundNode::ContainedBlock {
+            block_index, block, ..
+        } => (*block_index, block_type_name(block), None),
+        FoundNode::ListItem { block_index, .. } => (*block_index, "li".to_string(), None),
+        FoundNode::TableRow { block_index, .. } => (*block_index, "tr".to_string(), None),
+        FoundNode::TableCell { block_index, .. } => (*block_index, "td".to_string(), None),
+        FoundNode::Inline {
+            block_index,
+            inline,
+            ..
+        } => (*block_index, inline_select_type(inline).to_string(), None),
+    };
+    let own_level = own_level.unwrap_or(u8::MAX);
+
+    let within = blocks[..index]
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, block)| get_heading_level(block).is_some_and(|level| level < own_level))
+        .map(|(index, block)| positional_selector(blocks, &FoundNode::Block { index, block }))
+        .transpose()?
+        .map(Box::new);
+
+    let mut selector = Selector {
+        select_type: Some(select_type),
+        recursive: matches!(found, FoundNode::ContainedBlock { .. }),
+        within,
+        ..Selector::default()
+    };
+    let probe = resolve_selector_tree(&HashMap::new(), &selector)?.selector;
+    let position = locate_all(blocks, &probe)?
+        .iter()
+        .position(|candidate| same_node(candidate, found))
+        .ok_or(SpliceError::NodeNotFound)?;
+    selector.select_ordinal = position + 1;
+
+    Ok(selector)
+}
+
+fn same_node(left: &FoundNode, right: &FoundNode) -> bool {
+    match (left, right) {
+        (FoundNode::Block { index: left, .. }, FoundNode::Block { index: right, .. }) => {
+            left == right
+        }
+        (
+            FoundNode::ContainedBlock { block: left, .. },
+            FoundNode::ContainedBlock { block: right, .. },
+        ) => std::ptr::eq(*left, *right),
+        (FoundNode::ListItem { item: left, .. }, FoundNode::ListItem { item: right, .. }) => {
+            std::ptr::eq(*left, *right)
+        }
+        (FoundNode::TableRow { .. }, FoundNode::TableRow { .. })
+        | (FoundNode::TableCell { .. }, FoundNode::TableCell { .. })
+        | (FoundNode::Inline { .. }, FoundNode::Inline { .. }) => {
+            left.position_description() == right.position_description()
+        }
+        _ => false,
+    }
+}
+
+fn alias_map(aliases: &[ResolvedAlias]) -> Result<HashMap<String, locator::Selector>, SpliceError> {
+    let mut map = HashMap::new();
+    for alias in aliases {
+        let resolved = resolve_selector_tree(&map, &alias.selector)?.selector;
+        map.insert(alias.name.clone(), resolved);
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{InsertOperation, InsertPosition, ReplaceOperation};
+    use std::str::FromStr;
+
+    fn contains(select_type: &str, text: &str) -> Selector {
+        Selector {
+            select_type: Some(select_type.to_string()),
+            select_contains: Some(text.to_string()),
+            ..Selector::default()
+        }
+    }
+
+    #[test]
+    fn test_positional_operations_replay_on_translated_document() {
+        let english = MarkdownDocument::from_str(
+            "# Guide\n\n## Install\n\nDownload it.\n\nRun the installer.\n\n## Usage\n\nStart it.\n\n- Open\n- Close\n",
+        )
+        .unwrap();
+        let french = MarkdownDocument::from_str(
+            "# Guide\n\n## Installation\n\nTéléchargez-le.\n\nLancez l'installateur.\n\n## Utilisation\n\nDémarrez-le.\n\n- Ouvrir\n- Fermer\n",
+        )
+        .unwrap();
+        let operations = vec![
+            Operation::Insert(InsertOperation {
+                selector: Some(contains("p", "Download")),
+                content: Some("> Note".to_string()),
+                position: InsertPosition::Before,
+                ..InsertOperation::default()
+            }),
+            Operation::Replace(ReplaceOperation {
+                selector: Some(contains("p", "installer")),
+                content: Some("Double-cliquez.".to_string()),
+                ..ReplaceOperation::default()
+            }),
+            Operation::Replace(ReplaceOperation {
+                selector: Some(contains("li", "Close")),
+                content: Some("- Quitter".to_string()),
+                ..ReplaceOperation::default()
+            }),
+        ];
+
+        let positional = positional_operations(&english, operations, &[]).unwrap();
+
+        let Operation::Replace(replace) = &positional[1] else {
+            panic!("expected a replace operation");
+        };
+        let selector = replace.selector.as_ref().unwrap();
+        assert_eq!(selector.select_type.as_deref(), Some("p"));
+        assert_eq!(selector.select_contains, None);
+        assert_eq!(selector.select_ordinal, 2);
+        let within = selector.within.as_deref().unwrap();
+        assert_eq!(within.select_type.as_deref(), Some("h2"));
+        assert_eq!(within.select_ordinal, 1);
+
+        let mut translated = french;
+        translated.apply(positional).unwrap();
+        assert_eq!(
+            translated.render(),
+            "# Guide\n\n## Installation\n\n> Note\n\nTéléchargez-le.\n\nDouble-cliquez.\n\n## Utilisation\n\nDémarrez-le.\n\n- Ouvrir\n- Quitter"
+        );
+    }
+
+    #[test]
+    fn test_positional_operations_resolve_source_lines() {
+        let english = MarkdownDocument::from_str(
+            "# Guide\n\n## Install\n\nDownload it.\n\nRun the installer.\n\n## Usage\n\nStart it.\n",
+        )
+        .unwrap();
+        let operations: Vec<Operation> = serde_yaml::from_str(
+            "- op: insert\n  selector: {at_line: 5}\n  position: before\n  content: '> Note'\n\
+             - op: delete\n  selector: {at_line: 7}\n  until: {at_line: 11}\n",
+        )
+        .unwrap();
+
+        let positional = positional_operations(&english, operations, &[]).unwrap();
+
+        let Operation::Delete(delete) = &positional[1] else {
+            panic!("expected a delete operation");
+        };
+        let selector = delete.selector.as_ref().unwrap();
+        assert_eq!(selector.at_line, None);
+        assert_eq!(selector.select_ordinal, 2);
+        let until = delete.until.as_ref().unwrap();
+        assert_eq!(until.select_type.as_deref(), Some("p"));
+        assert_eq!(until.at_line, None);
+    }
+}
@@ -0,0 +1,137 @@
+//! Routes the operations of a batch to documents according to their frontmatter.
+//!
+//! An operations file shared by many documents can group operations under a `when` filter,
+//! so that one file expresses "for `type: api-reference` do X; for `type: guide` do Y":
+//!
+//! ```yaml
+//! - op: set_frontmatter
+//!   key: reviewed
+//!   value: true
+//! - when: {type: api-reference}
+//!   operations:
+//!     - op: delete
+//!       selector: {select_type: h2, select_contains: Draft}
+//!       section: true
+//! - when: {type: [guide, tutorial]}
+//!   operations:
+//!     - op: insert
+//!       selector: {select_type: h1}
+//!       content: "> Read the quickstart first."
+//! ```
+//!
+//! [`route_operations`] keeps the operations of the groups whose filter matches a document,
+//! in file order, along with the ungrouped operations that apply to every document.
+
+use crate::error::SpliceError;
+use crate::transaction::Operation;
+use crate::{get_value_at_path, parse_frontmatter_path};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value as YamlValue;
+use std::collections::BTreeMap;
+
+/// An entry of an operations file: either an operation, or a group of operations applied
+/// only to documents whose frontmatter matches its filter.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum OperationEntry {
+    /// An operation applied to every document.
+    Operation(Operation),
+    /// Operations applied only to the documents matching `when`.
+    Group(OperationGroup),
+}
+
+/// Operations applied only to documents whose frontmatter matches a filter.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OperationGroup {
+    /// Frontmatter key paths (as in `set_frontmatter`, e.g. `meta.type`) and the value each
+    /// must have. All of them must match. A list matches any of its values, and a key holding
+    /// a list matches when the list contains the value.
+    pub when: BTreeMap<String, YamlValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the group.
+    pub comment: Option<String>,
+    /// The operations applied to matching documents, in order.
+    #[serde(default)]
+    pub operations: Vec<Operation>,
+}
+
+impl OperationGroup {
+    /// Returns whether a document with `frontmatter` matches the group's filter. A document
+    /// without frontmatter only matches an empty filter.
+    pub fn matches(&self, frontmatter: Option<&YamlValue>) -> Result<bool, SpliceError> {
+        for (key, expected) in &self.when {
+            let segments = parse_frontmatter_path(key).map_err(|err| {
+                SpliceError::OperationParse(format!("invalid 'when' key '{key}': {err}"))
+            })?;
+            let actual =
+                frontmatter.and_then(|frontmatter| get_value_at_path(frontmatter, &segments));
+            if !actual.is_some_and(|actual| value_matches(actual, expected)) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn value_matches(actual: &YamlValue, expected: &YamlValue) -> bool {
+    if actual == expected {
+        return true;
+    }
+    match (actual, expected) {
+        (_, YamlValue::Sequence(options)) => options.iter().any(|option| option == actual),
+        (YamlValue::Sequence(values), _) => values.iter().any(|value| value == expected),
+        _ => false,
+    }
+}
+
+/// Flattens `entries` into the operations that apply to a document with `frontmatter`,
+/// dropping the groups whose filter does not match.
+pub fn route_operations(
+    entries: Vec<OperationEntry>,
+    frontmatter: Option<&YamlValue>,
+) -> Result<Vec<Operation>, SpliceError> {
+    let mut operations = Vec::new();
+    for entry in entries {
+        match entry {
+            OperationEntry::Operation(operation) => operations.push(operation),
+            OperationEntry::Group(group) => {
+                if group.matches(frontmatter)? {
+                    operations.extend(group.operations);
+                }
+            }
+        }
+    }
+    Ok(operations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_operations_keeps_matching_groups_in_order() {
+        let entries: Vec<OperationEntry> = serde_yaml::from_str(
+            "- op: delete_frontmatter\n  key: draft\n\
+             - when: {type: [guide, tutorial]}\n  operations:\n    - op: delete_frontmatter\n      key: guide\n\
+             - when: {type: api-reference}\n  operations:\n    - op: delete_frontmatter\n      key: api\n\
+             - when: {tags: beta, meta.level: 2}\n  operations:\n    - op: delete_frontmatter\n      key: beta\n",
+        )
+        .unwrap();
+        let frontmatter: YamlValue =
+            serde_yaml::from_str("type: guide\ntags: [beta, cli]\nmeta: {level: 2}\n").unwrap();
+
+        let keys: Vec<String> = route_operations(entries.clone(), Some(&frontmatter))
+            .unwrap()
+            .into_iter()
+            .map(|operation| match operation {
+                Operation::DeleteFrontmatter(op) => op.key,
+                other => panic!("unexpected operation {other:?}"),
+            })
+            .collect();
+        assert_eq!(keys, vec!["draft", "guide", "beta"]);
+
+        assert_eq!(route_operations(entries, None).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,115 @@
+//! Computes the extent of heading sections in a list of top-level blocks.
+//!
+//! A heading's section is the heading itself followed by every block up to (but not
+//! including) the next heading of the same or a higher level, or the end of the document.
+//! Slide decks are split by thematic breaks (`---`) instead, see [`slide_range`].
+
+use markdown_ppp::ast::{Block, Heading, HeadingKind, SetextHeading};
+use std::ops::Range;
+
+/// Gets the level (1-6) of a heading block, or `None` if `block` is not a heading.
+pub fn get_heading_level(block: &Block) -> Option<u8> {
+    if let Block::Heading(Heading { kind, .. }) = block {
+        Some(match kind {
+            HeadingKind::Atx(level) => *level,
+            HeadingKind::Setext(SetextHeading::Level1) => 1,
+            HeadingKind::Setext(SetextHeading::Level2) => 2,
+        })
+    } else {
+        None
+    }
+}
+
+/// Changes the level of a heading block to `level` (1-6); other blocks are left untouched.
+///
+/// Setext headings keep their style when the new level is 1 or 2 and become ATX headings otherwise.
+pub(crate) fn set_heading_level(block: &mut Block, level: u8) {
+    if let Block::Heading(Heading { kind, .. }) = block {
+        *kind = match (&*kind, level) {
+            (HeadingKind::Setext(_), 1) => HeadingKind::Setext(SetextHeading::Level1),
+            (HeadingKind::Setext(_), 2) => HeadingKind::Setext(SetextHeading::Level2),
+            _ => HeadingKind::Atx(level),
+        };
+    }
+}
+
+/// Finds the index that marks the end of a heading section.
+/// The section ends just before the next heading of the same or higher level,
+/// or at the end of the document.
+pub(crate) fn find_heading_section_end(
+    blocks: &[Block],
+    start_index: usize,
+    target_level: u8,
+) -> usize {
+    // We skip to the block after the starting one and find the first block
+    // that meets the end-of-section criteria.
+    let first_candidate = start_index.saturating_add(1);
+    for (i, block) in blocks.iter().enumerate().skip(first_candidate) {
+        if let Some(level) = get_heading_level(block) {
+            if level <= target_level {
+                return i; // Found the boundary, return its index.
+            }
+        }
+    }
+    blocks.len() // Reached the end of the document, return the length as the end index.
+}
+
+/// Returns the blocks spanned by the section of the heading at `heading_index`,
+/// including the heading itself.
+///
+/// Returns `None` if `heading_index` is out of bounds or the block there is not a heading.
+pub fn section_range(blocks: &[Block], heading_index: usize) -> Option<Range<usize>> {
+    let level = get_heading_level(blocks.get(heading_index)?)?;
+    Some(heading_index..find_heading_section_end(blocks, heading_index, level))
+}
+
+/// Returns the blocks of the `slide`th slide (1-indexed) of a deck whose slides are separated
+/// by thematic breaks, excluding the breaks themselves.
+///
+/// The first slide starts at the beginning of the document. Returns `None` if `slide` is 0 or
+/// the document has fewer slides.
+pub fn slide_range(blocks: &[Block], slide: usize) -> Option<Range<usize>> {
+    let mut start = 0;
+    let mut current = 1;
+    for (index, block) in blocks.iter().enumerate() {
+        if matches!(block, Block::ThematicBreak) {
+            if current == slide {
+                return Some(start..index);
+            }
+            current += 1;
+            start = index + 1;
+        }
+    }
+    (current == slide).then_some(start..blocks.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    #[test]
+    fn test_section_range_stops_at_same_or_higher_level() {
+        let markdown = "# One\n\n## A\n\nText.\n\n### Deep\n\n## B\n\n# Two\n";
+        let doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        assert_eq!(section_range(&doc.blocks, 0), Some(0..5));
+        assert_eq!(section_range(&doc.blocks, 1), Some(1..4));
+        assert_eq!(section_range(&doc.blocks, 5), Some(5..6));
+        assert_eq!(section_range(&doc.blocks, 2), None);
+        assert_eq!(section_range(&doc.blocks, 6), None);
+    }
+
+    #[test]
+    fn test_slide_range_splits_on_thematic_breaks() {
+        let markdown = "# Title\n\nIntro.\n\n---\n\n## Agenda\n\n---\n\n---\n\nThanks.\n";
+        let doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        assert_eq!(slide_range(&doc.blocks, 1), Some(0..2));
+        assert_eq!(slide_range(&doc.blocks, 2), Some(3..4));
+        assert_eq!(slide_range(&doc.blocks, 3), Some(5..5));
+        assert_eq!(slide_range(&doc.blocks, 4), Some(6..7));
+        assert_eq!(slide_range(&doc.blocks, 5), None);
+        assert_eq!(slide_range(&doc.blocks, 0), None);
+    }
+}
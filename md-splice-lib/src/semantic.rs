@@ -0,0 +1,228 @@
+//! Contains the logic for comparing Markdown documents while ignoring formatting.
+//!
+//! Two documents are considered semantically equal when their normalized ASTs
+//! and frontmatter payloads match. Normalization erases choices that only affect
+//! how the Markdown is spelled, such as Setext vs. ATX headings, the bullet
+//! character of unordered lists, indented vs. fenced code blocks, or how text is
+//! wrapped across lines.
+
+use markdown_ppp::ast::{
+    Block, CodeBlock, CodeBlockKind, FootnoteDefinition, GitHubAlert, Heading, HeadingKind, Inline,
+    Link, LinkDefinition, LinkReference, List, ListBulletKind, ListItem, ListKind, SetextHeading,
+    Table,
+};
+use serde_yaml::Value as YamlValue;
+
+/// Returns a normalized copy of `blocks` suitable for semantic comparison.
+pub fn normalize_blocks(blocks: &[Block]) -> Vec<Block> {
+    blocks.iter().filter_map(normalize_block).collect()
+}
+
+/// Returns a normalized copy of `inlines` suitable for semantic comparison.
+///
+/// Runs of whitespace (including soft line breaks) collapse to a single space,
+/// adjacent text nodes are merged, and leading/trailing whitespace is trimmed.
+pub fn normalize_inlines(inlines: &[Inline]) -> Vec<Inline> {
+    let mut normalized = collect_inlines(inlines);
+
+    if let Some(Inline::Text(text)) = normalized.first_mut() {
+        *text = text.trim_start().to_string();
+    }
+    if let Some(Inline::Text(text)) = normalized.last_mut() {
+        *text = text.trim_end().to_string();
+    }
+    normalized.retain(|inline| !matches!(inline, Inline::Text(text) if text.is_empty()));
+
+    normalized
+}
+
+/// Returns `true` when both frontmatter payloads carry the same data.
+///
+/// A missing frontmatter block and an empty one are treated as equivalent.
+pub fn frontmatter_equals(left: Option<&YamlValue>, right: Option<&YamlValue>) -> bool {
+    fn effective(value: Option<&YamlValue>) -> Option<&YamlValue> {
+        value.filter(|value| !value.is_null())
+    }
+
+    effective(left) == effective(right)
+}
+
+fn normalize_block(block: &Block) -> Option<Block> {
+    let normalized = match block {
+        Block::Empty => return None,
+        Block::Paragraph(inlines) => {
+            let inlines = normalize_inlines(inlines);
+            if inlines.is_empty() {
+                return None;
+            }
+            Block::Paragraph(inlines)
+        }
+        Block::Heading(heading) => Block::Heading(Heading {
+            kind: HeadingKind::Atx(heading_level(&heading.kind)),
+            content: normalize_inlines(&heading.content),
+        }),
+        Block::ThematicBreak => Block::ThematicBreak,
+        Block::BlockQuote(children) => Block::BlockQuote(normalize_blocks(children)),
+        Block::List(list) => Block::List(List {
+            kind: match &list.kind {
+                ListKind::Bullet(_) => ListKind::Bullet(ListBulletKind::Dash),
+                ListKind::Ordered(options) => ListKind::Ordered(options.clone()),
+            },
+            items: list
+                .items
+                .iter()
+                .map(|item| ListItem {
+                    task: item.task,
+                    blocks: normalize_blocks(&item.blocks),
+                })
+                .collect(),
+        }),
+        Block::CodeBlock(code) => Block::CodeBlock(CodeBlock {
+            kind: match &code.kind {
+                CodeBlockKind::Fenced { info } => CodeBlockKind::Fenced {
+                    info: info
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|info| !info.is_empty())
+                        .map(str::to_string),
+                },
+                CodeBlockKind::Indented => CodeBlockKind::Fenced { info: None },
+            },
+            literal: code.literal.trim_end_matches('\n').to_string(),
+        }),
+        Block::HtmlBlock(html) => Block::HtmlBlock(html.trim().to_string()),
+        Block::Definition(definition) => Block::Definition(LinkDefinition {
+            label: normalize_inlines(&definition.label),
+            destination: definition.destination.clone(),
+            title: definition.title.clone(),
+        }),
+        Block::Table(table) => Block::Table(Table {
+            rows: table
+                .rows
+                .iter()
+                .map(|row| row.iter().map(|cell| normalize_inlines(cell)).collect())
+                .collect(),
+            alignments: table.alignments.clone(),
+        }),
+        Block::FootnoteDefinition(footnote) => Block::FootnoteDefinition(FootnoteDefinition {
+            label: footnote.label.clone(),
+            blocks: normalize_blocks(&footnote.blocks),
+        }),
+        Block::GitHubAlert(alert) => Block::GitHubAlert(GitHubAlert {
+            alert_type: alert.alert_type,
+            blocks: normalize_blocks(&alert.blocks),
+        }),
+    };
+
+    Some(normalized)
+}
+
+fn heading_level(kind: &HeadingKind) -> u8 {
+    match kind {
+        HeadingKind::Atx(level) => *level,
+        HeadingKind::Setext(SetextHeading::Level1) => 1,
+        HeadingKind::Setext(SetextHeading::Level2) => 2,
+    }
+}
+
+fn collect_inlines(inlines: &[Inline]) -> Vec<Inline> {
+    let mut output: Vec<Inline> = Vec::with_capacity(inlines.len());
+
+    for inline in inlines {
+        let normalized = match inline {
+            Inline::Empty => continue,
+            Inline::Text(text) => Inline::Text(collapse_whitespace(text)),
+            Inline::Emphasis(children) => Inline::Emphasis(normalize_inlines(children)),
+            Inline::Strong(children) => Inline::Strong(normalize_inlines(children)),
+            Inline::Strikethrough(children) => Inline::Strikethrough(normalize_inlines(children)),
+            Inline::Link(link) => Inline::Link(Link {
+                destination: link.destination.clone(),
+                title: link.title.clone(),
+                children: normalize_inlines(&link.children),
+            }),
+            Inline::LinkReference(reference) => Inline::LinkReference(LinkReference {
+                label: normalize_inlines(&reference.label),
+                text: normalize_inlines(&reference.text),
+            }),
+            other => other.clone(),
+        };
+
+        match (output.last_mut(), normalized) {
+            (Some(Inline::Text(previous)), Inline::Text(next)) => {
+                previous.push_str(&next);
+                *previous = collapse_whitespace(previous);
+            }
+            (_, normalized) => output.push(normalized),
+        }
+    }
+
+    output
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+
+    for ch in text.chars() {
+        if ch.is_whitespace() {
+            if !in_whitespace {
+                collapsed.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            collapsed.push(ch);
+            in_whitespace = false;
+        }
+    }
+
+    collapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    fn normalized(markdown: &str) -> Vec<Block> {
+        let doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+        normalize_blocks(&doc.blocks)
+    }
+
+    #[test]
+    fn test_setext_and_atx_headings_normalize_identically() {
+        assert_eq!(normalized("Title\n=====\n"), normalized("# Title\n"));
+        assert_eq!(normalized("Sub\n---\n"), normalized("## Sub\n"));
+    }
+
+    #[test]
+    fn test_bullet_markers_and_wrapping_are_ignored() {
+        assert_eq!(
+            normalized("* one\n* two\n\nA long\nwrapped   line.\n"),
+            normalized("- one\n- two\n\nA long wrapped line.\n"),
+        );
+    }
+
+    #[test]
+    fn test_indented_and_fenced_code_normalize_identically() {
+        assert_eq!(
+            normalized("    let x = 1;\n"),
+            normalized("```\nlet x = 1;\n```\n")
+        );
+    }
+
+    #[test]
+    fn test_content_changes_are_detected() {
+        assert_ne!(normalized("# Title\n"), normalized("## Title\n"));
+        assert_ne!(normalized("1. one\n"), normalized("- one\n"));
+        assert_ne!(normalized("Some *text*.\n"), normalized("Some text.\n"));
+    }
+
+    #[test]
+    fn test_frontmatter_equality_treats_empty_as_missing() {
+        assert!(frontmatter_equals(None, Some(&YamlValue::Null)));
+        assert!(!frontmatter_equals(
+            None,
+            Some(&YamlValue::String("x".into()))
+        ));
+    }
+}
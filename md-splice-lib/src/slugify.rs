@@ -0,0 +1,145 @@
+//! Computes the anchors GitHub generates for headings, so that tooling can build links to
+//! them.
+//!
+//! A slug is the lowercased text of a heading with punctuation dropped and spaces replaced
+//! by hyphens. When several headings share a slug, GitHub suffixes the later ones with `-1`,
+//! `-2`, ...; a [`Slugger`] remembers the anchors handed out so far to do the same.
+//!
+//! [`heading_slugs`] returns the anchor of every heading of a document, honouring explicit
+//! `{#id}` attributes.
+
+use crate::anchors::explicit_id;
+use crate::locator::block_to_text;
+use crate::sections::get_heading_level;
+use markdown_ppp::ast::Block;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A heading together with the anchor links to it use.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeadingSlug {
+    /// The index of the heading among the top-level blocks.
+    pub index: usize,
+    /// The heading level, from 1 to 6.
+    pub level: u8,
+    /// The plain text of the heading, without its explicit `{#id}` attribute.
+    pub text: String,
+    /// The explicit `{#id}` of the heading, or its slug with a `-1`, `-2`, ... suffix when
+    /// an earlier heading has the same one.
+    pub anchor: String,
+}
+
+/// Returns the anchor of every top-level heading of `blocks`, in document order.
+pub fn heading_slugs(blocks: &[Block]) -> Vec<HeadingSlug> {
+    let mut slugger = Slugger::new();
+    blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, block)| {
+            let Block::Heading(heading) = block else {
+                return None;
+            };
+            let mut text = block_to_text(block).trim().to_string();
+            let anchor = match explicit_id(heading) {
+                Some(id) => {
+                    let attribute = format!("{{#{id}}}");
+                    text = text.trim_end_matches(&attribute).trim_end().to_string();
+                    id.to_string()
+                }
+                None => slugger.slug(&text),
+            };
+            Some(HeadingSlug {
+                index,
+                level: get_heading_level(block)?,
+                text,
+                anchor,
+            })
+        })
+        .collect()
+}
+
+/// Lowercases `text`, drops punctuation, and replaces spaces with hyphens.
+///
+/// ```
+/// use md_splice_lib::slugify::slugify;
+///
+/// assert_eq!(slugify("What's New in v2.0?"), "whats-new-in-v20");
+/// ```
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+/// Hands out unique anchors for the headings of a document, in document order.
+#[derive(Debug, Clone, Default)]
+pub struct Slugger {
+    seen: HashMap<String, usize>,
+}
+
+impl Slugger {
+    /// Creates a slugger that has not handed out any anchor yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the anchor of a heading whose text is `text`: its slug, suffixed with `-1`,
+    /// `-2`, ... when an earlier heading already uses it.
+    pub fn slug(&mut self, text: &str) -> String {
+        self.claim(slugify(text))
+    }
+
+    /// Returns `base` as an anchor, suffixed with `-1`, `-2`, ... when an earlier heading
+    /// already uses it.
+    pub fn claim(&mut self, base: String) -> String {
+        let mut anchor = base.clone();
+        while self.seen.contains_key(&anchor) {
+            let count = self.seen.entry(base.clone()).or_default();
+            *count += 1;
+            anchor = format!("{base}-{count}");
+        }
+        self.seen.insert(anchor.clone(), 0);
+        anchor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    #[test]
+    fn test_slugger_suffixes_repeated_slugs() {
+        let mut slugger = Slugger::new();
+        assert_eq!(slugger.slug("What's New in v2.0?"), "whats-new-in-v20");
+        assert_eq!(slugger.slug("Foo"), "foo");
+        assert_eq!(slugger.slug("Foo-1"), "foo-1");
+        assert_eq!(slugger.slug("Foo"), "foo-2");
+        assert_eq!(slugger.slug("Foo"), "foo-3");
+        assert_eq!(slugger.slug("snake_case & Co."), "snake_case--co");
+    }
+
+    #[test]
+    fn test_heading_slugs_prefer_explicit_ids() {
+        let markdown =
+            "# Setup\n\n## Setup\n\n## Install `cargo` {#install}\n\nText.\n\n### Setup\n";
+        let doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        let slugs: Vec<(usize, u8, String, String)> = heading_slugs(&doc.blocks)
+            .into_iter()
+            .map(|heading| (heading.index, heading.level, heading.text, heading.anchor))
+            .collect();
+        assert_eq!(
+            slugs,
+            vec![
+                (0, 1, "Setup".into(), "setup".into()),
+                (1, 2, "Setup".into(), "setup-1".into()),
+                (2, 2, "Install cargo".into(), "install".into()),
+                (4, 3, "Setup".into(), "setup-2".into()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! Contains the logic for finding where top-level blocks were written in the source.
+//!
+//! The parser keeps no byte or line positions, so spans are recovered after parsing: the
+//! body is cut into runs of non-blank lines, and runs are parsed on their own, one more at a
+//! time, until they yield exactly the next blocks of the document. Blocks spread over
+//! several runs (lists, fenced code with blank lines) take several runs, and runs holding
+//! several blocks (a heading directly followed by a paragraph) are cut again line by line.
+//! A search gives up on a block as soon as the runs parse to more blocks than remain, or to
+//! a block that differs from the document's and is closed by another one, so that blocks
+//! that cannot be found in the source cost no more than a few parses each.
+
+use crate::deflist::split_definition_lists;
+use crate::infer::{diff_hunks, Hunk};
+use markdown_ppp::ast::Block;
+use markdown_ppp::parser::config::MarkdownParserConfig;
+use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// Where a top-level block was written in the original document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceSpan {
+    /// Byte offsets of the block within the original document, frontmatter included.
+    pub bytes: Range<usize>,
+    /// The 1-indexed line where the block starts.
+    pub start_line: usize,
+    /// The 1-indexed line where the block ends, inclusive.
+    pub end_line: usize,
+}
+
+impl fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start_line == self.end_line {
+            write!(f, "line {}", self.start_line)
+        } else {
+            write!(f, "lines {}-{}", self.start_line, self.end_line)
+        }
+    }
+}
+
+/// Returns the span of each of `blocks`, parsed from `body`, which starts at byte `offset`
+/// and line `first_line` of the original document, with compact definition list entries
+/// split when `definition_lists` is set.
+///
+/// Blocks that cannot be told apart in the source, such as the regions lenient parsing
+/// keeps verbatim, have no span, and neither do the blocks after them.
+pub(crate) fn block_spans(
+    body: &str,
+    blocks: &[Block],
+    offset: usize,
+    first_line: usize,
+    definition_lists: bool,
+) -> Vec<Option<SourceSpan>> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(body.match_indices('\n').map(|(index, _)| index + 1))
+        .collect();
+    let parser = Parser {
+        config: Rc::new(MarkdownParserConfig::default()),
+        definition_lists,
+    };
+    align(body, &line_runs(body), blocks, false, &parser)
+        .into_iter()
+        .map(|range| {
+            let range = range?;
+            // The last byte of a run is its newline, which belongs to the line it ends.
+            let end_line = line_starts.partition_point(|start| *start < range.end);
+            Some(SourceSpan {
+                start_line: first_line + line_starts.partition_point(|start| *start <= range.start)
+                    - 1,
+                end_line: first_line + end_line - 1,
+                bytes: offset + range.start..offset + range.end,
+            })
+        })
+        .collect()
+}
+
+/// Carries the spans of `original` blocks over to the `edited` blocks they were turned into:
+/// blocks left unchanged keep their span, and blocks added or changed have none.
+pub(crate) fn track_spans(
+    original: &[Block],
+    spans: &[Option<SourceSpan>],
+    edited: &[Block],
+) -> Vec<Option<SourceSpan>> {
+    // Edits rarely touch more than a few blocks, so only the middle that differs is diffed.
+    let prefix = original
+        .iter()
+        .zip(edited)
+        .take_while(|(original, edited)| original == edited)
+        .count();
+    let suffix = original[prefix..]
+        .iter()
+        .rev()
+        .zip(edited[prefix..].iter().rev())
+        .take_while(|(original, edited)| original == edited)
+        .count();
+    let original_middle = &original[prefix..original.len() - suffix];
+    let edited_middle = &edited[prefix..edited.len() - suffix];
+
+    let mut tracked = vec![None; edited.len()];
+    tracked[..prefix].clone_from_slice(&spans[..prefix]);
+    tracked[edited.len() - suffix..].clone_from_slice(&spans[original.len() - suffix..]);
+
+    // Between two hunks, the blocks of both documents are the same.
+    let last = Hunk {
+        original: original_middle.len()..original_middle.len(),
+        edited: edited_middle.len()..edited_middle.len(),
+    };
+    let (mut original_index, mut edited_index) = (prefix, prefix);
+    for hunk in diff_hunks(original_middle, edited_middle)
+        .into_iter()
+        .chain(std::iter::once(last))
+    {
+        while original_index < prefix + hunk.original.start {
+            tracked[edited_index] = spans[original_index].clone();
+            original_index += 1;
+            edited_index += 1;
+        }
+        original_index = prefix + hunk.original.end;
+        edited_index = prefix + hunk.edited.end;
+    }
+    tracked
+}
+
+/// Finds the byte range of each of `blocks` by parsing ever longer sequences of `units`,
+/// which are runs of non-blank lines, or single lines when `by_line` is set.
+fn align(
+    body: &str,
+    units: &[Range<usize>],
+    blocks: &[Block],
+    by_line: bool,
+    parser: &Parser,
+) -> Vec<Option<Range<usize>>> {
+    let mut ranges = vec![None; blocks.len()];
+    let (mut next, mut unit) = (0, 0);
+    while next < blocks.len() && unit < units.len() {
+        let mut found = None;
+        for last in unit..units.len() {
+            let text = &body[units[unit].start..units[last].end];
+            let Some(parsed) = parser.parse(text) else {
+                continue;
+            };
+            let remaining = &blocks[next..];
+            if parsed.len() > remaining.len() {
+                break;
+            }
+            // A block that another one follows is closed, so more lines cannot make it match.
+            let closed = &parsed[..parsed.len().saturating_sub(1)];
+            if !remaining.starts_with(closed) {
+                break;
+            }
+            if !parsed.is_empty() && remaining.starts_with(&parsed) {
+                found = Some((last, parsed.len()));
+                break;
+            }
+        }
+        let Some((last, count)) = found else {
+            break;
+        };
+
+        let range = units[unit].start..units[last].end;
+        let lines = line_units(body, range.clone());
+        if count > 1 && !by_line && lines.len() > 1 {
+            let inner = align(body, &lines, &blocks[next..next + count], true, parser);
+            for (slot, inner) in ranges[next..next + count].iter_mut().zip(inner) {
+                *slot = Some(inner.unwrap_or_else(|| range.clone()));
+            }
+        } else {
+            ranges[next..next + count].fill(Some(range));
+        }
+        next += count;
+        unit = last + 1;
+    }
+    ranges
+}
+
+/// Parses the pieces of a body the way documents are parsed.
+struct Parser {
+    // Building a configuration is costly, so every parse shares the same one.
+    config: Rc<MarkdownParserConfig>,
+    definition_lists: bool,
+}
+
+impl Parser {
+    /// Parses `text`, or returns `None` if it is not valid on its own.
+    fn parse(&self, text: &str) -> Option<Vec<Block>> {
+        let state = MarkdownParserState {
+            config: Rc::clone(&self.config),
+        };
+        let mut blocks = parse_markdown(state, text).ok()?.blocks;
+        if self.definition_lists {
+            split_definition_lists(&mut blocks);
+        }
+        Some(blocks)
+    }
+}
+
+/// Returns the byte ranges of the runs of non-blank lines of `body`, each including the
+/// newline of its last line.
+fn line_runs(body: &str) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    let mut in_run = false;
+    for line in line_units(body, 0..body.len()) {
+        if body[line.clone()].trim().is_empty() {
+            in_run = false;
+        } else if in_run {
+            runs.last_mut().expect("a run is open").end = line.end;
+        } else {
+            runs.push(line);
+            in_run = true;
+        }
+    }
+    runs
+}
+
+/// Returns the byte range of each line within `range` of `body`, including its newline.
+fn line_units(body: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut start = range.start;
+    body[range]
+        .split_inclusive('\n')
+        .map(|line| {
+            let line_range = start..start + line.len();
+            start = line_range.end;
+            line_range
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_blocks(text: &str) -> Vec<Block> {
+        parse_markdown(MarkdownParserState::default(), text)
+            .unwrap()
+            .blocks
+    }
+
+    fn spans(body: &str) -> Vec<Option<(usize, usize)>> {
+        let blocks = parse_blocks(body);
+        block_spans(body, &blocks, 0, 1, false)
+            .into_iter()
+            .map(|span| span.map(|span| (span.start_line, span.end_line)))
+            .collect()
+    }
+
+    #[test]
+    fn test_block_spans_follow_blank_lines_and_adjacent_blocks() {
+        let body =
+            "# Title\nIntro.\n\n- one\n\n  more\n- two\n\n```sh\nmake\n\nmake test\n```\n\n| a |\n|---|\n";
+        assert_eq!(
+            spans(body),
+            vec![
+                Some((1, 1)),
+                Some((2, 2)),
+                Some((4, 7)),
+                Some((9, 13)),
+                Some((15, 16)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_block_spans_give_up_on_blocks_missing_from_the_source() {
+        let blocks = parse_blocks("# Title\n\nOther.\n\nMore.\n");
+        let lines: Vec<_> = block_spans("# Title\n\nMore.\n", &blocks, 0, 1, false)
+            .into_iter()
+            .map(|span| span.map(|span| span.start_line))
+            .collect();
+        assert_eq!(lines, vec![Some(1), None, None]);
+    }
+
+    #[test]
+    fn test_track_spans_keeps_spans_of_unchanged_blocks() {
+        let body = "# Title\n\nOne.\n\nTwo.\n";
+        let original = parse_blocks(body);
+        let spans = block_spans(body, &original, 10, 3, false);
+        assert_eq!(spans[2].as_ref().unwrap().bytes, 25..30);
+        assert_eq!(spans[2].as_ref().unwrap().to_string(), "line 7");
+
+        let edited = parse_blocks("# Title\n\nNew.\n\nTwo.\n");
+        let tracked = track_spans(&original, &spans, &edited);
+        assert_eq!(tracked, vec![spans[0].clone(), None, spans[2].clone()]);
+    }
+}
@@ -1,7 +1,78 @@
 //! Contains the logic for modifying the Markdown AST (inserting/replacing nodes).
 
+use crate::lint::{find_duplicate_headings, heading_key};
+use crate::locator::block_to_text;
+use crate::sections::{
+    find_heading_section_end, get_heading_level, section_range, set_heading_level,
+};
 use crate::{error::SpliceError, transaction::InsertPosition};
-use markdown_ppp::ast::{Block, Heading, HeadingKind, ListItem, SetextHeading};
+use markdown_ppp::ast::{
+    Alignment, Block, GitHubAlert, GitHubAlertType, Inline, ListItem, ListKind, Table, TableCell,
+};
+use regex::Regex;
+use std::collections::HashSet;
+use std::ops::{Range, RangeInclusive};
+
+/// How an edit changed a vector of blocks: the blocks in `range` of the edited vector took
+/// the place of `removed` blocks at `range.start`, and the blocks around them are the same.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BlockChange {
+    pub range: Range<usize>,
+    pub removed: usize,
+}
+
+impl BlockChange {
+    /// The change of replacing the `removed` blocks at `start` with `added` blocks.
+    pub fn splice(start: usize, removed: usize, added: usize) -> Self {
+        Self {
+            range: start..start + added,
+            removed,
+        }
+    }
+
+    /// The change of editing the block at `index` in place.
+    pub fn edit(index: usize) -> Self {
+        Self::splice(index, 1, 1)
+    }
+
+    /// The change of editing the blocks at `indices` in place, or `None` if there are none.
+    pub fn edits(indices: impl IntoIterator<Item = usize>) -> Option<Self> {
+        let (first, last) = indices.into_iter().fold(
+            None,
+            |bounds: Option<(usize, usize)>, index| match bounds {
+                Some((first, last)) => Some((first.min(index), last.max(index))),
+                None => Some((index, index)),
+            },
+        )?;
+        Some(Self::splice(first, last - first + 1, last - first + 1))
+    }
+
+    /// Returns whether the change left the blocks as they were.
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty() && self.removed == 0
+    }
+
+    /// Combines this change with `next`, made to the vector after it, into one change
+    /// covering both.
+    pub fn then(self, next: Self) -> Self {
+        let start = self.range.start.min(next.range.start);
+        // The end of the region covering both changes, in the vector between them.
+        let end = self.range.end.max(next.range.start + next.removed);
+        Self::splice(
+            start,
+            end - self.range.end + self.range.start + self.removed - start,
+            end - next.range.start - next.removed + next.range.end - start,
+        )
+    }
+
+    /// Combines two optional changes made one after the other.
+    pub fn chain(first: Option<Self>, next: Option<Self>) -> Option<Self> {
+        match (first, next) {
+            (Some(first), Some(next)) => Some(first.then(next)),
+            (first, next) => first.or(next),
+        }
+    }
+}
 
 /// Replaces a block at a specific index with a new set of blocks.
 ///
@@ -10,11 +81,19 @@ use markdown_ppp::ast::{Block, Heading, HeadingKind, ListItem, SetextHeading};
 /// * `doc_blocks`: The mutable vector of blocks from the document to modify.
 /// * `index`: The index of the block to be replaced.
 /// * `new_blocks`: A vector of blocks to insert in place of the old one.
-pub fn replace(doc_blocks: &mut Vec<Block>, index: usize, new_blocks: Vec<Block>) {
+///
+/// Fails, leaving `doc_blocks` untouched, if `index` is out of bounds.
+pub fn replace(
+    doc_blocks: &mut Vec<Block>,
+    index: usize,
+    new_blocks: Vec<Block>,
+) -> anyhow::Result<()> {
+    check_block_index(doc_blocks, index)?;
     // Vec::splice is the perfect tool here. It can replace a range of elements
     // with a new iterator of elements. By specifying the range `index..=index`,
     // we are targeting the single block at the given index for replacement.
     doc_blocks.splice(index..=index, new_blocks);
+    Ok(())
 }
 
 /// Inserts new blocks into the document relative to a target block.
@@ -25,22 +104,38 @@ pub fn replace(doc_blocks: &mut Vec<Block>, index: usize, new_blocks: Vec<Block>
 /// * `index`: The index of the target block.
 /// * `new_blocks`: A vector of blocks to insert.
 /// * `position`: Where to insert the new blocks relative to the target.
+///
+/// Fails, leaving `doc_blocks` untouched, if `index` is out of bounds.
 pub fn insert(
     doc_blocks: &mut Vec<Block>,
     index: usize,
-    mut new_blocks: Vec<Block>,
+    new_blocks: Vec<Block>,
     position: InsertPosition,
 ) -> anyhow::Result<()> {
-    match position {
+    insert_tracked(doc_blocks, index, new_blocks, position).map(|_| ())
+}
+
+/// Inserts like [`insert`], also returning the change made to `doc_blocks`.
+pub(crate) fn insert_tracked(
+    doc_blocks: &mut Vec<Block>,
+    index: usize,
+    mut new_blocks: Vec<Block>,
+    position: InsertPosition,
+) -> anyhow::Result<BlockChange> {
+    check_block_index(doc_blocks, index)?;
+    let added = new_blocks.len();
+    let change = match position {
         InsertPosition::Before => {
             // `splice` with an empty range (e.g., `index..index`) inserts at that
             // position without removing any elements.
             doc_blocks.splice(index..index, new_blocks);
+            BlockChange::splice(index, 0, added)
         }
         InsertPosition::After => {
             // To insert after the element at `index`, we specify the position `index + 1`.
             let insert_at = index + 1;
             doc_blocks.splice(insert_at..insert_at, new_blocks);
+            BlockChange::splice(insert_at, 0, added)
         }
         InsertPosition::PrependChild | InsertPosition::AppendChild => {
             // We match on an immutable reference first to avoid borrowing issues,
@@ -60,23 +155,25 @@ pub fn insert(
                         // AppendChild
                         inner_blocks.append(&mut new_blocks);
                     }
+                    BlockChange::edit(index)
                 }
                 Block::Heading(_) => {
                     // For headings, we operate on the main `doc_blocks` vector.
                     let target_level = get_heading_level(&doc_blocks[index]).unwrap(); // Safe to unwrap.
 
-                    if position == InsertPosition::PrependChild {
+                    let insert_at = if position == InsertPosition::PrependChild {
                         // Insert immediately after the heading.
-                        let insert_at = index + 1;
-                        doc_blocks.splice(insert_at..insert_at, new_blocks);
+                        index + 1
                     } else {
                         // AppendChild: Find the end of the section and insert there.
-                        let end_index = find_heading_section_end(doc_blocks, index, target_level);
-                        doc_blocks.splice(end_index..end_index, new_blocks);
-                    }
+                        find_heading_section_end(doc_blocks, index, target_level)
+                    };
+                    doc_blocks.splice(insert_at..insert_at, new_blocks);
+                    BlockChange::splice(insert_at, 0, added)
                 }
                 other_block => {
                     // All other block types are not considered containers for child insertion.
+                    // Code blocks take raw lines instead, see `insert_code_lines`.
                     return Err(SpliceError::InvalidChildInsertion(
                         block_type_name(other_block).to_string(),
                     )
@@ -84,13 +181,163 @@ pub fn insert(
                 }
             }
         }
+    };
+    Ok(change)
+}
+
+/// Adds raw `lines` to the start or the end of the code block at `index`, for the
+/// `PrependChild` and `AppendChild` positions. Trailing line breaks of `lines` are
+/// dropped so that the code block does not grow blank lines at the joint.
+///
+/// Fails, leaving `doc_blocks` untouched, if `index` is out of bounds, the block is
+/// not a code block, or `position` is not a child position.
+pub fn insert_code_lines(
+    doc_blocks: &mut [Block],
+    index: usize,
+    lines: &str,
+    position: InsertPosition,
+) -> anyhow::Result<()> {
+    check_block_index(doc_blocks, index)?;
+    let Block::CodeBlock(code) = &mut doc_blocks[index] else {
+        anyhow::bail!(
+            "Internal error: block at index {} is not a code block",
+            index
+        );
+    };
+    let lines = lines.trim_end_matches(['\r', '\n']);
+    let existing = code.literal.trim_end_matches('\n');
+    code.literal = match position {
+        _ if existing.is_empty() => lines.to_string(),
+        InsertPosition::PrependChild => format!("{lines}\n{existing}"),
+        InsertPosition::AppendChild => format!("{existing}\n{lines}"),
+        InsertPosition::Before | InsertPosition::After => {
+            anyhow::bail!("Internal error: code lines can only be inserted as children")
+        }
+    };
+    Ok(())
+}
+
+/// Inserts a column at the 0-indexed position `column` of `table`, with `header` in the
+/// header row, a copy of `value` in every body row, and `alignment` in the delimiter row.
+/// Rows missing trailing cells are padded first, so that the new cells line up.
+///
+/// Fails, leaving `table` untouched, if `column` is past the last column.
+pub fn insert_table_column(
+    table: &mut Table,
+    column: usize,
+    header: TableCell,
+    value: &TableCell,
+    alignment: Alignment,
+) -> anyhow::Result<()> {
+    let column_count = table.alignments.len();
+    if column > column_count {
+        anyhow::bail!(
+            "Internal error: column {} is out of bounds for a table with {} columns",
+            column,
+            column_count
+        );
+    }
+    for (row_index, row) in table.rows.iter_mut().enumerate() {
+        if row.len() < column_count {
+            row.resize_with(column_count, Vec::new);
+        }
+        let cell = if row_index == 0 {
+            header.clone()
+        } else {
+            value.clone()
+        };
+        row.insert(column, cell);
+    }
+    table.alignments.insert(column, alignment);
+    Ok(())
+}
+
+/// Removes the 0-indexed `column` of `table`, with its cells and its alignment.
+///
+/// Fails, leaving `table` untouched, if `column` is out of bounds or is the only column,
+/// since a table needs at least one.
+pub fn remove_table_column(table: &mut Table, column: usize) -> anyhow::Result<()> {
+    if column >= table.alignments.len() {
+        anyhow::bail!(
+            "Internal error: column {} is out of bounds for a table with {} columns",
+            column,
+            table.alignments.len()
+        );
+    }
+    if table.alignments.len() == 1 {
+        anyhow::bail!("Cannot remove the only column of a table; delete the table instead");
+    }
+    for row in &mut table.rows {
+        if column < row.len() {
+            row.remove(column);
+        }
+    }
+    table.alignments.remove(column);
+    Ok(())
+}
+
+/// Moves the blocks in `range` into a new blockquote, or into a GitHub alert of
+/// `alert_type` when given, which takes their place in the document.
+///
+/// Fails, leaving `doc_blocks` untouched, if `range` is empty or out of bounds.
+pub fn wrap(
+    doc_blocks: &mut Vec<Block>,
+    range: Range<usize>,
+    alert_type: Option<GitHubAlertType>,
+) -> anyhow::Result<()> {
+    if range.is_empty() || range.end > doc_blocks.len() {
+        anyhow::bail!(
+            "Internal error: block range {:?} cannot be wrapped in a document with {} blocks",
+            range,
+            doc_blocks.len()
+        );
     }
+    let blocks: Vec<Block> = doc_blocks.drain(range.clone()).collect();
+    let wrapper = match alert_type {
+        Some(alert_type) => Block::GitHubAlert(GitHubAlert { alert_type, blocks }),
+        None => Block::BlockQuote(blocks),
+    };
+    doc_blocks.insert(range.start, wrapper);
+    Ok(())
+}
+
+/// Replaces the blockquote or GitHub alert at `index` with the blocks it contains.
+///
+/// Fails, leaving `doc_blocks` untouched, if `index` is out of bounds or the block there
+/// is neither a blockquote nor an alert.
+pub fn unwrap(doc_blocks: &mut Vec<Block>, index: usize) -> anyhow::Result<()> {
+    check_block_index(doc_blocks, index)?;
+    let inner_blocks = match &mut doc_blocks[index] {
+        Block::BlockQuote(blocks) => std::mem::take(blocks),
+        Block::GitHubAlert(alert) => std::mem::take(&mut alert.blocks),
+        other => anyhow::bail!(
+            "unwrap requires a blockquote or an alert, but the selector matched a '{}'",
+            block_type_name(other)
+        ),
+    };
+    doc_blocks.splice(index..=index, inner_blocks);
     Ok(())
 }
 
 /// Deletes a block at the specified index from the document.
-pub fn delete(doc_blocks: &mut Vec<Block>, index: usize) {
+///
+/// Fails, leaving `doc_blocks` untouched, if `index` is out of bounds.
+pub fn delete(doc_blocks: &mut Vec<Block>, index: usize) -> anyhow::Result<()> {
+    check_block_index(doc_blocks, index)?;
     doc_blocks.remove(index);
+    Ok(())
+}
+
+/// Fails unless `index` points at a block of `doc_blocks`.
+fn check_block_index(doc_blocks: &[Block], index: usize) -> anyhow::Result<()> {
+    if index >= doc_blocks.len() {
+        anyhow::bail!(
+            "Internal error: block index {} is out of bounds for document with {} blocks",
+            index,
+            doc_blocks.len()
+        );
+    }
+    Ok(())
 }
 
 /// Extracts a vector of `ListItem`s from a vector of `Block`s.
@@ -150,6 +397,13 @@ pub(crate) fn insert_list_item(
         InsertPosition::Before | InsertPosition::After => {
             let new_items = extract_list_items_from_blocks(new_blocks)?;
             if let Some(Block::List(list)) = doc_blocks.get_mut(block_index) {
+                if item_index >= list.items.len() {
+                    anyhow::bail!(
+                        "Internal error: item index {} is out of bounds for list with {} items",
+                        item_index,
+                        list.items.len()
+                    );
+                }
                 let insert_at = if position == InsertPosition::Before {
                     item_index
                 } else {
@@ -214,45 +468,353 @@ pub(crate) fn delete_list_item(
     }
 }
 
+/// Returns the blocks holding the list that a list item found at `block_index` belongs to,
+/// following `path` into nested lists, with the index of that list among them: the
+/// document's blocks for the item of a top-level list, or the blocks of the item its list
+/// is nested in.
+pub(crate) fn item_list_blocks<'a>(
+    doc_blocks: &'a mut Vec<Block>,
+    block_index: usize,
+    path: &[(usize, usize)],
+) -> anyhow::Result<(&'a mut Vec<Block>, usize)> {
+    let mut blocks = doc_blocks;
+    let mut list_index = block_index;
+    for &(item_index, nested_index) in path {
+        let Some(Block::List(list)) = blocks.get_mut(list_index) else {
+            anyhow::bail!(
+                "Internal error: block at index {} is not a list",
+                list_index
+            );
+        };
+        let item_count = list.items.len();
+        let Some(item) = list.items.get_mut(item_index) else {
+            anyhow::bail!(
+                "Internal error: item index {} is out of bounds for list with {} items",
+                item_index,
+                item_count
+            );
+        };
+        blocks = &mut item.blocks;
+        list_index = nested_index;
+    }
+    Ok((blocks, list_index))
+}
+
+/// Returns the blocks holding a block found at `block_index`, following `path` into the
+/// containers nested there, with its index among them.
+pub(crate) fn contained_blocks<'a>(
+    doc_blocks: &'a mut Vec<Block>,
+    block_index: usize,
+    path: &[usize],
+) -> anyhow::Result<(&'a mut Vec<Block>, usize)> {
+    let mut blocks = doc_blocks;
+    let mut index = block_index;
+    let mut rest = path;
+    while let Some((&next, tail)) = rest.split_first() {
+        let (children, child_index, tail) = match blocks.get_mut(index) {
+            Some(Block::BlockQuote(children)) => (children, next, tail),
+            Some(Block::GitHubAlert(alert)) => (&mut alert.blocks, next, tail),
+            Some(Block::FootnoteDefinition(definition)) => (&mut definition.blocks, next, tail),
+            Some(Block::List(list)) => {
+                let (Some(item), Some((&child_index, tail))) =
+                    (list.items.get_mut(next), tail.split_first())
+                else {
+                    anyhow::bail!("Internal error: no list item at {:?}", path);
+                };
+                (&mut item.blocks, child_index, tail)
+            }
+            _ => anyhow::bail!(
+                "Internal error: block at index {} is not a container",
+                index
+            ),
+        };
+        blocks = children;
+        index = child_index;
+        rest = tail;
+    }
+    Ok((blocks, index))
+}
+
 /// Deletes a heading and all blocks in its section.
-pub fn delete_section(doc_blocks: &mut Vec<Block>, start_index: usize) {
+///
+/// Nothing is deleted if the block at `start_index` is not a heading. Fails if `start_index`
+/// is out of bounds.
+pub fn delete_section(doc_blocks: &mut Vec<Block>, start_index: usize) -> anyhow::Result<()> {
+    check_block_index(doc_blocks, start_index)?;
     if let Some(level) = get_heading_level(&doc_blocks[start_index]) {
         let end_index = find_heading_section_end(doc_blocks, start_index, level);
         doc_blocks.drain(start_index..end_index);
     }
+    Ok(())
 }
 
-/// Gets the level (1-6) of a heading block.
-pub(crate) fn get_heading_level(block: &Block) -> Option<u8> {
-    if let Block::Heading(Heading { kind, .. }) = block {
-        Some(match kind {
-            HeadingKind::Atx(level) => *level,
-            HeadingKind::Setext(SetextHeading::Level1) => 1,
-            HeadingKind::Setext(SetextHeading::Level2) => 2,
-        })
+/// Merges the section of the heading at `source_index` into the section of the heading at
+/// `target_index`: the source heading is removed and the blocks of its section are appended
+/// at the end of the target's section.
+///
+/// With `adjust_levels`, the headings among the moved blocks are shifted by the difference
+/// between the two heading levels, clamped to 1-6, so they keep their depth relative to
+/// the target. Fails, leaving `doc_blocks` untouched, if either block is not a heading or
+/// if the target lies inside the source section.
+pub fn merge_sections(
+    doc_blocks: &mut Vec<Block>,
+    source_index: usize,
+    target_index: usize,
+    adjust_levels: bool,
+) -> anyhow::Result<()> {
+    check_block_index(doc_blocks, source_index)?;
+    check_block_index(doc_blocks, target_index)?;
+    let (Some(source), Some(source_level)) = (
+        section_range(doc_blocks, source_index),
+        get_heading_level(&doc_blocks[source_index]),
+    ) else {
+        anyhow::bail!(
+            "merge_sections requires a heading, but the selector matched a '{}'",
+            block_type_name(&doc_blocks[source_index])
+        );
+    };
+    let Some(target_level) = get_heading_level(&doc_blocks[target_index]) else {
+        anyhow::bail!(
+            "merge_sections requires a heading, but the destination matched a '{}'",
+            block_type_name(&doc_blocks[target_index])
+        );
+    };
+    if source.contains(&target_index) {
+        anyhow::bail!(
+            "merge_sections cannot merge a section into itself or into a heading inside it"
+        );
+    }
+
+    let mut body: Vec<Block> = doc_blocks.drain(source.clone()).skip(1).collect();
+    if adjust_levels {
+        let shift = i16::from(target_level) - i16::from(source_level);
+        for block in &mut body {
+            if let Some(level) = get_heading_level(block) {
+                set_heading_level(block, (i16::from(level) + shift).clamp(1, 6) as u8);
+            }
+        }
+    }
+    let target_index = if target_index > source.start {
+        target_index - source.len()
     } else {
-        None
+        target_index
+    };
+    let end = find_heading_section_end(doc_blocks, target_index, target_level);
+    doc_blocks.splice(end..end, body);
+    Ok(())
+}
+
+/// Removes every heading in `scope` whose level is within `levels` and whose section holds
+/// nothing but blank blocks. Returns the number of headings removed and the change made to
+/// `doc_blocks`.
+///
+/// Headings are visited from last to first, so a parent whose only content was an empty
+/// sub-section is removed in the same pass. The part of `scope` past the last block is ignored.
+pub(crate) fn prune_empty_sections(
+    doc_blocks: &mut Vec<Block>,
+    scope: Range<usize>,
+    levels: RangeInclusive<u8>,
+) -> (usize, Option<BlockChange>) {
+    let mut scope_end = scope.end.min(doc_blocks.len());
+    let mut pruned = 0;
+    let mut change: Option<BlockChange> = None;
+
+    for index in (scope.start..scope_end).rev() {
+        let Some(level) = get_heading_level(&doc_blocks[index]) else {
+            continue;
+        };
+        if !levels.contains(&level) {
+            continue;
+        }
+
+        let section_end = find_heading_section_end(doc_blocks, index, level).min(scope_end);
+        if doc_blocks[index + 1..section_end]
+            .iter()
+            .all(is_blank_block)
+        {
+            doc_blocks.drain(index..section_end);
+            scope_end -= section_end - index;
+            pruned += 1;
+            change = BlockChange::chain(
+                change,
+                Some(BlockChange::splice(index, section_end - index, 0)),
+            );
+        }
     }
+
+    (pruned, change)
 }
 
-/// Finds the index that marks the end of a heading section.
-/// The section ends just before the next heading of the same or higher level,
-/// or at the end of the document.
-pub(crate) fn find_heading_section_end(
-    blocks: &[Block],
-    start_index: usize,
-    target_level: u8,
-) -> usize {
-    // We skip to the block after the starting one and find the first block
-    // that meets the end-of-section criteria.
-    for (i, block) in blocks.iter().enumerate().skip(start_index + 1) {
-        if let Some(level) = get_heading_level(block) {
-            if level <= target_level {
-                return i; // Found the boundary, return its index.
+/// Renames every heading in `scope` whose text repeats an earlier heading by appending a
+/// numeric suffix (` (2)`, ` (3)`, ...). Returns the number of headings renamed and the
+/// change made to `doc_blocks`.
+///
+/// The first occurrence keeps its text, and suffixes skip numbers that would collide with
+/// another heading already present in the scope. The part of `scope` past the last block is
+/// ignored.
+pub(crate) fn dedupe_headings(
+    doc_blocks: &mut [Block],
+    scope: Range<usize>,
+) -> (usize, Option<BlockChange>) {
+    let scope = scope.start.min(doc_blocks.len())..scope.end.min(doc_blocks.len());
+    let duplicates = find_duplicate_headings(doc_blocks, scope.clone());
+    let mut taken: HashSet<String> = doc_blocks[scope]
+        .iter()
+        .filter(|block| get_heading_level(block).is_some())
+        .map(|block| heading_key(&block_to_text(block)))
+        .collect();
+    let mut renamed = Vec::new();
+
+    for group in duplicates {
+        let mut suffix = 2;
+        for index in group.indices.into_iter().skip(1) {
+            while taken.contains(&heading_key(&format!("{} ({suffix})", group.text))) {
+                suffix += 1;
             }
+            taken.insert(heading_key(&format!("{} ({suffix})", group.text)));
+            if let Block::Heading(heading) = &mut doc_blocks[index] {
+                heading.content.push(Inline::Text(format!(" ({suffix})")));
+                renamed.push(index);
+            }
+            suffix += 1;
         }
     }
-    blocks.len() // Reached the end of the document, return the length as the end index.
+
+    (renamed.len(), BlockChange::edits(renamed))
+}
+
+/// Rewrites the start numbers of the ordered lists in `blocks` that continue an earlier
+/// list, so their numbering follows on from it. Returns the number of lists renumbered and
+/// the change made to `blocks`.
+///
+/// An ordered list continues the previous ordered list of the same container when it
+/// directly follows it, since Markdown reads the two as one list, or when it starts at a
+/// number other than 1 and only blocks other than headings and thematic breaks separate
+/// them. A list starting at 1 after other blocks begins a new numbering. Lists nested in
+/// list items, block quotes, alerts, and footnotes are renumbered within their container.
+pub(crate) fn renumber_ordered_lists(blocks: &mut [Block]) -> (usize, Option<BlockChange>) {
+    let mut renumbered = Vec::new();
+    renumber_lists(blocks, &mut |index| renumbered.push(index));
+    (renumbered.len(), BlockChange::edits(renumbered))
+}
+
+/// Renumbers the ordered lists of `blocks`, calling `on_renumber` with the index of the
+/// block holding each list it renumbers.
+fn renumber_lists(blocks: &mut [Block], on_renumber: &mut dyn FnMut(usize)) {
+    let mut next = None;
+    let mut follows_list = false;
+
+    for (index, block) in blocks.iter_mut().enumerate() {
+        let mut on_nested = |_| on_renumber(index);
+        match block {
+            Block::List(list) => {
+                for item in &mut list.items {
+                    renumber_lists(&mut item.blocks, &mut on_nested);
+                }
+                let ListKind::Ordered(options) = &mut list.kind else {
+                    follows_list = false;
+                    continue;
+                };
+                if let Some(next) = next {
+                    if (follows_list || options.start != 1) && options.start != next {
+                        options.start = next;
+                        on_renumber(index);
+                    }
+                }
+                next = Some(options.start + list.items.len() as u64);
+                follows_list = true;
+                continue;
+            }
+            Block::Heading(_) | Block::ThematicBreak => next = None,
+            Block::BlockQuote(blocks) => renumber_lists(blocks, &mut on_nested),
+            Block::GitHubAlert(alert) => renumber_lists(&mut alert.blocks, &mut on_nested),
+            Block::FootnoteDefinition(footnote) => {
+                renumber_lists(&mut footnote.blocks, &mut on_nested)
+            }
+            _ => {}
+        }
+        follows_list = false;
+    }
+}
+
+/// Replaces every match of `pattern` in the text of `blocks` with `replacement`, in which
+/// `$1` or `$name` expand to capture groups. Returns the number of replacements made.
+///
+/// Only plain text nodes are rewritten, so the emphasis, links, and code spans around the
+/// text are kept. Code spans, code blocks, raw HTML, and link destinations are left
+/// untouched, and a match cannot span two text nodes (such as text on both sides of a link).
+pub fn replace_text(blocks: &mut [Block], pattern: &Regex, replacement: &str) -> usize {
+    blocks
+        .iter_mut()
+        .map(|block| match block {
+            Block::Paragraph(inlines) => replace_inline_text(inlines, pattern, replacement),
+            Block::Heading(heading) => {
+                replace_inline_text(&mut heading.content, pattern, replacement)
+            }
+            Block::BlockQuote(blocks) => replace_text(blocks, pattern, replacement),
+            Block::List(list) => list
+                .items
+                .iter_mut()
+                .map(|item| replace_text(&mut item.blocks, pattern, replacement))
+                .sum(),
+            Block::Table(table) => table
+                .rows
+                .iter_mut()
+                .flatten()
+                .map(|cell| replace_inline_text(cell, pattern, replacement))
+                .sum(),
+            Block::FootnoteDefinition(footnote) => {
+                replace_text(&mut footnote.blocks, pattern, replacement)
+            }
+            Block::GitHubAlert(alert) => replace_text(&mut alert.blocks, pattern, replacement),
+            Block::ThematicBreak
+            | Block::CodeBlock(_)
+            | Block::HtmlBlock(_)
+            | Block::Definition(_)
+            | Block::Empty => 0,
+        })
+        .sum()
+}
+
+/// Replaces every match of `pattern` in the plain text nodes of `inlines`, as [`replace_text`]
+/// does. Returns the number of replacements made.
+pub(crate) fn replace_inline_text(
+    inlines: &mut [Inline],
+    pattern: &Regex,
+    replacement: &str,
+) -> usize {
+    inlines
+        .iter_mut()
+        .map(|inline| match inline {
+            Inline::Text(text) => {
+                let count = pattern.find_iter(text).count();
+                if count > 0 {
+                    *text = pattern.replace_all(text, replacement).into_owned();
+                }
+                count
+            }
+            Inline::Link(link) => replace_inline_text(&mut link.children, pattern, replacement),
+            Inline::LinkReference(link) => {
+                replace_inline_text(&mut link.text, pattern, replacement)
+            }
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children) => {
+                replace_inline_text(children, pattern, replacement)
+            }
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Returns `true` for blocks that carry no visible content.
+fn is_blank_block(block: &Block) -> bool {
+    match block {
+        Block::Empty => true,
+        Block::Paragraph(_) => block_to_text(block).trim().is_empty(),
+        Block::HtmlBlock(html) => html.trim().is_empty(),
+        _ => false,
+    }
 }
 
 /// Gets a user-friendly name for a block type, used in error messages.
@@ -277,10 +839,14 @@ fn block_type_name(block: &Block) -> &'static str {
 mod tests {
     use super::insert;
     use crate::error::SpliceError;
-    use crate::locator::{list_item_to_text, locate, FoundNode, Selector};
-    use crate::splicer::{insert_list_item, replace, replace_list_item};
+    use crate::locator::{block_to_text, list_item_to_text, locate, FoundNode, Selector};
+    use crate::splicer::{
+        dedupe_headings, delete, delete_section, insert_code_lines, insert_list_item,
+        merge_sections, prune_empty_sections, renumber_ordered_lists, replace, replace_list_item,
+        replace_text, BlockChange,
+    };
     use crate::transaction::InsertPosition;
-    use markdown_ppp::ast::{Block, Document, Inline};
+    use markdown_ppp::ast::{Block, Document, Inline, List, ListKind};
     use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
 
     const TEST_MARKDOWN: &str = r#"# A Heading
@@ -320,7 +886,7 @@ This is the second paragraph.
         };
 
         // --- Action ---
-        replace(&mut doc.blocks, target_index, new_content_doc.blocks);
+        replace(&mut doc.blocks, target_index, new_content_doc.blocks).unwrap();
 
         // --- Verification ---
         assert_eq!(doc.blocks.len(), 4); // H1, P1, P2(new), P3(new)
@@ -606,6 +1172,30 @@ Final content.
         );
     }
 
+    #[test]
+    fn test_insert_code_lines_appends_and_prepends_raw_lines() {
+        let mut doc = parse_str("```sh\ncargo build\n```\n");
+
+        insert_code_lines(
+            &mut doc.blocks,
+            0,
+            "cargo test\n",
+            InsertPosition::AppendChild,
+        )
+        .unwrap();
+        insert_code_lines(&mut doc.blocks, 0, "# Build", InsertPosition::PrependChild).unwrap();
+
+        assert!(
+            matches!(&doc.blocks[0], Block::CodeBlock(code) if code.literal == "# Build\ncargo build\ncargo test"),
+            "Unexpected code block: {:?}",
+            doc.blocks[0]
+        );
+        assert!(
+            insert_code_lines(&mut doc.blocks, 0, "x", InsertPosition::After).is_err(),
+            "Sibling positions should be rejected"
+        );
+    }
+
     #[test]
     fn test_s5_invalid_child_insertion_on_paragraph() {
         // --- Setup ---
@@ -944,4 +1534,166 @@ Final content.
             splice_error
         );
     }
+
+    #[test]
+    fn test_block_change_then_covers_both_changes() {
+        // Two blocks inserted at 1, then the block that was at 3 deleted.
+        let change = BlockChange::splice(1, 0, 2).then(BlockChange::splice(5, 1, 0));
+        assert_eq!(change, BlockChange::splice(1, 3, 4));
+
+        // An edit followed by a deletion before it.
+        let change = BlockChange::edit(4).then(BlockChange::splice(0, 1, 0));
+        assert_eq!(change, BlockChange::splice(0, 5, 4));
+
+        assert_eq!(
+            BlockChange::edits([6, 2, 4]),
+            Some(BlockChange::splice(2, 5, 5))
+        );
+        assert_eq!(BlockChange::edits([]), None);
+        assert!(BlockChange::splice(3, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_prune_empty_sections_cascades_to_parents() {
+        let markdown = "# Guide\n\n## Empty\n\n### Also empty\n\n## Kept\n\nBody.\n\n## Trailing\n";
+        let mut doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        let scope = 0..doc.blocks.len();
+        let (pruned, change) = prune_empty_sections(&mut doc.blocks, scope, 2..=6);
+
+        assert_eq!(pruned, 3);
+        // "## Kept" and its body stay, between the pruned sections.
+        assert_eq!(change, Some(BlockChange::splice(1, 5, 2)));
+        let rendered = markdown_ppp::printer::render_markdown(
+            &doc,
+            markdown_ppp::printer::config::Config::default(),
+        );
+        assert_eq!(rendered, "# Guide\n\n## Kept\n\nBody.");
+    }
+
+    #[test]
+    fn test_prune_empty_sections_respects_levels_and_scope() {
+        let markdown = "# One\n\n## A\n\n# Two\n\n## B\n";
+        let mut doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        // Only the section of "# Two" (blocks after index 2) is in scope, and only h2 qualifies.
+        let (pruned, change) = prune_empty_sections(&mut doc.blocks, 3..4, 2..=2);
+
+        assert_eq!(pruned, 1);
+        assert_eq!(change, Some(BlockChange::splice(3, 1, 0)));
+        assert_eq!(doc.blocks.len(), 3);
+    }
+
+    #[test]
+    fn test_dedupe_headings_appends_suffixes_that_avoid_collisions() {
+        let markdown = "# Setup\n\n## Setup\n\n## Setup (2)\n\n## setup\n";
+        let mut doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        let (renamed, change) = dedupe_headings(&mut doc.blocks, 0..4);
+
+        let texts: Vec<String> = doc.blocks.iter().map(block_to_text).collect();
+        assert_eq!(renamed, 2);
+        assert_eq!(change, Some(BlockChange::splice(1, 3, 3)));
+        assert_eq!(texts, vec!["Setup", "Setup (3)", "Setup (2)", "setup (4)"]);
+    }
+
+    #[test]
+    fn test_renumber_ordered_lists_continues_interrupted_lists() {
+        let markdown = "1. Configure\n\n1. Install\n2. Run\n\n```sh\nx\n```\n\n3. Done\n\n\
+                        Another procedure:\n\n1. Start\n   1. Nested\n\n   4. Stale\n\n\
+                        # Next\n\n7. Seven\n";
+        let mut doc = parse_str(markdown);
+
+        assert_eq!(
+            renumber_ordered_lists(&mut doc.blocks),
+            (3, Some(BlockChange::splice(1, 5, 5)))
+        );
+        fn starts(blocks: &[Block]) -> Vec<u64> {
+            blocks
+                .iter()
+                .filter_map(|block| match block {
+                    Block::List(List {
+                        kind: ListKind::Ordered(options),
+                        ..
+                    }) => Some(options.start),
+                    _ => None,
+                })
+                .collect()
+        }
+        assert_eq!(starts(&doc.blocks), vec![1, 2, 4, 1, 7]);
+        let Block::List(procedure) = &doc.blocks[5] else {
+            panic!("expected a list");
+        };
+        assert_eq!(starts(&procedure.items[0].blocks), vec![1, 2]);
+        assert_eq!(renumber_ordered_lists(&mut doc.blocks), (0, None));
+    }
+
+    #[test]
+    fn test_replace_text_keeps_inline_markup_and_code() {
+        let mut doc = parse_str(
+            "Run **md-splice** via [md-splice](https://x.y/md-splice) or `md-splice`.\n\n\
+             ## md-splice v1\n\n```sh\nmd-splice\n```\n",
+        );
+        let pattern = regex::Regex::new(r"md-splice( v(\d))?").unwrap();
+
+        assert_eq!(replace_text(&mut doc.blocks, &pattern, "mdsplice$1"), 3);
+        let rendered = markdown_ppp::printer::render_markdown(
+            &doc,
+            markdown_ppp::printer::config::Config::default(),
+        );
+        assert_eq!(
+            rendered,
+            "Run **mdsplice** via [mdsplice](https://x.y/md-splice) or `md-splice`.\n\n\
+             ## mdsplice v1\n\n```sh\nmd-splice\n```"
+        );
+    }
+
+    #[test]
+    fn test_merge_sections_appends_the_body_and_adjusts_levels() {
+        let markdown = "# Guide\n\n## Setup\n\nFirst.\n\n## Usage\n\nRun it.\n\n\
+                        # Appendix\n\n### Setup\n\nSecond.\n\n#### Linux\n";
+        let mut doc = parse_str(markdown);
+
+        merge_sections(&mut doc.blocks, 6, 1, true).unwrap();
+        let rendered = markdown_ppp::printer::render_markdown(
+            &doc,
+            markdown_ppp::printer::config::Config::default(),
+        );
+        assert_eq!(
+            rendered,
+            "# Guide\n\n## Setup\n\nFirst.\n\nSecond.\n\n### Linux\n\n## Usage\n\nRun it.\n\n# Appendix"
+        );
+
+        let original = doc.blocks.clone();
+        assert!(merge_sections(&mut doc.blocks, 0, 1, false).is_err());
+        assert!(merge_sections(&mut doc.blocks, 1, 2, false).is_err());
+        assert_eq!(doc.blocks, original);
+    }
+
+    #[test]
+    fn test_out_of_bounds_indices_fail_without_changes() {
+        let mut doc = parse_str("# Title\n\n- one\n- two\n");
+        let original = doc.blocks.clone();
+        let past_end = doc.blocks.len();
+
+        assert!(replace(&mut doc.blocks, past_end, Vec::new()).is_err());
+        assert!(insert(
+            &mut doc.blocks,
+            usize::MAX,
+            Vec::new(),
+            InsertPosition::After
+        )
+        .is_err());
+        assert!(delete(&mut doc.blocks, past_end).is_err());
+        assert!(delete_section(&mut doc.blocks, past_end).is_err());
+        let new_items = parse_str("- three").blocks;
+        assert!(insert_list_item(&mut doc.blocks, 1, 5, new_items, InsertPosition::After).is_err());
+        assert_eq!(
+            prune_empty_sections(&mut doc.blocks, 0..usize::MAX, 1..=6),
+            (0, None)
+        );
+        assert_eq!(dedupe_headings(&mut doc.blocks, 7..9), (0, None));
+
+        assert_eq!(doc.blocks, original);
+    }
 }
@@ -0,0 +1,277 @@
+//! Addresses the cells of pipe tables.
+//!
+//! A cell is addressed by a [`CellAddress`]: row `0` is the header row and rows `1..` are
+//! the body rows, while columns are counted from `1`. Cells hold inline content only, so
+//! content written into a cell must be a single paragraph.
+//!
+//! Body rows are inserted from a [`TableRow`] and matched by their text, which is the plain
+//! text of their cells separated by tabs.
+
+use crate::error::SpliceError;
+use crate::locator::{inlines_to_text, FoundNode};
+use markdown_ppp::ast::{Block, Inline, Table, TableCell, TableRow as AstTableRow};
+use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+use markdown_ppp::printer::{config::Config as PrinterConfig, render_markdown};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The position of a cell in a table.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+pub struct CellAddress {
+    /// The row of the cell: `0` for the header row, `1` for the first body row.
+    pub row: usize,
+    /// The column of the cell, counted from `1`.
+    pub column: usize,
+}
+
+impl fmt::Display for CellAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}, column {}", self.row, self.column)
+    }
+}
+
+/// Returns the cell at `address` in the table `block`.
+///
+/// Fails if `block` is not a table or the table has no such cell.
+pub fn table_cell(block: &Block, address: CellAddress) -> Result<&[Inline], SpliceError> {
+    let Block::Table(table) = block else {
+        return Err(not_a_table());
+    };
+    let (row, column) = cell_index(table.rows.len(), table.alignments.len(), address)?;
+    Ok(table.rows[row].get(column).map_or(&[], Vec::as_slice))
+}
+
+/// Returns the cell at `address` in the table `block`, for modification.
+///
+/// Fails if `block` is not a table or the table has no such cell.
+pub fn table_cell_mut(
+    block: &mut Block,
+    address: CellAddress,
+) -> Result<&mut TableCell, SpliceError> {
+    let Block::Table(table) = block else {
+        return Err(not_a_table());
+    };
+    let (row, column) = cell_index(table.rows.len(), table.alignments.len(), address)?;
+    let cells = &mut table.rows[row];
+    // Rows with missing trailing cells render them empty; materialize them before writing.
+    if cells.len() <= column {
+        cells.resize_with(column + 1, Vec::new);
+    }
+    Ok(&mut cells[column])
+}
+
+/// Renders the inline content of a cell as Markdown, without a trailing line break.
+pub fn render_cell(cell: &[Inline]) -> String {
+    let fragment = markdown_ppp::ast::Document {
+        blocks: vec![Block::Paragraph(cell.to_vec())],
+    };
+    render_markdown(&fragment, PrinterConfig::default())
+        .trim_end()
+        .to_string()
+}
+
+/// Turns the blocks parsed from new cell content into the inlines of a cell. No blocks
+/// give an empty cell.
+///
+/// Fails unless the content is a single paragraph, since cells cannot hold other blocks.
+pub fn cell_content(blocks: Vec<Block>) -> Result<TableCell, SpliceError> {
+    let mut blocks = blocks.into_iter();
+    match (blocks.next(), blocks.next()) {
+        (None, _) => Ok(Vec::new()),
+        (Some(Block::Paragraph(inlines)), None) => Ok(inlines),
+        _ => Err(SpliceError::OperationFailed(
+            "Table cell content must be a single paragraph of inline Markdown".to_string(),
+        )),
+    }
+}
+
+/// The content of a table row to insert.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[serde(untagged)]
+pub enum TableRow {
+    /// A pipe-delimited Markdown row, such as `| api | *ok* |`.
+    Markdown(String),
+    /// The inline Markdown of each cell, from the first column on.
+    Cells(Vec<String>),
+}
+
+impl Default for TableRow {
+    fn default() -> Self {
+        Self::Cells(Vec::new())
+    }
+}
+
+impl TableRow {
+    /// Parses the row into the cells of a table with `column_count` columns. Missing
+    /// trailing cells are left empty.
+    ///
+    /// Fails if the row has more cells than the table has columns, or if a cell holds
+    /// anything but inline Markdown.
+    pub fn to_cells(&self, column_count: usize) -> Result<AstTableRow, SpliceError> {
+        let mut cells = match self {
+            Self::Markdown(markdown) => parse_markdown_row(markdown)?,
+            Self::Cells(cells) => cells
+                .iter()
+                .map(|cell| parse_cell(cell))
+                .collect::<Result<_, _>>()?,
+        };
+        if cells.len() > column_count {
+            return Err(SpliceError::OperationFailed(format!(
+                "The row has {} cells, but the table has {column_count} columns",
+                cells.len()
+            )));
+        }
+        cells.resize_with(column_count, Vec::new);
+        Ok(cells)
+    }
+}
+
+/// Parses a pipe-delimited row as the body row of a table with at least as many columns.
+fn parse_markdown_row(markdown: &str) -> Result<AstTableRow, SpliceError> {
+    let line = markdown.trim();
+    if line.is_empty() || line.contains('\n') {
+        return Err(SpliceError::OperationFailed(
+            "A Markdown table row must be a single non-empty line".to_string(),
+        ));
+    }
+    // A row has at most one cell more than it has pipes; the parser pads it to the header.
+    let columns = line.matches('|').count() + 1;
+    let table = format!(
+        "|{}\n|{}\n{line}\n",
+        " |".repeat(columns),
+        "---|".repeat(columns)
+    );
+    let document = parse_markdown(MarkdownParserState::default(), &table)
+        .map_err(|err| SpliceError::MarkdownParse(err.to_string()))?;
+    match document.blocks.as_slice() {
+        [Block::Table(table)] if table.rows.len() == 2 => {
+            let mut cells = table.rows[1].clone();
+            // Padding cells hold an empty text node rather than no inlines at all.
+            while cells.last().is_some_and(|cell| {
+                cell.iter()
+                    .all(|inline| matches!(inline, Inline::Text(text) if text.is_empty()))
+            }) {
+                cells.pop();
+            }
+            Ok(cells)
+        }
+        _ => Err(SpliceError::OperationFailed(format!(
+            "'{line}' is not a Markdown table row"
+        ))),
+    }
+}
+
+/// Renders a table row as a pipe-delimited Markdown row, such as `| api | *ok* |`. Missing
+/// trailing cells of a table with `column_count` columns are rendered empty.
+pub fn render_row(row: &[TableCell], column_count: usize) -> String {
+    let mut rendered = String::from("|");
+    for column in 0..column_count.max(row.len()) {
+        let cell = row.get(column).map_or(&[][..], Vec::as_slice);
+        rendered.push(' ');
+        rendered.push_str(&render_cell(cell).replace('|', "\\|"));
+        rendered.push_str(" |");
+    }
+    rendered
+}
+
+/// Renders a table row matched by a `tr` selector as a pipe-delimited row, or a cell
+/// matched by a `td` selector as its inline Markdown, followed by a line break unless the
+/// cell is empty. Returns `None` for any other node.
+pub fn render_table_node(blocks: &[Block], found: &FoundNode) -> Option<String> {
+    let mut rendered = match *found {
+        FoundNode::TableRow {
+            block_index, row, ..
+        } => match blocks.get(block_index) {
+            Some(Block::Table(table)) => render_row(row, table.alignments.len()),
+            _ => return None,
+        },
+        FoundNode::TableCell { cell, .. } => render_cell(cell),
+        _ => return None,
+    };
+    if !rendered.is_empty() {
+        rendered.push('\n');
+    }
+    Some(rendered)
+}
+
+/// Returns the text a table row is matched by: the plain text of its cells, separated by
+/// tabs.
+pub fn row_text(row: &[TableCell]) -> String {
+    row.iter()
+        .map(|cell| inlines_to_text(cell))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// Returns the index in `table.rows` of the first body row whose text contains `contains`
+/// and matches `regex`, whichever are given.
+pub fn find_body_row(
+    table: &Table,
+    contains: Option<&str>,
+    regex: Option<&Regex>,
+) -> Option<usize> {
+    table
+        .rows
+        .iter()
+        .enumerate()
+        .skip(1)
+        .find_map(|(index, row)| {
+            let text = row_text(row);
+            let matches = contains.is_none_or(|contains| text.contains(contains))
+                && regex.is_none_or(|regex| regex.is_match(&text));
+            matches.then_some(index)
+        })
+}
+
+/// Returns the 0-indexed column of `table` whose header text is `name`, ignoring
+/// surrounding whitespace.
+///
+/// Fails with the list of headers when no column has that header.
+pub fn find_column(table: &Table, name: &str) -> Result<usize, SpliceError> {
+    let headers: Vec<String> = table
+        .rows
+        .first()
+        .map(|row| row.iter().map(|cell| inlines_to_text(cell)).collect())
+        .unwrap_or_default();
+    headers
+        .iter()
+        .position(|header| header.trim() == name.trim())
+        .filter(|&column| column < table.alignments.len())
+        .ok_or_else(|| {
+            SpliceError::OperationFailed(format!(
+                "The table has no column '{name}'; its columns are {}",
+                headers
+                    .iter()
+                    .map(|header| format!("'{}'", header.trim()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        })
+}
+
+/// Parses inline Markdown, such as a header or a default value, into the content of a cell.
+pub fn parse_cell(markdown: &str) -> Result<TableCell, SpliceError> {
+    let document = parse_markdown(MarkdownParserState::default(), markdown.trim())
+        .map_err(|err| SpliceError::MarkdownParse(err.to_string()))?;
+    cell_content(document.blocks)
+}
+
+fn cell_index(
+    row_count: usize,
+    column_count: usize,
+    address: CellAddress,
+) -> Result<(usize, usize), SpliceError> {
+    if address.row >= row_count || address.column == 0 || address.column > column_count {
+        return Err(SpliceError::OperationFailed(format!(
+            "The table has no cell at {address}: it has {} body rows and {} columns",
+            row_count.saturating_sub(1),
+            column_count
+        )));
+    }
+    Ok((address.row, address.column - 1))
+}
+
+fn not_a_table() -> SpliceError {
+    SpliceError::OperationFailed("The selector must match a table to address a cell".to_string())
+}
@@ -0,0 +1,200 @@
+//! Generates tables of contents: nested lists of links to the headings of a document.
+//!
+//! A table of contents sits between a `<!-- toc -->` and a `<!-- tocstop -->` comment, so
+//! that it can be found and refreshed once the headings change. `<!-- TOC -->` and
+//! `<!-- /TOC -->`, as written by other tools, are recognized too.
+//!
+//! Links point at the anchor of each heading: its explicit `{#id}` attribute when it has
+//! one, otherwise the slug the chosen [`SlugStyle`] derives from its text.
+
+use crate::anchors::explicit_id;
+use crate::comments::comment_text;
+use crate::error::SpliceError;
+use crate::locator::block_to_text;
+use crate::sections::get_heading_level;
+use crate::slugify::{slugify, Slugger};
+use crate::transaction::SlugStyle;
+use markdown_ppp::ast::{Block, Inline, Link, List, ListBulletKind, ListItem, ListKind};
+use std::ops::RangeInclusive;
+
+/// The comment opening a table of contents.
+pub const TOC_START: &str = "<!-- toc -->";
+/// The comment closing a table of contents.
+pub const TOC_END: &str = "<!-- tocstop -->";
+
+/// A heading listed in a table of contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    level: u8,
+    text: String,
+    anchor: String,
+}
+
+/// Returns the indices of the comments opening and closing the first table of contents in
+/// `blocks`, or `None` when there is no opening comment.
+///
+/// Fails when an opening comment is not followed by a closing one.
+pub fn find_toc_markers(blocks: &[Block]) -> Result<Option<(usize, usize)>, SpliceError> {
+    let Some(start) = blocks.iter().position(|block| is_marker(block, &["toc"])) else {
+        return Ok(None);
+    };
+    blocks[start + 1..]
+        .iter()
+        .position(|block| is_marker(block, &["tocstop", "/toc"]))
+        .map(|offset| Some((start, start + 1 + offset)))
+        .ok_or_else(|| {
+            SpliceError::OperationFailed(format!(
+                "The table of contents opened at block {start} has no closing {TOC_END} comment"
+            ))
+        })
+}
+
+/// Builds the table of contents of the top-level headings of `blocks` whose level is in
+/// `levels`, leaving out the heading at `exclude` (the one the table sits under).
+///
+/// Returns `None` when no heading qualifies. Deeper headings are nested under the closest
+/// shallower heading before them; a heading deeper than any before it starts the list.
+pub fn build_toc(
+    blocks: &[Block],
+    levels: RangeInclusive<u8>,
+    style: SlugStyle,
+    exclude: Option<usize>,
+) -> Option<Block> {
+    let mut slugger = Slugger::new();
+    let entries: Vec<Entry> = blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, block)| {
+            let Block::Heading(heading) = block else {
+                return None;
+            };
+            let level = get_heading_level(block)?;
+            let mut text = block_to_text(block).trim().to_string();
+            // Anchors are numbered across every heading, listed or not.
+            let anchor = match explicit_id(heading) {
+                Some(id) => {
+                    let attribute = format!("{{#{id}}}");
+                    text = text.trim_end_matches(&attribute).trim_end().to_string();
+                    id.to_string()
+                }
+                None => slugger.claim(style_slug(&text, style)),
+            };
+            let listed = levels.contains(&level) && exclude != Some(index);
+            listed.then_some(Entry {
+                level,
+                text,
+                anchor,
+            })
+        })
+        .collect();
+
+    let mut entries = entries.into_iter().peekable();
+    let items = nest(&mut entries, 0);
+    (!items.is_empty()).then(|| toc_list(items))
+}
+
+/// Returns the blocks of an empty table of contents: its opening and closing comments.
+pub fn toc_markers() -> Vec<Block> {
+    vec![
+        Block::HtmlBlock(TOC_START.to_string()),
+        Block::HtmlBlock(TOC_END.to_string()),
+    ]
+}
+
+fn is_marker(block: &Block, names: &[&str]) -> bool {
+    match block {
+        Block::HtmlBlock(html) => comment_text(html)
+            .is_some_and(|text| names.iter().any(|name| text.eq_ignore_ascii_case(name))),
+        _ => false,
+    }
+}
+
+fn style_slug(text: &str, style: SlugStyle) -> String {
+    let slug = slugify(text);
+    match style {
+        SlugStyle::Github => slug,
+        SlugStyle::Gitlab => {
+            let mut collapsed = String::with_capacity(slug.len());
+            for c in slug.chars() {
+                if !(c == '-' && collapsed.ends_with('-')) {
+                    collapsed.push(c);
+                }
+            }
+            collapsed
+        }
+    }
+}
+
+/// Turns consecutive entries deeper than `parent_level` into list items, nesting deeper
+/// ones under them.
+fn nest(
+    entries: &mut std::iter::Peekable<impl Iterator<Item = Entry>>,
+    parent_level: u8,
+) -> Vec<ListItem> {
+    let mut items = Vec::new();
+    while let Some(entry) = entries.next_if(|entry| entry.level > parent_level) {
+        let link = Inline::Link(Link {
+            destination: format!("#{}", entry.anchor),
+            title: None,
+            children: vec![Inline::Text(entry.text)],
+        });
+        let mut blocks = vec![Block::Paragraph(vec![link])];
+        let children = nest(entries, entry.level);
+        if !children.is_empty() {
+            blocks.push(toc_list(children));
+        }
+        items.push(ListItem { task: None, blocks });
+    }
+    items
+}
+
+fn toc_list(items: Vec<ListItem>) -> Block {
+    Block::List(List {
+        kind: ListKind::Bullet(ListBulletKind::Dash),
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{default_printer_config, render_fragment};
+    use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+
+    fn render(block: Block) -> String {
+        render_fragment(&[block], default_printer_config())
+    }
+
+    #[test]
+    fn test_build_toc_nests_headings_and_links_their_anchors() {
+        let markdown = "# Guide\n\n## Contents\n\n## Set up -- fast\n\n### Install {#install}\n\n\
+                        #### Deep\n\n## Set up -- fast\n";
+        let doc = parse_markdown(MarkdownParserState::default(), markdown).unwrap();
+
+        let github = build_toc(&doc.blocks, 2..=3, SlugStyle::Github, Some(1)).unwrap();
+        assert_eq!(
+            render(github),
+            "- [Set up -- fast](#set-up----fast)\n  \n  - [Install](#install)\n\
+             - [Set up -- fast](#set-up----fast-1)\n"
+        );
+
+        let gitlab = build_toc(&doc.blocks, 3..=6, SlugStyle::Gitlab, None).unwrap();
+        assert_eq!(
+            render(gitlab),
+            "- [Install](#install)\n  \n  - [Deep](#deep)\n"
+        );
+        assert_eq!(build_toc(&doc.blocks, 5..=6, SlugStyle::Github, None), None);
+    }
+
+    #[test]
+    fn test_find_toc_markers_requires_a_closing_comment() {
+        let doc = parse_markdown(
+            MarkdownParserState::default(),
+            "Intro.\n\n<!-- TOC -->\n\n- old\n\n<!-- /TOC -->\n",
+        )
+        .unwrap();
+        assert_eq!(find_toc_markers(&doc.blocks).unwrap(), Some((1, 3)));
+        assert_eq!(find_toc_markers(&doc.blocks[..1]).unwrap(), None);
+        assert!(find_toc_markers(&doc.blocks[..3]).is_err());
+    }
+}
@@ -1,60 +1,609 @@
 use crate::frontmatter::FrontmatterFormat;
-use serde::Deserialize;
+use crate::locator::{TaskStatus, TieBreak};
+use crate::table::{CellAddress, TableRow};
+use serde::{Deserialize, Serialize};
 use serde_yaml::Value as YamlValue;
+use std::fmt;
 use std::path::PathBuf;
 
 fn default_select_ordinal() -> usize {
     1
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+fn is_default_select_ordinal(ordinal: &usize) -> bool {
+    *ordinal == default_select_ordinal()
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+fn is_default_tie_break(tie_break: &TieBreak) -> bool {
+    *tie_break == TieBreak::default()
+}
+
+fn is_default_slug_style(style: &SlugStyle) -> bool {
+    *style == SlugStyle::default()
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 /// A single atomic mutation that can be applied to a [`MarkdownDocument`](crate::MarkdownDocument).
 #[serde(tag = "op", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum Operation {
     /// Insert content relative to a matched selector.
     Insert(InsertOperation),
     /// Replace the matched selector (optionally spanning until another selector).
     Replace(ReplaceOperation),
+    /// Run a regex substitution on the text of the matched blocks, keeping their markup.
+    ReplaceText(ReplaceTextOperation),
     /// Delete the matched selector (optionally spanning until another selector).
     Delete(DeleteOperation),
+    /// Replace the matched selector, or insert the content at a fallback when nothing matches.
+    Upsert(UpsertOperation),
+    /// Move the matched selector next to (or into) another node.
+    Move(MoveOperation),
+    /// Copy the matched selector (optionally a section or range) next to (or into) another node.
+    Copy(CopyOperation),
+    /// Exchange the positions of the nodes (or heading sections) matched by two selectors.
+    Swap(SwapOperation),
+    /// Change the text of the matched heading, keeping its section.
+    RenameHeading(RenameHeadingOperation),
+    /// Raise or lower the level of the matched heading, optionally with its whole section.
+    ShiftHeading(ShiftHeadingOperation),
+    /// Fold the section of the matched heading into the section of another heading.
+    MergeSections(MergeSectionsOperation),
     /// Assign or update a value within document frontmatter.
     SetFrontmatter(SetFrontmatterOperation),
     /// Remove a key from document frontmatter.
     DeleteFrontmatter(DeleteFrontmatterOperation),
     /// Replace the entire frontmatter block.
     ReplaceFrontmatter(ReplaceFrontmatterOperation),
+    /// Remove headings whose sections contain no content.
+    PruneEmptySections(PruneEmptySectionsOperation),
+    /// Rename headings whose text repeats an earlier heading.
+    DedupeHeadings(DedupeHeadingsOperation),
+    /// Renumber ordered lists that continue an earlier list.
+    Renumber(RenumberOperation),
+    /// Copy a heading section from another Markdown file into the document.
+    ImportSection(ImportSectionOperation),
+    /// Turn a blockquote into a GitHub alert, or change the type of an existing alert.
+    SetAlert(SetAlertOperation),
+    /// Turn a GitHub alert back into a plain blockquote.
+    RemoveAlert(RemoveAlertOperation),
+    /// Wrap the matched blocks in a blockquote, or in a GitHub alert of a given type.
+    Wrap(WrapOperation),
+    /// Replace the matched blockquote or GitHub alert with the blocks it contains.
+    Unwrap(UnwrapOperation),
+    /// Insert an HTML comment next to a block, or update the comment already there.
+    SetComment(SetCommentOperation),
+    /// Remove the HTML comment next to a block.
+    RemoveComment(RemoveCommentOperation),
+    /// Replace the content between the opening and closing tags of an HTML element.
+    ReplaceInnerHtml(ReplaceInnerHtmlOperation),
+    /// Wrap the matched blocks in a collapsible `<details>` element with a `<summary>`.
+    WrapDetails(WrapDetailsOperation),
+    /// Remove the `<details>` element around the matched blocks, keeping its Markdown.
+    UnwrapDetails(UnwrapDetailsOperation),
+    /// Set, change, or clear the language of a code block.
+    SetCodeLanguage(SetCodeLanguageOperation),
+    /// Set or remove one attribute in the info string of a code block.
+    SetCodeAttribute(SetCodeAttributeOperation),
+    /// Insert a body row into a table, before or after a matched row.
+    InsertTableRow(InsertTableRowOperation),
+    /// Delete the first body row of a table matching its text.
+    DeleteTableRow(DeleteTableRowOperation),
+    /// Add a column to a table, with a header and a value for every body row.
+    AddTableColumn(AddTableColumnOperation),
+    /// Remove a column of a table by its header.
+    RemoveTableColumn(RemoveTableColumnOperation),
+    /// Change the header of a table column.
+    RenameTableColumn(RenameTableColumnOperation),
+    /// Generate or refresh the table of contents of the document.
+    UpdateToc(UpdateTocOperation),
+    /// Check a condition on the document without changing it, failing the batch if it does not hold.
+    Assert(AssertOperation),
+    /// Render the matched content without changing it, capturing the result for later use.
+    Get(GetOperation),
+}
+
+impl Operation {
+    /// Returns the `op` tag used for this operation in operation files (e.g. `"replace"`).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Operation::Insert(_) => "insert",
+            Operation::Replace(_) => "replace",
+            Operation::ReplaceText(_) => "replace_text",
+            Operation::Delete(_) => "delete",
+            Operation::Upsert(_) => "upsert",
+            Operation::Move(_) => "move",
+            Operation::Copy(_) => "copy",
+            Operation::Swap(_) => "swap",
+            Operation::RenameHeading(_) => "rename_heading",
+            Operation::ShiftHeading(_) => "shift_heading",
+            Operation::MergeSections(_) => "merge_sections",
+            Operation::SetFrontmatter(_) => "set_frontmatter",
+            Operation::DeleteFrontmatter(_) => "delete_frontmatter",
+            Operation::ReplaceFrontmatter(_) => "replace_frontmatter",
+            Operation::PruneEmptySections(_) => "prune_empty_sections",
+            Operation::DedupeHeadings(_) => "dedupe_headings",
+            Operation::Renumber(_) => "renumber",
+            Operation::ImportSection(_) => "import_section",
+            Operation::SetAlert(_) => "set_alert",
+            Operation::RemoveAlert(_) => "remove_alert",
+            Operation::Wrap(_) => "wrap",
+            Operation::Unwrap(_) => "unwrap",
+            Operation::SetComment(_) => "set_comment",
+            Operation::RemoveComment(_) => "remove_comment",
+            Operation::ReplaceInnerHtml(_) => "replace_inner_html",
+            Operation::WrapDetails(_) => "wrap_details",
+            Operation::UnwrapDetails(_) => "unwrap_details",
+            Operation::SetCodeLanguage(_) => "set_code_language",
+            Operation::SetCodeAttribute(_) => "set_code_attribute",
+            Operation::InsertTableRow(_) => "insert_table_row",
+            Operation::DeleteTableRow(_) => "delete_table_row",
+            Operation::AddTableColumn(_) => "add_table_column",
+            Operation::RemoveTableColumn(_) => "remove_table_column",
+            Operation::RenameTableColumn(_) => "rename_table_column",
+            Operation::UpdateToc(_) => "update_toc",
+            Operation::Assert(_) => "assert",
+            Operation::Get(_) => "get",
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    /// Formats the operation as its `op` tag followed by the nodes it targets, as in
+    /// `delete h2~"Draft" (section)` or `move @intro before h1~"Guide"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.kind())?;
+        match self {
+            Operation::Insert(op) => write!(
+                f,
+                " {} {}",
+                position_name(op.position),
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::Replace(op) => {
+                write!(
+                    f,
+                    " {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                write_until(f, op.until.as_ref(), op.until_ref.as_ref())?;
+                write_cell(f, op.cell)
+            }
+            Operation::ReplaceText(op) => {
+                write!(
+                    f,
+                    " /{}/ in {}",
+                    op.pattern,
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                write_until(f, op.until.as_ref(), op.until_ref.as_ref())?;
+                write_section(f, op.section)
+            }
+            Operation::Delete(op) => {
+                write!(
+                    f,
+                    " {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                write_until(f, op.until.as_ref(), op.until_ref.as_ref())?;
+                write_section(f, op.section)
+            }
+            Operation::Upsert(op) => {
+                write!(
+                    f,
+                    " {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                if op.fallback.is_some() || op.fallback_ref.is_some() {
+                    write!(
+                        f,
+                        " else {} {}",
+                        position_name(op.position),
+                        SelectorSource(op.fallback.as_ref(), op.fallback_ref.as_ref())
+                    )?;
+                }
+                write_section(f, op.section)
+            }
+            Operation::Move(op) => {
+                write!(
+                    f,
+                    " {} {} {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref()),
+                    position_name(op.position),
+                    SelectorSource(op.destination.as_ref(), op.destination_ref.as_ref())
+                )?;
+                write_section(f, op.section)
+            }
+            Operation::Copy(op) => {
+                write!(
+                    f,
+                    " {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                write_until(f, op.until.as_ref(), op.until_ref.as_ref())?;
+                write!(
+                    f,
+                    " {} {}",
+                    position_name(op.position),
+                    SelectorSource(op.destination.as_ref(), op.destination_ref.as_ref())
+                )?;
+                write_section(f, op.section)
+            }
+            Operation::Swap(op) => {
+                write!(
+                    f,
+                    " {} with {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref()),
+                    SelectorSource(op.other.as_ref(), op.other_ref.as_ref())
+                )?;
+                write_section(f, op.section)
+            }
+            Operation::RenameHeading(op) => write!(
+                f,
+                " {} to {:?}",
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref()),
+                op.text
+            ),
+            Operation::ShiftHeading(op) => {
+                write!(
+                    f,
+                    " {} by {:+}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref()),
+                    op.delta
+                )?;
+                write_section(f, op.include_section)
+            }
+            Operation::MergeSections(op) => write!(
+                f,
+                " {} into {}",
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref()),
+                SelectorSource(op.destination.as_ref(), op.destination_ref.as_ref())
+            ),
+            Operation::SetFrontmatter(op) => write!(f, " {}", op.key),
+            Operation::DeleteFrontmatter(op) => write!(f, " {}", op.key),
+            Operation::ReplaceFrontmatter(_) => Ok(()),
+            Operation::PruneEmptySections(op) => {
+                write_scope(f, "within", op.within.as_ref(), op.within_ref.as_ref())
+            }
+            Operation::DedupeHeadings(op) => {
+                write_scope(f, "within", op.within.as_ref(), op.within_ref.as_ref())
+            }
+            Operation::Renumber(op) => {
+                write_scope(f, "within", op.within.as_ref(), op.within_ref.as_ref())
+            }
+            Operation::ImportSection(op) => write!(
+                f,
+                " {} from {}, {}",
+                op.source_selector,
+                op.source_file.display(),
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::SetAlert(op) => write!(
+                f,
+                " {} to [!{}]",
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref()),
+                format!("{:?}", op.alert_type).to_uppercase()
+            ),
+            Operation::Wrap(op) => {
+                write!(
+                    f,
+                    " {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                write_until(f, op.until.as_ref(), op.until_ref.as_ref())?;
+                write_section(f, op.section)
+            }
+            Operation::WrapDetails(op) => {
+                write!(
+                    f,
+                    " {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                write_until(f, op.until.as_ref(), op.until_ref.as_ref())?;
+                write_section(f, op.section)
+            }
+            Operation::Get(op) => {
+                write!(
+                    f,
+                    " {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                write_until(f, op.until.as_ref(), op.until_ref.as_ref())?;
+                write_section(f, op.section)?;
+                write_cell(f, op.cell)
+            }
+            Operation::Assert(op) if op.selector.is_none() && op.selector_ref.is_none() => {
+                match &op.frontmatter_key {
+                    Some(key) => write!(f, " frontmatter {key}"),
+                    None => Ok(()),
+                }
+            }
+            Operation::Assert(op) => write!(
+                f,
+                " {}",
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::RemoveAlert(op) => write!(
+                f,
+                " {}",
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::Unwrap(op) => write!(
+                f,
+                " {}",
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::SetComment(op) => write!(
+                f,
+                " {}",
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::RemoveComment(op) => write!(
+                f,
+                " {}",
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::ReplaceInnerHtml(op) => write!(
+                f,
+                " {}",
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::UnwrapDetails(op) => write!(
+                f,
+                " {}",
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::SetCodeLanguage(op) => {
+                write!(
+                    f,
+                    " {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                match &op.language {
+                    Some(language) => write!(f, " to {language}"),
+                    None => f.write_str(" (clear)"),
+                }
+            }
+            Operation::SetCodeAttribute(op) => {
+                write!(
+                    f,
+                    " {}",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                match (&op.value, op.remove) {
+                    (_, true) => write!(f, " remove {}", op.attribute),
+                    (Some(value), false) => write!(f, " {}={value}", op.attribute),
+                    (None, false) => write!(f, " {}", op.attribute),
+                }
+            }
+            Operation::InsertTableRow(op) => {
+                write!(
+                    f,
+                    " {} {} ",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref()),
+                    position_name(op.position)
+                )?;
+                if op.row_contains.is_none() && op.row_regex.is_none() {
+                    match op.position {
+                        InsertPosition::Before | InsertPosition::PrependChild => {
+                            f.write_str("first row")
+                        }
+                        InsertPosition::After | InsertPosition::AppendChild => {
+                            f.write_str("last row")
+                        }
+                    }
+                } else {
+                    write_row_match(f, op.row_contains.as_deref(), op.row_regex.as_deref())
+                }
+            }
+            Operation::DeleteTableRow(op) => {
+                write!(
+                    f,
+                    " {} ",
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                write_row_match(f, op.row_contains.as_deref(), op.row_regex.as_deref())
+            }
+            Operation::AddTableColumn(op) => {
+                write!(
+                    f,
+                    " {:?} in {}",
+                    op.header,
+                    SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+                )?;
+                match &op.column {
+                    Some(column) => write!(f, " {} {column:?}", position_name(op.position)),
+                    None => Ok(()),
+                }
+            }
+            Operation::RemoveTableColumn(op) => write!(
+                f,
+                " {:?} in {}",
+                op.column,
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::RenameTableColumn(op) => write!(
+                f,
+                " {:?} to {:?} in {}",
+                op.column,
+                op.header,
+                SelectorSource(op.selector.as_ref(), op.selector_ref.as_ref())
+            ),
+            Operation::UpdateToc(op) => {
+                write_scope(f, "under", op.selector.as_ref(), op.selector_ref.as_ref())
+            }
+        }
+    }
+}
+
+/// Writes the criteria matching a table row, as in `row~"api"` or `row/^v\d/`.
+fn write_row_match(
+    f: &mut fmt::Formatter<'_>,
+    contains: Option<&str>,
+    regex: Option<&str>,
+) -> fmt::Result {
+    f.write_str("row")?;
+    if let Some(contains) = contains {
+        write!(f, "~{contains:?}")?;
+    }
+    if let Some(regex) = regex {
+        write!(f, "/{regex}/")?;
+    }
+    Ok(())
+}
+
+fn write_until(
+    f: &mut fmt::Formatter<'_>,
+    until: Option<&Selector>,
+    until_ref: Option<&String>,
+) -> fmt::Result {
+    if until.is_some() || until_ref.is_some() {
+        write!(f, " until {}", SelectorSource(until, until_ref))?;
+    }
+    Ok(())
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone)]
+fn write_section(f: &mut fmt::Formatter<'_>, section: bool) -> fmt::Result {
+    if section {
+        f.write_str(" (section)")?;
+    }
+    Ok(())
+}
+
+fn write_cell(f: &mut fmt::Formatter<'_>, cell: Option<CellAddress>) -> fmt::Result {
+    if let Some(cell) = cell {
+        write!(f, " (cell at {cell})")?;
+    }
+    Ok(())
+}
+
+fn position_name(position: InsertPosition) -> &'static str {
+    match position {
+        InsertPosition::Before => "before",
+        InsertPosition::After => "after",
+        InsertPosition::PrependChild => "prepend_child",
+        InsertPosition::AppendChild => "append_child",
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 /// Criteria describing a node to match in the Markdown AST.
 pub struct Selector {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Optional alias assigned to this selector for later reuse.
     pub alias: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Restricts matches to nodes of a given HTML-like element type (e.g., `h2`).
     pub select_type: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Restricts matches to nodes whose rendered text contains the provided substring.
     pub select_contains: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to nodes whose rendered text contains the provided substring once
+    /// both are lowercased and their runs of whitespace collapsed, so `getting started`
+    /// matches `## Getting  Started`.
+    pub select_contains_ci: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Restricts matches to nodes whose rendered text satisfies the provided regex.
     pub select_regex: Option<String>,
-    #[serde(default = "default_select_ordinal")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Excludes nodes whose rendered text contains the provided substring, such as `[x]` to
+    /// skip checked tasks.
+    pub select_not_contains: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Excludes nodes whose rendered text satisfies the provided regex.
+    pub select_not_regex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to nodes whose content fingerprint starts with the provided hex digits.
+    pub select_fingerprint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to blocks directly preceded by an HTML comment with exactly this text,
+    /// such as `slot: release-notes` for `<!-- slot: release-notes -->`.
+    pub select_annotation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to raw HTML blocks whose first element has this tag name, such as
+    /// `details` for a `<details>` block.
+    pub select_tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to code blocks whose info string contains this text, such as
+    /// `no_run` for a fence opened with ```` ```rust,no_run ````.
+    pub select_info_contains: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to the heading whose anchor is this slug, such as
+    /// `installation-notes` for `## Installation notes`: its explicit `{#id}`, or the slug
+    /// GitHub derives from its text, suffixed with `-1`, `-2`, ... for repeated headings.
+    pub select_slug: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to nodes with at least this many words. Headings are measured by the
+    /// content of their section.
+    pub select_min_words: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to nodes with at most this many words. Headings are measured by the
+    /// content of their section.
+    pub select_max_words: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to nodes without any word (`true`), such as stub paragraphs or
+    /// headings with an empty section, or to nodes with at least one word (`false`).
+    pub select_empty: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to list items nested this many lists deep, such as `2` for the
+    /// sub-tasks of a checklist. List items are searched at depth 1, the items of top-level
+    /// lists, when unset.
+    pub select_depth: Option<usize>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Also searches the blocks nested in block quotes, alerts, footnote definitions and list
+    /// items, so a paragraph inside a callout can be edited on its own. A container is ranked
+    /// before the blocks it holds.
+    pub recursive: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to list items that are tasks in a given state: `complete` (`- [x]`),
+    /// `incomplete` (`- [ ]`) or `any`, which excludes plain items.
+    pub task_state: Option<TaskStatus>,
+    #[serde(
+        default = "default_select_ordinal",
+        skip_serializing_if = "is_default_select_ordinal"
+    )]
     /// Selects the _n_th match (1-indexed) when multiple nodes satisfy the selector.
     pub select_ordinal: usize,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Counts `select_ordinal` from the last match instead of the first, so `select_last: true`
+    /// alone selects the last match and `select_ordinal: 2` the one before it.
+    pub select_last: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Fails the operation when more than this many nodes satisfy the selector.
+    pub max_matches: Option<usize>,
+    #[serde(default, skip_serializing_if = "is_default_tie_break")]
+    /// Ranks the matches (`document_order` or `deepest_first`) before `select_ordinal` picks one.
+    pub tie_break: TieBreak,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Narrows the search to nodes appearing after another selector.
     pub after: Option<Box<Selector>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Narrows the search to nodes appearing after a referenced selector alias.
     pub after_ref: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Narrows the search to nodes contained within another selector's scope.
     pub within: Option<Box<Selector>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Narrows the search to nodes contained within a referenced selector alias.
     pub within_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Narrows the search to the top-level blocks between two landmarks.
+    pub between: Option<Box<Between>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Narrows the search to the _n_th slide (1-indexed) of a deck whose slides are separated
+    /// by thematic breaks (`---`), as in Marp or reveal.js presentations.
+    pub within_slide: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts matches to the top-level block written across this line (1-indexed) of the
+    /// original file, frontmatter included, and to the nodes nested in it, so that an editor
+    /// can target the block under its cursor. Blocks added or modified earlier in the batch
+    /// have no line and never match.
+    pub at_line: Option<usize>,
 }
 
 impl Default for Selector {
@@ -63,135 +612,1320 @@ impl Default for Selector {
             alias: None,
             select_type: None,
             select_contains: None,
+            select_contains_ci: None,
             select_regex: None,
+            select_not_contains: None,
+            select_not_regex: None,
+            select_fingerprint: None,
+            select_annotation: None,
+            select_tag: None,
+            select_info_contains: None,
+            select_slug: None,
+            select_min_words: None,
+            select_max_words: None,
+            select_empty: None,
+            select_depth: None,
+            recursive: false,
+            task_state: None,
             select_ordinal: default_select_ordinal(),
+            select_last: false,
+            max_matches: None,
+            tie_break: TieBreak::default(),
             after: None,
             after_ref: None,
             within: None,
             within_ref: None,
+            between: None,
+            within_slide: None,
+            at_line: None,
         }
     }
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default)]
+/// Two landmarks bounding a search to the top-level blocks strictly between them, as in
+/// `between: {start: {select_type: h2, select_contains: Usage}, end: {select_type: h2}}`.
+///
+/// The `end` landmark is searched after the `start` one, so its `select_ordinal` counts
+/// the matches following `start`. A landmark nested in a block, such as a list item,
+/// bounds the search at the block holding it.
+pub struct Between {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The landmark the search starts after.
+    pub start: Option<Box<Selector>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// A referenced selector alias for the landmark the search starts after.
+    pub start_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The landmark the search stops before.
+    pub end: Option<Box<Selector>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// A referenced selector alias for the landmark the search stops before.
+    pub end_ref: Option<String>,
+}
+
+impl Selector {
+    /// Creates a selector matching nodes of `select_type` (e.g. `h2`, `list`, `li`).
+    ///
+    /// The other builder methods narrow it further:
+    ///
+    /// ```rust
+    /// use md_splice_lib::transaction::Selector;
+    ///
+    /// let selector = Selector::of_type("h2")
+    ///     .containing("Install")
+    ///     .within(Selector::of_type("h1").containing("Guide"))
+    ///     .nth(2);
+    /// assert_eq!(selector.to_string(), r#"h2~"Install" within h1~"Guide" (#2)"#);
+    /// ```
+    pub fn of_type(select_type: impl Into<String>) -> Self {
+        Self {
+            select_type: Some(select_type.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Restricts matches to nodes whose text contains `text`.
+    pub fn containing(mut self, text: impl Into<String>) -> Self {
+        self.select_contains = Some(text.into());
+        self
+    }
+
+    /// Restricts matches to nodes whose text contains `text`, ignoring case and runs of
+    /// whitespace.
+    pub fn containing_ignoring_case(mut self, text: impl Into<String>) -> Self {
+        self.select_contains_ci = Some(text.into());
+        self
+    }
+
+    /// Restricts matches to nodes whose text matches the regular expression `regex`.
+    pub fn matching(mut self, regex: impl Into<String>) -> Self {
+        self.select_regex = Some(regex.into());
+        self
+    }
+
+    /// Excludes nodes whose text contains `text`.
+    pub fn not_containing(mut self, text: impl Into<String>) -> Self {
+        self.select_not_contains = Some(text.into());
+        self
+    }
+
+    /// Excludes nodes whose text matches the regular expression `regex`.
+    pub fn not_matching(mut self, regex: impl Into<String>) -> Self {
+        self.select_not_regex = Some(regex.into());
+        self
+    }
+
+    /// Restricts matches to list items nested `depth` lists deep (1-indexed).
+    pub fn at_depth(mut self, depth: usize) -> Self {
+        self.select_depth = Some(depth);
+        self
+    }
+
+    /// Also searches the blocks nested in containers such as block quotes and list items.
+    pub fn recursive(mut self) -> Self {
+        self.recursive = true;
+        self
+    }
+
+    /// Restricts matches to list items that are tasks in the state `status`.
+    pub fn with_task_state(mut self, status: TaskStatus) -> Self {
+        self.task_state = Some(status);
+        self
+    }
+
+    /// Picks the `ordinal`th match (1-indexed).
+    pub fn nth(mut self, ordinal: usize) -> Self {
+        self.select_ordinal = ordinal;
+        self
+    }
+
+    /// Picks the `ordinal`th match counting from the last one (1-indexed), so `nth_last(1)`
+    /// picks the last match.
+    pub fn nth_last(mut self, ordinal: usize) -> Self {
+        self.select_ordinal = ordinal;
+        self.select_last = true;
+        self
+    }
+
+    /// Narrows the search to nodes appearing after the node matched by `landmark`.
+    pub fn after(mut self, landmark: Selector) -> Self {
+        self.after = Some(Box::new(landmark));
+        self
+    }
+
+    /// Narrows the search to nodes contained within the node matched by `scope`.
+    pub fn within(mut self, scope: Selector) -> Self {
+        self.within = Some(Box::new(scope));
+        self
+    }
+
+    /// Narrows the search to the top-level blocks between the nodes matched by `start` and
+    /// `end`.
+    pub fn between(mut self, start: Selector, end: Selector) -> Self {
+        self.between = Some(Box::new(Between {
+            start: Some(Box::new(start)),
+            end: Some(Box::new(end)),
+            ..Between::default()
+        }));
+        self
+    }
+
+    /// Narrows the search to the `slide`th slide (1-indexed) of a deck separated by thematic
+    /// breaks.
+    pub fn within_slide(mut self, slide: usize) -> Self {
+        self.within_slide = Some(slide);
+        self
+    }
+
+    /// Restricts matches to the top-level block written across `line` (1-indexed) of the
+    /// original file, and to the nodes nested in it.
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.at_line = Some(line);
+        self
+    }
+
+    /// Registers the selector under `alias` for later `*_ref` fields.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Returns the selectors given inline in the `after`, `within` and `between` scopes.
+    pub(crate) fn nested_selectors(&self) -> impl Iterator<Item = &Selector> {
+        let between = self.between.as_deref();
+        self.after
+            .as_deref()
+            .into_iter()
+            .chain(self.within.as_deref())
+            .chain(between.and_then(|between| between.start.as_deref()))
+            .chain(between.and_then(|between| between.end.as_deref()))
+    }
+
+    /// Returns the aliases the `after`, `within` and `between` scopes reference.
+    pub(crate) fn nested_refs(&self) -> impl Iterator<Item = &String> {
+        let between = self.between.as_deref();
+        self.after_ref
+            .iter()
+            .chain(self.within_ref.iter())
+            .chain(between.and_then(|between| between.start_ref.as_ref()))
+            .chain(between.and_then(|between| between.end_ref.as_ref()))
+    }
+}
+
+impl fmt::Display for Selector {
+    /// Formats the selector compactly, as in `h2~"Install" within h1~"Guide" (#2)`.
+    ///
+    /// The node type comes first (`*` for any node), followed by `~"text"` for
+    /// `select_contains`, `~i"text"` for `select_contains_ci`, `~/regex/` for `select_regex`, `!~"text"` and `!~/regex/` for their
+    /// negations, bracketed filters such as
+    /// `[tag=details]` or `[line=120]`, the `after`/`within`/`between` scope (or `within slide N`), the ranking options in parentheses, and
+    /// the alias the selector defines as `as @name`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.select_type.as_deref().unwrap_or("*"))?;
+        if let Some(contains) = &self.select_contains {
+            write!(f, "~{contains:?}")?;
+        }
+        if let Some(contains) = &self.select_contains_ci {
+            write!(f, "~i{contains:?}")?;
+        }
+        if let Some(regex) = &self.select_regex {
+            write!(f, "~/{regex}/")?;
+        }
+        if let Some(contains) = &self.select_not_contains {
+            write!(f, "!~{contains:?}")?;
+        }
+        if let Some(regex) = &self.select_not_regex {
+            write!(f, "!~/{regex}/")?;
+        }
+        if let Some(fingerprint) = &self.select_fingerprint {
+            write!(f, "[fingerprint={fingerprint}]")?;
+        }
+        if let Some(annotation) = &self.select_annotation {
+            write!(f, "[annotation={annotation:?}]")?;
+        }
+        if let Some(tag) = &self.select_tag {
+            write!(f, "[tag={tag}]")?;
+        }
+        if let Some(info) = &self.select_info_contains {
+            write!(f, "[info~{info:?}]")?;
+        }
+        if let Some(slug) = &self.select_slug {
+            write!(f, "[slug={slug}]")?;
+        }
+        if let Some(line) = self.at_line {
+            write!(f, "[line={line}]")?;
+        }
+        if let Some(min_words) = self.select_min_words {
+            write!(f, "[min_words={min_words}]")?;
+        }
+        if let Some(max_words) = self.select_max_words {
+            write!(f, "[max_words={max_words}]")?;
+        }
+        match self.select_empty {
+            Some(true) => f.write_str("[empty]")?,
+            Some(false) => f.write_str("[non-empty]")?,
+            None => {}
+        }
+        if let Some(depth) = self.select_depth {
+            write!(f, "[depth={depth}]")?;
+        }
+        if self.recursive {
+            f.write_str("[recursive]")?;
+        }
+        match self.task_state {
+            Some(TaskStatus::Complete) => f.write_str("[complete]")?,
+            Some(TaskStatus::Incomplete) => f.write_str("[incomplete]")?,
+            Some(TaskStatus::Any) => f.write_str("[task]")?,
+            None => {}
+        }
+        write_scope(f, "after", self.after.as_deref(), self.after_ref.as_ref())?;
+        write_scope(
+            f,
+            "within",
+            self.within.as_deref(),
+            self.within_ref.as_ref(),
+        )?;
+        if let Some(between) = &self.between {
+            write_scope(
+                f,
+                "between",
+                between.start.as_deref(),
+                between.start_ref.as_ref(),
+            )?;
+            write_scope(f, "and", between.end.as_deref(), between.end_ref.as_ref())?;
+        }
+        if let Some(slide) = self.within_slide {
+            write!(f, " within slide {slide}")?;
+        }
+
+        let mut ranking = Vec::new();
+        if self.select_last {
+            ranking.push(format!("#-{}", self.select_ordinal));
+        } else if self.select_ordinal != default_select_ordinal() {
+            ranking.push(format!("#{}", self.select_ordinal));
+        }
+        if self.tie_break == TieBreak::DeepestFirst {
+            ranking.push("deepest first".to_string());
+        }
+        if let Some(max_matches) = self.max_matches {
+            ranking.push(format!("at most {max_matches} matches"));
+        }
+        if !ranking.is_empty() {
+            write!(f, " ({})", ranking.join(", "))?;
+        }
+        if let Some(alias) = &self.alias {
+            write!(f, " as @{alias}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the `after`, `within` or `between` scope of a selector, parenthesizing nested selectors that
+/// have a scope of their own.
+fn write_scope(
+    f: &mut fmt::Formatter<'_>,
+    keyword: &str,
+    selector: Option<&Selector>,
+    selector_ref: Option<&String>,
+) -> fmt::Result {
+    match (selector, selector_ref) {
+        (Some(selector), _)
+            if selector.after.is_some()
+                || selector.after_ref.is_some()
+                || selector.within.is_some()
+                || selector.within_ref.is_some()
+                || selector.between.is_some()
+                || selector.within_slide.is_some() =>
+        {
+            write!(f, " {keyword} ({selector})")
+        }
+        (Some(selector), _) => write!(f, " {keyword} {selector}"),
+        (None, Some(alias)) => write!(f, " {keyword} @{alias}"),
+        (None, None) => Ok(()),
+    }
+}
+
+/// Displays a selector given inline or as a reference to an alias (`@name`).
+struct SelectorSource<'a>(Option<&'a Selector>, Option<&'a String>);
+
+impl fmt::Display for SelectorSource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectorSource(Some(selector), _) => write!(f, "{selector}"),
+            SelectorSource(None, Some(alias)) => write!(f, "@{alias}"),
+            SelectorSource(None, None) => f.write_str("<no selector>"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
 /// Describes where and how new content should be inserted relative to a selector.
 pub struct InsertOperation {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// The selector that identifies the insertion anchor.
     pub selector: Option<Selector>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Reference to a selector alias that identifies the insertion anchor.
     pub selector_ref: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Optional human-readable note recorded alongside the operation.
     pub comment: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Inline Markdown content to insert.
     pub content: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Path to a file whose contents should be inserted.
     pub content_file: Option<PathBuf>,
     #[serde(default)]
     /// Placement relative to the selector.
     pub position: InsertPosition,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Renumbers the ordered lists of the document after inserting, as `renumber` does.
+    pub renumber: bool,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
 /// Describes a replacement of existing content matched by a selector.
 pub struct ReplaceOperation {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// The selector that identifies the content to replace.
     pub selector: Option<Selector>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Reference to a selector alias identifying the content to replace.
     pub selector_ref: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Optional human-readable note recorded alongside the operation.
     pub comment: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Inline Markdown content that replaces the selection.
     pub content: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Path to a file providing replacement Markdown content.
     pub content_file: Option<PathBuf>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Optional selector delimiting the end of a multi-block replacement.
     pub until: Option<Selector>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Reference to an alias delimiting the end of a multi-block replacement.
     pub until_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Rewrites links to the anchors of replaced headings so they point at the new anchors.
+    pub update_links: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Replaces only the inline content of this cell of the table matched by the selector.
+    pub cell: Option<CellAddress>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Runs a regex substitution on the text of the block, heading section, or range of blocks
+/// matched by a selector, such as fixing a product name without retyping the paragraph.
+///
+/// Only plain text is rewritten: emphasis, links, and other inline markup around the text
+/// are kept, and code spans, code blocks, raw HTML, and link destinations are untouched.
+pub struct ReplaceTextOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the content whose text is rewritten.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the content whose text is rewritten.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The regular expression to search for.
+    pub pattern: String,
+    /// The replacement text, in which `$1` or `${name}` expand to capture groups.
+    pub replacement: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Rewrites the entire section when targeting a heading selector.
+    pub section: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional selector delimiting the end of a multi-block substitution.
+    pub until: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to an alias delimiting the end of a multi-block substitution.
+    pub until_ref: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Replaces the content matched by a selector, or inserts it when the selector matches nothing.
+///
+/// When nothing matches, the content is inserted at `position` relative to the node matched by
+/// `fallback`, or appended to the document without a fallback. Running the same upsert twice
+/// therefore leaves the document as a single run did, which suits scheduled CI jobs.
+pub struct UpsertOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the content to replace.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the content to replace.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Inline Markdown content that replaces the selection or is inserted.
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Path to a file providing the Markdown content.
+    pub content_file: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Replaces the entire section when the selector matches a heading.
+    pub section: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying where the content goes when `selector` matches nothing.
+    pub fallback: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the fallback node.
+    pub fallback_ref: Option<String>,
+    #[serde(default)]
+    /// Placement relative to the fallback node.
+    pub position: InsertPosition,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
 /// Describes deletion of content matched by a selector.
 pub struct DeleteOperation {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// The selector identifying content to delete.
     pub selector: Option<Selector>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Reference to a selector alias identifying content to delete.
     pub selector_ref: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Optional human-readable note recorded alongside the operation.
     pub comment: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     /// Deletes the entire section when targeting a heading selector.
     pub section: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Optional selector delimiting the end of a multi-block deletion.
     pub until: Option<Selector>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Reference to an alias delimiting the end of a multi-block deletion.
     pub until_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Renumbers the ordered lists of the document after deleting, as `renumber` does.
+    pub renumber: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Moves the block, list item, or heading section matched by a selector to a place
+/// relative to the node matched by `destination`.
+///
+/// Both selectors are located in the document as it was before the move, and the
+/// destination may not lie inside the moved content.
+pub struct MoveOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying content to move.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying content to move.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Moves the entire section when targeting a heading selector.
+    pub section: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the node the content is moved relative to.
+    pub destination: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the destination node.
+    pub destination_ref: Option<String>,
+    #[serde(default)]
+    /// Placement relative to the destination.
+    pub position: InsertPosition,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Copies the block, list item, heading section, or range of blocks matched by a selector to
+/// a place relative to the node matched by `destination`, leaving the original in place.
+pub struct CopyOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying content to copy.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying content to copy.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Copies the entire section when targeting a heading selector.
+    pub section: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional selector delimiting the end of a multi-block copy.
+    pub until: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to an alias delimiting the end of a multi-block copy.
+    pub until_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the node the copy is placed relative to.
+    pub destination: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the destination node.
+    pub destination_ref: Option<String>,
+    #[serde(default)]
+    /// Placement relative to the destination.
+    pub position: InsertPosition,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Exchanges the positions of the blocks, list items, or heading sections matched by
+/// `selector` and `other`.
+///
+/// Both selectors are located in the document as it was before the swap, and neither match
+/// may lie inside the other.
+pub struct SwapOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the first node to swap.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the first node to swap.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Swaps the entire sections when both selectors target headings.
+    pub section: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the second node to swap.
+    pub other: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the second node to swap.
+    pub other_ref: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Replaces the text of the heading matched by a selector, keeping its level, style, and
+/// section.
+///
+/// An explicit `{#id}` attribute at the end of the heading is kept unless `id` replaces
+/// it. With `update_links`, links elsewhere in the document that pointed at the heading's
+/// old anchor (such as `[Setup](#setup)`) are rewritten to the new one.
+pub struct RenameHeadingOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the heading to rename.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the heading to rename.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The new heading text, as inline Markdown.
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Sets the explicit `{#id}` attribute of the heading.
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Rewrites links to the heading's old anchor so they point at the new one.
+    pub update_links: bool,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Changes the level of the heading matched by a selector by `delta` levels: a negative
+/// delta promotes it (`h3` to `h2`), a positive one demotes it (`h2` to `h3`).
+///
+/// With `include_section`, every heading inside its section is shifted along with it, so
+/// the section keeps its structure. The operation fails without changing anything when a
+/// shifted heading would go above `h1` or below `h6`.
+pub struct ShiftHeadingOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the heading to shift.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the heading to shift.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The number of levels to add to the heading level (negative to promote).
+    pub delta: i8,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Also shifts the headings nested inside the heading's section.
+    pub include_section: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Merges the section of the heading matched by `selector` into the section of the heading
+/// matched by `destination`: the matched heading is removed and the blocks of its section
+/// are appended at the end of the destination's section.
+///
+/// With `adjust_levels`, the subheadings that move are shifted by the difference between
+/// the two heading levels, so an `h4` under a merged `h3` becomes an `h3` under an `h2`.
+pub struct MergeSectionsOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the heading whose section is merged away.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the heading whose section is merged away.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the heading whose section receives the content.
+    pub destination: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the heading whose section receives the content.
+    pub destination_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Shifts the moved subheadings so they keep their depth relative to the destination.
+    pub adjust_levels: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
 /// Assigns a value to a frontmatter key path.
 pub struct SetFrontmatterOperation {
     /// The YAML path to assign.
     pub key: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Optional human-readable note recorded alongside the operation.
     pub comment: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Inline YAML value to assign.
     pub value: Option<YamlValue>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Path to a file providing the YAML value to assign.
     pub value_file: Option<PathBuf>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Overrides the frontmatter serialization format when creating a new block.
     pub format: Option<FrontmatterFormat>,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
 /// Removes a frontmatter key path.
 pub struct DeleteFrontmatterOperation {
     /// The YAML path to remove.
     pub key: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Optional human-readable note recorded alongside the operation.
     pub comment: Option<String>,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
 /// Replaces the entire frontmatter block with new content.
 pub struct ReplaceFrontmatterOperation {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Optional human-readable note recorded alongside the operation.
     pub comment: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Inline YAML content to use as the new frontmatter block.
     pub content: Option<YamlValue>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Path to a file providing replacement YAML content.
     pub content_file: Option<PathBuf>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Overrides the frontmatter serialization format when creating the block.
     pub format: Option<FrontmatterFormat>,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Removes headings whose sections hold no content other than blank blocks.
+///
+/// Nested headings are pruned first, so a heading whose only content was an empty
+/// sub-section is removed as well.
+pub struct PruneEmptySectionsOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Only prune headings at this level (1-6) or deeper.
+    pub min_level: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Only prune headings at this level (1-6) or shallower.
+    pub max_level: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts pruning to the section owned by the heading matched by this selector.
+    pub within: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to an alias restricting pruning to the section of the matched heading.
+    pub within_ref: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Detects headings with identical text, which produce colliding anchor links, and renames
+/// every repeat by appending a numeric suffix such as ` (2)`.
+pub struct DedupeHeadingsOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts detection to the section owned by the heading matched by this selector.
+    pub within: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to an alias restricting detection to the section of the matched heading.
+    pub within_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Report duplicates as warnings instead of renaming them.
+    pub report_only: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Rewrites the start numbers of ordered lists that continue an earlier list, so that a
+/// procedure split by code blocks or edited by earlier operations is numbered sequentially.
+///
+/// A list continues the previous ordered list when it directly follows it, or when it
+/// starts at a number other than 1 and no heading or thematic break separates them.
+pub struct RenumberOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restricts renumbering to the section owned by the heading matched by this selector.
+    pub within: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to an alias restricting renumbering to the section of the matched heading.
+    pub within_ref: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Copies the section of a heading in another Markdown file into the document.
+///
+/// Without a `position`, the section of the heading matched by `selector` is replaced by
+/// the imported section. With a `position`, the imported section is inserted relative to
+/// the matched node instead, like an `insert` operation.
+pub struct ImportSectionOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the heading to replace or the insertion anchor.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the heading or insertion anchor.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// Path to the Markdown file the section is read from.
+    pub source_file: PathBuf,
+    /// Selector identifying the heading whose section is imported from `source_file`.
+    pub source_selector: Selector,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Inserts the section relative to the selector instead of replacing a section.
+    pub position: Option<InsertPosition>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Converts the blockquote matched by the selector into a GitHub alert (`> [!NOTE]`), or
+/// changes the type of the alert it matches. The quoted content is kept as is.
+pub struct SetAlertOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the blockquote or alert to update.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the blockquote or alert to update.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The type the alert should have.
+    pub alert_type: AlertType,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Converts the GitHub alert matched by the selector back into a plain blockquote with the
+/// same content.
+pub struct RemoveAlertOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the alert to convert.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the alert to convert.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Wraps the block, heading section, or range of blocks matched by a selector in a
+/// blockquote, or in a GitHub alert when `alert_type` is given.
+pub struct WrapOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the content to wrap.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the content to wrap.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The type of the alert to wrap the content in; a plain blockquote when omitted.
+    pub alert_type: Option<AlertType>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Wraps the entire section when targeting a heading selector.
+    pub section: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional selector delimiting the end of a multi-block wrap.
+    pub until: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to an alias delimiting the end of a multi-block wrap.
+    pub until_ref: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Replaces the blockquote or GitHub alert matched by the selector with the blocks it
+/// contains, hoisted to the level of the quote.
+pub struct UnwrapOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the blockquote or alert to unwrap.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the blockquote or alert to unwrap.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Places an HTML comment (`<!-- text -->`) next to the block matched by the selector.
+///
+/// If the block already has a comment on that side (one containing `marker`, when given),
+/// its text is replaced; otherwise a new comment block is inserted. This makes the
+/// operation safe to repeat, e.g. to refresh a provenance stamp.
+pub struct SetCommentOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the block to annotate.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the block to annotate.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The text of the comment, without the `<!--` and `-->` delimiters.
+    pub text: String,
+    #[serde(default)]
+    /// The side of the block the comment is placed on.
+    pub position: CommentPosition,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Only treat an existing comment as this operation's when its text contains this string.
+    pub marker: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Removes the HTML comment next to the block matched by the selector.
+///
+/// Nothing is removed, and a `skipped_operation` warning is reported, when there is no
+/// comment on that side (or none containing `marker`, when given).
+pub struct RemoveCommentOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the annotated block.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the annotated block.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default)]
+    /// The side of the block the comment is looked for on.
+    pub position: CommentPosition,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Only remove the comment when its text contains this string.
+    pub marker: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Replaces what lies between the opening and closing tags of the HTML element opened by the
+/// block matched by the selector, such as the body of a `<details>` wrapper, keeping the tags
+/// and their attributes. The element may span Markdown blocks up to its closing tag.
+pub struct ReplaceInnerHtmlOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the HTML block that opens the element.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the HTML block that opens the element.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Inline HTML or Markdown placed between the tags.
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Path to a file providing the content placed between the tags.
+    pub content_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Wraps the block, heading section, or range of blocks matched by a selector in a
+/// collapsible `<details>` element whose `<summary>` is `summary`.
+///
+/// The wrapped blocks stay Markdown: blank lines separate them from the tags so that GitHub
+/// renders them inside the element.
+pub struct WrapDetailsOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the content to wrap.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the content to wrap.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The text of the `<summary>` element, as inline HTML.
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Adds the `open` attribute so the element starts expanded.
+    pub open: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Wraps the entire section when targeting a heading selector.
+    pub section: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional selector delimiting the end of a multi-block wrap.
+    pub until: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to an alias delimiting the end of a multi-block wrap.
+    pub until_ref: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Removes the `<details>` element opened by the HTML block matched by the selector, along
+/// with its `<summary>`, leaving the content it wrapped as plain Markdown.
+pub struct UnwrapDetailsOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the HTML block that opens the `<details>` element.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the HTML block that opens the element.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Sets the language of the code block matched by the selector, leaving its code untouched.
+///
+/// The language is the first word of a fenced block's info string; the rest of the info
+/// string (such as `title="main.rs"`) is kept. An indented code block becomes a fenced one
+/// when a language is set. Without `language`, the whole info string is removed.
+pub struct SetCodeLanguageOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the code block to update.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the code block to update.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The language to set (e.g. `rust`), or `None` to clear the info string.
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Sets or removes one attribute in the info string of the code block matched by the
+/// selector, keeping its language and its other attributes.
+///
+/// Comma-separated flags (`rust,no_run`), space-separated attributes (`js title="app.js"`)
+/// and Pandoc braces (`{.python #id}`) are understood; see `codeinfo`.
+pub struct SetCodeAttributeOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the code block to update.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the code block to update.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The key of the attribute, such as `title` or `no_run`.
+    pub attribute: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The value to set, or `None` to set the attribute as a bare flag.
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    /// Removes the attribute instead of setting it.
+    pub remove: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Inserts a body row into the table matched by the selector.
+///
+/// The row is placed before or after the first body row whose text contains `row_contains`
+/// and matches `row_regex`; a row's text is the plain text of its cells, separated by tabs.
+/// Without either, `before` inserts it as the first body row and `after` as the last one.
+pub struct InsertTableRowOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the table to update.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the table to update.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The row to insert: a pipe-delimited Markdown row, or a list of cells.
+    pub row: TableRow,
+    #[serde(default)]
+    /// Where to insert the row relative to the matched row: `before` or `after`.
+    pub position: InsertPosition,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Matches the body row whose text contains this string.
+    pub row_contains: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Matches the body row whose text matches this regular expression.
+    pub row_regex: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Deletes the first body row of the table matched by the selector whose text contains
+/// `row_contains` and matches `row_regex`. At least one of them is required, and the header
+/// row is never matched.
+pub struct DeleteTableRowOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the table to update.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the table to update.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Matches the body row whose text contains this string.
+    pub row_contains: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Matches the body row whose text matches this regular expression.
+    pub row_regex: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Adds a column to the table matched by the selector.
+///
+/// The column is placed `before` or `after` the column whose header is `column`, or, without
+/// `column`, becomes the first or the last column. Its header cell holds `header` and each
+/// body row holds `value`, both inline Markdown.
+pub struct AddTableColumnOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the table to update.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the table to update.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The header of the new column.
+    pub header: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The content of the new cell in every body row; empty when omitted.
+    pub value: Option<String>,
+    #[serde(default)]
+    /// Where to add the column relative to `column`: `before` or `after`.
+    pub position: InsertPosition,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The header of the column next to which the new column is added.
+    pub column: Option<String>,
+    #[serde(default)]
+    /// The alignment of the new column.
+    pub alignment: ColumnAlignment,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Removes the column whose header is `column`, with its alignment, from the table matched
+/// by the selector.
+pub struct RemoveTableColumnOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the table to update.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the table to update.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The header of the column to remove.
+    pub column: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Replaces the header `column` of the table matched by the selector with `header`, keeping
+/// the cells and the alignment of the column.
+pub struct RenameTableColumnOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the table to update.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the table to update.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    /// The current header of the column.
+    pub column: String,
+    /// The new header, as inline Markdown.
+    pub header: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Generates a nested list of links to the headings of the document and places it between
+/// the `<!-- toc -->` and `<!-- tocstop -->` comments, replacing the list already there.
+///
+/// When the document has no such comments, a selector must match the heading to place the
+/// table of contents under; the comments are inserted directly after it. With a selector,
+/// only comments inside the section of the matched heading are refreshed.
+pub struct UpdateTocOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying the heading the table of contents is placed under.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying the heading the table of contents is placed under.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Only list headings at this level (1-6) or deeper.
+    pub min_level: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Only list headings at this level (1-6) or shallower.
+    pub max_level: Option<u8>,
+    #[serde(default, skip_serializing_if = "is_default_slug_style")]
+    /// How the anchors of headings without an explicit `{#id}` are derived from their text.
+    pub slug_style: SlugStyle,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Checks a precondition or postcondition without modifying the document.
+///
+/// The condition is either a selector (which must match a node) or a frontmatter key (which
+/// must exist, or equal `equals` when given). `expect: no_match` inverts it. When the
+/// condition does not hold, the whole batch fails and the document is left untouched.
+pub struct AssertOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector whose match is checked.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias whose match is checked.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation and shown when it fails.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The frontmatter key path (dot and array notation) checked instead of a selector.
+    pub frontmatter_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The value `frontmatter_key` must equal.
+    pub equals: Option<YamlValue>,
+    #[serde(default)]
+    /// Whether the condition must hold (`match`) or must not hold (`no_match`).
+    pub expect: AssertExpectation,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// The outcome an `assert` operation requires.
+pub enum AssertExpectation {
+    /// The selector matches a node, or the frontmatter key exists (and equals the value).
+    #[default]
+    Match,
+    /// The selector matches nothing, or the frontmatter key is missing (or differs).
+    NoMatch,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+/// Renders matched content without modifying the document.
+///
+/// The rendered Markdown is reported in [`ApplyOutcome::captures`](crate::ApplyOutcome::captures).
+/// When `capture` names a variable, later `insert` and `replace` operations in the same batch
+/// can splice it into their inline `content` as `{{name}}`.
+pub struct GetOperation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The selector identifying content to render.
+    pub selector: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to a selector alias identifying content to render.
+    pub selector_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional human-readable note recorded alongside the operation.
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    /// Renders the entire section when targeting a heading selector.
+    pub section: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional selector delimiting the end of a multi-block range.
+    pub until: Option<Selector>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Reference to an alias delimiting the end of a multi-block range.
+    pub until_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Name of the variable holding the rendered content for later operations.
+    pub capture: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Renders only the inline content of this cell of the table matched by the selector.
+    pub cell: Option<CellAddress>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// Specifies on which side of a block an annotation comment sits.
+pub enum CommentPosition {
+    /// Directly before the block.
+    #[default]
+    Before,
+    /// Directly after the block.
+    After,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// How a table of contents derives the anchor of a heading from its text.
+pub enum SlugStyle {
+    /// GitHub anchors: the lowercased text without punctuation, spaces turned into hyphens.
+    #[default]
+    Github,
+    /// GitLab anchors: like GitHub's, with runs of hyphens collapsed into one.
+    Gitlab,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// The type of a GitHub alert, written as `[!NOTE]`, `[!TIP]`, etc. in Markdown.
+pub enum AlertType {
+    /// A `[!NOTE]` alert.
+    #[default]
+    #[serde(alias = "NOTE")]
+    Note,
+    /// A `[!TIP]` alert.
+    #[serde(alias = "TIP")]
+    Tip,
+    /// An `[!IMPORTANT]` alert.
+    #[serde(alias = "IMPORTANT")]
+    Important,
+    /// A `[!WARNING]` alert.
+    #[serde(alias = "WARNING")]
+    Warning,
+    /// A `[!CAUTION]` alert.
+    #[serde(alias = "CAUTION")]
+    Caution,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+/// The alignment of a table column, set by the colons of its delimiter row.
+pub enum ColumnAlignment {
+    /// No alignment: `---`.
+    #[default]
+    None,
+    /// Left aligned: `:--`.
+    Left,
+    /// Centered: `:-:`.
+    Center,
+    /// Right aligned: `--:`.
+    Right,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 /// Specifies where to place newly inserted content relative to the selector.
 pub enum InsertPosition {
@@ -470,7 +2204,10 @@ mod tests {
             .expect("inline selector must exist");
         assert_eq!(intro_selector.alias.as_deref(), Some("intro_h2"));
         assert_eq!(intro_selector.select_type.as_deref(), Some("h2"));
-        assert_eq!(intro_selector.select_contains.as_deref(), Some("Introduction"));
+        assert_eq!(
+            intro_selector.select_contains.as_deref(),
+            Some("Introduction")
+        );
 
         let Operation::Replace(changelog_replace) = &operations[1] else {
             panic!("expected replace operation for changelog heading");
@@ -491,7 +2228,10 @@ mod tests {
             panic!("expected insert operation using selector_ref");
         };
         assert!(insert_using_ref.selector.is_none());
-        assert_eq!(insert_using_ref.selector_ref.as_deref(), Some("changelog_h2"));
+        assert_eq!(
+            insert_using_ref.selector_ref.as_deref(),
+            Some("changelog_h2")
+        );
         assert_eq!(insert_using_ref.position, InsertPosition::AppendChild);
 
         let Operation::Delete(delete_within_ref) = &operations[3] else {
@@ -504,4 +2244,41 @@ mod tests {
         assert_eq!(delete_selector.within_ref.as_deref(), Some("changelog_h2"));
         assert_eq!(delete_within_ref.until_ref.as_deref(), Some("outro_h2"));
     }
+
+    #[test]
+    fn display_selectors_and_operations_compactly() {
+        let operations: Vec<Operation> = serde_yaml::from_str(
+            r#"
+            - op: delete
+              selector:
+                select_type: h2
+                select_contains: Install
+                select_ordinal: 2
+                within: {select_type: h1, select_contains: Guide}
+              section: true
+            - op: move
+              selector:
+                alias: intro
+                select_regex: "^Intro"
+                tie_break: deepest_first
+                after: {select_type: h2, within_ref: guide}
+              destination_ref: guide
+              position: before
+            - op: set_frontmatter
+              key: meta.status
+              value: draft
+            "#,
+        )
+        .unwrap();
+
+        let displayed: Vec<String> = operations.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            displayed,
+            vec![
+                r#"delete h2~"Install" within h1~"Guide" (#2) (section)"#,
+                r#"move *~/^Intro/ after (h2 within @guide) (deepest first) as @intro before @guide"#,
+                "set_frontmatter meta.status",
+            ]
+        );
+    }
 }
@@ -0,0 +1,69 @@
+//! Defines the non-fatal warnings collected while applying operations.
+
+use std::fmt;
+
+/// Classifies a non-fatal condition reported by [`crate::MarkdownDocument::apply_with_ambiguity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// A selector matched more than one node; the operation used the first match.
+    AmbiguousSelector,
+    /// The operation's content parsed to no Markdown blocks.
+    EmptyContent,
+    /// An optional operation was skipped because its preconditions were not met.
+    SkippedOperation,
+    /// Several headings share the same text, so their anchor links collide.
+    DuplicateHeading,
+    /// An earlier operation may have removed the operation's target, but a node still
+    /// matched it (see `detect_conflicts`).
+    PossibleConflict,
+}
+
+impl WarningKind {
+    /// Returns the stable snake_case identifier of the warning kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningKind::AmbiguousSelector => "ambiguous_selector",
+            WarningKind::EmptyContent => "empty_content",
+            WarningKind::SkippedOperation => "skipped_operation",
+            WarningKind::DuplicateHeading => "duplicate_heading",
+            WarningKind::PossibleConflict => "possible_conflict",
+        }
+    }
+}
+
+/// A non-fatal condition encountered while applying a batch of operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// The category of the warning.
+    pub kind: WarningKind,
+    /// The 0-indexed position of the operation that produced the warning.
+    pub operation_index: usize,
+    /// A human-readable description of the condition.
+    pub message: String,
+}
+
+impl Warning {
+    pub(crate) fn new(
+        kind: WarningKind,
+        operation_index: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            operation_index,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "operation {}: {}",
+            self.operation_index + 1,
+            self.message
+        )
+    }
+}
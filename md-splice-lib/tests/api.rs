@@ -1,10 +1,26 @@
+use md_splice_lib::alias::ResolvedAlias;
+use md_splice_lib::concat::{ConcatOptions, FrontmatterStrategy};
+use md_splice_lib::error::SpliceError;
+use md_splice_lib::frontmatter::FrontmatterFormat;
+use md_splice_lib::locator::{
+    found_block_index, locate, locate_all, MatchMetadata, Selector as LocatorSelector, TaskStatus,
+    TieBreak,
+};
+use md_splice_lib::policy::OperationPolicy;
 use md_splice_lib::transaction::{
-    InsertOperation, InsertPosition as TxInsertPosition, Operation, ReplaceOperation,
-    Selector as TxSelector, SetFrontmatterOperation,
+    AlertType, AssertExpectation, AssertOperation, DedupeHeadingsOperation, DeleteOperation,
+    GetOperation, ImportSectionOperation, InsertOperation, InsertPosition as TxInsertPosition,
+    Operation, RemoveAlertOperation, RemoveCommentOperation, ReplaceOperation,
+    Selector as TxSelector, SetAlertOperation, SetCommentOperation, SetFrontmatterOperation,
+};
+use md_splice_lib::warning::WarningKind;
+use md_splice_lib::{
+    apply_str, apply_str_with_limits, default_printer_config, render_fragment, AffectedBlocks,
+    Limits, MarkdownDocument, NearMiss, ParseOptions,
 };
-use md_splice_lib::MarkdownDocument;
 use serde_yaml::Value as YamlValue;
 use std::str::FromStr;
+use std::time::Duration;
 
 #[test]
 fn load_document_from_string_and_render() {
@@ -24,18 +40,39 @@ fn apply_insert_preserves_list_item_spacing() {
             alias: None,
             select_type: Some("h2".to_string()),
             select_contains: Some("Changelog".to_string()),
+            select_contains_ci: None,
             select_regex: None,
+            select_not_contains: None,
+            select_not_regex: None,
+            select_fingerprint: None,
+            select_annotation: None,
+            select_tag: None,
+            select_info_contains: None,
+            select_slug: None,
+            select_min_words: None,
+            select_max_words: None,
+            select_empty: None,
+            select_depth: None,
+            recursive: false,
+            task_state: None,
             select_ordinal: 1,
+            select_last: false,
+            max_matches: None,
+            tie_break: TieBreak::default(),
             after: None,
             after_ref: None,
             within: None,
             within_ref: None,
+            between: None,
+            within_slide: None,
+            at_line: None,
         }),
         selector_ref: None,
         comment: None,
         content: Some("## Release notes\n- Initial Python bindings\n".to_string()),
         content_file: None,
         position: TxInsertPosition::After,
+        renumber: false,
     })];
 
     doc.apply(operations).expect("insert succeeds");
@@ -61,12 +98,32 @@ fn apply_replace_operation_updates_body() {
             alias: None,
             select_type: None,
             select_contains: Some("Status: In Progress.".to_string()),
+            select_contains_ci: None,
             select_regex: None,
+            select_not_contains: None,
+            select_not_regex: None,
+            select_fingerprint: None,
+            select_annotation: None,
+            select_tag: None,
+            select_info_contains: None,
+            select_slug: None,
+            select_min_words: None,
+            select_max_words: None,
+            select_empty: None,
+            select_depth: None,
+            recursive: false,
+            task_state: None,
             select_ordinal: 1,
+            select_last: false,
+            max_matches: None,
+            tie_break: TieBreak::default(),
             after: None,
             after_ref: None,
             within: None,
             within_ref: None,
+            between: None,
+            within_slide: None,
+            at_line: None,
         }),
         selector_ref: None,
         comment: None,
@@ -74,6 +131,8 @@ fn apply_replace_operation_updates_body() {
         content_file: None,
         until: None,
         until_ref: None,
+        update_links: false,
+        cell: None,
     })];
 
     doc.apply(operations).expect("apply succeeds");
@@ -102,3 +161,2128 @@ fn apply_set_frontmatter_updates_metadata() {
     assert!(rendered.contains("status: published"));
     assert!(!rendered.contains("status: draft"));
 }
+
+#[test]
+fn semantically_equals_ignores_formatting_differences() {
+    let original = MarkdownDocument::from_str(
+        "---\ntitle: Guide\n---\n\nGuide\n=====\n\n* one\n* two\n\nWrapped\ntext.\n",
+    )
+    .expect("document loads");
+    let reformatted = MarkdownDocument::from_str(
+        "+++\ntitle = \"Guide\"\n+++\n\n# Guide\n\n- one\n- two\n\nWrapped text.\n",
+    )
+    .expect("document loads");
+    let changed = MarkdownDocument::from_str(
+        "---\ntitle: Guide\n---\n\n# Guide\n\n- one\n- three\n\nWrapped text.\n",
+    )
+    .expect("document loads");
+
+    assert!(original.semantically_equals(&reformatted));
+    assert!(!original.semantically_equals(&changed));
+}
+
+#[test]
+fn from_str_lenient_reports_unclosed_fences() {
+    let content = "---\ntitle: Guide\n---\n\n# Guide\n\n```sh\necho hi\n";
+    assert!(MarkdownDocument::from_str(content).is_ok());
+
+    let (doc, warnings) = MarkdownDocument::from_str_lenient(content).expect("document loads");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].line, 7);
+    assert!(doc.render().ends_with("# Guide\n\n```sh\necho hi"));
+}
+
+#[test]
+fn apply_with_ambiguity_collects_warnings() {
+    let mut doc = MarkdownDocument::from_str("First note.\n\nSecond note.\n\nObsolete.\n")
+        .expect("document loads");
+
+    let operations = vec![
+        Operation::Replace(ReplaceOperation {
+            selector: Some(TxSelector {
+                select_contains: Some("note.".to_string()),
+                ..TxSelector::default()
+            }),
+            content: Some("Updated note.".to_string()),
+            ..ReplaceOperation::default()
+        }),
+        Operation::Replace(ReplaceOperation {
+            selector: Some(TxSelector {
+                select_contains: Some("Obsolete.".to_string()),
+                ..TxSelector::default()
+            }),
+            content: Some("   ".to_string()),
+            ..ReplaceOperation::default()
+        }),
+    ];
+
+    let outcome = doc
+        .apply_with_ambiguity(operations)
+        .expect("apply succeeds");
+
+    assert!(outcome.ambiguity_detected);
+    let kinds: Vec<_> = outcome
+        .warnings
+        .iter()
+        .map(|warning| (warning.kind, warning.operation_index))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (WarningKind::AmbiguousSelector, 0),
+            (WarningKind::EmptyContent, 1)
+        ]
+    );
+    assert_eq!(doc.render(), "Updated note.\n\nSecond note.");
+}
+
+#[test]
+fn replacing_a_selector_twice_succeeds_while_it_still_matches() {
+    let mut doc = MarkdownDocument::from_str("Draft one.\n\nDraft two.\n").unwrap();
+    let batch = "- op: replace\n  selector: {select_type: p, select_contains: Draft}\n  content: Final.\n\
+                 - op: replace\n  selector: {select_type: p, select_contains: Draft}\n  content: Final.\n";
+
+    let outcome = doc
+        .apply_with_ambiguity(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    assert_eq!(doc.render(), "Final.\n\nFinal.");
+    let kinds: Vec<_> = outcome
+        .warnings
+        .iter()
+        .map(|warning| (warning.kind, warning.operation_index))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (WarningKind::AmbiguousSelector, 0),
+            (WarningKind::PossibleConflict, 1)
+        ]
+    );
+
+    let mut doc = MarkdownDocument::from_str("Draft one.\n").unwrap();
+    let err = doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap_err();
+    assert!(matches!(err, SpliceError::ConflictingOperations(_)));
+    assert!(err.to_string().contains("both replace the same target"));
+    assert_eq!(doc.render(), "Draft one.");
+}
+
+#[test]
+fn at_line_selects_the_block_written_across_a_source_line() {
+    let source = "---\ntitle: Notes\n---\n# Notes\n\nFirst paragraph\nwrapped.\n\n- one\n- two\n";
+    let doc = MarkdownDocument::from_str(source).unwrap();
+    let at_line = |line, select_type: Option<&str>| LocatorSelector {
+        select_type: select_type.map(str::to_string),
+        at_line: Some(line),
+        select_ordinal: 1,
+        ..LocatorSelector::default()
+    };
+
+    let (found, _) = doc.locate(&at_line(7, None)).unwrap();
+    assert_eq!(found_block_index(&found), 1);
+    assert_eq!(doc.locate_all(&at_line(10, Some("li"))).unwrap().len(), 2);
+    assert!(doc.locate_all(&at_line(8, None)).unwrap().is_empty());
+    assert!(matches!(
+        locate(doc.blocks(), &at_line(7, None)),
+        Err(SpliceError::NodeNotFound)
+    ));
+
+    let mut doc = MarkdownDocument::from_str(source).unwrap();
+    let batch = "- op: insert\n  selector: {at_line: 4}\n  position: before\n  content: Preface.\n\
+                 - op: delete\n  selector: {at_line: 6}\n";
+    doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap();
+    assert_eq!(
+        doc.render(),
+        "---\ntitle: Notes\n---\nPreface.\n\n# Notes\n\n- one\n- two"
+    );
+
+    let mut doc = MarkdownDocument::from_str(source).unwrap();
+    let batch = "- op: replace\n  selector: {at_line: 4}\n  content: '# Journal'\n\
+                 - op: delete\n  selector: {at_line: 4}\n";
+    let err = doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap_err();
+    assert!(err.to_string().contains("did not match any nodes"), "{err}");
+
+    let batch = "- op: delete\n  selector: {select_type: li, at_line: 4}\n";
+    let err = doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap_err();
+    assert!(
+        err.to_string().contains("not written across line 4"),
+        "{err}"
+    );
+}
+
+#[test]
+fn apply_with_ambiguity_reports_statistics() {
+    let mut doc =
+        MarkdownDocument::from_str("Intro.\n\nDraft.\n\nObsolete.\n").expect("document loads");
+
+    let operations = vec![
+        Operation::Replace(ReplaceOperation {
+            selector: Some(TxSelector {
+                select_contains: Some("Draft.".to_string()),
+                ..TxSelector::default()
+            }),
+            content: Some("First.\n\nSecond.".to_string()),
+            ..ReplaceOperation::default()
+        }),
+        Operation::Delete(DeleteOperation {
+            selector: Some(TxSelector {
+                select_contains: Some("Obsolete.".to_string()),
+                ..TxSelector::default()
+            }),
+            ..DeleteOperation::default()
+        }),
+        Operation::SetFrontmatter(SetFrontmatterOperation {
+            key: "status".to_string(),
+            value: Some(YamlValue::String("done".to_string())),
+            ..SetFrontmatterOperation::default()
+        }),
+    ];
+
+    let outcome = doc
+        .apply_with_ambiguity(operations)
+        .expect("apply succeeds");
+
+    assert_eq!(
+        outcome
+            .operation_counts
+            .clone()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![("delete", 1), ("replace", 1), ("set_frontmatter", 1)]
+    );
+    assert_eq!(
+        outcome.affected_blocks,
+        vec![
+            AffectedBlocks {
+                operation_index: 0,
+                range: 1..3,
+            },
+            AffectedBlocks {
+                operation_index: 1,
+                range: 3..3,
+            },
+        ]
+    );
+    assert_eq!(outcome.blocks_added, 2);
+    assert_eq!(outcome.blocks_removed, 2);
+    assert_eq!(outcome.net_blocks(), 0);
+}
+
+#[test]
+fn exported_aliases_can_be_imported_by_a_later_batch() {
+    let mut doc = MarkdownDocument::from_str("# Guide\n\nIntro.\n\n## Changelog\n\n- First\n")
+        .expect("parse succeeds");
+
+    let outcome = doc
+        .apply_with_ambiguity(vec![Operation::Insert(InsertOperation {
+            selector: Some(TxSelector {
+                alias: Some("changelog".to_string()),
+                select_type: Some("h2".to_string()),
+                select_contains: Some("Changelog".to_string()),
+                ..TxSelector::default()
+            }),
+            position: TxInsertPosition::Before,
+            content: Some("Preamble.".to_string()),
+            ..InsertOperation::default()
+        })])
+        .expect("first batch succeeds");
+
+    assert_eq!(outcome.aliases.len(), 1);
+    let exported = &outcome.aliases[0];
+    assert_eq!(exported.name, "changelog");
+    assert_eq!(exported.blocks, Some(3..4));
+    assert_eq!(exported.selector.alias, None);
+
+    let json = serde_json::to_string(&outcome.aliases).expect("aliases serialize");
+    let imported: Vec<ResolvedAlias> = serde_json::from_str(&json).expect("aliases deserialize");
+
+    let outcome = doc
+        .apply_with_aliases(
+            vec![Operation::Insert(InsertOperation {
+                selector_ref: Some("changelog".to_string()),
+                position: TxInsertPosition::AppendChild,
+                content: Some("- Second".to_string()),
+                ..InsertOperation::default()
+            })],
+            &imported,
+        )
+        .expect("second batch succeeds");
+
+    assert_eq!(outcome.aliases, imported);
+    let rendered = doc.render();
+    assert!(rendered.find("- Second") > rendered.find("## Changelog"));
+}
+
+#[test]
+fn line_based_aliases_survive_an_export_round_trip() {
+    let source = "# Title\n\nIntro para.\n\n## Notes\n\nalpha\n";
+    let mut doc = MarkdownDocument::from_str(source).unwrap();
+    let outcome = doc
+        .apply_with_ambiguity(
+            serde_yaml::from_str(
+                "- op: assert\n  selector: {select_type: p, at_line: 7, alias: target}\n",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+    let exported = &outcome.aliases[0];
+    assert_eq!(exported.selector.at_line, Some(7));
+    assert_eq!(exported.blocks, Some(3..4));
+
+    let json = serde_json::to_string(&outcome.aliases).unwrap();
+    let imported: Vec<ResolvedAlias> = serde_json::from_str(&json).unwrap();
+    let mut doc = MarkdownDocument::from_str(source).unwrap();
+    doc.apply_with_aliases(
+        serde_yaml::from_str("- op: delete\n  selector_ref: target\n").unwrap(),
+        &imported,
+    )
+    .unwrap();
+    assert_eq!(doc.render(), "# Title\n\nIntro para.\n\n## Notes");
+}
+
+#[test]
+fn dedupe_headings_report_only_warns_without_renaming() {
+    let content = "# Guide\n\n## Usage\n\n## Usage\n";
+    let mut doc = MarkdownDocument::from_str(content).expect("document loads");
+
+    let outcome = doc
+        .apply_with_ambiguity(vec![Operation::DedupeHeadings(DedupeHeadingsOperation {
+            report_only: true,
+            ..DedupeHeadingsOperation::default()
+        })])
+        .expect("dedupe succeeds");
+
+    assert_eq!(doc.render().trim_end(), content.trim_end());
+    assert_eq!(outcome.warnings.len(), 1);
+    assert_eq!(outcome.warnings[0].kind, WarningKind::DuplicateHeading);
+    assert_eq!(
+        outcome.warnings[0].message,
+        "heading 'Usage' appears 2 times"
+    );
+}
+
+#[test]
+fn render_fragment_renders_block_slices_with_trailing_newline() {
+    let doc = MarkdownDocument::from_str("# Title\n\nFirst.\n\n- one\n- two\n").unwrap();
+
+    let fragment = render_fragment(&doc.blocks()[1..], default_printer_config());
+
+    assert_eq!(fragment, "First.\n\n- one\n- two\n");
+    assert_eq!(render_fragment(&[], default_printer_config()), "");
+}
+
+#[test]
+fn set_and_remove_frontmatter_without_operations() {
+    let mut doc = MarkdownDocument::from_str("# Title\n").unwrap();
+    let value: YamlValue = serde_yaml::from_str("title: Guide\ndraft: true\n").unwrap();
+
+    doc.set_frontmatter(value.clone(), FrontmatterFormat::Toml)
+        .expect("frontmatter is set");
+
+    assert_eq!(doc.frontmatter(), Some(&value));
+    assert_eq!(doc.frontmatter_format(), Some(FrontmatterFormat::Toml));
+    assert!(doc.render().starts_with("+++\n"));
+
+    assert_eq!(doc.remove_frontmatter(), Some(value));
+    assert_eq!(doc.frontmatter(), None);
+    assert_eq!(doc.render(), "# Title");
+    assert_eq!(doc.remove_frontmatter(), None);
+}
+
+#[test]
+fn render_body_and_frontmatter_compose_into_render() {
+    let doc = MarkdownDocument::from_str("---\ntitle: Guide\n---\n# Guide\n\nBody.\n").unwrap();
+
+    let frontmatter = doc.render_frontmatter().expect("frontmatter is present");
+    assert!(frontmatter.starts_with("---\ntitle: Guide\n---"));
+    assert_eq!(doc.render_body(), "# Guide\n\nBody.");
+    assert_eq!(format!("{frontmatter}{}", doc.render_body()), doc.render());
+
+    let plain = MarkdownDocument::from_str("Body only.\n").unwrap();
+    assert_eq!(plain.render_frontmatter(), None);
+    assert_eq!(plain.render_body(), plain.render());
+}
+
+#[test]
+fn heading_tree_exposes_nested_sections() {
+    let doc = MarkdownDocument::from_str(
+        "---\ntitle: Guide\n---\n# Guide\n\n## Install **now**\n\nSteps.\n\n## Usage\n",
+    )
+    .unwrap();
+
+    let tree = doc.heading_tree();
+
+    assert_eq!(tree.len(), 1);
+    assert_eq!(tree[0].blocks, 0..doc.blocks().len());
+    let install = &tree[0].children[0];
+    assert_eq!(install.text, "Install now");
+    assert_eq!(install.slug, "install-now");
+    assert_eq!(install.blocks, 1..3);
+    assert_eq!(tree[0].children[1].slug, "usage");
+}
+
+#[test]
+fn section_of_returns_heading_section_extent() {
+    let doc = MarkdownDocument::from_str("# Guide\n\n## Setup\n\nSteps.\n\n## Usage\n").unwrap();
+    let selector = |select_type: &str, contains: &str| LocatorSelector {
+        select_type: Some(select_type.to_string()),
+        select_contains: Some(contains.to_string()),
+        select_contains_ci: None,
+        select_regex: None,
+        select_not_contains: None,
+        select_not_regex: None,
+        select_fingerprint: None,
+        select_annotation: None,
+        select_tag: None,
+        select_info_contains: None,
+        select_slug: None,
+        select_min_words: None,
+        select_max_words: None,
+        select_empty: None,
+        select_depth: None,
+        recursive: false,
+        task_state: None,
+        select_ordinal: 1,
+        select_last: false,
+        max_matches: None,
+        tie_break: TieBreak::default(),
+        after: None,
+        within: None,
+        between: None,
+        within_slide: None,
+        at_line: None,
+    };
+
+    assert_eq!(doc.section_of(&selector("h2", "Setup")).unwrap(), 1..3);
+    assert_eq!(doc.section_of(&selector("h1", "Guide")).unwrap(), 0..4);
+    assert!(matches!(
+        doc.section_of(&selector("p", "Steps")),
+        Err(SpliceError::SectionRequiresHeading)
+    ));
+    assert!(matches!(
+        doc.section_of(&selector("h2", "Missing")),
+        Err(SpliceError::NodeNotFound)
+    ));
+}
+
+#[test]
+fn apply_import_section_pulls_section_from_another_file() {
+    use assert_fs::prelude::*;
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let shared = temp.child("shared.md");
+    shared
+        .write_str("# Shared\n\n## Support\n\nAsk in the forum.\n\n## Other\n")
+        .unwrap();
+    let heading = |contains: &str| TxSelector {
+        alias: None,
+        select_type: Some("h2".to_string()),
+        select_contains: Some(contains.to_string()),
+        select_contains_ci: None,
+        select_regex: None,
+        select_not_contains: None,
+        select_not_regex: None,
+        select_fingerprint: None,
+        select_annotation: None,
+        select_tag: None,
+        select_info_contains: None,
+        select_slug: None,
+        select_min_words: None,
+        select_max_words: None,
+        select_empty: None,
+        select_depth: None,
+        recursive: false,
+        task_state: None,
+        select_ordinal: 1,
+        select_last: false,
+        max_matches: None,
+        tie_break: TieBreak::default(),
+        after: None,
+        after_ref: None,
+        within: None,
+        within_ref: None,
+        between: None,
+        within_slide: None,
+        at_line: None,
+    };
+    let import = |target: &str, position| {
+        Operation::ImportSection(ImportSectionOperation {
+            selector: Some(heading(target)),
+            selector_ref: None,
+            comment: None,
+            source_file: shared.path().to_path_buf(),
+            source_selector: heading("Support"),
+            position,
+        })
+    };
+
+    let mut doc =
+        MarkdownDocument::from_str("# Guide\n\n## Support\n\nOld text.\n\n## End\n").unwrap();
+    doc.apply(vec![import("Support", None)]).unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Guide\n\n## Support\n\nAsk in the forum.\n\n## End"
+    );
+
+    let mut doc = MarkdownDocument::from_str("# Guide\n\n## End\n").unwrap();
+    doc.apply(vec![import("End", Some(TxInsertPosition::Before))])
+        .unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Guide\n\n## Support\n\nAsk in the forum.\n\n## End"
+    );
+}
+
+#[test]
+fn concat_combines_documents_with_rebased_headings() {
+    let documents = [
+        MarkdownDocument::from_str("---\ntitle: Guide\n---\n# Guide\n\n## Setup\n").unwrap(),
+        MarkdownDocument::from_str("---\nowner: docs\n---\n### Usage\n").unwrap(),
+    ];
+
+    let first = MarkdownDocument::concat(&documents, &ConcatOptions::default()).unwrap();
+    assert_eq!(
+        first.render(),
+        "---\ntitle: Guide\n---\n# Guide\n\n## Setup\n\n### Usage"
+    );
+
+    let merged = MarkdownDocument::concat(
+        &documents,
+        &ConcatOptions {
+            base_level: Some(2),
+            frontmatter: FrontmatterStrategy::Merge,
+        },
+    )
+    .unwrap();
+    let expected: YamlValue = serde_yaml::from_str("title: Guide\nowner: docs\n").unwrap();
+    assert_eq!(merged.frontmatter(), Some(&expected));
+    assert_eq!(merged.render_body(), "## Guide\n\n### Setup\n\n## Usage");
+}
+
+#[test]
+fn select_fingerprint_finds_block_after_it_moves() {
+    let mut doc = MarkdownDocument::from_str("# Notes\n\nKeep this.\n\nAnd this.\n").unwrap();
+    let fingerprint = doc.fingerprints()[2].clone();
+
+    doc.apply(vec![Operation::Insert(InsertOperation {
+        selector: Some(TxSelector {
+            select_type: Some("h1".to_string()),
+            ..TxSelector::default()
+        }),
+        content: Some("Intro.".to_string()),
+        ..InsertOperation::default()
+    })])
+    .unwrap();
+    assert_eq!(doc.fingerprints()[3], fingerprint);
+
+    doc.apply(vec![Operation::Replace(ReplaceOperation {
+        selector: Some(TxSelector {
+            select_fingerprint: Some(fingerprint[..8].to_uppercase()),
+            ..TxSelector::default()
+        }),
+        content: Some("Replaced.".to_string()),
+        ..ReplaceOperation::default()
+    })])
+    .unwrap();
+    assert_eq!(doc.render(), "# Notes\n\nIntro.\n\nKeep this.\n\nReplaced.");
+}
+
+#[test]
+fn alert_operations_convert_blockquotes_and_change_types() {
+    let mut doc = MarkdownDocument::from_str("# Notes\n\n> Back up first.\n\nText.\n").unwrap();
+    let quote = || {
+        Some(TxSelector {
+            select_type: Some("blockquote".to_string()),
+            ..TxSelector::default()
+        })
+    };
+    let alert = || {
+        Some(TxSelector {
+            select_type: Some("alert".to_string()),
+            ..TxSelector::default()
+        })
+    };
+
+    doc.apply(vec![Operation::SetAlert(SetAlertOperation {
+        selector: quote(),
+        alert_type: AlertType::Note,
+        ..SetAlertOperation::default()
+    })])
+    .unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Notes\n\n> [!NOTE]\n> Back up first.\n\nText."
+    );
+
+    doc.apply(vec![Operation::SetAlert(SetAlertOperation {
+        selector: alert(),
+        alert_type: AlertType::Warning,
+        ..SetAlertOperation::default()
+    })])
+    .unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Notes\n\n> [!WARNING]\n> Back up first.\n\nText."
+    );
+
+    doc.apply(vec![Operation::RemoveAlert(RemoveAlertOperation {
+        selector: alert(),
+        ..RemoveAlertOperation::default()
+    })])
+    .unwrap();
+    assert_eq!(doc.render(), "# Notes\n\n> Back up first.\n\nText.");
+
+    let err = doc
+        .apply(vec![Operation::RemoveAlert(RemoveAlertOperation {
+            selector: quote(),
+            ..RemoveAlertOperation::default()
+        })])
+        .unwrap_err();
+    assert!(err.to_string().contains("requires an alert"));
+}
+
+#[test]
+fn comment_operations_insert_update_and_remove_annotations() {
+    let mut doc = MarkdownDocument::from_str("# Notes\n\nGenerated table.\n").unwrap();
+    let stamp = |text: &str| {
+        Operation::SetComment(SetCommentOperation {
+            selector: Some(TxSelector {
+                select_contains: Some("Generated".to_string()),
+                ..TxSelector::default()
+            }),
+            text: text.to_string(),
+            marker: Some("md-splice".to_string()),
+            ..SetCommentOperation::default()
+        })
+    };
+
+    doc.apply(vec![stamp("generated by md-splice on 2024-01-01")])
+        .unwrap();
+    doc.apply(vec![stamp("generated by md-splice on 2024-02-01")])
+        .unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Notes\n\n<!-- generated by md-splice on 2024-02-01 -->\n\nGenerated table."
+    );
+
+    let comment = LocatorSelector {
+        select_type: Some("comment".to_string()),
+        select_contains: Some("2024-02".to_string()),
+        ..LocatorSelector::default()
+    };
+    assert!(locate(doc.blocks(), &comment).is_ok());
+
+    let remove = Operation::RemoveComment(RemoveCommentOperation {
+        selector: Some(TxSelector {
+            select_type: Some("p".to_string()),
+            ..TxSelector::default()
+        }),
+        ..RemoveCommentOperation::default()
+    });
+    doc.apply(vec![remove.clone()]).unwrap();
+    assert_eq!(doc.render(), "# Notes\n\nGenerated table.");
+
+    let outcome = doc.apply_with_ambiguity(vec![remove]).unwrap();
+    assert_eq!(outcome.warnings[0].kind, WarningKind::SkippedOperation);
+}
+
+#[test]
+fn annotation_selector_targets_block_after_comment_marker() {
+    let mut doc = MarkdownDocument::from_str(
+        "# Release\n\n<!-- slot: summary -->\n\nSummary.\n\n<!-- slot: release-notes -->\n\nOld notes.\n",
+    )
+    .unwrap();
+    let slot = || {
+        Some(TxSelector {
+            select_annotation: Some("slot: release-notes".to_string()),
+            ..TxSelector::default()
+        })
+    };
+
+    doc.apply(vec![
+        Operation::Insert(InsertOperation {
+            selector: Some(TxSelector {
+                select_type: Some("h1".to_string()),
+                ..TxSelector::default()
+            }),
+            content: Some("Intro.".to_string()),
+            position: TxInsertPosition::After,
+            ..InsertOperation::default()
+        }),
+        Operation::Replace(ReplaceOperation {
+            selector: slot(),
+            content: Some("New notes.".to_string()),
+            ..ReplaceOperation::default()
+        }),
+    ])
+    .unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Release\n\nIntro.\n\n<!-- slot: summary -->\n\nSummary.\n\n<!-- slot: release-notes -->\n\nNew notes."
+    );
+
+    let missing = LocatorSelector {
+        select_annotation: Some("slot: changelog".to_string()),
+        ..LocatorSelector::default()
+    };
+    assert!(matches!(
+        locate(doc.blocks(), &missing),
+        Err(SpliceError::NodeNotFound)
+    ));
+}
+
+#[test]
+fn assert_operations_guard_the_batch_without_changing_the_document() {
+    let source = "---\nstatus: draft\n---\n# Notes\n\nOld text.\n";
+    let mut doc = MarkdownDocument::from_str(source).unwrap();
+    let paragraph = || {
+        Some(TxSelector {
+            select_type: Some("p".to_string()),
+            select_contains: Some("Old".to_string()),
+            ..TxSelector::default()
+        })
+    };
+
+    doc.apply(vec![
+        Operation::Assert(AssertOperation {
+            frontmatter_key: Some("status".to_string()),
+            equals: Some(YamlValue::String("draft".to_string())),
+            ..AssertOperation::default()
+        }),
+        Operation::Replace(ReplaceOperation {
+            selector: paragraph(),
+            content: Some("New text.".to_string()),
+            ..ReplaceOperation::default()
+        }),
+        Operation::Assert(AssertOperation {
+            selector: paragraph(),
+            expect: AssertExpectation::NoMatch,
+            ..AssertOperation::default()
+        }),
+    ])
+    .unwrap();
+    assert_eq!(
+        doc.render(),
+        "---\nstatus: draft\n---\n# Notes\n\nNew text."
+    );
+
+    let before = doc.render();
+    let err = doc
+        .apply(vec![
+            Operation::Replace(ReplaceOperation {
+                selector: Some(TxSelector {
+                    select_type: Some("p".to_string()),
+                    ..TxSelector::default()
+                }),
+                content: Some("Clobbered.".to_string()),
+                ..ReplaceOperation::default()
+            }),
+            Operation::Assert(AssertOperation {
+                frontmatter_key: Some("status".to_string()),
+                equals: Some(YamlValue::String("final".to_string())),
+                comment: Some("only edit published notes".to_string()),
+                ..AssertOperation::default()
+            }),
+        ])
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Assertion failed: frontmatter key 'status' does not equal \"final\" (only edit published notes)"
+    );
+    assert_eq!(doc.render(), before);
+}
+
+#[test]
+fn get_operations_capture_content_for_later_operations() {
+    let mut doc =
+        MarkdownDocument::from_str("# Notes\n\n## Summary\n\nShip it.\n\n## Details\n\nMore.\n")
+            .unwrap();
+    let heading = |text: &str| {
+        Some(TxSelector {
+            select_type: Some("h2".to_string()),
+            select_contains: Some(text.to_string()),
+            ..TxSelector::default()
+        })
+    };
+
+    let outcome = doc
+        .apply_with_ambiguity(vec![
+            Operation::Get(GetOperation {
+                selector: Some(TxSelector {
+                    select_type: Some("p".to_string()),
+                    ..TxSelector::default()
+                }),
+                capture: Some("summary".to_string()),
+                ..GetOperation::default()
+            }),
+            Operation::Insert(InsertOperation {
+                selector: heading("Details"),
+                content: Some("> {{summary}} ({{unknown}})".to_string()),
+                position: TxInsertPosition::After,
+                ..InsertOperation::default()
+            }),
+            Operation::Get(GetOperation {
+                selector: heading("Details"),
+                section: true,
+                ..GetOperation::default()
+            }),
+        ])
+        .unwrap();
+
+    let captures: Vec<_> = outcome
+        .captures
+        .iter()
+        .map(|capture| {
+            (
+                capture.operation_index,
+                capture.name.as_deref(),
+                capture.content.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        captures,
+        vec![
+            (0, Some("summary"), "Ship it.\n"),
+            (2, None, "## Details\n\n> Ship it. ({{unknown}})\n\nMore.\n"),
+        ]
+    );
+    assert_eq!(outcome.operation_counts.get("get"), Some(&2));
+    assert!(outcome
+        .affected_blocks
+        .iter()
+        .all(|affected| affected.operation_index == 1));
+}
+
+#[test]
+fn regex_selector_captures_expand_in_inline_content() {
+    let mut doc = MarkdownDocument::from_str("# Deps\n\nserde = 1.0.200\n").unwrap();
+
+    doc.apply(vec![Operation::Insert(InsertOperation {
+        selector: Some(TxSelector {
+            select_regex: Some(r"^(?<name>\w+) = (\d+)\.".to_string()),
+            ..TxSelector::default()
+        }),
+        content: Some("Pinned `${name}` to major version $2 (not $$2).".to_string()),
+        position: TxInsertPosition::After,
+        ..InsertOperation::default()
+    })])
+    .unwrap();
+
+    assert_eq!(
+        doc.render(),
+        "# Deps\n\nserde = 1.0.200\n\nPinned `serde` to major version 1 (not $2)."
+    );
+}
+
+#[test]
+fn selectors_limit_matches_and_rank_them_by_tie_break() {
+    let markdown = "# Guide\n\nTODO: intro.\n\n## Setup\n\n### Linux\n\nTODO: packages.\n\n## Usage\n\nTODO: examples.\n";
+    let todo = |max_matches, tie_break| TxSelector {
+        select_contains: Some("TODO".to_string()),
+        max_matches,
+        tie_break,
+        ..TxSelector::default()
+    };
+
+    let mut doc = MarkdownDocument::from_str(markdown).unwrap();
+    let err = doc
+        .apply(vec![Operation::Delete(DeleteOperation {
+            selector: Some(todo(Some(2), TieBreak::DocumentOrder)),
+            ..DeleteOperation::default()
+        })])
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SpliceError::TooManyMatches { found: 3, max: 2 }
+    ));
+
+    let outcome = doc
+        .apply_with_ambiguity(vec![Operation::Replace(ReplaceOperation {
+            selector: Some(todo(Some(3), TieBreak::DeepestFirst)),
+            content: Some("Install the packages.".to_string()),
+            ..ReplaceOperation::default()
+        })])
+        .unwrap();
+
+    assert_eq!(
+        outcome.warnings[0].message,
+        "selector of `replace *~\"TODO\" (deepest first, at most 3 matches)` matched multiple nodes \
+         (block 4, block 6, block 1); the operation was applied to the first match only"
+    );
+    assert_eq!(
+        doc.render(),
+        "# Guide\n\nTODO: intro.\n\n## Setup\n\n### Linux\n\nInstall the packages.\n\n## Usage\n\nTODO: examples."
+    );
+}
+
+#[test]
+fn unmatched_selectors_report_their_nearest_misses() {
+    let mut doc = MarkdownDocument::from_str(
+        "# Guide\n\nRun the installer.\n\n## Setup\n\nInstall the pakages.\n",
+    )
+    .unwrap();
+
+    let err = doc
+        .apply(vec![Operation::Replace(ReplaceOperation {
+            selector: Some(TxSelector {
+                select_type: Some("p".to_string()),
+                select_contains: Some("packages".to_string()),
+                ..TxSelector::default()
+            }),
+            content: Some("Install the packages.".to_string()),
+            ..ReplaceOperation::default()
+        })])
+        .unwrap_err();
+    let SpliceError::NodeNotFoundWithNearMisses(near_misses) = &err else {
+        panic!("expected near misses, got {err:?}");
+    };
+    assert_eq!(
+        near_misses,
+        &vec![NearMiss {
+            block_index: 3,
+            item_index: None,
+            row_index: None,
+            column_index: None,
+            inline_position: None,
+            node_type: "p".to_string(),
+            excerpt: "Install the pakages.".to_string(),
+            reason: "text is 88% similar to \"packages\"".to_string(),
+            line: Some(7),
+        }]
+    );
+    assert!(err
+        .to_string()
+        .ends_with("Nearest misses:\n  - line 7: block 3 (p \"Install the pakages.\"): text is 88% similar to \"packages\""));
+
+    let section = doc.section_of(&LocatorSelector {
+        select_type: Some("h3".to_string()),
+        select_contains: Some("Setup".to_string()),
+        ..LocatorSelector::default()
+    });
+    let Err(SpliceError::NodeNotFoundWithNearMisses(near_misses)) = section else {
+        panic!("expected near misses");
+    };
+    assert_eq!(near_misses[0].reason, "is a `h2`, not a `h3`");
+
+    let unrelated = doc.section_of(&LocatorSelector {
+        select_type: Some("h2".to_string()),
+        select_contains: Some("Deployment".to_string()),
+        ..LocatorSelector::default()
+    });
+    assert!(matches!(unrelated, Err(SpliceError::NodeNotFound)));
+}
+
+#[test]
+fn apply_str_applies_operations_and_reports_bad_input_as_errors() {
+    let doc = "# Guide\n\n## Setup\n\n- one\n- two\n";
+
+    let rendered = apply_str(
+        doc,
+        "- op: replace\n  selector: {select_type: li, select_ordinal: 2}\n  content: '- three'\n",
+    )
+    .unwrap();
+    assert_eq!(rendered, "# Guide\n\n## Setup\n\n- one\n- three");
+
+    let json = r#"[{"op": "delete", "selector": {"select_type": "h2"}, "section": true}]"#;
+    assert_eq!(apply_str(doc, json).unwrap(), "# Guide");
+
+    assert!(matches!(
+        apply_str(doc, "- op: explode\n"),
+        Err(SpliceError::OperationParse(_))
+    ));
+    assert!(matches!(
+        apply_str("---\n: [\n---\n", "[]"),
+        Err(SpliceError::FrontmatterParse(_))
+    ));
+    let out_of_range = format!(
+        "- op: insert\n  selector: {{select_type: li, select_ordinal: {}}}\n  content: '- x'\n",
+        usize::MAX
+    );
+    assert!(apply_str(doc, &out_of_range).is_err());
+    let list_item_range =
+        "- op: delete\n  selector: {select_type: li}\n  until: {select_type: h1}\n";
+    assert!(apply_str(doc, list_item_range).is_err());
+}
+
+#[test]
+fn limits_reject_oversized_inputs_before_applying() {
+    let doc = "# Guide\n\n## Setup\n\nInstall it.\n";
+    let replace = "- op: replace\n  selector: {select_type: p}\n  content: Run it.\n";
+    let limits = Limits::untrusted();
+    assert_eq!(
+        apply_str_with_limits(doc, replace, &limits).unwrap(),
+        "# Guide\n\n## Setup\n\nRun it."
+    );
+
+    let small = Limits {
+        max_input_bytes: Some(16),
+        ..Limits::default()
+    };
+    let err = apply_str_with_limits(doc, replace, &small).unwrap_err();
+    assert!(matches!(err, SpliceError::LimitExceeded(_)));
+    assert_eq!(
+        err.to_string(),
+        "Limit exceeded: the document is 31 bytes, more than the max_input_bytes limit of 16"
+    );
+
+    let two_operations = format!("{replace}{replace}");
+    let one_operation = Limits {
+        max_operations: Some(1),
+        ..Limits::default()
+    };
+    assert!(matches!(
+        apply_str_with_limits(doc, &two_operations, &one_operation),
+        Err(SpliceError::LimitExceeded(_))
+    ));
+
+    let huge_regex = "- op: delete\n  selector: {select_regex: '\\w{1000}'}\n";
+    assert!(matches!(
+        apply_str_with_limits(doc, huge_regex, &limits),
+        Err(SpliceError::LimitExceeded(_))
+    ));
+
+    let nested = "- op: delete\n  selector: {select_type: p, within: {select_type: h2, within: {select_type: h1}}}\n";
+    let shallow = Limits {
+        max_selector_depth: Some(2),
+        ..Limits::default()
+    };
+    let mut document = MarkdownDocument::from_str(doc).unwrap();
+    let operations: Vec<Operation> = serde_yaml::from_str(nested).unwrap();
+    let err = document
+        .apply_with_limits(operations, &shallow)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Limit exceeded: operation 1 nests 3 selectors, more than the max_selector_depth limit of 2"
+    );
+    assert_eq!(document.render(), doc.trim_end());
+}
+
+#[test]
+fn time_limits_stop_slow_batches_and_leave_the_document_untouched() {
+    let source = "# Guide\n\nInstall it.\n";
+    let batch = "- op: replace\n  selector: {select_type: p}\n  content: Run it.\n\
+                 - op: delete\n  selector: {select_type: h1}\n";
+    let mut document = MarkdownDocument::from_str(source).unwrap();
+
+    let generous = Limits {
+        operation_timeout: Some(Duration::from_secs(60)),
+        batch_timeout: Some(Duration::from_secs(60)),
+        ..Limits::default()
+    };
+    MarkdownDocument::from_str(source)
+        .unwrap()
+        .apply_with_limits(serde_yaml::from_str(batch).unwrap(), &generous)
+        .unwrap();
+
+    let no_time_per_operation = Limits {
+        operation_timeout: Some(Duration::ZERO),
+        ..Limits::default()
+    };
+    let err = document
+        .apply_with_limits(serde_yaml::from_str(batch).unwrap(), &no_time_per_operation)
+        .unwrap_err();
+    assert!(matches!(err, SpliceError::TimeLimitExceeded(_)));
+    assert!(err
+        .to_string()
+        .starts_with("Time limit exceeded: operation 1 took"));
+
+    let no_time_at_all = Limits {
+        batch_timeout: Some(Duration::ZERO),
+        ..Limits::default()
+    };
+    let err = document
+        .apply_with_aliases_and_limits(serde_yaml::from_str(batch).unwrap(), &[], &no_time_at_all)
+        .unwrap_err();
+    assert!(err.to_string().contains("batch_timeout limit"));
+    assert_eq!(document.render(), "# Guide\n\nInstall it.");
+}
+
+#[test]
+fn a_single_operation_stops_when_it_overruns_its_time_limit() {
+    let source: String = (1..=2000).map(|n| format!("Paragraph {n}.\n\n")).collect();
+    let mut document = MarkdownDocument::from_str(&source).unwrap();
+    let batch = "- op: replace_text\n  selector: {select_contains: Paragraph 2000.}\n  pattern: Paragraph\n  replacement: Section\n";
+
+    let limits = Limits {
+        operation_timeout: Some(Duration::ZERO),
+        ..Limits::untrusted()
+    };
+    let err = document
+        .apply_with_limits(serde_yaml::from_str(batch).unwrap(), &limits)
+        .unwrap_err();
+    assert!(matches!(err, SpliceError::TimeLimitExceeded(_)));
+    assert!(err
+        .to_string()
+        .starts_with("Time limit exceeded: operation 1 took"));
+    assert_eq!(document.render(), source.trim_end());
+}
+
+#[test]
+fn sandboxed_policy_rejects_operations_that_read_files() {
+    let doc = "# Guide\n\nInstall it.\n";
+    let batch = "- op: replace\n  selector: {select_type: p}\n  content: Run it.\n\
+                 - op: insert\n  selector: {select_type: h1}\n  content_file: /etc/passwd\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+    let sandboxed = OperationPolicy::sandboxed();
+
+    let mut document = MarkdownDocument::from_str(doc).unwrap();
+    let err = document
+        .apply_with_policy(operations.clone(), &sandboxed)
+        .unwrap_err();
+    assert!(matches!(err, SpliceError::OperationNotAllowed(_)));
+    assert_eq!(
+        err.to_string(),
+        "Operation not allowed: operation 2 (insert) reads `content_file`, but filesystem access is disabled"
+    );
+    assert_eq!(document.render(), doc.trim_end());
+
+    let frontmatter = "- op: set_frontmatter\n  key: title\n  value_file: title.txt\n";
+    let frontmatter: Vec<Operation> = serde_yaml::from_str(frontmatter).unwrap();
+    assert!(sandboxed.check_operations(&frontmatter).is_err());
+    assert!(OperationPolicy::default()
+        .check_operations(&frontmatter)
+        .is_ok());
+
+    let inline = operations[..1].to_vec();
+    assert!(sandboxed.check_operations(&inline).is_ok());
+    document.apply_with_policy(inline, &sandboxed).unwrap();
+    assert_eq!(document.render(), "# Guide\n\nRun it.");
+}
+
+#[test]
+fn move_operations_relocate_sections_and_list_items() {
+    let mut document = MarkdownDocument::from_str(
+        "# Guide\n\n## Usage\n\nRun it.\n\n## Install\n\nGet it.\n\n- one\n- two\n- three\n",
+    )
+    .unwrap();
+    let batch = "- op: move\n  selector: {select_type: h2, select_contains: Install}\n  section: true\n  destination: {select_type: h2, select_contains: Usage}\n  position: before\n\
+                 - op: move\n  selector: {select_type: li, select_contains: one}\n  destination: {select_type: li, select_contains: three}\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# Guide\n\n## Install\n\nGet it.\n\n- two\n- three\n- one\n\n## Usage\n\nRun it."
+    );
+
+    let into_itself = "- op: move\n  selector: {select_type: h2, select_contains: Install}\n  section: true\n  destination: {select_type: p, select_contains: Get}\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(into_itself).unwrap();
+    let err = document.apply(operations).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&SpliceError::InvalidMoveDestination.to_string()));
+}
+
+#[test]
+fn copy_operations_clone_sections_ranges_and_list_items() {
+    let mut document = MarkdownDocument::from_str(
+        "# Log\n\n## Template\n\n- [ ] Review\n\nNotes.\n\n---\n\n## Monday\n\nDone.\n",
+    )
+    .unwrap();
+    let batch = "- op: copy\n  selector: {select_type: li}\n  destination: {select_type: h2, select_contains: Monday}\n  position: append_child\n\
+                 - op: copy\n  selector: {select_type: h2, select_contains: Template}\n  until: {select_type: thematicbreak}\n  destination: {select_type: p, select_contains: Done}\n\
+                 - op: copy\n  selector: {select_type: h2, select_contains: Template}\n  section: true\n  destination: {select_type: h1}\n  position: append_child\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# Log\n\n## Template\n\n- [ ] Review\n\nNotes.\n\n---\n\n## Monday\n\nDone.\n\n\
+         ## Template\n\n- [ ] Review\n\nNotes.\n\n- [ ] Review\n\n\
+         ## Template\n\n- [ ] Review\n\nNotes.\n\n---"
+    );
+}
+
+#[test]
+fn upsert_inserts_at_fallback_then_replaces_idempotently() {
+    let mut document =
+        MarkdownDocument::from_str("# Project\n\nIntro.\n\n## Usage\n\nRun it.\n").unwrap();
+    let batch = "- op: upsert\n  selector: {select_type: h2, select_contains: Status}\n  section: true\n  content: \"## Status\\n\\nBuild: {{build}}\"\n  fallback: {select_type: h2, select_contains: Usage}\n  position: before\n\
+                 - op: upsert\n  selector: {select_type: p, select_contains: Footer}\n  content: Footer.\n";
+    let apply = |document: &mut MarkdownDocument, build: &str| {
+        let batch = batch.replace("{{build}}", build);
+        document
+            .apply(serde_yaml::from_str(&batch).unwrap())
+            .unwrap();
+    };
+
+    apply(&mut document, "passing");
+    let expected =
+        "# Project\n\nIntro.\n\n## Status\n\nBuild: passing\n\n## Usage\n\nRun it.\n\nFooter.";
+    assert_eq!(document.render(), expected);
+
+    apply(&mut document, "passing");
+    assert_eq!(document.render(), expected);
+
+    apply(&mut document, "failing");
+    assert_eq!(document.render(), expected.replace("passing", "failing"));
+
+    let err = document
+        .apply(
+            serde_yaml::from_str(
+                "- op: upsert\n  selector: {select_type: h3}\n  content: x\n  fallback: {select_type: h4}\n",
+            )
+            .unwrap(),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("did not match any nodes"), "{err}");
+}
+
+#[test]
+fn swap_operations_exchange_sections_blocks_and_list_items() {
+    let mut document = MarkdownDocument::from_str(
+        "# Book\n\n## One\n\nFirst.\n\n### Notes\n\n## Two\n\nSecond.\n\n## Three\n\n- a\n- b\n\n- c\n",
+    )
+    .unwrap();
+    let batch = "- op: swap\n  selector: {select_type: h2, select_contains: One}\n  other: {select_type: h2, select_contains: Three}\n  section: true\n\
+                 - op: swap\n  selector: {select_type: p, select_contains: First}\n  other: {select_type: h3}\n\
+                 - op: swap\n  selector: {select_type: li, select_contains: a}\n  other: {select_type: li, select_contains: c}\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# Book\n\n## Three\n\n- c\n- b\n\n- a\n\n## Two\n\nSecond.\n\n## One\n\n### Notes\n\nFirst."
+    );
+
+    let err = document
+        .apply(
+            serde_yaml::from_str(
+                "- op: swap\n  selector: {select_type: h2, select_contains: One}\n  other: {select_type: h3}\n  section: true\n",
+            )
+            .unwrap(),
+        )
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&SpliceError::OverlappingSwap.to_string()));
+}
+
+#[test]
+fn wrap_details_and_unwrap_details_round_trip_markdown() {
+    let source =
+        "# Guide\n\n## FAQ\n\n- Why?\n\n```sh\nmd-splice --help\n```\n\n## Next\n\nMore.\n";
+    let mut document = MarkdownDocument::from_str(source).unwrap();
+    let batch = "- op: wrap_details\n  selector: {select_type: h2, select_contains: FAQ}\n  section: true\n  summary: Frequently <b>asked</b>\n\
+                 - op: wrap_details\n  selector: {select_type: h2, select_contains: Next}\n  until: {select_type: p}\n  summary: Next\n  open: true\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    let wrapped = document.render();
+    assert_eq!(
+        wrapped,
+        "# Guide\n\n<details>\n<summary>Frequently <b>asked</b></summary>\n\n## FAQ\n\n- Why?\n\n\
+         ```sh\nmd-splice --help\n```\n\n</details>\n\n<details open>\n<summary>Next</summary>\n\n\
+         ## Next\n\n</details>\n\nMore."
+    );
+
+    // The wrapped Markdown survives a round trip through the parser.
+    let mut document = MarkdownDocument::from_str(&wrapped).unwrap();
+    let batch = "- op: unwrap_details\n  selector: {select_tag: details}\n\
+                 - op: unwrap_details\n  selector: {select_tag: details}\n";
+    document
+        .apply(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+    assert_eq!(
+        document.render(),
+        "# Guide\n\n## FAQ\n\n- Why?\n\n```sh\nmd-splice --help\n```\n\n## Next\n\nMore."
+    );
+
+    let mut document =
+        MarkdownDocument::from_str("<details><summary>Hi</summary>*Hidden*</details>\n").unwrap();
+    document
+        .apply(
+            serde_yaml::from_str("- op: unwrap_details\n  selector: {select_tag: details}\n")
+                .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(document.render(), "*Hidden*");
+}
+
+#[test]
+fn wrap_turns_blocks_and_sections_into_blockquotes_and_alerts() {
+    let mut document = MarkdownDocument::from_str(
+        "# Guide\n\nRead this first.\n\nAnd this.\n\nNot this.\n\n## Caveats\n\n- Slow.\n\n## Next\n",
+    )
+    .unwrap();
+    let batch = "- op: wrap\n  selector: {select_type: p, select_contains: Read}\n  until: {select_type: p, select_contains: Not}\n  alert_type: important\n\
+                 - op: wrap\n  selector: {select_type: h2, select_contains: Caveats}\n  section: true\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# Guide\n\n> [!IMPORTANT]\n> Read this first.\n>\n> And this.\n\nNot this.\n\n\
+         > ## Caveats\n> \n> - Slow.\n\n## Next"
+    );
+
+    let mut document = MarkdownDocument::from_str("- Item.\n").unwrap();
+    let list_item = "- op: wrap\n  selector: {select_type: li}\n";
+    let err = document
+        .apply(serde_yaml::from_str(list_item).unwrap())
+        .unwrap_err();
+    assert!(err.to_string().contains("matched a list item"));
+}
+
+#[test]
+fn unwrap_hoists_the_content_of_blockquotes_and_alerts() {
+    let mut document = MarkdownDocument::from_str(
+        "# Guide\n\n> [!NOTE]\n> Read this first.\n>\n> - Really.\n\n> Quoted.\n\nPlain.\n",
+    )
+    .unwrap();
+    let batch = "- op: unwrap\n  selector: {select_type: alert}\n\
+                 - op: unwrap\n  selector: {select_type: blockquote}\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# Guide\n\nRead this first.\n\n- Really.\n\nQuoted.\n\nPlain."
+    );
+
+    let not_a_quote = "- op: unwrap\n  selector: {select_type: h1}\n";
+    let err = document
+        .apply(serde_yaml::from_str(not_a_quote).unwrap())
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("requires a blockquote or an alert"));
+}
+
+#[test]
+fn shift_heading_promotes_and_demotes_headings_within_bounds() {
+    let mut document = MarkdownDocument::from_str(
+        "# Guide\n\n### Setup\n\nSteps.\n\n#### Linux\n\n## Usage\n\nRun it.\n",
+    )
+    .unwrap();
+    let batch = "- op: shift_heading\n  selector: {select_type: h3, select_contains: Setup}\n  delta: -1\n  include_section: true\n\
+                 - op: shift_heading\n  selector: {select_type: h2, select_contains: Usage}\n  delta: 2\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# Guide\n\n## Setup\n\nSteps.\n\n### Linux\n\n#### Usage\n\nRun it."
+    );
+
+    let above_h1 =
+        "- op: shift_heading\n  selector: {select_type: h2}\n  delta: -1\n  include_section: true\n\
+         - op: shift_heading\n  selector: {select_type: h1}\n  delta: -1\n  include_section: true\n";
+    let err = document
+        .apply(serde_yaml::from_str(above_h1).unwrap())
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("cannot shift an h1 heading by -1: heading levels range from h1 to h6"));
+    assert_eq!(
+        document.render(),
+        "# Guide\n\n## Setup\n\nSteps.\n\n### Linux\n\n#### Usage\n\nRun it."
+    );
+
+    let not_a_heading = "- op: shift_heading\n  selector: {select_type: p}\n  delta: 1\n";
+    let err = document
+        .apply(serde_yaml::from_str(not_a_heading).unwrap())
+        .unwrap_err();
+    assert!(err.to_string().contains("requires a heading"));
+}
+
+#[test]
+fn merge_sections_folds_duplicate_sections_into_the_first() {
+    let mut document = MarkdownDocument::from_str(
+        "# API\n\n## Options\n\n- `--fast`\n\n## Usage\n\nRun it.\n\n\
+         # Reference\n\n### Options\n\nMore options.\n\n#### Advanced\n\nTuning.\n",
+    )
+    .unwrap();
+    let batch = "- op: merge_sections\n  selector: {select_type: h3, select_contains: Options}\n  destination: {select_type: h2, select_contains: Options}\n  adjust_levels: true\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# API\n\n## Options\n\n- `--fast`\n\nMore options.\n\n### Advanced\n\nTuning.\n\n\
+         ## Usage\n\nRun it.\n\n# Reference"
+    );
+
+    let into_itself = "- op: merge_sections\n  selector: {select_type: h1, select_contains: API}\n  destination: {select_type: h2, select_contains: Usage}\n";
+    let err = document
+        .apply(serde_yaml::from_str(into_itself).unwrap())
+        .unwrap_err();
+    assert!(err.to_string().contains("into a heading inside it"));
+}
+
+#[test]
+fn insert_with_renumber_keeps_an_interrupted_procedure_sequential() {
+    let mut document = MarkdownDocument::from_str(
+        "# Install\n\n1. Download\n2. Unpack\n\n```sh\ntar xf md-splice.tgz\n```\n\n3. Run\n",
+    )
+    .unwrap();
+    let batch = "- op: insert\n  selector: {select_type: li, select_contains: Unpack}\n  content: '1. Verify the checksum'\n  renumber: true\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# Install\n\n1. Download\n2. Unpack\n3. Verify the checksum\n\n```sh\ntar xf md-splice.tgz\n```\n\n4. Run"
+    );
+
+    let batch = "- op: delete\n  selector: {select_type: li, select_contains: Download}\n- op: renumber\n  within: {select_type: h1}\n";
+    document
+        .apply(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+    assert!(document.render().ends_with("\n\n3. Run"));
+}
+
+#[test]
+fn insert_as_child_of_a_code_block_adds_raw_lines() {
+    let mut document =
+        MarkdownDocument::from_str("# Usage\n\n```sh\nmd-splice get --select-type h1\n```\n")
+            .unwrap();
+    let batch = "- op: insert\n  selector: {select_type: code}\n  position: append_child\n  content: |\n    md-splice get --select-type h2\n\n    # *not* Markdown\n\
+                 - op: insert\n  selector: {select_type: code}\n  position: prepend_child\n  content: '# Examples'\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# Usage\n\n```sh\n# Examples\nmd-splice get --select-type h1\nmd-splice get --select-type h2\n\n# *not* Markdown\n```"
+    );
+}
+
+#[test]
+fn set_code_language_tags_fences_and_keeps_info_attributes() {
+    let mut document = MarkdownDocument::from_str(
+        "# Setup\n\n```\ncargo build\n```\n\n```rust title=\"main.rs\"\nfn main() {}\n```\n",
+    )
+    .unwrap();
+    let batch = "- op: set_code_language\n  selector: {select_type: code, select_contains: cargo}\n  language: sh\n\
+                 - op: set_code_language\n  selector: {select_type: code, select_contains: main}\n  language: rs\n\
+                 - op: set_code_language\n  selector: {select_type: code, select_contains: cargo}\n  language: sh\n";
+    let outcome = document
+        .apply_with_ambiguity(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        document.render(),
+        "# Setup\n\n```sh\ncargo build\n```\n\n```rs title=\"main.rs\"\nfn main() {}\n```"
+    );
+    assert_eq!(outcome.warnings.len(), 1);
+    assert_eq!(outcome.warnings[0].kind, WarningKind::SkippedOperation);
+}
+
+#[test]
+fn code_info_attributes_are_selectable_and_editable() {
+    let mut document = MarkdownDocument::from_str(
+        "# Examples\n\n```rust,no_run,edition2021\nserve();\n```\n\n\
+         ```{.python #hello caption=\"Hello\"}\nprint(1)\n```\n\n```js title=\"a.js\"\nrun()\n```\n",
+    )
+    .unwrap();
+    let original = document.render();
+    assert!(original.contains("```{.python #hello caption=\"Hello\"}\n"));
+
+    let batch = "- op: set_code_attribute\n  selector: {select_info_contains: no_run}\n  attribute: no_run\n  remove: true\n\
+                 - op: set_code_attribute\n  selector: {select_info_contains: '#hello'}\n  attribute: caption\n  value: Hello world\n\
+                 - op: set_code_attribute\n  selector: {select_info_contains: 'title='}\n  attribute: linenos\n\
+                 - op: set_code_attribute\n  selector: {select_info_contains: 'title='}\n  attribute: linenos\n";
+    let outcome = document
+        .apply_with_ambiguity(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        document.render(),
+        "# Examples\n\n```rust,edition2021\nserve();\n```\n\n\
+         ```{.python #hello caption=\"Hello world\"}\nprint(1)\n```\n\n```js title=\"a.js\" linenos\nrun()\n```"
+    );
+    assert_eq!(outcome.warnings.len(), 1);
+    assert_eq!(outcome.warnings[0].kind, WarningKind::SkippedOperation);
+    let err = document
+        .apply(
+            serde_yaml::from_str(
+                "- op: set_code_attribute\n  selector: {select_info_contains: no_run}\n  attribute: x\n",
+            )
+            .unwrap(),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("did not match any nodes"), "{err}");
+}
+
+#[test]
+fn table_cells_are_read_and_replaced_in_place() {
+    let mut document = MarkdownDocument::from_str(
+        "# Status\n\n| Service | State |\n| --- | --- |\n| api | *ok* |\n| cli | todo |\n",
+    )
+    .unwrap();
+    let batch = "- op: get\n  selector: {select_type: table}\n  cell: {row: 1, column: 2}\n\
+                 - op: replace\n  selector: {select_type: table}\n  cell: {row: 2, column: 2}\n  content: '**done**'\n\
+                 - op: replace\n  selector: {select_type: table}\n  cell: {row: 0, column: 2}\n  content: Status\n";
+    let outcome = document
+        .apply_with_ambiguity(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    assert_eq!(outcome.captures[0].content, "*ok*\n");
+    assert_eq!(
+        document.render(),
+        "# Status\n\n| Service | Status   |\n| ------- | -------- |\n| api     | *ok*     |\n| cli     | **done** |"
+    );
+
+    let err = document
+        .apply(
+            serde_yaml::from_str(
+                "- op: replace\n  selector: {select_type: table}\n  cell: {row: 3, column: 1}\n  content: x\n",
+            )
+            .unwrap(),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("no cell at row 3, column 1"),
+        "{err}"
+    );
+}
+
+#[test]
+fn table_rows_are_inserted_and_deleted_by_their_text() {
+    let mut document = MarkdownDocument::from_str(
+        "| Service | State |\n| --- | --- |\n| api | ok |\n| cli | todo |\n| web | ok |\n",
+    )
+    .unwrap();
+    let batch = "- op: insert_table_row\n  selector: {select_type: table}\n  row: '| docs | *draft* |'\n  position: before\n  row_contains: cli\n\
+                 - op: insert_table_row\n  selector: {select_type: table}\n  row: [worker]\n\
+                 - op: delete_table_row\n  selector: {select_type: table}\n  row_regex: '^web\\t'\n";
+
+    document
+        .apply(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        document.render(),
+        "| Service | State   |\n| ------- | ------- |\n| api     | ok      |\n| docs    | *draft* |\n\
+         | cli     | todo    |\n| worker  |         |"
+    );
+
+    let err = document
+        .apply(
+            serde_yaml::from_str(
+                "- op: insert_table_row\n  selector: {select_type: table}\n  row: [a, b, c]\n",
+            )
+            .unwrap(),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("The row has 3 cells, but the table has 2 columns"),
+        "{err}"
+    );
+}
+
+#[test]
+fn table_columns_are_added_removed_and_renamed_by_header() {
+    let mut document = MarkdownDocument::from_str(
+        "| Service | State | Notes |\n| --: | --- | --- |\n| api | ok | fast |\n| cli | todo |\n",
+    )
+    .unwrap();
+    let batch = "- op: add_table_column\n  selector: {select_type: table}\n  header: Owner\n  value: '*none*'\n  position: before\n  column: State\n  alignment: center\n\
+                 - op: remove_table_column\n  selector: {select_type: table}\n  column: Notes\n\
+                 - op: rename_table_column\n  selector: {select_type: table}\n  column: State\n  header: Status\n";
+
+    document
+        .apply(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        document.render(),
+        "| Service | Owner  | Status |\n| ------: | :----: | ------ |\n|     api | *none* | ok     |\n\
+         |     cli | *none* | todo   |"
+    );
+
+    let err = document
+        .apply(
+            serde_yaml::from_str(
+                "- op: remove_table_column\n  selector: {select_type: table}\n  column: Notes\n",
+            )
+            .unwrap(),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "The table has no column 'Notes'; its columns are 'Service', 'Owner', 'Status'"
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn update_toc_inserts_a_table_of_contents_and_refreshes_it() {
+    let mut document = MarkdownDocument::from_str(
+        "# Guide\n\n## Contents\n\n## Install {#setup}\n\n### Linux\n\n## Usage\n",
+    )
+    .unwrap();
+    let batch = "- op: update_toc\n  selector: {select_type: h2, select_contains: Contents}\n  min_level: 2\n  max_level: 2\n";
+
+    document
+        .apply(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+    assert_eq!(
+        document.render(),
+        "# Guide\n\n## Contents\n\n<!-- toc -->\n\n- [Install](#setup)\n- [Usage](#usage)\n\n\
+         <!-- tocstop -->\n\n## Install {#setup}\n\n### Linux\n\n## Usage"
+    );
+
+    let batch = "- op: rename_heading\n  selector: {select_type: h2, select_contains: Usage}\n  text: Running it\n\
+                 - op: update_toc\n  min_level: 2\n  max_level: 2\n";
+    document
+        .apply(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+    assert!(
+        document
+            .render()
+            .contains("- [Install](#setup)\n- [Running it](#running-it)\n\n<!-- tocstop -->"),
+        "{}",
+        document.render()
+    );
+}
+
+#[test]
+fn table_rows_and_cells_are_selected_like_list_items() {
+    let mut document = MarkdownDocument::from_str(
+        "| Service | State |\n| --- | --- |\n| api | ok |\n| cli | todo |\n| web | ok |\n",
+    )
+    .unwrap();
+    let batch = "- op: get\n  selector: {select_type: tr, select_contains: cli}\n\
+                 - op: get\n  selector: {select_type: td, select_contains: ok, select_ordinal: 2}\n\
+                 - op: replace\n  selector: {select_type: tr, select_contains: api}\n  content: '| api | *down* |'\n\
+                 - op: replace\n  selector: {select_type: td, select_contains: todo}\n  content: done\n\
+                 - op: delete\n  selector: {select_type: row, select_contains: web}\n\
+                 - op: delete\n  selector:\n    select_type: cell\n    select_contains: Service\n";
+
+    let outcome = document
+        .apply_with_ambiguity(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    let captured: Vec<&str> = outcome
+        .captures
+        .iter()
+        .map(|capture| capture.content.as_str())
+        .collect();
+    assert_eq!(captured, vec!["| cli | todo |\n", "ok\n"]);
+    assert_eq!(
+        document.render(),
+        "|     | State  |\n| --- | ------ |\n| api | *down* |\n| cli | done   |"
+    );
+
+    let err = document
+        .apply(
+            serde_yaml::from_str(
+                "- op: move\n  selector: {select_type: tr}\n  destination: {select_type: table}\n",
+            )
+            .unwrap(),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("move cannot target a table row; select the table instead"),
+        "{err}"
+    );
+}
+
+#[test]
+fn links_images_and_code_spans_are_selected_inside_blocks() {
+    let mut document = MarkdownDocument::from_str(
+        "# Docs\n\nRead [the guide](guide.md) ![logo](logo.png).\n\n\
+         - [API](api.md) uses `curl`\n- [CLI](cli.md) uses `sh`\n",
+    )
+    .unwrap();
+    let batch = "- op: get\n  selector: {select_type: link, select_contains: cli.md}\n\
+                 - op: get\n  selector:\n    select_type: codespan\n    within: {select_type: li, select_contains: API}\n\
+                 - op: replace\n  selector: {select_type: link, select_contains: guide}\n  content: '[the manual](manual.md)'\n\
+                 - op: replace_text\n  selector: {select_type: codespan, select_contains: sh}\n  pattern: sh\n  replacement: bash\n\
+                 - op: delete\n  selector: {select_type: image}\n";
+
+    let outcome = document
+        .apply_with_ambiguity(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    let captured: Vec<&str> = outcome
+        .captures
+        .iter()
+        .map(|capture| capture.content.as_str())
+        .collect();
+    assert_eq!(captured, vec!["[CLI](cli.md)\n", "`curl`\n"]);
+    assert_eq!(
+        document.render(),
+        "# Docs\n\nRead [the manual](manual.md) .\n\n\
+         - [API](api.md) uses `curl`\n- [CLI](cli.md) uses `bash`"
+    );
+
+    let err = document
+        .apply(
+            serde_yaml::from_str(
+                "- op: insert\n  selector: {select_type: codespan}\n  content: x\n",
+            )
+            .unwrap(),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("insert cannot target a code span; select the block holding it instead"),
+        "{err}"
+    );
+}
+
+#[test]
+fn definition_list_terms_and_definitions_are_selectable() {
+    let options = ParseOptions {
+        definition_lists: true,
+        ..ParseOptions::default()
+    };
+    let (mut document, _) = MarkdownDocument::from_str_with_options(
+        "# Glossary\n\nAST\n: Abstract syntax tree.\n\nCLI\n: Command-line interface.\n",
+        &options,
+    )
+    .unwrap();
+    let batch = "- op: insert\n  selector: {select_type: dt, select_contains: CLI}\n  position: before\n  content: |\n    Node\n    : An element of the AST.\n\
+                 - op: replace\n  selector: {select_type: dd, select_contains: Command}\n  content: ': Command-line tool.'\n";
+    document
+        .apply(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        document.render(),
+        "# Glossary\n\nAST\n\n: Abstract syntax tree.\n\nNode\n\n: An element of the AST.\n\n\
+         CLI\n\n: Command-line tool."
+    );
+}
+
+#[test]
+fn compact_definition_entries_are_left_unsplit_by_default() {
+    let markdown = "Ratio is\n: 3 to 1\n\n# Notes\n\nDraft.\n";
+    let mut document = MarkdownDocument::from_str(markdown).unwrap();
+    let batch =
+        "- op: replace\n  selector: {select_type: p, select_contains: Draft}\n  content: Final.\n";
+    document
+        .apply(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    // The entry stays the paragraph it parses to, so no blank line is added after the term.
+    assert_eq!(document.blocks().len(), 3);
+    assert_eq!(document.render(), "Ratio is : 3 to 1\n\n# Notes\n\nFinal.");
+    let dd = "- op: delete\n  selector: {select_type: dd}\n";
+    assert!(document.apply(serde_yaml::from_str(dd).unwrap()).is_err());
+}
+
+#[test]
+fn within_slide_targets_a_slide_of_a_thematic_break_deck() {
+    let mut document = MarkdownDocument::from_str(
+        "# Roadmap\n\n- TBD\n\n---\n\n## Q1\n\n- TBD\n\n---\n\n## Q2\n\n- TBD\n",
+    )
+    .unwrap();
+    let batch = "- op: replace\n  selector: {select_type: li, select_contains: TBD, within_slide: 3}\n  content: '- Launch'\n";
+    document
+        .apply(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        document.render(),
+        "# Roadmap\n\n- TBD\n\n---\n\n## Q1\n\n- TBD\n\n---\n\n## Q2\n\n- Launch"
+    );
+
+    let missing = "- op: delete\n  selector: {select_type: p, within_slide: 4}\n";
+    assert!(document
+        .apply(serde_yaml::from_str(missing).unwrap())
+        .is_err());
+}
+
+#[test]
+fn replace_text_rewrites_plain_text_of_a_section_only() {
+    let mut document = MarkdownDocument::from_str(
+        "# Changelog\n\n## 1.2\n\n- Fixed the *colour* picker ([colour](https://x.y/colour))\n\n\
+         ## 1.1\n\nNew colour themes.\n",
+    )
+    .unwrap();
+    let batch = "- op: replace_text\n  selector: {select_type: h2, select_contains: '1.2'}\n  section: true\n  pattern: '\\bcolou?r\\b'\n  replacement: color\n";
+    let outcome = document
+        .apply_with_ambiguity(serde_yaml::from_str(batch).unwrap())
+        .unwrap();
+
+    assert!(outcome.warnings.is_empty());
+    assert_eq!(
+        document.render(),
+        "# Changelog\n\n## 1.2\n\n- Fixed the *color* picker ([color](https://x.y/colour))\n\n\
+         ## 1.1\n\nNew colour themes."
+    );
+
+    let invalid =
+        "- op: replace_text\n  selector: {select_type: p}\n  pattern: '('\n  replacement: x\n";
+    let err = document
+        .apply(serde_yaml::from_str(invalid).unwrap())
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid replace_text pattern"));
+}
+
+#[test]
+fn rename_heading_keeps_section_and_explicit_id_and_updates_links() {
+    let mut document = MarkdownDocument::from_str(
+        "# Guide\n\n## Setup\n\nSee [usage](#usage).\n\n## Usage {#usage}\n\nRead [setup](#setup).\n",
+    )
+    .unwrap();
+    let batch = "- op: rename_heading\n  selector: {select_type: h2, select_contains: Setup}\n  text: Installing *md-splice*\n  update_links: true\n\
+                 - op: rename_heading\n  selector: {select_type: h2, select_contains: Usage}\n  text: Day-to-day use\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# Guide\n\n## Installing *md-splice*\n\nSee [usage](#usage).\n\n\
+         ## Day-to-day use {#usage}\n\nRead [setup](#installing-md-splice)."
+    );
+
+    let not_a_heading = "- op: rename_heading\n  selector: {select_type: p}\n  text: Title\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(not_a_heading).unwrap();
+    let err = document.apply(operations).unwrap_err();
+    assert!(err.to_string().contains("requires a heading"));
+}
+
+#[test]
+fn replace_with_update_links_follows_renamed_and_renumbered_anchors() {
+    let mut document = MarkdownDocument::from_str(
+        "## Setup\n\nOld steps.\n\n## Setup\n\nSee [first](#setup) and [second](#setup-1).\n",
+    )
+    .unwrap();
+    let batch = "- op: replace\n  selector: {select_type: h2, select_contains: Setup}\n  until: {select_type: h2}\n  content: \"## Install\\n\\nNew steps.\\n\\nMore steps.\"\n  update_links: true\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "## Install\n\nNew steps.\n\nMore steps.\n\n## Setup\n\n\
+         See [first](#install) and [second](#setup)."
+    );
+}
+
+#[test]
+fn replace_inner_html_rewrites_details_body_and_single_block_elements() {
+    let mut document = MarkdownDocument::from_str(
+        "# Tool\n\n<p align=\"center\"><img src=\"old.png\"></p>\n\n\
+         <details>\n<summary>Install</summary>\n\nRun `make`.\n\n</details>\n\nEnd.\n",
+    )
+    .unwrap();
+    let batch = "- op: replace_inner_html\n  selector: {select_tag: details}\n  content: \"<summary>Install</summary>\\n\\nRun `cargo install md-splice`.\"\n\
+                 - op: replace_inner_html\n  selector: {select_type: html, select_regex: 'align=\"center\"'}\n  content: <img src=\"new.png\">\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(batch).unwrap();
+
+    document.apply(operations).unwrap();
+    assert_eq!(
+        document.render(),
+        "# Tool\n\n<p align=\"center\"><img src=\"new.png\"></p>\n\n\
+         <details>\n\n<summary>Install</summary>\n\nRun `cargo install md-splice`.\n\n</details>\n\nEnd."
+    );
+
+    let not_an_element = "- op: replace_inner_html\n  selector: {select_type: p}\n  content: x\n";
+    let operations: Vec<Operation> = serde_yaml::from_str(not_an_element).unwrap();
+    let err = document.apply(operations).unwrap_err();
+    assert!(err.to_string().contains("must match an HTML block"));
+}
+
+#[test]
+fn word_count_criteria_select_stubs_and_placeholder_sections() {
+    let mut doc = MarkdownDocument::from_str(
+        "# Guide\n\nIntro text here.\n\n## Setup\n\n## Usage\n\nTODO\n\n## FAQ\n\nAsk us anything.\n",
+    )
+    .unwrap();
+    let operations: Vec<Operation> = serde_yaml::from_str(
+        "- op: delete\n  selector: {select_type: h2, select_empty: true}\n  section: true\n\
+         - op: replace\n  selector: {select_type: p, select_max_words: 1}\n  content: Run it.\n",
+    )
+    .unwrap();
+    doc.apply(operations).unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Guide\n\nIntro text here.\n\n## Usage\n\nRun it.\n\n## FAQ\n\nAsk us anything."
+    );
+
+    let err = doc
+        .section_of(&LocatorSelector {
+            select_type: Some("h2".to_string()),
+            select_contains: Some("FAQ".to_string()),
+            select_min_words: Some(5),
+            ..LocatorSelector::default()
+        })
+        .unwrap_err();
+    let SpliceError::NodeNotFoundWithNearMisses(near_misses) = &err else {
+        panic!("expected near misses, got {err:?}");
+    };
+    assert_eq!(
+        near_misses[0].reason,
+        "has 3 word(s), outside the word-count criteria"
+    );
+}
+
+#[test]
+fn select_slug_targets_headings_by_their_anchor() {
+    let mut doc = MarkdownDocument::from_str(
+        "# Guide\n\n## Installation notes\n\nOld.\n\n## Installation notes\n\nOlder.\n\n\
+         ## Usage {#run}\n\nRun it.\n",
+    )
+    .unwrap();
+    let batch = "- op: replace\n  selector: {select_slug: installation-notes-1}\n  content: \"## Upgrading\"\n\
+                 - op: delete\n  selector: {select_slug: run}\n  section: true\n";
+    doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Guide\n\n## Installation notes\n\nOld.\n\n## Upgrading\n\nOlder."
+    );
+
+    let err = doc
+        .section_of(&LocatorSelector {
+            select_slug: Some("installation".to_string()),
+            ..LocatorSelector::default()
+        })
+        .unwrap_err();
+    let SpliceError::NodeNotFoundWithNearMisses(near_misses) = &err else {
+        panic!("expected near misses, got {err:?}");
+    };
+    assert_eq!(
+        near_misses[0].reason,
+        "not the heading with anchor `#installation`"
+    );
+}
+
+#[test]
+fn between_scope_bounds_the_search_by_two_landmarks() {
+    let mut doc = MarkdownDocument::from_str(
+        "# Guide\n\n## Install\n\nStep one.\n\nStep two.\n\n## Usage\n\nStep three.\n",
+    )
+    .unwrap();
+    let batch = "- op: replace\n  selector:\n    select_type: p\n    select_ordinal: 2\n    between:\n      \
+                 start: {select_contains: Install, alias: install}\n      end: {select_type: h2}\n  \
+                 content: Step 2.\n\
+                 - op: delete\n  selector:\n    select_type: p\n    between: {start_ref: install, end: {select_type: h2}}\n";
+    doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Guide\n\n## Install\n\nStep 2.\n\n## Usage\n\nStep three."
+    );
+
+    let batch = "- op: delete\n  selector:\n    select_contains: three\n    between:\n      \
+                 start: {select_contains: Guide}\n      end: {select_contains: Usage}\n";
+    let err = doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap_err();
+    let SpliceError::NodeNotFoundWithNearMisses(near_misses) = &err else {
+        panic!("expected near misses, got {err:?}");
+    };
+    assert_eq!(near_misses[0].reason, "outside the `between` landmarks");
+}
+
+#[test]
+fn negated_criteria_skip_matching_nodes() {
+    let mut doc = MarkdownDocument::from_str(
+        "```rust\n#![allow(dead_code)]\nfn old() {}\n```\n\n```rust\nfn new() {}\n```\n\n\
+         - [x] Done\n- [ ] Todo\n",
+    )
+    .unwrap();
+    let batch =
+        "- op: delete\n  selector: {select_type: code, select_not_contains: \"#![allow\"}\n\
+                 - op: replace\n  selector: {select_type: li, select_not_regex: '\\[[xX]\\]'}\n  \
+                 content: \"- [ ] Todo soon\"\n";
+    doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap();
+    assert_eq!(
+        doc.render(),
+        "```rust\n#![allow(dead_code)]\nfn old() {}\n```\n\n- [X] Done\n- [ ] Todo soon"
+    );
+
+    let batch = "- op: delete\n  selector: {select_type: code, select_not_regex: allow}\n";
+    let err = doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap_err();
+    let SpliceError::NodeNotFoundWithNearMisses(near_misses) = &err else {
+        panic!("expected near misses, got {err:?}");
+    };
+    assert_eq!(near_misses[0].reason, "matches the excluded regex `allow`");
+}
+
+#[test]
+fn select_contains_ci_ignores_case_and_whitespace() {
+    let mut doc =
+        MarkdownDocument::from_str("# Guide\n\nRun   the\nInstaller first.\n\nThen relax.\n")
+            .unwrap();
+    let batch = "- op: replace\n  selector: {select_contains_ci: \"run the installer\"}\n  \
+                 content: Run the installer.\n";
+    doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap();
+    assert_eq!(doc.render(), "# Guide\n\nRun the installer.\n\nThen relax.");
+
+    let selector = TxSelector::default().containing_ignoring_case("guide");
+    assert_eq!(selector.to_string(), "*~i\"guide\"");
+}
+
+#[test]
+fn select_last_counts_the_ordinal_from_the_last_match() {
+    let mut doc =
+        MarkdownDocument::from_str("# Plan\n\n## Now\n\n- a\n\n## Backlog\n\n- b\n- c\n").unwrap();
+    let batch = "- op: insert\n  selector: {select_type: li, select_last: true}\n  \
+                 position: after\n  content: \"- d\"\n\
+                 - op: replace\n  selector: {select_type: h2, select_last: true, select_ordinal: 2}\n  \
+                 content: \"## Today\"\n";
+    doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Plan\n\n## Today\n\n- a\n\n## Backlog\n\n- b\n- c\n- d"
+    );
+
+    let selector = TxSelector::of_type("h2").nth_last(1);
+    assert_eq!(selector.to_string(), "h2 (#-1)");
+}
+
+#[test]
+fn select_depth_edits_and_deletes_nested_list_items() {
+    let mut doc = MarkdownDocument::from_str(
+        "# Tasks\n\n- [ ] Release\n  - [x] Tag\n  - [ ] Publish\n- [ ] Announce\n  - [x] Draft\n",
+    )
+    .unwrap();
+    let batch = "- op: replace\n  selector: {select_type: li, select_depth: 2, select_contains: Publish}\n  \
+                 content: \"- [x] Publish\"\n\
+                 - op: insert\n  selector: {select_type: li, select_depth: 2, select_contains: Publish}\n  \
+                 position: after\n  content: \"- [ ] Verify\"\n\
+                 - op: delete\n  selector: {select_type: li, select_depth: 2, select_contains: Draft}\n";
+    doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Tasks\n\n- [ ] Release\n  \n  - [X] Tag\n  - [X] Publish\n  - [ ] Verify\n- [ ] Announce"
+    );
+
+    let move_batch = "- op: move\n  selector: {select_type: li, select_depth: 2}\n  \
+                      destination: {select_type: h1}\n";
+    let err = doc
+        .apply(serde_yaml::from_str(move_batch).unwrap())
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("move cannot target a nested list item"));
+    assert_eq!(
+        TxSelector::of_type("li").at_depth(2).to_string(),
+        "li[depth=2]"
+    );
+}
+
+#[test]
+fn task_state_selects_checked_or_unchecked_items() {
+    let mut doc = MarkdownDocument::from_str(
+        "# Board\n\n## Done\n\n- [x] Ship\n- [ ] Announce\n- [x] Tag\n- Notes\n",
+    )
+    .unwrap();
+    let delete = "- op: delete\n  selector: {select_type: li, task_state: complete, \
+                  within: {select_type: h2, select_contains: Done}}\n";
+    let batch = format!("{delete}{delete}");
+    doc.apply(serde_yaml::from_str(&batch).unwrap()).unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Board\n\n## Done\n\n- [ ] Announce\n- Notes"
+    );
+
+    let batch =
+        "- op: replace\n  selector: {select_type: li, task_state: any, select_last: true}\n  \
+                 content: \"- [x] Announce\"\n";
+    doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Board\n\n## Done\n\n- [X] Announce\n- Notes"
+    );
+    assert_eq!(
+        TxSelector::of_type("li")
+            .with_task_state(TaskStatus::Incomplete)
+            .to_string(),
+        "li[incomplete]"
+    );
+}
+
+#[test]
+fn locate_all_matches_describe_their_positions() {
+    let doc = MarkdownDocument::from_str("# Plan\n\n- Draft\n  - Review\n\n> Quoted\n").unwrap();
+    let selector = LocatorSelector {
+        select_type: Some("li".to_string()),
+        select_depth: Some(2),
+        ..LocatorSelector::default()
+    };
+    let matches = locate_all(doc.blocks(), &selector).unwrap();
+    assert_eq!(
+        matches[0].metadata(),
+        MatchMetadata {
+            node_type: "li",
+            block_index: 1,
+            nested_path: Vec::new(),
+            list_indices: vec![0, 0],
+            row_index: None,
+            column_index: None,
+            inline_position: None,
+            heading_level: None,
+        }
+    );
+
+    let selector = LocatorSelector {
+        select_type: Some("p".to_string()),
+        recursive: true,
+        ..LocatorSelector::default()
+    };
+    let matches = locate_all(doc.blocks(), &selector).unwrap();
+    let metadata = matches.last().unwrap().metadata();
+    assert_eq!((metadata.block_index, metadata.nested_path), (2, vec![0]));
+
+    let (heading, _) = locate(doc.blocks(), &LocatorSelector::default()).unwrap();
+    assert_eq!(heading.metadata().node_type, "h1");
+    assert_eq!(heading.metadata().heading_level, Some(1));
+}
+
+#[test]
+fn block_spans_survive_edits_to_other_blocks() {
+    let mut doc =
+        MarkdownDocument::from_str("---\ntitle: Notes\n---\n# Notes\n\nDraft.\n\n- one\n- two\n")
+            .unwrap();
+    let lines = |doc: &MarkdownDocument| -> Vec<Option<String>> {
+        doc.block_spans()
+            .iter()
+            .map(|span| span.as_ref().map(ToString::to_string))
+            .collect()
+    };
+    assert_eq!(
+        lines(&doc),
+        vec![
+            Some("line 4".to_string()),
+            Some("line 6".to_string()),
+            Some("lines 8-9".to_string()),
+        ]
+    );
+
+    let batch = "- op: replace\n  selector: {select_type: p}\n  content: Final.\n\
+                 - op: insert\n  selector: {select_type: h1}\n  content: Intro.\n";
+    doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap();
+    assert_eq!(
+        lines(&doc),
+        vec![
+            Some("line 4".to_string()),
+            None,
+            None,
+            Some("lines 8-9".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn recursive_selectors_edit_blocks_nested_in_containers() {
+    let mut doc = MarkdownDocument::from_str(
+        "# Notes\n\n> [!NOTE]\n> Beta builds are unsigned.\n>\n> Report bugs upstream.\n\n- Setup\n\n  Beta keys expire.\n",
+    )
+    .unwrap();
+    let batch = "- op: replace\n  selector: {select_type: p, select_contains: Beta builds, recursive: true}\n  \
+                 content: Beta builds are signed.\n\
+                 - op: delete\n  selector: {select_type: p, select_contains: Beta keys, recursive: true}\n";
+    doc.apply(serde_yaml::from_str(batch).unwrap()).unwrap();
+    assert_eq!(
+        doc.render(),
+        "# Notes\n\n> [!NOTE]\n> Beta builds are signed.\n>\n> Report bugs upstream.\n\n- Setup"
+    );
+
+    let flat = TxSelector::of_type("p").containing("Report bugs");
+    let batch = vec![Operation::Delete(DeleteOperation {
+        selector: Some(flat.clone()),
+        ..DeleteOperation::default()
+    })];
+    assert!(doc.apply(batch).is_err());
+    assert_eq!(flat.recursive().to_string(), "p~\"Report bugs\"[recursive]");
+}
@@ -8,6 +8,8 @@ from . import errors
 from .errors import (
     AmbiguousContentSourceError,
     AmbiguousStdinSourceError,
+    AssertionFailedError,
+    ConflictingOperationsError,
     ConflictingScopeError,
     FrontmatterKeyNotFoundError,
     FrontmatterMissingError,
@@ -15,53 +17,153 @@ from .errors import (
     FrontmatterSerializeError,
     InvalidChildInsertionError,
     InvalidListItemContentError,
+    InvalidMoveDestinationError,
     InvalidRegexError,
     InvalidSectionDeleteError,
     IoError,
+    LimitExceededError,
     MarkdownParseError,
     MdSpliceError,
     NoContentError,
     NodeNotFoundError,
     OperationFailedError,
+    OperationNotAllowedError,
     OperationParseError,
+    OverlappingSwapError,
     RangeRequiresBlockError,
     SectionRequiresHeadingError,
+    TimeLimitExceededError,
+    TooManyMatchesError,
 )
 from ._native import (
     MarkdownDocument,
     __version__,
+    apply_to_files,
+    diff_structural,
     diff_unified,
     dumps_operations,
     loads_operations,
+    merge,
 )
 from .types import (
+    AddTableColumnOperation,
+    AlertType,
+    ApplyWarning,
+    AssertExpectation,
+    AssertOperation,
+    Between,
+    Capture,
+    ColumnAlignment,
+    CommentPosition,
+    CopyOperation,
+    DedupeHeadingsOperation,
     DeleteFrontmatterOperation,
     DeleteOperation,
+    DeleteTableRowOperation,
     FrontmatterFormat,
+    GetOperation,
+    ImportSectionOperation,
     InsertOperation,
     InsertPosition,
+    InsertTableRowOperation,
+    MergeConflict,
+    MergeResult,
+    MergeSectionsOperation,
+    MoveOperation,
     Operation,
+    PruneEmptySectionsOperation,
+    RemoveAlertOperation,
+    RemoveCommentOperation,
+    RemoveTableColumnOperation,
+    ReplaceInnerHtmlOperation,
+    RenameHeadingOperation,
+    RenameTableColumnOperation,
+    RenumberOperation,
     ReplaceFrontmatterOperation,
     ReplaceOperation,
+    ReplaceTextOperation,
     Selector,
+    SetAlertOperation,
+    SetCodeAttributeOperation,
+    SetCodeLanguageOperation,
+    SetCommentOperation,
     SetFrontmatterOperation,
+    ShiftHeadingOperation,
+    SlugStyle,
+    StructuralChange,
+    SwapOperation,
+    TaskState,
+    TieBreak,
+    UnwrapDetailsOperation,
+    UnwrapOperation,
+    UpdateTocOperation,
+    UpsertOperation,
+    WrapDetailsOperation,
+    WrapOperation,
 )
 
 __all__ = [
     "MarkdownDocument",
+    "apply_to_files",
     "diff_unified",
+    "diff_structural",
+    "merge",
     "loads_operations",
     "dumps_operations",
     "InsertPosition",
     "Selector",
+    "Between",
+    "TieBreak",
+    "TaskState",
     "FrontmatterFormat",
+    "AlertType",
+    "CommentPosition",
+    "AssertExpectation",
+    "ColumnAlignment",
+    "SlugStyle",
     "InsertOperation",
     "ReplaceOperation",
+    "ReplaceTextOperation",
+    "UpsertOperation",
     "DeleteOperation",
+    "MoveOperation",
+    "CopyOperation",
+    "SwapOperation",
+    "RenameHeadingOperation",
+    "ShiftHeadingOperation",
+    "MergeSectionsOperation",
     "SetFrontmatterOperation",
     "DeleteFrontmatterOperation",
     "ReplaceFrontmatterOperation",
+    "PruneEmptySectionsOperation",
+    "DedupeHeadingsOperation",
+    "RenumberOperation",
+    "ImportSectionOperation",
+    "SetAlertOperation",
+    "RemoveAlertOperation",
+    "SetCommentOperation",
+    "RemoveCommentOperation",
+    "ReplaceInnerHtmlOperation",
+    "WrapOperation",
+    "UnwrapOperation",
+    "WrapDetailsOperation",
+    "UnwrapDetailsOperation",
+    "SetCodeAttributeOperation",
+    "SetCodeLanguageOperation",
+    "InsertTableRowOperation",
+    "DeleteTableRowOperation",
+    "AddTableColumnOperation",
+    "RemoveTableColumnOperation",
+    "RenameTableColumnOperation",
+    "UpdateTocOperation",
+    "AssertOperation",
+    "GetOperation",
     "Operation",
+    "ApplyWarning",
+    "Capture",
+    "StructuralChange",
+    "MergeConflict",
+    "MergeResult",
     "errors",
     "__version__",
 ]
@@ -76,9 +178,12 @@ __all__.extend(
         "InvalidListItemContentError",
         "AmbiguousStdinSourceError",
         "InvalidSectionDeleteError",
+        "InvalidMoveDestinationError",
+        "OverlappingSwapError",
         "SectionRequiresHeadingError",
         "ConflictingScopeError",
         "RangeRequiresBlockError",
+        "TooManyMatchesError",
         "FrontmatterMissingError",
         "FrontmatterKeyNotFoundError",
         "FrontmatterParseError",
@@ -86,6 +191,11 @@ __all__.extend(
         "MarkdownParseError",
         "OperationParseError",
         "OperationFailedError",
+        "ConflictingOperationsError",
+        "LimitExceededError",
+        "TimeLimitExceededError",
+        "OperationNotAllowedError",
+        "AssertionFailedError",
         "IoError",
         "InvalidRegexError",
         "errors",
@@ -43,6 +43,14 @@ class InvalidSectionDeleteError(MdSpliceError):
     """Raised when deleting a section from a non-heading target."""
 
 
+class InvalidMoveDestinationError(MdSpliceError):
+    """Raised when a move destination lies inside the content being moved."""
+
+
+class OverlappingSwapError(MdSpliceError):
+    """Raised when one of the nodes being swapped lies inside the other."""
+
+
 class SectionRequiresHeadingError(MdSpliceError):
     """Raised when section semantics are requested on a non-heading selector."""
 
@@ -55,6 +63,10 @@ class RangeRequiresBlockError(MdSpliceError):
     """Raised when range selectors are applied to non-block selections."""
 
 
+class TooManyMatchesError(MdSpliceError):
+    """Raised when a selector matches more nodes than its ``max_matches`` limit."""
+
+
 class FrontmatterMissingError(MdSpliceError):
     """Raised when attempting to mutate or read frontmatter that does not exist."""
 
@@ -83,6 +95,26 @@ class OperationFailedError(MdSpliceError):
     """Raised when an operation fails during execution."""
 
 
+class ConflictingOperationsError(MdSpliceError):
+    """Raised when a batch contains operations that conflict with each other."""
+
+
+class LimitExceededError(MdSpliceError):
+    """Raised when a document or batch exceeds a configured resource limit."""
+
+
+class TimeLimitExceededError(LimitExceededError):
+    """Raised when applying a batch or one of its operations takes too long."""
+
+
+class OperationNotAllowedError(MdSpliceError):
+    """Raised when an operation does something the operation policy forbids."""
+
+
+class AssertionFailedError(MdSpliceError):
+    """Raised when an ``assert`` operation's condition does not hold."""
+
+
 class IoError(MdSpliceError):
     """Raised for underlying I/O errors."""
 
@@ -116,9 +148,12 @@ __all__ = [
     "InvalidListItemContentError",
     "AmbiguousStdinSourceError",
     "InvalidSectionDeleteError",
+    "InvalidMoveDestinationError",
+    "OverlappingSwapError",
     "SectionRequiresHeadingError",
     "ConflictingScopeError",
     "RangeRequiresBlockError",
+    "TooManyMatchesError",
     "FrontmatterMissingError",
     "FrontmatterKeyNotFoundError",
     "FrontmatterParseError",
@@ -126,6 +161,11 @@ __all__ = [
     "MarkdownParseError",
     "OperationParseError",
     "OperationFailedError",
+    "ConflictingOperationsError",
+    "LimitExceededError",
+    "TimeLimitExceededError",
+    "OperationNotAllowedError",
+    "AssertionFailedError",
     "IoError",
     "InvalidRegexError",
     "SelectorAliasNotDefinedError",
@@ -17,6 +17,46 @@ class FrontmatterFormat(str, Enum):
     TOML = "toml"
 
 
+class AlertType(str, Enum):
+    """Type of a GitHub alert, written as ``> [!NOTE]`` and so on."""
+
+    NOTE = "note"
+    TIP = "tip"
+    IMPORTANT = "important"
+    WARNING = "warning"
+    CAUTION = "caution"
+
+
+class CommentPosition(str, Enum):
+    """Side of a block on which an annotation comment sits."""
+
+    BEFORE = "before"
+    AFTER = "after"
+
+
+class AssertExpectation(str, Enum):
+    """Whether an :class:`AssertOperation` expects its condition to hold."""
+
+    MATCH = "match"
+    NO_MATCH = "no_match"
+
+
+class ColumnAlignment(str, Enum):
+    """Alignment of a table column, written in its delimiter row."""
+
+    NONE = "none"
+    LEFT = "left"
+    CENTER = "center"
+    RIGHT = "right"
+
+
+class SlugStyle(str, Enum):
+    """How a table of contents derives the anchor of a heading from its text."""
+
+    GITHUB = "github"
+    GITLAB = "gitlab"
+
+
 class InsertPosition(str, Enum):
     """Insertion position relative to a selector target."""
 
@@ -26,6 +66,28 @@ class InsertPosition(str, Enum):
     APPEND_CHILD = "append_child"
 
 
+class TieBreak(str, Enum):
+    """Order in which the nodes matching a :class:`Selector` are ranked.
+
+    ``select_ordinal`` counts matches in this order. ``DEEPEST_FIRST`` ranks
+    nodes in deeper heading sections first, keeping document order otherwise.
+    """
+
+    DOCUMENT_ORDER = "document_order"
+    DEEPEST_FIRST = "deepest_first"
+
+
+class TaskState(str, Enum):
+    """State of the task list items a :class:`Selector` matches.
+
+    ``ANY`` matches checked and unchecked tasks alike, but not plain list items.
+    """
+
+    COMPLETE = "complete"
+    INCOMPLETE = "incomplete"
+    ANY = "any"
+
+
 @dataclass(frozen=True, slots=True)
 class Selector:
     """Criteria for locating Markdown nodes via the Rust core.
@@ -48,10 +110,32 @@ class Selector:
     after_ref: str | None = None
     within: Selector | None = None
     within_ref: str | None = None
+    select_fingerprint: str | None = None
+    select_annotation: str | None = None
+    select_tag: str | None = None
+    select_min_words: int | None = None
+    select_max_words: int | None = None
+    select_empty: bool | None = None
+    max_matches: int | None = None
+    tie_break: TieBreak = TieBreak.DOCUMENT_ORDER
+    within_slide: int | None = None
+    select_info_contains: str | None = None
+    select_slug: str | None = None
+    between: Between | None = None
+    select_not_contains: str | None = None
+    select_not_regex: Pattern[str] | str | None = field(default=None, repr=False)
+    select_contains_ci: str | None = None
+    select_last: bool = False
+    select_depth: int | None = None
+    recursive: bool = False
+    task_state: TaskState | None = None
+    at_line: int | None = None
 
     def __post_init__(self) -> None:  # noqa: D401 - dataclass validation hook
         has_after = self.after is not None or self.after_ref is not None
         has_within = self.within is not None or self.within_ref is not None
+        has_slide = self.within_slide is not None
+        has_between = self.between is not None
 
         if self.after is not None and self.after_ref is not None:
             raise ValueError("Cannot specify both 'after' and 'after_ref'.")
@@ -62,22 +146,63 @@ class Selector:
             raise ConflictingScopeError(
                 "Selector cannot specify both 'after' and 'within' scopes."
             )
+        if has_slide and (has_after or has_within):
+            raise ConflictingScopeError(
+                "Selector cannot combine 'within_slide' with 'after' or 'within'."
+            )
+        if has_between and (has_after or has_within or has_slide):
+            raise ConflictingScopeError(
+                "Selector cannot combine 'between' with 'after', 'within', or 'within_slide'."
+            )
+        if has_slide and self.within_slide < 1:
+            raise ValueError("within_slide must be a positive integer")
+        if self.at_line is not None and self.at_line < 1:
+            raise ValueError("at_line must be a positive integer")
 
         if self.select_ordinal < 1:
             raise ValueError("select_ordinal must be a positive integer")
+        if self.max_matches is not None and self.max_matches < 0:
+            raise ValueError("max_matches must be a non-negative integer")
+        for name in ("select_min_words", "select_max_words"):
+            value = getattr(self, name)
+            if value is not None and value < 0:
+                raise ValueError(f"{name} must be a non-negative integer")
+
+        for name in ("select_regex", "select_not_regex"):
+            pattern = getattr(self, name)
+            if isinstance(pattern, str):
+                try:
+                    compiled = re.compile(pattern)
+                except re.error as exc:  # pragma: no cover - exercised in tests
+                    raise InvalidRegexError(str(exc)) from exc
+                object.__setattr__(self, name, compiled)
+            elif pattern is None or isinstance(pattern, re.Pattern):
+                # Already compiled or absent; no action needed.
+                pass
+            else:  # pragma: no cover - defensive branch
+                raise TypeError(f"{name} must be a str, compiled Pattern, or None")
 
-        pattern = self.select_regex
-        if isinstance(pattern, str):
-            try:
-                compiled = re.compile(pattern)
-            except re.error as exc:  # pragma: no cover - exercised in tests
-                raise InvalidRegexError(str(exc)) from exc
-            object.__setattr__(self, "select_regex", compiled)
-        elif pattern is None or isinstance(pattern, re.Pattern):
-            # Already compiled or absent; no action needed.
-            pass
-        else:  # pragma: no cover - defensive branch
-            raise TypeError("select_regex must be a str, compiled Pattern, or None")
+
+@dataclass(frozen=True, slots=True)
+class Between:
+    """Two landmarks bounding a :class:`Selector` search to the blocks between them.
+
+    The ``end`` landmark is searched after the ``start`` one, so its
+    ``select_ordinal`` counts the matches following ``start``. Either landmark
+    may reference a selector alias through ``start_ref`` or ``end_ref``.
+    """
+
+    start: Selector | None = None
+    end: Selector | None = None
+    start_ref: str | None = None
+    end_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        for name in ("start", "end"):
+            has_inline = getattr(self, name) is not None
+            has_ref = getattr(self, f"{name}_ref") is not None
+            if has_inline == has_ref:
+                raise ValueError(f"Specify exactly one of '{name}' or '{name}_ref'.")
 
 
 @dataclass(frozen=True, slots=True)
@@ -86,13 +211,16 @@ class InsertOperation:
 
     ``position`` controls where the new content lands with respect to the
     matched node (before, after, or as a child), matching the CLI schema
-    defined in ``goal-Python-library/Specification.md``.
+    defined in ``goal-Python-library/Specification.md``. With
+    ``renumber=True``, ordered lists are renumbered afterwards as
+    :class:`RenumberOperation` does.
     """
 
     selector: Selector | None = None
     selector_ref: str | None = None
     content: str | None = None
     position: InsertPosition = InsertPosition.AFTER
+    renumber: bool = False
 
     def __post_init__(self) -> None:
         if (self.selector is None) == (self.selector_ref is None):
@@ -101,13 +229,22 @@ class InsertOperation:
             )
 
 
+def _validate_cell(cell: tuple[int, int]) -> None:
+    row, column = cell
+    if row < 0 or column < 1:
+        raise ValueError("cell must be (row, column) with row >= 0 and column >= 1")
+
+
 @dataclass(frozen=True, slots=True)
 class ReplaceOperation:
     """Replace Markdown matched by a selector, optionally up to ``until``.
 
     When ``until`` is provided the replacement covers the range from the
     selector through (but excluding) the ``until`` target, mirroring the Rust
-    transaction semantics.
+    transaction semantics. With ``update_links=True``, links pointing at the
+    anchors of replaced headings follow the anchors of their replacements.
+    ``cell=(row, column)`` replaces only the inline content of that cell of the
+    matched table, where row 0 is the header row and columns start at 1.
     """
 
     selector: Selector | None = None
@@ -115,6 +252,8 @@ class ReplaceOperation:
     content: str | None = None
     until: Selector | None = None
     until_ref: str | None = None
+    update_links: bool = False
+    cell: tuple[int, int] | None = None
 
     def __post_init__(self) -> None:
         if (self.selector is None) == (self.selector_ref is None):
@@ -125,6 +264,67 @@ class ReplaceOperation:
             raise ValueError(
                 "ReplaceOperation requires exactly one of 'until' or 'until_ref'."
             )
+        if self.cell is not None:
+            _validate_cell(self.cell)
+
+
+@dataclass(frozen=True, slots=True)
+class ReplaceTextOperation:
+    """Run a regex substitution on the text of the content matched by a selector.
+
+    ``replacement`` may refer to capture groups as ``$1`` or ``${name}``. Only
+    plain text is rewritten, so emphasis, links, and code spans around it are
+    kept. ``section=True`` covers a heading's whole section and ``until``
+    covers a range of blocks; when nothing matches, a ``"skipped_operation"``
+    warning is returned.
+    """
+
+    pattern: str
+    replacement: str
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    section: bool = False
+    until: Selector | None = None
+    until_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "ReplaceTextOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if self.until is not None and self.until_ref is not None:
+            raise ValueError(
+                "ReplaceTextOperation accepts at most one of 'until' or 'until_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class UpsertOperation:
+    """Replace Markdown matched by a selector, or insert it when nothing matches.
+
+    ``section=True`` replaces the entire heading section for a heading match.
+    When the selector matches nothing, the content is inserted at ``position``
+    relative to ``fallback`` (or ``fallback_ref``), or appended to the
+    document without a fallback, so repeated runs stay idempotent.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    content: str | None = None
+    section: bool = False
+    fallback: Selector | None = None
+    fallback_ref: str | None = None
+    position: InsertPosition = InsertPosition.AFTER
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "UpsertOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if self.fallback is not None and self.fallback_ref is not None:
+            raise ValueError(
+                "UpsertOperation requires exactly one of 'fallback' or 'fallback_ref'."
+            )
 
 
 @dataclass(frozen=True, slots=True)
@@ -133,7 +333,9 @@ class DeleteOperation:
 
     Setting ``section=True`` removes the entire heading section for a heading
     match. Providing ``until`` deletes a range ending before the ``until``
-    selector. Both behaviors mirror the CLI and Rust core.
+    selector. Both behaviors mirror the CLI and Rust core. With
+    ``renumber=True``, ordered lists are renumbered afterwards as
+    :class:`RenumberOperation` does.
     """
 
     selector: Selector | None = None
@@ -141,6 +343,7 @@ class DeleteOperation:
     section: bool = False
     until: Selector | None = None
     until_ref: str | None = None
+    renumber: bool = False
 
     def __post_init__(self) -> None:
         if (self.selector is None) == (self.selector_ref is None):
@@ -153,6 +356,165 @@ class DeleteOperation:
             )
 
 
+@dataclass(frozen=True, slots=True)
+class MoveOperation:
+    """Move Markdown matched by a selector next to (or into) a destination.
+
+    Setting ``section=True`` moves the entire heading section for a heading
+    match. Both selectors are located before anything moves, so the move
+    happens in one step of the batch instead of a delete followed by an
+    insert.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    section: bool = False
+    destination: Selector | None = None
+    destination_ref: str | None = None
+    position: InsertPosition = InsertPosition.AFTER
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "MoveOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if (self.destination is None) == (self.destination_ref is None):
+            raise ValueError(
+                "MoveOperation requires exactly one of 'destination' or 'destination_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class CopyOperation:
+    """Copy Markdown matched by a selector next to (or into) a destination.
+
+    ``section=True`` copies the entire heading section for a heading match,
+    and ``until`` copies a range ending before the ``until`` selector. The
+    original content stays in place.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    section: bool = False
+    until: Selector | None = None
+    until_ref: str | None = None
+    destination: Selector | None = None
+    destination_ref: str | None = None
+    position: InsertPosition = InsertPosition.AFTER
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "CopyOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if self.until is not None and self.until_ref is not None:
+            raise ValueError(
+                "CopyOperation requires exactly one of 'until' or 'until_ref'."
+            )
+        if (self.destination is None) == (self.destination_ref is None):
+            raise ValueError(
+                "CopyOperation requires exactly one of 'destination' or 'destination_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class SwapOperation:
+    """Exchange the positions of the Markdown matched by two selectors.
+
+    Setting ``section=True`` swaps entire heading sections, which reorders
+    chapters without computing indices. Both selectors are located before
+    anything moves, and neither match may lie inside the other.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    section: bool = False
+    other: Selector | None = None
+    other_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "SwapOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if (self.other is None) == (self.other_ref is None):
+            raise ValueError(
+                "SwapOperation requires exactly one of 'other' or 'other_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class RenameHeadingOperation:
+    """Replace the text of a heading, keeping its level and section.
+
+    An explicit ``{#id}`` attribute on the heading is kept unless ``id``
+    replaces it. With ``update_links=True``, links such as ``[Setup](#setup)``
+    elsewhere in the document follow the heading's new anchor.
+    """
+
+    text: str
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    id: str | None = None
+    update_links: bool = False
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "RenameHeadingOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class ShiftHeadingOperation:
+    """Raise or lower the level of a heading by ``delta`` levels.
+
+    A negative ``delta`` promotes the heading (``h3`` to ``h2``) and a positive
+    one demotes it. With ``include_section=True``, the headings nested in its
+    section shift along with it. Shifting a heading above ``h1`` or below
+    ``h6`` fails without changing the document.
+    """
+
+    delta: int
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    include_section: bool = False
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "ShiftHeadingOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class MergeSectionsOperation:
+    """Merge the section of the heading matched by ``selector`` into the
+    section of the heading matched by ``destination``.
+
+    The matched heading is removed and its section's blocks are appended at
+    the end of the destination's section. With ``adjust_levels=True``, the
+    moved subheadings keep their depth relative to the destination heading.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    destination: Selector | None = None
+    destination_ref: str | None = None
+    adjust_levels: bool = False
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "MergeSectionsOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if (self.destination is None) == (self.destination_ref is None):
+            raise ValueError(
+                "MergeSectionsOperation requires exactly one of 'destination' or "
+                "'destination_ref'."
+            )
+
+
 @dataclass(frozen=True, slots=True)
 class SetFrontmatterOperation:
     """Assign a value at the given frontmatter key path.
@@ -187,25 +549,676 @@ class ReplaceFrontmatterOperation:
     format: FrontmatterFormat | None = None
 
 
+@dataclass(frozen=True, slots=True)
+class PruneEmptySectionsOperation:
+    """Remove headings whose section contains no content.
+
+    ``min_level`` and ``max_level`` restrict which heading levels are pruned,
+    and ``within``/``within_ref`` limit pruning to the section of a heading.
+    Parents left empty by pruning their subsections are removed as well.
+    """
+
+    min_level: int | None = None
+    max_level: int | None = None
+    within: Selector | None = None
+    within_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if self.within is not None and self.within_ref is not None:
+            raise ValueError(
+                "PruneEmptySectionsOperation accepts at most one of 'within' or 'within_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class DedupeHeadingsOperation:
+    """Rename headings whose text repeats an earlier heading.
+
+    Repeats receive a numeric suffix such as ``" (2)"`` so their anchor links
+    no longer collide. ``within``/``within_ref`` limit detection to the section
+    of a heading, and ``report_only=True`` reports each group of duplicates as a
+    ``"duplicate_heading"`` warning instead of renaming.
+    """
+
+    within: Selector | None = None
+    within_ref: str | None = None
+    report_only: bool = False
+
+    def __post_init__(self) -> None:
+        if self.within is not None and self.within_ref is not None:
+            raise ValueError(
+                "DedupeHeadingsOperation accepts at most one of 'within' or 'within_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class RenumberOperation:
+    """Renumber ordered lists that continue an earlier list.
+
+    A list continues the previous ordered list when it directly follows it, or
+    when it starts at a number other than 1 and no heading or thematic break
+    separates them, as in a procedure split by code blocks. ``within``/
+    ``within_ref`` limit renumbering to the section of a heading.
+    """
+
+    within: Selector | None = None
+    within_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if self.within is not None and self.within_ref is not None:
+            raise ValueError(
+                "RenumberOperation accepts at most one of 'within' or 'within_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class ImportSectionOperation:
+    """Copy a heading's section from another Markdown file into the document.
+
+    ``source_selector`` picks the heading in ``source_file`` whose section is
+    imported. Without ``position`` the section of the heading matched by
+    ``selector`` is replaced; with a ``position`` the imported section is
+    inserted relative to the match instead.
+    """
+
+    source_file: str
+    source_selector: Selector
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    position: InsertPosition | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "ImportSectionOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class SetAlertOperation:
+    """Turn a blockquote into a GitHub alert, or change an alert's type.
+
+    The quoted content is kept unchanged; only the ``[!TYPE]`` marker is
+    added or updated.
+    """
+
+    alert_type: AlertType
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "SetAlertOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class RemoveAlertOperation:
+    """Turn a GitHub alert back into a plain blockquote with the same content."""
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "RemoveAlertOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class SetCommentOperation:
+    """Place an HTML comment (``<!-- text -->``) next to the matched block.
+
+    An existing comment on that side is updated instead of adding another
+    one; when ``marker`` is set, only a comment containing it counts.
+    """
+
+    text: str
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    position: CommentPosition = CommentPosition.BEFORE
+    marker: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "SetCommentOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class RemoveCommentOperation:
+    """Remove the HTML comment next to the matched block.
+
+    When there is no such comment (or none containing ``marker``), nothing
+    changes and a ``skipped_operation`` warning is returned.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    position: CommentPosition = CommentPosition.BEFORE
+    marker: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "RemoveCommentOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class ReplaceInnerHtmlOperation:
+    """Replace what lies between the tags of the matched HTML element.
+
+    The selector matches the HTML block opening the element, for example
+    ``Selector(select_tag="details")``. The element may wrap Markdown blocks
+    up to its closing tag; the tags and their attributes are kept.
+    """
+
+    content: str
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "ReplaceInnerHtmlOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class WrapOperation:
+    """Wrap the matched Markdown in a blockquote, or in a GitHub alert.
+
+    ``alert_type`` wraps the content in an alert of that type instead of a
+    plain blockquote. ``section=True`` wraps the entire heading section for a
+    heading match, and ``until`` wraps a range ending before the ``until``
+    selector.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    alert_type: AlertType | None = None
+    section: bool = False
+    until: Selector | None = None
+    until_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "WrapOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if self.until is not None and self.until_ref is not None:
+            raise ValueError(
+                "WrapOperation requires exactly one of 'until' or 'until_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class UnwrapOperation:
+    """Replace the matched blockquote or alert with the blocks it contains."""
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "UnwrapOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class WrapDetailsOperation:
+    """Wrap the matched Markdown in a collapsible ``<details>`` element.
+
+    ``summary`` becomes the ``<summary>`` text and ``open=True`` expands the
+    element by default. ``section=True`` wraps the entire heading section for
+    a heading match, and ``until`` wraps a range ending before the ``until``
+    selector. The wrapped blocks stay Markdown.
+    """
+
+    summary: str
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    open: bool = False
+    section: bool = False
+    until: Selector | None = None
+    until_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "WrapDetailsOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if self.until is not None and self.until_ref is not None:
+            raise ValueError(
+                "WrapDetailsOperation requires exactly one of 'until' or 'until_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class UnwrapDetailsOperation:
+    """Remove the ``<details>`` element opened by the matched HTML block.
+
+    The ``<summary>`` is dropped and the content the element wrapped is kept
+    as plain Markdown.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "UnwrapDetailsOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class SetCodeLanguageOperation:
+    """Set, change, or clear the language of the matched code block.
+
+    ``language`` replaces the first word of the fence's info string and keeps
+    the rest of it; indented code blocks become fenced ones. With
+    ``language=None`` the info string is removed. The code is left untouched.
+    """
+
+    language: str | None = None
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "SetCodeLanguageOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if self.language is not None and not self.language.strip():
+            raise ValueError("language must not be empty; use None to clear it")
+
+
+@dataclass(frozen=True, slots=True)
+class SetCodeAttributeOperation:
+    """Set or remove one attribute in the info string of the matched code block.
+
+    ``attribute`` is set to ``value``, or added as a bare flag when ``value``
+    is ``None``; ``remove=True`` deletes it instead. The language and the other
+    attributes are kept, whether they are comma-separated (``rust,no_run``),
+    space-separated (``js title="app.js"``), or in Pandoc braces.
+    """
+
+    attribute: str
+    value: str | None = None
+    remove: bool = False
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "SetCodeAttributeOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if not self.attribute.strip():
+            raise ValueError("attribute must not be empty")
+        if self.remove and self.value is not None:
+            raise ValueError("SetCodeAttributeOperation cannot combine 'value' with 'remove'")
+
+
+@dataclass(frozen=True, slots=True)
+class InsertTableRowOperation:
+    """Insert a body row into the matched table.
+
+    ``row`` is either a pipe-delimited Markdown row (``"| api | *ok* |"``) or a
+    sequence with the inline Markdown of each cell; missing trailing cells are
+    left empty. The row lands ``before`` or ``after`` the first body row whose
+    text (its cells separated by tabs) contains ``row_contains`` and matches
+    ``row_regex``. Without either, it becomes the first or the last body row.
+    """
+
+    row: str | tuple[str, ...]
+    position: InsertPosition = InsertPosition.AFTER
+    row_contains: str | None = None
+    row_regex: str | None = None
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "InsertTableRowOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if not isinstance(self.row, str):
+            object.__setattr__(self, "row", tuple(self.row))
+        if self.position not in (InsertPosition.BEFORE, InsertPosition.AFTER):
+            raise ValueError("InsertTableRowOperation only accepts the BEFORE and AFTER positions")
+
+
+@dataclass(frozen=True, slots=True)
+class DeleteTableRowOperation:
+    """Delete the first body row of the matched table whose text contains
+    ``row_contains`` and matches ``row_regex``. The header row is never matched.
+    """
+
+    row_contains: str | None = None
+    row_regex: str | None = None
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "DeleteTableRowOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if self.row_contains is None and self.row_regex is None:
+            raise ValueError("DeleteTableRowOperation requires 'row_contains' or 'row_regex'")
+
+
+@dataclass(frozen=True, slots=True)
+class AddTableColumnOperation:
+    """Add a column headed ``header`` to the matched table.
+
+    Every body row gets ``value`` (inline Markdown) in the new column, or an
+    empty cell. The column lands ``before`` or ``after`` the column headed
+    ``column``; without it, it becomes the first or the last column.
+    """
+
+    header: str
+    value: str | None = None
+    position: InsertPosition = InsertPosition.AFTER
+    column: str | None = None
+    alignment: ColumnAlignment = ColumnAlignment.NONE
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "AddTableColumnOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if self.position not in (InsertPosition.BEFORE, InsertPosition.AFTER):
+            raise ValueError("AddTableColumnOperation only accepts the BEFORE and AFTER positions")
+
+
+@dataclass(frozen=True, slots=True)
+class RemoveTableColumnOperation:
+    """Remove the column headed ``column`` from the matched table."""
+
+    column: str
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "RemoveTableColumnOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class RenameTableColumnOperation:
+    """Replace the header of the column headed ``column`` with ``header``."""
+
+    column: str
+    header: str
+    selector: Selector | None = None
+    selector_ref: str | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "RenameTableColumnOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class UpdateTocOperation:
+    """Generate or refresh the table of contents of the document.
+
+    The nested list of links to the headings between ``min_level`` and
+    ``max_level`` replaces whatever lies between the ``<!-- toc -->`` and
+    ``<!-- tocstop -->`` comments. Without such comments, ``selector`` (or
+    ``selector_ref``) must match the heading to place the table of contents
+    under.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    min_level: int | None = None
+    max_level: int | None = None
+    slug_style: SlugStyle = SlugStyle.GITHUB
+
+    def __post_init__(self) -> None:
+        if self.selector is not None and self.selector_ref is not None:
+            raise ValueError(
+                "UpdateTocOperation accepts at most one of 'selector' or 'selector_ref'."
+            )
+
+
+@dataclass(frozen=True, slots=True)
+class AssertOperation:
+    """Fail the batch unless a condition holds, without changing the document.
+
+    The condition is either that ``selector`` (or ``selector_ref``) matches a
+    node, or that the frontmatter ``frontmatter_key`` exists and, when
+    ``equals`` is given, has that value. ``expect=AssertExpectation.NO_MATCH``
+    inverts it. A failed assertion raises :class:`AssertionFailedError`.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    frontmatter_key: str | None = None
+    equals: Any | None = None
+    expect: AssertExpectation = AssertExpectation.MATCH
+
+    def __post_init__(self) -> None:
+        sources = (self.selector, self.selector_ref, self.frontmatter_key)
+        if sum(source is not None for source in sources) != 1:
+            raise ValueError(
+                "AssertOperation requires exactly one of 'selector', 'selector_ref', "
+                "or 'frontmatter_key'."
+            )
+        if self.equals is not None and self.frontmatter_key is None:
+            raise ValueError("AssertOperation only accepts 'equals' with 'frontmatter_key'.")
+
+
+@dataclass(frozen=True, slots=True)
+class GetOperation:
+    """Render Markdown matched by a selector without changing the document.
+
+    ``section`` and ``until`` behave as in :meth:`MarkdownDocument.get`. The
+    result is returned by :meth:`MarkdownDocument.apply_capturing`, and when
+    ``capture`` names a variable, later insert and replace operations in the
+    same batch can use it in their ``content`` as ``{{name}}``. ``cell``
+    renders a single table cell, as in :class:`ReplaceOperation`.
+    """
+
+    selector: Selector | None = None
+    selector_ref: str | None = None
+    section: bool = False
+    until: Selector | None = None
+    until_ref: str | None = None
+    capture: str | None = None
+    cell: tuple[int, int] | None = None
+
+    def __post_init__(self) -> None:
+        if (self.selector is None) == (self.selector_ref is None):
+            raise ValueError(
+                "GetOperation requires exactly one of 'selector' or 'selector_ref'."
+            )
+        if self.until is not None and self.until_ref is not None:
+            raise ValueError(
+                "GetOperation requires exactly one of 'until' or 'until_ref'."
+            )
+        if self.cell is not None:
+            _validate_cell(self.cell)
+
+
+@dataclass(frozen=True, slots=True)
+class ApplyWarning:
+    """Non-fatal condition reported by :meth:`MarkdownDocument.apply`.
+
+    ``kind`` is one of ``"ambiguous_selector"``, ``"empty_content"``,
+    ``"skipped_operation"``, ``"duplicate_heading"``, or ``"possible_conflict"``, and
+    ``operation_index`` is the 0-based position of the operation that produced
+    the warning.
+    """
+
+    kind: str
+    operation_index: int
+    message: str
+
+
+@dataclass(frozen=True, slots=True)
+class Capture:
+    """Markdown rendered by a :class:`GetOperation` during an apply.
+
+    ``operation_index`` is the 0-based position of the operation and ``name``
+    its ``capture`` variable, if any.
+    """
+
+    operation_index: int
+    name: str | None
+    content: str
+
+
+@dataclass(frozen=True, slots=True)
+class StructuralChange:
+    """A run of top-level blocks that differs, as returned by ``diff_structural``.
+
+    ``kind`` is one of ``"insert"``, ``"delete"``, or ``"replace"``. The ranges
+    are ``(start, end)`` block indices, end exclusive, into the original and
+    edited documents, and ``original``/``edited`` hold the rendered Markdown of
+    those blocks.
+    """
+
+    kind: str
+    original_range: tuple[int, int]
+    edited_range: tuple[int, int]
+    original: str
+    edited: str
+
+
+@dataclass(frozen=True, slots=True)
+class MergeConflict:
+    """A run of blocks that both sides of a ``merge`` changed differently.
+
+    The ranges are ``(start, end)`` block indices, end exclusive, into the base,
+    our, and their documents, and ``base``/``ours``/``theirs`` hold the rendered
+    Markdown of those blocks. The merged document keeps ``ours``.
+    """
+
+    base_range: tuple[int, int]
+    ours_range: tuple[int, int]
+    theirs_range: tuple[int, int]
+    base: str
+    ours: str
+    theirs: str
+
+
+@dataclass(frozen=True, slots=True)
+class MergeResult:
+    """The outcome of a three-way ``merge``."""
+
+    document: Any
+    conflicts: list[MergeConflict]
+
+
 Operation = Union[
     InsertOperation,
     ReplaceOperation,
+    ReplaceTextOperation,
+    UpsertOperation,
     DeleteOperation,
+    MoveOperation,
+    CopyOperation,
+    SwapOperation,
+    RenameHeadingOperation,
+    ShiftHeadingOperation,
+    MergeSectionsOperation,
     SetFrontmatterOperation,
     DeleteFrontmatterOperation,
     ReplaceFrontmatterOperation,
+    PruneEmptySectionsOperation,
+    DedupeHeadingsOperation,
+    RenumberOperation,
+    ImportSectionOperation,
+    SetAlertOperation,
+    RemoveAlertOperation,
+    SetCommentOperation,
+    RemoveCommentOperation,
+    ReplaceInnerHtmlOperation,
+    WrapOperation,
+    UnwrapOperation,
+    WrapDetailsOperation,
+    UnwrapDetailsOperation,
+    SetCodeLanguageOperation,
+    SetCodeAttributeOperation,
+    InsertTableRowOperation,
+    DeleteTableRowOperation,
+    AddTableColumnOperation,
+    RemoveTableColumnOperation,
+    RenameTableColumnOperation,
+    UpdateTocOperation,
+    AssertOperation,
+    GetOperation,
 ]
 
 
 __all__ = [
+    "AlertType",
+    "AssertExpectation",
+    "Between",
+    "ColumnAlignment",
+    "CommentPosition",
     "FrontmatterFormat",
     "InsertPosition",
     "Selector",
+    "SlugStyle",
+    "TaskState",
+    "TieBreak",
     "InsertOperation",
     "ReplaceOperation",
+    "ReplaceTextOperation",
+    "UpsertOperation",
     "DeleteOperation",
+    "MoveOperation",
+    "CopyOperation",
+    "SwapOperation",
+    "RenameHeadingOperation",
+    "ShiftHeadingOperation",
+    "MergeSectionsOperation",
     "SetFrontmatterOperation",
     "DeleteFrontmatterOperation",
     "ReplaceFrontmatterOperation",
+    "PruneEmptySectionsOperation",
+    "DedupeHeadingsOperation",
+    "RenumberOperation",
+    "ImportSectionOperation",
+    "SetAlertOperation",
+    "RemoveAlertOperation",
+    "SetCommentOperation",
+    "RemoveCommentOperation",
+    "ReplaceInnerHtmlOperation",
+    "WrapOperation",
+    "UnwrapOperation",
+    "WrapDetailsOperation",
+    "UnwrapDetailsOperation",
+    "SetCodeAttributeOperation",
+    "SetCodeLanguageOperation",
+    "InsertTableRowOperation",
+    "DeleteTableRowOperation",
+    "AddTableColumnOperation",
+    "RemoveTableColumnOperation",
+    "RenameTableColumnOperation",
+    "UpdateTocOperation",
+    "AssertOperation",
+    "GetOperation",
     "Operation",
+    "ApplyWarning",
+    "Capture",
+    "StructuralChange",
+    "MergeConflict",
+    "MergeResult",
 ]
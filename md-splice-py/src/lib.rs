@@ -1,26 +1,60 @@
 use std::{
     fs,
     io::{self, Write},
+    num::NonZeroUsize,
     path::{Path, PathBuf},
     str::FromStr,
+    thread,
 };
 
-use markdown_ppp::ast::{Block, Document, HeadingKind, SetextHeading};
-use markdown_ppp::printer::render_markdown;
+use markdown_ppp::ast::Block;
 use md_splice_lib::{
     default_printer_config,
     error::SpliceError,
     frontmatter::FrontmatterFormat,
-    locator::{locate, locate_all, FoundNode, Selector as LocatorSelector},
+    infer::{diff_hunks, merge_documents},
+    inlines::render_inline_node,
+    locator::{
+        item_list, Between as LocatorBetween, FoundNode, Selector as LocatorSelector, TaskStatus,
+        TieBreak,
+    },
+    render_fragment,
+    sections::section_range,
+    table::{render_cell, render_table_node, table_cell, CellAddress, TableRow},
     transaction::{
+        AddTableColumnOperation as TxAddTableColumnOperation, AlertType as TxAlertType,
+        AssertExpectation as TxAssertExpectation, AssertOperation as TxAssertOperation,
+        Between as TxBetween, ColumnAlignment as TxColumnAlignment,
+        CommentPosition as TxCommentPosition, CopyOperation as TxCopyOperation,
+        DedupeHeadingsOperation as TxDedupeHeadingsOperation,
         DeleteFrontmatterOperation as TxDeleteFrontmatterOperation,
-        DeleteOperation as TxDeleteOperation, InsertOperation as TxInsertOperation,
-        InsertPosition as TxInsertPosition, Operation as TxOperation,
+        DeleteOperation as TxDeleteOperation, DeleteTableRowOperation as TxDeleteTableRowOperation,
+        GetOperation as TxGetOperation, ImportSectionOperation as TxImportSectionOperation,
+        InsertOperation as TxInsertOperation, InsertPosition as TxInsertPosition,
+        InsertTableRowOperation as TxInsertTableRowOperation,
+        MergeSectionsOperation as TxMergeSectionsOperation, MoveOperation as TxMoveOperation,
+        Operation as TxOperation, PruneEmptySectionsOperation as TxPruneEmptySectionsOperation,
+        RemoveAlertOperation as TxRemoveAlertOperation,
+        RemoveCommentOperation as TxRemoveCommentOperation,
+        RemoveTableColumnOperation as TxRemoveTableColumnOperation,
+        RenameHeadingOperation as TxRenameHeadingOperation,
+        RenameTableColumnOperation as TxRenameTableColumnOperation,
+        RenumberOperation as TxRenumberOperation,
         ReplaceFrontmatterOperation as TxReplaceFrontmatterOperation,
-        ReplaceOperation as TxReplaceOperation, Selector as TxSelector,
+        ReplaceInnerHtmlOperation as TxReplaceInnerHtmlOperation,
+        ReplaceOperation as TxReplaceOperation, ReplaceTextOperation as TxReplaceTextOperation,
+        Selector as TxSelector, SetAlertOperation as TxSetAlertOperation,
+        SetCodeAttributeOperation as TxSetCodeAttributeOperation,
+        SetCodeLanguageOperation as TxSetCodeLanguageOperation,
+        SetCommentOperation as TxSetCommentOperation,
         SetFrontmatterOperation as TxSetFrontmatterOperation,
+        ShiftHeadingOperation as TxShiftHeadingOperation, SlugStyle as TxSlugStyle,
+        SwapOperation as TxSwapOperation, UnwrapDetailsOperation as TxUnwrapDetailsOperation,
+        UnwrapOperation as TxUnwrapOperation, UpdateTocOperation as TxUpdateTocOperation,
+        UpsertOperation as TxUpsertOperation, WrapDetailsOperation as TxWrapDetailsOperation,
+        WrapOperation as TxWrapOperation,
     },
-    ApplyOutcome, MarkdownDocument as CoreMarkdownDocument,
+    ApplyOutcome, MarkdownDocument as CoreMarkdownDocument, ParseOptions,
 };
 use pyo3::{
     conversion::IntoPyObjectExt,
@@ -31,7 +65,6 @@ use pyo3::{
     Bound,
 };
 use regex::{Regex, RegexBuilder};
-use serde_json;
 use serde_yaml::{Mapping as YamlMapping, Number as YamlNumber, Value as YamlValue};
 use similar::TextDiff;
 use tempfile::Builder as TempFileBuilder;
@@ -55,10 +88,22 @@ impl PyMarkdownDocument {
     ///
     /// Use this constructor when you already hold the Markdown source. The
     /// resulting document can be queried with selectors, mutated via
-    /// operations, and rendered back to Markdown with :meth:`render`.
+    /// operations, and rendered back to Markdown with :meth:`render`. When
+    /// ``lenient`` is ``True``, malformed constructs such as unclosed code
+    /// fences are preserved verbatim and reported as :class:`UserWarning`.
+    /// When ``definition_lists`` is ``True``, compact definition list entries
+    /// (``Term\n: Definition``) are split into one block per term and per
+    /// definition so that ``dt`` and ``dd`` selectors match them.
     #[classmethod]
-    pub fn from_string(_cls: &Bound<'_, PyType>, markdown: &str) -> PyResult<Self> {
-        let document = CoreMarkdownDocument::from_str(markdown).map_err(map_splice_error)?;
+    #[pyo3(signature = (markdown, *, lenient=false, definition_lists=false))]
+    pub fn from_string(
+        _cls: &Bound<'_, PyType>,
+        py: Python<'_>,
+        markdown: &str,
+        lenient: bool,
+        definition_lists: bool,
+    ) -> PyResult<Self> {
+        let document = load_document(py, markdown, lenient, definition_lists)?;
         Ok(Self {
             inner: document,
             source_path: None,
@@ -69,11 +114,19 @@ impl PyMarkdownDocument {
     ///
     /// Subsequent calls to :meth:`write_in_place` will persist changes back to
     /// this path using the atomic semantics required by the specification.
+    /// ``lenient`` and ``definition_lists`` behave as in :meth:`from_string`.
     #[classmethod]
-    pub fn from_file(_cls: &Bound<'_, PyType>, path: &Bound<'_, PyAny>) -> PyResult<Self> {
+    #[pyo3(signature = (path, *, lenient=false, definition_lists=false))]
+    pub fn from_file(
+        _cls: &Bound<'_, PyType>,
+        py: Python<'_>,
+        path: &Bound<'_, PyAny>,
+        lenient: bool,
+        definition_lists: bool,
+    ) -> PyResult<Self> {
         let path_buf: PathBuf = path.extract()?;
-        let content = fs::read_to_string(&path_buf).map_err(|err| map_io_error(err))?;
-        let document = CoreMarkdownDocument::from_str(&content).map_err(map_splice_error)?;
+        let content = fs::read_to_string(&path_buf).map_err(map_io_error)?;
+        let document = load_document(py, &content, lenient, definition_lists)?;
 
         Ok(Self {
             inner: document,
@@ -126,20 +179,46 @@ impl PyMarkdownDocument {
     /// unit or the document remains unchanged. When ``warn_on_ambiguity`` is
     /// ``True`` a :class:`UserWarning` is emitted if any selector matches more
     /// than one node, matching the behavior mandated in the specification.
+    ///
+    /// Returns the non-fatal warnings collected while applying the batch as a
+    /// list of :class:`md_splice.types.ApplyWarning` instances.
     #[pyo3(signature = (ops, *, warn_on_ambiguity=true))]
     pub fn apply(
         &mut self,
         py: Python<'_>,
         ops: &Bound<'_, PyAny>,
         warn_on_ambiguity: bool,
-    ) -> PyResult<()> {
+    ) -> PyResult<Py<PyAny>> {
         let operations = py_operations_to_rust(py, ops)?;
         let outcome = self
             .inner
             .apply_with_ambiguity(operations)
             .map_err(map_splice_error)?;
-        maybe_emit_ambiguity_warning(py, warn_on_ambiguity, outcome)?;
-        Ok(())
+        maybe_emit_ambiguity_warning(py, warn_on_ambiguity, &outcome)?;
+        apply_warnings_to_py(py, &outcome)
+    }
+
+    /// Apply operations like :meth:`apply`, also returning what ``GetOperation``
+    /// entries captured.
+    ///
+    /// Returns a ``(warnings, captures)`` tuple, where ``captures`` lists one
+    /// :class:`md_splice.types.Capture` per ``GetOperation`` in batch order.
+    #[pyo3(signature = (ops, *, warn_on_ambiguity=true))]
+    pub fn apply_capturing(
+        &mut self,
+        py: Python<'_>,
+        ops: &Bound<'_, PyAny>,
+        warn_on_ambiguity: bool,
+    ) -> PyResult<Py<PyAny>> {
+        let operations = py_operations_to_rust(py, ops)?;
+        let outcome = self
+            .inner
+            .apply_with_ambiguity(operations)
+            .map_err(map_splice_error)?;
+        maybe_emit_ambiguity_warning(py, warn_on_ambiguity, &outcome)?;
+        let warnings = apply_warnings_to_py(py, &outcome)?;
+        let captures = captures_to_py(py, &outcome)?;
+        Ok(PyTuple::new(py, [warnings, captures])?.into_any().unbind())
     }
 
     /// Preview a list of operations without mutating the original document.
@@ -158,7 +237,7 @@ impl PyMarkdownDocument {
         let outcome = clone
             .apply_with_ambiguity(operations)
             .map_err(map_splice_error)?;
-        maybe_emit_ambiguity_warning(py, warn_on_ambiguity, outcome)?;
+        maybe_emit_ambiguity_warning(py, warn_on_ambiguity, &outcome)?;
         Ok(clone.render())
     }
 
@@ -168,8 +247,10 @@ impl PyMarkdownDocument {
     /// ``section`` renders an entire heading section, while ``until`` defines a
     /// range ending before the provided selector. When ``select_all`` is
     /// ``True`` the return value is a list of rendered snippets for every
-    /// match, and ``until`` must be omitted.
-    #[pyo3(signature = (selector, *, select_all=false, section=false, until=None))]
+    /// match, and ``until`` must be omitted. A ``cell`` given as a
+    /// ``(row, column)`` tuple returns the inline content of that cell of the
+    /// matched table, where row 0 is the header row and columns start at 1.
+    #[pyo3(signature = (selector, *, select_all=false, section=false, until=None, cell=None))]
     pub fn get(
         &self,
         py: Python<'_>,
@@ -177,10 +258,31 @@ impl PyMarkdownDocument {
         select_all: bool,
         section: bool,
         until: Option<&Bound<'_, PyAny>>,
+        cell: Option<(usize, usize)>,
     ) -> PyResult<Py<PyAny>> {
         let locator_selector = py_selector_to_locator(py, selector)?;
         let blocks = self.inner.blocks();
 
+        if let Some((row, column)) = cell {
+            if select_all || section || until.is_some() {
+                return Err(PyValueError::new_err(
+                    "cell cannot be combined with select_all, section, or until",
+                ));
+            }
+            let (found_node, _) = self.inner.locate(&locator_selector).map_err(|err| {
+                map_splice_error(self.inner.explain_no_match(err, &locator_selector))
+            })?;
+            let FoundNode::Block { block, .. } = found_node else {
+                return Err(PyValueError::new_err(
+                    "cell requires the selector to match a table",
+                ));
+            };
+            let rendered = render_cell(
+                table_cell(block, CellAddress { row, column }).map_err(map_splice_error)?,
+            );
+            return Ok(PyString::new(py, &rendered).into_any().unbind());
+        }
+
         if select_all {
             if until.is_some() {
                 return Err(PyValueError::new_err(
@@ -188,7 +290,10 @@ impl PyMarkdownDocument {
                 ));
             }
 
-            let matches = locate_all(blocks, &locator_selector).map_err(map_splice_error)?;
+            let matches = self
+                .inner
+                .locate_all(&locator_selector)
+                .map_err(map_splice_error)?;
             let py_list = PyList::empty(py);
 
             for found in &matches {
@@ -203,17 +308,24 @@ impl PyMarkdownDocument {
             return Ok(py_list.into_any().unbind());
         }
 
-        let (found_node, _) = locate(blocks, &locator_selector).map_err(map_splice_error)?;
+        let (found_node, _) = self
+            .inner
+            .locate(&locator_selector)
+            .map_err(|err| map_splice_error(self.inner.explain_no_match(err, &locator_selector)))?;
 
         if let Some(until_selector) = until {
             let until_selector = py_selector_to_locator(py, until_selector)?;
             match &found_node {
                 FoundNode::Block { index, .. } => {
-                    let end_index = compute_range_end(blocks, *index, &until_selector)?;
-                    let rendered = render_blocks(&blocks[*index..end_index]);
+                    let end_index = self
+                        .inner
+                        .range_end(*index, &until_selector)
+                        .map_err(map_splice_error)?;
+                    let rendered =
+                        render_fragment(&blocks[*index..end_index], default_printer_config());
                     return Ok(PyString::new(py, &rendered).into_any().unbind());
                 }
-                FoundNode::ListItem { .. } => {
+                _ => {
                     return Err(map_splice_error(SpliceError::RangeRequiresBlock));
                 }
             }
@@ -228,6 +340,15 @@ impl PyMarkdownDocument {
         Ok(PyString::new(py, &rendered).into_any().unbind())
     }
 
+    /// Return the content fingerprint of each top-level block, in order.
+    ///
+    /// Fingerprints hash a block's type and whitespace-normalized text, so
+    /// they can be passed back as ``Selector(select_fingerprint=...)`` to find
+    /// the same block after edits change its position.
+    pub fn fingerprints(&self) -> Vec<String> {
+        self.inner.fingerprints()
+    }
+
     /// Return the frontmatter as native Python data or ``None``.
     ///
     /// The value mirrors the YAML/TOML content as described in the
@@ -258,12 +379,31 @@ impl PyMarkdownDocument {
     }
 
     /// Create a deep copy of the document, including pending mutations.
+    #[allow(clippy::should_implement_trait)]
     pub fn clone(&self) -> PyResult<Self> {
         Ok(Self {
             inner: self.inner.clone(),
             source_path: self.source_path.clone(),
         })
     }
+
+    /// Rewrite relative link and image paths for a move from ``from_dir`` to ``to_dir``.
+    ///
+    /// Returns the number of rewritten destinations.
+    pub fn rebase_links(
+        &mut self,
+        from_dir: &Bound<'_, PyAny>,
+        to_dir: &Bound<'_, PyAny>,
+    ) -> PyResult<usize> {
+        let from_dir: PathBuf = from_dir.extract()?;
+        let to_dir: PathBuf = to_dir.extract()?;
+        Ok(self.inner.rebase_links(&from_dir, &to_dir))
+    }
+
+    /// Compare with another document, ignoring formatting-only differences.
+    pub fn semantically_equals(&self, other: PyRef<'_, PyMarkdownDocument>) -> bool {
+        self.inner.semantically_equals(&other.inner)
+    }
 }
 
 #[pymodule]
@@ -272,6 +412,9 @@ fn _native(py: Python, module: &Bound<'_, PyModule>) -> PyResult<()> {
     module.add_class::<PyMarkdownDocument>()?;
     module.add("MdSpliceError", py.get_type::<MdSpliceError>())?;
     module.add_function(pyo3::wrap_pyfunction!(diff_unified, module)?)?;
+    module.add_function(pyo3::wrap_pyfunction!(diff_structural, module)?)?;
+    module.add_function(pyo3::wrap_pyfunction!(merge, module)?)?;
+    module.add_function(pyo3::wrap_pyfunction!(apply_to_files, module)?)?;
     module.add_function(pyo3::wrap_pyfunction!(loads_operations, module)?)?;
     module.add_function(pyo3::wrap_pyfunction!(dumps_operations, module)?)?;
     Ok(())
@@ -287,16 +430,21 @@ fn map_splice_error(err: SpliceError) -> PyErr {
 fn map_splice_error_inner(py: Python<'_>, err: &SpliceError) -> PyResult<PyErr> {
     let errors_module = py.import("md_splice.errors")?;
     let (class_name, message) = match err {
-        SpliceError::NodeNotFound => ("NodeNotFoundError", err.to_string()),
+        SpliceError::NodeNotFound | SpliceError::NodeNotFoundWithNearMisses(_) => {
+            ("NodeNotFoundError", err.to_string())
+        }
         SpliceError::InvalidChildInsertion(_) => ("InvalidChildInsertionError", err.to_string()),
         SpliceError::AmbiguousContentSource => ("AmbiguousContentSourceError", err.to_string()),
         SpliceError::NoContent => ("NoContentError", err.to_string()),
         SpliceError::InvalidListItemContent => ("InvalidListItemContentError", err.to_string()),
         SpliceError::AmbiguousStdinSource => ("AmbiguousStdinSourceError", err.to_string()),
         SpliceError::InvalidSectionDelete => ("InvalidSectionDeleteError", err.to_string()),
+        SpliceError::InvalidMoveDestination => ("InvalidMoveDestinationError", err.to_string()),
+        SpliceError::OverlappingSwap => ("OverlappingSwapError", err.to_string()),
         SpliceError::SectionRequiresHeading => ("SectionRequiresHeadingError", err.to_string()),
         SpliceError::ConflictingScopeModifiers => ("ConflictingScopeError", err.to_string()),
         SpliceError::RangeRequiresBlock => ("RangeRequiresBlockError", err.to_string()),
+        SpliceError::TooManyMatches { .. } => ("TooManyMatchesError", err.to_string()),
         SpliceError::SelectorAliasNotDefined(_) => {
             ("SelectorAliasNotDefinedError", err.to_string())
         }
@@ -316,6 +464,11 @@ fn map_splice_error_inner(py: Python<'_>, err: &SpliceError) -> PyResult<PyErr>
         SpliceError::MarkdownParse(_) => ("MarkdownParseError", err.to_string()),
         SpliceError::OperationParse(_) => ("OperationParseError", err.to_string()),
         SpliceError::OperationFailed(_) => ("OperationFailedError", err.to_string()),
+        SpliceError::ConflictingOperations(_) => ("ConflictingOperationsError", err.to_string()),
+        SpliceError::LimitExceeded(_) => ("LimitExceededError", err.to_string()),
+        SpliceError::TimeLimitExceeded(_) => ("TimeLimitExceededError", err.to_string()),
+        SpliceError::OperationNotAllowed(_) => ("OperationNotAllowedError", err.to_string()),
+        SpliceError::AssertionFailed(_) => ("AssertionFailedError", err.to_string()),
         SpliceError::Io(_) => ("IoError", err.to_string()),
     };
 
@@ -323,10 +476,70 @@ fn map_splice_error_inner(py: Python<'_>, err: &SpliceError) -> PyResult<PyErr>
     Ok(PyErr::from_type(error_type, (message,)))
 }
 
+fn load_document(
+    py: Python<'_>,
+    markdown: &str,
+    lenient: bool,
+    definition_lists: bool,
+) -> PyResult<CoreMarkdownDocument> {
+    let options = ParseOptions {
+        lenient,
+        definition_lists,
+        ..ParseOptions::default()
+    };
+    let (document, parse_warnings) =
+        CoreMarkdownDocument::from_str_with_options(markdown, &options)
+            .map_err(map_splice_error)?;
+    if !parse_warnings.is_empty() {
+        let warnings = py.import("warnings")?;
+        let builtins = py.import("builtins")?;
+        let warning_type = builtins.getattr("UserWarning")?;
+        for warning in parse_warnings {
+            warnings.call_method1("warn", (warning.to_string(), &warning_type))?;
+        }
+    }
+
+    Ok(document)
+}
+
+fn apply_warnings_to_py(py: Python<'_>, outcome: &ApplyOutcome) -> PyResult<Py<PyAny>> {
+    let types_module = py.import("md_splice.types")?;
+    let warning_class = types_module.getattr("ApplyWarning")?;
+    let list = PyList::empty(py);
+
+    for warning in &outcome.warnings {
+        let instance = warning_class.call1((
+            warning.kind.as_str(),
+            warning.operation_index,
+            warning.message.as_str(),
+        ))?;
+        list.append(instance)?;
+    }
+
+    Ok(list.into_any().unbind())
+}
+
+fn captures_to_py(py: Python<'_>, outcome: &ApplyOutcome) -> PyResult<Py<PyAny>> {
+    let types_module = py.import("md_splice.types")?;
+    let capture_class = types_module.getattr("Capture")?;
+    let list = PyList::empty(py);
+
+    for capture in &outcome.captures {
+        let instance = capture_class.call1((
+            capture.operation_index,
+            capture.name.as_deref(),
+            capture.content.as_str(),
+        ))?;
+        list.append(instance)?;
+    }
+
+    Ok(list.into_any().unbind())
+}
+
 fn maybe_emit_ambiguity_warning(
     py: Python<'_>,
     warn_on_ambiguity: bool,
-    outcome: ApplyOutcome,
+    outcome: &ApplyOutcome,
 ) -> PyResult<()> {
     if warn_on_ambiguity && outcome.ambiguity_detected {
         let warnings = py.import("warnings")?;
@@ -375,6 +588,7 @@ fn py_operation_to_rust(py: Python<'_>, operation: &Bound<'_, PyAny>) -> PyResul
             let content = operation.getattr("content")?.extract::<Option<String>>()?;
             let position_obj = operation.getattr("position")?;
             let position = py_insert_position_to_rust(&position_obj)?;
+            let renumber = operation.getattr("renumber")?.extract::<bool>()?;
             Ok(TxOperation::Insert(TxInsertOperation {
                 selector,
                 selector_ref,
@@ -382,6 +596,7 @@ fn py_operation_to_rust(py: Python<'_>, operation: &Bound<'_, PyAny>) -> PyResul
                 content,
                 content_file: None,
                 position,
+                renumber,
             }))
         }
         "ReplaceOperation" => {
@@ -404,6 +619,8 @@ fn py_operation_to_rust(py: Python<'_>, operation: &Bound<'_, PyAny>) -> PyResul
             let until_ref = operation
                 .getattr("until_ref")?
                 .extract::<Option<String>>()?;
+            let update_links = operation.getattr("update_links")?.extract::<bool>()?;
+            let cell = py_cell_address(operation)?;
             Ok(TxOperation::Replace(TxReplaceOperation {
                 selector,
                 selector_ref,
@@ -412,6 +629,76 @@ fn py_operation_to_rust(py: Python<'_>, operation: &Bound<'_, PyAny>) -> PyResul
                 content_file: None,
                 until,
                 until_ref,
+                update_links,
+                cell,
+            }))
+        }
+        "ReplaceTextOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let pattern = operation.getattr("pattern")?.extract::<String>()?;
+            let replacement = operation.getattr("replacement")?.extract::<String>()?;
+            let section = operation.getattr("section")?.extract::<bool>()?;
+            let until_obj = operation.getattr("until")?;
+            let until = if until_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &until_obj)?)
+            };
+            let until_ref = operation
+                .getattr("until_ref")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::ReplaceText(TxReplaceTextOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                pattern,
+                replacement,
+                section,
+                until,
+                until_ref,
+            }))
+        }
+        "UpsertOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let content = operation.getattr("content")?.extract::<Option<String>>()?;
+            let section = operation.getattr("section")?.extract::<bool>()?;
+            let fallback_obj = operation.getattr("fallback")?;
+            let fallback = if fallback_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &fallback_obj)?)
+            };
+            let fallback_ref = operation
+                .getattr("fallback_ref")?
+                .extract::<Option<String>>()?;
+            let position_obj = operation.getattr("position")?;
+            let position = py_insert_position_to_rust(&position_obj)?;
+            Ok(TxOperation::Upsert(TxUpsertOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                content,
+                content_file: None,
+                section,
+                fallback,
+                fallback_ref,
+                position,
             }))
         }
         "DeleteOperation" => {
@@ -434,6 +721,7 @@ fn py_operation_to_rust(py: Python<'_>, operation: &Bound<'_, PyAny>) -> PyResul
             let until_ref = operation
                 .getattr("until_ref")?
                 .extract::<Option<String>>()?;
+            let renumber = operation.getattr("renumber")?.extract::<bool>()?;
             Ok(TxOperation::Delete(TxDeleteOperation {
                 selector,
                 selector_ref,
@@ -441,702 +729,3715 @@ fn py_operation_to_rust(py: Python<'_>, operation: &Bound<'_, PyAny>) -> PyResul
                 section,
                 until,
                 until_ref,
+                renumber,
             }))
         }
-        "SetFrontmatterOperation" => {
-            let key: String = operation.getattr("key")?.extract()?;
-            let value_obj = operation.getattr("value")?;
-            let value = Some(py_to_yaml_value(py, &value_obj)?);
-            let format_obj = operation.getattr("format")?;
-            let format = if format_obj.is_none() {
+        "MoveOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
                 None
             } else {
-                Some(py_frontmatter_format_to_rust(&format_obj)?)
+                Some(py_selector_to_transaction(py, &selector_obj)?)
             };
-            Ok(TxOperation::SetFrontmatter(TxSetFrontmatterOperation {
-                key,
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let section = operation.getattr("section")?.extract::<bool>()?;
+            let destination_obj = operation.getattr("destination")?;
+            let destination = if destination_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &destination_obj)?)
+            };
+            let destination_ref = operation
+                .getattr("destination_ref")?
+                .extract::<Option<String>>()?;
+            let position_obj = operation.getattr("position")?;
+            let position = py_insert_position_to_rust(&position_obj)?;
+            Ok(TxOperation::Move(TxMoveOperation {
+                selector,
+                selector_ref,
                 comment: None,
-                value,
-                value_file: None,
-                format,
+                section,
+                destination,
+                destination_ref,
+                position,
             }))
         }
-        "DeleteFrontmatterOperation" => {
-            let key: String = operation.getattr("key")?.extract()?;
-            Ok(TxOperation::DeleteFrontmatter(
-                TxDeleteFrontmatterOperation { key, comment: None },
-            ))
+        "CopyOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let section = operation.getattr("section")?.extract::<bool>()?;
+            let until_obj = operation.getattr("until")?;
+            let until = if until_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &until_obj)?)
+            };
+            let until_ref = operation
+                .getattr("until_ref")?
+                .extract::<Option<String>>()?;
+            let destination_obj = operation.getattr("destination")?;
+            let destination = if destination_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &destination_obj)?)
+            };
+            let destination_ref = operation
+                .getattr("destination_ref")?
+                .extract::<Option<String>>()?;
+            let position_obj = operation.getattr("position")?;
+            let position = py_insert_position_to_rust(&position_obj)?;
+            Ok(TxOperation::Copy(TxCopyOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                section,
+                until,
+                until_ref,
+                destination,
+                destination_ref,
+                position,
+            }))
         }
-        "ReplaceFrontmatterOperation" => {
-            let content_obj = operation.getattr("content")?;
-            let content = Some(py_to_yaml_value(py, &content_obj)?);
-            let format_obj = operation.getattr("format")?;
-            let format = if format_obj.is_none() {
+        "SwapOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
                 None
             } else {
-                Some(py_frontmatter_format_to_rust(&format_obj)?)
+                Some(py_selector_to_transaction(py, &selector_obj)?)
             };
-            Ok(TxOperation::ReplaceFrontmatter(
-                TxReplaceFrontmatterOperation {
-                    comment: None,
-                    content,
-                    content_file: None,
-                    format,
-                },
-            ))
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let section = operation.getattr("section")?.extract::<bool>()?;
+            let other_obj = operation.getattr("other")?;
+            let other = if other_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &other_obj)?)
+            };
+            let other_ref = operation
+                .getattr("other_ref")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::Swap(TxSwapOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                section,
+                other,
+                other_ref,
+            }))
         }
-        other => Err(PyValueError::new_err(format!(
-            "Unsupported operation type: {other}"
-        ))),
-    }
-}
-
-fn py_selector_to_transaction(py: Python<'_>, selector: &Bound<'_, PyAny>) -> PyResult<TxSelector> {
-    let alias = selector.getattr("alias")?.extract::<Option<String>>()?;
-    let select_type = selector
-        .getattr("select_type")?
-        .extract::<Option<String>>()?;
-    let select_contains = selector
-        .getattr("select_contains")?
-        .extract::<Option<String>>()?;
-    let select_regex_obj = selector.getattr("select_regex")?;
-    let select_regex = if select_regex_obj.is_none() {
-        None
-    } else {
-        Some(extract_regex_pattern(&select_regex_obj)?)
-    };
-    let select_ordinal = selector.getattr("select_ordinal")?.extract::<usize>()?;
-    let after_obj = selector.getattr("after")?;
-    let after = if after_obj.is_none() {
-        None
-    } else {
-        Some(Box::new(py_selector_to_transaction(py, &after_obj)?))
-    };
-    let after_ref = selector.getattr("after_ref")?.extract::<Option<String>>()?;
-    let within_obj = selector.getattr("within")?;
-    let within = if within_obj.is_none() {
-        None
-    } else {
-        Some(Box::new(py_selector_to_transaction(py, &within_obj)?))
-    };
-    let within_ref = selector
-        .getattr("within_ref")?
-        .extract::<Option<String>>()?;
-
-    Ok(TxSelector {
-        alias,
+        "RenameHeadingOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let text = operation.getattr("text")?.extract::<String>()?;
+            let id = operation.getattr("id")?.extract::<Option<String>>()?;
+            let update_links = operation.getattr("update_links")?.extract::<bool>()?;
+            Ok(TxOperation::RenameHeading(TxRenameHeadingOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                text,
+                id,
+                update_links,
+            }))
+        }
+        "ShiftHeadingOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let delta = operation.getattr("delta")?.extract::<i8>()?;
+            let include_section = operation.getattr("include_section")?.extract::<bool>()?;
+            Ok(TxOperation::ShiftHeading(TxShiftHeadingOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                delta,
+                include_section,
+            }))
+        }
+        "MergeSectionsOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let destination_obj = operation.getattr("destination")?;
+            let destination = if destination_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &destination_obj)?)
+            };
+            let destination_ref = operation
+                .getattr("destination_ref")?
+                .extract::<Option<String>>()?;
+            let adjust_levels = operation.getattr("adjust_levels")?.extract::<bool>()?;
+            Ok(TxOperation::MergeSections(TxMergeSectionsOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                destination,
+                destination_ref,
+                adjust_levels,
+            }))
+        }
+        "GetOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let section = operation.getattr("section")?.extract::<bool>()?;
+            let until_obj = operation.getattr("until")?;
+            let until = if until_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &until_obj)?)
+            };
+            let until_ref = operation
+                .getattr("until_ref")?
+                .extract::<Option<String>>()?;
+            let capture = operation.getattr("capture")?.extract::<Option<String>>()?;
+            let cell = py_cell_address(operation)?;
+            Ok(TxOperation::Get(TxGetOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                section,
+                until,
+                until_ref,
+                capture,
+                cell,
+            }))
+        }
+        "SetFrontmatterOperation" => {
+            let key: String = operation.getattr("key")?.extract()?;
+            let value_obj = operation.getattr("value")?;
+            let value = Some(py_to_yaml_value(py, &value_obj)?);
+            let format_obj = operation.getattr("format")?;
+            let format = if format_obj.is_none() {
+                None
+            } else {
+                Some(py_frontmatter_format_to_rust(&format_obj)?)
+            };
+            Ok(TxOperation::SetFrontmatter(TxSetFrontmatterOperation {
+                key,
+                comment: None,
+                value,
+                value_file: None,
+                format,
+            }))
+        }
+        "DeleteFrontmatterOperation" => {
+            let key: String = operation.getattr("key")?.extract()?;
+            Ok(TxOperation::DeleteFrontmatter(
+                TxDeleteFrontmatterOperation { key, comment: None },
+            ))
+        }
+        "ReplaceFrontmatterOperation" => {
+            let content_obj = operation.getattr("content")?;
+            let content = Some(py_to_yaml_value(py, &content_obj)?);
+            let format_obj = operation.getattr("format")?;
+            let format = if format_obj.is_none() {
+                None
+            } else {
+                Some(py_frontmatter_format_to_rust(&format_obj)?)
+            };
+            Ok(TxOperation::ReplaceFrontmatter(
+                TxReplaceFrontmatterOperation {
+                    comment: None,
+                    content,
+                    content_file: None,
+                    format,
+                },
+            ))
+        }
+        "PruneEmptySectionsOperation" => {
+            let min_level = operation.getattr("min_level")?.extract::<Option<u8>>()?;
+            let max_level = operation.getattr("max_level")?.extract::<Option<u8>>()?;
+            let within_obj = operation.getattr("within")?;
+            let within = if within_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &within_obj)?)
+            };
+            let within_ref = operation
+                .getattr("within_ref")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::PruneEmptySections(
+                TxPruneEmptySectionsOperation {
+                    comment: None,
+                    min_level,
+                    max_level,
+                    within,
+                    within_ref,
+                },
+            ))
+        }
+        "DedupeHeadingsOperation" => {
+            let within_obj = operation.getattr("within")?;
+            let within = if within_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &within_obj)?)
+            };
+            let within_ref = operation
+                .getattr("within_ref")?
+                .extract::<Option<String>>()?;
+            let report_only = operation.getattr("report_only")?.extract::<bool>()?;
+            Ok(TxOperation::DedupeHeadings(TxDedupeHeadingsOperation {
+                comment: None,
+                within,
+                within_ref,
+                report_only,
+            }))
+        }
+        "RenumberOperation" => {
+            let within_obj = operation.getattr("within")?;
+            let within = if within_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &within_obj)?)
+            };
+            let within_ref = operation
+                .getattr("within_ref")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::Renumber(TxRenumberOperation {
+                comment: None,
+                within,
+                within_ref,
+            }))
+        }
+        "ImportSectionOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let source_file = operation.getattr("source_file")?.extract::<PathBuf>()?;
+            let source_selector_obj = operation.getattr("source_selector")?;
+            let source_selector = py_selector_to_transaction(py, &source_selector_obj)?;
+            let position_obj = operation.getattr("position")?;
+            let position = if position_obj.is_none() {
+                None
+            } else {
+                Some(py_insert_position_to_rust(&position_obj)?)
+            };
+            Ok(TxOperation::ImportSection(TxImportSectionOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                source_file,
+                source_selector,
+                position,
+            }))
+        }
+        "SetAlertOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let alert_type = py_alert_type_to_rust(&operation.getattr("alert_type")?)?;
+            Ok(TxOperation::SetAlert(TxSetAlertOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                alert_type,
+            }))
+        }
+        "RemoveAlertOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::RemoveAlert(TxRemoveAlertOperation {
+                selector,
+                selector_ref,
+                comment: None,
+            }))
+        }
+        "SetCommentOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let text = operation.getattr("text")?.extract::<String>()?;
+            let position = py_comment_position_to_rust(&operation.getattr("position")?)?;
+            let marker = operation.getattr("marker")?.extract::<Option<String>>()?;
+            Ok(TxOperation::SetComment(TxSetCommentOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                text,
+                position,
+                marker,
+            }))
+        }
+        "RemoveCommentOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let position = py_comment_position_to_rust(&operation.getattr("position")?)?;
+            let marker = operation.getattr("marker")?.extract::<Option<String>>()?;
+            Ok(TxOperation::RemoveComment(TxRemoveCommentOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                position,
+                marker,
+            }))
+        }
+        "ReplaceInnerHtmlOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let content = operation.getattr("content")?.extract::<String>()?;
+            Ok(TxOperation::ReplaceInnerHtml(TxReplaceInnerHtmlOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                content: Some(content),
+                content_file: None,
+            }))
+        }
+        "WrapOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let alert_type_obj = operation.getattr("alert_type")?;
+            let alert_type = if alert_type_obj.is_none() {
+                None
+            } else {
+                Some(py_alert_type_to_rust(&alert_type_obj)?)
+            };
+            let section = operation.getattr("section")?.extract::<bool>()?;
+            let until_obj = operation.getattr("until")?;
+            let until = if until_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &until_obj)?)
+            };
+            let until_ref = operation
+                .getattr("until_ref")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::Wrap(TxWrapOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                alert_type,
+                section,
+                until,
+                until_ref,
+            }))
+        }
+        "UnwrapOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::Unwrap(TxUnwrapOperation {
+                selector,
+                selector_ref,
+                comment: None,
+            }))
+        }
+        "WrapDetailsOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let summary = operation.getattr("summary")?.extract::<String>()?;
+            let open = operation.getattr("open")?.extract::<bool>()?;
+            let section = operation.getattr("section")?.extract::<bool>()?;
+            let until_obj = operation.getattr("until")?;
+            let until = if until_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &until_obj)?)
+            };
+            let until_ref = operation
+                .getattr("until_ref")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::WrapDetails(TxWrapDetailsOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                summary,
+                open,
+                section,
+                until,
+                until_ref,
+            }))
+        }
+        "UnwrapDetailsOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::UnwrapDetails(TxUnwrapDetailsOperation {
+                selector,
+                selector_ref,
+                comment: None,
+            }))
+        }
+        "SetCodeLanguageOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let language = operation.getattr("language")?.extract::<Option<String>>()?;
+            Ok(TxOperation::SetCodeLanguage(TxSetCodeLanguageOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                language,
+            }))
+        }
+        "SetCodeAttributeOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let attribute = operation.getattr("attribute")?.extract::<String>()?;
+            let value = operation.getattr("value")?.extract::<Option<String>>()?;
+            let remove = operation.getattr("remove")?.extract::<bool>()?;
+            Ok(TxOperation::SetCodeAttribute(TxSetCodeAttributeOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                attribute,
+                value,
+                remove,
+            }))
+        }
+        "InsertTableRowOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let row_obj = operation.getattr("row")?;
+            let row = match row_obj.extract::<String>() {
+                Ok(markdown) => TableRow::Markdown(markdown),
+                Err(_) => TableRow::Cells(row_obj.extract::<Vec<String>>()?),
+            };
+            let position_obj = operation.getattr("position")?;
+            let position = py_insert_position_to_rust(&position_obj)?;
+            let row_contains = operation
+                .getattr("row_contains")?
+                .extract::<Option<String>>()?;
+            let row_regex = operation
+                .getattr("row_regex")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::InsertTableRow(TxInsertTableRowOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                row,
+                position,
+                row_contains,
+                row_regex,
+            }))
+        }
+        "DeleteTableRowOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let row_contains = operation
+                .getattr("row_contains")?
+                .extract::<Option<String>>()?;
+            let row_regex = operation
+                .getattr("row_regex")?
+                .extract::<Option<String>>()?;
+            Ok(TxOperation::DeleteTableRow(TxDeleteTableRowOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                row_contains,
+                row_regex,
+            }))
+        }
+        "AddTableColumnOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let header = operation.getattr("header")?.extract::<String>()?;
+            let value = operation.getattr("value")?.extract::<Option<String>>()?;
+            let position_obj = operation.getattr("position")?;
+            let position = py_insert_position_to_rust(&position_obj)?;
+            let column = operation.getattr("column")?.extract::<Option<String>>()?;
+            let alignment = py_column_alignment_to_rust(&operation.getattr("alignment")?)?;
+            Ok(TxOperation::AddTableColumn(TxAddTableColumnOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                header,
+                value,
+                position,
+                column,
+                alignment,
+            }))
+        }
+        "RemoveTableColumnOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let column = operation.getattr("column")?.extract::<String>()?;
+            Ok(TxOperation::RemoveTableColumn(
+                TxRemoveTableColumnOperation {
+                    selector,
+                    selector_ref,
+                    comment: None,
+                    column,
+                },
+            ))
+        }
+        "RenameTableColumnOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let column = operation.getattr("column")?.extract::<String>()?;
+            let header = operation.getattr("header")?.extract::<String>()?;
+            Ok(TxOperation::RenameTableColumn(
+                TxRenameTableColumnOperation {
+                    selector,
+                    selector_ref,
+                    comment: None,
+                    column,
+                    header,
+                },
+            ))
+        }
+        "UpdateTocOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let min_level = operation.getattr("min_level")?.extract::<Option<u8>>()?;
+            let max_level = operation.getattr("max_level")?.extract::<Option<u8>>()?;
+            let slug_style = py_slug_style_to_rust(&operation.getattr("slug_style")?)?;
+            Ok(TxOperation::UpdateToc(TxUpdateTocOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                min_level,
+                max_level,
+                slug_style,
+            }))
+        }
+        "AssertOperation" => {
+            let selector_obj = operation.getattr("selector")?;
+            let selector = if selector_obj.is_none() {
+                None
+            } else {
+                Some(py_selector_to_transaction(py, &selector_obj)?)
+            };
+            let selector_ref = operation
+                .getattr("selector_ref")?
+                .extract::<Option<String>>()?;
+            let frontmatter_key = operation
+                .getattr("frontmatter_key")?
+                .extract::<Option<String>>()?;
+            let equals_obj = operation.getattr("equals")?;
+            let equals = if equals_obj.is_none() {
+                None
+            } else {
+                Some(py_to_yaml_value(py, &equals_obj)?)
+            };
+            let expect = py_assert_expectation_to_rust(&operation.getattr("expect")?)?;
+            Ok(TxOperation::Assert(TxAssertOperation {
+                selector,
+                selector_ref,
+                comment: None,
+                frontmatter_key,
+                equals,
+                expect,
+            }))
+        }
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported operation type: {other}"
+        ))),
+    }
+}
+
+/// Reads the optional `cell` attribute, a `(row, column)` tuple, of an operation.
+fn py_cell_address(operation: &Bound<'_, PyAny>) -> PyResult<Option<CellAddress>> {
+    Ok(operation
+        .getattr("cell")?
+        .extract::<Option<(usize, usize)>>()?
+        .map(|(row, column)| CellAddress { row, column }))
+}
+
+#[allow(clippy::only_used_in_recursion)]
+fn py_selector_to_transaction(py: Python<'_>, selector: &Bound<'_, PyAny>) -> PyResult<TxSelector> {
+    let alias = selector.getattr("alias")?.extract::<Option<String>>()?;
+    let select_type = selector
+        .getattr("select_type")?
+        .extract::<Option<String>>()?;
+    let select_contains = selector
+        .getattr("select_contains")?
+        .extract::<Option<String>>()?;
+    let select_contains_ci = selector
+        .getattr("select_contains_ci")?
+        .extract::<Option<String>>()?;
+    let select_regex_obj = selector.getattr("select_regex")?;
+    let select_regex = if select_regex_obj.is_none() {
+        None
+    } else {
+        Some(extract_regex_pattern(&select_regex_obj)?)
+    };
+    let select_not_contains = selector
+        .getattr("select_not_contains")?
+        .extract::<Option<String>>()?;
+    let select_not_regex_obj = selector.getattr("select_not_regex")?;
+    let select_not_regex = if select_not_regex_obj.is_none() {
+        None
+    } else {
+        Some(extract_regex_pattern(&select_not_regex_obj)?)
+    };
+    let select_fingerprint = selector
+        .getattr("select_fingerprint")?
+        .extract::<Option<String>>()?;
+    let select_annotation = selector
+        .getattr("select_annotation")?
+        .extract::<Option<String>>()?;
+    let select_tag = selector
+        .getattr("select_tag")?
+        .extract::<Option<String>>()?;
+    let select_info_contains = selector
+        .getattr("select_info_contains")?
+        .extract::<Option<String>>()?;
+    let select_slug = selector
+        .getattr("select_slug")?
+        .extract::<Option<String>>()?;
+    let select_min_words = selector
+        .getattr("select_min_words")?
+        .extract::<Option<usize>>()?;
+    let select_max_words = selector
+        .getattr("select_max_words")?
+        .extract::<Option<usize>>()?;
+    let select_empty = selector
+        .getattr("select_empty")?
+        .extract::<Option<bool>>()?;
+    let select_depth = selector
+        .getattr("select_depth")?
+        .extract::<Option<usize>>()?;
+    let recursive = selector.getattr("recursive")?.extract::<bool>()?;
+    let task_state_obj = selector.getattr("task_state")?;
+    let task_state = if task_state_obj.is_none() {
+        None
+    } else {
+        Some(py_task_state_to_rust(&task_state_obj)?)
+    };
+    let select_ordinal = selector.getattr("select_ordinal")?.extract::<usize>()?;
+    let select_last = selector.getattr("select_last")?.extract::<bool>()?;
+    let max_matches = selector
+        .getattr("max_matches")?
+        .extract::<Option<usize>>()?;
+    let tie_break = py_tie_break_to_rust(&selector.getattr("tie_break")?)?;
+    let after_obj = selector.getattr("after")?;
+    let after = if after_obj.is_none() {
+        None
+    } else {
+        Some(Box::new(py_selector_to_transaction(py, &after_obj)?))
+    };
+    let after_ref = selector.getattr("after_ref")?.extract::<Option<String>>()?;
+    let within_obj = selector.getattr("within")?;
+    let within = if within_obj.is_none() {
+        None
+    } else {
+        Some(Box::new(py_selector_to_transaction(py, &within_obj)?))
+    };
+    let within_ref = selector
+        .getattr("within_ref")?
+        .extract::<Option<String>>()?;
+    let between_obj = selector.getattr("between")?;
+    let between = if between_obj.is_none() {
+        None
+    } else {
+        let landmark = |name: &str| -> PyResult<Option<Box<TxSelector>>> {
+            let landmark_obj = between_obj.getattr(name)?;
+            if landmark_obj.is_none() {
+                Ok(None)
+            } else {
+                Ok(Some(Box::new(py_selector_to_transaction(
+                    py,
+                    &landmark_obj,
+                )?)))
+            }
+        };
+        Some(Box::new(TxBetween {
+            start: landmark("start")?,
+            start_ref: between_obj
+                .getattr("start_ref")?
+                .extract::<Option<String>>()?,
+            end: landmark("end")?,
+            end_ref: between_obj
+                .getattr("end_ref")?
+                .extract::<Option<String>>()?,
+        }))
+    };
+    let within_slide = selector
+        .getattr("within_slide")?
+        .extract::<Option<usize>>()?;
+    let at_line = selector.getattr("at_line")?.extract::<Option<usize>>()?;
+
+    Ok(TxSelector {
+        alias,
+        select_type,
+        select_contains,
+        select_contains_ci,
+        select_regex,
+        select_not_contains,
+        select_not_regex,
+        select_fingerprint,
+        select_annotation,
+        select_tag,
+        select_info_contains,
+        select_slug,
+        select_min_words,
+        select_max_words,
+        select_empty,
+        select_depth,
+        recursive,
+        task_state,
+        select_ordinal,
+        select_last,
+        max_matches,
+        tie_break,
+        after,
+        after_ref,
+        within,
+        within_ref,
+        between,
+        within_slide,
+        at_line,
+    })
+}
+
+fn py_insert_position_to_rust(position: &Bound<'_, PyAny>) -> PyResult<TxInsertPosition> {
+    let value: String = if let Ok(val) = position.getattr("value") {
+        val.extract()?
+    } else {
+        position.extract()?
+    };
+
+    let normalized = value.replace('-', "_");
+
+    match normalized.as_str() {
+        "before" => Ok(TxInsertPosition::Before),
+        "after" => Ok(TxInsertPosition::After),
+        "prepend_child" => Ok(TxInsertPosition::PrependChild),
+        "append_child" => Ok(TxInsertPosition::AppendChild),
+        _ => Err(PyValueError::new_err(format!(
+            "Unsupported insert position: {value}"
+        ))),
+    }
+}
+
+fn py_frontmatter_format_to_rust(format_obj: &Bound<'_, PyAny>) -> PyResult<FrontmatterFormat> {
+    let value: String = if let Ok(val) = format_obj.getattr("name") {
+        val.extract()?
+    } else if let Ok(val) = format_obj.getattr("value") {
+        val.extract::<String>()?
+    } else {
+        format_obj.extract()?
+    };
+
+    match value.as_str() {
+        "YAML" | "yaml" => Ok(FrontmatterFormat::Yaml),
+        "TOML" | "toml" => Ok(FrontmatterFormat::Toml),
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported frontmatter format: {other}"
+        ))),
+    }
+}
+
+fn py_column_alignment_to_rust(alignment: &Bound<'_, PyAny>) -> PyResult<TxColumnAlignment> {
+    let value: String = if let Ok(val) = alignment.getattr("value") {
+        val.extract()?
+    } else {
+        alignment.extract()?
+    };
+
+    match value.to_ascii_lowercase().as_str() {
+        "none" => Ok(TxColumnAlignment::None),
+        "left" => Ok(TxColumnAlignment::Left),
+        "center" => Ok(TxColumnAlignment::Center),
+        "right" => Ok(TxColumnAlignment::Right),
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported column alignment: {other}"
+        ))),
+    }
+}
+
+fn py_alert_type_to_rust(alert_type: &Bound<'_, PyAny>) -> PyResult<TxAlertType> {
+    let value: String = if let Ok(val) = alert_type.getattr("value") {
+        val.extract()?
+    } else {
+        alert_type.extract()?
+    };
+
+    match value.to_ascii_lowercase().as_str() {
+        "note" => Ok(TxAlertType::Note),
+        "tip" => Ok(TxAlertType::Tip),
+        "important" => Ok(TxAlertType::Important),
+        "warning" => Ok(TxAlertType::Warning),
+        "caution" => Ok(TxAlertType::Caution),
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported alert type: {other}"
+        ))),
+    }
+}
+
+fn py_comment_position_to_rust(position: &Bound<'_, PyAny>) -> PyResult<TxCommentPosition> {
+    let value: String = if let Ok(val) = position.getattr("value") {
+        val.extract()?
+    } else {
+        position.extract()?
+    };
+
+    match value.as_str() {
+        "before" => Ok(TxCommentPosition::Before),
+        "after" => Ok(TxCommentPosition::After),
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported comment position: {other}"
+        ))),
+    }
+}
+
+fn py_slug_style_to_rust(style: &Bound<'_, PyAny>) -> PyResult<TxSlugStyle> {
+    let value: String = if let Ok(val) = style.getattr("value") {
+        val.extract()?
+    } else {
+        style.extract()?
+    };
+
+    match value.as_str() {
+        "github" => Ok(TxSlugStyle::Github),
+        "gitlab" => Ok(TxSlugStyle::Gitlab),
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported slug style: {other}"
+        ))),
+    }
+}
+
+fn py_assert_expectation_to_rust(expect: &Bound<'_, PyAny>) -> PyResult<TxAssertExpectation> {
+    let value: String = if let Ok(val) = expect.getattr("value") {
+        val.extract()?
+    } else {
+        expect.extract()?
+    };
+
+    match value.as_str() {
+        "match" => Ok(TxAssertExpectation::Match),
+        "no_match" => Ok(TxAssertExpectation::NoMatch),
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported assert expectation: {other}"
+        ))),
+    }
+}
+
+fn py_task_state_to_rust(task_state: &Bound<'_, PyAny>) -> PyResult<TaskStatus> {
+    let value: String = if let Ok(val) = task_state.getattr("value") {
+        val.extract()?
+    } else {
+        task_state.extract()?
+    };
+
+    match value.as_str() {
+        "complete" => Ok(TaskStatus::Complete),
+        "incomplete" => Ok(TaskStatus::Incomplete),
+        "any" => Ok(TaskStatus::Any),
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported task state: {other}"
+        ))),
+    }
+}
+
+fn py_tie_break_to_rust(tie_break: &Bound<'_, PyAny>) -> PyResult<TieBreak> {
+    let value: String = if let Ok(val) = tie_break.getattr("value") {
+        val.extract()?
+    } else {
+        tie_break.extract()?
+    };
+
+    match value.replace('-', "_").as_str() {
+        "document_order" => Ok(TieBreak::DocumentOrder),
+        "deepest_first" => Ok(TieBreak::DeepestFirst),
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported tie break order: {other}"
+        ))),
+    }
+}
+
+#[allow(clippy::only_used_in_recursion)]
+fn py_to_yaml_value(py: Python<'_>, obj: &Bound<'_, PyAny>) -> PyResult<YamlValue> {
+    if obj.is_none() {
+        return Ok(YamlValue::Null);
+    }
+
+    if let Ok(value) = obj.extract::<bool>() {
+        return Ok(YamlValue::Bool(value));
+    }
+
+    if let Ok(value) = obj.extract::<i64>() {
+        return Ok(YamlValue::Number(YamlNumber::from(value)));
+    }
+
+    if let Ok(value) = obj.extract::<u64>() {
+        return Ok(YamlValue::Number(YamlNumber::from(value)));
+    }
+
+    if let Ok(value) = obj.extract::<f64>() {
+        if value.is_finite() {
+            return Ok(YamlValue::from(value));
+        } else {
+            return Err(PyValueError::new_err(
+                "Float value is not representable in YAML",
+            ));
+        }
+    }
+
+    if let Ok(value) = obj.extract::<String>() {
+        return Ok(YamlValue::String(value));
+    }
+
+    if let Ok(list) = obj.cast::<PyList>() {
+        let mut seq = Vec::with_capacity(list.len());
+        for item in list {
+            seq.push(py_to_yaml_value(py, &item)?);
+        }
+        return Ok(YamlValue::Sequence(seq));
+    }
+
+    if let Ok(tuple) = obj.cast::<PyTuple>() {
+        let mut seq = Vec::with_capacity(tuple.len());
+        for item in tuple {
+            seq.push(py_to_yaml_value(py, &item)?);
+        }
+        return Ok(YamlValue::Sequence(seq));
+    }
+
+    if let Ok(dict) = obj.cast::<PyDict>() {
+        let mut mapping = YamlMapping::new();
+        for (key, value) in dict.iter() {
+            let key_value = py_to_yaml_value(py, &key)?;
+            let value_value = py_to_yaml_value(py, &value)?;
+            mapping.insert(key_value, value_value);
+        }
+        return Ok(YamlValue::Mapping(mapping));
+    }
+
+    Err(PyTypeError::new_err(format!(
+        "Unsupported value type for YAML conversion: {}",
+        obj.get_type().name()?
+    )))
+}
+
+fn yaml_value_to_py(py: Python<'_>, value: &YamlValue) -> PyResult<Py<PyAny>> {
+    match value {
+        YamlValue::Null => Ok(py.None()),
+        YamlValue::Bool(value) => (*value).into_py_any(py),
+        YamlValue::Number(number) => {
+            if let Some(int_value) = number.as_i64() {
+                int_value.into_py_any(py)
+            } else if let Some(uint_value) = number.as_u64() {
+                uint_value.into_py_any(py)
+            } else if let Some(float_value) = number.as_f64() {
+                float_value.into_py_any(py)
+            } else {
+                Err(PyErr::new::<PyException, _>(
+                    "Unsupported YAML number representation",
+                ))
+            }
+        }
+        YamlValue::String(value) => value.clone().into_py_any(py),
+        YamlValue::Sequence(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(yaml_value_to_py(py, item)?)?;
+            }
+            Ok(list.into_any().unbind())
+        }
+        YamlValue::Mapping(mapping) => {
+            let dict = PyDict::new(py);
+            for (key, value) in mapping {
+                let key_obj = yaml_value_to_py(py, key)?;
+                let value_obj = yaml_value_to_py(py, value)?;
+                dict.set_item(key_obj, value_obj)?;
+            }
+            Ok(dict.into_any().unbind())
+        }
+        YamlValue::Tagged(tagged) => yaml_value_to_py(py, &tagged.value),
+    }
+}
+
+fn py_selector_to_locator(
+    py: Python<'_>,
+    selector: &Bound<'_, PyAny>,
+) -> PyResult<LocatorSelector> {
+    let select_type = selector
+        .getattr("select_type")?
+        .extract::<Option<String>>()?;
+    let select_contains = selector
+        .getattr("select_contains")?
+        .extract::<Option<String>>()?;
+    let select_contains_ci = selector
+        .getattr("select_contains_ci")?
+        .extract::<Option<String>>()?;
+    let select_regex_obj = selector.getattr("select_regex")?;
+    let select_regex = if select_regex_obj.is_none() {
+        None
+    } else {
+        Some(python_regex_to_rust(py, &select_regex_obj)?)
+    };
+    let select_not_contains = selector
+        .getattr("select_not_contains")?
+        .extract::<Option<String>>()?;
+    let select_not_regex_obj = selector.getattr("select_not_regex")?;
+    let select_not_regex = if select_not_regex_obj.is_none() {
+        None
+    } else {
+        Some(python_regex_to_rust(py, &select_not_regex_obj)?)
+    };
+    let select_fingerprint = selector
+        .getattr("select_fingerprint")?
+        .extract::<Option<String>>()?;
+    let select_annotation = selector
+        .getattr("select_annotation")?
+        .extract::<Option<String>>()?;
+    let select_tag = selector
+        .getattr("select_tag")?
+        .extract::<Option<String>>()?;
+    let select_info_contains = selector
+        .getattr("select_info_contains")?
+        .extract::<Option<String>>()?;
+    let select_slug = selector
+        .getattr("select_slug")?
+        .extract::<Option<String>>()?;
+    let select_min_words = selector
+        .getattr("select_min_words")?
+        .extract::<Option<usize>>()?;
+    let select_max_words = selector
+        .getattr("select_max_words")?
+        .extract::<Option<usize>>()?;
+    let select_empty = selector
+        .getattr("select_empty")?
+        .extract::<Option<bool>>()?;
+    let select_depth = selector
+        .getattr("select_depth")?
+        .extract::<Option<usize>>()?;
+    let recursive = selector.getattr("recursive")?.extract::<bool>()?;
+    let task_state_obj = selector.getattr("task_state")?;
+    let task_state = if task_state_obj.is_none() {
+        None
+    } else {
+        Some(py_task_state_to_rust(&task_state_obj)?)
+    };
+    let select_ordinal = selector.getattr("select_ordinal")?.extract::<usize>()?;
+    let select_last = selector.getattr("select_last")?.extract::<bool>()?;
+    let max_matches = selector
+        .getattr("max_matches")?
+        .extract::<Option<usize>>()?;
+    let tie_break = py_tie_break_to_rust(&selector.getattr("tie_break")?)?;
+    let after_obj = selector.getattr("after")?;
+    let after = if after_obj.is_none() {
+        None
+    } else {
+        Some(Box::new(py_selector_to_locator(py, &after_obj)?))
+    };
+    let within_obj = selector.getattr("within")?;
+    let within = if within_obj.is_none() {
+        None
+    } else {
+        Some(Box::new(py_selector_to_locator(py, &within_obj)?))
+    };
+    let between_obj = selector.getattr("between")?;
+    let between = if between_obj.is_none() {
+        None
+    } else {
+        let landmark = |name: &str| -> PyResult<LocatorSelector> {
+            let landmark_obj = between_obj.getattr(name)?;
+            if landmark_obj.is_none() {
+                return Err(PyValueError::new_err(format!(
+                    "'{name}_ref' is only resolved when applying operations; pass '{name}' as a Selector"
+                )));
+            }
+            py_selector_to_locator(py, &landmark_obj)
+        };
+        Some(Box::new(LocatorBetween {
+            start: landmark("start")?,
+            end: landmark("end")?,
+        }))
+    };
+    let within_slide = selector
+        .getattr("within_slide")?
+        .extract::<Option<usize>>()?;
+    let at_line = selector.getattr("at_line")?.extract::<Option<usize>>()?;
+
+    Ok(LocatorSelector {
         select_type,
         select_contains,
+        select_contains_ci,
         select_regex,
+        select_not_contains,
+        select_not_regex,
+        select_fingerprint,
+        select_annotation,
+        select_tag,
+        select_info_contains,
+        select_slug,
+        select_min_words,
+        select_max_words,
+        select_empty,
+        select_depth,
+        recursive,
+        task_state,
         select_ordinal,
+        select_last,
         after,
-        after_ref,
         within,
-        within_ref,
+        between,
+        within_slide,
+        at_line,
+        max_matches,
+        tie_break,
+    })
+}
+
+fn python_regex_to_rust(py: Python<'_>, pattern_obj: &Bound<'_, PyAny>) -> PyResult<Regex> {
+    let pattern = extract_regex_pattern(pattern_obj)?;
+    let flags = extract_regex_flags(py, pattern_obj)?;
+
+    let mut builder = RegexBuilder::new(&pattern);
+    builder.case_insensitive(flags.ignore_case);
+    builder.multi_line(flags.multi_line);
+    builder.dot_matches_new_line(flags.dot_all);
+    builder.unicode(true);
+
+    builder
+        .build()
+        .map_err(|err| invalid_regex_pyerr(py, err.to_string()))
+}
+
+fn invalid_regex_pyerr(py: Python<'_>, message: String) -> PyErr {
+    if let Ok(errors_module) = py.import("md_splice.errors") {
+        if let Ok(obj) = errors_module.getattr("InvalidRegexError") {
+            if let Ok(error_type) = obj.cast_into::<PyType>() {
+                return PyErr::from_type(error_type, (message,));
+            }
+        }
+    }
+
+    PyException::new_err(message)
+}
+
+fn extract_regex_pattern(pattern_obj: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(pattern_attr) = pattern_obj.getattr("pattern") {
+        pattern_attr.extract::<String>()
+    } else {
+        pattern_obj.extract::<String>()
+    }
+}
+
+#[derive(Default)]
+struct RegexFlags {
+    ignore_case: bool,
+    multi_line: bool,
+    dot_all: bool,
+}
+
+fn extract_regex_flags(py: Python<'_>, pattern_obj: &Bound<'_, PyAny>) -> PyResult<RegexFlags> {
+    if !pattern_obj.hasattr("flags")? {
+        return Ok(RegexFlags::default());
+    }
+
+    let flags_value = pattern_obj.getattr("flags")?.extract::<u32>()?;
+    if flags_value == 0 {
+        return Ok(RegexFlags::default());
+    }
+
+    let re_module = py.import("re")?;
+    let flag_ignorecase = re_module.getattr("IGNORECASE")?.extract::<u32>()?;
+    let flag_multiline = re_module.getattr("MULTILINE")?.extract::<u32>()?;
+    let flag_dotall = re_module.getattr("DOTALL")?.extract::<u32>()?;
+    let flag_unicode = re_module.getattr("UNICODE")?.extract::<u32>()?;
+
+    let supported_mask = flag_ignorecase | flag_multiline | flag_dotall | flag_unicode;
+
+    let known_unsupported = [
+        ("VERBOSE", re_module.getattr("VERBOSE")?.extract::<u32>()?),
+        ("ASCII", re_module.getattr("ASCII")?.extract::<u32>()?),
+        ("LOCALE", re_module.getattr("LOCALE")?.extract::<u32>()?),
+        ("DEBUG", re_module.getattr("DEBUG")?.extract::<u32>()?),
+    ];
+
+    let mut unsupported: Vec<String> = Vec::new();
+    let mut known_mask = 0u32;
+    for (name, value) in &known_unsupported {
+        known_mask |= *value;
+        if flags_value & value != 0 {
+            unsupported.push(name.to_string());
+        }
+    }
+
+    let leftover = flags_value & !(supported_mask | known_mask);
+    if leftover != 0 {
+        unsupported.push(format!("0x{leftover:x}"));
+    }
+
+    if !unsupported.is_empty() {
+        return Err(invalid_regex_pyerr(
+            py,
+            format!("Unsupported regex flag(s): {}", unsupported.join(", ")),
+        ));
+    }
+
+    Ok(RegexFlags {
+        ignore_case: flags_value & flag_ignorecase != 0,
+        multi_line: flags_value & flag_multiline != 0,
+        dot_all: flags_value & flag_dotall != 0,
+    })
+}
+
+fn render_heading_section(blocks: &[Block], found: &FoundNode) -> PyResult<String> {
+    if let FoundNode::Block { index, .. } = found {
+        if let Some(section) = section_range(blocks, *index) {
+            return Ok(render_fragment(&blocks[section], default_printer_config()));
+        }
+    }
+
+    Err(map_splice_error(SpliceError::SectionRequiresHeading))
+}
+
+fn render_found_node(blocks: &[Block], found: &FoundNode) -> PyResult<String> {
+    match found {
+        FoundNode::Block { block, .. } | FoundNode::ContainedBlock { block, .. } => Ok(
+            render_fragment(std::slice::from_ref(block), default_printer_config()),
+        ),
+        FoundNode::ListItem {
+            block_index,
+            path,
+            item,
+            ..
+        } => match item_list(blocks, *block_index, path) {
+            Some(list) => {
+                let mut single_list = list.clone();
+                single_list.items = vec![(*item).clone()];
+                Ok(render_fragment(
+                    std::slice::from_ref(&Block::List(single_list)),
+                    default_printer_config(),
+                ))
+            }
+            _ => Err(PyException::new_err(format!(
+                "Internal error: block at index {} is not a list",
+                block_index
+            ))),
+        },
+        _ => render_table_node(blocks, found)
+            .or_else(|| render_inline_node(found))
+            .ok_or_else(|| {
+                PyException::new_err("Internal error: the matched node cannot be rendered")
+            }),
+    }
+}
+
+/// Produce a unified diff between two Markdown strings.
+///
+/// The optional ``fromfile`` and ``tofile`` labels appear in the diff header.
+#[pyfunction]
+#[pyo3(signature = (before, after, *, fromfile="original", tofile="modified"))]
+fn diff_unified(before: &str, after: &str, fromfile: &str, tofile: &str) -> PyResult<String> {
+    let diff = TextDiff::from_lines(before, after)
+        .unified_diff()
+        .header(fromfile, tofile)
+        .to_string();
+    Ok(diff)
+}
+
+/// Compare the top-level blocks of two documents.
+///
+/// Returns a list of :class:`md_splice.types.StructuralChange` instances, one per
+/// run of blocks that differs, in document order. Frontmatter is not compared.
+#[pyfunction]
+fn diff_structural(
+    py: Python<'_>,
+    before: PyRef<'_, PyMarkdownDocument>,
+    after: PyRef<'_, PyMarkdownDocument>,
+) -> PyResult<Py<PyAny>> {
+    let types_module = py.import("md_splice.types")?;
+    let change_class = types_module.getattr("StructuralChange")?;
+    let list = PyList::empty(py);
+    let (before, after) = (before.inner.blocks(), after.inner.blocks());
+
+    for hunk in diff_hunks(before, after) {
+        let kind = if hunk.original.is_empty() {
+            "insert"
+        } else if hunk.edited.is_empty() {
+            "delete"
+        } else {
+            "replace"
+        };
+        let instance = change_class.call1((
+            kind,
+            (hunk.original.start, hunk.original.end),
+            (hunk.edited.start, hunk.edited.end),
+            render_fragment(&before[hunk.original], default_printer_config()),
+            render_fragment(&after[hunk.edited], default_printer_config()),
+        ))?;
+        list.append(instance)?;
+    }
+
+    Ok(list.into_any().unbind())
+}
+
+/// Merge the changes ``ours`` and ``theirs`` each made to ``base``.
+///
+/// Returns a :class:`md_splice.types.MergeResult` holding the merged document and a
+/// list of :class:`md_splice.types.MergeConflict` instances, one per run of blocks
+/// both sides changed differently. Conflicting runs keep our version.
+#[pyfunction]
+fn merge(
+    py: Python<'_>,
+    base: PyRef<'_, PyMarkdownDocument>,
+    ours: PyRef<'_, PyMarkdownDocument>,
+    theirs: PyRef<'_, PyMarkdownDocument>,
+) -> PyResult<Py<PyAny>> {
+    let types_module = py.import("md_splice.types")?;
+    let conflict_class = types_module.getattr("MergeConflict")?;
+    let result_class = types_module.getattr("MergeResult")?;
+    let (merged, conflicts) = merge_documents(&base.inner, &ours.inner, &theirs.inner);
+    let (base, ours, theirs) = (
+        base.inner.blocks(),
+        ours.inner.blocks(),
+        theirs.inner.blocks(),
+    );
+
+    let list = PyList::empty(py);
+    for conflict in conflicts {
+        let instance = conflict_class.call1((
+            (conflict.base.start, conflict.base.end),
+            (conflict.ours.start, conflict.ours.end),
+            (conflict.theirs.start, conflict.theirs.end),
+            render_fragment(&base[conflict.base], default_printer_config()),
+            render_fragment(&ours[conflict.ours], default_printer_config()),
+            render_fragment(&theirs[conflict.theirs], default_printer_config()),
+        ))?;
+        list.append(instance)?;
+    }
+
+    let document = Py::new(
+        py,
+        PyMarkdownDocument {
+            inner: merged,
+            source_path: None,
+        },
+    )?;
+    let result = result_class.call1((document, list))?;
+    Ok(result.unbind())
+}
+
+/// Apply the same operations to every file of ``paths`` and write them back.
+///
+/// Reading, parsing, applying, and writing run in Rust with the GIL released,
+/// spread over the available cores when ``parallel`` is ``True``. Each file is
+/// its own transaction, and no file is written unless every one of them
+/// applied cleanly: the first failure, in ``paths`` order, is raised with the
+/// offending path prefixed to its message. With ``backup=True`` each file is
+/// first copied to a ``path~`` sibling.
+///
+/// Returns one list of :class:`md_splice.types.ApplyWarning` per path, in
+/// ``paths`` order. Ambiguity warnings follow the same rules as
+/// :meth:`MarkdownDocument.apply`.
+#[pyfunction]
+#[pyo3(signature = (paths, ops, *, parallel=true, backup=false, warn_on_ambiguity=true))]
+fn apply_to_files(
+    py: Python<'_>,
+    paths: &Bound<'_, PyAny>,
+    ops: &Bound<'_, PyAny>,
+    parallel: bool,
+    backup: bool,
+    warn_on_ambiguity: bool,
+) -> PyResult<Py<PyAny>> {
+    let paths = paths
+        .try_iter()?
+        .map(|path| path?.extract::<PathBuf>())
+        .collect::<PyResult<Vec<_>>>()?;
+    let operations = py_operations_to_rust(py, ops)?;
+
+    let applied = py.detach(|| {
+        map_files(&paths, parallel, |path| {
+            let content =
+                fs::read_to_string(path).map_err(|err| SpliceError::Io(err.to_string()))?;
+            let mut document = CoreMarkdownDocument::from_str(&content)?;
+            let outcome = document.apply_with_ambiguity(operations.clone())?;
+            Ok((document.render(), outcome))
+        })
+    });
+    let mut outcomes = Vec::with_capacity(applied.len());
+    for (path, result) in paths.iter().zip(applied) {
+        match result {
+            Ok(applied) => outcomes.push(applied),
+            Err(err) => return Err(map_file_error(py, path, err)),
+        }
+    }
+
+    let written = py.detach(|| {
+        let files: Vec<_> = paths.iter().zip(&outcomes).collect();
+        map_files(&files, parallel, |(path, (rendered, _))| {
+            if backup {
+                let mut backup_name = path.as_os_str().to_os_string();
+                backup_name.push("~");
+                fs::copy(path, backup_name)?;
+            }
+            write_atomic_io(path, rendered)
+        })
+    });
+    for (path, result) in paths.iter().zip(written) {
+        result.map_err(|err| map_file_error(py, path, SpliceError::Io(err.to_string())))?;
+    }
+
+    let list = PyList::empty(py);
+    for (_, outcome) in &outcomes {
+        maybe_emit_ambiguity_warning(py, warn_on_ambiguity, outcome)?;
+        list.append(apply_warnings_to_py(py, outcome)?)?;
+    }
+    Ok(list.into_any().unbind())
+}
+
+/// Maps `f` over `items`, splitting them across one scoped thread per core when
+/// `parallel` is set, and returns the results in the order of `items`.
+fn map_files<T: Sync, R: Send>(items: &[T], parallel: bool, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    if !parallel || workers < 2 || items.len() < 2 {
+        return items.iter().map(f).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(workers);
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&f).collect::<Vec<_>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("batch worker thread panicked"))
+            .collect()
     })
 }
 
-fn py_insert_position_to_rust(position: &Bound<'_, PyAny>) -> PyResult<TxInsertPosition> {
-    let value: String = if let Ok(val) = position.getattr("value") {
-        val.extract()?
-    } else {
-        position.extract()?
-    };
+/// Converts an error raised while processing `path` into the matching Python
+/// exception, with the path prefixed to its message.
+fn map_file_error(py: Python<'_>, path: &Path, err: SpliceError) -> PyErr {
+    let py_err = map_splice_error(err);
+    let message = format!("{}: {}", path.display(), py_err.value(py));
+    PyErr::from_type(py_err.get_type(py), (message,))
+}
+
+/// Parse YAML or JSON operation definitions into Python dataclasses.
+///
+/// The ``format`` parameter can force ``"yaml"`` or ``"json"``. When omitted
+/// the loader first attempts YAML then falls back to JSON, matching the CLI.
+#[pyfunction]
+#[pyo3(signature = (text, *, format=None))]
+fn loads_operations(py: Python<'_>, text: &str, format: Option<&str>) -> PyResult<Py<PyAny>> {
+    let operations = parse_operations(text, format).map_err(map_splice_error)?;
+    let types_module = py.import("md_splice.types")?;
+    let py_list = PyList::empty(py);
+
+    for operation in &operations {
+        let py_op = tx_operation_to_py(py, &types_module, operation)?;
+        py_list.append(py_op)?;
+    }
+
+    Ok(py_list.into_any().unbind())
+}
+
+/// Serialize Python operation dataclasses to YAML or JSON.
+///
+/// ``format`` defaults to ``"yaml"``; specifying ``"json"`` returns formatted
+/// JSON compatible with the CLI tooling.
+#[pyfunction]
+#[pyo3(signature = (operations, *, format="yaml"))]
+fn dumps_operations(
+    py: Python<'_>,
+    operations: &Bound<'_, PyAny>,
+    format: &str,
+) -> PyResult<String> {
+    let tx_operations = py_operations_to_rust(py, operations)?;
+    let yaml_operations = tx_operations
+        .iter()
+        .map(tx_operation_to_yaml_value)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_splice_error)?;
+    let normalized = format.to_ascii_lowercase();
+
+    match normalized.as_str() {
+        "yaml" => serde_yaml::to_string(&yaml_operations)
+            .map_err(|err| map_splice_error(SpliceError::OperationParse(err.to_string()))),
+        "json" => serde_json::to_string_pretty(&yaml_operations)
+            .map_err(|err| map_splice_error(SpliceError::OperationParse(err.to_string()))),
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported operations format: {other}"
+        ))),
+    }
+}
+
+fn parse_operations(text: &str, format: Option<&str>) -> Result<Vec<TxOperation>, SpliceError> {
+    let normalized = format.map(|value| value.to_ascii_lowercase());
+    match normalized.as_deref() {
+        Some("yaml") => serde_yaml::from_str(text)
+            .map_err(|err| SpliceError::OperationParse(err.to_string())),
+        Some("json") => serde_json::from_str(text)
+            .map_err(|err| SpliceError::OperationParse(err.to_string())),
+        Some(other) => Err(SpliceError::OperationParse(format!(
+            "Unsupported operations format: {other}"
+        ))),
+        None => match serde_yaml::from_str(text) {
+            Ok(value) => Ok(value),
+            Err(yaml_err) => serde_json::from_str(text).map_err(|json_err| {
+                SpliceError::OperationParse(format!(
+                    "Failed to parse operations as YAML ({yaml_err}). Attempt to parse as JSON also failed ({json_err})."
+                ))
+            }),
+        },
+    }
+}
+
+fn tx_operation_to_py(
+    py: Python<'_>,
+    types_module: &Bound<'_, PyModule>,
+    operation: &TxOperation,
+) -> PyResult<Py<PyAny>> {
+    match operation {
+        TxOperation::Insert(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("InsertOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            if let Some(content) = &op.content {
+                kwargs.set_item("content", content)?;
+            }
+            let position = insert_position_to_py(py, types_module, op.position)?;
+            kwargs.set_item("position", position)?;
+            kwargs.set_item("renumber", op.renumber)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Replace(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("ReplaceOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            if let Some(content) = &op.content {
+                kwargs.set_item("content", content)?;
+            }
+            if let Some(until) = &op.until {
+                let until_selector = tx_selector_to_py(py, types_module, until)?;
+                kwargs.set_item("until", until_selector)?;
+            }
+            if let Some(until_ref) = &op.until_ref {
+                kwargs.set_item("until_ref", until_ref)?;
+            }
+            kwargs.set_item("update_links", op.update_links)?;
+            if let Some(cell) = op.cell {
+                kwargs.set_item("cell", (cell.row, cell.column))?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::ReplaceText(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("ReplaceTextOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("pattern", &op.pattern)?;
+            kwargs.set_item("replacement", &op.replacement)?;
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("section", op.section)?;
+            if let Some(until) = &op.until {
+                let until_selector = tx_selector_to_py(py, types_module, until)?;
+                kwargs.set_item("until", until_selector)?;
+            }
+            if let Some(until_ref) = &op.until_ref {
+                kwargs.set_item("until_ref", until_ref)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Upsert(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("UpsertOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            if let Some(content) = &op.content {
+                kwargs.set_item("content", content)?;
+            }
+            kwargs.set_item("section", op.section)?;
+            if let Some(fallback) = &op.fallback {
+                let fallback_selector = tx_selector_to_py(py, types_module, fallback)?;
+                kwargs.set_item("fallback", fallback_selector)?;
+            }
+            if let Some(fallback_ref) = &op.fallback_ref {
+                kwargs.set_item("fallback_ref", fallback_ref)?;
+            }
+            let position = insert_position_to_py(py, types_module, op.position)?;
+            kwargs.set_item("position", position)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Delete(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("DeleteOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("section", op.section)?;
+            if let Some(until) = &op.until {
+                let until_selector = tx_selector_to_py(py, types_module, until)?;
+                kwargs.set_item("until", until_selector)?;
+            }
+            if let Some(until_ref) = &op.until_ref {
+                kwargs.set_item("until_ref", until_ref)?;
+            }
+            kwargs.set_item("renumber", op.renumber)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Move(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("MoveOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("section", op.section)?;
+            if let Some(destination) = &op.destination {
+                let destination_selector = tx_selector_to_py(py, types_module, destination)?;
+                kwargs.set_item("destination", destination_selector)?;
+            }
+            if let Some(destination_ref) = &op.destination_ref {
+                kwargs.set_item("destination_ref", destination_ref)?;
+            }
+            let position = insert_position_to_py(py, types_module, op.position)?;
+            kwargs.set_item("position", position)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Copy(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("CopyOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("section", op.section)?;
+            if let Some(until) = &op.until {
+                let until_selector = tx_selector_to_py(py, types_module, until)?;
+                kwargs.set_item("until", until_selector)?;
+            }
+            if let Some(until_ref) = &op.until_ref {
+                kwargs.set_item("until_ref", until_ref)?;
+            }
+            if let Some(destination) = &op.destination {
+                let destination_selector = tx_selector_to_py(py, types_module, destination)?;
+                kwargs.set_item("destination", destination_selector)?;
+            }
+            if let Some(destination_ref) = &op.destination_ref {
+                kwargs.set_item("destination_ref", destination_ref)?;
+            }
+            let position = insert_position_to_py(py, types_module, op.position)?;
+            kwargs.set_item("position", position)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Swap(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("SwapOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("section", op.section)?;
+            if let Some(other) = &op.other {
+                let other_selector = tx_selector_to_py(py, types_module, other)?;
+                kwargs.set_item("other", other_selector)?;
+            }
+            if let Some(other_ref) = &op.other_ref {
+                kwargs.set_item("other_ref", other_ref)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::RenameHeading(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("RenameHeadingOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("text", &op.text)?;
+            if let Some(id) = &op.id {
+                kwargs.set_item("id", id)?;
+            }
+            kwargs.set_item("update_links", op.update_links)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::ShiftHeading(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("ShiftHeadingOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("delta", op.delta)?;
+            kwargs.set_item("include_section", op.include_section)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::MergeSections(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("MergeSectionsOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            if let Some(destination) = &op.destination {
+                let destination_selector = tx_selector_to_py(py, types_module, destination)?;
+                kwargs.set_item("destination", destination_selector)?;
+            }
+            if let Some(destination_ref) = &op.destination_ref {
+                kwargs.set_item("destination_ref", destination_ref)?;
+            }
+            kwargs.set_item("adjust_levels", op.adjust_levels)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Get(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("GetOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("section", op.section)?;
+            if let Some(until) = &op.until {
+                let until_selector = tx_selector_to_py(py, types_module, until)?;
+                kwargs.set_item("until", until_selector)?;
+            }
+            if let Some(until_ref) = &op.until_ref {
+                kwargs.set_item("until_ref", until_ref)?;
+            }
+            if let Some(capture) = &op.capture {
+                kwargs.set_item("capture", capture)?;
+            }
+            if let Some(cell) = op.cell {
+                kwargs.set_item("cell", (cell.row, cell.column))?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::SetFrontmatter(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+            ensure_operation_field_absent(op.value_file.as_ref(), "value_file")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("SetFrontmatterOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("key", &op.key)?;
+            let value = match &op.value {
+                Some(value) => yaml_value_to_py(py, value)?,
+                None => py.None(),
+            };
+            kwargs.set_item("value", value)?;
+            if let Some(format) = op.format {
+                let format_value = frontmatter_format_to_py(py, types_module, format)?;
+                kwargs.set_item("format", format_value)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::DeleteFrontmatter(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("DeleteFrontmatterOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("key", &op.key)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::ReplaceFrontmatter(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("ReplaceFrontmatterOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            let content = match &op.content {
+                Some(value) => yaml_value_to_py(py, value)?,
+                None => py.None(),
+            };
+            kwargs.set_item("content", content)?;
+            if let Some(format) = op.format {
+                let format_value = frontmatter_format_to_py(py, types_module, format)?;
+                kwargs.set_item("format", format_value)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::PruneEmptySections(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("PruneEmptySectionsOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(min_level) = op.min_level {
+                kwargs.set_item("min_level", min_level)?;
+            }
+            if let Some(max_level) = op.max_level {
+                kwargs.set_item("max_level", max_level)?;
+            }
+            if let Some(within) = &op.within {
+                let within_selector = tx_selector_to_py(py, types_module, within)?;
+                kwargs.set_item("within", within_selector)?;
+            }
+            if let Some(within_ref) = &op.within_ref {
+                kwargs.set_item("within_ref", within_ref)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::DedupeHeadings(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("DedupeHeadingsOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(within) = &op.within {
+                let within_selector = tx_selector_to_py(py, types_module, within)?;
+                kwargs.set_item("within", within_selector)?;
+            }
+            if let Some(within_ref) = &op.within_ref {
+                kwargs.set_item("within_ref", within_ref)?;
+            }
+            kwargs.set_item("report_only", op.report_only)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Renumber(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("RenumberOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(within) = &op.within {
+                let within_selector = tx_selector_to_py(py, types_module, within)?;
+                kwargs.set_item("within", within_selector)?;
+            }
+            if let Some(within_ref) = &op.within_ref {
+                kwargs.set_item("within_ref", within_ref)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::ImportSection(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("ImportSectionOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("source_file", op.source_file.to_string_lossy())?;
+            let source_selector = tx_selector_to_py(py, types_module, &op.source_selector)?;
+            kwargs.set_item("source_selector", source_selector)?;
+            if let Some(position) = op.position {
+                let position = insert_position_to_py(py, types_module, position)?;
+                kwargs.set_item("position", position)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::SetAlert(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("SetAlertOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            let alert_type = alert_type_to_py(py, types_module, op.alert_type)?;
+            kwargs.set_item("alert_type", alert_type)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::RemoveAlert(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("RemoveAlertOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::SetComment(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("SetCommentOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("text", &op.text)?;
+            let position = comment_position_to_py(py, types_module, op.position)?;
+            kwargs.set_item("position", position)?;
+            if let Some(marker) = &op.marker {
+                kwargs.set_item("marker", marker)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::RemoveComment(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("RemoveCommentOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            let position = comment_position_to_py(py, types_module, op.position)?;
+            kwargs.set_item("position", position)?;
+            if let Some(marker) = &op.marker {
+                kwargs.set_item("marker", marker)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::ReplaceInnerHtml(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("ReplaceInnerHtmlOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("content", op.content.clone().unwrap_or_default())?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Wrap(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("WrapOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            if let Some(alert_type) = op.alert_type {
+                let alert_type = alert_type_to_py(py, types_module, alert_type)?;
+                kwargs.set_item("alert_type", alert_type)?;
+            }
+            kwargs.set_item("section", op.section)?;
+            if let Some(until) = &op.until {
+                let until_selector = tx_selector_to_py(py, types_module, until)?;
+                kwargs.set_item("until", until_selector)?;
+            }
+            if let Some(until_ref) = &op.until_ref {
+                kwargs.set_item("until_ref", until_ref)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Unwrap(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("UnwrapOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::WrapDetails(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("WrapDetailsOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("summary", &op.summary)?;
+            kwargs.set_item("open", op.open)?;
+            kwargs.set_item("section", op.section)?;
+            if let Some(until) = &op.until {
+                let until_selector = tx_selector_to_py(py, types_module, until)?;
+                kwargs.set_item("until", until_selector)?;
+            }
+            if let Some(until_ref) = &op.until_ref {
+                kwargs.set_item("until_ref", until_ref)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::UnwrapDetails(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
 
-    let normalized = value.replace('-', "_");
+            let class = types_module
+                .getattr("UnwrapDetailsOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::SetCodeLanguage(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
 
-    match normalized.as_str() {
-        "before" => Ok(TxInsertPosition::Before),
-        "after" => Ok(TxInsertPosition::After),
-        "prepend_child" => Ok(TxInsertPosition::PrependChild),
-        "append_child" => Ok(TxInsertPosition::AppendChild),
-        _ => Err(PyValueError::new_err(format!(
-            "Unsupported insert position: {value}"
-        ))),
+            let class = types_module
+                .getattr("SetCodeLanguageOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            if let Some(language) = &op.language {
+                kwargs.set_item("language", language)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::SetCodeAttribute(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("SetCodeAttributeOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("attribute", &op.attribute)?;
+            if let Some(value) = &op.value {
+                kwargs.set_item("value", value)?;
+            }
+            kwargs.set_item("remove", op.remove)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::InsertTableRow(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("InsertTableRowOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            match &op.row {
+                TableRow::Markdown(markdown) => kwargs.set_item("row", markdown)?,
+                TableRow::Cells(cells) => {
+                    kwargs.set_item("row", PyTuple::new(py, cells)?)?;
+                }
+            }
+            let position = insert_position_to_py(py, types_module, op.position)?;
+            kwargs.set_item("position", position)?;
+            if let Some(row_contains) = &op.row_contains {
+                kwargs.set_item("row_contains", row_contains)?;
+            }
+            if let Some(row_regex) = &op.row_regex {
+                kwargs.set_item("row_regex", row_regex)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::DeleteTableRow(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("DeleteTableRowOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            if let Some(row_contains) = &op.row_contains {
+                kwargs.set_item("row_contains", row_contains)?;
+            }
+            if let Some(row_regex) = &op.row_regex {
+                kwargs.set_item("row_regex", row_regex)?;
+            }
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::AddTableColumn(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("AddTableColumnOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("header", &op.header)?;
+            if let Some(value) = &op.value {
+                kwargs.set_item("value", value)?;
+            }
+            let position = insert_position_to_py(py, types_module, op.position)?;
+            kwargs.set_item("position", position)?;
+            if let Some(column) = &op.column {
+                kwargs.set_item("column", column)?;
+            }
+            let alignment = column_alignment_to_py(py, types_module, op.alignment)?;
+            kwargs.set_item("alignment", alignment)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::RemoveTableColumn(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("RemoveTableColumnOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("column", &op.column)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::RenameTableColumn(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("RenameTableColumnOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            kwargs.set_item("column", &op.column)?;
+            kwargs.set_item("header", &op.header)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::UpdateToc(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("UpdateTocOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            if let Some(min_level) = op.min_level {
+                kwargs.set_item("min_level", min_level)?;
+            }
+            if let Some(max_level) = op.max_level {
+                kwargs.set_item("max_level", max_level)?;
+            }
+            let slug_style = slug_style_to_py(py, types_module, op.slug_style)?;
+            kwargs.set_item("slug_style", slug_style)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
+        TxOperation::Assert(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")
+                .map_err(map_splice_error)?;
+
+            let class = types_module
+                .getattr("AssertOperation")?
+                .cast_into::<PyType>()?;
+            let kwargs = PyDict::new(py);
+            if let Some(selector) = &op.selector {
+                let selector_value = tx_selector_to_py(py, types_module, selector)?;
+                kwargs.set_item("selector", selector_value)?;
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                kwargs.set_item("selector_ref", selector_ref)?;
+            }
+            if let Some(frontmatter_key) = &op.frontmatter_key {
+                kwargs.set_item("frontmatter_key", frontmatter_key)?;
+            }
+            if let Some(equals) = &op.equals {
+                kwargs.set_item("equals", yaml_value_to_py(py, equals)?)?;
+            }
+            let expect = assert_expectation_to_py(py, types_module, op.expect)?;
+            kwargs.set_item("expect", expect)?;
+            let instance = class.call((), Some(&kwargs))?;
+            Ok(instance.into_any().unbind())
+        }
     }
 }
 
-fn py_frontmatter_format_to_rust(format_obj: &Bound<'_, PyAny>) -> PyResult<FrontmatterFormat> {
-    let value: String = if let Ok(val) = format_obj.getattr("name") {
-        val.extract()?
-    } else if let Ok(val) = format_obj.getattr("value") {
-        val.extract::<String>()?
-    } else {
-        format_obj.extract()?
-    };
+fn tx_operation_to_yaml_value(operation: &TxOperation) -> Result<YamlValue, SpliceError> {
+    let mut mapping = YamlMapping::new();
+
+    match operation {
+        TxOperation::Insert(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
+            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")?;
+
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("insert".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if let Some(content) = &op.content {
+                mapping.insert(
+                    YamlValue::String("content".to_string()),
+                    YamlValue::String(content.clone()),
+                );
+            }
+            if op.position != TxInsertPosition::After {
+                mapping.insert(
+                    YamlValue::String("position".to_string()),
+                    YamlValue::String(insert_position_to_str(op.position).to_string()),
+                );
+            }
+            if op.renumber {
+                mapping.insert(
+                    YamlValue::String("renumber".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+        }
+        TxOperation::Replace(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
+            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")?;
+
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("replace".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if let Some(content) = &op.content {
+                mapping.insert(
+                    YamlValue::String("content".to_string()),
+                    YamlValue::String(content.clone()),
+                );
+            }
+            if let Some(until) = &op.until {
+                mapping.insert(
+                    YamlValue::String("until".to_string()),
+                    tx_selector_to_yaml_value(until),
+                );
+            }
+            if let Some(until_ref) = &op.until_ref {
+                mapping.insert(
+                    YamlValue::String("until_ref".to_string()),
+                    YamlValue::String(until_ref.clone()),
+                );
+            }
+            if op.update_links {
+                mapping.insert(
+                    YamlValue::String("update_links".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+            if let Some(cell) = op.cell {
+                mapping.insert(
+                    YamlValue::String("cell".to_string()),
+                    cell_address_to_yaml_value(cell),
+                );
+            }
+        }
+        TxOperation::ReplaceText(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    match value.as_str() {
-        "YAML" | "yaml" => Ok(FrontmatterFormat::Yaml),
-        "TOML" | "toml" => Ok(FrontmatterFormat::Toml),
-        other => Err(PyValueError::new_err(format!(
-            "Unsupported frontmatter format: {other}"
-        ))),
-    }
-}
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("replace_text".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            mapping.insert(
+                YamlValue::String("pattern".to_string()),
+                YamlValue::String(op.pattern.clone()),
+            );
+            mapping.insert(
+                YamlValue::String("replacement".to_string()),
+                YamlValue::String(op.replacement.clone()),
+            );
+            if op.section {
+                mapping.insert(
+                    YamlValue::String("section".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+            if let Some(until) = &op.until {
+                mapping.insert(
+                    YamlValue::String("until".to_string()),
+                    tx_selector_to_yaml_value(until),
+                );
+            }
+            if let Some(until_ref) = &op.until_ref {
+                mapping.insert(
+                    YamlValue::String("until_ref".to_string()),
+                    YamlValue::String(until_ref.clone()),
+                );
+            }
+        }
+        TxOperation::Upsert(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
+            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")?;
 
-fn py_to_yaml_value(py: Python<'_>, obj: &Bound<'_, PyAny>) -> PyResult<YamlValue> {
-    if obj.is_none() {
-        return Ok(YamlValue::Null);
-    }
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("upsert".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if let Some(content) = &op.content {
+                mapping.insert(
+                    YamlValue::String("content".to_string()),
+                    YamlValue::String(content.clone()),
+                );
+            }
+            if op.section {
+                mapping.insert(
+                    YamlValue::String("section".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+            if let Some(fallback) = &op.fallback {
+                mapping.insert(
+                    YamlValue::String("fallback".to_string()),
+                    tx_selector_to_yaml_value(fallback),
+                );
+            }
+            if let Some(fallback_ref) = &op.fallback_ref {
+                mapping.insert(
+                    YamlValue::String("fallback_ref".to_string()),
+                    YamlValue::String(fallback_ref.clone()),
+                );
+            }
+            if op.position != TxInsertPosition::After {
+                mapping.insert(
+                    YamlValue::String("position".to_string()),
+                    YamlValue::String(insert_position_to_str(op.position).to_string()),
+                );
+            }
+        }
+        TxOperation::Delete(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    if let Ok(value) = obj.extract::<bool>() {
-        return Ok(YamlValue::Bool(value));
-    }
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("delete".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if op.section {
+                mapping.insert(
+                    YamlValue::String("section".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+            if let Some(until) = &op.until {
+                mapping.insert(
+                    YamlValue::String("until".to_string()),
+                    tx_selector_to_yaml_value(until),
+                );
+            }
+            if let Some(until_ref) = &op.until_ref {
+                mapping.insert(
+                    YamlValue::String("until_ref".to_string()),
+                    YamlValue::String(until_ref.clone()),
+                );
+            }
+            if op.renumber {
+                mapping.insert(
+                    YamlValue::String("renumber".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+        }
+        TxOperation::Move(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    if let Ok(value) = obj.extract::<i64>() {
-        return Ok(YamlValue::Number(YamlNumber::from(value)));
-    }
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("move".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if op.section {
+                mapping.insert(
+                    YamlValue::String("section".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+            if let Some(destination) = &op.destination {
+                mapping.insert(
+                    YamlValue::String("destination".to_string()),
+                    tx_selector_to_yaml_value(destination),
+                );
+            }
+            if let Some(destination_ref) = &op.destination_ref {
+                mapping.insert(
+                    YamlValue::String("destination_ref".to_string()),
+                    YamlValue::String(destination_ref.clone()),
+                );
+            }
+            if op.position != TxInsertPosition::After {
+                mapping.insert(
+                    YamlValue::String("position".to_string()),
+                    YamlValue::String(insert_position_to_str(op.position).to_string()),
+                );
+            }
+        }
+        TxOperation::Copy(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
+
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("copy".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if op.section {
+                mapping.insert(
+                    YamlValue::String("section".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+            if let Some(until) = &op.until {
+                mapping.insert(
+                    YamlValue::String("until".to_string()),
+                    tx_selector_to_yaml_value(until),
+                );
+            }
+            if let Some(until_ref) = &op.until_ref {
+                mapping.insert(
+                    YamlValue::String("until_ref".to_string()),
+                    YamlValue::String(until_ref.clone()),
+                );
+            }
+            if let Some(destination) = &op.destination {
+                mapping.insert(
+                    YamlValue::String("destination".to_string()),
+                    tx_selector_to_yaml_value(destination),
+                );
+            }
+            if let Some(destination_ref) = &op.destination_ref {
+                mapping.insert(
+                    YamlValue::String("destination_ref".to_string()),
+                    YamlValue::String(destination_ref.clone()),
+                );
+            }
+            if op.position != TxInsertPosition::After {
+                mapping.insert(
+                    YamlValue::String("position".to_string()),
+                    YamlValue::String(insert_position_to_str(op.position).to_string()),
+                );
+            }
+        }
+        TxOperation::Swap(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    if let Ok(value) = obj.extract::<u64>() {
-        return Ok(YamlValue::Number(YamlNumber::from(value)));
-    }
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("swap".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if op.section {
+                mapping.insert(
+                    YamlValue::String("section".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+            if let Some(other) = &op.other {
+                mapping.insert(
+                    YamlValue::String("other".to_string()),
+                    tx_selector_to_yaml_value(other),
+                );
+            }
+            if let Some(other_ref) = &op.other_ref {
+                mapping.insert(
+                    YamlValue::String("other_ref".to_string()),
+                    YamlValue::String(other_ref.clone()),
+                );
+            }
+        }
+        TxOperation::RenameHeading(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    if let Ok(value) = obj.extract::<f64>() {
-        if value.is_finite() {
-            return Ok(YamlValue::from(value));
-        } else {
-            return Err(PyValueError::new_err(
-                "Float value is not representable in YAML",
-            ));
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("rename_heading".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            mapping.insert(
+                YamlValue::String("text".to_string()),
+                YamlValue::String(op.text.clone()),
+            );
+            if let Some(id) = &op.id {
+                mapping.insert(
+                    YamlValue::String("id".to_string()),
+                    YamlValue::String(id.clone()),
+                );
+            }
+            if op.update_links {
+                mapping.insert(
+                    YamlValue::String("update_links".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
         }
-    }
+        TxOperation::ShiftHeading(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    if let Ok(value) = obj.extract::<String>() {
-        return Ok(YamlValue::String(value));
-    }
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("shift_heading".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            mapping.insert(
+                YamlValue::String("delta".to_string()),
+                YamlValue::Number(YamlNumber::from(op.delta)),
+            );
+            if op.include_section {
+                mapping.insert(
+                    YamlValue::String("include_section".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+        }
+        TxOperation::MergeSections(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    if let Ok(list) = obj.cast::<PyList>() {
-        let mut seq = Vec::with_capacity(list.len());
-        for item in list {
-            seq.push(py_to_yaml_value(py, &item)?);
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("merge_sections".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if let Some(destination) = &op.destination {
+                mapping.insert(
+                    YamlValue::String("destination".to_string()),
+                    tx_selector_to_yaml_value(destination),
+                );
+            }
+            if let Some(destination_ref) = &op.destination_ref {
+                mapping.insert(
+                    YamlValue::String("destination_ref".to_string()),
+                    YamlValue::String(destination_ref.clone()),
+                );
+            }
+            if op.adjust_levels {
+                mapping.insert(
+                    YamlValue::String("adjust_levels".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
         }
-        return Ok(YamlValue::Sequence(seq));
-    }
+        TxOperation::Get(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    if let Ok(tuple) = obj.cast::<PyTuple>() {
-        let mut seq = Vec::with_capacity(tuple.len());
-        for item in tuple {
-            seq.push(py_to_yaml_value(py, &item)?);
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("get".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if op.section {
+                mapping.insert(
+                    YamlValue::String("section".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+            if let Some(until) = &op.until {
+                mapping.insert(
+                    YamlValue::String("until".to_string()),
+                    tx_selector_to_yaml_value(until),
+                );
+            }
+            if let Some(until_ref) = &op.until_ref {
+                mapping.insert(
+                    YamlValue::String("until_ref".to_string()),
+                    YamlValue::String(until_ref.clone()),
+                );
+            }
+            if let Some(capture) = &op.capture {
+                mapping.insert(
+                    YamlValue::String("capture".to_string()),
+                    YamlValue::String(capture.clone()),
+                );
+            }
+            if let Some(cell) = op.cell {
+                mapping.insert(
+                    YamlValue::String("cell".to_string()),
+                    cell_address_to_yaml_value(cell),
+                );
+            }
         }
-        return Ok(YamlValue::Sequence(seq));
-    }
+        TxOperation::SetFrontmatter(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
+            ensure_operation_field_absent(op.value_file.as_ref(), "value_file")?;
 
-    if let Ok(dict) = obj.cast::<PyDict>() {
-        let mut mapping = YamlMapping::new();
-        for (key, value) in dict.iter() {
-            let key_value = py_to_yaml_value(py, &key)?;
-            let value_value = py_to_yaml_value(py, &value)?;
-            mapping.insert(key_value, value_value);
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("set_frontmatter".to_string()),
+            );
+            mapping.insert(
+                YamlValue::String("key".to_string()),
+                YamlValue::String(op.key.clone()),
+            );
+            let value = op.value.as_ref().cloned().unwrap_or(YamlValue::Null);
+            mapping.insert(YamlValue::String("value".to_string()), value);
+            if let Some(format) = op.format {
+                mapping.insert(
+                    YamlValue::String("format".to_string()),
+                    YamlValue::String(frontmatter_format_to_str(format).to_string()),
+                );
+            }
         }
-        return Ok(YamlValue::Mapping(mapping));
-    }
+        TxOperation::DeleteFrontmatter(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    Err(PyTypeError::new_err(format!(
-        "Unsupported value type for YAML conversion: {}",
-        obj.get_type().name()?
-    )))
-}
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("delete_frontmatter".to_string()),
+            );
+            mapping.insert(
+                YamlValue::String("key".to_string()),
+                YamlValue::String(op.key.clone()),
+            );
+        }
+        TxOperation::ReplaceFrontmatter(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
+            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")?;
 
-fn yaml_value_to_py(py: Python<'_>, value: &YamlValue) -> PyResult<Py<PyAny>> {
-    match value {
-        YamlValue::Null => Ok(py.None()),
-        YamlValue::Bool(value) => (*value).into_py_any(py),
-        YamlValue::Number(number) => {
-            if let Some(int_value) = number.as_i64() {
-                int_value.into_py_any(py)
-            } else if let Some(uint_value) = number.as_u64() {
-                uint_value.into_py_any(py)
-            } else if let Some(float_value) = number.as_f64() {
-                float_value.into_py_any(py)
-            } else {
-                Err(PyErr::new::<PyException, _>(
-                    "Unsupported YAML number representation",
-                ))
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("replace_frontmatter".to_string()),
+            );
+            let content = op.content.as_ref().cloned().unwrap_or(YamlValue::Null);
+            mapping.insert(YamlValue::String("content".to_string()), content);
+            if let Some(format) = op.format {
+                mapping.insert(
+                    YamlValue::String("format".to_string()),
+                    YamlValue::String(frontmatter_format_to_str(format).to_string()),
+                );
             }
         }
-        YamlValue::String(value) => value.clone().into_py_any(py),
-        YamlValue::Sequence(items) => {
-            let list = PyList::empty(py);
-            for item in items {
-                list.append(yaml_value_to_py(py, item)?)?;
+        TxOperation::PruneEmptySections(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
+
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("prune_empty_sections".to_string()),
+            );
+            if let Some(min_level) = op.min_level {
+                mapping.insert(
+                    YamlValue::String("min_level".to_string()),
+                    YamlValue::Number(YamlNumber::from(min_level)),
+                );
             }
-            Ok(list.into_any().unbind())
-        }
-        YamlValue::Mapping(mapping) => {
-            let dict = PyDict::new(py);
-            for (key, value) in mapping {
-                let key_obj = yaml_value_to_py(py, key)?;
-                let value_obj = yaml_value_to_py(py, value)?;
-                dict.set_item(key_obj, value_obj)?;
+            if let Some(max_level) = op.max_level {
+                mapping.insert(
+                    YamlValue::String("max_level".to_string()),
+                    YamlValue::Number(YamlNumber::from(max_level)),
+                );
+            }
+            if let Some(within) = &op.within {
+                mapping.insert(
+                    YamlValue::String("within".to_string()),
+                    tx_selector_to_yaml_value(within),
+                );
+            }
+            if let Some(within_ref) = &op.within_ref {
+                mapping.insert(
+                    YamlValue::String("within_ref".to_string()),
+                    YamlValue::String(within_ref.clone()),
+                );
             }
-            Ok(dict.into_any().unbind())
         }
-        YamlValue::Tagged(tagged) => yaml_value_to_py(py, &tagged.value),
-    }
-}
-
-fn py_selector_to_locator(
-    py: Python<'_>,
-    selector: &Bound<'_, PyAny>,
-) -> PyResult<LocatorSelector> {
-    let select_type = selector
-        .getattr("select_type")?
-        .extract::<Option<String>>()?;
-    let select_contains = selector
-        .getattr("select_contains")?
-        .extract::<Option<String>>()?;
-    let select_regex_obj = selector.getattr("select_regex")?;
-    let select_regex = if select_regex_obj.is_none() {
-        None
-    } else {
-        Some(python_regex_to_rust(py, &select_regex_obj)?)
-    };
-    let select_ordinal = selector.getattr("select_ordinal")?.extract::<usize>()?;
-    let after_obj = selector.getattr("after")?;
-    let after = if after_obj.is_none() {
-        None
-    } else {
-        Some(Box::new(py_selector_to_locator(py, &after_obj)?))
-    };
-    let within_obj = selector.getattr("within")?;
-    let within = if within_obj.is_none() {
-        None
-    } else {
-        Some(Box::new(py_selector_to_locator(py, &within_obj)?))
-    };
-
-    Ok(LocatorSelector {
-        select_type,
-        select_contains,
-        select_regex,
-        select_ordinal,
-        after,
-        within,
-    })
-}
-
-fn python_regex_to_rust(py: Python<'_>, pattern_obj: &Bound<'_, PyAny>) -> PyResult<Regex> {
-    let pattern = extract_regex_pattern(pattern_obj)?;
-    let flags = extract_regex_flags(py, pattern_obj)?;
-
-    let mut builder = RegexBuilder::new(&pattern);
-    builder.case_insensitive(flags.ignore_case);
-    builder.multi_line(flags.multi_line);
-    builder.dot_matches_new_line(flags.dot_all);
-    builder.unicode(true);
-
-    builder
-        .build()
-        .map_err(|err| invalid_regex_pyerr(py, err.to_string()))
-}
+        TxOperation::DedupeHeadings(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-fn invalid_regex_pyerr(py: Python<'_>, message: String) -> PyErr {
-    if let Ok(errors_module) = py.import("md_splice.errors") {
-        if let Ok(obj) = errors_module.getattr("InvalidRegexError") {
-            if let Ok(error_type) = obj.cast_into::<PyType>() {
-                return PyErr::from_type(error_type, (message,));
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("dedupe_headings".to_string()),
+            );
+            if let Some(within) = &op.within {
+                mapping.insert(
+                    YamlValue::String("within".to_string()),
+                    tx_selector_to_yaml_value(within),
+                );
+            }
+            if let Some(within_ref) = &op.within_ref {
+                mapping.insert(
+                    YamlValue::String("within_ref".to_string()),
+                    YamlValue::String(within_ref.clone()),
+                );
+            }
+            if op.report_only {
+                mapping.insert(
+                    YamlValue::String("report_only".to_string()),
+                    YamlValue::Bool(true),
+                );
             }
         }
-    }
-
-    PyException::new_err(message)
-}
-
-fn extract_regex_pattern(pattern_obj: &Bound<'_, PyAny>) -> PyResult<String> {
-    if let Ok(pattern_attr) = pattern_obj.getattr("pattern") {
-        pattern_attr.extract::<String>()
-    } else {
-        pattern_obj.extract::<String>()
-    }
-}
-
-#[derive(Default)]
-struct RegexFlags {
-    ignore_case: bool,
-    multi_line: bool,
-    dot_all: bool,
-}
-
-fn extract_regex_flags(py: Python<'_>, pattern_obj: &Bound<'_, PyAny>) -> PyResult<RegexFlags> {
-    if !pattern_obj.hasattr("flags")? {
-        return Ok(RegexFlags::default());
-    }
-
-    let flags_value = pattern_obj.getattr("flags")?.extract::<u32>()?;
-    if flags_value == 0 {
-        return Ok(RegexFlags::default());
-    }
-
-    let re_module = py.import("re")?;
-    let flag_ignorecase = re_module.getattr("IGNORECASE")?.extract::<u32>()?;
-    let flag_multiline = re_module.getattr("MULTILINE")?.extract::<u32>()?;
-    let flag_dotall = re_module.getattr("DOTALL")?.extract::<u32>()?;
-    let flag_unicode = re_module.getattr("UNICODE")?.extract::<u32>()?;
-
-    let supported_mask = flag_ignorecase | flag_multiline | flag_dotall | flag_unicode;
-
-    let known_unsupported = [
-        ("VERBOSE", re_module.getattr("VERBOSE")?.extract::<u32>()?),
-        ("ASCII", re_module.getattr("ASCII")?.extract::<u32>()?),
-        ("LOCALE", re_module.getattr("LOCALE")?.extract::<u32>()?),
-        ("DEBUG", re_module.getattr("DEBUG")?.extract::<u32>()?),
-    ];
+        TxOperation::Renumber(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    let mut unsupported: Vec<String> = Vec::new();
-    let mut known_mask = 0u32;
-    for (name, value) in &known_unsupported {
-        known_mask |= *value;
-        if flags_value & value != 0 {
-            unsupported.push(name.to_string());
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("renumber".to_string()),
+            );
+            if let Some(within) = &op.within {
+                mapping.insert(
+                    YamlValue::String("within".to_string()),
+                    tx_selector_to_yaml_value(within),
+                );
+            }
+            if let Some(within_ref) = &op.within_ref {
+                mapping.insert(
+                    YamlValue::String("within_ref".to_string()),
+                    YamlValue::String(within_ref.clone()),
+                );
+            }
         }
-    }
-
-    let leftover = flags_value & !(supported_mask | known_mask);
-    if leftover != 0 {
-        unsupported.push(format!("0x{leftover:x}"));
-    }
+        TxOperation::ImportSection(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    if !unsupported.is_empty() {
-        return Err(invalid_regex_pyerr(
-            py,
-            format!("Unsupported regex flag(s): {}", unsupported.join(", ")),
-        ));
-    }
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("import_section".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            mapping.insert(
+                YamlValue::String("source_file".to_string()),
+                YamlValue::String(op.source_file.to_string_lossy().into_owned()),
+            );
+            mapping.insert(
+                YamlValue::String("source_selector".to_string()),
+                tx_selector_to_yaml_value(&op.source_selector),
+            );
+            if let Some(position) = op.position {
+                mapping.insert(
+                    YamlValue::String("position".to_string()),
+                    YamlValue::String(insert_position_to_str(position).to_string()),
+                );
+            }
+        }
+        TxOperation::SetAlert(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    Ok(RegexFlags {
-        ignore_case: flags_value & flag_ignorecase != 0,
-        multi_line: flags_value & flag_multiline != 0,
-        dot_all: flags_value & flag_dotall != 0,
-    })
-}
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("set_alert".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            mapping.insert(
+                YamlValue::String("alert_type".to_string()),
+                YamlValue::String(alert_type_to_str(op.alert_type).to_string()),
+            );
+        }
+        TxOperation::RemoveAlert(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-fn compute_range_end(
-    blocks: &[Block],
-    start_index: usize,
-    until_selector: &LocatorSelector,
-) -> PyResult<usize> {
-    if start_index + 1 >= blocks.len() {
-        return Ok(blocks.len());
-    }
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("remove_alert".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+        }
+        TxOperation::SetComment(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-    match locate(&blocks[start_index + 1..], until_selector) {
-        Ok((FoundNode::Block { index, .. }, _)) => Ok(start_index + 1 + index),
-        Ok((FoundNode::ListItem { .. }, _)) => {
-            Err(map_splice_error(SpliceError::RangeRequiresBlock))
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("set_comment".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            mapping.insert(
+                YamlValue::String("text".to_string()),
+                YamlValue::String(op.text.clone()),
+            );
+            mapping.insert(
+                YamlValue::String("position".to_string()),
+                YamlValue::String(comment_position_to_str(op.position).to_string()),
+            );
+            if let Some(marker) = &op.marker {
+                mapping.insert(
+                    YamlValue::String("marker".to_string()),
+                    YamlValue::String(marker.clone()),
+                );
+            }
         }
-        Err(SpliceError::NodeNotFound) => Ok(blocks.len()),
-        Err(other) => Err(map_splice_error(other)),
-    }
-}
+        TxOperation::RemoveComment(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-fn render_heading_section(blocks: &[Block], found: &FoundNode) -> PyResult<String> {
-    if let FoundNode::Block { index, block } = found {
-        if let Some(level) = get_heading_level(block) {
-            let end_index = find_heading_section_end(blocks, *index, level);
-            return Ok(render_blocks(&blocks[*index..end_index]));
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("remove_comment".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            mapping.insert(
+                YamlValue::String("position".to_string()),
+                YamlValue::String(comment_position_to_str(op.position).to_string()),
+            );
+            if let Some(marker) = &op.marker {
+                mapping.insert(
+                    YamlValue::String("marker".to_string()),
+                    YamlValue::String(marker.clone()),
+                );
+            }
         }
-    }
-
-    Err(map_splice_error(SpliceError::SectionRequiresHeading))
-}
+        TxOperation::ReplaceInnerHtml(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
+            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")?;
 
-fn render_found_node(blocks: &[Block], found: &FoundNode) -> PyResult<String> {
-    match found {
-        FoundNode::Block { block, .. } => Ok(render_blocks(std::slice::from_ref(block))),
-        FoundNode::ListItem {
-            block_index, item, ..
-        } => match blocks.get(*block_index) {
-            Some(Block::List(list)) => {
-                let mut single_list = list.clone();
-                single_list.items = vec![(*item).clone()];
-                Ok(render_blocks(std::slice::from_ref(&Block::List(
-                    single_list,
-                ))))
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("replace_inner_html".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
             }
-            _ => Err(PyException::new_err(format!(
-                "Internal error: block at index {} is not a list",
-                block_index
-            ))),
-        },
-    }
-}
-
-fn render_blocks(blocks: &[Block]) -> String {
-    let temp_doc = Document {
-        blocks: blocks.to_vec(),
-    };
-    let mut rendered = render_markdown(&temp_doc, default_printer_config());
-    if !rendered.is_empty() && !rendered.ends_with('\n') {
-        rendered.push('\n');
-    }
-    rendered
-}
-
-fn get_heading_level(block: &Block) -> Option<u8> {
-    match block {
-        Block::Heading(heading) => match heading.kind {
-            HeadingKind::Atx(level) => Some(level),
-            HeadingKind::Setext(SetextHeading::Level1) => Some(1),
-            HeadingKind::Setext(SetextHeading::Level2) => Some(2),
-        },
-        _ => None,
-    }
-}
-
-fn find_heading_section_end(blocks: &[Block], heading_index: usize, target_level: u8) -> usize {
-    let mut end = blocks.len();
-    for (idx, block) in blocks.iter().enumerate().skip(heading_index + 1) {
-        if let Some(level) = get_heading_level(block) {
-            if level <= target_level {
-                end = idx;
-                break;
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if let Some(content) = &op.content {
+                mapping.insert(
+                    YamlValue::String("content".to_string()),
+                    YamlValue::String(content.clone()),
+                );
             }
         }
-    }
-    end
-}
-
-/// Produce a unified diff between two Markdown strings.
-///
-/// The optional ``fromfile`` and ``tofile`` labels appear in the diff header.
-#[pyfunction]
-#[pyo3(signature = (before, after, *, fromfile="original", tofile="modified"))]
-fn diff_unified(before: &str, after: &str, fromfile: &str, tofile: &str) -> PyResult<String> {
-    let diff = TextDiff::from_lines(before, after)
-        .unified_diff()
-        .header(fromfile, tofile)
-        .to_string();
-    Ok(diff)
-}
-
-/// Parse YAML or JSON operation definitions into Python dataclasses.
-///
-/// The ``format`` parameter can force ``"yaml"`` or ``"json"``. When omitted
-/// the loader first attempts YAML then falls back to JSON, matching the CLI.
-#[pyfunction]
-#[pyo3(signature = (text, *, format=None))]
-fn loads_operations(py: Python<'_>, text: &str, format: Option<&str>) -> PyResult<Py<PyAny>> {
-    let operations = parse_operations(text, format).map_err(map_splice_error)?;
-    let types_module = py.import("md_splice.types")?;
-    let py_list = PyList::empty(py);
-
-    for operation in &operations {
-        let py_op = tx_operation_to_py(py, &types_module, operation)?;
-        py_list.append(py_op)?;
-    }
-
-    Ok(py_list.into_any().unbind())
-}
-
-/// Serialize Python operation dataclasses to YAML or JSON.
-///
-/// ``format`` defaults to ``"yaml"``; specifying ``"json"`` returns formatted
-/// JSON compatible with the CLI tooling.
-#[pyfunction]
-#[pyo3(signature = (operations, *, format="yaml"))]
-fn dumps_operations(
-    py: Python<'_>,
-    operations: &Bound<'_, PyAny>,
-    format: &str,
-) -> PyResult<String> {
-    let tx_operations = py_operations_to_rust(py, operations)?;
-    let yaml_operations = tx_operations
-        .iter()
-        .map(tx_operation_to_yaml_value)
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(map_splice_error)?;
-    let normalized = format.to_ascii_lowercase();
-
-    match normalized.as_str() {
-        "yaml" => serde_yaml::to_string(&yaml_operations)
-            .map_err(|err| map_splice_error(SpliceError::OperationParse(err.to_string()))),
-        "json" => serde_json::to_string_pretty(&yaml_operations)
-            .map_err(|err| map_splice_error(SpliceError::OperationParse(err.to_string()))),
-        other => Err(PyValueError::new_err(format!(
-            "Unsupported operations format: {other}"
-        ))),
-    }
-}
+        TxOperation::Wrap(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-fn parse_operations(text: &str, format: Option<&str>) -> Result<Vec<TxOperation>, SpliceError> {
-    let normalized = format.map(|value| value.to_ascii_lowercase());
-    match normalized.as_deref() {
-        Some("yaml") => serde_yaml::from_str(text)
-            .map_err(|err| SpliceError::OperationParse(err.to_string())),
-        Some("json") => serde_json::from_str(text)
-            .map_err(|err| SpliceError::OperationParse(err.to_string())),
-        Some(other) => Err(SpliceError::OperationParse(format!(
-            "Unsupported operations format: {other}"
-        ))),
-        None => match serde_yaml::from_str(text) {
-            Ok(value) => Ok(value),
-            Err(yaml_err) => serde_json::from_str(text).map_err(|json_err| {
-                SpliceError::OperationParse(format!(
-                    "Failed to parse operations as YAML ({yaml_err}). Attempt to parse as JSON also failed ({json_err})."
-                ))
-            }),
-        },
-    }
-}
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("wrap".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if let Some(alert_type) = op.alert_type {
+                mapping.insert(
+                    YamlValue::String("alert_type".to_string()),
+                    YamlValue::String(alert_type_to_str(alert_type).to_string()),
+                );
+            }
+            if op.section {
+                mapping.insert(
+                    YamlValue::String("section".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+            if let Some(until) = &op.until {
+                mapping.insert(
+                    YamlValue::String("until".to_string()),
+                    tx_selector_to_yaml_value(until),
+                );
+            }
+            if let Some(until_ref) = &op.until_ref {
+                mapping.insert(
+                    YamlValue::String("until_ref".to_string()),
+                    YamlValue::String(until_ref.clone()),
+                );
+            }
+        }
+        TxOperation::Unwrap(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-fn tx_operation_to_py(
-    py: Python<'_>,
-    types_module: &Bound<'_, PyModule>,
-    operation: &TxOperation,
-) -> PyResult<Py<PyAny>> {
-    match operation {
-        TxOperation::Insert(op) => {
-            ensure_operation_field_absent(op.comment.as_ref(), "comment")
-                .map_err(map_splice_error)?;
-            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")
-                .map_err(map_splice_error)?;
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("unwrap".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+        }
+        TxOperation::WrapDetails(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-            let class = types_module
-                .getattr("InsertOperation")?
-                .cast_into::<PyType>()?;
-            let kwargs = PyDict::new(py);
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("wrap_details".to_string()),
+            );
             if let Some(selector) = &op.selector {
-                let selector_value = tx_selector_to_py(py, types_module, selector)?;
-                kwargs.set_item("selector", selector_value)?;
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
             }
-            if let Some(selector_ref) = &op.selector_ref {
-                kwargs.set_item("selector_ref", selector_ref)?;
+            mapping.insert(
+                YamlValue::String("summary".to_string()),
+                YamlValue::String(op.summary.clone()),
+            );
+            if op.open {
+                mapping.insert(YamlValue::String("open".to_string()), YamlValue::Bool(true));
             }
-            if let Some(content) = &op.content {
-                kwargs.set_item("content", content)?;
+            if op.section {
+                mapping.insert(
+                    YamlValue::String("section".to_string()),
+                    YamlValue::Bool(true),
+                );
+            }
+            if let Some(until) = &op.until {
+                mapping.insert(
+                    YamlValue::String("until".to_string()),
+                    tx_selector_to_yaml_value(until),
+                );
+            }
+            if let Some(until_ref) = &op.until_ref {
+                mapping.insert(
+                    YamlValue::String("until_ref".to_string()),
+                    YamlValue::String(until_ref.clone()),
+                );
             }
-            let position = insert_position_to_py(py, types_module, op.position)?;
-            kwargs.set_item("position", position)?;
-            let instance = class.call((), Some(&kwargs))?;
-            Ok(instance.into_any().unbind())
         }
-        TxOperation::Replace(op) => {
-            ensure_operation_field_absent(op.comment.as_ref(), "comment")
-                .map_err(map_splice_error)?;
-            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")
-                .map_err(map_splice_error)?;
+        TxOperation::UnwrapDetails(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-            let class = types_module
-                .getattr("ReplaceOperation")?
-                .cast_into::<PyType>()?;
-            let kwargs = PyDict::new(py);
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("unwrap_details".to_string()),
+            );
             if let Some(selector) = &op.selector {
-                let selector_value = tx_selector_to_py(py, types_module, selector)?;
-                kwargs.set_item("selector", selector_value)?;
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
             }
             if let Some(selector_ref) = &op.selector_ref {
-                kwargs.set_item("selector_ref", selector_ref)?;
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
             }
-            if let Some(content) = &op.content {
-                kwargs.set_item("content", content)?;
+        }
+        TxOperation::SetCodeLanguage(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
+
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("set_code_language".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
             }
-            if let Some(until) = &op.until {
-                let until_selector = tx_selector_to_py(py, types_module, until)?;
-                kwargs.set_item("until", until_selector)?;
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
             }
-            if let Some(until_ref) = &op.until_ref {
-                kwargs.set_item("until_ref", until_ref)?;
+            if let Some(language) = &op.language {
+                mapping.insert(
+                    YamlValue::String("language".to_string()),
+                    YamlValue::String(language.clone()),
+                );
             }
-            let instance = class.call((), Some(&kwargs))?;
-            Ok(instance.into_any().unbind())
         }
-        TxOperation::Delete(op) => {
-            ensure_operation_field_absent(op.comment.as_ref(), "comment")
-                .map_err(map_splice_error)?;
+        TxOperation::SetCodeAttribute(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-            let class = types_module
-                .getattr("DeleteOperation")?
-                .cast_into::<PyType>()?;
-            let kwargs = PyDict::new(py);
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("set_code_attribute".to_string()),
+            );
             if let Some(selector) = &op.selector {
-                let selector_value = tx_selector_to_py(py, types_module, selector)?;
-                kwargs.set_item("selector", selector_value)?;
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
             }
             if let Some(selector_ref) = &op.selector_ref {
-                kwargs.set_item("selector_ref", selector_ref)?;
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
             }
-            kwargs.set_item("section", op.section)?;
-            if let Some(until) = &op.until {
-                let until_selector = tx_selector_to_py(py, types_module, until)?;
-                kwargs.set_item("until", until_selector)?;
+            mapping.insert(
+                YamlValue::String("attribute".to_string()),
+                YamlValue::String(op.attribute.clone()),
+            );
+            if let Some(value) = &op.value {
+                mapping.insert(
+                    YamlValue::String("value".to_string()),
+                    YamlValue::String(value.clone()),
+                );
             }
-            if let Some(until_ref) = &op.until_ref {
-                kwargs.set_item("until_ref", until_ref)?;
+            if op.remove {
+                mapping.insert(
+                    YamlValue::String("remove".to_string()),
+                    YamlValue::Bool(true),
+                );
             }
-            let instance = class.call((), Some(&kwargs))?;
-            Ok(instance.into_any().unbind())
         }
-        TxOperation::SetFrontmatter(op) => {
-            ensure_operation_field_absent(op.comment.as_ref(), "comment")
-                .map_err(map_splice_error)?;
-            ensure_operation_field_absent(op.value_file.as_ref(), "value_file")
-                .map_err(map_splice_error)?;
+        TxOperation::InsertTableRow(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-            let class = types_module
-                .getattr("SetFrontmatterOperation")?
-                .cast_into::<PyType>()?;
-            let kwargs = PyDict::new(py);
-            kwargs.set_item("key", &op.key)?;
-            let value = match &op.value {
-                Some(value) => yaml_value_to_py(py, value)?,
-                None => py.None(),
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("insert_table_row".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            let row = match &op.row {
+                TableRow::Markdown(markdown) => YamlValue::String(markdown.clone()),
+                TableRow::Cells(cells) => {
+                    YamlValue::Sequence(cells.iter().cloned().map(YamlValue::String).collect())
+                }
             };
-            kwargs.set_item("value", value)?;
-            if let Some(format) = op.format {
-                let format_value = frontmatter_format_to_py(py, types_module, format)?;
-                kwargs.set_item("format", format_value)?;
+            mapping.insert(YamlValue::String("row".to_string()), row);
+            if op.position != TxInsertPosition::After {
+                mapping.insert(
+                    YamlValue::String("position".to_string()),
+                    YamlValue::String(insert_position_to_str(op.position).to_string()),
+                );
+            }
+            if let Some(row_contains) = &op.row_contains {
+                mapping.insert(
+                    YamlValue::String("row_contains".to_string()),
+                    YamlValue::String(row_contains.clone()),
+                );
+            }
+            if let Some(row_regex) = &op.row_regex {
+                mapping.insert(
+                    YamlValue::String("row_regex".to_string()),
+                    YamlValue::String(row_regex.clone()),
+                );
             }
-            let instance = class.call((), Some(&kwargs))?;
-            Ok(instance.into_any().unbind())
-        }
-        TxOperation::DeleteFrontmatter(op) => {
-            ensure_operation_field_absent(op.comment.as_ref(), "comment")
-                .map_err(map_splice_error)?;
-
-            let class = types_module
-                .getattr("DeleteFrontmatterOperation")?
-                .cast_into::<PyType>()?;
-            let kwargs = PyDict::new(py);
-            kwargs.set_item("key", &op.key)?;
-            let instance = class.call((), Some(&kwargs))?;
-            Ok(instance.into_any().unbind())
         }
-        TxOperation::ReplaceFrontmatter(op) => {
-            ensure_operation_field_absent(op.comment.as_ref(), "comment")
-                .map_err(map_splice_error)?;
-            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")
-                .map_err(map_splice_error)?;
+        TxOperation::DeleteTableRow(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
-            let class = types_module
-                .getattr("ReplaceFrontmatterOperation")?
-                .cast_into::<PyType>()?;
-            let kwargs = PyDict::new(py);
-            let content = match &op.content {
-                Some(value) => yaml_value_to_py(py, value)?,
-                None => py.None(),
-            };
-            kwargs.set_item("content", content)?;
-            if let Some(format) = op.format {
-                let format_value = frontmatter_format_to_py(py, types_module, format)?;
-                kwargs.set_item("format", format_value)?;
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("delete_table_row".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if let Some(row_contains) = &op.row_contains {
+                mapping.insert(
+                    YamlValue::String("row_contains".to_string()),
+                    YamlValue::String(row_contains.clone()),
+                );
+            }
+            if let Some(row_regex) = &op.row_regex {
+                mapping.insert(
+                    YamlValue::String("row_regex".to_string()),
+                    YamlValue::String(row_regex.clone()),
+                );
             }
-            let instance = class.call((), Some(&kwargs))?;
-            Ok(instance.into_any().unbind())
         }
-    }
-}
-
-fn tx_operation_to_yaml_value(operation: &TxOperation) -> Result<YamlValue, SpliceError> {
-    let mut mapping = YamlMapping::new();
-
-    match operation {
-        TxOperation::Insert(op) => {
+        TxOperation::AddTableColumn(op) => {
             ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
-            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")?;
 
             mapping.insert(
                 YamlValue::String("op".to_string()),
-                YamlValue::String("insert".to_string()),
+                YamlValue::String("add_table_column".to_string()),
             );
             if let Some(selector) = &op.selector {
                 mapping.insert(
@@ -1150,10 +4451,14 @@ fn tx_operation_to_yaml_value(operation: &TxOperation) -> Result<YamlValue, Spli
                     YamlValue::String(selector_ref.clone()),
                 );
             }
-            if let Some(content) = &op.content {
+            mapping.insert(
+                YamlValue::String("header".to_string()),
+                YamlValue::String(op.header.clone()),
+            );
+            if let Some(value) = &op.value {
                 mapping.insert(
-                    YamlValue::String("content".to_string()),
-                    YamlValue::String(content.clone()),
+                    YamlValue::String("value".to_string()),
+                    YamlValue::String(value.clone()),
                 );
             }
             if op.position != TxInsertPosition::After {
@@ -1162,14 +4467,49 @@ fn tx_operation_to_yaml_value(operation: &TxOperation) -> Result<YamlValue, Spli
                     YamlValue::String(insert_position_to_str(op.position).to_string()),
                 );
             }
+            if let Some(column) = &op.column {
+                mapping.insert(
+                    YamlValue::String("column".to_string()),
+                    YamlValue::String(column.clone()),
+                );
+            }
+            if op.alignment != TxColumnAlignment::None {
+                mapping.insert(
+                    YamlValue::String("alignment".to_string()),
+                    YamlValue::String(column_alignment_to_str(op.alignment).to_string()),
+                );
+            }
+        }
+        TxOperation::RemoveTableColumn(op) => {
+            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
+
+            mapping.insert(
+                YamlValue::String("op".to_string()),
+                YamlValue::String("remove_table_column".to_string()),
+            );
+            if let Some(selector) = &op.selector {
+                mapping.insert(
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
+                );
+            }
+            if let Some(selector_ref) = &op.selector_ref {
+                mapping.insert(
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            mapping.insert(
+                YamlValue::String("column".to_string()),
+                YamlValue::String(op.column.clone()),
+            );
         }
-        TxOperation::Replace(op) => {
+        TxOperation::RenameTableColumn(op) => {
             ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
-            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")?;
 
             mapping.insert(
                 YamlValue::String("op".to_string()),
-                YamlValue::String("replace".to_string()),
+                YamlValue::String("rename_table_column".to_string()),
             );
             if let Some(selector) = &op.selector {
                 mapping.insert(
@@ -1183,31 +4523,21 @@ fn tx_operation_to_yaml_value(operation: &TxOperation) -> Result<YamlValue, Spli
                     YamlValue::String(selector_ref.clone()),
                 );
             }
-            if let Some(content) = &op.content {
-                mapping.insert(
-                    YamlValue::String("content".to_string()),
-                    YamlValue::String(content.clone()),
-                );
-            }
-            if let Some(until) = &op.until {
-                mapping.insert(
-                    YamlValue::String("until".to_string()),
-                    tx_selector_to_yaml_value(until),
-                );
-            }
-            if let Some(until_ref) = &op.until_ref {
-                mapping.insert(
-                    YamlValue::String("until_ref".to_string()),
-                    YamlValue::String(until_ref.clone()),
-                );
-            }
+            mapping.insert(
+                YamlValue::String("column".to_string()),
+                YamlValue::String(op.column.clone()),
+            );
+            mapping.insert(
+                YamlValue::String("header".to_string()),
+                YamlValue::String(op.header.clone()),
+            );
         }
-        TxOperation::Delete(op) => {
+        TxOperation::UpdateToc(op) => {
             ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
 
             mapping.insert(
                 YamlValue::String("op".to_string()),
-                YamlValue::String("delete".to_string()),
+                YamlValue::String("update_toc".to_string()),
             );
             if let Some(selector) = &op.selector {
                 mapping.insert(
@@ -1221,72 +4551,57 @@ fn tx_operation_to_yaml_value(operation: &TxOperation) -> Result<YamlValue, Spli
                     YamlValue::String(selector_ref.clone()),
                 );
             }
-            if op.section {
+            if let Some(min_level) = op.min_level {
                 mapping.insert(
-                    YamlValue::String("section".to_string()),
-                    YamlValue::Bool(true),
+                    YamlValue::String("min_level".to_string()),
+                    YamlValue::Number(YamlNumber::from(min_level)),
                 );
             }
-            if let Some(until) = &op.until {
+            if let Some(max_level) = op.max_level {
                 mapping.insert(
-                    YamlValue::String("until".to_string()),
-                    tx_selector_to_yaml_value(until),
+                    YamlValue::String("max_level".to_string()),
+                    YamlValue::Number(YamlNumber::from(max_level)),
                 );
             }
-            if let Some(until_ref) = &op.until_ref {
+            if op.slug_style != TxSlugStyle::default() {
                 mapping.insert(
-                    YamlValue::String("until_ref".to_string()),
-                    YamlValue::String(until_ref.clone()),
+                    YamlValue::String("slug_style".to_string()),
+                    YamlValue::String(slug_style_to_str(op.slug_style).to_string()),
                 );
             }
         }
-        TxOperation::SetFrontmatter(op) => {
+        TxOperation::Assert(op) => {
             ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
-            ensure_operation_field_absent(op.value_file.as_ref(), "value_file")?;
 
             mapping.insert(
                 YamlValue::String("op".to_string()),
-                YamlValue::String("set_frontmatter".to_string()),
-            );
-            mapping.insert(
-                YamlValue::String("key".to_string()),
-                YamlValue::String(op.key.clone()),
+                YamlValue::String("assert".to_string()),
             );
-            let value = op.value.as_ref().cloned().unwrap_or(YamlValue::Null);
-            mapping.insert(YamlValue::String("value".to_string()), value);
-            if let Some(format) = op.format {
+            if let Some(selector) = &op.selector {
                 mapping.insert(
-                    YamlValue::String("format".to_string()),
-                    YamlValue::String(frontmatter_format_to_str(format).to_string()),
+                    YamlValue::String("selector".to_string()),
+                    tx_selector_to_yaml_value(selector),
                 );
             }
-        }
-        TxOperation::DeleteFrontmatter(op) => {
-            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
-
-            mapping.insert(
-                YamlValue::String("op".to_string()),
-                YamlValue::String("delete_frontmatter".to_string()),
-            );
-            mapping.insert(
-                YamlValue::String("key".to_string()),
-                YamlValue::String(op.key.clone()),
-            );
-        }
-        TxOperation::ReplaceFrontmatter(op) => {
-            ensure_operation_field_absent(op.comment.as_ref(), "comment")?;
-            ensure_operation_field_absent(op.content_file.as_ref(), "content_file")?;
-
-            mapping.insert(
-                YamlValue::String("op".to_string()),
-                YamlValue::String("replace_frontmatter".to_string()),
-            );
-            let content = op.content.as_ref().cloned().unwrap_or(YamlValue::Null);
-            mapping.insert(YamlValue::String("content".to_string()), content);
-            if let Some(format) = op.format {
+            if let Some(selector_ref) = &op.selector_ref {
                 mapping.insert(
-                    YamlValue::String("format".to_string()),
-                    YamlValue::String(frontmatter_format_to_str(format).to_string()),
+                    YamlValue::String("selector_ref".to_string()),
+                    YamlValue::String(selector_ref.clone()),
+                );
+            }
+            if let Some(frontmatter_key) = &op.frontmatter_key {
+                mapping.insert(
+                    YamlValue::String("frontmatter_key".to_string()),
+                    YamlValue::String(frontmatter_key.clone()),
+                );
+            }
+            if let Some(equals) = &op.equals {
+                mapping.insert(YamlValue::String("equals".to_string()), equals.clone());
+            }
+            if op.expect != TxAssertExpectation::Match {
+                mapping.insert(
+                    YamlValue::String("expect".to_string()),
+                    YamlValue::String(assert_expectation_to_str(op.expect).to_string()),
                 );
             }
         }
@@ -1295,6 +4610,19 @@ fn tx_operation_to_yaml_value(operation: &TxOperation) -> Result<YamlValue, Spli
     Ok(YamlValue::Mapping(mapping))
 }
 
+fn cell_address_to_yaml_value(cell: CellAddress) -> YamlValue {
+    let mut mapping = YamlMapping::new();
+    mapping.insert(
+        YamlValue::String("row".to_string()),
+        YamlValue::Number(YamlNumber::from(cell.row as i64)),
+    );
+    mapping.insert(
+        YamlValue::String("column".to_string()),
+        YamlValue::Number(YamlNumber::from(cell.column as i64)),
+    );
+    YamlValue::Mapping(mapping)
+}
+
 fn tx_selector_to_yaml_value(selector: &TxSelector) -> YamlValue {
     let mut mapping = YamlMapping::new();
 
@@ -1316,18 +4644,120 @@ fn tx_selector_to_yaml_value(selector: &TxSelector) -> YamlValue {
             YamlValue::String(select_contains.clone()),
         );
     }
+    if let Some(select_contains_ci) = &selector.select_contains_ci {
+        mapping.insert(
+            YamlValue::String("select_contains_ci".to_string()),
+            YamlValue::String(select_contains_ci.clone()),
+        );
+    }
     if let Some(select_regex) = &selector.select_regex {
         mapping.insert(
             YamlValue::String("select_regex".to_string()),
             YamlValue::String(select_regex.clone()),
         );
     }
+    if let Some(select_not_contains) = &selector.select_not_contains {
+        mapping.insert(
+            YamlValue::String("select_not_contains".to_string()),
+            YamlValue::String(select_not_contains.clone()),
+        );
+    }
+    if let Some(select_not_regex) = &selector.select_not_regex {
+        mapping.insert(
+            YamlValue::String("select_not_regex".to_string()),
+            YamlValue::String(select_not_regex.clone()),
+        );
+    }
+    if let Some(select_fingerprint) = &selector.select_fingerprint {
+        mapping.insert(
+            YamlValue::String("select_fingerprint".to_string()),
+            YamlValue::String(select_fingerprint.clone()),
+        );
+    }
+    if let Some(select_annotation) = &selector.select_annotation {
+        mapping.insert(
+            YamlValue::String("select_annotation".to_string()),
+            YamlValue::String(select_annotation.clone()),
+        );
+    }
+    if let Some(select_tag) = &selector.select_tag {
+        mapping.insert(
+            YamlValue::String("select_tag".to_string()),
+            YamlValue::String(select_tag.clone()),
+        );
+    }
+    if let Some(select_info_contains) = &selector.select_info_contains {
+        mapping.insert(
+            YamlValue::String("select_info_contains".to_string()),
+            YamlValue::String(select_info_contains.clone()),
+        );
+    }
+    if let Some(select_slug) = &selector.select_slug {
+        mapping.insert(
+            YamlValue::String("select_slug".to_string()),
+            YamlValue::String(select_slug.clone()),
+        );
+    }
+    if let Some(select_min_words) = selector.select_min_words {
+        mapping.insert(
+            YamlValue::String("select_min_words".to_string()),
+            YamlValue::Number(YamlNumber::from(select_min_words as i64)),
+        );
+    }
+    if let Some(select_max_words) = selector.select_max_words {
+        mapping.insert(
+            YamlValue::String("select_max_words".to_string()),
+            YamlValue::Number(YamlNumber::from(select_max_words as i64)),
+        );
+    }
+    if let Some(select_empty) = selector.select_empty {
+        mapping.insert(
+            YamlValue::String("select_empty".to_string()),
+            YamlValue::Bool(select_empty),
+        );
+    }
+    if let Some(select_depth) = selector.select_depth {
+        mapping.insert(
+            YamlValue::String("select_depth".to_string()),
+            YamlValue::Number(YamlNumber::from(select_depth as i64)),
+        );
+    }
+    if selector.recursive {
+        mapping.insert(
+            YamlValue::String("recursive".to_string()),
+            YamlValue::Bool(true),
+        );
+    }
+    if let Some(task_state) = selector.task_state {
+        mapping.insert(
+            YamlValue::String("task_state".to_string()),
+            YamlValue::String(task_state_to_str(task_state).to_string()),
+        );
+    }
     if selector.select_ordinal != 1 {
         mapping.insert(
             YamlValue::String("select_ordinal".to_string()),
             YamlValue::Number(YamlNumber::from(selector.select_ordinal as i64)),
         );
     }
+    if selector.select_last {
+        mapping.insert(
+            YamlValue::String("select_last".to_string()),
+            YamlValue::Bool(true),
+        );
+    }
+    if let Some(max_matches) = selector.max_matches {
+        mapping.insert(
+            YamlValue::String("max_matches".to_string()),
+            YamlValue::Number(YamlNumber::from(max_matches as i64)),
+        );
+    }
+    if selector.tie_break != TieBreak::default() {
+        mapping.insert(
+            YamlValue::String("tie_break".to_string()),
+            YamlValue::String(tie_break_to_str(selector.tie_break).to_string()),
+        );
+    }
     if let Some(after) = &selector.after {
         mapping.insert(
             YamlValue::String("after".to_string()),
@@ -1352,6 +4782,44 @@ fn tx_selector_to_yaml_value(selector: &TxSelector) -> YamlValue {
             YamlValue::String(within_ref.clone()),
         );
     }
+    if let Some(between) = &selector.between {
+        let mut between_mapping = YamlMapping::new();
+        for (name, landmark) in [("start", &between.start), ("end", &between.end)] {
+            if let Some(landmark) = landmark {
+                between_mapping.insert(
+                    YamlValue::String(name.to_string()),
+                    tx_selector_to_yaml_value(landmark),
+                );
+            }
+        }
+        for (name, landmark_ref) in [
+            ("start_ref", &between.start_ref),
+            ("end_ref", &between.end_ref),
+        ] {
+            if let Some(landmark_ref) = landmark_ref {
+                between_mapping.insert(
+                    YamlValue::String(name.to_string()),
+                    YamlValue::String(landmark_ref.clone()),
+                );
+            }
+        }
+        mapping.insert(
+            YamlValue::String("between".to_string()),
+            YamlValue::Mapping(between_mapping),
+        );
+    }
+    if let Some(within_slide) = selector.within_slide {
+        mapping.insert(
+            YamlValue::String("within_slide".to_string()),
+            YamlValue::Number(YamlNumber::from(within_slide as i64)),
+        );
+    }
+    if let Some(at_line) = selector.at_line {
+        mapping.insert(
+            YamlValue::String("at_line".to_string()),
+            YamlValue::Number(YamlNumber::from(at_line as i64)),
+        );
+    }
 
     YamlValue::Mapping(mapping)
 }
@@ -1373,12 +4841,69 @@ fn tx_selector_to_py(
     if let Some(select_contains) = &selector.select_contains {
         kwargs.set_item("select_contains", select_contains)?;
     }
+    if let Some(select_contains_ci) = &selector.select_contains_ci {
+        kwargs.set_item("select_contains_ci", select_contains_ci)?;
+    }
     if let Some(select_regex) = &selector.select_regex {
         kwargs.set_item("select_regex", select_regex)?;
     }
+    if let Some(select_not_contains) = &selector.select_not_contains {
+        kwargs.set_item("select_not_contains", select_not_contains)?;
+    }
+    if let Some(select_not_regex) = &selector.select_not_regex {
+        kwargs.set_item("select_not_regex", select_not_regex)?;
+    }
+    if let Some(select_fingerprint) = &selector.select_fingerprint {
+        kwargs.set_item("select_fingerprint", select_fingerprint)?;
+    }
+    if let Some(select_annotation) = &selector.select_annotation {
+        kwargs.set_item("select_annotation", select_annotation)?;
+    }
+    if let Some(select_tag) = &selector.select_tag {
+        kwargs.set_item("select_tag", select_tag)?;
+    }
+    if let Some(select_info_contains) = &selector.select_info_contains {
+        kwargs.set_item("select_info_contains", select_info_contains)?;
+    }
+    if let Some(select_slug) = &selector.select_slug {
+        kwargs.set_item("select_slug", select_slug)?;
+    }
+    if let Some(select_min_words) = selector.select_min_words {
+        kwargs.set_item("select_min_words", select_min_words)?;
+    }
+    if let Some(select_max_words) = selector.select_max_words {
+        kwargs.set_item("select_max_words", select_max_words)?;
+    }
+    if let Some(select_empty) = selector.select_empty {
+        kwargs.set_item("select_empty", select_empty)?;
+    }
+    if let Some(select_depth) = selector.select_depth {
+        kwargs.set_item("select_depth", select_depth)?;
+    }
+    if selector.recursive {
+        kwargs.set_item("recursive", true)?;
+    }
+    if let Some(task_state) = selector.task_state {
+        kwargs.set_item(
+            "task_state",
+            task_state_to_py(py, types_module, task_state)?,
+        )?;
+    }
     if selector.select_ordinal != 1 {
         kwargs.set_item("select_ordinal", selector.select_ordinal)?;
     }
+    if selector.select_last {
+        kwargs.set_item("select_last", true)?;
+    }
+    if let Some(max_matches) = selector.max_matches {
+        kwargs.set_item("max_matches", max_matches)?;
+    }
+    if selector.tie_break != TieBreak::default() {
+        kwargs.set_item(
+            "tie_break",
+            tie_break_to_py(py, types_module, selector.tie_break)?,
+        )?;
+    }
     if let Some(after) = &selector.after {
         let nested = tx_selector_to_py(py, types_module, after)?;
         kwargs.set_item("after", nested)?;
@@ -1393,6 +4918,24 @@ fn tx_selector_to_py(
     if let Some(within_ref) = &selector.within_ref {
         kwargs.set_item("within_ref", within_ref)?;
     }
+    if let Some(between) = &selector.between {
+        let between_kwargs = PyDict::new(py);
+        for (name, landmark) in [("start", &between.start), ("end", &between.end)] {
+            if let Some(landmark) = landmark {
+                between_kwargs.set_item(name, tx_selector_to_py(py, types_module, landmark)?)?;
+            }
+        }
+        between_kwargs.set_item("start_ref", &between.start_ref)?;
+        between_kwargs.set_item("end_ref", &between.end_ref)?;
+        let between_class = types_module.getattr("Between")?;
+        kwargs.set_item("between", between_class.call((), Some(&between_kwargs))?)?;
+    }
+    if let Some(within_slide) = selector.within_slide {
+        kwargs.set_item("within_slide", within_slide)?;
+    }
+    if let Some(at_line) = selector.at_line {
+        kwargs.set_item("at_line", at_line)?;
+    }
 
     let instance = class.call((), Some(&kwargs))?;
     Ok(instance.into_any().unbind())
@@ -1422,6 +4965,158 @@ fn insert_position_to_str(position: TxInsertPosition) -> &'static str {
     }
 }
 
+fn alert_type_to_py(
+    _py: Python<'_>,
+    types_module: &Bound<'_, PyModule>,
+    alert_type: TxAlertType,
+) -> PyResult<Py<PyAny>> {
+    let enum_class = types_module.getattr("AlertType")?;
+    let variant_name = match alert_type {
+        TxAlertType::Note => "NOTE",
+        TxAlertType::Tip => "TIP",
+        TxAlertType::Important => "IMPORTANT",
+        TxAlertType::Warning => "WARNING",
+        TxAlertType::Caution => "CAUTION",
+    };
+    Ok(enum_class.getattr(variant_name)?.into_any().unbind())
+}
+
+fn alert_type_to_str(alert_type: TxAlertType) -> &'static str {
+    match alert_type {
+        TxAlertType::Note => "note",
+        TxAlertType::Tip => "tip",
+        TxAlertType::Important => "important",
+        TxAlertType::Warning => "warning",
+        TxAlertType::Caution => "caution",
+    }
+}
+
+fn column_alignment_to_py(
+    _py: Python<'_>,
+    types_module: &Bound<'_, PyModule>,
+    alignment: TxColumnAlignment,
+) -> PyResult<Py<PyAny>> {
+    let enum_class = types_module.getattr("ColumnAlignment")?;
+    let variant_name = match alignment {
+        TxColumnAlignment::None => "NONE",
+        TxColumnAlignment::Left => "LEFT",
+        TxColumnAlignment::Center => "CENTER",
+        TxColumnAlignment::Right => "RIGHT",
+    };
+    Ok(enum_class.getattr(variant_name)?.into_any().unbind())
+}
+
+fn column_alignment_to_str(alignment: TxColumnAlignment) -> &'static str {
+    match alignment {
+        TxColumnAlignment::None => "none",
+        TxColumnAlignment::Left => "left",
+        TxColumnAlignment::Center => "center",
+        TxColumnAlignment::Right => "right",
+    }
+}
+
+fn comment_position_to_py(
+    _py: Python<'_>,
+    types_module: &Bound<'_, PyModule>,
+    position: TxCommentPosition,
+) -> PyResult<Py<PyAny>> {
+    let enum_class = types_module.getattr("CommentPosition")?;
+    let variant_name = match position {
+        TxCommentPosition::Before => "BEFORE",
+        TxCommentPosition::After => "AFTER",
+    };
+    Ok(enum_class.getattr(variant_name)?.into_any().unbind())
+}
+
+fn comment_position_to_str(position: TxCommentPosition) -> &'static str {
+    match position {
+        TxCommentPosition::Before => "before",
+        TxCommentPosition::After => "after",
+    }
+}
+
+fn slug_style_to_py(
+    _py: Python<'_>,
+    types_module: &Bound<'_, PyModule>,
+    style: TxSlugStyle,
+) -> PyResult<Py<PyAny>> {
+    let enum_class = types_module.getattr("SlugStyle")?;
+    let variant_name = match style {
+        TxSlugStyle::Github => "GITHUB",
+        TxSlugStyle::Gitlab => "GITLAB",
+    };
+    Ok(enum_class.getattr(variant_name)?.into_any().unbind())
+}
+
+fn slug_style_to_str(style: TxSlugStyle) -> &'static str {
+    match style {
+        TxSlugStyle::Github => "github",
+        TxSlugStyle::Gitlab => "gitlab",
+    }
+}
+
+fn assert_expectation_to_py(
+    _py: Python<'_>,
+    types_module: &Bound<'_, PyModule>,
+    expect: TxAssertExpectation,
+) -> PyResult<Py<PyAny>> {
+    let enum_class = types_module.getattr("AssertExpectation")?;
+    let variant_name = match expect {
+        TxAssertExpectation::Match => "MATCH",
+        TxAssertExpectation::NoMatch => "NO_MATCH",
+    };
+    Ok(enum_class.getattr(variant_name)?.into_any().unbind())
+}
+
+fn assert_expectation_to_str(expect: TxAssertExpectation) -> &'static str {
+    match expect {
+        TxAssertExpectation::Match => "match",
+        TxAssertExpectation::NoMatch => "no_match",
+    }
+}
+
+fn task_state_to_py(
+    _py: Python<'_>,
+    types_module: &Bound<'_, PyModule>,
+    task_state: TaskStatus,
+) -> PyResult<Py<PyAny>> {
+    let enum_class = types_module.getattr("TaskState")?;
+    let variant_name = match task_state {
+        TaskStatus::Complete => "COMPLETE",
+        TaskStatus::Incomplete => "INCOMPLETE",
+        TaskStatus::Any => "ANY",
+    };
+    Ok(enum_class.getattr(variant_name)?.into_any().unbind())
+}
+
+fn task_state_to_str(task_state: TaskStatus) -> &'static str {
+    match task_state {
+        TaskStatus::Complete => "complete",
+        TaskStatus::Incomplete => "incomplete",
+        TaskStatus::Any => "any",
+    }
+}
+
+fn tie_break_to_py(
+    _py: Python<'_>,
+    types_module: &Bound<'_, PyModule>,
+    tie_break: TieBreak,
+) -> PyResult<Py<PyAny>> {
+    let enum_class = types_module.getattr("TieBreak")?;
+    let variant_name = match tie_break {
+        TieBreak::DocumentOrder => "DOCUMENT_ORDER",
+        TieBreak::DeepestFirst => "DEEPEST_FIRST",
+    };
+    Ok(enum_class.getattr(variant_name)?.into_any().unbind())
+}
+
+fn tie_break_to_str(tie_break: TieBreak) -> &'static str {
+    match tie_break {
+        TieBreak::DocumentOrder => "document_order",
+        TieBreak::DeepestFirst => "deepest_first",
+    }
+}
+
 fn frontmatter_format_to_py(
     _py: Python<'_>,
     types_module: &Bound<'_, PyModule>,
@@ -1472,11 +5167,15 @@ fn create_backup(path: &Path) -> PyResult<PathBuf> {
     backup_name.push("~");
     let backup_path = PathBuf::from(backup_name);
 
-    fs::copy(path, &backup_path).map_err(|err| map_io_error(err))?;
+    fs::copy(path, &backup_path).map_err(map_io_error)?;
     Ok(backup_path)
 }
 
 fn write_atomic(path: &Path, content: &str) -> PyResult<()> {
+    write_atomic_io(path, content).map_err(map_io_error)
+}
+
+fn write_atomic_io(path: &Path, content: &str) -> io::Result<()> {
     let parent = match path.parent() {
         Some(parent) if !parent.as_os_str().is_empty() => parent,
         Some(_) | None => Path::new("."),
@@ -1485,16 +5184,11 @@ fn write_atomic(path: &Path, content: &str) -> PyResult<()> {
     let mut temp_file = TempFileBuilder::new()
         .prefix(".md-splice-")
         .suffix(".tmp")
-        .tempfile_in(parent)
-        .map_err(|err| map_io_error(io::Error::new(io::ErrorKind::Other, err.to_string())))?;
-
-    temp_file
-        .write_all(content.as_bytes())
-        .map_err(|err| map_io_error(err))?;
-    temp_file.flush().map_err(|err| map_io_error(err))?;
-    temp_file
-        .persist(path)
-        .map_err(|err| map_io_error(err.error))?;
+        .tempfile_in(parent)?;
+
+    temp_file.write_all(content.as_bytes())?;
+    temp_file.flush()?;
+    temp_file.persist(path).map_err(|err| err.error)?;
     Ok(())
 }
 
@@ -8,21 +8,67 @@ from textwrap import dedent
 import pytest
 
 from md_splice import (
+    AddTableColumnOperation,
+    AlertType,
+    ApplyWarning,
+    AssertExpectation,
+    AssertOperation,
+    Between,
+    Capture,
+    ColumnAlignment,
+    CopyOperation,
+    DedupeHeadingsOperation,
     DeleteFrontmatterOperation,
     DeleteOperation,
+    DeleteTableRowOperation,
     FrontmatterFormat,
+    GetOperation,
+    ImportSectionOperation,
     InsertOperation,
     InsertPosition,
+    InsertTableRowOperation,
     MarkdownDocument,
+    MergeSectionsOperation,
+    MoveOperation,
+    PruneEmptySectionsOperation,
+    RemoveAlertOperation,
+    RemoveCommentOperation,
+    RemoveTableColumnOperation,
+    ReplaceInnerHtmlOperation,
+    RenameHeadingOperation,
+    RenameTableColumnOperation,
+    RenumberOperation,
     ReplaceOperation,
+    ReplaceTextOperation,
     Selector,
+    SetAlertOperation,
+    SetCodeAttributeOperation,
+    SetCodeLanguageOperation,
+    SetCommentOperation,
     SetFrontmatterOperation,
+    ShiftHeadingOperation,
+    SlugStyle,
+    SwapOperation,
+    TaskState,
+    TieBreak,
+    UnwrapDetailsOperation,
+    UnwrapOperation,
+    UpdateTocOperation,
+    UpsertOperation,
+    WrapDetailsOperation,
+    WrapOperation,
+    diff_structural,
     diff_unified,
+    merge,
 )
 from md_splice.errors import (
+    AssertionFailedError,
+    ConflictingOperationsError,
+    NodeNotFoundError,
     OperationFailedError,
     SelectorAliasAlreadyDefinedError,
     SelectorAliasNotDefinedError,
+    TooManyMatchesError,
 )
 
 
@@ -182,6 +228,31 @@ def test_apply_warns_on_ambiguity() -> None:
     assert captured == []
 
 
+def test_apply_returns_structured_warnings() -> None:
+    doc = MarkdownDocument.from_string("Paragraph one.\n\nParagraph two.\n")
+
+    result = doc.apply(
+        [
+            ReplaceOperation(
+                selector=Selector(select_type="p"),
+                content="Updated paragraph.",
+            )
+        ],
+        warn_on_ambiguity=False,
+    )
+
+    assert result == [
+        ApplyWarning(
+            kind="ambiguous_selector",
+            operation_index=0,
+            message=(
+                "selector of `replace p` matched multiple nodes (block 0, "
+                "block 1); the operation was applied to the first match only"
+            ),
+        )
+    ]
+
+
 def test_apply_frontmatter_operations_modify_payload() -> None:
     doc = MarkdownDocument.from_string(
         dedent(
@@ -216,7 +287,7 @@ def test_apply_is_atomic_on_failure() -> None:
     doc = MarkdownDocument.from_string("Paragraph.\n")
     original = doc.render()
 
-    with pytest.raises(OperationFailedError):
+    with pytest.raises(NodeNotFoundError):
         doc.apply(
             [
                 ReplaceOperation(
@@ -259,6 +330,49 @@ def test_diff_unified_includes_custom_headers() -> None:
     assert "+Line three" in diff
 
 
+def test_diff_structural_reports_changed_blocks() -> None:
+    before = MarkdownDocument.from_string("# Title\n\nOld text.\n\n## Extra\n")
+    after = MarkdownDocument.from_string("# Title\n\nNew text.\n\nAdded.\n\n## Extra\n")
+
+    changes = diff_structural(before, after)
+
+    assert len(changes) == 1
+    change = changes[0]
+    assert change.kind == "replace"
+    assert change.original_range == (1, 2)
+    assert change.edited_range == (1, 3)
+    assert change.original == "Old text.\n"
+    assert change.edited == "New text.\n\nAdded.\n"
+    assert diff_structural(before, before) == []
+
+
+def test_merge_combines_both_sides_and_reports_conflicts() -> None:
+    base = MarkdownDocument.from_string("Intro.\n\nBody.\n\nMiddle.\n\nOutro.\n")
+    ours = MarkdownDocument.from_string(
+        "Intro, revised.\n\nBody.\n\nMiddle.\n\nOurs.\n"
+    )
+    theirs = MarkdownDocument.from_string(
+        "Intro.\n\nBody, revised.\n\nMiddle.\n\nTheirs.\n"
+    )
+
+    result = merge(base, ours, theirs)
+
+    assert result.document.render() == (
+        "Intro, revised.\n\nBody, revised.\n\nMiddle.\n\nOurs."
+    )
+    assert len(result.conflicts) == 1
+    conflict = result.conflicts[0]
+    assert conflict.base_range == (3, 4)
+    assert conflict.ours_range == (3, 4)
+    assert conflict.theirs_range == (3, 4)
+    assert (conflict.base, conflict.ours, conflict.theirs) == (
+        "Outro.\n",
+        "Ours.\n",
+        "Theirs.\n",
+    )
+    assert merge(base, base, theirs).conflicts == []
+
+
 def test_apply_supports_selector_alias_reuse() -> None:
     doc = MarkdownDocument.from_string(
         dedent(
@@ -359,3 +473,802 @@ def test_apply_errors_on_duplicate_selector_alias() -> None:
                 ),
             ]
         )
+
+
+def test_apply_rejects_conflicting_operations() -> None:
+    doc = MarkdownDocument.from_string("# Notes\n\nLegacy entry.\n\nSummary.\n")
+    original = doc.render()
+
+    with pytest.raises(ConflictingOperationsError, match="deleted by operation 1"):
+        doc.apply(
+            [
+                DeleteOperation(
+                    selector=Selector(alias="legacy", select_contains="Legacy"),
+                ),
+                ReplaceOperation(selector_ref="legacy", content="Updated entry."),
+            ]
+        )
+
+    assert doc.render() == original
+
+
+def test_apply_warns_when_a_replaced_selector_still_matches() -> None:
+    doc = MarkdownDocument.from_string("Draft one.\n\nDraft two.\n")
+    selector = Selector(select_type="p", select_contains="Draft")
+
+    with pytest.warns(UserWarning):
+        warnings = doc.apply(
+            [
+                ReplaceOperation(selector=selector, content="Final."),
+                ReplaceOperation(selector=selector, content="Final."),
+            ]
+        )
+
+    assert doc.render() == "Final.\n\nFinal."
+    assert [(w.kind, w.operation_index) for w in warnings] == [
+        ("ambiguous_selector", 0),
+        ("possible_conflict", 1),
+    ]
+
+
+def test_semantically_equals_ignores_formatting_differences() -> None:
+    setext = MarkdownDocument.from_string("Title\n=====\n\n* one\n* two\n")
+    atx = MarkdownDocument.from_string("# Title\n\n- one\n- two\n")
+    changed = MarkdownDocument.from_string("# Title\n\n- one\n- three\n")
+
+    assert setext.semantically_equals(atx)
+    assert not setext.semantically_equals(changed)
+
+
+def test_rebase_links_rewrites_relative_paths() -> None:
+    doc = MarkdownDocument.from_string(
+        "See [setup](setup.md#install), ![logo](../img/logo.png), and "
+        "[home](https://example.com).\n"
+    )
+
+    assert doc.rebase_links("docs/guide", "docs") == 2
+    assert doc.render() == (
+        "See [setup](guide/setup.md#install), ![logo](img/logo.png), and\n"
+        "[home](https://example.com)."
+    )
+
+
+def test_apply_prune_empty_sections_removes_empty_headings() -> None:
+    doc = MarkdownDocument.from_string(
+        dedent(
+            """
+            # Guide
+
+            ## Empty
+
+            ### Also empty
+
+            ## Setup
+
+            Steps.
+            """
+        ).lstrip()
+    )
+
+    doc.apply([PruneEmptySectionsOperation(min_level=2)])
+
+    assert doc.render() == "# Guide\n\n## Setup\n\nSteps."
+
+
+def test_prune_empty_sections_rejects_within_and_within_ref() -> None:
+    with pytest.raises(ValueError):
+        PruneEmptySectionsOperation(within=Selector(select_type="h1"), within_ref="intro")
+
+
+def test_apply_dedupe_headings_renames_or_reports_duplicates() -> None:
+    source = "# Guide\n\n## Usage\n\n## Usage\n"
+
+    doc = MarkdownDocument.from_string(source)
+    result = doc.apply([DedupeHeadingsOperation(report_only=True)])
+
+    assert doc.render() == source.rstrip("\n")
+    assert result == [
+        ApplyWarning(
+            kind="duplicate_heading",
+            operation_index=0,
+            message="heading 'Usage' appears 2 times",
+        )
+    ]
+
+    doc.apply([DedupeHeadingsOperation()])
+
+    assert doc.render() == "# Guide\n\n## Usage\n\n## Usage (2)"
+
+
+def test_apply_replace_text_keeps_markup_and_reports_misses() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Guide\n\nInstall **mdsplice** with `cargo install mdsplice`.\n"
+    )
+
+    result = doc.apply(
+        [
+            ReplaceTextOperation(
+                pattern=r"\bmdsplice\b",
+                replacement="md-splice",
+                selector=Selector(select_type="h1"),
+                section=True,
+            ),
+            ReplaceTextOperation(
+                pattern="colour",
+                replacement="color",
+                selector=Selector(select_type="p"),
+            ),
+        ]
+    )
+
+    assert doc.render() == (
+        "# Guide\n\nInstall **md-splice** with `cargo install mdsplice`."
+    )
+    assert [warning.kind for warning in result] == ["skipped_operation"]
+
+
+def test_apply_renumber_continues_an_interrupted_procedure() -> None:
+    doc = MarkdownDocument.from_string(
+        "1. Download\n2. Unpack\n\n```sh\nmake\n```\n\n3. Run\n"
+    )
+
+    doc.apply(
+        [
+            DeleteOperation(
+                selector=Selector(select_type="li", select_contains="Download"),
+                renumber=True,
+            )
+        ]
+    )
+
+    assert doc.render() == "1. Unpack\n\n```sh\nmake\n```\n\n2. Run"
+
+    doc = MarkdownDocument.from_string("1. One\n\nNote.\n\n5. Two\n")
+    doc.apply([RenumberOperation()])
+
+    assert doc.render() == "1. One\n\nNote.\n\n2. Two"
+
+
+def test_apply_import_section_replaces_section_from_other_file(tmp_path) -> None:
+    shared = tmp_path / "shared.md"
+    shared.write_text("# Shared\n\n## Support\n\nAsk in the forum.\n\n## Other\n")
+    doc = MarkdownDocument.from_string("# Guide\n\n## Support\n\nOld text.\n\n## End\n")
+
+    doc.apply(
+        [
+            ImportSectionOperation(
+                source_file=str(shared),
+                source_selector=Selector(select_type="h2", select_contains="Support"),
+                selector=Selector(select_type="h2", select_contains="Support"),
+            )
+        ]
+    )
+
+    assert doc.render() == "# Guide\n\n## Support\n\nAsk in the forum.\n\n## End"
+
+
+def test_apply_move_relocates_section_in_one_step() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Guide\n\n## Usage\n\nRun it.\n\n## Install\n\nGet it.\n"
+    )
+    install = Selector(select_type="h2", select_contains="Install")
+
+    doc.apply(
+        [
+            MoveOperation(
+                selector=install,
+                section=True,
+                destination=Selector(select_type="h2", select_contains="Usage"),
+                position=InsertPosition.BEFORE,
+            )
+        ]
+    )
+
+    assert doc.render() == "# Guide\n\n## Install\n\nGet it.\n\n## Usage\n\nRun it."
+
+    with pytest.raises(OperationFailedError, match="inside the content being moved"):
+        doc.apply(
+            [
+                MoveOperation(
+                    selector=install,
+                    section=True,
+                    destination=Selector(select_type="p", select_contains="Get"),
+                )
+            ]
+        )
+
+
+def test_apply_copy_clones_template_section() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Log\n\n## Template\n\n- [ ] Review\n\n## Monday\n\nDone.\n"
+    )
+
+    doc.apply(
+        [
+            CopyOperation(
+                selector=Selector(select_type="li"),
+                destination=Selector(select_type="h2", select_contains="Monday"),
+                position=InsertPosition.APPEND_CHILD,
+            )
+        ]
+    )
+
+    assert doc.render() == (
+        "# Log\n\n## Template\n\n- [ ] Review\n\n## Monday\n\nDone.\n\n- [ ] Review"
+    )
+
+
+def test_apply_swap_exchanges_sections() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Book\n\n## One\n\nFirst.\n\n## Two\n\nSecond.\n\n## Three\n\nThird.\n"
+    )
+
+    doc.apply(
+        [
+            SwapOperation(
+                selector=Selector(select_type="h2", select_contains="One"),
+                other=Selector(select_type="h2", select_contains="Three"),
+                section=True,
+            )
+        ]
+    )
+
+    assert doc.render() == (
+        "# Book\n\n## Three\n\nThird.\n\n## Two\n\nSecond.\n\n## One\n\nFirst."
+    )
+
+
+def test_apply_rename_heading_updates_links_to_its_anchor() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Guide\n\n## Setup\n\nSteps.\n\nSee [setup](#setup).\n"
+    )
+
+    doc.apply(
+        [
+            RenameHeadingOperation(
+                selector=Selector(select_type="h2"),
+                text="Installation",
+                update_links=True,
+            )
+        ]
+    )
+
+    assert doc.render() == (
+        "# Guide\n\n## Installation\n\nSteps.\n\nSee [setup](#installation)."
+    )
+
+
+def test_apply_shift_heading_demotes_a_section_within_bounds() -> None:
+    doc = MarkdownDocument.from_string("# Guide\n\n## Setup\n\n### Linux\n")
+
+    doc.apply(
+        [
+            ShiftHeadingOperation(
+                selector=Selector(select_type="h2"),
+                delta=1,
+                include_section=True,
+            )
+        ]
+    )
+
+    assert doc.render() == "# Guide\n\n### Setup\n\n#### Linux"
+
+    with pytest.raises(OperationFailedError, match="heading levels range from h1 to h6"):
+        doc.apply(
+            [ShiftHeadingOperation(selector=Selector(select_type="h4"), delta=3)]
+        )
+
+
+def test_apply_merge_sections_folds_a_duplicate_section() -> None:
+    doc = MarkdownDocument.from_string(
+        "# API\n\n## Options\n\n- `--fast`\n\n## Usage\n\n## Options\n\n- `--safe`\n"
+    )
+
+    doc.apply(
+        [
+            MergeSectionsOperation(
+                selector=Selector(select_type="h2", select_contains="Options", select_ordinal=2),
+                destination=Selector(select_type="h2", select_contains="Options"),
+            )
+        ]
+    )
+
+    assert doc.render() == "# API\n\n## Options\n\n- `--fast`\n\n- `--safe`\n\n## Usage"
+
+
+def test_apply_set_and_remove_alert() -> None:
+    doc = MarkdownDocument.from_string("# Notes\n\n> Back up first.\n")
+
+    doc.apply(
+        [
+            SetAlertOperation(
+                selector=Selector(select_type="blockquote"),
+                alert_type=AlertType.WARNING,
+            )
+        ]
+    )
+    assert doc.render() == "# Notes\n\n> [!WARNING]\n> Back up first."
+
+    doc.apply([RemoveAlertOperation(selector=Selector(select_type="alert"))])
+    assert doc.render() == "# Notes\n\n> Back up first."
+
+
+def test_apply_insert_appends_lines_to_a_code_block() -> None:
+    doc = MarkdownDocument.from_string("# Usage\n\n```sh\nmd-splice get\n```\n")
+
+    doc.apply(
+        [
+            InsertOperation(
+                selector=Selector(select_type="code"),
+                content="md-splice outline\n",
+                position=InsertPosition.APPEND_CHILD,
+            )
+        ]
+    )
+
+    assert doc.render() == "# Usage\n\n```sh\nmd-splice get\nmd-splice outline\n```"
+
+
+def test_apply_set_code_language_keeps_code() -> None:
+    doc = MarkdownDocument.from_string("# Setup\n\n```\ncargo build\n```\n")
+    selector = Selector(select_type="code")
+
+    doc.apply([SetCodeLanguageOperation(selector=selector, language="sh")])
+    assert doc.render() == "# Setup\n\n```sh\ncargo build\n```"
+
+    doc.apply([SetCodeLanguageOperation(selector=selector)])
+    assert doc.render() == "# Setup\n\n```\ncargo build\n```"
+
+
+def test_apply_set_code_attribute_edits_info_string() -> None:
+    doc = MarkdownDocument.from_string("```rust,no_run\nserve();\n```\n")
+
+    doc.apply(
+        [
+            SetCodeAttributeOperation(
+                selector=Selector(select_info_contains="no_run"),
+                attribute="no_run",
+                remove=True,
+            ),
+            SetCodeAttributeOperation(
+                selector=Selector(select_type="code"),
+                attribute="edition",
+                value="2021",
+            ),
+        ]
+    )
+
+    assert doc.render() == "```rust edition=2021\nserve();\n```"
+
+
+def test_apply_inserts_and_deletes_table_rows() -> None:
+    doc = MarkdownDocument.from_string(
+        "| Service | State |\n| --- | --- |\n| api | ok |\n| cli | todo |\n"
+    )
+    table = Selector(select_type="table")
+
+    doc.apply(
+        [
+            InsertTableRowOperation(
+                selector=table,
+                row="| docs | *draft* |",
+                position=InsertPosition.BEFORE,
+                row_contains="cli",
+            ),
+            InsertTableRowOperation(selector=table, row=["web", "ok"]),
+            DeleteTableRowOperation(selector=table, row_regex=r"^api\t"),
+        ]
+    )
+
+    assert doc.get(table, cell=(1, 1)) == "docs"
+    assert doc.get(table, cell=(3, 2)) == "ok"
+    with pytest.raises(ValueError):
+        DeleteTableRowOperation(selector=table)
+
+
+def test_apply_adds_removes_and_renames_table_columns() -> None:
+    doc = MarkdownDocument.from_string(
+        "| Service | State | Notes |\n| --: | --- | --- |\n| api | ok | fast |\n| cli | todo |\n"
+    )
+    table = Selector(select_type="table")
+
+    doc.apply(
+        [
+            AddTableColumnOperation(
+                selector=table,
+                header="Owner",
+                value="*none*",
+                position=InsertPosition.BEFORE,
+                column="State",
+                alignment=ColumnAlignment.CENTER,
+            ),
+            RemoveTableColumnOperation(selector=table, column="Notes"),
+            RenameTableColumnOperation(selector=table, column="State", header="Status"),
+        ]
+    )
+
+    assert doc.render() == (
+        "| Service | Owner  | Status |\n| ------: | :----: | ------ |\n"
+        "|     api | *none* | ok     |\n|     cli | *none* | todo   |"
+    )
+    with pytest.raises(ValueError):
+        AddTableColumnOperation(selector=table, header="x", position=InsertPosition.APPEND_CHILD)
+
+
+def test_apply_update_toc_inserts_and_refreshes_the_table_of_contents() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Guide\n\n## Contents\n\n## Set up -- fast\n\n## FAQ\n"
+    )
+
+    doc.apply(
+        [
+            UpdateTocOperation(
+                selector=Selector(select_type="h2", select_contains="Contents"),
+                min_level=2,
+                slug_style=SlugStyle.GITLAB,
+            )
+        ]
+    )
+    doc.apply([UpdateTocOperation(min_level=2, slug_style=SlugStyle.GITLAB)])
+
+    assert doc.render() == (
+        "# Guide\n\n## Contents\n\n<!-- toc -->\n\n"
+        "- [Set up -- fast](#set-up-fast)\n- [FAQ](#faq)\n\n<!-- tocstop -->\n\n"
+        "## Set up -- fast\n\n## FAQ"
+    )
+    with pytest.raises(ValueError):
+        UpdateTocOperation(selector=Selector(select_type="h2"), selector_ref="contents")
+
+
+def test_apply_targets_table_rows_and_cells() -> None:
+    doc = MarkdownDocument.from_string(
+        "| Service | State |\n| --- | --- |\n| api | ok |\n| cli | todo |\n"
+    )
+
+    assert doc.get(Selector(select_type="tr", select_contains="cli")) == "| cli | todo |\n"
+
+    doc.apply(
+        [
+            ReplaceOperation(
+                selector=Selector(select_type="td", select_contains="todo"),
+                content="done",
+            ),
+            DeleteOperation(selector=Selector(select_type="tr", select_contains="api")),
+        ]
+    )
+
+    assert doc.render() == "| Service | State |\n| ------- | ----- |\n| cli     | done  |"
+
+
+def test_apply_targets_links_and_code_spans() -> None:
+    doc = MarkdownDocument.from_string(
+        "See [the guide](guide.md) and run `make`.\n\n- [API](api.md)\n"
+    )
+
+    assert doc.get(Selector(select_type="link", select_contains="api.md")) == "[API](api.md)\n"
+
+    doc.apply(
+        [
+            ReplaceOperation(
+                selector=Selector(select_type="link", select_contains="guide"),
+                content="[the manual](manual.md)",
+            ),
+            DeleteOperation(selector=Selector(select_type="codespan")),
+        ]
+    )
+
+    assert doc.render() == "See [the manual](manual.md) and run .\n\n- [API](api.md)"
+
+
+def test_apply_set_comment_updates_existing_stamp() -> None:
+    doc = MarkdownDocument.from_string("# Notes\n\nGenerated text.\n")
+    selector = Selector(select_type="p")
+
+    for stamp in ("generated by md-splice (1)", "generated by md-splice (2)"):
+        doc.apply(
+            [SetCommentOperation(selector=selector, text=stamp, marker="md-splice")]
+        )
+
+    assert doc.render() == (
+        "# Notes\n\n<!-- generated by md-splice (2) -->\n\nGenerated text."
+    )
+    assert doc.get(Selector(select_type="comment")) == (
+        "<!-- generated by md-splice (2) -->\n"
+    )
+
+    doc.apply([RemoveCommentOperation(selector=selector)])
+    warnings = doc.apply([RemoveCommentOperation(selector=selector)])
+
+    assert doc.render() == "# Notes\n\nGenerated text."
+    assert warnings[0].kind == "skipped_operation"
+
+
+def test_apply_assert_operation_guards_batch() -> None:
+    doc = MarkdownDocument.from_string("---\nstatus: draft\n---\n# Notes\n\nBody.\n")
+
+    doc.apply(
+        [
+            AssertOperation(frontmatter_key="status", equals="draft"),
+            ReplaceOperation(selector=Selector(select_type="p"), content="New body."),
+            AssertOperation(
+                selector=Selector(select_contains="Body."),
+                expect=AssertExpectation.NO_MATCH,
+            ),
+        ]
+    )
+    assert doc.render() == "---\nstatus: draft\n---\n# Notes\n\nNew body."
+
+    with pytest.raises(AssertionFailedError, match="does not equal"):
+        doc.apply(
+            [
+                DeleteOperation(selector=Selector(select_type="p")),
+                AssertOperation(frontmatter_key="status", equals="final"),
+            ]
+        )
+    assert doc.render() == "---\nstatus: draft\n---\n# Notes\n\nNew body."
+
+    with pytest.raises(ValueError):
+        AssertOperation(selector=Selector(select_type="p"), frontmatter_key="status")
+
+
+def test_apply_capturing_returns_get_operation_results() -> None:
+    doc = MarkdownDocument.from_string("# Notes\n\nKeep this.\n\n## Archive\n")
+
+    warnings, captures = doc.apply_capturing(
+        [
+            GetOperation(selector=Selector(select_type="p"), capture="kept"),
+            InsertOperation(
+                selector=Selector(select_type="h2"),
+                position=InsertPosition.AFTER,
+                content="Archived: {{kept}}",
+            ),
+        ]
+    )
+
+    assert warnings == []
+    assert captures == [Capture(operation_index=0, name="kept", content="Keep this.\n")]
+    assert doc.render() == "# Notes\n\nKeep this.\n\n## Archive\n\nArchived: Keep this."
+
+
+def test_apply_selector_max_matches_and_tie_break() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Guide\n\nTODO intro.\n\n### Details\n\nTODO details.\n"
+    )
+
+    with pytest.raises(TooManyMatchesError, match="max_matches limit of 1"):
+        doc.apply(
+            [
+                DeleteOperation(
+                    selector=Selector(select_contains="TODO", max_matches=1)
+                )
+            ]
+        )
+
+    doc.apply(
+        [
+            ReplaceOperation(
+                selector=Selector(
+                    select_contains="TODO",
+                    tie_break=TieBreak.DEEPEST_FIRST,
+                ),
+                content="Done.",
+            )
+        ],
+        warn_on_ambiguity=False,
+    )
+    assert doc.render() == "# Guide\n\nTODO intro.\n\n### Details\n\nDone."
+
+
+def test_apply_replace_inner_html_rewrites_details_body() -> None:
+    doc = MarkdownDocument.from_string(
+        "<details>\n<summary>FAQ</summary>\n\nOld.\n\n</details>\n"
+    )
+
+    doc.apply(
+        [
+            ReplaceInnerHtmlOperation(
+                selector=Selector(select_tag="details"),
+                content="<summary>FAQ</summary>\n\nNew.",
+            )
+        ]
+    )
+
+    assert doc.render() == (
+        "<details>\n\n<summary>FAQ</summary>\n\nNew.\n\n</details>"
+    )
+
+
+def test_apply_wrap_and_unwrap_details_round_trip_a_section() -> None:
+    source = "# Guide\n\n## FAQ\n\n- Why?\n\n## Next\n"
+    doc = MarkdownDocument.from_string(source)
+
+    doc.apply(
+        [
+            WrapDetailsOperation(
+                selector=Selector(select_type="h2", select_contains="FAQ"),
+                section=True,
+                summary="Frequently asked questions",
+            )
+        ]
+    )
+    assert doc.render() == (
+        "# Guide\n\n<details>\n<summary>Frequently asked questions</summary>\n\n"
+        "## FAQ\n\n- Why?\n\n</details>\n\n## Next"
+    )
+
+    doc.apply([UnwrapDetailsOperation(selector=Selector(select_tag="details"))])
+    assert doc.render() == "# Guide\n\n## FAQ\n\n- Why?\n\n## Next"
+
+
+
+def test_apply_selects_headings_by_slug() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Guide\n\n## Installation notes\n\nOld.\n\n## Installation notes\n\nOlder.\n"
+    )
+
+    doc.apply(
+        [
+            DeleteOperation(
+                selector=Selector(select_slug="installation-notes-1"), section=True
+            )
+        ]
+    )
+
+    assert doc.render() == "# Guide\n\n## Installation notes\n\nOld."
+
+
+def test_apply_scopes_selectors_between_two_landmarks() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Guide\n\n## Install\n\nStep one.\n\nStep two.\n\n## Usage\n\nStep three.\n"
+    )
+
+    doc.apply(
+        [
+            DeleteOperation(
+                selector=Selector(
+                    select_type="p",
+                    select_ordinal=2,
+                    between=Between(
+                        start=Selector(select_contains="Install"),
+                        end=Selector(select_type="h2"),
+                    ),
+                )
+            )
+        ]
+    )
+
+    assert doc.render() == "# Guide\n\n## Install\n\nStep one.\n\n## Usage\n\nStep three."
+
+
+def test_apply_skips_nodes_matching_negated_criteria() -> None:
+    doc = MarkdownDocument.from_string("- [x] Ship it\n- [ ] Write docs\n- Review\n")
+
+    doc.apply(
+        [
+            DeleteOperation(
+                selector=Selector(
+                    select_type="li",
+                    select_not_contains="[x]",
+                    select_not_regex=r"Write",
+                )
+            )
+        ]
+    )
+
+    assert doc.render() == "- [X] Ship it\n- [ ] Write docs"
+
+
+def test_apply_matches_contains_ignoring_case_and_whitespace() -> None:
+    doc = MarkdownDocument.from_string("# Guide\n\n## Getting   STARTED\n\nText.\n")
+
+    doc.apply(
+        [
+            ReplaceOperation(
+                selector=Selector(select_contains_ci="getting started"),
+                content="## Getting started",
+            )
+        ]
+    )
+
+    assert doc.render() == "# Guide\n\n## Getting started\n\nText."
+
+
+def test_apply_counts_the_ordinal_from_the_last_match() -> None:
+    doc = MarkdownDocument.from_string("# Plan\n\n## Now\n\n## Next\n\n## Later\n")
+
+    doc.apply(
+        [
+            DeleteOperation(
+                selector=Selector(select_type="h2", select_ordinal=2, select_last=True)
+            )
+        ]
+    )
+
+    assert doc.render() == "# Plan\n\n## Now\n\n## Later"
+
+
+def test_apply_deletes_nested_list_items_by_depth() -> None:
+    doc = MarkdownDocument.from_string("- Release\n  - Tag\n  - Publish\n- Announce\n")
+
+    doc.apply(
+        [
+            DeleteOperation(
+                selector=Selector(
+                    select_type="li", select_depth=2, select_contains="Tag"
+                )
+            )
+        ]
+    )
+
+    assert doc.render() == "- Release\n  \n  - Publish\n- Announce"
+
+
+def test_apply_deletes_completed_tasks_by_task_state() -> None:
+    doc = MarkdownDocument.from_string("- [x] Ship\n- [ ] Announce\n- Notes\n")
+
+    doc.apply(
+        [
+            DeleteOperation(
+                selector=Selector(select_type="li", task_state=TaskState.COMPLETE)
+            )
+        ]
+    )
+
+    assert doc.render() == "- [ ] Announce\n- Notes"
+
+
+def test_apply_replaces_paragraph_inside_blockquote_recursively() -> None:
+    doc = MarkdownDocument.from_string("> Beta builds are unsigned.\n>\n> Report bugs.\n")
+
+    doc.apply(
+        [
+            ReplaceOperation(
+                selector=Selector(
+                    select_type="p", select_contains="Beta", recursive=True
+                ),
+                content="Beta builds are signed.",
+            )
+        ]
+    )
+
+    assert doc.render() == "> Beta builds are signed.\n> \n> Report bugs."
+
+
+def test_apply_upsert_inserts_once_then_replaces() -> None:
+    doc = MarkdownDocument.from_string("# Project\n\nIntro.\n")
+    upsert = UpsertOperation(
+        selector=Selector(select_type="h2", select_contains="Status"),
+        section=True,
+        content="## Status\n\nPassing.",
+        fallback=Selector(select_type="p", select_contains="Intro"),
+    )
+
+    doc.apply([upsert])
+    doc.apply([upsert])
+
+    assert doc.render() == "# Project\n\nIntro.\n\n## Status\n\nPassing."
+
+
+def test_apply_wrap_turns_a_paragraph_into_an_alert() -> None:
+    doc = MarkdownDocument.from_string("# Setup\n\nBack up your data first.\n\nThen run it.\n")
+
+    doc.apply(
+        [
+            WrapOperation(
+                selector=Selector(select_type="p", select_contains="Back up"),
+                alert_type=AlertType.WARNING,
+            )
+        ]
+    )
+
+    assert doc.render() == (
+        "# Setup\n\n> [!WARNING]\n> Back up your data first.\n\nThen run it."
+    )
+
+
+def test_apply_unwrap_hoists_alert_content() -> None:
+    doc = MarkdownDocument.from_string("> [!TIP]\n> Use *lenient* mode.\n>\n> - For drafts.\n")
+
+    doc.apply([UnwrapOperation(selector=Selector(select_type="alert"))])
+
+    assert doc.render() == "Use *lenient* mode.\n\n- For drafts."
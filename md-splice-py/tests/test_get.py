@@ -7,9 +7,10 @@ from textwrap import dedent
 import pytest
 import re
 
-from md_splice import MarkdownDocument, Selector
+from md_splice import InsertOperation, MarkdownDocument, ReplaceOperation, Selector
 from md_splice.errors import (
     InvalidRegexError,
+    NodeNotFoundError,
     RangeRequiresBlockError,
     SectionRequiresHeadingError,
 )
@@ -217,3 +218,105 @@ def test_get_rejects_unsupported_regex_flags():
 
     with pytest.raises(InvalidRegexError):
         doc.get(Selector(select_type="p", select_regex=pattern))
+
+
+def test_get_by_fingerprint_after_block_moves() -> None:
+    doc = MarkdownDocument.from_string("# Notes\n\nKeep this.\n")
+    fingerprint = doc.fingerprints()[1]
+
+    doc.apply(
+        [
+            InsertOperation(
+                selector=Selector(select_type="h1"),
+                content="Intro.",
+            )
+        ]
+    )
+
+    selector = Selector(select_fingerprint=fingerprint[:8])
+    assert doc.get(selector) == "Keep this.\n"
+
+
+def test_get_by_annotation_comment() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Notes\n\n<!-- slot: summary -->\n\nShort.\n\n"
+        "<!-- slot: details -->\n\nLong.\n"
+    )
+
+    assert doc.get(Selector(select_annotation="slot: details")) == "Long.\n"
+    with pytest.raises(NodeNotFoundError):
+        doc.get(Selector(select_annotation="slot: missing"))
+
+
+def test_get_by_word_count_and_emptiness() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Guide\n\n## Setup\n\n## Usage\n\nRun the tool with care.\n\nDone.\n"
+    )
+
+    assert doc.get(Selector(select_type="p", select_max_words=1)) == "Done.\n"
+    assert (
+        doc.get(Selector(select_type="p", select_min_words=3))
+        == "Run the tool with care.\n"
+    )
+    assert doc.get(Selector(select_type="h2", select_empty=True)) == "## Setup\n"
+    with pytest.raises(ValueError):
+        Selector(select_max_words=-1)
+
+
+def test_get_definition_list_terms_and_definitions() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Glossary\n\nAST\n: Abstract syntax tree.\n\nCLI\n: Command-line interface.\n",
+        definition_lists=True,
+    )
+
+    assert doc.get(Selector(select_type="dt", select_ordinal=2)) == "CLI\n"
+    assert (
+        doc.get(Selector(select_type="dd", select_contains="Command"))
+        == ": Command-line interface.\n"
+    )
+
+
+def test_get_within_slide_of_a_deck() -> None:
+    doc = MarkdownDocument.from_string(
+        "# Talk\n\nWelcome.\n\n---\n\n- first point\n\n---\n\n- second point\n"
+    )
+
+    assert doc.get(Selector(select_type="li", within_slide=3)) == "- second point\n"
+    with pytest.raises(NodeNotFoundError):
+        doc.get(Selector(select_type="li", within_slide=1))
+    with pytest.raises(ValueError):
+        Selector(within_slide=0)
+
+
+def test_get_block_at_a_source_line() -> None:
+    doc = MarkdownDocument.from_string("# Notes\n\nFirst.\n\n- one\n- two\n")
+
+    assert doc.get(Selector(at_line=3)) == "First.\n"
+    assert doc.get(Selector(select_type="li", at_line=6), select_all=True) == [
+        "- one\n",
+        "- two\n",
+    ]
+    with pytest.raises(NodeNotFoundError):
+        doc.get(Selector(at_line=4))
+    with pytest.raises(ValueError):
+        Selector(at_line=0)
+
+
+def test_get_not_found_lists_nearest_misses() -> None:
+    doc = MarkdownDocument.from_string("# Guide\n\n## Setup\n\nInstall the pakages.\n")
+
+    with pytest.raises(NodeNotFoundError, match="88% similar"):
+        doc.get(Selector(select_type="p", select_contains="packages"))
+
+
+def test_get_and_replace_table_cell():
+    doc = MarkdownDocument.from_string(
+        "| Service | State |\n| --- | --- |\n| api | *ok* |\n| cli | todo |\n"
+    )
+    table = Selector(select_type="table")
+
+    assert doc.get(table, cell=(1, 2)) == "*ok*"
+
+    doc.apply([ReplaceOperation(selector=table, cell=(2, 2), content="done")])
+    assert doc.get(table, cell=(2, 2)) == "done"
+    assert doc.get(table, cell=(0, 1)) == "Service"
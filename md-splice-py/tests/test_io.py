@@ -11,8 +11,9 @@ from md_splice import (
     MarkdownDocument,
     ReplaceOperation,
     Selector,
+    apply_to_files,
 )
-from md_splice.errors import IoError
+from md_splice.errors import IoError, NodeNotFoundError
 
 
 def test_from_file_reads_contents(tmp_path: Path) -> None:
@@ -125,3 +126,50 @@ def test_write_to_supports_relative_paths(
 
     assert relative_path.exists()
     assert relative_path.read_text(encoding="utf-8") == doc.render()
+
+
+def test_from_string_lenient_warns_about_unclosed_fences() -> None:
+    with pytest.warns(UserWarning, match="line 3: unclosed code fence"):
+        doc = MarkdownDocument.from_string("# Setup\n\n```sh\ncargo build\n", lenient=True)
+
+    assert doc.render().endswith("```sh\ncargo build")
+
+
+def test_apply_to_files_edits_every_file(tmp_path: Path) -> None:
+    paths = []
+    for index in range(4):
+        path = tmp_path / f"doc{index}.md"
+        path.write_text(f"# Doc {index}\n\nDraft.\n", encoding="utf-8")
+        paths.append(path)
+    ops = [
+        ReplaceOperation(
+            selector=Selector(select_type="p", select_contains="Draft"),
+            content="Final.",
+        )
+    ]
+
+    warnings = apply_to_files(paths, ops, backup=True)
+
+    assert warnings == [[], [], [], []]
+    for index, path in enumerate(paths):
+        updated = path.read_text(encoding="utf-8")
+        assert updated.rstrip("\n") == f"# Doc {index}\n\nFinal."
+        assert Path(f"{path}~").read_text(encoding="utf-8") == f"# Doc {index}\n\nDraft.\n"
+
+
+def test_apply_to_files_writes_nothing_when_one_file_fails(tmp_path: Path) -> None:
+    good = tmp_path / "good.md"
+    good.write_text("# Good\n\nDraft.\n", encoding="utf-8")
+    bad = tmp_path / "bad.md"
+    bad.write_text("# Bad\n\nNothing here.\n", encoding="utf-8")
+    ops = [
+        ReplaceOperation(
+            selector=Selector(select_type="p", select_contains="Draft"),
+            content="Final.",
+        )
+    ]
+
+    with pytest.raises(NodeNotFoundError, match="bad.md"):
+        apply_to_files([good, bad], ops, parallel=False)
+
+    assert good.read_text(encoding="utf-8") == "# Good\n\nDraft.\n"
@@ -1,122 +1,420 @@
 use crate::cli::{
-    ApplyArgs, Cli, Command, DeleteArgs, FrontmatterCommand, FrontmatterDeleteArgs,
-    FrontmatterFormatArg, FrontmatterGetArgs, FrontmatterOutputFormat, FrontmatterSetArgs, GetArgs,
-    InsertPosition as CliInsertPosition, ModificationArgs,
+    ApplyArgs, CatArgs, Cli, ColorChoice, Command, CompletionsArgs, ConcatArgs, DedupeHeadingsArgs,
+    DeleteArgs, DoctorArgs, EmitOp, FrontmatterCommand, FrontmatterDeleteArgs,
+    FrontmatterFormatArg, FrontmatterGetArgs, FrontmatterOutputFormat, FrontmatterSetArgs,
+    FrontmatterStrategyArg, GetArgs, InferArgs, InsertPosition as CliInsertPosition,
+    ModificationArgs, MoveArgs, OperationsFormat, OutlineArgs, PruneEmptySectionsArgs,
+    SlugStyleArg, SplitArgs, SplitPlaceholder, TaskStateArg, TieBreakArg, TocArgs,
 };
+use crate::profile::Profile;
+use crate::{reference, style};
 use anyhow::{anyhow, Context};
-use clap::Parser;
-use markdown_ppp::ast::{Block, Heading, HeadingKind, SetextHeading};
-use markdown_ppp::parser::{parse_markdown, MarkdownParserState};
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
+use markdown_ppp::ast::{Block, Document, Inline, Link, ListKind};
 use markdown_ppp::printer::render_markdown;
+use md_splice_lib::alias::ResolvedAlias;
+use md_splice_lib::concat::{ConcatOptions, FrontmatterStrategy};
+use md_splice_lib::doctor::{examine, FindingKind};
 use md_splice_lib::error::SpliceError;
-use md_splice_lib::frontmatter::{self, FrontmatterFormat};
-use md_splice_lib::locator::{locate, locate_all, FoundNode, Selector};
+use md_splice_lib::fingerprint::node_fingerprint;
+use md_splice_lib::frontmatter::{self, FrontmatterDelimiter, FrontmatterFormat};
+use md_splice_lib::infer::infer_operations;
+use md_splice_lib::inlines::{inline_select_type, render_inline_node};
+use md_splice_lib::lint::{find_duplicate_headings, find_slug_collisions};
+use md_splice_lib::locator::{
+    found_block_index, item_list, Between, FoundNode, Selector, TaskStatus, TieBreak,
+};
+use md_splice_lib::outline::HeadingNode;
+use md_splice_lib::policy::OperationPolicy;
+use md_splice_lib::replay::positional_operations;
+use md_splice_lib::sections::{get_heading_level, section_range};
+use md_splice_lib::slugify::{heading_slugs, HeadingSlug};
+use md_splice_lib::table::{render_cell, render_table_node, table_cell, CellAddress};
 use md_splice_lib::transaction::{
-    DeleteFrontmatterOperation, DeleteOperation, InsertOperation,
-    InsertPosition as TxInsertPosition, Operation, ReplaceOperation, Selector as TxSelector,
-    SetFrontmatterOperation,
+    DedupeHeadingsOperation, DeleteFrontmatterOperation, DeleteOperation, InsertOperation,
+    InsertPosition as TxInsertPosition, MoveOperation, Operation, PruneEmptySectionsOperation,
+    ReplaceOperation, Selector as TxSelector, SetFrontmatterOperation, SlugStyle,
+    UpdateTocOperation,
+};
+use md_splice_lib::warning::WarningKind;
+use md_splice_lib::{
+    default_printer_config, render_fragment, route_operations, ApplyOutcome, Limits,
+    MarkdownDocument, OperationEntry, ParseOptions, SourceSpan,
 };
-use md_splice_lib::{default_printer_config, MarkdownDocument};
 use regex::Regex;
+use serde_json::json;
 use serde_yaml::Value as YamlValue;
 use similar::TextDiff;
+use std::collections::HashMap;
 use std::fs;
 use std::io::{self, Read, Write};
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process;
 use std::str::FromStr;
 use tempfile::Builder as TempFileBuilder;
 
+/// Exit status for failures that do not fall into a more specific class.
+pub const EXIT_FAILURE: u8 = 1;
+/// Exit status when a selector or frontmatter key matched nothing.
+pub const EXIT_NO_MATCH: u8 = 3;
+/// Exit status when a selector matched several nodes while `--strict` is set, or more nodes
+/// than its `max_matches` limit.
+pub const EXIT_AMBIGUOUS: u8 = 4;
+/// Exit status when the document, its frontmatter, or the operations could not be parsed.
+pub const EXIT_PARSE_ERROR: u8 = 5;
+/// Exit status when reading or writing a file failed.
+pub const EXIT_IO_ERROR: u8 = 6;
+
+/// Flags shared by every subcommand.
+struct GlobalOptions {
+    lenient: bool,
+    definition_lists: bool,
+    frontmatter_delimiters: Vec<FrontmatterDelimiter>,
+    selector_definitions: HashMap<String, TxSelector>,
+    /// The directories the document moves from and to, as absolute paths.
+    rebase_links: Option<(PathBuf, PathBuf)>,
+    quiet: bool,
+    strict: bool,
+    policy: OperationPolicy,
+    stdin_filename: Option<String>,
+    color: ColorChoice,
+    profile: Option<Profile>,
+}
+
+impl GlobalOptions {
+    /// Returns the stream used for regular (non-error) output.
+    fn stdout(&self) -> Box<dyn Write> {
+        if self.quiet {
+            Box::new(io::sink())
+        } else {
+            Box::new(io::stdout().lock())
+        }
+    }
+
+    /// Runs `f`, recording its duration under `phase` when `--profile` is set.
+    fn time<T>(&self, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        match &self.profile {
+            Some(profile) => profile.time(phase, f),
+            None => f(),
+        }
+    }
+}
+
+/// Error raised when `--strict` is set and a selector matched more than one node.
+#[derive(Debug)]
+struct AmbiguousSelectorError;
+
+impl std::fmt::Display for AmbiguousSelectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Selector matched multiple nodes (rejected by --strict).")
+    }
+}
+
+impl std::error::Error for AmbiguousSelectorError {}
+
+/// Error raised by `exists` when the selector matched nothing, reported by the exit status
+/// alone.
+#[derive(Debug)]
+pub struct SilentNoMatchError;
+
+impl std::fmt::Display for SilentNoMatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Selector did not match any nodes in the document")
+    }
+}
+
+impl std::error::Error for SilentNoMatchError {}
+
 pub fn run() -> anyhow::Result<()> {
     env_logger::init();
 
+    // Like --help, --help-selectors works without a subcommand.
+    let lenient_matches = Cli::command().ignore_errors(true).get_matches();
+    if lenient_matches.get_flag("help_selectors") {
+        io::stdout().write_all(reference::selector_reference().as_bytes())?;
+        return Ok(());
+    }
+
+    let matches = Cli::command().get_matches();
     let Cli {
         file,
         output,
+        stdin_filename,
+        lenient,
+        definition_lists,
+        frontmatter_delimiters,
+        selector_definitions,
+        rebase_links,
+        quiet,
+        strict,
+        no_fs,
+        profile,
+        color,
+        help_selectors: _,
         command,
-    } = Cli::parse();
+    } = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    style::init_stderr(color);
+    let options = GlobalOptions {
+        lenient,
+        definition_lists,
+        frontmatter_delimiters,
+        selector_definitions: collect_selector_definitions(selector_definitions)?,
+        rebase_links: rebase_links.map(rebase_directories).transpose()?,
+        quiet,
+        strict,
+        policy: if no_fs {
+            OperationPolicy::sandboxed()
+        } else {
+            OperationPolicy::default()
+        },
+        stdin_filename,
+        color,
+        profile: profile.then(Profile::default),
+    };
+
+    let apply_matches = matches.subcommand_matches("apply");
+    let result = run_command(file, output, command, apply_matches, &options);
+    if let Some(profile) = &options.profile {
+        eprint!("{}", profile.report());
+    }
+    match options.stdin_filename.as_deref() {
+        Some(name) => result.with_context(|| format!("Failed to process {name}")),
+        None => result,
+    }
+}
 
+fn run_command(
+    file: Option<PathBuf>,
+    output: Option<PathBuf>,
+    command: Command,
+    apply_matches: Option<&ArgMatches>,
+    options: &GlobalOptions,
+) -> anyhow::Result<()> {
     validate_stdin_usage(&file, &command)?;
 
-    let input_content = read_input(file.as_ref())?;
+    // `concat` reads its own inputs, and `completions` and `man` need none.
+    let input_content = match &command {
+        Command::Concat(_) | Command::Completions(_) | Command::Man => String::new(),
+        // The document comes from git; the worktree file is only compared against, and may
+        // not exist yet.
+        Command::Apply(ApplyArgs {
+            from_git: Some(_), ..
+        }) => match &file {
+            Some(path) if path.exists() => options.time("read", || read_input(Some(path)))?,
+            _ => String::new(),
+        },
+        _ => options.time("read", || read_input(file.as_ref()))?,
+    };
 
     match command {
         Command::Get(args) => {
-            process_get(&input_content, args)?;
+            process_get(&input_content, args, options)?;
             Ok(())
         }
+        Command::Exists(args) => process_exists(&input_content, args, options),
+        Command::Cat(args) => process_cat(&input_content, args, options),
+        Command::Doctor(args) => process_doctor(&input_content, args, options),
+        Command::CheckHeadings => process_check_headings(&input_content, options),
+        Command::Outline(args) => process_outline(&input_content, args, options),
         Command::Frontmatter(FrontmatterCommand::Get(args)) => {
-            process_frontmatter_get(&input_content, args)?;
+            process_frontmatter_get(&input_content, args, options)?;
             Ok(())
         }
         Command::Insert(args) => {
-            let mut doc = MarkdownDocument::from_str(&input_content)?;
-            let operation = Operation::Insert(build_insert_operation(args)?);
-            doc.apply(vec![operation]).map_err(map_splice_error)?;
-            finalize_output(
-                OutputMode::Write,
-                &output,
-                &file,
-                &input_content,
-                doc.render(),
-            )
+            let mode = OutputMode::preview(args.dry_run, args.diff);
+            let mut doc = load_document(&input_content, options)?;
+            let operation = Operation::Insert(build_insert_operation(args, options)?);
+            apply_operations(&mut doc, vec![operation], options)?;
+            let rendered = render(&doc, options);
+            finalize_output(options, mode, &output, &file, &input_content, rendered)
         }
         Command::Replace(args) => {
-            let mut doc = MarkdownDocument::from_str(&input_content)?;
-            let operation = Operation::Replace(build_replace_operation(args)?);
-            doc.apply(vec![operation]).map_err(map_splice_error)?;
-            finalize_output(
-                OutputMode::Write,
-                &output,
-                &file,
-                &input_content,
-                doc.render(),
-            )
+            let mode = OutputMode::preview(args.dry_run, args.diff);
+            let mut doc = load_document(&input_content, options)?;
+            let operation = Operation::Replace(build_replace_operation(args, options)?);
+            apply_operations(&mut doc, vec![operation], options)?;
+            let rendered = render(&doc, options);
+            finalize_output(options, mode, &output, &file, &input_content, rendered)
         }
         Command::Delete(args) => {
-            let mut doc = MarkdownDocument::from_str(&input_content)?;
-            let operation = Operation::Delete(build_delete_operation(args)?);
-            doc.apply(vec![operation]).map_err(map_splice_error)?;
-            finalize_output(
-                OutputMode::Write,
-                &output,
-                &file,
-                &input_content,
-                doc.render(),
-            )
+            let mode = OutputMode::preview(args.dry_run, args.diff);
+            let mut doc = load_document(&input_content, options)?;
+            let operation = Operation::Delete(build_delete_operation(args, options)?);
+            apply_operations(&mut doc, vec![operation], options)?;
+            let rendered = render(&doc, options);
+            finalize_output(options, mode, &output, &file, &input_content, rendered)
+        }
+        Command::Move(args) => {
+            let mode = OutputMode::preview(args.dry_run, args.diff);
+            let mut doc = load_document(&input_content, options)?;
+            let operation = Operation::Move(build_move_operation(args, options)?);
+            apply_operations(&mut doc, vec![operation], options)?;
+            let rendered = render(&doc, options);
+            finalize_output(options, mode, &output, &file, &input_content, rendered)
+        }
+        Command::PruneEmptySections(args) => {
+            let mode = OutputMode::preview(args.dry_run, args.diff);
+            let mut doc = load_document(&input_content, options)?;
+            let operation =
+                Operation::PruneEmptySections(build_prune_empty_sections_operation(args, options)?);
+            apply_operations(&mut doc, vec![operation], options)?;
+            let rendered = render(&doc, options);
+            finalize_output(options, mode, &output, &file, &input_content, rendered)
+        }
+        Command::DedupeHeadings(args) => {
+            let check = args.check;
+            let mode = OutputMode::preview(args.dry_run, args.diff);
+            let mut doc = load_document(&input_content, options)?;
+            let mut operation = build_dedupe_headings_operation(args, options)?;
+            operation.report_only = check;
+            let outcome = apply_operations_with_aliases(
+                &mut doc,
+                vec![Operation::DedupeHeadings(operation)],
+                &[],
+                &Limits::default(),
+                options,
+            )?;
+            if check {
+                return check_duplicate_headings(&outcome);
+            }
+            let rendered = render(&doc, options);
+            finalize_output(options, mode, &output, &file, &input_content, rendered)
+        }
+        Command::Toc(args) => {
+            let check = args.check;
+            let mode = OutputMode::preview(args.dry_run, args.diff);
+            let mut doc = load_document(&input_content, options)?;
+            let original = check.then(|| render(&doc, options));
+            let operation = Operation::UpdateToc(build_update_toc_operation(args, options)?);
+            apply_operations(&mut doc, vec![operation], options)?;
+            let rendered = render(&doc, options);
+            if let Some(original) = original {
+                if original != rendered {
+                    return Err(anyhow!(
+                        "The table of contents is out of date; run `md-splice toc` to update it."
+                    ));
+                }
+                return Ok(());
+            }
+            finalize_output(options, mode, &output, &file, &input_content, rendered)
         }
         Command::Apply(args) => {
-            let (operations, mode) = prepare_apply_operations(args)?;
-            let mut doc = MarkdownDocument::from_str(&input_content)?;
-            doc.apply(operations).map_err(map_splice_error)?;
-            finalize_output(mode, &output, &file, &input_content, doc.render())
+            let imported_aliases = match &args.import_aliases {
+                Some(path) => read_aliases(path)?,
+                None => Vec::new(),
+            };
+            let export_path = args.export_aliases.clone();
+            let report_path = args.report.clone();
+            let positions_from = args.positions_from.clone();
+            let limits = Limits {
+                operation_timeout: args.operation_timeout,
+                batch_timeout: args.timeout,
+                ..Limits::default()
+            };
+            let git_content = args
+                .from_git
+                .as_deref()
+                .map(|revision| {
+                    options.time("read", || read_git_revision(revision, file.as_deref()))
+                })
+                .transpose()?;
+            let (entries, mode) = prepare_apply_operations(args, apply_matches)?;
+            let mut doc = load_document(git_content.as_deref().unwrap_or(&input_content), options)?;
+            let mut operations =
+                route_operations(entries, doc.frontmatter()).map_err(map_splice_error)?;
+            if let Some(path) = positions_from {
+                let reference_content = options.time("read", || read_input(Some(&path)))?;
+                let reference = load_document(&reference_content, options)?;
+                operations = positional_operations(&reference, operations, &imported_aliases)
+                    .map_err(map_splice_error)
+                    .with_context(|| {
+                        format!(
+                            "Failed to record selector positions in reference document {}",
+                            path.display()
+                        )
+                    })?;
+            }
+            let outcome = apply_operations_with_aliases(
+                &mut doc,
+                operations,
+                &imported_aliases,
+                &limits,
+                options,
+            )?;
+            if let Some(path) = report_path {
+                write_output_file(options, &path, &apply_report(&outcome)?)?;
+            }
+            let rendered = render(&doc, options);
+            finalize_output(options, mode, &output, &file, &input_content, rendered)?;
+            match (export_path, mode) {
+                (Some(path), OutputMode::Write) => write_aliases(&path, &outcome.aliases),
+                _ => Ok(()),
+            }
+        }
+        Command::Split(args) => {
+            let mode = OutputMode::preview(args.dry_run, args.diff);
+            let doc = load_document(&input_content, options)?;
+            let (files, remaining) = split_sections(&doc, &args)?;
+            if matches!(mode, OutputMode::Write) {
+                write_split_files(&args.out_dir, &files, args.force)?;
+            }
+            let mut rendered = doc.render_frontmatter().unwrap_or_default().to_string();
+            rendered.push_str(&options.time("render", || {
+                render_markdown(&Document { blocks: remaining }, default_printer_config())
+            }));
+            finalize_output(options, mode, &output, &file, &input_content, rendered)
+        }
+        Command::Infer(args) => process_infer(&input_content, args, &output, options),
+        Command::Concat(args) => process_concat(args, &file, &output, options),
+        Command::Completions(args) => process_completions(args, options),
+        Command::Man => {
+            let mut stdout = options.stdout();
+            reference::write_man_page(&mut stdout)?;
+            stdout.flush()?;
+            Ok(())
         }
         Command::Frontmatter(FrontmatterCommand::Set(args)) => {
-            let mut doc = MarkdownDocument::from_str(&input_content)?;
+            let mut doc = load_document(&input_content, options)?;
             let operation = Operation::SetFrontmatter(build_set_frontmatter_operation(args)?);
-            doc.apply(vec![operation]).map_err(map_splice_error)?;
+            apply_operations(&mut doc, vec![operation], options)?;
             finalize_output(
+                options,
                 OutputMode::Write,
                 &output,
                 &file,
                 &input_content,
-                doc.render(),
+                render(&doc, options),
             )
         }
         Command::Frontmatter(FrontmatterCommand::Delete(args)) => {
-            let mut doc = MarkdownDocument::from_str(&input_content)?;
+            let mut doc = load_document(&input_content, options)?;
             let operation = Operation::DeleteFrontmatter(build_delete_frontmatter_operation(args));
-            doc.apply(vec![operation]).map_err(map_splice_error)?;
+            apply_operations(&mut doc, vec![operation], options)?;
             finalize_output(
+                options,
                 OutputMode::Write,
                 &output,
                 &file,
                 &input_content,
-                doc.render(),
+                render(&doc, options),
             )
         }
     }
 }
 
+/// Resolves the `--rebase-links` directories against the current directory, so that either
+/// can be given relative to it.
+fn rebase_directories(directories: Vec<PathBuf>) -> anyhow::Result<(PathBuf, PathBuf)> {
+    let [from, to] = <[PathBuf; 2]>::try_from(directories)
+        .map_err(|_| anyhow!("--rebase-links takes a FROM and a TO directory"))?;
+    let absolute = |directory: PathBuf| {
+        std::path::absolute(&directory)
+            .with_context(|| format!("Invalid --rebase-links directory: {}", directory.display()))
+    };
+    Ok((absolute(from)?, absolute(to)?))
+}
+
 fn validate_stdin_usage(file: &Option<PathBuf>, command: &Command) -> anyhow::Result<()> {
     if let Command::Insert(args) | Command::Replace(args) = command {
         let content_from_stdin = args
@@ -129,6 +427,12 @@ fn validate_stdin_usage(file: &Option<PathBuf>, command: &Command) -> anyhow::Re
         }
     }
 
+    if let Command::Infer(args) = command {
+        if file.is_none() && args.edited.to_string_lossy() == "-" {
+            return Err(SpliceError::AmbiguousStdinSource.into());
+        }
+    }
+
     if let Command::Frontmatter(FrontmatterCommand::Set(args)) = command {
         let value_from_stdin = args
             .value_file
@@ -143,6 +447,146 @@ fn validate_stdin_usage(file: &Option<PathBuf>, command: &Command) -> anyhow::Re
     Ok(())
 }
 
+fn load_document(content: &str, options: &GlobalOptions) -> anyhow::Result<MarkdownDocument> {
+    let parse_options = ParseOptions {
+        lenient: options.lenient,
+        frontmatter_delimiters: options.frontmatter_delimiters.clone(),
+        definition_lists: options.definition_lists,
+    };
+    let (mut doc, warnings) = options.time("parse", || {
+        MarkdownDocument::from_str_with_options(content, &parse_options)
+    })?;
+    if let Some((from, to)) = &options.rebase_links {
+        doc.rebase_links(from, to);
+    }
+    if !options.quiet {
+        for warning in warnings {
+            match options.stdin_filename.as_deref() {
+                Some(name) => eprintln!("{} {name}: {warning}", style::warning_label()),
+                None => eprintln!("{} {warning}", style::warning_label()),
+            }
+        }
+    }
+    Ok(doc)
+}
+
+/// Renders `doc`, timed as the `render` phase.
+fn render(doc: &MarkdownDocument, options: &GlobalOptions) -> String {
+    options.time("render", || doc.render())
+}
+
+fn apply_operations(
+    doc: &mut MarkdownDocument,
+    operations: Vec<Operation>,
+    options: &GlobalOptions,
+) -> anyhow::Result<()> {
+    apply_operations_with_aliases(doc, operations, &[], &Limits::default(), options)?;
+    Ok(())
+}
+
+fn apply_operations_with_aliases(
+    doc: &mut MarkdownDocument,
+    operations: Vec<Operation>,
+    aliases: &[ResolvedAlias],
+    limits: &Limits,
+    options: &GlobalOptions,
+) -> anyhow::Result<ApplyOutcome> {
+    options
+        .policy
+        .check_operations(&operations)
+        .map_err(map_splice_error)?;
+    let outcome = options
+        .time("apply", || {
+            doc.apply_with_aliases_and_limits(operations, aliases, limits)
+        })
+        .map_err(map_splice_error)?;
+    if options.strict && outcome.ambiguity_detected {
+        return Err(AmbiguousSelectorError.into());
+    }
+    Ok(outcome)
+}
+
+fn read_aliases(path: &PathBuf) -> anyhow::Result<Vec<ResolvedAlias>> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read aliases file: {}", path.display()))?;
+    serde_json::from_str(&data)
+        .with_context(|| format!("Failed to parse aliases file: {}", path.display()))
+}
+
+fn write_aliases(path: &PathBuf, aliases: &[ResolvedAlias]) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(aliases)?;
+    fs::write(path, format!("{json}\n"))
+        .with_context(|| format!("Failed to write to aliases file: {}", path.display()))
+}
+
+/// Renders the `apply --report` JSON for a batch.
+fn apply_report(outcome: &ApplyOutcome) -> anyhow::Result<String> {
+    let warnings: Vec<serde_json::Value> = outcome
+        .warnings
+        .iter()
+        .map(|warning| {
+            json!({
+                "kind": warning.kind.as_str(),
+                "operation_index": warning.operation_index,
+                "message": warning.message,
+            })
+        })
+        .collect();
+    let captures: Vec<serde_json::Value> = outcome
+        .captures
+        .iter()
+        .map(|capture| {
+            json!({
+                "operation_index": capture.operation_index,
+                "name": capture.name,
+                "content": capture.content,
+            })
+        })
+        .collect();
+    let report = json!({
+        "operation_counts": outcome.operation_counts,
+        "blocks_added": outcome.blocks_added,
+        "blocks_removed": outcome.blocks_removed,
+        "warnings": warnings,
+        "captures": captures,
+    });
+    Ok(format!("{}\n", serde_json::to_string_pretty(&report)?))
+}
+
+/// Maps an error returned by [`run`] to the documented process exit status.
+pub fn exit_code(err: &anyhow::Error) -> u8 {
+    for cause in err.chain() {
+        if let Some(splice_error) = cause.downcast_ref::<SpliceError>() {
+            return match splice_error {
+                SpliceError::NodeNotFound
+                | SpliceError::NodeNotFoundWithNearMisses(_)
+                | SpliceError::FrontmatterMissing
+                | SpliceError::FrontmatterKeyNotFound(_) => EXIT_NO_MATCH,
+                SpliceError::FrontmatterParse(_)
+                | SpliceError::MarkdownParse(_)
+                | SpliceError::OperationParse(_) => EXIT_PARSE_ERROR,
+                SpliceError::Io(_) => EXIT_IO_ERROR,
+                SpliceError::TooManyMatches { .. } => EXIT_AMBIGUOUS,
+                _ => EXIT_FAILURE,
+            };
+        }
+        if cause.is::<AmbiguousSelectorError>() {
+            return EXIT_AMBIGUOUS;
+        }
+        if cause.is::<SilentNoMatchError>() {
+            return EXIT_NO_MATCH;
+        }
+        if cause.is::<io::Error>() || cause.is::<tempfile::PersistError>() {
+            return EXIT_IO_ERROR;
+        }
+        if cause.is::<serde_yaml::Error>() || cause.is::<serde_json::Error>() {
+            return EXIT_PARSE_ERROR;
+        }
+    }
+
+    EXIT_FAILURE
+}
+
 fn read_input(path: Option<&PathBuf>) -> anyhow::Result<String> {
     if let Some(file_path) = path {
         fs::read_to_string(file_path)
@@ -154,7 +598,65 @@ fn read_input(path: Option<&PathBuf>) -> anyhow::Result<String> {
     }
 }
 
+/// Reads a file as of a git revision, given as `REV:PATH` or as `REV` for `file`.
+fn read_git_revision(revision: &str, file: Option<&Path>) -> anyhow::Result<String> {
+    let mut git = process::Command::new("git");
+    if revision.contains(':') {
+        git.args(["show", revision]);
+    } else {
+        let file = file.ok_or_else(|| {
+            anyhow!("--from-git {revision} requires --file, or a revision of the form REV:PATH")
+        })?;
+        // `git show REV:./NAME` resolves NAME relative to the directory git runs in.
+        if let Some(parent) = file
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            git.current_dir(parent);
+        }
+        let name = file
+            .file_name()
+            .ok_or_else(|| anyhow!("Invalid input file path: {}", file.display()))?;
+        git.arg("show")
+            .arg(format!("{revision}:./{}", name.to_string_lossy()));
+    }
+
+    let output = git
+        .output()
+        .context("Failed to run git to read the document from a revision")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Failed to read {revision} from git: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout)
+        .with_context(|| format!("The document at {revision} is not valid UTF-8"))
+}
+
 fn finalize_output(
+    options: &GlobalOptions,
+    mode: OutputMode,
+    output_path: &Option<PathBuf>,
+    input_path: &Option<PathBuf>,
+    original_content: &str,
+    rendered_content: String,
+) -> anyhow::Result<()> {
+    options.time("write", || {
+        write_rendered(
+            options,
+            mode,
+            output_path,
+            input_path,
+            original_content,
+            rendered_content,
+        )
+    })
+}
+
+/// Writes, previews, diffs, or checks `rendered_content` according to `mode`.
+fn write_rendered(
+    options: &GlobalOptions,
     mode: OutputMode,
     output_path: &Option<PathBuf>,
     input_path: &Option<PathBuf>,
@@ -163,25 +665,51 @@ fn finalize_output(
 ) -> anyhow::Result<()> {
     match mode {
         OutputMode::DryRun => {
-            io::stdout().write_all(rendered_content.as_bytes())?;
-            return Ok(());
+            // The preview may go to a separate file, but never replaces the input.
+            return match output_path {
+                Some(path) => write_output_file(options, path, &rendered_content),
+                None => Ok(options.stdout().write_all(rendered_content.as_bytes())?),
+            };
         }
         OutputMode::Diff => {
+            let (original_header, modified_header) = match options.stdin_filename.as_deref() {
+                Some(name) => (format!("a/{name}"), format!("b/{name}")),
+                None => ("original".to_string(), "modified".to_string()),
+            };
             let diff_output = TextDiff::from_lines(original_content, &rendered_content)
                 .unified_diff()
-                .header("original", "modified")
+                .header(&original_header, &modified_header)
                 .to_string();
+            let diff_output = if style::stdout_enabled(options.color) {
+                style::colorize_diff(&diff_output)
+            } else {
+                diff_output
+            };
 
-            io::stdout().write_all(diff_output.as_bytes())?;
+            options.stdout().write_all(diff_output.as_bytes())?;
             return Ok(());
         }
+        OutputMode::Check { semantic } => {
+            let up_to_date = if semantic {
+                let original = MarkdownDocument::from_str(original_content)?;
+                let modified = MarkdownDocument::from_str(&rendered_content)?;
+                original.semantically_equals(&modified)
+            } else {
+                original_content == rendered_content
+            };
+
+            if up_to_date {
+                return Ok(());
+            }
+            return Err(anyhow!(
+                "Document is out of date: applying the operations would change it."
+            ));
+        }
         OutputMode::Write => {}
     }
 
     if let Some(path) = output_path {
-        fs::write(path, &rendered_content)
-            .with_context(|| format!("Failed to write to output file: {}", path.display()))?;
-        return Ok(());
+        return write_output_file(options, path, &rendered_content);
     }
 
     if let Some(input_path) = input_path {
@@ -211,20 +739,54 @@ fn finalize_output(
             .persist(input_path)
             .with_context(|| format!("Failed to replace original file {}", input_path.display()))?;
     } else {
-        io::stdout().write_all(rendered_content.as_bytes())?;
+        options.stdout().write_all(rendered_content.as_bytes())?;
     }
 
     Ok(())
 }
 
-fn build_insert_operation(args: ModificationArgs) -> anyhow::Result<InsertOperation> {
+/// Writes `content` to the `--output` path, where `-` stands for stdout.
+fn write_output_file(options: &GlobalOptions, path: &Path, content: &str) -> anyhow::Result<()> {
+    if path.as_os_str() == "-" {
+        let mut stdout = options.stdout();
+        stdout.write_all(content.as_bytes())?;
+        stdout.flush()?;
+        return Ok(());
+    }
+
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write to output file: {}", path.display()))
+}
+
+fn build_insert_operation(
+    args: ModificationArgs,
+    options: &GlobalOptions,
+) -> anyhow::Result<InsertOperation> {
     let ModificationArgs {
         content,
         content_file,
         select_type,
         select_contains,
+        select_contains_ci,
         select_regex,
+        select_not_contains,
+        select_not_regex,
+        select_fingerprint,
+        select_annotation,
+        select_tag,
+        select_info_contains,
+        select_slug,
         select_ordinal,
+        select_last,
+        max_matches,
+        tie_break,
+        select_min_words,
+        select_max_words,
+        select_empty,
+        select_depth,
+        recursive,
+        task_state,
+        selector_ref,
         after_select_type,
         after_select_contains,
         after_select_regex,
@@ -233,10 +795,18 @@ fn build_insert_operation(args: ModificationArgs) -> anyhow::Result<InsertOperat
         within_select_contains,
         within_select_regex,
         within_select_ordinal,
+        within_slide,
+        select_at_line,
         until_type,
         until_contains,
         until_regex,
         position,
+        renumber,
+        update_links,
+        row,
+        col: _,
+        dry_run: _,
+        diff: _,
     } = args;
 
     if until_type.is_some() || until_contains.is_some() || until_regex.is_some() {
@@ -244,27 +814,78 @@ fn build_insert_operation(args: ModificationArgs) -> anyhow::Result<InsertOperat
             "The --until-* flags can only be used with the 'replace' command"
         ));
     }
+    if update_links {
+        return Err(anyhow!(
+            "The --update-links flag can only be used with the 'replace' command"
+        ));
+    }
+    if row.is_some() {
+        return Err(anyhow!(
+            "The --row and --col flags can only be used with the 'replace' and 'get' commands"
+        ));
+    }
 
-    let selector = build_transaction_selector(
-        select_type,
-        select_contains,
-        select_regex,
-        select_ordinal,
-        build_optional_transaction_selector(
-            after_select_type,
-            after_select_contains,
-            after_select_regex,
-            after_select_ordinal,
-            "--after-select-regex",
-        )?,
-        build_optional_transaction_selector(
-            within_select_type,
-            within_select_contains,
-            within_select_regex,
-            within_select_ordinal,
-            "--within-select-regex",
+    let mut selector = match selector_ref {
+        Some(name) => resolve_selector_definition(&name, options)?,
+        None => build_transaction_selector(
+            select_type,
+            select_contains,
+            select_regex,
+            select_fingerprint,
+            select_annotation,
+            select_tag,
+            select_ordinal,
+            build_optional_transaction_selector(
+                after_select_type,
+                after_select_contains,
+                after_select_regex,
+                after_select_ordinal,
+                "--after-select-regex",
+            )?,
+            build_optional_transaction_selector(
+                within_select_type,
+                within_select_contains,
+                within_select_regex,
+                within_select_ordinal,
+                "--within-select-regex",
+            )?,
         )?,
-    )?;
+    };
+    override_match_limits(&mut selector, max_matches, tie_break);
+    narrow_by_length(
+        &mut selector,
+        select_min_words,
+        select_max_words,
+        select_empty,
+    );
+    if within_slide.is_some() {
+        selector.within_slide = within_slide;
+    }
+    if select_at_line.is_some() {
+        selector.at_line = select_at_line;
+    }
+    if select_info_contains.is_some() {
+        selector.select_info_contains = select_info_contains;
+    }
+    if select_slug.is_some() {
+        selector.select_slug = select_slug;
+    }
+    if select_contains_ci.is_some() {
+        selector.select_contains_ci = select_contains_ci;
+    }
+    if select_last {
+        selector.select_last = true;
+    }
+    if select_depth.is_some() {
+        selector.select_depth = select_depth;
+    }
+    if recursive {
+        selector.recursive = true;
+    }
+    if let Some(task_state) = task_state {
+        selector.task_state = Some(map_cli_task_state(task_state));
+    }
+    exclude_by_text(&mut selector, select_not_contains, select_not_regex)?;
 
     Ok(InsertOperation {
         selector: Some(selector),
@@ -273,17 +894,39 @@ fn build_insert_operation(args: ModificationArgs) -> anyhow::Result<InsertOperat
         content,
         content_file,
         position: map_cli_insert_position(position),
+        renumber,
     })
 }
 
-fn build_replace_operation(args: ModificationArgs) -> anyhow::Result<ReplaceOperation> {
+fn build_replace_operation(
+    args: ModificationArgs,
+    options: &GlobalOptions,
+) -> anyhow::Result<ReplaceOperation> {
     let ModificationArgs {
         content,
         content_file,
         select_type,
         select_contains,
+        select_contains_ci,
         select_regex,
+        select_not_contains,
+        select_not_regex,
+        select_fingerprint,
+        select_annotation,
+        select_tag,
+        select_info_contains,
+        select_slug,
         select_ordinal,
+        select_last,
+        max_matches,
+        tie_break,
+        select_min_words,
+        select_max_words,
+        select_empty,
+        select_depth,
+        recursive,
+        task_state,
+        selector_ref,
         after_select_type,
         after_select_contains,
         after_select_regex,
@@ -292,32 +935,87 @@ fn build_replace_operation(args: ModificationArgs) -> anyhow::Result<ReplaceOper
         within_select_contains,
         within_select_regex,
         within_select_ordinal,
+        within_slide,
+        select_at_line,
         until_type,
         until_contains,
         until_regex,
         position: _,
+        renumber,
+        update_links,
+        row,
+        col,
+        dry_run: _,
+        diff: _,
     } = args;
 
-    let selector = build_transaction_selector(
-        select_type,
-        select_contains,
-        select_regex,
-        select_ordinal,
-        build_optional_transaction_selector(
-            after_select_type,
-            after_select_contains,
-            after_select_regex,
-            after_select_ordinal,
-            "--after-select-regex",
-        )?,
-        build_optional_transaction_selector(
-            within_select_type,
-            within_select_contains,
-            within_select_regex,
-            within_select_ordinal,
-            "--within-select-regex",
+    if renumber {
+        return Err(anyhow!(
+            "The --renumber flag can only be used with the 'insert' and 'delete' commands"
+        ));
+    }
+
+    let mut selector = match selector_ref {
+        Some(name) => resolve_selector_definition(&name, options)?,
+        None => build_transaction_selector(
+            select_type,
+            select_contains,
+            select_regex,
+            select_fingerprint,
+            select_annotation,
+            select_tag,
+            select_ordinal,
+            build_optional_transaction_selector(
+                after_select_type,
+                after_select_contains,
+                after_select_regex,
+                after_select_ordinal,
+                "--after-select-regex",
+            )?,
+            build_optional_transaction_selector(
+                within_select_type,
+                within_select_contains,
+                within_select_regex,
+                within_select_ordinal,
+                "--within-select-regex",
+            )?,
         )?,
-    )?;
+    };
+    override_match_limits(&mut selector, max_matches, tie_break);
+    narrow_by_length(
+        &mut selector,
+        select_min_words,
+        select_max_words,
+        select_empty,
+    );
+    if within_slide.is_some() {
+        selector.within_slide = within_slide;
+    }
+    if select_at_line.is_some() {
+        selector.at_line = select_at_line;
+    }
+    if select_info_contains.is_some() {
+        selector.select_info_contains = select_info_contains;
+    }
+    if select_slug.is_some() {
+        selector.select_slug = select_slug;
+    }
+    if select_contains_ci.is_some() {
+        selector.select_contains_ci = select_contains_ci;
+    }
+    if select_last {
+        selector.select_last = true;
+    }
+    if select_depth.is_some() {
+        selector.select_depth = select_depth;
+    }
+    if recursive {
+        selector.recursive = true;
+    }
+    if let Some(task_state) = task_state {
+        selector.task_state = Some(map_cli_task_state(task_state));
+    }
+    exclude_by_text(&mut selector, select_not_contains, select_not_regex)?;
 
     let until_selector = build_optional_transaction_selector(
         until_type,
@@ -335,15 +1033,40 @@ fn build_replace_operation(args: ModificationArgs) -> anyhow::Result<ReplaceOper
         content_file,
         until: until_selector,
         until_ref: None,
+        update_links,
+        cell: row
+            .zip(col)
+            .map(|(row, column)| CellAddress { row, column }),
     })
 }
 
-fn build_delete_operation(args: DeleteArgs) -> anyhow::Result<DeleteOperation> {
+fn build_delete_operation(
+    args: DeleteArgs,
+    options: &GlobalOptions,
+) -> anyhow::Result<DeleteOperation> {
     let DeleteArgs {
         select_type,
         select_contains,
+        select_contains_ci,
         select_regex,
+        select_not_contains,
+        select_not_regex,
+        select_fingerprint,
+        select_annotation,
+        select_tag,
+        select_info_contains,
+        select_slug,
         select_ordinal,
+        select_last,
+        max_matches,
+        tie_break,
+        select_min_words,
+        select_max_words,
+        select_empty,
+        select_depth,
+        recursive,
+        task_state,
+        selector_ref,
         after_select_type,
         after_select_contains,
         after_select_regex,
@@ -352,32 +1075,78 @@ fn build_delete_operation(args: DeleteArgs) -> anyhow::Result<DeleteOperation> {
         within_select_contains,
         within_select_regex,
         within_select_ordinal,
+        within_slide,
+        select_at_line,
         until_type,
         until_contains,
         until_regex,
         section,
+        renumber,
+        dry_run: _,
+        diff: _,
     } = args;
 
-    let selector = build_transaction_selector(
-        select_type,
-        select_contains,
-        select_regex,
-        select_ordinal,
-        build_optional_transaction_selector(
-            after_select_type,
-            after_select_contains,
-            after_select_regex,
-            after_select_ordinal,
-            "--after-select-regex",
-        )?,
-        build_optional_transaction_selector(
-            within_select_type,
-            within_select_contains,
-            within_select_regex,
-            within_select_ordinal,
-            "--within-select-regex",
+    let mut selector = match selector_ref {
+        Some(name) => resolve_selector_definition(&name, options)?,
+        None => build_transaction_selector(
+            select_type,
+            select_contains,
+            select_regex,
+            select_fingerprint,
+            select_annotation,
+            select_tag,
+            select_ordinal,
+            build_optional_transaction_selector(
+                after_select_type,
+                after_select_contains,
+                after_select_regex,
+                after_select_ordinal,
+                "--after-select-regex",
+            )?,
+            build_optional_transaction_selector(
+                within_select_type,
+                within_select_contains,
+                within_select_regex,
+                within_select_ordinal,
+                "--within-select-regex",
+            )?,
         )?,
-    )?;
+    };
+    override_match_limits(&mut selector, max_matches, tie_break);
+    narrow_by_length(
+        &mut selector,
+        select_min_words,
+        select_max_words,
+        select_empty,
+    );
+    if within_slide.is_some() {
+        selector.within_slide = within_slide;
+    }
+    if select_at_line.is_some() {
+        selector.at_line = select_at_line;
+    }
+    if select_info_contains.is_some() {
+        selector.select_info_contains = select_info_contains;
+    }
+    if select_slug.is_some() {
+        selector.select_slug = select_slug;
+    }
+    if select_contains_ci.is_some() {
+        selector.select_contains_ci = select_contains_ci;
+    }
+    if select_last {
+        selector.select_last = true;
+    }
+    if select_depth.is_some() {
+        selector.select_depth = select_depth;
+    }
+    if recursive {
+        selector.recursive = true;
+    }
+    if let Some(task_state) = task_state {
+        selector.task_state = Some(map_cli_task_state(task_state));
+    }
+    exclude_by_text(&mut selector, select_not_contains, select_not_regex)?;
 
     let until_selector = build_optional_transaction_selector(
         until_type,
@@ -394,9 +1163,272 @@ fn build_delete_operation(args: DeleteArgs) -> anyhow::Result<DeleteOperation> {
         section,
         until: until_selector,
         until_ref: None,
+        renumber,
     })
 }
 
+fn build_move_operation(args: MoveArgs, options: &GlobalOptions) -> anyhow::Result<MoveOperation> {
+    let MoveArgs {
+        select_type,
+        select_contains,
+        select_contains_ci,
+        select_regex,
+        select_not_contains,
+        select_not_regex,
+        select_fingerprint,
+        select_annotation,
+        select_tag,
+        select_info_contains,
+        select_slug,
+        select_ordinal,
+        select_last,
+        max_matches,
+        tie_break,
+        select_min_words,
+        select_max_words,
+        select_empty,
+        select_depth,
+        recursive,
+        task_state,
+        selector_ref,
+        after_select_type,
+        after_select_contains,
+        after_select_regex,
+        after_select_ordinal,
+        within_select_type,
+        within_select_contains,
+        within_select_regex,
+        within_select_ordinal,
+        within_slide,
+        select_at_line,
+        to_select_type,
+        to_select_contains,
+        to_select_regex,
+        to_select_ordinal,
+        to_selector_ref,
+        position,
+        section,
+        dry_run: _,
+        diff: _,
+    } = args;
+
+    let mut selector = match selector_ref {
+        Some(name) => resolve_selector_definition(&name, options)?,
+        None => build_transaction_selector(
+            select_type,
+            select_contains,
+            select_regex,
+            select_fingerprint,
+            select_annotation,
+            select_tag,
+            select_ordinal,
+            build_optional_transaction_selector(
+                after_select_type,
+                after_select_contains,
+                after_select_regex,
+                after_select_ordinal,
+                "--after-select-regex",
+            )?,
+            build_optional_transaction_selector(
+                within_select_type,
+                within_select_contains,
+                within_select_regex,
+                within_select_ordinal,
+                "--within-select-regex",
+            )?,
+        )?,
+    };
+    override_match_limits(&mut selector, max_matches, tie_break);
+    narrow_by_length(
+        &mut selector,
+        select_min_words,
+        select_max_words,
+        select_empty,
+    );
+    if within_slide.is_some() {
+        selector.within_slide = within_slide;
+    }
+    if select_at_line.is_some() {
+        selector.at_line = select_at_line;
+    }
+    if select_info_contains.is_some() {
+        selector.select_info_contains = select_info_contains;
+    }
+    if select_slug.is_some() {
+        selector.select_slug = select_slug;
+    }
+    if select_contains_ci.is_some() {
+        selector.select_contains_ci = select_contains_ci;
+    }
+    if select_last {
+        selector.select_last = true;
+    }
+    if select_depth.is_some() {
+        selector.select_depth = select_depth;
+    }
+    if recursive {
+        selector.recursive = true;
+    }
+    if let Some(task_state) = task_state {
+        selector.task_state = Some(map_cli_task_state(task_state));
+    }
+    exclude_by_text(&mut selector, select_not_contains, select_not_regex)?;
+
+    let destination = match to_selector_ref {
+        Some(name) => Some(resolve_selector_definition(&name, options)?),
+        None => build_optional_transaction_selector(
+            to_select_type,
+            to_select_contains,
+            to_select_regex,
+            to_select_ordinal,
+            "--to-select-regex",
+        )?,
+    };
+
+    Ok(MoveOperation {
+        selector: Some(selector),
+        selector_ref: None,
+        comment: None,
+        section,
+        destination,
+        destination_ref: None,
+        position: map_cli_insert_position(position),
+    })
+}
+
+fn build_prune_empty_sections_operation(
+    args: PruneEmptySectionsArgs,
+    options: &GlobalOptions,
+) -> anyhow::Result<PruneEmptySectionsOperation> {
+    let PruneEmptySectionsArgs {
+        min_level,
+        max_level,
+        within_select_type,
+        within_select_contains,
+        within_select_regex,
+        within_select_ordinal,
+        within_ref,
+        dry_run: _,
+        diff: _,
+    } = args;
+
+    let within = match within_ref {
+        Some(name) => Some(resolve_selector_definition(&name, options)?),
+        None => build_optional_transaction_selector(
+            within_select_type,
+            within_select_contains,
+            within_select_regex,
+            within_select_ordinal,
+            "--within-select-regex",
+        )?,
+    };
+
+    Ok(PruneEmptySectionsOperation {
+        comment: None,
+        min_level,
+        max_level,
+        within,
+        within_ref: None,
+    })
+}
+
+fn build_dedupe_headings_operation(
+    args: DedupeHeadingsArgs,
+    options: &GlobalOptions,
+) -> anyhow::Result<DedupeHeadingsOperation> {
+    let DedupeHeadingsArgs {
+        within_select_type,
+        within_select_contains,
+        within_select_regex,
+        within_select_ordinal,
+        within_ref,
+        dry_run: _,
+        diff: _,
+        check: _,
+    } = args;
+
+    let within = match within_ref {
+        Some(name) => Some(resolve_selector_definition(&name, options)?),
+        None => build_optional_transaction_selector(
+            within_select_type,
+            within_select_contains,
+            within_select_regex,
+            within_select_ordinal,
+            "--within-select-regex",
+        )?,
+    };
+
+    Ok(DedupeHeadingsOperation {
+        comment: None,
+        within,
+        within_ref: None,
+        report_only: false,
+    })
+}
+
+fn build_update_toc_operation(
+    args: TocArgs,
+    options: &GlobalOptions,
+) -> anyhow::Result<UpdateTocOperation> {
+    let TocArgs {
+        select_type,
+        select_contains,
+        select_regex,
+        select_ordinal,
+        selector_ref,
+        min_level,
+        max_level,
+        slug_style,
+        dry_run: _,
+        diff: _,
+        check: _,
+    } = args;
+
+    let selector = match selector_ref {
+        Some(name) => Some(resolve_selector_definition(&name, options)?),
+        None => build_optional_transaction_selector(
+            select_type,
+            select_contains,
+            select_regex,
+            select_ordinal,
+            "--select-regex",
+        )?,
+    };
+
+    Ok(UpdateTocOperation {
+        selector,
+        selector_ref: None,
+        comment: None,
+        min_level,
+        max_level,
+        slug_style: match slug_style {
+            SlugStyleArg::Github => SlugStyle::Github,
+            SlugStyleArg::Gitlab => SlugStyle::Gitlab,
+        },
+    })
+}
+
+/// Prints every group of duplicated headings to stderr and fails if there was at least one.
+fn check_duplicate_headings(outcome: &ApplyOutcome) -> anyhow::Result<()> {
+    let duplicates: Vec<&str> = outcome
+        .warnings
+        .iter()
+        .filter(|warning| warning.kind == WarningKind::DuplicateHeading)
+        .map(|warning| warning.message.as_str())
+        .collect();
+
+    if duplicates.is_empty() {
+        return Ok(());
+    }
+    for message in &duplicates {
+        eprintln!("{message}");
+    }
+    Err(anyhow!(
+        "Found {} duplicated heading(s); anchor links to them are ambiguous.",
+        duplicates.len()
+    ))
+}
+
 fn build_set_frontmatter_operation(
     args: FrontmatterSetArgs,
 ) -> anyhow::Result<SetFrontmatterOperation> {
@@ -427,69 +1459,204 @@ fn build_delete_frontmatter_operation(args: FrontmatterDeleteArgs) -> DeleteFron
     DeleteFrontmatterOperation { key, comment: None }
 }
 
-fn prepare_apply_operations(args: ApplyArgs) -> anyhow::Result<(Vec<Operation>, OutputMode)> {
+/// A single source of operations passed to `apply`.
+enum OperationSource {
+    File(PathBuf),
+    Inline(String),
+}
+
+/// Orders the `--operations-file` and `--operations` values as they appeared on the command line.
+fn ordered_operation_sources(
+    operations_file: Vec<PathBuf>,
+    operations: Vec<String>,
+    matches: Option<&ArgMatches>,
+) -> Vec<OperationSource> {
+    let indices_of = |id: &str| -> Vec<usize> {
+        matches
+            .and_then(|matches| matches.indices_of(id))
+            .map(|indices| indices.collect())
+            .unwrap_or_default()
+    };
+    let file_indices = indices_of("operations_file");
+    let inline_indices = indices_of("operations");
+
+    let mut sources: Vec<(usize, OperationSource)> = Vec::new();
+    for (position, path) in operations_file.into_iter().enumerate() {
+        let index = file_indices.get(position).copied().unwrap_or(position);
+        sources.push((index, OperationSource::File(path)));
+    }
+    for (position, inline) in operations.into_iter().enumerate() {
+        let index = inline_indices.get(position).copied().unwrap_or(usize::MAX);
+        sources.push((index, OperationSource::Inline(inline)));
+    }
+    sources.sort_by_key(|(index, _)| *index);
+
+    sources.into_iter().map(|(_, source)| source).collect()
+}
+
+fn prepare_apply_operations(
+    args: ApplyArgs,
+    matches: Option<&ArgMatches>,
+) -> anyhow::Result<(Vec<OperationEntry>, OutputMode)> {
     let ApplyArgs {
         operations_file,
-        operations,
+        operations: inline_operations,
         dry_run,
         diff,
+        check,
+        semantic,
+        import_aliases: _,
+        export_aliases: _,
+        positions_from: _,
+        from_git: _,
+        operation_timeout: _,
+        timeout: _,
+        report: _,
     } = args;
 
-    let operations_data = match (operations_file, operations) {
-        (Some(path), None) => {
-            if path.to_string_lossy() == "-" {
+    let sources = ordered_operation_sources(operations_file, inline_operations, matches);
+    if sources.is_empty() {
+        return Err(anyhow!(
+            "Either --operations-file or --operations must be provided."
+        ));
+    }
+
+    let mut stdin_consumed = false;
+    let mut operations = Vec::new();
+    for source in sources {
+        let operations_data = match source {
+            OperationSource::File(path) if path.to_string_lossy() == "-" => {
+                if stdin_consumed {
+                    return Err(anyhow!(
+                        "Operations can only be read from stdin ('-') once."
+                    ));
+                }
+                stdin_consumed = true;
                 let mut buf = String::new();
                 io::stdin().read_to_string(&mut buf)?;
                 buf
-            } else {
-                fs::read_to_string(&path).with_context(|| {
-                    format!("Failed to read operations file: {}", path.display())
-                })?
             }
-        }
-        (None, Some(inline)) => inline,
-        (Some(_), Some(_)) => unreachable!("clap enforces mutual exclusivity"),
-        (None, None) => {
-            return Err(anyhow!(
-                "Either --operations-file or --operations must be provided."
-            ));
-        }
-    };
-
-    let operations: Vec<Operation> = serde_yaml::from_str(&operations_data)
-        .with_context(|| "Failed to parse operations data as JSON or YAML")?;
+            OperationSource::File(path) => fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read operations file: {}", path.display()))?,
+            OperationSource::Inline(inline) => inline,
+        };
+
+        // Plain operation lists are parsed first, as their errors point at the faulty field.
+        let parsed: Vec<OperationEntry> =
+            match serde_yaml::from_str::<Vec<Operation>>(&operations_data) {
+                Ok(parsed) => parsed.into_iter().map(OperationEntry::Operation).collect(),
+                Err(err) => serde_yaml::from_str(&operations_data)
+                    .map_err(|_| err)
+                    .with_context(|| "Failed to parse operations data as JSON or YAML")?,
+            };
+        operations.extend(parsed);
+    }
 
-    let mode = if diff {
-        OutputMode::Diff
-    } else if dry_run {
-        OutputMode::DryRun
+    let mode = if check {
+        OutputMode::Check { semantic }
     } else {
-        OutputMode::Write
+        OutputMode::preview(dry_run, diff)
     };
 
     Ok((operations, mode))
 }
 
-fn process_get(content: &str, args: GetArgs) -> anyhow::Result<()> {
-    let parsed = frontmatter::parse(content)?;
-    let doc = parse_markdown(MarkdownParserState::default(), &parsed.body)
-        .map_err(|e| anyhow!("Failed to parse input markdown: {}", e))?;
-    let blocks = doc.blocks;
-
-    let selector = build_locator_selector_from_args(
-        args.select_type,
-        args.select_contains,
-        args.select_regex,
-        args.select_ordinal,
-        args.after_select_type,
-        args.after_select_contains,
-        args.after_select_regex,
-        args.after_select_ordinal,
-        args.within_select_type,
-        args.within_select_contains,
-        args.within_select_regex,
-        args.within_select_ordinal,
-    )?;
+/// Succeeds when the selector of `exists` matches a node and fails with
+/// [`SilentNoMatchError`] otherwise, printing nothing either way.
+fn process_exists(content: &str, mut args: GetArgs, options: &GlobalOptions) -> anyhow::Result<()> {
+    let doc = load_document(content, options)?;
+    let selector = get_selector(&mut args, options)?;
+    match options.time("locate", || doc.locate(&selector)) {
+        Ok((_, is_ambiguous)) if options.strict && is_ambiguous => {
+            Err(AmbiguousSelectorError.into())
+        }
+        Ok(_) => Ok(()),
+        Err(SpliceError::NodeNotFound) => Err(SilentNoMatchError.into()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Builds the selector described by the selection flags of `get`, taking them out of `args`.
+fn get_selector(args: &mut GetArgs, options: &GlobalOptions) -> anyhow::Result<Selector> {
+    let mut selector = match args.selector_ref.take() {
+        Some(name) => {
+            locator_selector_from_definition(&resolve_selector_definition(&name, options)?)?
+        }
+        None => build_locator_selector_from_args(
+            args.select_type.take(),
+            args.select_contains.take(),
+            args.select_regex.take(),
+            args.select_fingerprint.take(),
+            args.select_annotation.take(),
+            args.select_tag.take(),
+            args.select_ordinal,
+            args.after_select_type.take(),
+            args.after_select_contains.take(),
+            args.after_select_regex.take(),
+            args.after_select_ordinal,
+            args.within_select_type.take(),
+            args.within_select_contains.take(),
+            args.within_select_regex.take(),
+            args.within_select_ordinal,
+        )?,
+    };
+    if args.max_matches.is_some() {
+        selector.max_matches = args.max_matches;
+    }
+    if let Some(tie_break) = args.tie_break.take() {
+        selector.tie_break = map_cli_tie_break(tie_break);
+    }
+    if args.select_min_words.is_some() {
+        selector.select_min_words = args.select_min_words;
+    }
+    if args.select_max_words.is_some() {
+        selector.select_max_words = args.select_max_words;
+    }
+    if args.select_empty {
+        selector.select_empty = Some(true);
+    }
+    if args.within_slide.is_some() {
+        selector.within_slide = args.within_slide;
+    }
+    if args.select_at_line.is_some() {
+        selector.at_line = args.select_at_line;
+    }
+    if args.select_info_contains.is_some() {
+        selector.select_info_contains = args.select_info_contains.take();
+    }
+    if args.select_slug.is_some() {
+        selector.select_slug = args.select_slug.take();
+    }
+    if args.select_last {
+        selector.select_last = true;
+    }
+    if args.select_depth.is_some() {
+        selector.select_depth = args.select_depth;
+    }
+    if args.recursive {
+        selector.recursive = true;
+    }
+    if let Some(task_state) = args.task_state.take() {
+        selector.task_state = Some(map_cli_task_state(task_state));
+    }
+    if args.select_contains_ci.is_some() {
+        selector.select_contains_ci = args.select_contains_ci.take();
+    }
+    if args.select_not_contains.is_some() {
+        selector.select_not_contains = args.select_not_contains.take();
+    }
+    if args.select_not_regex.is_some() {
+        selector.select_not_regex =
+            compile_optional_regex(args.select_not_regex.take(), "--select-not-regex")?;
+    }
+    Ok(selector)
+}
+
+fn process_get(content: &str, mut args: GetArgs, options: &GlobalOptions) -> anyhow::Result<()> {
+    let doc = load_document(content, options)?;
+    let blocks = doc.blocks();
+
+    let selector = get_selector(&mut args, options)?;
 
     let until_selector = build_optional_locator_selector_from_args(
         "--until-regex",
@@ -498,26 +1665,94 @@ fn process_get(content: &str, args: GetArgs) -> anyhow::Result<()> {
         args.until_regex,
         None,
     )?;
+    if args.count {
+        let matches = options.time("locate", || doc.locate_all(&selector))?;
+        let mut stdout = options.stdout();
+        writeln!(stdout, "{}", matches.len())?;
+        stdout.flush()?;
+        return Ok(());
+    }
+    if let Some(kind) = args.emit_ops {
+        let matches = if args.select_all {
+            options.time("locate", || doc.locate_all(&selector))?
+        } else {
+            let (found_node, is_ambiguous) = options
+                .time("locate", || doc.locate(&selector))
+                .map_err(|err| doc.explain_no_match(err, &selector))?;
+            if options.strict && is_ambiguous {
+                return Err(AmbiguousSelectorError.into());
+            }
+            vec![found_node]
+        };
+        return emit_operations(
+            kind,
+            blocks,
+            &matches,
+            args.section,
+            args.output_format,
+            options,
+        );
+    }
+
+    let colored =
+        !args.fingerprint && !args.json && !args.ndjson && style::stdout_enabled(options.color);
+    let highlight = |rendered: String| {
+        if !colored {
+            return rendered;
+        }
+        style::highlight_matches(
+            &rendered,
+            selector.select_contains.as_deref(),
+            selector.select_regex.as_ref(),
+        )
+    };
 
     if args.select_all {
-        let matches = locate_all(&blocks, &selector)?;
+        let matches = options.time("locate", || doc.locate_all(&selector))?;
         if matches.is_empty() {
             return Ok(());
         }
 
+        if args.json || args.ndjson {
+            let headings = heading_slugs(blocks);
+            let spans = doc.block_spans();
+            let mut stdout = options.stdout();
+            let mut values = Vec::with_capacity(matches.len());
+            for found in &matches {
+                let markdown = if args.section {
+                    render_section(blocks, found)?
+                } else {
+                    render_found_node(blocks, found)?
+                };
+                let value = match_json(found, &headings, &spans, markdown);
+                if args.ndjson {
+                    writeln!(stdout, "{}", serde_json::to_string(&value)?)?;
+                } else {
+                    values.push(value);
+                }
+            }
+            if args.json {
+                writeln!(stdout, "{}", serde_json::to_string_pretty(&values)?)?;
+            }
+            stdout.flush()?;
+            return Ok(());
+        }
+
         let mut had_trailing_newline = false;
         let mut rendered_items = Vec::with_capacity(matches.len());
         for found in &matches {
-            let rendered = if args.section {
-                render_heading_section(&blocks, found)?
+            let rendered = if args.fingerprint {
+                render_fingerprint(found)
+            } else if args.section {
+                render_section(blocks, found)?
             } else {
-                render_found_node(&blocks, found)?
+                render_found_node(blocks, found)?
             };
 
             if rendered.ends_with('\n') {
                 had_trailing_newline = true;
             }
-            rendered_items.push(rendered);
+            rendered_items.push(highlight(rendered));
         }
 
         let normalized: Vec<String> = rendered_items
@@ -530,40 +1765,468 @@ fn process_get(content: &str, args: GetArgs) -> anyhow::Result<()> {
             output.push('\n');
         }
 
-        let mut stdout = io::stdout().lock();
+        let mut stdout = options.stdout();
         stdout.write_all(output.as_bytes())?;
         stdout.flush()?;
         return Ok(());
     }
 
-    let (found_node, _) = locate(&blocks, &selector)?;
-    let mut stdout = io::stdout().lock();
+    let (found_node, is_ambiguous) = options
+        .time("locate", || doc.locate(&selector))
+        .map_err(|err| doc.explain_no_match(err, &selector))?;
+    if options.strict && is_ambiguous {
+        return Err(AmbiguousSelectorError.into());
+    }
+    let mut stdout = options.stdout();
+    if let Some((row, column)) = args.row.zip(args.col) {
+        let FoundNode::Block { block, .. } = &found_node else {
+            return Err(anyhow!(
+                "The --row and --col flags require the selector to match a table"
+            ));
+        };
+        let rendered = render_cell(table_cell(block, CellAddress { row, column })?);
+        stdout.write_all(highlight(format!("{rendered}\n")).as_bytes())?;
+        stdout.flush()?;
+        return Ok(());
+    }
     let rendered = match &found_node {
+        _ if args.fingerprint => render_fingerprint(&found_node),
         FoundNode::Block { index, .. } => {
             if let Some(until_selector) = until_selector.as_ref() {
-                let end_index = compute_range_end(&blocks, *index, until_selector)?;
-                render_blocks(&blocks[*index..end_index])
+                let end_index = doc.range_end(*index, until_selector)?;
+                render_fragment(&blocks[*index..end_index], default_printer_config())
             } else if args.section {
-                render_heading_section(&blocks, &found_node)?
+                render_section(blocks, &found_node)?
             } else {
-                render_found_node(&blocks, &found_node)?
+                render_found_node(blocks, &found_node)?
             }
         }
-        FoundNode::ListItem { .. } => {
+        _ => {
             if until_selector.is_some() {
                 return Err(SpliceError::RangeRequiresBlock.into());
             }
-            render_found_node(&blocks, &found_node)?
+            // A list item is always rendered with its nested blocks, so `--section` adds nothing.
+            render_found_node(blocks, &found_node)?
+        }
+    };
+    if args.json {
+        let value = match_json(
+            &found_node,
+            &heading_slugs(blocks),
+            &doc.block_spans(),
+            rendered,
+        );
+        writeln!(stdout, "{}", serde_json::to_string_pretty(&value)?)?;
+        stdout.flush()?;
+        return Ok(());
+    }
+    stdout.write_all(highlight(rendered).as_bytes())?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Describes a match printed by `get --json`: its kind, position, metadata, source lines and
+/// Markdown, and for a heading, its level, text and anchor, `headings` and `spans` being the
+/// headings and block spans of the document.
+fn match_json(
+    found: &FoundNode,
+    headings: &[HeadingSlug],
+    spans: &[Option<SourceSpan>],
+    markdown: String,
+) -> serde_json::Value {
+    let mut value = json!(found.metadata());
+    value["kind"] = json!(found.kind_description());
+    value["position"] = json!(found.position_description());
+    value["markdown"] = json!(markdown);
+    if let Some(Some(span)) = spans.get(found_block_index(found)) {
+        value["lines"] = json!({ "start": span.start_line, "end": span.end_line });
+    }
+    if let FoundNode::Block { index, .. } = found {
+        if let Some(heading) = headings.iter().find(|heading| heading.index == *index) {
+            value["heading"] = json!({
+                "level": heading.level,
+                "text": heading.text,
+                "anchor": heading.anchor,
+            });
+        }
+    }
+    value
+}
+
+/// Prints the document with the body of every section folded away, except in the sections
+/// selected by `--expand`, `--expand-regex`, and `--expand-ref`.
+fn process_cat(content: &str, args: CatArgs, options: &GlobalOptions) -> anyhow::Result<()> {
+    let doc = load_document(content, options)?;
+    let blocks = doc.blocks();
+
+    let heading = |select_contains, select_regex| Selector {
+        select_type: Some("heading".to_string()),
+        select_contains,
+        select_regex,
+        select_ordinal: 1,
+        ..Selector::default()
+    };
+    let mut selectors = Vec::new();
+    for text in args.expand {
+        selectors.push(heading(Some(text), None));
+    }
+    for pattern in args.expand_regex {
+        selectors.push(heading(
+            None,
+            compile_optional_regex(Some(pattern), "--expand-regex")?,
+        ));
+    }
+    for name in args.expand_ref {
+        selectors.push(locator_selector_from_definition(
+            &resolve_selector_definition(&name, options)?,
+        )?);
+    }
+
+    let mut expanded = vec![false; blocks.len()];
+    for selector in &selectors {
+        let matches = options.time("locate", || doc.locate_all(selector))?;
+        if matches.is_empty() {
+            return Err(doc
+                .explain_no_match(SpliceError::NodeNotFound, selector)
+                .into());
+        }
+        for found in matches {
+            expanded[enclosing_section(blocks, found_block_index(&found))].fill(true);
+        }
+    }
+
+    let colored = style::stdout_enabled(options.color);
+    let mut rendered = doc.render_frontmatter().unwrap_or_default().to_string();
+    rendered.push_str(&options.time("render", || fold_sections(blocks, &expanded, colored)));
+    let mut stdout = options.stdout();
+    stdout.write_all(rendered.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Returns the innermost section holding the block at `index`: the section of the block
+/// itself if it is a heading, else that of the nearest heading before it, or the blocks
+/// before the first heading.
+fn enclosing_section(blocks: &[Block], index: usize) -> Range<usize> {
+    let is_heading = |block: &Block| get_heading_level(block).is_some();
+    match blocks[..=index].iter().rposition(is_heading) {
+        Some(heading) => section_range(blocks, heading).unwrap_or(heading..index + 1),
+        None => 0..blocks.iter().position(is_heading).unwrap_or(blocks.len()),
+    }
+}
+
+/// Renders the headings of `blocks` and the blocks marked in `expanded`, replacing each run
+/// of other blocks with a `[… N blocks]` marker.
+fn fold_sections(blocks: &[Block], expanded: &[bool], colored: bool) -> String {
+    let shown = |index: usize| expanded[index] || get_heading_level(&blocks[index]).is_some();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while start < blocks.len() {
+        let visible = shown(start);
+        let end = (start..blocks.len())
+            .find(|&index| shown(index) != visible)
+            .unwrap_or(blocks.len());
+        if visible {
+            let fragment = render_fragment(&blocks[start..end], default_printer_config());
+            parts.push(fragment.trim_end().to_string());
+        } else {
+            let count = end - start;
+            let noun = if count == 1 { "block" } else { "blocks" };
+            let marker = format!("[… {count} {noun}]");
+            parts.push(if colored {
+                style::fold_marker(&marker)
+            } else {
+                marker
+            });
         }
+        start = end;
+    }
+
+    let mut rendered = parts.join("\n\n");
+    if !rendered.is_empty() {
+        rendered.push('\n');
+    }
+    rendered
+}
+
+/// Renders the content fingerprint of a matched node on its own line.
+/// Prints an operations file applying `kind` to each of `matches`. Each operation targets its
+/// node by fingerprint rather than by ordinal, so it still matches once earlier operations of
+/// the batch have changed the document.
+fn emit_operations(
+    kind: EmitOp,
+    blocks: &[Block],
+    matches: &[FoundNode],
+    section: bool,
+    format: OperationsFormat,
+    options: &GlobalOptions,
+) -> anyhow::Result<()> {
+    if kind == EmitOp::Replace && section {
+        return Err(anyhow!(
+            "--emit-ops replace cannot target whole sections; drop --section or emit delete operations"
+        ));
+    }
+    let operations = matches
+        .iter()
+        .map(|found| {
+            let selector = Some(fingerprint_selector(found));
+            Ok(match kind {
+                EmitOp::Delete => Operation::Delete(DeleteOperation {
+                    selector,
+                    section,
+                    ..DeleteOperation::default()
+                }),
+                EmitOp::Replace => Operation::Replace(ReplaceOperation {
+                    selector,
+                    content: Some(render_found_node(blocks, found)?),
+                    ..ReplaceOperation::default()
+                }),
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let rendered = match format {
+        OperationsFormat::Yaml => serde_yaml::to_string(&operations)?,
+        OperationsFormat::Json => format!("{}\n", serde_json::to_string_pretty(&operations)?),
     };
+
+    let mut stdout = options.stdout();
     stdout.write_all(rendered.as_bytes())?;
     stdout.flush()?;
+    Ok(())
+}
+
+/// Returns a selector matching `found` by its fingerprint.
+fn fingerprint_selector(found: &FoundNode) -> TxSelector {
+    let select_type = match found {
+        FoundNode::Block { .. } | FoundNode::ContainedBlock { .. } => None,
+        FoundNode::ListItem { .. } => Some("li".to_string()),
+        FoundNode::TableRow { .. } => Some("tr".to_string()),
+        FoundNode::TableCell { .. } => Some("td".to_string()),
+        FoundNode::Inline { inline, .. } => Some(inline_select_type(inline).to_string()),
+    };
+    TxSelector {
+        select_type,
+        select_fingerprint: Some(node_fingerprint(found)),
+        recursive: matches!(found, FoundNode::ContainedBlock { .. }),
+        ..TxSelector::default()
+    }
+}
+
+fn render_fingerprint(found: &FoundNode) -> String {
+    format!("{}\n", node_fingerprint(found))
+}
+
+/// Prints every heading whose text or anchor repeats another heading's, and fails if there is
+/// at least one.
+/// Prints the heading tree of the document, one indented line per heading, or as JSON.
+fn process_outline(
+    content: &str,
+    args: OutlineArgs,
+    options: &GlobalOptions,
+) -> anyhow::Result<()> {
+    let doc = load_document(content, options)?;
+    let mut tree = doc.heading_tree();
+    if let Some(max_level) = args.max_level {
+        prune_deeper_headings(&mut tree, max_level);
+    }
+    let spans = doc.block_spans();
+    let mut stdout = options.stdout();
+
+    if args.json {
+        let nodes: Vec<_> = tree.iter().map(|node| outline_json(node, &spans)).collect();
+        writeln!(stdout, "{}", serde_json::to_string_pretty(&nodes)?)?;
+        return Ok(());
+    }
+
+    let mut pending: Vec<(usize, &HeadingNode)> = tree.iter().rev().map(|node| (0, node)).collect();
+    while let Some((depth, node)) = pending.pop() {
+        let line = match &spans[node.blocks.start] {
+            Some(span) => format!(", line {}", span.start_line),
+            None => String::new(),
+        };
+        writeln!(
+            stdout,
+            "{}{} {} (#{}; block {}{line}; {} block(s), {} subheading(s))",
+            "  ".repeat(depth),
+            "#".repeat(usize::from(node.level)),
+            node.text,
+            node.slug,
+            node.blocks.start,
+            node.blocks.len(),
+            node.children.len()
+        )?;
+        pending.extend(node.children.iter().rev().map(|child| (depth + 1, child)));
+    }
+    Ok(())
+}
+
+/// Removes the headings deeper than `max_level` from `nodes`, along with their subheadings.
+fn prune_deeper_headings(nodes: &mut Vec<HeadingNode>, max_level: u8) {
+    nodes.retain(|node| node.level <= max_level);
+    for node in nodes {
+        prune_deeper_headings(&mut node.children, max_level);
+    }
+}
+
+/// Describes a heading of `outline --json` and, recursively, its subheadings.
+fn outline_json(node: &HeadingNode, spans: &[Option<SourceSpan>]) -> serde_json::Value {
+    let mut value = json!({
+        "level": node.level,
+        "text": node.text,
+        "slug": node.slug,
+        "block": node.blocks.start,
+        "section_blocks": node.blocks.len(),
+        "child_count": node.children.len(),
+    });
+    if let Some(span) = &spans[node.blocks.start] {
+        value["line"] = json!(span.start_line);
+    }
+    value["children"] = node
+        .children
+        .iter()
+        .map(|child| outline_json(child, spans))
+        .collect();
+    value
+}
+
+fn process_check_headings(content: &str, options: &GlobalOptions) -> anyhow::Result<()> {
+    let doc = load_document(content, options)?;
+    let blocks = doc.blocks();
+    let duplicates = find_duplicate_headings(blocks, 0..blocks.len());
+    let collisions = find_slug_collisions(blocks);
+    let spans = doc.block_spans();
+    let line = |index: usize| match &spans[index] {
+        Some(span) => format!(", line {}", span.start_line),
+        None => String::new(),
+    };
+    let mut stdout = options.stdout();
+
+    for group in &duplicates {
+        let headings: Vec<String> = group
+            .indices
+            .iter()
+            .map(|index| format!("block {index}{}", line(*index)))
+            .collect();
+        writeln!(
+            stdout,
+            "Duplicate heading \"{}\" at {}",
+            group.text,
+            headings.join("; ")
+        )?;
+    }
+    for group in &collisions {
+        let headings: Vec<String> = group
+            .texts
+            .iter()
+            .zip(&group.indices)
+            .map(|(text, index)| format!("\"{text}\" (block {index}{})", line(*index)))
+            .collect();
+        writeln!(
+            stdout,
+            "Anchor #{} shared by {}",
+            group.slug,
+            headings.join(", ")
+        )?;
+    }
+
+    if duplicates.is_empty() && collisions.is_empty() {
+        writeln!(stdout, "No duplicate headings or colliding anchors found.")?;
+        return Ok(());
+    }
+    Err(anyhow!(
+        "Found {} duplicated heading(s) and {} colliding anchor(s).",
+        duplicates.len(),
+        collisions.len()
+    ))
+}
+
+fn process_doctor(content: &str, args: DoctorArgs, options: &GlobalOptions) -> anyhow::Result<()> {
+    let parse_options = ParseOptions {
+        lenient: options.lenient,
+        frontmatter_delimiters: options.frontmatter_delimiters.clone(),
+        definition_lists: options.definition_lists,
+    };
+    let report = options.time("parse", || examine(content, &parse_options))?;
+    let mut stdout = options.stdout();
+
+    for finding in &report.findings {
+        writeln!(stdout, "{finding}")?;
+    }
+
+    let diff = TextDiff::from_lines(content, &report.rendered);
+    let changed = diff
+        .iter_all_changes()
+        .filter(|change| change.tag() == similar::ChangeTag::Delete)
+        .count();
+    if changed > 0 {
+        writeln!(
+            stdout,
+            "Round trip: {changed} of {} lines change when the document is rendered without any edit{}.",
+            content.lines().count(),
+            if args.diff { "" } else { " (see --diff)" }
+        )?;
+    } else if report.findings.is_empty() {
+        writeln!(
+            stdout,
+            "No problems found: the document renders back unchanged."
+        )?;
+    }
+
+    let mut suggestions = Vec::new();
+    for finding in &report.findings {
+        let suggestion = match &finding.kind {
+            FindingKind::Malformed if !options.lenient => {
+                "--lenient: keep malformed regions verbatim instead of parsing them as Markdown"
+                    .to_string()
+            }
+            FindingKind::UnrecognizedFrontmatter { delimiter } => format!(
+                "--frontmatter-delimiter '{delimiter}': recognize the block at the top of the document as frontmatter"
+            ),
+            _ => continue,
+        };
+        if !suggestions.contains(&suggestion) {
+            suggestions.push(suggestion);
+        }
+    }
+    if !suggestions.is_empty() {
+        writeln!(stdout, "Suggested options:")?;
+        for suggestion in suggestions {
+            writeln!(stdout, "  {suggestion}")?;
+        }
+    }
 
+    if args.diff && changed > 0 {
+        let diff_output = diff
+            .unified_diff()
+            .header("original", "rendered")
+            .to_string();
+        let diff_output = if style::stdout_enabled(options.color) {
+            style::colorize_diff(&diff_output)
+        } else {
+            diff_output
+        };
+        writeln!(stdout)?;
+        stdout.write_all(diff_output.as_bytes())?;
+    }
+    stdout.flush()?;
     Ok(())
 }
 
-fn process_frontmatter_get(content: &str, args: FrontmatterGetArgs) -> anyhow::Result<()> {
-    let parsed = frontmatter::parse(content)?;
+fn process_frontmatter_get(
+    content: &str,
+    args: FrontmatterGetArgs,
+    options: &GlobalOptions,
+) -> anyhow::Result<()> {
+    let mut stdout = options.stdout();
+    let parsed = frontmatter::parse_with_delimiters(content, &options.frontmatter_delimiters)?;
+
+    if args.raw {
+        if let Some(raw) = parsed.raw_frontmatter() {
+            stdout.write_all(raw.as_bytes())?;
+        }
+        return Ok(());
+    }
 
     let Some(frontmatter) = parsed.frontmatter else {
         if args.key.is_some() {
@@ -575,7 +2238,7 @@ fn process_frontmatter_get(content: &str, args: FrontmatterGetArgs) -> anyhow::R
     if let Some(key) = args.key {
         let segments = parse_frontmatter_path(&key)?;
         if let Some(value) = resolve_frontmatter_path(&frontmatter, &segments) {
-            print_frontmatter_value(value, args.output_format)?;
+            print_frontmatter_value(&mut stdout, value, args.output_format)?;
         } else {
             return Err(SpliceError::FrontmatterKeyNotFound(key).into());
         }
@@ -583,11 +2246,11 @@ fn process_frontmatter_get(content: &str, args: FrontmatterGetArgs) -> anyhow::R
         match args.output_format {
             FrontmatterOutputFormat::String | FrontmatterOutputFormat::Yaml => {
                 let rendered = frontmatter::serialize_yaml_value(&frontmatter)?;
-                println!("{}", rendered);
+                writeln!(stdout, "{}", rendered)?;
             }
             FrontmatterOutputFormat::Json => {
                 let json = serde_json::to_string_pretty(&frontmatter)?;
-                println!("{}", json);
+                writeln!(stdout, "{}", json)?;
             }
         }
     }
@@ -595,10 +2258,179 @@ fn process_frontmatter_get(content: &str, args: FrontmatterGetArgs) -> anyhow::R
     Ok(())
 }
 
+fn process_infer(
+    content: &str,
+    args: InferArgs,
+    output_path: &Option<PathBuf>,
+    options: &GlobalOptions,
+) -> anyhow::Result<()> {
+    let original = load_document(content, options)?;
+    let edited_content = if args.edited.to_string_lossy() == "-" {
+        read_input(None)?
+    } else {
+        read_input(Some(&args.edited))?
+    };
+    let edited = load_document(&edited_content, options)?;
+
+    let operations = infer_operations(&original, &edited).map_err(map_splice_error)?;
+    let rendered = match args.output_format {
+        OperationsFormat::Yaml => serde_yaml::to_string(&operations)?,
+        OperationsFormat::Json => format!("{}\n", serde_json::to_string_pretty(&operations)?),
+    };
+
+    match output_path {
+        Some(path) => write_output_file(options, path, &rendered),
+        None => {
+            let mut stdout = options.stdout();
+            stdout.write_all(rendered.as_bytes())?;
+            stdout.flush()?;
+            Ok(())
+        }
+    }
+}
+
+fn process_completions(args: CompletionsArgs, options: &GlobalOptions) -> anyhow::Result<()> {
+    let mut command = Cli::command();
+    let mut stdout = options.stdout();
+    clap_complete::generate(args.shell, &mut command, "md-splice", &mut stdout);
+    stdout.flush()?;
+    Ok(())
+}
+
+fn process_concat(
+    args: ConcatArgs,
+    file: &Option<PathBuf>,
+    output_path: &Option<PathBuf>,
+    options: &GlobalOptions,
+) -> anyhow::Result<()> {
+    if file.is_some() {
+        return Err(anyhow!(
+            "concat reads the files given as arguments and does not accept --file."
+        ));
+    }
+    let is_stdin = |path: &PathBuf| path.to_string_lossy() == "-";
+    if args.inputs.iter().filter(|path| is_stdin(path)).count() > 1 {
+        return Err(SpliceError::AmbiguousStdinSource.into());
+    }
+
+    let documents = args
+        .inputs
+        .iter()
+        .map(|path| {
+            let content = read_input(Some(path).filter(|path| !is_stdin(path)))?;
+            load_document(&content, options)
+                .with_context(|| format!("Failed to load {}", path.display()))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let concat_options = ConcatOptions {
+        base_level: args.base_level,
+        frontmatter: map_frontmatter_strategy(args.frontmatter),
+    };
+    let combined =
+        MarkdownDocument::concat(&documents, &concat_options).map_err(map_splice_error)?;
+
+    finalize_output(
+        options,
+        OutputMode::Write,
+        output_path,
+        &None,
+        "",
+        combined.render(),
+    )
+}
+
+/// A section moved out of the document by `split`.
+struct SectionFile {
+    path: PathBuf,
+    content: String,
+}
+
+/// Renders each section at `args.level` into the file it moves to, and returns the blocks
+/// left in the document with any requested placeholders.
+fn split_sections(
+    doc: &MarkdownDocument,
+    args: &SplitArgs,
+) -> anyhow::Result<(Vec<SectionFile>, Vec<Block>)> {
+    let mut sections = Vec::new();
+    collect_sections_at_level(doc.heading_tree(), args.level, &mut sections);
+    if sections.is_empty() {
+        return Err(anyhow!("No level {} headings found to split.", args.level));
+    }
+
+    let blocks = doc.blocks();
+    let mut files = Vec::with_capacity(sections.len());
+    let mut remaining = Vec::new();
+    let mut cursor = 0;
+    for section in sections {
+        remaining.extend_from_slice(&blocks[cursor..section.blocks.start]);
+        let stem = match section.slug.trim_matches('-') {
+            "" => format!("section-{}", files.len() + 1),
+            _ => section.slug.clone(),
+        };
+        let path = args.out_dir.join(format!("{stem}.md"));
+        remaining.extend(split_placeholder(args.leave, &section.text, &path));
+        let content = render_fragment(&blocks[section.blocks.clone()], default_printer_config());
+        files.push(SectionFile { path, content });
+        cursor = section.blocks.end;
+    }
+    remaining.extend_from_slice(&blocks[cursor..]);
+
+    Ok((files, remaining))
+}
+
+/// Collects the headings of `level` in document order, without descending into their sections.
+fn collect_sections_at_level(nodes: Vec<HeadingNode>, level: u8, sections: &mut Vec<HeadingNode>) {
+    for node in nodes {
+        if node.level == level {
+            sections.push(node);
+        } else if node.level < level {
+            collect_sections_at_level(node.children, level, sections);
+        }
+    }
+}
+
+/// Builds the block left behind in place of a section moved to `path`.
+fn split_placeholder(kind: Option<SplitPlaceholder>, title: &str, path: &Path) -> Option<Block> {
+    let target = path.to_string_lossy().replace('\\', "/");
+    match kind? {
+        SplitPlaceholder::Link => Some(Block::Paragraph(vec![Inline::Link(Link {
+            destination: target,
+            title: None,
+            children: vec![Inline::Text(title.to_string())],
+        })])),
+        SplitPlaceholder::Include => Some(Block::HtmlBlock(format!("<!-- include: {target} -->"))),
+    }
+}
+
+fn write_split_files(out_dir: &Path, files: &[SectionFile], force: bool) -> anyhow::Result<()> {
+    if !force {
+        if let Some(file) = files.iter().find(|file| file.path.exists()) {
+            return Err(anyhow!(
+                "Section file {} already exists. Use --force to overwrite it.",
+                file.path.display()
+            ));
+        }
+    }
+
+    fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create output directory: {}", out_dir.display()))?;
+    for file in files {
+        fs::write(&file.path, &file.content)
+            .with_context(|| format!("Failed to write section file: {}", file.path.display()))?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn build_transaction_selector(
     select_type: Option<String>,
     select_contains: Option<String>,
     select_regex: Option<String>,
+    select_fingerprint: Option<String>,
+    select_annotation: Option<String>,
+    select_tag: Option<String>,
     select_ordinal: usize,
     after: Option<TxSelector>,
     within: Option<TxSelector>,
@@ -612,12 +2444,32 @@ fn build_transaction_selector(
         alias: None,
         select_type,
         select_contains,
+        select_contains_ci: None,
         select_regex,
+        select_not_contains: None,
+        select_not_regex: None,
+        select_fingerprint,
+        select_annotation,
+        select_tag,
+        select_info_contains: None,
+        select_slug: None,
+        select_min_words: None,
+        select_max_words: None,
+        select_empty: None,
+        select_depth: None,
+        recursive: false,
+        task_state: None,
         select_ordinal,
+        select_last: false,
+        max_matches: None,
+        tie_break: TieBreak::default(),
         after: after.map(Box::new),
         after_ref: None,
         within: within.map(Box::new),
         within_ref: None,
+        between: None,
+        within_slide: None,
+        at_line: None,
     })
 }
 
@@ -641,20 +2493,249 @@ fn build_optional_transaction_selector(
         alias: None,
         select_type,
         select_contains,
+        select_contains_ci: None,
         select_regex,
+        select_not_contains: None,
+        select_not_regex: None,
+        select_fingerprint: None,
+        select_annotation: None,
+        select_tag: None,
+        select_info_contains: None,
+        select_slug: None,
+        select_min_words: None,
+        select_max_words: None,
+        select_empty: None,
+        select_depth: None,
+        recursive: false,
+        task_state: None,
         select_ordinal: select_ordinal.unwrap_or(1),
+        select_last: false,
+        max_matches: None,
+        tie_break: TieBreak::default(),
         after: None,
         after_ref: None,
         within: None,
         within_ref: None,
+        between: None,
+        within_slide: None,
+        at_line: None,
     }))
 }
 
+/// Applies `--max-matches` and `--tie-break` to a primary selector, overriding the values of a
+/// `--selector-ref` definition when given.
+fn override_match_limits(
+    selector: &mut TxSelector,
+    max_matches: Option<usize>,
+    tie_break: Option<TieBreakArg>,
+) {
+    if max_matches.is_some() {
+        selector.max_matches = max_matches;
+    }
+    if let Some(tie_break) = tie_break {
+        selector.tie_break = map_cli_tie_break(tie_break);
+    }
+}
+
+/// Applies `--select-min-words`, `--select-max-words` and `--select-empty` to a primary
+/// selector, on top of a `--selector-ref` definition when given.
+fn narrow_by_length(
+    selector: &mut TxSelector,
+    min_words: Option<usize>,
+    max_words: Option<usize>,
+    empty: bool,
+) {
+    if min_words.is_some() {
+        selector.select_min_words = min_words;
+    }
+    if max_words.is_some() {
+        selector.select_max_words = max_words;
+    }
+    if empty {
+        selector.select_empty = Some(true);
+    }
+}
+
+/// Applies `--select-not-contains` and `--select-not-regex` to a primary selector, on top of
+/// a `--selector-ref` definition when given.
+fn exclude_by_text(
+    selector: &mut TxSelector,
+    not_contains: Option<String>,
+    not_regex: Option<String>,
+) -> anyhow::Result<()> {
+    if not_contains.is_some() {
+        selector.select_not_contains = not_contains;
+    }
+    if let Some(pattern) = not_regex {
+        Regex::new(&pattern)
+            .with_context(|| "Invalid regex pattern for --select-not-regex".to_string())?;
+        selector.select_not_regex = Some(pattern);
+    }
+    Ok(())
+}
+
+/// Indexes the selectors defined with `--define-selector`, rejecting duplicate names.
+fn collect_selector_definitions(
+    definitions: Vec<(String, TxSelector)>,
+) -> anyhow::Result<HashMap<String, TxSelector>> {
+    let mut selectors = HashMap::new();
+    for (name, selector) in definitions {
+        if selectors.contains_key(&name) {
+            return Err(SpliceError::SelectorAliasAlreadyDefined(name).into());
+        }
+        selectors.insert(name, selector);
+    }
+    Ok(selectors)
+}
+
+/// Looks up a selector defined with `--define-selector`, inlining any `after_ref`/`within_ref`
+/// that point at other defined selectors.
+fn resolve_selector_definition(name: &str, options: &GlobalOptions) -> anyhow::Result<TxSelector> {
+    lookup_selector_definition(name, &options.selector_definitions, &mut Vec::new())
+}
+
+fn lookup_selector_definition(
+    name: &str,
+    definitions: &HashMap<String, TxSelector>,
+    visiting: &mut Vec<String>,
+) -> anyhow::Result<TxSelector> {
+    if visiting.iter().any(|visited| visited == name) {
+        return Err(anyhow!("Selector '{name}' refers to itself."));
+    }
+    let mut selector = definitions
+        .get(name)
+        .cloned()
+        .ok_or_else(|| SpliceError::SelectorAliasNotDefined(name.to_string()))?;
+
+    visiting.push(name.to_string());
+    expand_selector_refs(&mut selector, definitions, visiting)?;
+    visiting.pop();
+    Ok(selector)
+}
+
+fn expand_selector_refs(
+    selector: &mut TxSelector,
+    definitions: &HashMap<String, TxSelector>,
+    visiting: &mut Vec<String>,
+) -> anyhow::Result<()> {
+    selector.alias = None;
+    let after_ref = selector.after_ref.take();
+    expand_nested_selector(
+        &mut selector.after,
+        after_ref,
+        "after",
+        definitions,
+        visiting,
+    )?;
+    let within_ref = selector.within_ref.take();
+    expand_nested_selector(
+        &mut selector.within,
+        within_ref,
+        "within",
+        definitions,
+        visiting,
+    )?;
+    if let Some(between) = selector.between.as_deref_mut() {
+        let start_ref = between.start_ref.take();
+        expand_nested_selector(
+            &mut between.start,
+            start_ref,
+            "start",
+            definitions,
+            visiting,
+        )?;
+        let end_ref = between.end_ref.take();
+        expand_nested_selector(&mut between.end, end_ref, "end", definitions, visiting)?;
+    }
+    Ok(())
+}
+
+fn expand_nested_selector(
+    nested: &mut Option<Box<TxSelector>>,
+    nested_ref: Option<String>,
+    field_name: &str,
+    definitions: &HashMap<String, TxSelector>,
+    visiting: &mut Vec<String>,
+) -> anyhow::Result<()> {
+    match (nested.as_deref_mut(), nested_ref) {
+        (Some(_), Some(_)) => {
+            Err(SpliceError::AmbiguousNestedSelectorSource(field_name.to_string()).into())
+        }
+        (Some(inner), None) => expand_selector_refs(inner, definitions, visiting),
+        (None, Some(name)) => {
+            let resolved = lookup_selector_definition(&name, definitions, visiting)?;
+            *nested = Some(Box::new(resolved));
+            Ok(())
+        }
+        (None, None) => Ok(()),
+    }
+}
+
+/// Converts a fully expanded selector definition into a locator selector.
+fn locator_selector_from_definition(selector: &TxSelector) -> anyhow::Result<Selector> {
+    let nested = |nested: Option<&TxSelector>| {
+        nested
+            .map(locator_selector_from_definition)
+            .transpose()
+            .map(|selector| selector.map(Box::new))
+    };
+
+    Ok(Selector {
+        select_type: selector.select_type.clone(),
+        select_contains: selector.select_contains.clone(),
+        select_contains_ci: selector.select_contains_ci.clone(),
+        select_regex: compile_optional_regex(selector.select_regex.clone(), "--selector-ref")?,
+        select_not_contains: selector.select_not_contains.clone(),
+        select_not_regex: compile_optional_regex(
+            selector.select_not_regex.clone(),
+            "--selector-ref",
+        )?,
+        select_fingerprint: selector.select_fingerprint.clone(),
+        select_annotation: selector.select_annotation.clone(),
+        select_tag: selector.select_tag.clone(),
+        select_info_contains: selector.select_info_contains.clone(),
+        select_slug: selector.select_slug.clone(),
+        select_min_words: selector.select_min_words,
+        select_max_words: selector.select_max_words,
+        select_empty: selector.select_empty,
+        select_depth: selector.select_depth,
+        recursive: selector.recursive,
+        task_state: selector.task_state,
+        select_ordinal: selector.select_ordinal,
+        select_last: selector.select_last,
+        after: nested(selector.after.as_deref())?,
+        within: nested(selector.within.as_deref())?,
+        between: match selector.between.as_deref() {
+            Some(between) => {
+                let landmark = |landmark: Option<&TxSelector>, field_name: &str| {
+                    nested(landmark)?.ok_or_else(|| {
+                        anyhow::Error::from(SpliceError::AmbiguousNestedSelectorSource(
+                            field_name.to_string(),
+                        ))
+                    })
+                };
+                Some(Box::new(Between {
+                    start: *landmark(between.start.as_deref(), "start")?,
+                    end: *landmark(between.end.as_deref(), "end")?,
+                }))
+            }
+            None => None,
+        },
+        within_slide: selector.within_slide,
+        at_line: selector.at_line,
+        max_matches: selector.max_matches,
+        tie_break: selector.tie_break,
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 fn build_locator_selector_from_args(
     select_type: Option<String>,
     select_contains: Option<String>,
     select_regex: Option<String>,
+    select_fingerprint: Option<String>,
+    select_annotation: Option<String>,
+    select_tag: Option<String>,
     select_ordinal: usize,
     after_select_type: Option<String>,
     after_select_contains: Option<String>,
@@ -684,6 +2765,9 @@ fn build_locator_selector_from_args(
         select_type,
         select_contains,
         select_regex,
+        select_fingerprint,
+        select_annotation,
+        select_tag,
         select_ordinal,
         after,
         within,
@@ -706,17 +2790,41 @@ fn build_optional_locator_selector_from_args(
     Ok(Some(Selector {
         select_type,
         select_contains,
+        select_contains_ci: None,
         select_regex,
+        select_not_contains: None,
+        select_not_regex: None,
+        select_fingerprint: None,
+        select_annotation: None,
+        select_tag: None,
+        select_info_contains: None,
+        select_slug: None,
+        select_min_words: None,
+        select_max_words: None,
+        select_empty: None,
+        select_depth: None,
+        recursive: false,
+        task_state: None,
         select_ordinal: select_ordinal.unwrap_or(1),
+        select_last: false,
         after: None,
         within: None,
+        between: None,
+        within_slide: None,
+        at_line: None,
+        max_matches: None,
+        tie_break: TieBreak::default(),
     }))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_primary_selector(
     select_type: Option<String>,
     select_contains: Option<String>,
     select_regex: Option<String>,
+    select_fingerprint: Option<String>,
+    select_annotation: Option<String>,
+    select_tag: Option<String>,
     select_ordinal: usize,
     after: Option<Selector>,
     within: Option<Selector>,
@@ -726,10 +2834,30 @@ fn build_primary_selector(
     Ok(Selector {
         select_type,
         select_contains,
+        select_contains_ci: None,
         select_regex,
+        select_not_contains: None,
+        select_not_regex: None,
+        select_fingerprint,
+        select_annotation,
+        select_tag,
+        select_info_contains: None,
+        select_slug: None,
+        select_min_words: None,
+        select_max_words: None,
+        select_empty: None,
+        select_depth: None,
+        recursive: false,
+        task_state: None,
         select_ordinal,
+        select_last: false,
         after: after.map(Box::new),
         within: within.map(Box::new),
+        between: None,
+        within_slide: None,
+        at_line: None,
+        max_matches: None,
+        tie_break: TieBreak::default(),
     })
 }
 
@@ -741,66 +2869,56 @@ fn compile_optional_regex(pattern: Option<String>, context: &str) -> anyhow::Res
         .transpose()
 }
 
-fn compute_range_end(
-    blocks: &[Block],
-    start_index: usize,
-    until_selector: &Selector,
-) -> anyhow::Result<usize> {
-    if start_index + 1 >= blocks.len() {
-        return Ok(blocks.len());
-    }
-
-    match locate(&blocks[start_index + 1..], until_selector) {
-        Ok((FoundNode::Block { index, .. }, _)) => Ok(start_index + 1 + index),
-        Ok((FoundNode::ListItem { .. }, _)) => Err(SpliceError::RangeRequiresBlock.into()),
-        Err(SpliceError::NodeNotFound) => Ok(blocks.len()),
-        Err(other) => Err(other.into()),
-    }
-}
-
-fn render_heading_section(doc_blocks: &[Block], found: &FoundNode) -> anyhow::Result<String> {
-    if let FoundNode::Block { index, block } = found {
-        if let Some(level) = get_heading_level(block) {
-            let end_index = find_heading_section_end(doc_blocks, *index, level);
-            return Ok(render_blocks(&doc_blocks[*index..end_index]));
+/// Renders the section owned by a heading, or a list item together with everything nested
+/// under it (as a standalone list).
+fn render_section(doc_blocks: &[Block], found: &FoundNode) -> anyhow::Result<String> {
+    match found {
+        FoundNode::Block { index, .. } => {
+            let section =
+                section_range(doc_blocks, *index).ok_or(SpliceError::SectionRequiresHeading)?;
+            Ok(render_fragment(
+                &doc_blocks[section],
+                default_printer_config(),
+            ))
         }
+        _ => render_found_node(doc_blocks, found),
     }
-
-    Err(SpliceError::SectionRequiresHeading.into())
 }
 
 fn render_found_node(doc_blocks: &[Block], found: &FoundNode) -> anyhow::Result<String> {
     match found {
-        FoundNode::Block { block, .. } => Ok(render_blocks(std::slice::from_ref(block))),
+        FoundNode::Block { block, .. } | FoundNode::ContainedBlock { block, .. } => Ok(
+            render_fragment(std::slice::from_ref(block), default_printer_config()),
+        ),
         FoundNode::ListItem {
-            block_index, item, ..
-        } => match doc_blocks.get(*block_index) {
-            Some(Block::List(list)) => {
+            block_index,
+            item_index,
+            path,
+            item,
+        } => match item_list(doc_blocks, *block_index, path) {
+            Some(list) => {
                 let mut single_list = list.clone();
                 single_list.items = vec![(*item).clone()];
-                Ok(render_blocks(std::slice::from_ref(&Block::List(
-                    single_list,
-                ))))
+                // Keep the item's own number when it is extracted from an ordered list.
+                if let ListKind::Ordered(options) = &mut single_list.kind {
+                    options.start += *item_index as u64;
+                }
+                Ok(render_fragment(
+                    std::slice::from_ref(&Block::List(single_list)),
+                    default_printer_config(),
+                ))
             }
             _ => Err(anyhow!(
                 "Internal error: block at index {} is not a list",
                 block_index
             )),
         },
+        _ => render_table_node(doc_blocks, found)
+            .or_else(|| render_inline_node(found))
+            .ok_or_else(|| anyhow!("Internal error: the matched node cannot be rendered")),
     }
 }
 
-fn render_blocks(blocks: &[Block]) -> String {
-    let temp_doc = markdown_ppp::ast::Document {
-        blocks: blocks.to_vec(),
-    };
-    let mut rendered = render_markdown(&temp_doc, default_printer_config());
-    if !rendered.is_empty() && !rendered.ends_with('\n') {
-        rendered.push('\n');
-    }
-    rendered
-}
-
 fn parse_frontmatter_path(path: &str) -> anyhow::Result<Vec<FrontmatterPathSegment>> {
     if path.trim().is_empty() {
         return Err(anyhow!("Frontmatter key cannot be empty"));
@@ -924,27 +3042,28 @@ fn resolve_frontmatter_path<'a>(
 }
 
 fn print_frontmatter_value(
+    stdout: &mut dyn Write,
     value: &YamlValue,
     format: FrontmatterOutputFormat,
 ) -> anyhow::Result<()> {
     match format {
         FrontmatterOutputFormat::String => match value {
             YamlValue::Null => {}
-            YamlValue::Bool(v) => println!("{}", v),
-            YamlValue::Number(v) => println!("{}", v),
-            YamlValue::String(v) => println!("{}", v),
+            YamlValue::Bool(v) => writeln!(stdout, "{}", v)?,
+            YamlValue::Number(v) => writeln!(stdout, "{}", v)?,
+            YamlValue::String(v) => writeln!(stdout, "{}", v)?,
             other => {
                 let rendered = frontmatter::serialize_yaml_value(other)?;
-                println!("{}", rendered);
+                writeln!(stdout, "{}", rendered)?;
             }
         },
         FrontmatterOutputFormat::Json => {
             let json = serde_json::to_string_pretty(value)?;
-            println!("{}", json);
+            writeln!(stdout, "{}", json)?;
         }
         FrontmatterOutputFormat::Yaml => {
             let rendered = frontmatter::serialize_yaml_value(value)?;
-            println!("{}", rendered);
+            writeln!(stdout, "{}", rendered)?;
         }
     }
 
@@ -956,6 +3075,14 @@ fn parse_yaml_value(content: &str) -> anyhow::Result<YamlValue> {
         .with_context(|| "Failed to parse value as YAML for frontmatter set operation")
 }
 
+fn map_frontmatter_strategy(arg: FrontmatterStrategyArg) -> FrontmatterStrategy {
+    match arg {
+        FrontmatterStrategyArg::First => FrontmatterStrategy::First,
+        FrontmatterStrategyArg::Merge => FrontmatterStrategy::Merge,
+        FrontmatterStrategyArg::Drop => FrontmatterStrategy::Drop,
+    }
+}
+
 fn map_frontmatter_format(arg: FrontmatterFormatArg) -> FrontmatterFormat {
     match arg {
         FrontmatterFormatArg::Yaml => FrontmatterFormat::Yaml,
@@ -978,27 +3105,19 @@ fn map_cli_insert_position(position: CliInsertPosition) -> TxInsertPosition {
     }
 }
 
-fn get_heading_level(block: &Block) -> Option<u8> {
-    if let Block::Heading(Heading { kind, .. }) = block {
-        Some(match kind {
-            HeadingKind::Atx(level) => *level,
-            HeadingKind::Setext(SetextHeading::Level1) => 1,
-            HeadingKind::Setext(SetextHeading::Level2) => 2,
-        })
-    } else {
-        None
+fn map_cli_task_state(task_state: TaskStateArg) -> TaskStatus {
+    match task_state {
+        TaskStateArg::Complete => TaskStatus::Complete,
+        TaskStateArg::Incomplete => TaskStatus::Incomplete,
+        TaskStateArg::Any => TaskStatus::Any,
     }
 }
 
-fn find_heading_section_end(blocks: &[Block], start_index: usize, target_level: u8) -> usize {
-    for (i, block) in blocks.iter().enumerate().skip(start_index + 1) {
-        if let Some(level) = get_heading_level(block) {
-            if level <= target_level {
-                return i;
-            }
-        }
+fn map_cli_tie_break(tie_break: TieBreakArg) -> TieBreak {
+    match tie_break {
+        TieBreakArg::DocumentOrder => TieBreak::DocumentOrder,
+        TieBreakArg::DeepestFirst => TieBreak::DeepestFirst,
     }
-    blocks.len()
 }
 
 fn map_splice_error(err: SpliceError) -> anyhow::Error {
@@ -1013,4 +3132,18 @@ enum OutputMode {
     Write,
     DryRun,
     Diff,
+    Check { semantic: bool },
+}
+
+impl OutputMode {
+    /// Returns the mode selected by the `--dry-run` and `--diff` flags; `--diff` wins.
+    fn preview(dry_run: bool, diff: bool) -> Self {
+        if diff {
+            OutputMode::Diff
+        } else if dry_run {
+            OutputMode::DryRun
+        } else {
+            OutputMode::Write
+        }
+    }
 }
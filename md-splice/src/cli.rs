@@ -1,7 +1,14 @@
 //! Defines the command-line interface for the application.
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::builder::{PossibleValue, StringValueParser, TypedValueParser};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum, ValueHint};
+use clap_complete::Shell;
+use md_splice_lib::frontmatter::{FrontmatterDelimiter, FrontmatterFormat};
+use md_splice_lib::locator::SELECT_TYPES;
+use md_splice_lib::transaction::Selector;
+use std::ffi::OsStr;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -11,17 +18,166 @@ use std::path::PathBuf;
 )]
 pub struct Cli {
     /// The Markdown file to modify. [default: reads from stdin]
-    #[arg(short, long, global = true, value_name = "FILE_PATH")]
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "FILE_PATH",
+        value_hint = ValueHint::FilePath
+    )]
     pub file: Option<PathBuf>,
 
-    /// Write the output to a new file instead of modifying the original.
-    #[arg(short, long, global = true, value_name = "OUTPUT_PATH")]
+    /// Write the output to a new file instead of modifying the original ('-' for stdout).
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "OUTPUT_PATH",
+        value_hint = ValueHint::FilePath
+    )]
     pub output: Option<PathBuf>,
 
+    /// Name used for the document read from stdin in error messages and diff headers.
+    #[arg(long, global = true, value_name = "NAME", conflicts_with = "file")]
+    pub stdin_filename: Option<String>,
+
+    /// Keep malformed Markdown (e.g. unclosed code fences) verbatim and warn instead of failing.
+    #[arg(long, global = true)]
+    pub lenient: bool,
+
+    /// Split compact definition list entries ("Term" then ": Definition") so that dt and dd selectors match them.
+    #[arg(long, global = true)]
+    pub definition_lists: bool,
+
+    /// Also recognize YAML frontmatter between OPEN[:CLOSE] delimiter lines (e.g. ";;;", "---yaml:---"). Repeatable.
+    #[arg(
+        long = "frontmatter-delimiter",
+        global = true,
+        value_name = "DELIM",
+        value_parser = parse_frontmatter_delimiter
+    )]
+    pub frontmatter_delimiters: Vec<FrontmatterDelimiter>,
+
+    /// Define a selector as NAME=SELECTOR (YAML or JSON, as in operations files) for use with --selector-ref. Repeatable.
+    #[arg(
+        long = "define-selector",
+        global = true,
+        value_name = "DEFINITION",
+        value_parser = parse_selector_definition
+    )]
+    pub selector_definitions: Vec<(String, Selector)>,
+
+    /// Rewrite the relative link and image paths of the document for its move from directory FROM to directory TO.
+    #[arg(
+        long,
+        global = true,
+        num_args = 2,
+        value_names = ["FROM", "TO"],
+        value_hint = ValueHint::DirPath
+    )]
+    pub rebase_links: Option<Vec<PathBuf>>,
+
+    /// Suppress all output except error messages; rely on the exit status instead.
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Fail when a selector matches more than one node instead of using the first match.
+    #[arg(long, global = true)]
+    pub strict: bool,
+
+    /// Reject operations that read files (content_file, value_file, import_section), e.g. in batches from untrusted clients.
+    #[arg(long, global = true)]
+    pub no_fs: bool,
+
+    /// Print the time spent in each phase (read, parse, locate, apply, render, write) to stderr.
+    #[arg(long, global = true)]
+    pub profile: bool,
+
+    /// When to color diffs, `get` matches, and errors. 'auto' also honors NO_COLOR.
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "WHEN",
+        default_value_t = ColorChoice::Auto
+    )]
+    pub color: ColorChoice,
+
+    /// Print the accepted node types, selector criteria, and scope modifiers, then exit.
+    #[arg(long, global = true)]
+    pub help_selectors: bool,
+
     #[command(subcommand)]
     pub command: Command,
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Parses an `OPEN[:CLOSE]` pair; the closing line defaults to the opening one.
+fn parse_frontmatter_delimiter(value: &str) -> Result<FrontmatterDelimiter, String> {
+    let (open, close) = value.split_once(':').unwrap_or((value, value));
+    if open.trim().is_empty() || close.trim().is_empty() {
+        return Err("delimiters must not be empty".to_string());
+    }
+    Ok(FrontmatterDelimiter::new(
+        open,
+        close,
+        FrontmatterFormat::Yaml,
+    ))
+}
+
+/// Parses a number of seconds, such as `30` or `0.5`.
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    let seconds: f64 = value
+        .parse()
+        .map_err(|_| "expected a number of seconds".to_string())?;
+    Duration::try_from_secs_f64(seconds).map_err(|err| err.to_string())
+}
+
+/// Parses a `NAME=SELECTOR` pair where the selector uses the operations file syntax.
+fn parse_selector_definition(value: &str) -> Result<(String, Selector), String> {
+    let (name, selector) = value
+        .split_once('=')
+        .ok_or_else(|| "expected NAME=SELECTOR".to_string())?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("selector name must not be empty".to_string());
+    }
+    let selector = serde_yaml::from_str(selector).map_err(|err| err.to_string())?;
+    Ok((name.to_string(), selector))
+}
+
+/// Accepts any node type, advertising the common ones to shell completions without
+/// rejecting aliases such as `paragraph` or `codeblock`.
+#[derive(Clone)]
+struct SelectTypeParser;
+
+impl TypedValueParser for SelectTypeParser {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<String, clap::Error> {
+        StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            SELECT_TYPES
+                .iter()
+                .map(|select_type| PossibleValue::new(select_type.names[0])),
+        ))
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Command {
     /// Insert new Markdown content at a specified position.
@@ -31,13 +187,41 @@ pub enum Command {
     /// Delete a Markdown node or section.
     #[command(alias = "remove")]
     Delete(DeleteArgs),
+    /// Move a Markdown node or section next to (or into) another node.
+    Move(MoveArgs),
     /// Read Markdown content matching a selector without modifying the file.
     Get(GetArgs),
+    /// Exit with status 0 if a node matches the selector and 3 if none does, printing nothing.
+    Exists(GetArgs),
+    /// Print the document with sections folded to their headings, except the expanded ones.
+    Cat(CatArgs),
+    /// Report the constructs of the document that md-splice does not preserve when editing it.
+    Doctor(DoctorArgs),
     /// Apply a sequence of transactional operations to the document.
     Apply(ApplyArgs),
+    /// Remove headings whose sections contain no content.
+    PruneEmptySections(PruneEmptySectionsArgs),
+    /// Rename headings whose text repeats an earlier heading.
+    DedupeHeadings(DedupeHeadingsArgs),
+    /// Report headings with the same text or anchor as another heading, failing if any exist.
+    CheckHeadings,
+    /// Print the heading hierarchy of the document with the slug and position of each heading.
+    Outline(OutlineArgs),
+    /// Generate or refresh the table of contents of the document.
+    Toc(TocArgs),
+    /// Move each section of a heading level into its own file.
+    Split(SplitArgs),
+    /// Combine several Markdown files into one document.
+    Concat(ConcatArgs),
+    /// Infer the operations that turn the document into an edited version of it.
+    Infer(InferArgs),
     /// Inspect or modify document frontmatter.
     #[command(subcommand)]
     Frontmatter(FrontmatterCommand),
+    /// Print a shell completion script to stdout.
+    Completions(CompletionsArgs),
+    /// Print the man page (roff) to stdout.
+    Man,
 }
 
 #[derive(Subcommand, Debug)]
@@ -64,6 +248,10 @@ pub struct FrontmatterGetArgs {
         value_name = "FORMAT"
     )]
     pub output_format: FrontmatterOutputFormat,
+
+    /// Print the frontmatter exactly as written between its delimiters instead of re-serializing it.
+    #[arg(long, conflicts_with_all = ["key", "output_format"])]
+    pub raw: bool,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
@@ -110,6 +298,25 @@ pub enum FrontmatterFormatArg {
     Toml,
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum SlugStyleArg {
+    Github,
+    Gitlab,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum TieBreakArg {
+    DocumentOrder,
+    DeepestFirst,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum TaskStateArg {
+    Complete,
+    Incomplete,
+    Any,
+}
+
 #[derive(Parser, Debug)]
 pub struct ModificationArgs {
     // --- Content to be added ---
@@ -129,23 +336,119 @@ pub struct ModificationArgs {
 
     // --- Node Selection ---
     /// Select node by type (e.g., 'p', 'h1', 'list', 'table').
-    #[arg(long, value_name = "TYPE")]
+    #[arg(long, value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub select_type: Option<String>,
 
     /// Select node by its text content (fixed string).
     #[arg(long, value_name = "TEXT")]
     pub select_contains: Option<String>,
 
+    /// Select node by its text content, ignoring case and runs of whitespace.
+    #[arg(long, value_name = "TEXT")]
+    pub select_contains_ci: Option<String>,
+
     /// Select node by its text content (regex pattern).
     #[arg(long, value_name = "REGEX")]
     pub select_regex: Option<String>,
 
+    /// Skip nodes whose text content contains this text (e.g. '[x]').
+    #[arg(long, value_name = "TEXT")]
+    pub select_not_contains: Option<String>,
+
+    /// Skip nodes whose text content matches this regex pattern.
+    #[arg(long, value_name = "REGEX")]
+    pub select_not_regex: Option<String>,
+
+    /// Select node by its content fingerprint, as printed by `get --fingerprint` (hex prefix).
+    #[arg(long, value_name = "HASH")]
+    pub select_fingerprint: Option<String>,
+
+    /// Select the node directly after an HTML comment with this text (e.g. 'slot: notes').
+    #[arg(long, value_name = "TEXT")]
+    pub select_annotation: Option<String>,
+
+    /// Select raw HTML blocks opening an element with this tag (e.g. 'details').
+    #[arg(long, value_name = "TAG")]
+    pub select_tag: Option<String>,
+
+    /// Select code blocks whose info string contains this text (e.g. 'no_run').
+    #[arg(long, value_name = "TEXT")]
+    pub select_info_contains: Option<String>,
+
+    /// Select the heading whose anchor is this slug, as GitHub generates it (e.g. 'installation-notes').
+    #[arg(long, value_name = "SLUG")]
+    pub select_slug: Option<String>,
+
     /// Select the Nth matching node (1-indexed). Default is 1.
     #[arg(long, value_name = "N", default_value_t = 1)]
     pub select_ordinal: usize,
 
+    /// Count --select-ordinal from the last matching node, so that --select-last alone selects the last one.
+    #[arg(long)]
+    pub select_last: bool,
+
+    /// Fail instead of picking a node when the selector matches more than N nodes.
+    #[arg(long, value_name = "N")]
+    pub max_matches: Option<usize>,
+
+    /// The order in which matches are ranked before --select-ordinal picks one.
+    #[arg(long, value_enum, value_name = "ORDER")]
+    pub tie_break: Option<TieBreakArg>,
+
+    /// Select nodes with at least N words (for a heading, in its section).
+    #[arg(long, value_name = "N")]
+    pub select_min_words: Option<usize>,
+
+    /// Select nodes with at most N words (for a heading, in its section).
+    #[arg(long, value_name = "N")]
+    pub select_max_words: Option<usize>,
+
+    /// Select nodes without any word, such as stub paragraphs or headings with an empty section.
+    #[arg(long)]
+    pub select_empty: bool,
+
+    /// Select list items nested N lists deep, 1 being the items of a top-level list (use with --select-type li).
+    #[arg(long, value_name = "N")]
+    pub select_depth: Option<usize>,
+
+    /// Also search blocks nested in block quotes, alerts, footnote definitions and list items.
+    #[arg(long)]
+    pub recursive: bool,
+
+    /// Select task list items in this state (use with --select-type li).
+    #[arg(long, value_enum, value_name = "STATE")]
+    pub task_state: Option<TaskStateArg>,
+
+    /// Use a selector defined with --define-selector instead of the --select-* flags.
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = [
+            "select_type",
+            "select_contains",
+            "select_contains_ci",
+            "select_regex",
+            "select_not_contains",
+            "select_not_regex",
+            "select_fingerprint",
+            "select_annotation",
+            "select_tag",
+            "select_slug",
+            "select_ordinal",
+            "after_select_type",
+            "after_select_contains",
+            "after_select_regex",
+            "after_select_ordinal",
+            "within_select_type",
+            "within_select_contains",
+            "within_select_regex",
+            "within_select_ordinal",
+        ]
+    )]
+    pub selector_ref: Option<String>,
+
     /// Restrict the search to the first match that occurs after another selector.
-    #[arg(long = "after-select-type", value_name = "TYPE")]
+    #[arg(long = "after-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub after_select_type: Option<String>,
 
     /// Restrict the search to the first match that occurs after another selector.
@@ -161,7 +464,7 @@ pub struct ModificationArgs {
     pub after_select_ordinal: Option<usize>,
 
     /// Restrict the search to nodes contained within another selector.
-    #[arg(long = "within-select-type", value_name = "TYPE")]
+    #[arg(long = "within-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub within_select_type: Option<String>,
 
     /// Restrict the search to nodes contained within another selector.
@@ -176,8 +479,16 @@ pub struct ModificationArgs {
     #[arg(long = "within-select-ordinal", value_name = "N")]
     pub within_select_ordinal: Option<usize>,
 
+    /// Restrict the search to the Nth slide (1-indexed) of a deck split by `---` thematic breaks.
+    #[arg(long, value_name = "N")]
+    pub within_slide: Option<usize>,
+
+    /// Select the top-level block written across line N (1-indexed) of the input, or nodes nested in it (e.g. the block under an editor's cursor).
+    #[arg(long, value_name = "N")]
+    pub select_at_line: Option<usize>,
+
     /// Select nodes up to (but not including) another selector.
-    #[arg(long = "until-type", value_name = "TYPE")]
+    #[arg(long = "until-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub until_type: Option<String>,
 
     /// Select nodes up to (but not including) another selector.
@@ -192,30 +503,153 @@ pub struct ModificationArgs {
     /// Position for the 'insert' operation.
     #[arg(short, long, value_enum, default_value_t = InsertPosition::After)]
     pub position: InsertPosition,
+
+    /// Renumber ordered lists that continue an earlier list after inserting.
+    #[arg(long)]
+    pub renumber: bool,
+
+    // --- Replace-specific options ---
+    /// Rewrite links to the anchors of replaced headings so they follow the new headings.
+    #[arg(long)]
+    pub update_links: bool,
+
+    /// Replace only the cell in row N of the matched table (0 is the header row).
+    #[arg(long, value_name = "N", requires = "col")]
+    pub row: Option<usize>,
+
+    /// Replace only the cell in column N (1-indexed) of the matched table.
+    #[arg(long, value_name = "N", requires = "row")]
+    pub col: Option<usize>,
+
+    // --- Output options ---
+    /// Preview the result without writing any files.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Show a diff of the pending changes instead of writing files.
+    #[arg(long)]
+    pub diff: bool,
 }
 
 /// Arguments for the `delete` command.
 #[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("section_target").args(["select_type", "select_slug", "selector_ref"]).multiple(true)))]
 pub struct DeleteArgs {
     // --- Node Selection ---
     /// Select node by type (e.g., 'p', 'h1', 'list', 'table').
-    #[arg(long, value_name = "TYPE")]
+    #[arg(long, value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub select_type: Option<String>,
 
     /// Select node by its text content (fixed string).
     #[arg(long, value_name = "TEXT")]
     pub select_contains: Option<String>,
 
+    /// Select node by its text content, ignoring case and runs of whitespace.
+    #[arg(long, value_name = "TEXT")]
+    pub select_contains_ci: Option<String>,
+
     /// Select node by its text content (regex pattern).
     #[arg(long, value_name = "REGEX")]
     pub select_regex: Option<String>,
 
+    /// Skip nodes whose text content contains this text (e.g. '[x]').
+    #[arg(long, value_name = "TEXT")]
+    pub select_not_contains: Option<String>,
+
+    /// Skip nodes whose text content matches this regex pattern.
+    #[arg(long, value_name = "REGEX")]
+    pub select_not_regex: Option<String>,
+
+    /// Select node by its content fingerprint, as printed by `get --fingerprint` (hex prefix).
+    #[arg(long, value_name = "HASH")]
+    pub select_fingerprint: Option<String>,
+
+    /// Select the node directly after an HTML comment with this text (e.g. 'slot: notes').
+    #[arg(long, value_name = "TEXT")]
+    pub select_annotation: Option<String>,
+
+    /// Select raw HTML blocks opening an element with this tag (e.g. 'details').
+    #[arg(long, value_name = "TAG")]
+    pub select_tag: Option<String>,
+
+    /// Select code blocks whose info string contains this text (e.g. 'no_run').
+    #[arg(long, value_name = "TEXT")]
+    pub select_info_contains: Option<String>,
+
+    /// Select the heading whose anchor is this slug, as GitHub generates it (e.g. 'installation-notes').
+    #[arg(long, value_name = "SLUG")]
+    pub select_slug: Option<String>,
+
     /// Select the Nth matching node (1-indexed). Default is 1.
     #[arg(long, value_name = "N", default_value_t = 1)]
     pub select_ordinal: usize,
 
+    /// Count --select-ordinal from the last matching node, so that --select-last alone selects the last one.
+    #[arg(long)]
+    pub select_last: bool,
+
+    /// Fail instead of picking a node when the selector matches more than N nodes.
+    #[arg(long, value_name = "N")]
+    pub max_matches: Option<usize>,
+
+    /// The order in which matches are ranked before --select-ordinal picks one.
+    #[arg(long, value_enum, value_name = "ORDER")]
+    pub tie_break: Option<TieBreakArg>,
+
+    /// Select nodes with at least N words (for a heading, in its section).
+    #[arg(long, value_name = "N")]
+    pub select_min_words: Option<usize>,
+
+    /// Select nodes with at most N words (for a heading, in its section).
+    #[arg(long, value_name = "N")]
+    pub select_max_words: Option<usize>,
+
+    /// Select nodes without any word, such as stub paragraphs or headings with an empty section.
+    #[arg(long)]
+    pub select_empty: bool,
+
+    /// Select list items nested N lists deep, 1 being the items of a top-level list (use with --select-type li).
+    #[arg(long, value_name = "N")]
+    pub select_depth: Option<usize>,
+
+    /// Also search blocks nested in block quotes, alerts, footnote definitions and list items.
+    #[arg(long)]
+    pub recursive: bool,
+
+    /// Select task list items in this state (use with --select-type li).
+    #[arg(long, value_enum, value_name = "STATE")]
+    pub task_state: Option<TaskStateArg>,
+
+    /// Use a selector defined with --define-selector instead of the --select-* flags.
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = [
+            "select_type",
+            "select_contains",
+            "select_contains_ci",
+            "select_regex",
+            "select_not_contains",
+            "select_not_regex",
+            "select_fingerprint",
+            "select_annotation",
+            "select_tag",
+            "select_slug",
+            "select_ordinal",
+            "after_select_type",
+            "after_select_contains",
+            "after_select_regex",
+            "after_select_ordinal",
+            "within_select_type",
+            "within_select_contains",
+            "within_select_regex",
+            "within_select_ordinal",
+        ]
+    )]
+    pub selector_ref: Option<String>,
+
     /// Restrict the search to the first match that occurs after another selector.
-    #[arg(long = "after-select-type", value_name = "TYPE")]
+    #[arg(long = "after-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub after_select_type: Option<String>,
 
     /// Restrict the search to the first match that occurs after another selector.
@@ -231,7 +665,7 @@ pub struct DeleteArgs {
     pub after_select_ordinal: Option<usize>,
 
     /// Restrict the search to nodes contained within another selector.
-    #[arg(long = "within-select-type", value_name = "TYPE")]
+    #[arg(long = "within-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub within_select_type: Option<String>,
 
     /// Restrict the search to nodes contained within another selector.
@@ -246,8 +680,16 @@ pub struct DeleteArgs {
     #[arg(long = "within-select-ordinal", value_name = "N")]
     pub within_select_ordinal: Option<usize>,
 
+    /// Restrict the search to the Nth slide (1-indexed) of a deck split by `---` thematic breaks.
+    #[arg(long, value_name = "N")]
+    pub within_slide: Option<usize>,
+
+    /// Select the top-level block written across line N (1-indexed) of the input, or nodes nested in it (e.g. the block under an editor's cursor).
+    #[arg(long, value_name = "N")]
+    pub select_at_line: Option<usize>,
+
     /// Select nodes up to (but not including) another selector.
-    #[arg(long = "until-type", value_name = "TYPE")]
+    #[arg(long = "until-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub until_type: Option<String>,
 
     /// Select nodes up to (but not including) another selector.
@@ -260,26 +702,284 @@ pub struct DeleteArgs {
 
     // --- Delete-specific options ---
     /// When deleting a heading, also delete its entire section.
-    #[arg(long, requires = "select_type")]
+    #[arg(long, requires = "section_target")]
+    pub section: bool,
+
+    /// Renumber ordered lists that continue an earlier list after deleting.
+    #[arg(long)]
+    pub renumber: bool,
+
+    // --- Output options ---
+    /// Preview the result without writing any files.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Show a diff of the pending changes instead of writing files.
+    #[arg(long)]
+    pub diff: bool,
+}
+
+/// Arguments for the `move` command.
+#[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("section_target").args(["select_type", "select_slug", "selector_ref"]).multiple(true)))]
+#[command(group(
+    ArgGroup::new("destination")
+        .args(["to_select_type", "to_select_contains", "to_select_regex", "to_selector_ref"])
+        .multiple(true)
+        .required(true)
+))]
+pub struct MoveArgs {
+    // --- Node Selection ---
+    /// Select node by type (e.g., 'p', 'h1', 'list', 'table').
+    #[arg(long, value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
+    pub select_type: Option<String>,
+
+    /// Select node by its text content (fixed string).
+    #[arg(long, value_name = "TEXT")]
+    pub select_contains: Option<String>,
+
+    /// Select node by its text content, ignoring case and runs of whitespace.
+    #[arg(long, value_name = "TEXT")]
+    pub select_contains_ci: Option<String>,
+
+    /// Select node by its text content (regex pattern).
+    #[arg(long, value_name = "REGEX")]
+    pub select_regex: Option<String>,
+
+    /// Skip nodes whose text content contains this text (e.g. '[x]').
+    #[arg(long, value_name = "TEXT")]
+    pub select_not_contains: Option<String>,
+
+    /// Skip nodes whose text content matches this regex pattern.
+    #[arg(long, value_name = "REGEX")]
+    pub select_not_regex: Option<String>,
+
+    /// Select node by its content fingerprint, as printed by `get --fingerprint` (hex prefix).
+    #[arg(long, value_name = "HASH")]
+    pub select_fingerprint: Option<String>,
+
+    /// Select the node directly after an HTML comment with this text (e.g. 'slot: notes').
+    #[arg(long, value_name = "TEXT")]
+    pub select_annotation: Option<String>,
+
+    /// Select raw HTML blocks opening an element with this tag (e.g. 'details').
+    #[arg(long, value_name = "TAG")]
+    pub select_tag: Option<String>,
+
+    /// Select code blocks whose info string contains this text (e.g. 'no_run').
+    #[arg(long, value_name = "TEXT")]
+    pub select_info_contains: Option<String>,
+
+    /// Select the heading whose anchor is this slug, as GitHub generates it (e.g. 'installation-notes').
+    #[arg(long, value_name = "SLUG")]
+    pub select_slug: Option<String>,
+
+    /// Select the Nth matching node (1-indexed). Default is 1.
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    pub select_ordinal: usize,
+
+    /// Count --select-ordinal from the last matching node, so that --select-last alone selects the last one.
+    #[arg(long)]
+    pub select_last: bool,
+
+    /// Fail instead of picking a node when the selector matches more than N nodes.
+    #[arg(long, value_name = "N")]
+    pub max_matches: Option<usize>,
+
+    /// The order in which matches are ranked before --select-ordinal picks one.
+    #[arg(long, value_enum, value_name = "ORDER")]
+    pub tie_break: Option<TieBreakArg>,
+
+    /// Select nodes with at least N words (for a heading, in its section).
+    #[arg(long, value_name = "N")]
+    pub select_min_words: Option<usize>,
+
+    /// Select nodes with at most N words (for a heading, in its section).
+    #[arg(long, value_name = "N")]
+    pub select_max_words: Option<usize>,
+
+    /// Select nodes without any word, such as stub paragraphs or headings with an empty section.
+    #[arg(long)]
+    pub select_empty: bool,
+
+    /// Select list items nested N lists deep, 1 being the items of a top-level list (use with --select-type li).
+    #[arg(long, value_name = "N")]
+    pub select_depth: Option<usize>,
+
+    /// Also search blocks nested in block quotes, alerts, footnote definitions and list items.
+    #[arg(long)]
+    pub recursive: bool,
+
+    /// Select task list items in this state (use with --select-type li).
+    #[arg(long, value_enum, value_name = "STATE")]
+    pub task_state: Option<TaskStateArg>,
+
+    /// Use a selector defined with --define-selector instead of the --select-* flags.
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = [
+            "select_type",
+            "select_contains",
+            "select_contains_ci",
+            "select_regex",
+            "select_not_contains",
+            "select_not_regex",
+            "select_fingerprint",
+            "select_annotation",
+            "select_tag",
+            "select_slug",
+            "select_ordinal",
+            "after_select_type",
+            "after_select_contains",
+            "after_select_regex",
+            "after_select_ordinal",
+            "within_select_type",
+            "within_select_contains",
+            "within_select_regex",
+            "within_select_ordinal",
+        ]
+    )]
+    pub selector_ref: Option<String>,
+
+    /// Restrict the search to the first match that occurs after another selector.
+    #[arg(long = "after-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
+    pub after_select_type: Option<String>,
+
+    /// Restrict the search to the first match that occurs after another selector.
+    #[arg(long = "after-select-contains", value_name = "TEXT")]
+    pub after_select_contains: Option<String>,
+
+    /// Restrict the search to the first match that occurs after another selector.
+    #[arg(long = "after-select-regex", value_name = "REGEX")]
+    pub after_select_regex: Option<String>,
+
+    /// Choose the Nth landmark match for the `--after` selector (1-indexed).
+    #[arg(long = "after-select-ordinal", value_name = "N")]
+    pub after_select_ordinal: Option<usize>,
+
+    /// Restrict the search to nodes contained within another selector.
+    #[arg(long = "within-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
+    pub within_select_type: Option<String>,
+
+    /// Restrict the search to nodes contained within another selector.
+    #[arg(long = "within-select-contains", value_name = "TEXT")]
+    pub within_select_contains: Option<String>,
+
+    /// Restrict the search to nodes contained within another selector.
+    #[arg(long = "within-select-regex", value_name = "REGEX")]
+    pub within_select_regex: Option<String>,
+
+    /// Choose the Nth landmark match for the `--within` selector (1-indexed).
+    #[arg(long = "within-select-ordinal", value_name = "N")]
+    pub within_select_ordinal: Option<usize>,
+
+    /// Restrict the search to the Nth slide (1-indexed) of a deck split by `---` thematic breaks.
+    #[arg(long, value_name = "N")]
+    pub within_slide: Option<usize>,
+
+    /// Select the top-level block written across line N (1-indexed) of the input, or nodes nested in it (e.g. the block under an editor's cursor).
+    #[arg(long, value_name = "N")]
+    pub select_at_line: Option<usize>,
+
+    // --- Destination ---
+    /// Select the destination node by type.
+    #[arg(long = "to-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
+    pub to_select_type: Option<String>,
+
+    /// Select the destination node by its text content (fixed string).
+    #[arg(long = "to-select-contains", value_name = "TEXT")]
+    pub to_select_contains: Option<String>,
+
+    /// Select the destination node by its text content (regex pattern).
+    #[arg(long = "to-select-regex", value_name = "REGEX")]
+    pub to_select_regex: Option<String>,
+
+    /// Choose the Nth match for the destination selector (1-indexed).
+    #[arg(long = "to-select-ordinal", value_name = "N")]
+    pub to_select_ordinal: Option<usize>,
+
+    /// Use a selector defined with --define-selector as the destination.
+    #[arg(
+        long = "to-selector-ref",
+        value_name = "NAME",
+        conflicts_with_all = [
+            "to_select_type",
+            "to_select_contains",
+            "to_select_regex",
+            "to_select_ordinal",
+        ]
+    )]
+    pub to_selector_ref: Option<String>,
+
+    /// Where to place the moved content relative to the destination.
+    #[arg(short, long, value_enum, default_value_t = InsertPosition::After)]
+    pub position: InsertPosition,
+
+    // --- Move-specific options ---
+    /// When moving a heading, also move its entire section.
+    #[arg(long, requires = "section_target")]
     pub section: bool,
+
+    // --- Output options ---
+    /// Preview the result without writing any files.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Show a diff of the pending changes instead of writing files.
+    #[arg(long)]
+    pub diff: bool,
 }
 
 /// Arguments for the `get` command.
 #[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("section_target").args(["select_type", "select_slug", "selector_ref"]).multiple(true)))]
 pub struct GetArgs {
     // --- Node Selection ---
     /// Select node by type (e.g., 'p', 'h1', 'list', 'table').
-    #[arg(long, value_name = "TYPE")]
+    #[arg(long, value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub select_type: Option<String>,
 
     /// Select node by its text content (fixed string).
     #[arg(long, value_name = "TEXT")]
     pub select_contains: Option<String>,
 
+    /// Select node by its text content, ignoring case and runs of whitespace.
+    #[arg(long, value_name = "TEXT")]
+    pub select_contains_ci: Option<String>,
+
     /// Select node by its text content (regex pattern).
     #[arg(long, value_name = "REGEX")]
     pub select_regex: Option<String>,
 
+    /// Skip nodes whose text content contains this text (e.g. '[x]').
+    #[arg(long, value_name = "TEXT")]
+    pub select_not_contains: Option<String>,
+
+    /// Skip nodes whose text content matches this regex pattern.
+    #[arg(long, value_name = "REGEX")]
+    pub select_not_regex: Option<String>,
+
+    /// Select node by its content fingerprint, as printed by `get --fingerprint` (hex prefix).
+    #[arg(long, value_name = "HASH")]
+    pub select_fingerprint: Option<String>,
+
+    /// Select the node directly after an HTML comment with this text (e.g. 'slot: notes').
+    #[arg(long, value_name = "TEXT")]
+    pub select_annotation: Option<String>,
+
+    /// Select raw HTML blocks opening an element with this tag (e.g. 'details').
+    #[arg(long, value_name = "TAG")]
+    pub select_tag: Option<String>,
+
+    /// Select code blocks whose info string contains this text (e.g. 'no_run').
+    #[arg(long, value_name = "TEXT")]
+    pub select_info_contains: Option<String>,
+
+    /// Select the heading whose anchor is this slug, as GitHub generates it (e.g. 'installation-notes').
+    #[arg(long, value_name = "SLUG")]
+    pub select_slug: Option<String>,
+
     /// Select the Nth matching node (1-indexed). Default is 1.
     #[arg(
         long,
@@ -289,8 +989,72 @@ pub struct GetArgs {
     )]
     pub select_ordinal: usize,
 
+    /// Count --select-ordinal from the last matching node, so that --select-last alone selects the last one.
+    #[arg(long, conflicts_with = "select_all")]
+    pub select_last: bool,
+
+    /// Fail instead of picking a node when the selector matches more than N nodes.
+    #[arg(long, value_name = "N")]
+    pub max_matches: Option<usize>,
+
+    /// The order in which matches are ranked before --select-ordinal picks one.
+    #[arg(long, value_enum, value_name = "ORDER")]
+    pub tie_break: Option<TieBreakArg>,
+
+    /// Select nodes with at least N words (for a heading, in its section).
+    #[arg(long, value_name = "N")]
+    pub select_min_words: Option<usize>,
+
+    /// Select nodes with at most N words (for a heading, in its section).
+    #[arg(long, value_name = "N")]
+    pub select_max_words: Option<usize>,
+
+    /// Select nodes without any word, such as stub paragraphs or headings with an empty section.
+    #[arg(long)]
+    pub select_empty: bool,
+
+    /// Select list items nested N lists deep, 1 being the items of a top-level list (use with --select-type li).
+    #[arg(long, value_name = "N")]
+    pub select_depth: Option<usize>,
+
+    /// Also search blocks nested in block quotes, alerts, footnote definitions and list items.
+    #[arg(long)]
+    pub recursive: bool,
+
+    /// Select task list items in this state (use with --select-type li).
+    #[arg(long, value_enum, value_name = "STATE")]
+    pub task_state: Option<TaskStateArg>,
+
+    /// Use a selector defined with --define-selector instead of the --select-* flags.
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = [
+            "select_type",
+            "select_contains",
+            "select_contains_ci",
+            "select_regex",
+            "select_not_contains",
+            "select_not_regex",
+            "select_fingerprint",
+            "select_annotation",
+            "select_tag",
+            "select_slug",
+            "select_ordinal",
+            "after_select_type",
+            "after_select_contains",
+            "after_select_regex",
+            "after_select_ordinal",
+            "within_select_type",
+            "within_select_contains",
+            "within_select_regex",
+            "within_select_ordinal",
+        ]
+    )]
+    pub selector_ref: Option<String>,
+
     /// Restrict the search to the first match that occurs after another selector.
-    #[arg(long = "after-select-type", value_name = "TYPE")]
+    #[arg(long = "after-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub after_select_type: Option<String>,
 
     /// Restrict the search to the first match that occurs after another selector.
@@ -306,7 +1070,7 @@ pub struct GetArgs {
     pub after_select_ordinal: Option<usize>,
 
     /// Restrict the search to nodes contained within another selector.
-    #[arg(long = "within-select-type", value_name = "TYPE")]
+    #[arg(long = "within-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
     pub within_select_type: Option<String>,
 
     /// Restrict the search to nodes contained within another selector.
@@ -321,10 +1085,20 @@ pub struct GetArgs {
     #[arg(long = "within-select-ordinal", value_name = "N")]
     pub within_select_ordinal: Option<usize>,
 
+    /// Restrict the search to the Nth slide (1-indexed) of a deck split by `---` thematic breaks.
+    #[arg(long, value_name = "N")]
+    pub within_slide: Option<usize>,
+
+    /// Select the top-level block written across line N (1-indexed) of the input, or nodes nested in it (e.g. the block under an editor's cursor).
+    #[arg(long, value_name = "N")]
+    pub select_at_line: Option<usize>,
+
     /// Select nodes up to (but not including) another selector.
     #[arg(
         long = "until-type",
         value_name = "TYPE",
+        value_parser = SelectTypeParser,
+        hide_possible_values = true,
         conflicts_with = "select_all"
     )]
     pub until_type: Option<String>,
@@ -345,10 +1119,30 @@ pub struct GetArgs {
     )]
     pub until_regex: Option<String>,
 
-    /// When selecting a heading, include the entire section.
-    #[arg(long, requires = "select_type")]
+    /// Include a heading's entire section, or a list item with everything nested under it.
+    #[arg(long, requires = "section_target")]
     pub section: bool,
 
+    /// Print the content fingerprint of the matched node instead of its Markdown.
+    #[arg(
+        long,
+        conflicts_with_all = ["section", "until_type", "until_contains", "until_regex"]
+    )]
+    pub fingerprint: bool,
+
+    /// Print only the cell in row N of the matched table (0 is the header row).
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "col",
+        conflicts_with_all = ["section", "fingerprint", "select_all", "until_type", "until_contains", "until_regex"]
+    )]
+    pub row: Option<usize>,
+
+    /// Print only the cell in column N (1-indexed) of the matched table.
+    #[arg(long, value_name = "N", requires = "row")]
+    pub col: Option<usize>,
+
     /// Select all nodes matching the criteria instead of a single node.
     #[arg(long)]
     pub select_all: bool,
@@ -361,19 +1155,279 @@ pub struct GetArgs {
         allow_hyphen_values = true
     )]
     pub separator: String,
+
+    /// Print each match as JSON with its kind, position, type, indices, source lines and Markdown, and the level, text and anchor of headings.
+    #[arg(
+        long,
+        conflicts_with_all = ["fingerprint", "row", "separator", "emit_ops"]
+    )]
+    pub json: bool,
+
+    /// With --select-all, print each match as a compact JSON object on a line of its own (NDJSON), with the fields of --json.
+    #[arg(
+        long,
+        requires = "select_all",
+        conflicts_with_all = ["json", "fingerprint", "row", "separator", "emit_ops"]
+    )]
+    pub ndjson: bool,
+
+    /// Print only the number of nodes matching the selector, which may be 0, instead of their content.
+    #[arg(
+        long,
+        conflicts_with_all = ["section", "fingerprint", "row", "separator", "json", "ndjson", "emit_ops", "until_type", "until_contains", "until_regex"]
+    )]
+    pub count: bool,
+
+    /// Print an operations file applying OP to each match, targeted by its fingerprint, to review and then `apply`.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "OP",
+        conflicts_with_all = ["fingerprint", "row", "until_type", "until_contains", "until_regex"]
+    )]
+    pub emit_ops: Option<EmitOp>,
+
+    /// Format of the operations file printed by --emit-ops.
+    #[arg(
+        long = "output-format",
+        value_enum,
+        default_value_t = OperationsFormat::Yaml,
+        value_name = "FORMAT",
+        requires = "emit_ops"
+    )]
+    pub output_format: OperationsFormat,
+}
+
+/// Arguments for the `cat` command.
+#[derive(Parser, Debug)]
+pub struct CatArgs {
+    /// Expand the sections whose heading contains TEXT. Repeatable.
+    #[arg(long, value_name = "TEXT")]
+    pub expand: Vec<String>,
+
+    /// Expand the sections whose heading matches REGEX. Repeatable.
+    #[arg(long, value_name = "REGEX")]
+    pub expand_regex: Vec<String>,
+
+    /// Expand the section around each node matched by a selector defined with --define-selector. Repeatable.
+    #[arg(long, value_name = "NAME")]
+    pub expand_ref: Vec<String>,
+}
+
+/// Arguments for the `doctor` command.
+#[derive(Parser, Debug)]
+pub struct DoctorArgs {
+    /// Also print how rendering the document without any edit changes it, as a unified diff.
+    #[arg(long)]
+    pub diff: bool,
 }
 
 /// Arguments for the `apply` command.
 #[derive(Parser, Debug)]
 pub struct ApplyArgs {
-    /// Path to a JSON or YAML file containing the operations. Use '-' for stdin.
-    #[arg(short = 'O', long, value_name = "PATH", conflicts_with = "operations")]
-    pub operations_file: Option<PathBuf>,
+    /// Path to a JSON or YAML file containing the operations. Use '-' for stdin. Repeatable.
+    #[arg(short = 'O', long, value_name = "PATH")]
+    pub operations_file: Vec<PathBuf>,
+
+    /// JSON string describing the operations inline. Repeatable.
+    #[arg(long, value_name = "JSON_STRING")]
+    pub operations: Vec<String>,
+
+    /// Preview the result without writing any files.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Show a diff of the pending changes instead of writing files.
+    #[arg(long)]
+    pub diff: bool,
+
+    /// Exit with an error if the operations would change the document. No files are written.
+    #[arg(long, conflicts_with_all = ["dry_run", "diff"])]
+    pub check: bool,
+
+    /// With --check, ignore formatting-only differences by comparing the documents semantically.
+    #[arg(long, requires = "check")]
+    pub semantic: bool,
+
+    /// Load selector aliases exported by an earlier run so operations can reference them.
+    #[arg(long, value_name = "PATH")]
+    pub import_aliases: Option<PathBuf>,
+
+    /// Write the resolved selector aliases to a JSON file once the document is written.
+    #[arg(long, value_name = "PATH", conflicts_with = "check")]
+    pub export_aliases: Option<PathBuf>,
+
+    /// Resolve selectors in this reference document and target the same structural positions
+    /// (node type, ordinal, and heading section) in the input, e.g. in a translated copy.
+    #[arg(long, value_name = "REFERENCE_PATH")]
+    pub positions_from: Option<PathBuf>,
+
+    /// Read the document from a git revision instead of the worktree, as REV (the --file at
+    /// that revision) or REV:PATH (e.g. "HEAD~1:docs/README.md"), still writing to --file.
+    #[arg(long, value_name = "REV")]
+    pub from_git: Option<String>,
+
+    /// Fail, leaving the document untouched, when a single operation takes longer than SECONDS.
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    pub operation_timeout: Option<Duration>,
+
+    /// Fail, leaving the document untouched, when the whole batch takes longer than SECONDS.
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    pub timeout: Option<Duration>,
+
+    /// Write a JSON report of the batch, including the content captured by `get` operations ('-' for stdout).
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub report: Option<PathBuf>,
+}
+
+/// Arguments for the `prune-empty-sections` command.
+#[derive(Parser, Debug)]
+pub struct PruneEmptySectionsArgs {
+    /// Only prune headings at this level or deeper (1-6).
+    #[arg(long, value_name = "N")]
+    pub min_level: Option<u8>,
+
+    /// Only prune headings at this level or shallower (1-6).
+    #[arg(long, value_name = "N")]
+    pub max_level: Option<u8>,
+
+    /// Only prune headings inside the section of another selector.
+    #[arg(long = "within-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
+    pub within_select_type: Option<String>,
+
+    /// Only prune headings inside the section of another selector.
+    #[arg(long = "within-select-contains", value_name = "TEXT")]
+    pub within_select_contains: Option<String>,
+
+    /// Only prune headings inside the section of another selector.
+    #[arg(long = "within-select-regex", value_name = "REGEX")]
+    pub within_select_regex: Option<String>,
 
-    /// JSON string describing the operations inline.
-    #[arg(long, value_name = "JSON_STRING", conflicts_with = "operations_file")]
-    pub operations: Option<String>,
+    /// Choose the Nth landmark match for the `--within` selector (1-indexed).
+    #[arg(long = "within-select-ordinal", value_name = "N")]
+    pub within_select_ordinal: Option<usize>,
+
+    /// Use a selector defined with --define-selector as the `--within` scope.
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = [
+            "within_select_type",
+            "within_select_contains",
+            "within_select_regex",
+            "within_select_ordinal",
+        ]
+    )]
+    pub within_ref: Option<String>,
+
+    // --- Output options ---
+    /// Preview the result without writing any files.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Show a diff of the pending changes instead of writing files.
+    #[arg(long)]
+    pub diff: bool,
+}
+
+/// Arguments for the `dedupe-headings` command.
+#[derive(Parser, Debug)]
+pub struct DedupeHeadingsArgs {
+    /// Only consider headings inside the section of another selector.
+    #[arg(long = "within-select-type", value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
+    pub within_select_type: Option<String>,
+
+    /// Only consider headings inside the section of another selector.
+    #[arg(long = "within-select-contains", value_name = "TEXT")]
+    pub within_select_contains: Option<String>,
+
+    /// Only consider headings inside the section of another selector.
+    #[arg(long = "within-select-regex", value_name = "REGEX")]
+    pub within_select_regex: Option<String>,
+
+    /// Choose the Nth landmark match for the `--within` selector (1-indexed).
+    #[arg(long = "within-select-ordinal", value_name = "N")]
+    pub within_select_ordinal: Option<usize>,
+
+    /// Use a selector defined with --define-selector as the `--within` scope.
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = [
+            "within_select_type",
+            "within_select_contains",
+            "within_select_regex",
+            "within_select_ordinal",
+        ]
+    )]
+    pub within_ref: Option<String>,
+
+    // --- Output options ---
+    /// Preview the result without writing any files.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Show a diff of the pending changes instead of writing files.
+    #[arg(long)]
+    pub diff: bool,
+
+    /// List duplicated headings and exit with an error if any are found. No files are written.
+    #[arg(long, conflicts_with_all = ["dry_run", "diff"])]
+    pub check: bool,
+}
+
+/// Arguments for the `outline` command.
+#[derive(Parser, Debug)]
+pub struct OutlineArgs {
+    /// Only list headings at this level or shallower (1-6).
+    #[arg(long, value_name = "N")]
+    pub max_level: Option<u8>,
+
+    /// Print the outline as a JSON array of nested headings instead of indented text.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments for the `toc` command.
+#[derive(Parser, Debug)]
+pub struct TocArgs {
+    /// Place the table of contents under the heading matched by a selector, if it has no markers.
+    #[arg(long, value_name = "TYPE", value_parser = SelectTypeParser, hide_possible_values = true)]
+    pub select_type: Option<String>,
+
+    /// Place the table of contents under the heading matched by a selector, if it has no markers.
+    #[arg(long, value_name = "TEXT")]
+    pub select_contains: Option<String>,
+
+    /// Place the table of contents under the heading matched by a selector, if it has no markers.
+    #[arg(long, value_name = "REGEX")]
+    pub select_regex: Option<String>,
 
+    /// Choose the Nth matching heading (1-indexed).
+    #[arg(long, value_name = "N")]
+    pub select_ordinal: Option<usize>,
+
+    /// Use a selector defined with --define-selector instead of the --select-* flags.
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = ["select_type", "select_contains", "select_regex", "select_ordinal"]
+    )]
+    pub selector_ref: Option<String>,
+
+    /// Only list headings at this level or deeper (1-6).
+    #[arg(long, value_name = "N")]
+    pub min_level: Option<u8>,
+
+    /// Only list headings at this level or shallower (1-6).
+    #[arg(long, value_name = "N")]
+    pub max_level: Option<u8>,
+
+    /// How the anchors of headings are derived from their text.
+    #[arg(long, value_enum, value_name = "STYLE", default_value_t = SlugStyleArg::Github)]
+    pub slug_style: SlugStyleArg,
+
+    // --- Output options ---
     /// Preview the result without writing any files.
     #[arg(long)]
     pub dry_run: bool,
@@ -381,6 +1435,118 @@ pub struct ApplyArgs {
     /// Show a diff of the pending changes instead of writing files.
     #[arg(long)]
     pub diff: bool,
+
+    /// Exit with an error if the table of contents is out of date. No files are written.
+    #[arg(long, conflicts_with_all = ["dry_run", "diff"])]
+    pub check: bool,
+}
+
+/// Arguments for the `split` command.
+#[derive(Parser, Debug)]
+pub struct SplitArgs {
+    /// Heading level whose sections are moved out (1-6).
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=6))]
+    pub level: u8,
+
+    /// Directory receiving one `<slug>.md` file per section. Created if missing.
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: PathBuf,
+
+    /// Leave a link to, or an include comment for, each extracted file where its section was.
+    #[arg(long, value_enum, value_name = "KIND")]
+    pub leave: Option<SplitPlaceholder>,
+
+    /// Overwrite section files that already exist in the output directory.
+    #[arg(long)]
+    pub force: bool,
+
+    // --- Output options ---
+    /// Preview the remaining document without writing any files.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Show a diff of the remaining document instead of writing files.
+    #[arg(long)]
+    pub diff: bool,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum SplitPlaceholder {
+    /// A paragraph linking to the extracted file, labeled with the heading text.
+    Link,
+    /// An `<!-- include: PATH -->` comment naming the extracted file.
+    Include,
+}
+
+/// Arguments for the `concat` command.
+#[derive(Parser, Debug)]
+pub struct ConcatArgs {
+    /// Markdown files to combine, in order. Use '-' to read one of them from stdin.
+    #[arg(required = true, value_name = "FILE")]
+    pub inputs: Vec<PathBuf>,
+
+    /// Shift each file's headings so its shallowest heading lands on this level (1-6).
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(1..=6))]
+    pub base_level: Option<u8>,
+
+    /// How to combine the frontmatter of the files.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = FrontmatterStrategyArg::First,
+        value_name = "STRATEGY"
+    )]
+    pub frontmatter: FrontmatterStrategyArg,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum FrontmatterStrategyArg {
+    /// Keep the frontmatter of the first file that has one.
+    First,
+    /// Merge all frontmatter; later files override keys set by earlier ones.
+    Merge,
+    /// Drop all frontmatter.
+    Drop,
+}
+
+/// Arguments for the `infer` command.
+#[derive(Parser, Debug)]
+pub struct InferArgs {
+    /// Path to the edited version of the document. Use '-' for stdin.
+    #[arg(long, value_name = "EDITED_PATH")]
+    pub edited: PathBuf,
+
+    /// Format of the emitted operations file.
+    #[arg(
+        long = "output-format",
+        value_enum,
+        default_value_t = OperationsFormat::Yaml,
+        value_name = "FORMAT"
+    )]
+    pub output_format: OperationsFormat,
+}
+
+/// Arguments for the `completions` command.
+#[derive(Parser, Debug)]
+pub struct CompletionsArgs {
+    /// The shell to generate completions for.
+    #[arg(value_enum, value_name = "SHELL")]
+    pub shell: Shell,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum OperationsFormat {
+    Yaml,
+    Json,
+}
+
+/// The operation `get --emit-ops` emits for each match.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum EmitOp {
+    /// Delete the match, with its section when --section is set.
+    Delete,
+    /// Replace the match, starting from its current Markdown as the content to edit.
+    Replace,
 }
 
 #[derive(ValueEnum, Clone, Debug, PartialEq, Eq)]
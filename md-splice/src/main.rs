@@ -2,7 +2,20 @@
 
 mod app;
 mod cli;
+mod profile;
+mod reference;
+mod style;
 
-fn main() -> anyhow::Result<()> {
-    app::run()
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match app::run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if !err.is::<app::SilentNoMatchError>() {
+                eprintln!("{} {err:?}", style::error_label());
+            }
+            ExitCode::from(app::exit_code(&err))
+        }
+    }
 }
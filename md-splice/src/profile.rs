@@ -0,0 +1,43 @@
+//! Per-phase timings printed to stderr by `--profile`.
+//!
+//! Phases are recorded in the order they first run, and the time spent in a phase that runs
+//! several times (such as parsing both documents of `infer`) is added up.
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// Time spent in each phase of a command.
+#[derive(Default)]
+pub struct Profile {
+    phases: RefCell<Vec<(&'static str, Duration)>>,
+}
+
+impl Profile {
+    /// Runs `f`, adding the time it takes to `phase`.
+    pub fn time<T>(&self, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        let elapsed = start.elapsed();
+
+        let mut phases = self.phases.borrow_mut();
+        match phases.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => phases.push((phase, elapsed)),
+        }
+        result
+    }
+
+    /// Renders one line per phase, followed by their total, in milliseconds.
+    pub fn report(&self) -> String {
+        let phases = self.phases.borrow();
+        let total: Duration = phases.iter().map(|(_, elapsed)| *elapsed).sum();
+        let mut text = String::from("Profile:\n");
+        for (phase, elapsed) in phases.iter().chain([&("total", total)]) {
+            text.push_str(&format!(
+                "  {phase:<8} {:>10.3} ms\n",
+                elapsed.as_secs_f64() * 1000.0
+            ));
+        }
+        text
+    }
+}
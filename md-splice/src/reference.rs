@@ -0,0 +1,153 @@
+//! The selector reference printed by `--help-selectors` and included in the man page.
+//!
+//! Node types come from [`SELECT_TYPES`], the table the locator matches `select_type`
+//! against, so the reference always lists exactly the values the locator accepts.
+
+use crate::cli::Cli;
+use clap::CommandFactory;
+use md_splice_lib::locator::SELECT_TYPES;
+use std::io::{self, Write};
+
+/// Flags that narrow down which node a selector matches.
+const CRITERIA: &[(&str, &str)] = &[
+    (
+        "--select-contains <TEXT>",
+        "The node's plain text contains TEXT (case-sensitive)",
+    ),
+    (
+        "--select-regex <REGEX>",
+        "The node's plain text (the source of a raw HTML block) matches REGEX",
+    ),
+    (
+        "--select-fingerprint <HASH>",
+        "The node's content fingerprint (see `get --fingerprint`) starts with HASH",
+    ),
+    (
+        "--select-annotation <TEXT>",
+        "The node directly follows the HTML comment `<!-- TEXT -->`",
+    ),
+    (
+        "--select-tag <TAG>",
+        "The node is a raw HTML block opening a TAG element, such as `<details>`",
+    ),
+    (
+        "--select-min-words <N>",
+        "The node has at least N words; a heading counts the words of its section",
+    ),
+    (
+        "--select-max-words <N>",
+        "The node has at most N words; a heading counts the words of its section",
+    ),
+    (
+        "--select-empty",
+        "The node has no words, e.g. a stub paragraph or a heading with an empty section",
+    ),
+    (
+        "--select-ordinal <N>",
+        "Pick the Nth node matching all other criteria (default: 1)",
+    ),
+    (
+        "--max-matches <N>",
+        "Fail instead of picking a node when more than N nodes match",
+    ),
+    (
+        "--tie-break <ORDER>",
+        "Rank matches in document-order (default) or deepest-first (deeper heading sections first)",
+    ),
+];
+
+/// Flags that restrict where a selector searches, or where a range ends.
+const SCOPE_MODIFIERS: &[(&str, &str)] = &[
+    (
+        "--after-select-*",
+        "Only search after the node matched by another selector",
+    ),
+    (
+        "--within-select-*",
+        "Only search inside the section of a heading, or the items of a list, matched by another selector",
+    ),
+    (
+        "--within-slide <N>",
+        "Only search the Nth slide of a deck whose slides are separated by `---` thematic breaks",
+    ),
+    (
+        "--until-*",
+        "End a replace, delete, or get range before the next node matched by another selector",
+    ),
+    (
+        "--selector-ref <NAME>",
+        "Use a selector defined with --define-selector instead of the --select-* flags",
+    ),
+];
+
+const FOOTER: &str = "Operations files use the same criteria as selector fields (select_type, \
+select_contains, select_contains_ci, select_regex, select_not_contains, select_not_regex, select_fingerprint, select_annotation, select_tag, \
+select_info_contains, select_slug, select_min_words, select_max_words, select_empty, select_depth, recursive, task_state, select_ordinal, select_last, max_matches, tie_break) with nested `after`, `within`, and `until` selectors, or their `*_ref` alias forms, `within_slide`, and `at_line`.";
+
+/// Renders the selector reference as plain text for `--help-selectors`.
+pub fn selector_reference() -> String {
+    let types: Vec<(String, &str)> = SELECT_TYPES
+        .iter()
+        .map(|select_type| (select_type.names.join(", "), select_type.description))
+        .collect();
+
+    let mut text = String::new();
+    push_section(
+        &mut text,
+        "Node types (--select-type, --after-select-type, --within-select-type, --until-type):",
+        types
+            .iter()
+            .map(|(names, description)| (names.as_str(), *description)),
+    );
+    push_section(&mut text, "Criteria:", CRITERIA.iter().copied());
+    push_section(
+        &mut text,
+        "Scope modifiers:",
+        SCOPE_MODIFIERS.iter().copied(),
+    );
+    text.push_str(FOOTER);
+    text.push('\n');
+    text
+}
+
+fn push_section<'a>(
+    text: &mut String,
+    title: &str,
+    rows: impl Iterator<Item = (&'a str, &'a str)> + Clone,
+) {
+    let width = rows.clone().map(|(term, _)| term.len()).max().unwrap_or(0);
+    text.push_str(title);
+    text.push('\n');
+    for (term, description) in rows {
+        text.push_str(&format!("  {term:width$}  {description}\n"));
+    }
+    text.push('\n');
+}
+
+/// Writes the man page, with the selector reference as its SELECTORS section.
+pub fn write_man_page(out: &mut dyn Write) -> io::Result<()> {
+    clap_mangen::Man::new(Cli::command()).render(out)?;
+
+    writeln!(out, ".SH SELECTORS")?;
+    let types = SELECT_TYPES
+        .iter()
+        .map(|select_type| (select_type.names.join(", "), select_type.description));
+    let criteria = CRITERIA
+        .iter()
+        .chain(SCOPE_MODIFIERS)
+        .map(|(term, description)| (term.to_string(), *description));
+    for (term, description) in types.chain(criteria) {
+        writeln!(
+            out,
+            ".TP\n\\fB{}\\fR\n{}",
+            roff_escape(&term),
+            roff_escape(description)
+        )?;
+    }
+    writeln!(out, ".PP\n{}", roff_escape(FOOTER))
+}
+
+/// Escapes backslashes and hyphens, which roff would otherwise interpret.
+fn roff_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('-', "\\-")
+}
@@ -0,0 +1,133 @@
+//! Terminal styling for diffs, `get` previews, `cat` fold markers, and error headlines.
+//!
+//! With `--color auto` (the default), a stream is only colored when it is a terminal and
+//! `NO_COLOR` is unset or empty. `--color always` and `--color never` override both checks.
+
+use crate::cli::ColorChoice;
+use anstyle::{AnsiColor, Style};
+use regex::Regex;
+use std::io::{self, IsTerminal};
+use std::ops::Range;
+use std::sync::OnceLock;
+
+const ERROR: Style = AnsiColor::Red.on_default().bold();
+const WARNING: Style = AnsiColor::Yellow.on_default().bold();
+const DIFF_HEADER: Style = Style::new().bold();
+const DIFF_HUNK: Style = AnsiColor::Cyan.on_default();
+const DIFF_ADDED: Style = AnsiColor::Green.on_default();
+const DIFF_REMOVED: Style = AnsiColor::Red.on_default();
+const MATCH: Style = AnsiColor::Yellow.on_default().bold().underline();
+const FOLDED: Style = Style::new().dimmed();
+
+/// Whether stderr is colored, decided once the command line has been parsed.
+static STDERR_COLOR: OnceLock<bool> = OnceLock::new();
+
+/// Decides whether a stream is colored under `choice`.
+fn enabled(choice: ColorChoice, is_terminal: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            is_terminal && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        }
+    }
+}
+
+/// Returns whether regular output should be colored.
+pub fn stdout_enabled(choice: ColorChoice) -> bool {
+    enabled(choice, io::stdout().is_terminal())
+}
+
+/// Records whether errors and warnings printed to stderr should be colored.
+pub fn init_stderr(choice: ColorChoice) {
+    let _ = STDERR_COLOR.set(enabled(choice, io::stderr().is_terminal()));
+}
+
+fn stderr_enabled() -> bool {
+    STDERR_COLOR.get().copied().unwrap_or(false)
+}
+
+fn paint(style: Style, text: &str) -> String {
+    format!("{style}{text}{style:#}")
+}
+
+/// The `Error:` prefix of messages printed to stderr.
+pub fn error_label() -> String {
+    if stderr_enabled() {
+        paint(ERROR, "Error:")
+    } else {
+        "Error:".to_string()
+    }
+}
+
+/// The `Warning:` prefix of messages printed to stderr.
+pub fn warning_label() -> String {
+    if stderr_enabled() {
+        paint(WARNING, "Warning:")
+    } else {
+        "Warning:".to_string()
+    }
+}
+
+/// Colors the file headers, hunk headers, and added and removed lines of a unified diff.
+pub fn colorize_diff(diff: &str) -> String {
+    diff.split_inclusive('\n')
+        .map(|line| {
+            let (body, newline) = match line.strip_suffix('\n') {
+                Some(body) => (body, "\n"),
+                None => (line, ""),
+            };
+            let style = if body.starts_with("+++") || body.starts_with("---") {
+                DIFF_HEADER
+            } else if body.starts_with("@@") {
+                DIFF_HUNK
+            } else if body.starts_with('+') {
+                DIFF_ADDED
+            } else if body.starts_with('-') {
+                DIFF_REMOVED
+            } else {
+                return line.to_string();
+            };
+            format!("{}{newline}", paint(style, body))
+        })
+        .collect()
+}
+
+/// Dims the marker standing for blocks folded away by `cat`.
+pub fn fold_marker(marker: &str) -> String {
+    paint(FOLDED, marker)
+}
+
+/// Highlights every occurrence of `contains` and every match of `regex` in `text`.
+pub fn highlight_matches(text: &str, contains: Option<&str>, regex: Option<&Regex>) -> String {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    if let Some(needle) = contains.filter(|needle| !needle.is_empty()) {
+        ranges.extend(
+            text.match_indices(needle)
+                .map(|(start, found)| start..start + found.len()),
+        );
+    }
+    if let Some(regex) = regex {
+        ranges.extend(
+            regex
+                .find_iter(text)
+                .filter(|found| !found.is_empty())
+                .map(|found| found.range()),
+        );
+    }
+    ranges.sort_by_key(|range| range.start);
+
+    let mut highlighted = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for range in ranges {
+        if range.end <= cursor {
+            continue;
+        }
+        let start = range.start.max(cursor);
+        highlighted.push_str(&text[cursor..start]);
+        highlighted.push_str(&paint(MATCH, &text[start..range.end]));
+        cursor = range.end;
+    }
+    highlighted.push_str(&text[cursor..]);
+    highlighted
+}
@@ -416,3 +416,730 @@ fn apply_command_replaces_frontmatter_block() {
     assert!(content.contains("version = 2"));
     assert!(content.contains("Body text."));
 }
+
+#[test]
+fn apply_command_check_reports_out_of_date_documents() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("input.md");
+    input_file
+        .write_str("Title\n=====\n\n* one\n* two\n\nCurrent status.\n")
+        .unwrap();
+
+    let unchanged = json!([
+        {
+            "op": "replace",
+            "selector": { "select_contains": "Current status." },
+            "content": "Current status.",
+        }
+    ]);
+    let changed = json!([
+        {
+            "op": "replace",
+            "selector": { "select_contains": "Current status." },
+            "content": "New status.",
+        }
+    ]);
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations")
+        .arg(unchanged.to_string())
+        .arg("--check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Document is out of date"));
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations")
+        .arg(unchanged.to_string())
+        .arg("--check")
+        .arg("--semantic")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations")
+        .arg(changed.to_string())
+        .arg("--check")
+        .arg("--semantic")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Document is out of date"));
+
+    let content = std::fs::read_to_string(input_file.path()).unwrap();
+    assert_eq!(content, "Title\n=====\n\n* one\n* two\n\nCurrent status.\n");
+}
+
+#[test]
+fn apply_command_merges_operation_sources_in_argument_order() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("input.md");
+    input_file.write_str("# Title\n\nStage one.\n").unwrap();
+
+    let common_ops = temp.child("common.yaml");
+    common_ops
+        .write_str(
+            "- op: replace\n  selector:\n    select_contains: Stage one.\n  content: Stage two.\n",
+        )
+        .unwrap();
+    let specific_ops = temp.child("specific.json");
+    specific_ops
+        .write_str(
+            r#"[{"op": "replace", "selector": {"select_contains": "Stage three."}, "content": "Done."}]"#,
+        )
+        .unwrap();
+    let inline = json!([
+        {
+            "op": "replace",
+            "selector": { "select_contains": "Stage two." },
+            "content": "Stage three.",
+        }
+    ]);
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations-file")
+        .arg(common_ops.path())
+        .arg("--operations")
+        .arg(inline.to_string())
+        .arg("-O")
+        .arg(specific_ops.path())
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(input_file.path()).unwrap();
+    assert_eq!(content, "# Title\n\nDone.");
+}
+
+#[test]
+fn apply_command_rejects_conflicting_operations_before_executing() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("input.md");
+    input_file
+        .write_str("# Title\n\nLegacy notes.\n\nStatus: draft.\n")
+        .unwrap();
+
+    let replace_twice = json!([
+        {
+            "op": "replace",
+            "selector": { "select_contains": "Status:" },
+            "content": "Reviewed.",
+        },
+        {
+            "op": "delete",
+            "selector": { "alias": "legacy", "select_contains": "Legacy" },
+        },
+        {
+            "op": "replace",
+            "selector": { "select_contains": "Status:" },
+            "content": "Published.",
+        }
+    ]);
+    let insert_after_deleted = json!([
+        {
+            "op": "delete",
+            "selector": { "alias": "legacy", "select_contains": "Legacy" },
+        },
+        {
+            "op": "insert",
+            "selector_ref": "legacy",
+            "content": "Replacement notes.",
+            "position": "after",
+        }
+    ]);
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations")
+        .arg(replace_twice.to_string())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "operation 1 (`replace *~\"Status:\"`) and operation 3 (`replace *~\"Status:\"`) both replace the same target",
+        ));
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations")
+        .arg(insert_after_deleted.to_string())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "operation 2 (`insert after @legacy`) references alias 'legacy', whose node was deleted by operation 1 (`delete *~\"Legacy\" as @legacy`)",
+        ));
+
+    input_file.assert("# Title\n\nLegacy notes.\n\nStatus: draft.\n");
+}
+
+#[test]
+fn apply_command_exports_and_imports_aliases_across_runs() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("input.md");
+    input_file
+        .write_str("# Guide\n\nIntro.\n\n## Changelog\n\n- First\n")
+        .unwrap();
+    let aliases_file = temp.child("aliases.json");
+
+    let define = json!([{
+        "op": "replace",
+        "selector": {"alias": "intro", "select_type": "p", "select_contains": "Intro"},
+        "content": "Intro, revised."
+    }, {
+        "op": "insert",
+        "selector": {"alias": "changelog", "select_type": "h2", "select_contains": "Changelog"},
+        "position": "before",
+        "content": "Preamble."
+    }]);
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations")
+        .arg(define.to_string())
+        .arg("--export-aliases")
+        .arg(aliases_file.path())
+        .assert()
+        .success();
+
+    let exported = std::fs::read_to_string(aliases_file.path()).unwrap();
+    assert_snapshot!(exported, @r#"
+    [
+      {
+        "name": "changelog",
+        "selector": {
+          "select_type": "h2",
+          "select_contains": "Changelog"
+        },
+        "blocks": {
+          "start": 3,
+          "end": 4
+        }
+      },
+      {
+        "name": "intro",
+        "selector": {
+          "select_type": "p",
+          "select_contains": "Intro"
+        },
+        "blocks": {
+          "start": 1,
+          "end": 2
+        }
+      }
+    ]
+    "#);
+
+    let reuse = json!([{
+        "op": "delete",
+        "selector_ref": "changelog",
+        "section": true
+    }]);
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations")
+        .arg(reuse.to_string())
+        .arg("--import-aliases")
+        .arg(aliases_file.path())
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(input_file.path()).unwrap();
+    assert_eq!(content, "# Guide\n\nIntro, revised.\n\nPreamble.");
+
+    // Without the import, the alias is unknown.
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations")
+        .arg(reuse.to_string())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("changelog"));
+}
+
+#[test]
+fn apply_import_section_replaces_and_inserts_shared_sections() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("shared.md")
+        .write_str("# Shared\n\n## Support\n\nAsk in the forum.\n\n## License\n\nMIT.\n")
+        .unwrap();
+    let input_file = temp.child("README.md");
+    input_file
+        .write_str("# Project\n\n## Support\n\nOutdated.\n\n## Usage\n\nRun it.\n")
+        .unwrap();
+    let operations_file = temp.child("ops.yaml");
+    operations_file
+        .write_str(
+            r#"- op: import_section
+  source_file: shared.md
+  source_selector:
+    select_type: h2
+    select_contains: Support
+  selector:
+    select_type: h2
+    select_contains: Support
+- op: import_section
+  source_file: shared.md
+  source_selector:
+    select_type: h2
+    select_contains: License
+  selector:
+    select_type: p
+    select_contains: Run it.
+  position: after
+"#,
+        )
+        .unwrap();
+
+    cmd()
+        .current_dir(temp.path())
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations-file")
+        .arg(operations_file.path())
+        .assert()
+        .success();
+
+    input_file.assert(
+        "# Project\n\n## Support\n\nAsk in the forum.\n\n## Usage\n\nRun it.\n\n## License\n\nMIT.",
+    );
+}
+
+#[test]
+fn apply_set_and_remove_alert_operations() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("README.md");
+    input_file
+        .write_str("# Project\n\n> Requires Rust 1.80.\n\n> [!TIP]\n> Use the cache.\n\n> [!NOTE]\n> Old note.\n")
+        .unwrap();
+    let operations_file = temp.child("ops.yaml");
+    operations_file
+        .write_str(
+            r#"- op: set_alert
+  selector:
+    select_type: blockquote
+    select_contains: Requires Rust
+  alert_type: important
+- op: set_alert
+  selector:
+    select_type: tip
+  alert_type: WARNING
+- op: remove_alert
+  selector:
+    select_type: note
+"#,
+        )
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations-file")
+        .arg(operations_file.path())
+        .assert()
+        .success();
+
+    input_file.assert(
+        "# Project\n\n> [!IMPORTANT]\n> Requires Rust 1.80.\n\n> [!WARNING]\n> Use the cache.\n\n> Old note.",
+    );
+}
+
+#[test]
+fn apply_set_comment_refreshes_provenance_stamp() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("README.md");
+    input_file
+        .write_str("# Project\n\n<!-- keep me -->\n\n| a |\n| - |\n| 1 |\n")
+        .unwrap();
+    let operations_file = temp.child("ops.yaml");
+    operations_file
+        .write_str(
+            r#"- op: set_comment
+  selector:
+    select_type: table
+  text: generated by md-splice from ops.yaml
+  marker: md-splice
+"#,
+        )
+        .unwrap();
+
+    for _ in 0..2 {
+        cmd()
+            .arg("--file")
+            .arg(input_file.path())
+            .arg("apply")
+            .arg("--operations-file")
+            .arg(operations_file.path())
+            .assert()
+            .success();
+    }
+
+    input_file.assert(
+        "# Project\n\n<!-- keep me -->\n\n<!-- generated by md-splice from ops.yaml -->\n\n| a |\n| - |\n| 1 |",
+    );
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("comment")
+        .arg("--select-contains")
+        .arg("keep")
+        .assert()
+        .success()
+        .stdout("<!-- keep me -->\n");
+}
+
+#[test]
+fn apply_positions_from_replays_text_selectors_on_translated_copy() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let reference = temp.child("en.md");
+    reference
+        .write_str("# Guide\n\n## Install\n\nDownload it.\n\nRun the installer.\n\n## Usage\n\nStart it.\n")
+        .unwrap();
+    let translated = temp.child("de.md");
+    translated
+        .write_str("# Anleitung\n\n## Installation\n\nHerunterladen.\n\nInstaller starten.\n\n## Nutzung\n\nStarten.\n")
+        .unwrap();
+    let operations_file = temp.child("ops.yaml");
+    operations_file
+        .write_str(
+            r#"- op: replace
+  selector:
+    select_type: p
+    select_contains: installer
+  content: "Doppelklicken."
+- op: insert
+  selector:
+    select_type: h2
+    select_contains: Usage
+  position: append_child
+  content: "Beenden."
+"#,
+        )
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(translated.path())
+        .arg("apply")
+        .arg("--operations-file")
+        .arg(operations_file.path())
+        .arg("--positions-from")
+        .arg(reference.path())
+        .assert()
+        .success();
+
+    translated.assert(
+        "# Anleitung\n\n## Installation\n\nHerunterladen.\n\nDoppelklicken.\n\n## Nutzung\n\nStarten.\n\nBeenden.",
+    );
+    reference.assert(
+        "# Guide\n\n## Install\n\nDownload it.\n\nRun the installer.\n\n## Usage\n\nStart it.\n",
+    );
+
+    cmd()
+        .arg("--file")
+        .arg(translated.path())
+        .arg("apply")
+        .arg("--operations")
+        .arg(r#"[{"op": "delete", "selector": {"select_contains": "Missing"}}]"#)
+        .arg("--positions-from")
+        .arg(reference.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("reference document"));
+}
+
+#[test]
+fn apply_assert_operation_aborts_batch_when_precondition_fails() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("input.md");
+    input_file
+        .write_str("# Changelog\n\n## Unreleased\n\n- Fix parser.\n")
+        .unwrap();
+
+    let operations_file = temp.child("ops.yaml");
+    operations_file
+        .write_str(
+            r#"- op: assert
+  selector:
+    select_type: h2
+    select_contains: Unreleased
+- op: assert
+  comment: release section must not exist yet
+  expect: no_match
+  selector:
+    select_type: h2
+    select_contains: "1.0.0"
+- op: replace
+  selector:
+    select_type: h2
+    select_contains: Unreleased
+  content: '## 1.0.0'
+- op: assert
+  expect: no_match
+  selector:
+    select_type: h2
+    select_contains: "1.0.0"
+"#,
+        )
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations-file")
+        .arg(operations_file.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Assertion failed: selector was expected to match nothing, but matched a node",
+        ));
+
+    let content = std::fs::read_to_string(input_file.path()).unwrap();
+    assert_eq!(content, "# Changelog\n\n## Unreleased\n\n- Fix parser.\n");
+}
+
+#[test]
+fn apply_report_includes_content_captured_by_get_operations() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("input.md");
+    input_file
+        .write_str("# Release\n\nVersion 1.2.0 is out.\n")
+        .unwrap();
+
+    let operations_file = temp.child("ops.yaml");
+    operations_file
+        .write_str(
+            r#"- op: get
+  capture: announcement
+  selector:
+    select_type: p
+- op: insert
+  selector:
+    select_type: h1
+  position: after
+  content: "> [!NOTE]\n> {{announcement}}"
+"#,
+        )
+        .unwrap();
+    let report_file = temp.child("report.json");
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("apply")
+        .arg("--operations-file")
+        .arg(operations_file.path())
+        .arg("--report")
+        .arg(report_file.path())
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(input_file.path()).unwrap();
+    assert_eq!(
+        content,
+        "# Release\n\n> [!NOTE]\n> Version 1.2.0 is out.\n\nVersion 1.2.0 is out."
+    );
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(report_file.path()).unwrap()).unwrap();
+    assert_eq!(
+        report["captures"],
+        json!([{
+            "operation_index": 0,
+            "name": "announcement",
+            "content": "Version 1.2.0 is out.\n",
+        }])
+    );
+    assert_eq!(report["operation_counts"], json!({"get": 1, "insert": 1}));
+}
+
+#[test]
+fn apply_routes_operation_groups_by_frontmatter() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let operations_file = temp.child("ops.yaml");
+    operations_file
+        .write_str(
+            r#"- op: delete
+  selector:
+    select_type: p
+    select_contains: TODO
+- when:
+    type: api-reference
+  operations:
+    - op: insert
+      selector:
+        select_type: h1
+      content: "> Generated from the OpenAPI schema."
+- when:
+    type: [guide, tutorial]
+  operations:
+    - op: insert
+      selector:
+        select_type: h1
+      content: "> Read the quickstart first."
+"#,
+        )
+        .unwrap();
+
+    let documents = [
+        (
+            "api.md",
+            "api-reference",
+            "> Generated from the OpenAPI schema.",
+        ),
+        ("guide.md", "guide", "> Read the quickstart first."),
+        ("notes.md", "notes", ""),
+    ];
+    for (name, doc_type, _) in documents {
+        let input_file = temp.child(name);
+        input_file
+            .write_str(&format!("---\ntype: {doc_type}\n---\n# Title\n\nTODO.\n"))
+            .unwrap();
+        cmd()
+            .arg("--file")
+            .arg(input_file.path())
+            .arg("apply")
+            .arg("--operations-file")
+            .arg(operations_file.path())
+            .assert()
+            .success();
+    }
+
+    for (name, doc_type, note) in documents {
+        let body = if note.is_empty() {
+            "# Title".to_string()
+        } else {
+            format!("# Title\n\n{note}")
+        };
+        temp.child(name)
+            .assert(format!("---\ntype: {doc_type}\n---\n{body}"));
+    }
+}
+
+#[test]
+fn apply_from_git_reads_the_document_at_a_revision() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args([
+                "-c",
+                "user.name=md-splice",
+                "-c",
+                "user.email=md-splice@example.com",
+            ])
+            .args(args)
+            .current_dir(temp.path())
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    };
+    let input_file = temp.child("docs/README.md");
+    input_file.write_str("# Title\n\nKnown good.\n").unwrap();
+    git(&["init", "--quiet"]);
+    git(&["add", "."]);
+    git(&["commit", "--quiet", "-m", "base"]);
+    input_file
+        .write_str("# Title\n\nEdited by hand.\n")
+        .unwrap();
+
+    let operations =
+        r#"[{"op": "insert", "selector": {"select_type": "p"}, "content": "Generated."}]"#;
+    cmd()
+        .current_dir(temp.path())
+        .args(["--file", "docs/README.md", "apply", "--from-git", "HEAD"])
+        .args(["--operations", operations])
+        .assert()
+        .success();
+    input_file.assert("# Title\n\nKnown good.\n\nGenerated.");
+
+    cmd()
+        .current_dir(temp.path())
+        .args(["--file", "docs/README.md", "apply", "--check"])
+        .args([
+            "--from-git",
+            "HEAD:docs/README.md",
+            "--operations",
+            operations,
+        ])
+        .assert()
+        .success();
+
+    cmd()
+        .current_dir(temp.path())
+        .args([
+            "--file",
+            "docs/README.md",
+            "apply",
+            "--from-git",
+            "no-such-rev",
+        ])
+        .args(["--operations", operations])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Failed to read no-such-rev from git",
+        ));
+}
+
+#[test]
+fn apply_timeouts_fail_without_writing_the_document() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("input.md");
+    input_file.write_str("# Title\n\nReplace me.\n").unwrap();
+    let operations = r#"[{"op": "replace", "selector": {"select_type": "p"}, "content": "Done."}]"#;
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["apply", "--timeout", "0", "--operations", operations])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Time limit exceeded"));
+    input_file.assert("# Title\n\nReplace me.\n");
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args([
+            "apply",
+            "--operation-timeout",
+            "soon",
+            "--operations",
+            operations,
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected a number of seconds"));
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["apply", "--operation-timeout", "30", "--timeout", "60"])
+        .args(["--operations", operations])
+        .assert()
+        .success();
+    input_file.assert("# Title\n\nDone.");
+}
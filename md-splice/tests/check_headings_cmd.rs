@@ -0,0 +1,36 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn check_headings_reports_duplicates_and_colliding_anchors() {
+    cmd()
+        .arg("check-headings")
+        .write_stdin("# C++\n\n## Usage\n\nText.\n\n## C\n\n## usage\n")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Duplicate heading \"Usage\" at block 1, line 3; block 4, line 9",
+        ))
+        .stdout(predicate::str::contains(
+            "Anchor #c shared by \"C++\" (block 0, line 1), \"C\" (block 3, line 7)",
+        ))
+        .stderr(predicate::str::contains(
+            "Found 1 duplicated heading(s) and 1 colliding anchor(s).",
+        ));
+}
+
+#[test]
+fn check_headings_succeeds_when_headings_are_unique() {
+    cmd()
+        .arg("check-headings")
+        .write_stdin("# Title\n\n## Install\n\n## Usage\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "No duplicate headings or colliding anchors found.",
+        ));
+}
@@ -476,3 +476,496 @@ fn test_i10_source_from_stdin_output_to_file() {
     let output_content = std::fs::read_to_string(output_file.path()).unwrap();
     insta::assert_snapshot!("i10_stdin_to_file", output_content);
 }
+
+#[test]
+fn test_i11_lenient_mode_preserves_unclosed_fence() {
+    // I11: In lenient mode, an unclosed fence is kept verbatim and reported on stderr.
+    let input_md = "# Setup\n\nRun this:\n\n```sh\ncargo build\n";
+
+    cmd()
+        .arg("--lenient")
+        .arg("replace")
+        .arg("--select-type")
+        .arg("p")
+        .arg("--content")
+        .arg("Run the following:")
+        .write_stdin(input_md)
+        .assert()
+        .success()
+        .stdout(eq("# Setup\n\nRun the following:\n\n```sh\ncargo build"))
+        .stderr(predicates::str::contains(
+            "Warning: line 5: unclosed code fence",
+        ));
+}
+
+#[test]
+fn test_i11_lenient_mode_preserves_malformed_table() {
+    // I11: In lenient mode, a table whose rows disagree on their cell count is kept verbatim.
+    let input_md = "# Data\n\n| a | b\n|--|\n| 1 | 2 | 3 |\n\nDraft.\n";
+
+    cmd()
+        .arg("--lenient")
+        .arg("replace")
+        .arg("--select-type")
+        .arg("p")
+        .arg("--content")
+        .arg("Final.")
+        .write_stdin(input_md)
+        .assert()
+        .success()
+        .stdout(eq("# Data\n\n| a | b\n|--|\n| 1 | 2 | 3 |\n\nFinal."))
+        .stderr(predicates::str::contains(
+            "Warning: line 3: malformed table, the delimiter row has 1 cell(s) but the header has 2",
+        ));
+}
+
+#[test]
+fn test_i12_stdin_filename_labels_errors_and_diffs() {
+    // I12: --stdin-filename names the stdin document in errors and diff headers.
+    cmd()
+        .args(["--stdin-filename", "docs/guide.md", "delete"])
+        .args(["--select-contains", "Missing"])
+        .write_stdin("# Guide\n")
+        .assert()
+        .code(3)
+        .stderr(predicates::str::contains(
+            "Error: Failed to process docs/guide.md",
+        ));
+
+    let operations = r#"[{"op": "replace", "selector": {"select_type": "p"}, "content": "New."}]"#;
+    cmd()
+        .args(["--stdin-filename", "docs/guide.md", "apply", "--diff"])
+        .args(["--operations", operations])
+        .write_stdin("# Guide\n\nOld.\n")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "--- a/docs/guide.md\n+++ b/docs/guide.md\n",
+        ));
+}
+
+#[test]
+fn test_i13_single_commands_support_dry_run_and_diff() {
+    // I13: insert/replace/delete can be previewed without touching the file.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    input_file.write_str("# Title\n\nOld.\n").unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["replace", "--select-contains", "Old.", "--content", "New."])
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(eq("# Title\n\nNew."));
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["insert", "--select-type", "h1", "--content", "Intro."])
+        .arg("--diff")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("--- original\n+++ modified\n"))
+        .stdout(predicates::str::contains("+Intro."));
+
+    input_file.assert("# Title\n\nOld.\n");
+}
+
+#[test]
+fn test_i14_define_selector_is_usable_from_single_commands() {
+    // I14: --define-selector names a selector that --selector-ref reuses outside `apply`.
+    let input_md = "# Guide\n\nIntro.\n\n## Legacy\n\nOld.\n\n## Next\n\nKeep.\n";
+    let definitions = [
+        "--define-selector",
+        "legacy={select_type: h2, select_contains: Legacy}",
+        "--define-selector",
+        r#"next_p={"select_type": "p", "after_ref": "legacy", "select_ordinal": 2}"#,
+    ];
+
+    cmd()
+        .args(definitions)
+        .args(["get", "--selector-ref", "next_p"])
+        .write_stdin(input_md)
+        .assert()
+        .success()
+        .stdout(eq("Keep.\n"));
+
+    cmd()
+        .args(definitions)
+        .args([
+            "delete",
+            "--selector-ref",
+            "legacy",
+            "--section",
+            "--dry-run",
+        ])
+        .write_stdin(input_md)
+        .assert()
+        .success()
+        .stdout(eq("# Guide\n\nIntro.\n\n## Next\n\nKeep."));
+
+    cmd()
+        .args(definitions)
+        .args(["replace", "--selector-ref", "missing", "--content", "New."])
+        .write_stdin(input_md)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "Selector alias 'missing' was referenced before being defined.",
+        ));
+}
+
+#[test]
+fn test_i15_dry_run_writes_preview_to_output_and_dash_means_stdout() {
+    // I15: `--dry-run --output PATH` writes the preview there; `--output -` is stdout.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    input_file.write_str("# Title\n\nOld.\n").unwrap();
+    let preview = temp.child("preview.md");
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("--output")
+        .arg(preview.path())
+        .args(["replace", "--select-contains", "Old.", "--content", "New."])
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(eq(""));
+
+    preview.assert("# Title\n\nNew.");
+    input_file.assert("# Title\n\nOld.\n");
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["--output", "-"])
+        .args(["insert", "--select-type", "h1", "--content", "Intro."])
+        .assert()
+        .success()
+        .stdout(eq("# Title\n\nIntro.\n\nOld."));
+
+    input_file.assert("# Title\n\nOld.\n");
+}
+
+#[test]
+fn test_i16_regex_capture_groups_expand_in_content() {
+    // I16: inline content can reference the groups captured by `--select-regex`.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    input_file
+        .write_str("# Report\n\nStatus: In Progress\n\n- Owner: sam\n")
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["replace", "--select-regex", r"^Status: (?<state>.+)$"])
+        .args(["--content", "Status: **${state}** (was $0, costs $$5)"])
+        .assert()
+        .success();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args([
+            "replace",
+            "--select-type",
+            "li",
+            "--select-regex",
+            r"Owner: (\w+)",
+        ])
+        .args(["--content", "- Owner: @$1"])
+        .assert()
+        .success();
+
+    input_file.assert(
+        "# Report\n\nStatus: **In Progress** (was Status: In Progress, costs $5)\n\n- Owner: @sam",
+    );
+}
+
+#[test]
+fn test_i17_max_matches_and_tie_break() {
+    // I17: `--max-matches` rejects broad selectors and `--tie-break` ranks the candidates.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    let original = "# Guide\n\nTODO: intro.\n\n## Setup\n\n### Linux\n\nTODO: packages.\n";
+    input_file.write_str(original).unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["delete", "--select-contains", "TODO", "--max-matches", "1"])
+        .assert()
+        .code(4)
+        .stderr(predicates::str::contains(
+            "Selector matched 2 nodes, more than its max_matches limit of 1.",
+        ));
+    input_file.assert(original);
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args([
+            "get",
+            "--select-contains",
+            "TODO",
+            "--tie-break",
+            "deepest-first",
+        ])
+        .assert()
+        .success()
+        .stdout("TODO: packages.\n");
+}
+
+#[test]
+fn test_i18_unmatched_selector_lists_nearest_misses() {
+    // I18: a selector that matches nothing points at the nodes that came closest.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    input_file
+        .write_str("# Guide\n\n## Setup\n\nInstall the pakages.\n")
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["replace", "--select-type", "p", "--select-contains", "packages"])
+        .args(["--content", "Install the packages."])
+        .assert()
+        .code(3)
+        .stderr(predicates::str::contains(
+            "Nearest misses:\n  - line 5: block 2 (p \"Install the pakages.\"): text is 88% similar to \"packages\"",
+        ));
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["get", "--select-type", "h3", "--select-contains", "Setup"])
+        .assert()
+        .code(3)
+        .stderr(predicates::str::contains(
+            "block 1 (h2 \"Setup\"): is a `h2`, not a `h3`",
+        ));
+}
+
+#[test]
+fn test_i19_profile_prints_phase_timings() {
+    // I19: --profile reports per-phase timings on stderr and leaves the output untouched.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    input_file.write_str("# Guide\n\nOld text.\n").unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["--profile", "replace", "--select-type", "p"])
+        .args(["--content", "New text."])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr(
+            predicates::str::is_match(
+                r"(?s)^Profile:\n  read .* ms\n  parse .* ms\n  apply .* ms\n  render .* ms\n  write .* ms\n  total .* ms\n$",
+            )
+            .unwrap(),
+        );
+
+    input_file.assert("# Guide\n\nNew text.");
+}
+
+#[test]
+fn test_i20_no_fs_rejects_file_references() {
+    // I20: --no-fs rejects batches that read files, before changing the document.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    input_file.write_str("# Guide\n\nOld text.\n").unwrap();
+    let secret_file = temp.child("secret.txt");
+    secret_file.write_str("Secret.\n").unwrap();
+    let ops_file = temp.child("ops.yaml");
+    ops_file
+        .write_str(&format!(
+            "- op: replace\n  selector: {{select_type: h1}}\n  content: '# Manual'\n\
+             - op: replace\n  selector: {{select_type: p}}\n  content_file: {}\n",
+            secret_file.path().display()
+        ))
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["--no-fs", "apply", "--operations-file"])
+        .arg(ops_file.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "operation 2 (replace) reads `content_file`, but filesystem access is disabled",
+        ));
+
+    input_file.assert("# Guide\n\nOld text.\n");
+}
+
+#[test]
+fn test_i21_cat_folds_sections_except_expanded_ones() {
+    // I21: `cat` keeps every heading, folds section bodies, and expands the selected sections.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    let markdown = "Intro.\n\n# Tool\n\nAbout.\n\n## Install\n\nRun it.\n\nThen test it.\n\n\
+                    ## Usage\n\nCall it.\n\n### Flags\n\n- `-v`: verbose\n\n## FAQ\n\nAsk.\n";
+    input_file.write_str(markdown).unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["cat", "--expand", "Usage"])
+        .assert()
+        .success()
+        .stdout(
+            "[… 1 block]\n\n# Tool\n\n[… 1 block]\n\n## Install\n\n[… 2 blocks]\n\n\
+             ## Usage\n\nCall it.\n\n### Flags\n\n- `-v`: verbose\n\n## FAQ\n\n[… 1 block]\n",
+        );
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args([
+            "--define-selector",
+            "verbose={select_type: li, select_contains: verbose}",
+        ])
+        .args(["cat", "--expand-ref", "verbose", "--expand-regex", "^Inst"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "## Install\n\nRun it.\n\nThen test it.\n\n## Usage\n\n[… 1 block]\n\n\
+             ### Flags\n\n- `-v`: verbose\n\n## FAQ",
+        ));
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args(["cat", "--expand", "Missing"])
+        .assert()
+        .code(3);
+
+    input_file.assert(markdown);
+}
+
+#[test]
+fn test_i22_move_relocates_a_section() {
+    // I22: `move` relocates a heading section in one step and rejects moving content into itself.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    input_file
+        .write_str("# Guide\n\n## Usage\n\nRun it.\n\n## Install\n\nGet it.\n")
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args([
+            "move",
+            "--select-type",
+            "h2",
+            "--select-contains",
+            "Install",
+            "--section",
+            "--to-select-type",
+            "h2",
+            "--to-select-contains",
+            "Usage",
+            "--position",
+            "before",
+        ])
+        .assert()
+        .success();
+
+    input_file.assert("# Guide\n\n## Install\n\nGet it.\n\n## Usage\n\nRun it.");
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args([
+            "move",
+            "--select-type",
+            "h2",
+            "--select-contains",
+            "Install",
+            "--section",
+            "--to-select-contains",
+            "Get it",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "The move destination lies inside the content being moved.",
+        ));
+
+    input_file.assert("# Guide\n\n## Install\n\nGet it.\n\n## Usage\n\nRun it.");
+}
+
+#[test]
+fn test_i23_replace_with_update_links_rewrites_anchor_links() {
+    // I23: `replace --update-links` keeps links pointing at a replaced heading resolvable.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    input_file
+        .write_str(
+            "# Guide\n\n## Setup\n\nSee [usage](#usage).\n\n## Usage\n\nRead [setup](#setup).\n",
+        )
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args([
+            "replace",
+            "--select-type",
+            "h2",
+            "--select-contains",
+            "Usage",
+            "--content",
+            "## Everyday use",
+            "--update-links",
+        ])
+        .assert()
+        .success();
+
+    input_file.assert(
+        "# Guide\n\n## Setup\n\nSee [usage](#everyday-use).\n\n## Everyday use\n\nRead [setup](#setup).",
+    );
+}
+
+#[test]
+fn test_i24_rebase_links_rewrites_relative_paths_for_a_moved_document() {
+    // I24: `--rebase-links FROM TO` keeps relative links resolvable when `--output` moves the
+    // document to another directory.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("docs/guide/index.md");
+    input_file
+        .write_str(
+            "# Guide\n\nSee [setup](setup.md#install), ![logo](../img/logo.png), and \
+             [home](https://example.com).\n\n[api]: ./api/\n",
+        )
+        .unwrap();
+    let output_file = temp.child("docs/index.md");
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .arg("--output")
+        .arg(output_file.path())
+        .arg("--rebase-links")
+        .arg(temp.child("docs/guide").path())
+        .arg(temp.child("docs").path())
+        .args(["replace", "--select-type", "h1", "--content", "# Index"])
+        .assert()
+        .success();
+
+    output_file.assert(
+        "# Index\n\nSee [setup](guide/setup.md#install), ![logo](img/logo.png), and\n\
+         [home](https://example.com).\n\n[api]: ./guide/api/",
+    );
+}
@@ -134,6 +134,40 @@ fn missing_frontmatter_with_key_errors() {
     ));
 }
 
+#[test]
+fn get_raw_prints_frontmatter_verbatim() {
+    let file = assert_fs::NamedTempFile::new("raw.md").unwrap();
+    file.write_str(
+        "---\n# Reviewed by docs team\ntitle:   'Sample'\ntags: [a,  b]\n---\n# Heading\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("frontmatter")
+        .arg("get")
+        .arg("--raw");
+
+    let assert = cmd.assert().success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert_eq!(
+        stdout,
+        "# Reviewed by docs team\ntitle:   'Sample'\ntags: [a,  b]\n"
+    );
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("frontmatter")
+        .arg("get")
+        .arg("--raw")
+        .arg("--key")
+        .arg("title");
+
+    cmd.assert().code(2);
+}
+
 #[test]
 fn set_updates_existing_key_in_yaml() {
     let file = assert_fs::NamedTempFile::new("doc.md").unwrap();
@@ -155,6 +189,54 @@ fn set_updates_existing_key_in_yaml() {
     file.assert(predicate::str::contains("# Heading"));
 }
 
+#[test]
+fn set_preserves_anchors_and_tags_of_other_keys() {
+    let file = assert_fs::NamedTempFile::new("anchors.md").unwrap();
+    file.write_str(
+        "---\ndefaults: &defaults\n  retries: 3\nservice: *defaults\nsecret: !vault abc\nstatus: draft\n---\n# Heading\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("frontmatter")
+        .arg("set")
+        .arg("--key")
+        .arg("status")
+        .arg("--value")
+        .arg("published");
+
+    cmd.assert().success();
+
+    file.assert(predicate::str::starts_with(
+        "---\ndefaults: &defaults\n  retries: 3\nservice: *defaults\nsecret: !vault abc\nstatus: published\n---\n",
+    ));
+}
+
+#[test]
+fn set_keeps_configured_frontmatter_delimiters() {
+    let file = assert_fs::NamedTempFile::new("delimiters.md").unwrap();
+    file.write_str(";;;\nstatus: draft\n;;;\n# Heading\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("--frontmatter-delimiter")
+        .arg(";;;")
+        .arg("frontmatter")
+        .arg("set")
+        .arg("--key")
+        .arg("status")
+        .arg("--value")
+        .arg("published");
+
+    cmd.assert().success();
+
+    file.assert(";;;\nstatus: published\n;;;\n# Heading");
+}
+
 #[test]
 fn set_creates_frontmatter_when_missing() {
     let file = assert_fs::NamedTempFile::new("new.md").unwrap();
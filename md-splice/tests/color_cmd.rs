@@ -0,0 +1,60 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+const ESCAPE: &str = "\u{1b}[";
+
+#[test]
+fn color_always_colors_diff_lines() {
+    cmd()
+        .args(["--color", "always", "replace", "--select-contains", "Old."])
+        .args(["--content", "New.", "--diff"])
+        .write_stdin("# Title\n\nOld.\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\u{1b}[32m+New.\u{1b}[0m\n"))
+        .stdout(predicate::str::contains("\u{1b}[31m-Old.\u{1b}[0m\n"))
+        .stdout(predicate::str::contains("\u{1b}[36m@@"));
+}
+
+#[test]
+fn color_never_and_no_color_leave_output_plain() {
+    cmd()
+        .args(["--color", "never", "replace", "--select-contains", "Old."])
+        .args(["--content", "New.", "--diff"])
+        .write_stdin("# Title\n\nOld.\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(ESCAPE).not());
+
+    cmd()
+        .env("NO_COLOR", "1")
+        .args(["get", "--select-contains", "Old"])
+        .write_stdin("# Title\n\nOld.\n")
+        .assert()
+        .success()
+        .stdout("Old.\n");
+}
+
+#[test]
+fn color_always_highlights_get_matches_and_error_headline() {
+    cmd()
+        .args(["--color", "always", "get", "--select-type", "p"])
+        .args(["--select-regex", "l+"])
+        .write_stdin("# Title\n\nHello world.\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\u{1b}[33mll\u{1b}[0mo wor"));
+
+    cmd()
+        .args(["--color", "always", "get", "--select-contains", "Missing"])
+        .write_stdin("# Title\n\nHello world.\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::starts_with(
+            "\u{1b}[1m\u{1b}[31mError:\u{1b}[0m ",
+        ));
+}
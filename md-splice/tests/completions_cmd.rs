@@ -0,0 +1,38 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn completions_offer_node_types_and_insert_positions() {
+    cmd()
+        .args(["completions", "zsh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#compdef md-splice"))
+        .stdout(predicate::str::contains(
+            ":TYPE:(p h1 h2 h3 h4 h5 h6 heading list li",
+        ))
+        .stdout(predicate::str::contains(":POSITION:((before"))
+        .stdout(predicate::str::contains("append-child"));
+
+    for shell in ["bash", "fish", "powershell"] {
+        cmd()
+            .args(["completions", shell])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("select-type"));
+    }
+}
+
+#[test]
+fn select_type_hints_do_not_reject_aliases() {
+    cmd()
+        .args(["get", "--select-type", "paragraph"])
+        .write_stdin("# Title\n\nBody.\n")
+        .assert()
+        .success()
+        .stdout("Body.\n");
+}
@@ -0,0 +1,94 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn concat_rebases_headings_and_merges_frontmatter() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let first = temp.child("a.md");
+    first
+        .write_str("---\ntitle: A\ntags: [x]\n---\n# Alpha\n\nText A.\n\n## Sub\n")
+        .unwrap();
+    let second = temp.child("b.md");
+    second
+        .write_str("---\ntitle: B\nauthor: Bo\n---\n## Beta\n\nText B.\n")
+        .unwrap();
+    let output = temp.child("handbook.md");
+
+    cmd()
+        .arg("--output")
+        .arg(output.path())
+        .arg("concat")
+        .arg(first.path())
+        .arg(second.path())
+        .arg("--base-level")
+        .arg("2")
+        .arg("--frontmatter")
+        .arg("merge")
+        .assert()
+        .success();
+
+    output.assert(
+        "---\ntitle: B\ntags: [x]\nauthor: Bo\n---\n## Alpha\n\nText A.\n\n### Sub\n\n## Beta\n\nText B.",
+    );
+    first.assert("---\ntitle: A\ntags: [x]\n---\n# Alpha\n\nText A.\n\n## Sub\n");
+}
+
+#[test]
+fn concat_keeps_first_frontmatter_by_default_and_can_drop_it() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let first = temp.child("a.md");
+    first.write_str("Intro.\n").unwrap();
+    let second = temp.child("b.md");
+    second.write_str("---\ntitle: B\n---\n# Beta\n").unwrap();
+    let third = temp.child("c.md");
+    third.write_str("---\ntitle: C\n---\n# Gamma\n").unwrap();
+
+    cmd()
+        .arg("concat")
+        .arg(first.path())
+        .arg(second.path())
+        .arg(third.path())
+        .assert()
+        .success()
+        .stdout("---\ntitle: B\n---\nIntro.\n\n# Beta\n\n# Gamma");
+
+    cmd()
+        .arg("concat")
+        .arg("-")
+        .arg(second.path())
+        .arg("--frontmatter")
+        .arg("drop")
+        .write_stdin("From stdin.\n")
+        .assert()
+        .success()
+        .stdout("From stdin.\n\n# Beta");
+}
+
+#[test]
+fn concat_rejects_file_flag_and_repeated_stdin() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let first = temp.child("a.md");
+    first.write_str("# A\n").unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(first.path())
+        .arg("concat")
+        .arg(first.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not accept --file"));
+
+    cmd()
+        .arg("concat")
+        .arg("-")
+        .arg("-")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("stdin"));
+}
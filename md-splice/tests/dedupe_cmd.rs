@@ -0,0 +1,72 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn dedupe_headings_appends_suffixes_to_repeats() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    file.write_str("# API\n\n## Example\n\nOne.\n\n## Example\n\nTwo.\n\n## Example\n")
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("dedupe-headings")
+        .assert()
+        .success();
+
+    file.assert("# API\n\n## Example\n\nOne.\n\n## Example (2)\n\nTwo.\n\n## Example (3)");
+}
+
+#[test]
+fn dedupe_headings_check_lists_duplicates_without_writing() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    let original = "# Guide\n\n## Usage\n\n## Notes\n\n## usage\n";
+    file.write_str(original).unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("dedupe-headings")
+        .arg("--check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("heading 'Usage' appears 2 times"));
+
+    file.assert(original);
+}
+
+#[test]
+fn dedupe_headings_respects_within_scope() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    file.write_str("# Linux\n\n## Install\n\n# macOS\n\n## Install\n\n## Install\n")
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("dedupe-headings")
+        .arg("--within-select-contains")
+        .arg("macOS")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout("# Linux\n\n## Install\n\n# macOS\n\n## Install\n\n## Install (2)");
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("dedupe-headings")
+        .arg("--check")
+        .arg("--within-select-contains")
+        .arg("Linux")
+        .assert()
+        .success();
+}
@@ -132,3 +132,49 @@ First paragraph.
 Third paragraph.
 "###);
 }
+
+#[test]
+fn delete_dry_run_prints_result_without_writing() {
+    let file = assert_fs::NamedTempFile::new("test.md").unwrap();
+    file.write_str("# Title\n\nKeep.\n\nDrop.\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("delete")
+        .arg("--select-contains")
+        .arg("Drop.")
+        .arg("--dry-run");
+
+    cmd.assert().success().stdout("# Title\n\nKeep.");
+    file.assert("# Title\n\nKeep.\n\nDrop.\n");
+}
+
+#[test]
+fn delete_with_renumber_closes_the_gap_in_an_interrupted_list() {
+    let file = assert_fs::NamedTempFile::new("test.md").unwrap();
+    file.write_str("1. Download\n2. Unpack\n\n```sh\nmake\n```\n\n3. Run\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("delete")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--select-contains")
+        .arg("Unpack")
+        .arg("--renumber");
+
+    cmd.assert().success();
+
+    let result = std::fs::read_to_string(file.path()).unwrap();
+    assert_snapshot!(result, @r###"1. Download
+
+```sh
+make
+```
+
+2. Run
+"###);
+}
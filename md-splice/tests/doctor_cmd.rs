@@ -0,0 +1,63 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn doctor_reports_constructs_and_suggests_options() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    let original =
+        ";;;\ntitle: Notes\n;;;\n\nNotes\n=====\n\n<details>\n\nHidden.\n\n</details>\n\n\
+                    ```sh\nmake\n";
+    file.write_str(original).unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "line 1: the document starts with a block between `;;;` lines",
+        ))
+        .stdout(predicate::str::contains("line 6: setext heading"))
+        .stdout(predicate::str::contains("line 8: HTML block <details>"))
+        .stdout(predicate::str::contains(
+            "line 14: malformed Markdown; when parsed leniently: unclosed code fence",
+        ))
+        .stdout(predicate::str::contains("(see --diff)"))
+        .stdout(predicate::str::contains("  --frontmatter-delimiter ';;;'"))
+        .stdout(predicate::str::contains("  --lenient"));
+
+    file.assert(original);
+}
+
+#[test]
+fn doctor_diff_shows_the_round_trip() {
+    cmd()
+        .arg("doctor")
+        .arg("--diff")
+        .write_stdin("# Title\n\n1) one\n2) two\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Round trip: 2 of 4 lines change when the document is rendered without any edit.",
+        ))
+        .stdout(predicate::str::contains(
+            "-1) one\n-2) two\n+1. one\n+2. two",
+        ));
+}
+
+#[test]
+fn doctor_accepts_a_document_that_renders_back_unchanged() {
+    cmd()
+        .arg("doctor")
+        .write_stdin("# Title\n\nText.")
+        .assert()
+        .success()
+        .stdout("No problems found: the document renders back unchanged.\n");
+}
@@ -0,0 +1,58 @@
+use assert_cmd::Command;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+const DOC: &str = "# Changelog\n\n## Unreleased\n\n- Fix\n\n## 1.0.0\n";
+
+#[test]
+fn exists_succeeds_silently_when_the_selector_matches() {
+    cmd()
+        .args([
+            "exists",
+            "--select-type",
+            "h2",
+            "--select-contains",
+            "Unreleased",
+        ])
+        .write_stdin(DOC)
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+}
+
+#[test]
+fn exists_exits_with_no_match_status_silently() {
+    cmd()
+        .args([
+            "exists",
+            "--select-type",
+            "h2",
+            "--select-contains",
+            "2.0.0",
+        ])
+        .write_stdin(DOC)
+        .assert()
+        .code(3)
+        .stdout("")
+        .stderr("");
+
+    cmd()
+        .args(["exists", "--select-type", "h2", "--select-ordinal", "3"])
+        .write_stdin(DOC)
+        .assert()
+        .code(3)
+        .stderr("");
+}
+
+#[test]
+fn exists_still_reports_other_errors() {
+    cmd()
+        .args(["exists", "--select-type", "h2", "--strict"])
+        .write_stdin(DOC)
+        .assert()
+        .code(4)
+        .stderr(predicates::str::contains("Error:"));
+}
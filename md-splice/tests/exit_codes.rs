@@ -0,0 +1,114 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn selector_without_match_exits_with_no_match_status() {
+    cmd()
+        .args(["delete", "--select-contains", "Missing"])
+        .write_stdin("# Title\n\nBody.\n")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            "Selector did not match any nodes in the document",
+        ));
+
+    cmd()
+        .args(["get", "--select-type", "table"])
+        .write_stdin("# Title\n")
+        .assert()
+        .code(3);
+
+    cmd()
+        .args(["frontmatter", "get", "--key", "title"])
+        .write_stdin("---\nstatus: draft\n---\n# Title\n")
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn strict_mode_rejects_ambiguous_selectors() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input_file = temp.child("doc.md");
+    input_file.write_str("First.\n\nSecond.\n").unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(input_file.path())
+        .args([
+            "--strict",
+            "replace",
+            "--select-type",
+            "p",
+            "--content",
+            "X",
+        ])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("rejected by --strict"));
+
+    input_file.assert("First.\n\nSecond.\n");
+
+    cmd()
+        .args(["--strict", "get", "--select-type", "p"])
+        .write_stdin("First.\n\nSecond.\n")
+        .assert()
+        .code(4);
+
+    cmd()
+        .args(["get", "--select-type", "p"])
+        .write_stdin("First.\n\nSecond.\n")
+        .assert()
+        .success();
+}
+
+#[test]
+fn parse_failures_exit_with_parse_error_status() {
+    cmd()
+        .args(["apply", "--operations", "[{\"op\": \"explode\"}]"])
+        .write_stdin("# Title\n")
+        .assert()
+        .code(5);
+
+    cmd()
+        .args(["get", "--select-type", "p"])
+        .write_stdin("---\ntitle: [unterminated\n---\nBody.\n")
+        .assert()
+        .code(5);
+}
+
+#[test]
+fn io_failures_exit_with_io_error_status() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(temp.child("missing.md").path())
+        .args(["get", "--select-type", "p"])
+        .assert()
+        .code(6)
+        .stderr(predicate::str::contains("Failed to read input file"));
+}
+
+#[test]
+fn quiet_mode_suppresses_regular_output() {
+    cmd()
+        .args(["--quiet", "get", "--select-type", "p"])
+        .write_stdin("# Title\n\nBody.\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+
+    cmd()
+        .args(["-q", "get", "--select-type", "table"])
+        .write_stdin("# Title\n")
+        .assert()
+        .code(3)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("Error:"));
+}
@@ -0,0 +1,74 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn get_fingerprint_then_select_it_after_edits() {
+    let file = assert_fs::NamedTempFile::new("doc.md").unwrap();
+    file.write_str("# Notes\n\n- Alpha\n- Beta\n\nKeep this.\n")
+        .unwrap();
+
+    let output = cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--select-all")
+        .arg("--fingerprint")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let fingerprints: Vec<&str> = stdout.lines().collect();
+    assert_eq!(fingerprints.len(), 2);
+    assert!(fingerprints.iter().all(|hash| hash.len() == 16));
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("insert")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--position")
+        .arg("before")
+        .arg("--content")
+        .arg("- Zero")
+        .assert()
+        .success();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("replace")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--select-fingerprint")
+        .arg(&fingerprints[1][..10])
+        .arg("--content")
+        .arg("- Gamma")
+        .assert()
+        .success();
+
+    file.assert("# Notes\n\n- Zero\n- Alpha\n- Gamma\n\nKeep this.");
+}
+
+#[test]
+fn unknown_fingerprint_is_not_found() {
+    let file = assert_fs::NamedTempFile::new("doc.md").unwrap();
+    file.write_str("Some text.\n").unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-fingerprint")
+        .arg("ffffffffffffffff")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("did not match"));
+}
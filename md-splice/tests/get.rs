@@ -1,6 +1,7 @@
 use assert_cmd::prelude::*;
 use assert_fs::prelude::*;
 use insta::assert_snapshot;
+use predicates::prelude::PredicateBooleanExt;
 use predicates::str::contains;
 use std::process::Command;
 
@@ -126,3 +127,613 @@ fn get_all_list_items_with_custom_separator() {
     let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
     assert_snapshot!(stdout, @"- [ ] One---- [ ] Two");
 }
+
+#[test]
+fn get_list_item_section_includes_nested_blocks_and_keeps_its_number() {
+    let file = assert_fs::NamedTempFile::new("sample.md").unwrap();
+    file.write_str(
+        "3. Alpha\n4. Beta task\n\n   Notes on beta.\n\n   - sub one\n   - sub two\n5. Gamma\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--select-contains")
+        .arg("Beta")
+        .arg("--section");
+
+    let assert = cmd.assert().success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let lines: Vec<&str> = stdout.lines().map(str::trim_end).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "4. Beta task",
+            "",
+            "   Notes on beta.",
+            "",
+            "   - sub one",
+            "   - sub two",
+        ]
+    );
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--select-all")
+        .arg("--section")
+        .arg("--separator")
+        .arg("|");
+
+    cmd.assert()
+        .success()
+        .stdout(contains("3. Alpha|4. Beta task"))
+        .stdout(contains("|5. Gamma"));
+}
+
+#[test]
+fn get_block_by_annotation_comment() {
+    let file = assert_fs::NamedTempFile::new("annotated.md").unwrap();
+    file.write_str(
+        "# Docs\n\n<!-- slot: install -->\n\n- Download\n- Run\n\nUnrelated.\n\n<!-- slot: usage -->\n\nStart it.\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-annotation")
+        .arg("slot: usage");
+    cmd.assert().success().stdout("Start it.\n");
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--select-contains")
+        .arg("Run")
+        .arg("--select-annotation")
+        .arg("slot: install");
+    cmd.assert().success().stdout(contains("Run"));
+}
+
+#[test]
+fn get_html_block_by_tag_and_source() {
+    let file = assert_fs::NamedTempFile::new("readme.md").unwrap();
+    file.write_str(
+        "# Tool\n\n<p align=\"center\">Logo</p>\n\n<details>\n<summary>FAQ</summary>\n\nAsk.\n\n</details>\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-tag")
+        .arg("details");
+    cmd.assert()
+        .success()
+        .stdout(contains("<summary>FAQ</summary>"));
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("html")
+        .arg("--select-regex")
+        .arg("align=\"center\"");
+    cmd.assert().success().stdout(contains("Logo"));
+}
+
+#[test]
+fn get_blocks_by_word_count_and_emptiness() {
+    let file = assert_fs::NamedTempFile::new("stubs.md").unwrap();
+    file.write_str(
+        "# Guide\n\n## Setup\n\n## Usage\n\nRun the tool with care.\n\nTBD.\n\n- [ ] \n- [ ] Write the docs\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("heading")
+        .arg("--select-empty")
+        .arg("--select-all");
+    cmd.assert().success().stdout("## Setup\n");
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("p")
+        .arg("--select-max-words")
+        .arg("1");
+    cmd.assert().success().stdout("TBD.\n");
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--select-min-words")
+        .arg("2");
+    cmd.assert().success().stdout(contains("Write the docs"));
+}
+
+#[test]
+fn get_list_items_within_a_slide() {
+    let file = assert_fs::NamedTempFile::new("deck.md").unwrap();
+    file.write_str(
+        "# Talk\n\n- intro\n\n---\n\n- first point\n- second point\n\n---\n\n- questions\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--within-slide")
+        .arg("2")
+        .arg("--select-all");
+    cmd.assert()
+        .success()
+        .stdout("- first point\n- second point\n");
+}
+
+#[test]
+fn get_the_block_at_a_source_line() {
+    let file = assert_fs::NamedTempFile::new("notes.md").unwrap();
+    file.write_str("# Notes\n\nFirst paragraph\nwrapped.\n\n- one\n- two\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-at-line")
+        .arg("4");
+    cmd.assert().success().stdout("First paragraph wrapped.\n");
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--select-at-line")
+        .arg("7")
+        .arg("--select-all");
+    cmd.assert().success().stdout("- one\n- two\n");
+}
+
+#[test]
+fn get_code_blocks_by_info_string() {
+    let file = assert_fs::NamedTempFile::new("lib.md").unwrap();
+    file.write_str("```rust\nfn a() {}\n```\n\n```rust,no_run\nfn b() {}\n```\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-info-contains")
+        .arg("no_run");
+    cmd.assert()
+        .success()
+        .stdout("```rust,no_run\nfn b() {}\n```\n");
+}
+
+#[test]
+fn get_code_blocks_by_language() {
+    let file = assert_fs::NamedTempFile::new("polyglot.md").unwrap();
+    file.write_str(
+        "```python\nprint(1)\n```\n\n```rust\nfn a() {}\n```\n\n```{.Python #two}\nprint(2)\n```\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "code:python",
+        "--select-all",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout("```python\nprint(1)\n```\n```{.Python #two}\nprint(2)\n```\n");
+}
+
+#[test]
+fn get_and_replace_a_table_cell() {
+    let file = assert_fs::NamedTempFile::new("status.md").unwrap();
+    file.write_str("| Service | State |\n| --- | --- |\n| api | *ok* |\n| cli | todo |\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "table",
+        "--row",
+        "1",
+        "--col",
+        "2",
+    ]);
+    cmd.assert().success().stdout("*ok*\n");
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "replace",
+        "--select-type",
+        "table",
+        "--row",
+        "2",
+        "--col",
+        "2",
+        "--content",
+        "done",
+    ]);
+    cmd.assert().success();
+    assert!(std::fs::read_to_string(file.path())
+        .unwrap()
+        .contains("| cli     | done  |"));
+}
+
+#[test]
+fn get_emit_ops_generates_operations_for_every_match() {
+    let file = assert_fs::NamedTempFile::new("sample.md").unwrap();
+    file.write_str("# Title\n\nTODO: draft.\n\nKeep me.\n\nTODO: draft.\n\n- TODO: item\n- Done\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("p")
+        .arg("--select-contains")
+        .arg("TODO")
+        .arg("--select-all")
+        .arg("--emit-ops")
+        .arg("delete");
+    let assert = cmd.assert().success();
+    let operations = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert_snapshot!(operations, @r###"
+    - op: delete
+      selector:
+        select_fingerprint: b79f7888047d8444
+    - op: delete
+      selector:
+        select_fingerprint: b79f7888047d8444
+    "###);
+
+    // The reviewed operations apply to every match, duplicates included.
+    let ops_file = assert_fs::NamedTempFile::new("ops.yaml").unwrap();
+    ops_file.write_str(&operations).unwrap();
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("apply")
+        .arg("--operations-file")
+        .arg(ops_file.path());
+    cmd.assert().success();
+    let file_contents = std::fs::read_to_string(file.path()).unwrap();
+    assert_eq!(file_contents, "# Title\n\nKeep me.\n\n- TODO: item\n- Done");
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .arg("get")
+        .arg("--select-type")
+        .arg("li")
+        .arg("--emit-ops")
+        .arg("replace")
+        .arg("--output-format")
+        .arg("json");
+    cmd.assert()
+        .success()
+        .stdout(contains(r#""select_type": "li""#))
+        .stdout(contains(r#""content": "- TODO: item\n""#));
+}
+
+#[test]
+fn get_json_reports_heading_anchors() {
+    let file = assert_fs::NamedTempFile::new("guide.md").unwrap();
+    file.write_str("# Setup\n\n## Setup {#install}\n\nText.\n\n## Setup\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "h2",
+        "--select-all",
+        "--json",
+    ]);
+    let assert = cmd.assert().success();
+    let matches: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(
+        matches,
+        serde_json::json!([
+            {
+                "kind": "block",
+                "block": 1,
+                "node_type": "h2",
+                "heading_level": 2,
+                "position": "block 1",
+                "lines": { "start": 3, "end": 3 },
+                "markdown": "## Setup {#install}\n",
+                "heading": { "level": 2, "text": "Setup", "anchor": "install" },
+            },
+            {
+                "kind": "block",
+                "block": 3,
+                "node_type": "h2",
+                "heading_level": 2,
+                "position": "block 3",
+                "lines": { "start": 7, "end": 7 },
+                "markdown": "## Setup\n",
+                "heading": { "level": 2, "text": "Setup", "anchor": "setup-1" },
+            },
+        ])
+    );
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .args(["get", "--select-contains", "Text", "--json"]);
+    cmd.assert()
+        .success()
+        .stdout(contains(r#""kind": "block""#))
+        .stdout(contains(r#""heading""#).not());
+}
+
+#[test]
+fn get_ndjson_prints_one_match_per_line() {
+    let file = assert_fs::NamedTempFile::new("tasks.md").unwrap();
+    file.write_str("# Tasks\n\n- Plan\n- Ship\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "li",
+        "--select-all",
+        "--ndjson",
+    ]);
+    let assert = cmd.assert().success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let matches: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0]["markdown"], "- Plan\n");
+    assert_eq!(matches[1]["list_indices"], serde_json::json!([1]));
+    assert_eq!(
+        matches[1]["lines"],
+        serde_json::json!({ "start": 3, "end": 4 })
+    );
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .args(["get", "--select-type", "li", "--ndjson"]);
+    cmd.assert().failure().stderr(contains("--select-all"));
+}
+
+#[test]
+fn get_count_prints_the_number_of_matches() {
+    let file = assert_fs::NamedTempFile::new("CHANGELOG.md").unwrap();
+    file.write_str("# Changelog\n\n## Unreleased\n\n## 1.0.0\n\n## 0.9.0\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .args(["get", "--select-type", "h2", "--count"]);
+    cmd.assert().success().stdout("3\n");
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .args(["get", "--select-contains", "Released", "--count"]);
+    cmd.assert().success().stdout("0\n");
+}
+
+#[test]
+fn get_json_reports_structured_positions() {
+    let file = assert_fs::NamedTempFile::new("tasks.md").unwrap();
+    file.write_str("# Tasks\n\n- Plan\n  - Draft\n  - Review\n\n| Step | Owner |\n|---|---|\n| Review | Ann |\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "li",
+        "--select-depth",
+        "2",
+        "--select-contains",
+        "Review",
+        "--json",
+    ]);
+    let assert = cmd.assert().success();
+    let item: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(item["node_type"], "li");
+    assert_eq!(item["block"], 1);
+    assert_eq!(item["list_indices"], serde_json::json!([0, 1]));
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "td",
+        "--select-contains",
+        "Ann",
+        "--json",
+    ]);
+    let assert = cmd.assert().success();
+    let cell: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(cell["node_type"], "td");
+    assert_eq!(cell["block"], 2);
+    assert_eq!(cell["row_index"], 1);
+    assert_eq!(cell["column_index"], 1);
+    assert!(cell.get("list_indices").is_none());
+}
+
+#[test]
+fn get_selects_a_heading_by_slug() {
+    let file = assert_fs::NamedTempFile::new("guide.md").unwrap();
+    file.write_str("# Setup\n\n## Setup\n\nFirst.\n\n## Setup\n\nSecond.\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .args(["get", "--select-slug", "setup-2", "--section"]);
+    cmd.assert().success().stdout("## Setup\n\nSecond.\n");
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .args(["get", "--select-slug", "setup-3"]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn get_skips_nodes_matching_negated_criteria() {
+    let file = assert_fs::NamedTempFile::new("tasks.md").unwrap();
+    file.write_str("- [x] Ship it\n- [ ] Write docs\n- Review\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "li",
+        "--select-not-contains",
+        "[x]",
+        "--select-not-regex",
+        "^Rev",
+        "--select-all",
+    ]);
+    cmd.assert().success().stdout("- [ ] Write docs\n");
+}
+
+#[test]
+fn get_matches_contains_ignoring_case_and_whitespace() {
+    let file = assert_fs::NamedTempFile::new("guide.md").unwrap();
+    file.write_str("# Guide\n\n## Getting   STARTED\n\nText.\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .args(["get", "--select-contains-ci", "getting started"]);
+    cmd.assert().success().stdout("## Getting STARTED\n");
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .args(["get", "--select-contains", "getting started"]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn get_counts_the_ordinal_from_the_last_match() {
+    let file = assert_fs::NamedTempFile::new("backlog.md").unwrap();
+    file.write_str("# Plan\n\n## Now\n\n## Backlog\n\n- a\n- b\n- c\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file")
+        .arg(file.path())
+        .args(["get", "--select-type", "h2", "--select-last"]);
+    cmd.assert().success().stdout("## Backlog\n");
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "li",
+        "--within-select-contains",
+        "Backlog",
+        "--select-last",
+        "--select-ordinal",
+        "2",
+    ]);
+    cmd.assert().success().stdout("- b\n");
+}
+
+#[test]
+fn get_selects_nested_list_items_by_depth() {
+    let file = assert_fs::NamedTempFile::new("tasks.md").unwrap();
+    file.write_str("- Release\n  1. Tag\n  2. Publish\n- Announce\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "li",
+        "--select-depth",
+        "2",
+        "--select-ordinal",
+        "2",
+    ]);
+    cmd.assert().success().stdout("2. Publish\n");
+}
+
+#[test]
+fn get_selects_list_items_by_task_state() {
+    let file = assert_fs::NamedTempFile::new("tasks.md").unwrap();
+    file.write_str("- [x] Ship\n- [ ] Announce\n- Notes\n- [ ] Tag\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "li",
+        "--task-state",
+        "incomplete",
+        "--select-all",
+    ]);
+    cmd.assert().success().stdout("- [ ] Announce\n- [ ] Tag\n");
+}
+
+#[test]
+fn get_recursive_selects_paragraphs_inside_blockquotes() {
+    let file = assert_fs::NamedTempFile::new("notes.md").unwrap();
+    file.write_str("> Intro.\n>\n> Details.\n\nOutro.\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("md-splice").unwrap();
+    cmd.arg("--file").arg(file.path()).args([
+        "get",
+        "--select-type",
+        "p",
+        "--recursive",
+        "--select-ordinal",
+        "2",
+    ]);
+    cmd.assert().success().stdout("Details.\n");
+}
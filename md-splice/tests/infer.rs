@@ -0,0 +1,111 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use insta::assert_snapshot;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn infer_command_emits_operations_that_replay_the_edit() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let original = temp.child("original.md");
+    original
+        .write_str("---\ntitle: Guide\n---\n# Guide\n\nIntro.\n\n## Setup\n\nSteps.\n")
+        .unwrap();
+    let edited = temp.child("edited.md");
+    edited
+        .write_str(
+            "---\ntitle: Guide\nstatus: done\n---\n# Guide\n\nIntro, revised.\n\n## Setup\n\nSteps.\n\n- one\n- two\n",
+        )
+        .unwrap();
+
+    let output = cmd()
+        .arg("--file")
+        .arg(original.path())
+        .arg("infer")
+        .arg("--edited")
+        .arg(edited.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let operations = String::from_utf8(output.stdout).unwrap();
+    assert_snapshot!(operations, @r"
+    - op: set_frontmatter
+      key: status
+      value: done
+    - op: replace
+      selector:
+        select_type: p
+        select_contains: Intro.
+      content: Intro, revised.
+    - op: insert
+      selector:
+        select_type: p
+        select_contains: Steps.
+      content: |-
+        - one
+        - two
+      position: after
+    ");
+
+    // Replaying the inferred operations against a similar document applies the same edit.
+    let similar = temp.child("similar.md");
+    similar
+        .write_str("---\ntitle: Other\n---\n# Other\n\nIntro.\n\n## Setup\n\nSteps.\n\n## Extra\n")
+        .unwrap();
+    let operations_file = temp.child("ops.yaml");
+    operations_file.write_str(&operations).unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(similar.path())
+        .arg("apply")
+        .arg("--operations-file")
+        .arg(operations_file.path())
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(similar.path()).unwrap();
+    assert_eq!(
+        content,
+        "---\ntitle: Other\nstatus: done\n---\n# Other\n\nIntro, revised.\n\n## Setup\n\nSteps.\n\n- one\n- two\n\n## Extra"
+    );
+}
+
+#[test]
+fn infer_command_supports_json_output_and_leaves_files_untouched() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let original = temp.child("original.md");
+    original.write_str("# Title\n\nOld.\n\nGone.\n").unwrap();
+    let edited = temp.child("edited.md");
+    edited.write_str("# Title\n\nOld.\n").unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(original.path())
+        .arg("infer")
+        .arg("--edited")
+        .arg(edited.path())
+        .arg("--output-format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""op": "delete""#))
+        .stdout(predicate::str::contains(r#""select_contains": "Gone.""#));
+
+    original.assert("# Title\n\nOld.\n\nGone.\n");
+}
+
+#[test]
+fn infer_command_rejects_edited_document_from_stdin_without_file() {
+    cmd()
+        .arg("infer")
+        .arg("--edited")
+        .arg("-")
+        .write_stdin("# Title\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("stdin"));
+}
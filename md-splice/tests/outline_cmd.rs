@@ -0,0 +1,57 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+const DOC: &str = "# Guide\n\nIntro.\n\n## Setup {#install}\n\n### Linux\n\n## Usage\n\n# FAQ\n";
+
+#[test]
+fn outline_prints_the_indented_heading_tree() {
+    cmd()
+        .arg("outline")
+        .write_stdin(DOC)
+        .assert()
+        .success()
+        .stdout(
+            "# Guide (#guide; block 0, line 1; 5 block(s), 2 subheading(s))\n\
+             \x20 ## Setup (#install; block 2, line 5; 2 block(s), 1 subheading(s))\n\
+             \x20   ### Linux (#linux; block 3, line 7; 1 block(s), 0 subheading(s))\n\
+             \x20 ## Usage (#usage; block 4, line 9; 1 block(s), 0 subheading(s))\n\
+             # FAQ (#faq; block 5, line 11; 1 block(s), 0 subheading(s))\n",
+        );
+}
+
+#[test]
+fn outline_json_nests_headings_up_to_max_level() {
+    let output = cmd()
+        .args(["outline", "--json", "--max-level", "2"])
+        .write_stdin(DOC)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let outline: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let guide = &outline[0];
+    assert_eq!(guide["slug"], "guide");
+    assert_eq!(guide["section_blocks"], 5);
+    assert_eq!(guide["child_count"], 2);
+    let setup = &guide["children"][0];
+    assert_eq!(setup["text"], "Setup");
+    assert_eq!(setup["slug"], "install");
+    assert_eq!(setup["block"], 2);
+    assert_eq!(setup["line"], 5);
+    assert_eq!(setup["child_count"], 0);
+    assert_eq!(outline[1]["text"], "FAQ");
+}
+
+#[test]
+fn outline_prints_nothing_without_headings() {
+    cmd()
+        .arg("outline")
+        .write_stdin("Just a paragraph.\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
@@ -0,0 +1,90 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn prune_empty_sections_removes_empty_headings_and_cascades() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    file.write_str("# Guide\n\n## Setup\n\nSteps.\n\n## Usage\n\n### Advanced\n\n## FAQ\n")
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("prune-empty-sections")
+        .assert()
+        .success();
+
+    file.assert("# Guide\n\n## Setup\n\nSteps.");
+}
+
+#[test]
+fn prune_empty_sections_honours_levels_and_within_scope() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    let original = "# Draft\n\n## Todo\n\n# Release\n\n## Notes\n\n### Empty\n";
+    file.write_str(original).unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("prune-empty-sections")
+        .arg("--min-level")
+        .arg("3")
+        .arg("--within-select-type")
+        .arg("h1")
+        .arg("--within-select-contains")
+        .arg("Release")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("## Todo"))
+        .stdout(predicate::str::contains("## Notes"))
+        .stdout(predicate::str::contains("### Empty").not());
+
+    file.assert(original);
+}
+
+#[test]
+fn prune_empty_sections_rejects_invalid_level_range() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    file.write_str("# Title\n").unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("prune-empty-sections")
+        .arg("--min-level")
+        .arg("4")
+        .arg("--max-level")
+        .arg("2")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn prune_empty_sections_operation_runs_from_apply() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    file.write_str("# Title\n\nBody.\n\n## Empty\n").unwrap();
+    let ops = temp.child("ops.yaml");
+    ops.write_str("- op: prune_empty_sections\n  min_level: 2\n")
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("apply")
+        .arg("--operations-file")
+        .arg(ops.path())
+        .assert()
+        .success();
+
+    file.assert("# Title\n\nBody.");
+}
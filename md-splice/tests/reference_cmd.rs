@@ -0,0 +1,37 @@
+use assert_cmd::Command;
+use md_splice_lib::locator::SELECT_TYPES;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn help_selectors_lists_every_select_type_without_a_subcommand() {
+    let assert = cmd().arg("--help-selectors").assert().success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+
+    for select_type in SELECT_TYPES {
+        let names = select_type.names.join(", ");
+        assert!(
+            stdout
+                .lines()
+                .any(|line| line.trim_start().starts_with(&names)
+                    && line.ends_with(select_type.description)),
+            "missing {names}"
+        );
+    }
+    assert!(stdout.contains("--within-select-*"));
+    assert!(stdout.contains("--select-annotation <TEXT>"));
+}
+
+#[test]
+fn man_page_includes_selectors_section() {
+    cmd()
+        .arg("man")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".TH md-splice"))
+        .stdout(predicate::str::contains(".SH SELECTORS"))
+        .stdout(predicate::str::contains("\\fBli, item, listitem\\fR"));
+}
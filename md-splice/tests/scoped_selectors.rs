@@ -60,6 +60,7 @@ fn get_paragraph_after_heading() {
 }
 
 #[test]
+#[allow(clippy::suspicious_command_arg_space)]
 fn insert_task_within_section() {
     let file = assert_fs::NamedTempFile::new("doc.md").unwrap();
     file.write_str(
@@ -0,0 +1,99 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn split_writes_each_section_to_a_slug_named_file_and_leaves_links() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("book.md");
+    file.write_str(
+        "---\ntitle: Book\n---\n# Book\n\nIntro.\n\n## Getting Started\n\nInstall it.\n\n### Details\n\nMore.\n\n## FAQ?\n\nAsk.\n",
+    )
+    .unwrap();
+
+    cmd()
+        .current_dir(temp.path())
+        .arg("--file")
+        .arg(file.path())
+        .arg("split")
+        .arg("--level")
+        .arg("2")
+        .arg("--out-dir")
+        .arg("parts")
+        .arg("--leave")
+        .arg("link")
+        .assert()
+        .success();
+
+    file.assert(
+        "---\ntitle: Book\n---\n# Book\n\nIntro.\n\n[Getting Started](parts/getting-started.md)\n\n[FAQ?](parts/faq.md)",
+    );
+    temp.child("parts/getting-started.md")
+        .assert("## Getting Started\n\nInstall it.\n\n### Details\n\nMore.\n");
+    temp.child("parts/faq.md").assert("## FAQ?\n\nAsk.\n");
+}
+
+#[test]
+fn split_dry_run_previews_include_placeholders_without_writing() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    let original = "# One\n\nFirst.\n\n# Two\n\nSecond.\n";
+    file.write_str(original).unwrap();
+
+    cmd()
+        .current_dir(temp.path())
+        .arg("--file")
+        .arg(file.path())
+        .arg("split")
+        .arg("--out-dir")
+        .arg("out")
+        .arg("--leave")
+        .arg("include")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout("<!-- include: out/one.md -->\n\n<!-- include: out/two.md -->");
+
+    file.assert(original);
+    temp.child("out").assert(predicate::path::missing());
+}
+
+#[test]
+fn split_refuses_to_overwrite_existing_files_without_force() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    let original = "# Notes\n\nKeep me.\n";
+    file.write_str(original).unwrap();
+    let existing = temp.child("out/notes.md");
+    existing.write_str("Older notes.\n").unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("split")
+        .arg("--out-dir")
+        .arg(temp.child("out").path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+
+    file.assert(original);
+    existing.assert("Older notes.\n");
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("split")
+        .arg("--out-dir")
+        .arg(temp.child("out").path())
+        .arg("--force")
+        .assert()
+        .success();
+
+    file.assert("");
+    existing.assert("# Notes\n\nKeep me.\n");
+}
@@ -0,0 +1,103 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+fn cmd() -> Command {
+    Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
+}
+
+#[test]
+fn toc_is_inserted_under_the_selected_heading() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    file.write_str("# Guide\n\n## Contents\n\n## Install\n\n### From source\n\n## Usage\n")
+        .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("toc")
+        .arg("--select-contains")
+        .arg("Contents")
+        .arg("--min-level")
+        .arg("2")
+        .assert()
+        .success();
+
+    file.assert(
+        "# Guide\n\n## Contents\n\n<!-- toc -->\n\n- [Install](#install)\n  \n  \
+         - [From source](#from-source)\n- [Usage](#usage)\n\n<!-- tocstop -->\n\n\
+         ## Install\n\n### From source\n\n## Usage",
+    );
+}
+
+#[test]
+fn toc_refreshes_the_list_between_markers() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    file.write_str(
+        "# Guide\n\n<!-- toc -->\n\n- [Old](#old)\n\n<!-- tocstop -->\n\n## Set up -- fast\n\n\
+         ## FAQ\n",
+    )
+    .unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("toc")
+        .arg("--slug-style")
+        .arg("gitlab")
+        .assert()
+        .success();
+
+    file.assert(
+        "# Guide\n\n<!-- toc -->\n\n- [Set up -- fast](#set-up-fast)\n- [FAQ](#faq)\n\n\
+         <!-- tocstop -->\n\n## Set up -- fast\n\n## FAQ",
+    );
+}
+
+#[test]
+fn toc_check_fails_when_the_list_is_out_of_date() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("doc.md");
+    let stale = "# Guide\n\n<!-- toc -->\n\n- [Usage](#usage)\n\n<!-- tocstop -->\n\n## Install\n";
+    file.write_str(stale).unwrap();
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("toc")
+        .arg("--check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The table of contents is out of date",
+        ));
+    file.assert(stale);
+
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("toc")
+        .assert()
+        .success();
+    cmd()
+        .arg("--file")
+        .arg(file.path())
+        .arg("toc")
+        .arg("--check")
+        .assert()
+        .success();
+}
+
+#[test]
+fn toc_requires_markers_or_a_heading() {
+    cmd()
+        .arg("toc")
+        .write_stdin("# Guide\n\nText.\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The document has no <!-- toc --> comment",
+        ));
+}